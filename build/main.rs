@@ -11,6 +11,7 @@ fn main() {
     let dest = Path::new(&dest);
 
     textures::build_texture_file(&mut File::create(&dest.join("textures.rs")).unwrap());
+    textures::build_buffer_texture_file(&mut File::create(&dest.join("buffer_textures.rs")).unwrap());
     println!("cargo:rerun-if-changed=build/main.rs");
 
     let mut file_output = File::create(&dest.join("gl_bindings.rs")).unwrap();
@@ -29,6 +30,7 @@ where
         vec![
             "GL_AMD_depth_clamp_separate",
             "GL_APPLE_vertex_array_object",
+            "GL_ARB_base_instance",
             "GL_ARB_bindless_texture",
             "GL_ARB_blend_func_extended",
             "GL_ARB_buffer_storage",
@@ -50,17 +52,22 @@ where
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
+            "GL_ARB_pipeline_statistics_query",
             "GL_ARB_pixel_buffer_object",
             "GL_ARB_robustness",
+            "GL_ARB_sample_shading",
             "GL_ARB_seamless_cube_map",
             "GL_ARB_shader_image_load_store",
             "GL_ARB_shader_objects",
+            "GL_ARB_sparse_texture",
             "GL_ARB_texture_buffer_object",
+            "GL_ARB_texture_filter_anisotropic",
             "GL_ARB_texture_float",
             "GL_ARB_texture_multisample",
             "GL_ARB_texture_rg",
             "GL_ARB_texture_rgb10_a2ui",
             "GL_ARB_texture_storage",
+            "GL_ARB_transform_feedback2",
             "GL_ARB_transform_feedback3",
             "GL_ARB_vertex_buffer_object",
             "GL_ARB_vertex_shader",
@@ -70,6 +77,7 @@ where
             "GL_EXT_direct_state_access",
             "GL_EXT_memory_object",
             "GL_EXT_memory_object_fd",
+            "GL_EXT_memory_object_win32",
             "GL_EXT_framebuffer_blit",
             "GL_EXT_framebuffer_multisample",
             "GL_EXT_framebuffer_object",
@@ -79,6 +87,7 @@ where
             "GL_EXT_provoking_vertex",
             "GL_EXT_semaphore",
             "GL_EXT_semaphore_fd",
+            "GL_EXT_semaphore_win32",
             "GL_EXT_texture_array",
             "GL_EXT_texture_buffer_object",
             "GL_EXT_texture_compression_s3tc",
@@ -104,8 +113,10 @@ where
             "GL_APPLE_framebuffer_multisample",
             "GL_APPLE_sync",
             "GL_ARM_rgba8",
+            "GL_EXT_base_instance",
             "GL_EXT_buffer_storage",
             "GL_EXT_disjoint_timer_query",
+            "GL_EXT_multi_draw_arrays",
             "GL_EXT_multi_draw_indirect",
             "GL_EXT_multisampled_render_to_texture",
             "GL_EXT_occlusion_query_boolean",
@@ -118,12 +129,15 @@ where
             "GL_NV_pixel_buffer_object",
             "GL_OES_depth_texture",
             "GL_OES_draw_elements_base_vertex",
+            "GL_OES_EGL_image",
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
+            "GL_OES_sample_shading",
             "GL_OES_texture_buffer",
             "GL_OES_texture_npot",
             "GL_OES_vertex_array_object",
+            "GL_OES_vertex_half_float",
             "GL_OES_vertex_type_10_10_10_2",
         ],
     );
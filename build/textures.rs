@@ -248,6 +248,8 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
     let mipmap_default = match ty {
         TextureType::Compressed | TextureType::CompressedSrgb => "CompressedMipmapsOption::NoMipmap",
         TextureType::Unsigned | TextureType::Integral => "MipmapsOption::NoMipmap",
+        // mipmaps can't be generated for textures that contain stencil data
+        TextureType::Stencil | TextureType::DepthStencil => "MipmapsOption::NoMipmap",
         _ => "MipmapsOption::AutoGeneratedMipmaps",
     };
 
@@ -305,14 +307,17 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             #![allow(unused_imports)]
 
             use std::borrow::Cow;
+            use std::cmp;
 
             use crate::texture::any::{{self, TextureAny, TextureAnyLayer, TextureAnyMipmap}};
             use crate::texture::any::{{TextureAnyLayerMipmap, TextureAnyImage, Dimensions}};
             use crate::texture::bindless::{{ResidentTexture, BindlessTexturesNotSupportedError}};
             use crate::texture::get_format::{{InternalFormat, InternalFormatType, GetFormatError}};
             use crate::texture::pixel_buffer::PixelBuffer;
+            use crate::buffer::BufferSlice;
             use crate::texture::{{TextureCreationError, Texture1dDataSource, Texture2dDataSource}};
-            use crate::texture::{{Texture3dDataSource, Texture2dDataSink, MipmapsOption, CompressedMipmapsOption}};
+            use crate::texture::{{Texture3dDataSource, Texture2dDataSink, Texture3dDataSink}};
+            use crate::texture::{{MipmapsOption, CompressedMipmapsOption}};
             use crate::texture::{{RawImage1d, RawImage2d, RawImage3d, CubeLayer}};
             use crate::texture::pixel::PixelValue;
 
@@ -322,12 +327,13 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             use crate::image_format::{{CompressedSrgbFormat, SrgbFormat, UncompressedUintFormat}};
 
             use crate::backend::Facade;
-            use crate::uniforms::{{UniformValue, AsUniformValue, Sampler, ImageUnit, ImageUnitError, ImageUnitFormat}};
+            use crate::uniforms::{{UniformValue, AsUniformValue, Sampler, ImageUnit, ImageUnitAccess, ImageUnitError, ImageUnitFormat}};
             use crate::framebuffer;
 
             use crate::Rect;
 
             use crate::GlObject;
+            use crate::Recreatable;
             use crate::TextureExt;
             use crate::TextureMipmapExt;
             use crate::gl;
@@ -388,6 +394,16 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
             ", name)).unwrap();
 
+    // `Recreatable` trait impl
+    (writeln!(dest, "
+                impl Recreatable for {} {{
+                    #[inline]
+                    fn recreate(&mut self) -> Result<(), crate::Error> {{
+                        self.0.recreate()
+                    }}
+                }}
+            ", name)).unwrap();
+
     // `Debug` trait impl
     (writeln!(dest, "
                 impl ::std::fmt::Debug for {} {{
@@ -413,10 +429,17 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
 
     // `UniformValue` trait impl for samplers
     {
-        match ty {
+        // stencil textures can only be sampled in two dimensions for now
+        let is_sampler = match ty {
             TextureType::Regular | TextureType::Compressed |
             TextureType::Srgb | TextureType::CompressedSrgb |
-            TextureType::Integral | TextureType::Unsigned | TextureType::Depth => {
+            TextureType::Integral | TextureType::Unsigned | TextureType::Depth => true,
+            TextureType::Stencil | TextureType::DepthStencil =>
+                dimensions == TextureDimensions::Texture2d,
+        };
+
+        match is_sampler {
+            true => {
                 (writeln!(dest, "
                             impl<'a> AsUniformValue for &'a {myname} {{
                                 #[inline]
@@ -460,7 +483,7 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                             }}
                         ", myname = name)).unwrap();
             },
-            _ => ()
+            false => ()
         }
     }
 
@@ -483,6 +506,17 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                         pub fn image_unit(&self, format: ImageUnitFormat) -> Result<ImageUnit<{myname}>, ImageUnitError> {{
                              ImageUnit::new(self, format)
                         }}
+
+                        /// Builds an image unit marker object with the given access.
+                        ///
+                        /// Glium automatically calls `glMemoryBarrier` before the texture is
+                        /// accessed again if the shader is allowed to write to it.
+                        #[inline]
+                        pub fn image_unit_with_access(&self, format: ImageUnitFormat, access: ImageUnitAccess)
+                                                      -> Result<ImageUnit<{myname}>, ImageUnitError>
+                        {{
+                             ImageUnit::with_access(self, format, access)
+                        }}
                     }}
                 ", myname = name, valname = image_variant).unwrap();
     }
@@ -536,6 +570,47 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
         }
     }
 
+    // `ToLayeredXXXAttachment` trait impl
+    if dimensions.is_array() || dimensions.is_cube() || dimensions == TextureDimensions::Texture3d {
+        match ty {
+            TextureType::Regular | TextureType::Srgb | TextureType::Integral | TextureType::Unsigned => {
+                (writeln!(dest, "
+                        impl<'t> crate::framebuffer::ToLayeredColorAttachment<'t> for &'t {name} {{
+                            #[inline]
+                            fn to_layered_color_attachment(self) -> crate::framebuffer::LayeredColorAttachment<'t> {{
+                                crate::framebuffer::LayeredColorAttachment::Texture(self.0.main_level())
+                            }}
+                        }}
+
+                        impl<'t> crate::framebuffer::ToLayeredColorAttachment<'t> for {name}Mipmap<'t> {{
+                            #[inline]
+                            fn to_layered_color_attachment(self) -> crate::framebuffer::LayeredColorAttachment<'t> {{
+                                crate::framebuffer::LayeredColorAttachment::Texture(self.0)
+                            }}
+                        }}
+                    ", name = name)).unwrap();
+            },
+            TextureType::Depth => {
+                (writeln!(dest, "
+                        impl<'t> crate::framebuffer::ToLayeredDepthAttachment<'t> for &'t {name} {{
+                            #[inline]
+                            fn to_layered_depth_attachment(self) -> crate::framebuffer::LayeredDepthAttachment<'t> {{
+                                crate::framebuffer::LayeredDepthAttachment::Texture(self.0.main_level())
+                            }}
+                        }}
+
+                        impl<'t> crate::framebuffer::ToLayeredDepthAttachment<'t> for {name}Mipmap<'t> {{
+                            #[inline]
+                            fn to_layered_depth_attachment(self) -> crate::framebuffer::LayeredDepthAttachment<'t> {{
+                                crate::framebuffer::LayeredDepthAttachment::Texture(self.0)
+                            }}
+                        }}
+                    ", name = name)).unwrap();
+            },
+            _ => ()
+        }
+    }
+
     // opening `impl Texture` block
     (writeln!(dest, "impl {} {{", name)).unwrap();
 
@@ -641,6 +716,66 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                mipmaps = mipmaps_option_ty).unwrap());
     }
 
+    // writing the `from_compressed_raw` function
+    if dimensions == TextureDimensions::Texture2d && is_compressed {
+        (writeln!(dest, "
+                /// Builds a new texture from data that is already compressed, including all of its
+                /// mipmap levels.
+                ///
+                /// `data` must contain the `mip_levels` levels one after another, starting with the
+                /// main level, like in KTX or DDS files. Each level has half the dimensions of the
+                /// previous one, rounded down.
+                ///
+                /// Returns `FormatNotSupported` if the backend doesn't support `format`, and
+                /// `DataSizeMismatch` if the size of `data` doesn't match the dimensions.
+                pub fn from_compressed_raw<F: ?Sized>(facade: &F, format: {format}, data: &[u8],
+                                                      dimensions: (u32, u32), mip_levels: u32)
+                                                      -> Result<{name}, TextureCreationError>
+                                                      where F: Facade
+                {{
+                    let (width, height) = dimensions;
+                    let client_format = {client_format_any}(format);
+
+                    let max_levels = MipmapsOption::EmptyMipmaps.num_levels(width, Some(height), None);
+                    if mip_levels == 0 || mip_levels > max_levels {{
+                        return Err(TextureCreationError::MipmapLevelsOutOfRange);
+                    }}
+
+                    // splitting the data between the levels
+                    let mut levels = Vec::with_capacity(mip_levels as usize);
+                    let mut offset = 0;
+                    for level in 0 .. mip_levels {{
+                        let level_width = cmp::max(1, width >> level);
+                        let level_height = cmp::max(1, height >> level);
+                        let size = client_format.get_buffer_size(level_width, Some(level_height),
+                                                                 None, None);
+                        let level_data = data.get(offset .. offset + size)
+                                             .ok_or(TextureCreationError::DataSizeMismatch)?;
+                        levels.push((level_width, level_height, level_data));
+                        offset += size;
+                    }}
+
+                    if offset != data.len() {{
+                        return Err(TextureCreationError::DataSizeMismatch);
+                    }}
+
+                    let texture = {name}::with_compressed_data(facade, levels[0].2, width, height,
+                                                        format,
+                                                        CompressedMipmapsOption::EmptyMipmapsMax(mip_levels - 1))?;
+
+                    for (level, &(width, height, data)) in levels.iter().enumerate().skip(1) {{
+                        let rect = Rect {{ left: 0, bottom: 0, width, height }};
+                        texture.mipmap(level as u32).unwrap()
+                               .write_compressed_data(rect, data, width, height, format)
+                               .map_err(|_| TextureCreationError::FormatNotSupported)?;
+                    }}
+
+                    Ok(texture)
+                }}
+            ", name = name, format = relevant_format,
+               client_format_any = client_format_any_ty).unwrap());
+    }
+
     // writing the `with_format` function
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
@@ -806,6 +941,30 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
         (writeln!(dest, "}}")).unwrap();
     }
 
+    // writing the `with_mipmaps_storage` function
+    if !dimensions.is_multisample() {
+        (writeln!(dest, "
+                /// Creates an empty texture with immutable storage for exactly `levels` mipmap
+                /// levels, including the main level.
+                ///
+                /// The storage is allocated with `glTexStorage*`, which means that the format,
+                /// the dimensions and the number of levels can't change afterwards. The content of
+                /// every level is undefined and no mipmap is generated. Each level can then be
+                /// uploaded individually with `mipmap(level)`, or generated from the main level.
+                ///
+                /// Returns `ImmutableStorageNotSupported` if the backend doesn't support immutable
+                /// storage, and `MipmapLevelsOutOfRange` if `levels` is `0` or is larger than what
+                /// the dimensions allow.
+                #[inline]
+                pub fn with_mipmaps_storage<F: ?Sized>(facade: &F, format: {format}, levels: u32, {dim_params}) -> Result<{name}, TextureCreationError> where F: Facade {{
+                    let format = format.to_texture_format();
+                    let format = TextureFormatRequest::Specific(format);
+                    any::new_texture_storage(facade, format, levels, {dim_passing}).map(|t| {name}(t))
+                }}
+            ", format = relevant_format, dim_params = dimensions_parameters_input, name = name,
+               dim_passing = dimensions_parameters_passing)).unwrap();
+    }
+
     // writing the 'from_id' function
     (writeln!(dest, "
                 /// Builds a new texture reference from an existing, externally created OpenGL texture.
@@ -825,6 +984,40 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 }}
         ", format = relevant_format, name = name)).unwrap();
 
+    // writing the 'from_any' function, used by the texture loader
+    (writeln!(dest, "
+                /// Wraps a texture that has the dimensions and format of this type of texture.
+                #[cfg(feature = \"loader\")]
+                #[inline]
+                pub(crate) fn from_any(texture: TextureAny) -> {name} {{
+                    {name}(texture)
+                }}
+        ", name = name)).unwrap();
+
+    // writing the 'into_transfer' and 'from_transfer' functions
+    (writeln!(dest, "
+                /// Detaches this texture from its context, so that it can be used by another
+                /// context that shares lists with it. See `TextureAny::into_transfer`.
+                #[inline]
+                pub fn into_transfer(self) -> any::TextureTransfer<{name}> {{
+                    self.0.into_transfer().cast()
+                }}
+
+                /// Attaches a texture that was detached with `into_transfer` to the context of
+                /// `facade`. See `TextureAny::from_transfer`.
+                ///
+                /// # Safety
+                ///
+                /// The context of `facade` must share lists with the context the texture was
+                /// detached from.
+                #[inline]
+                pub unsafe fn from_transfer<F: Facade + ?Sized>(facade: &F,
+                                                               transfer: any::TextureTransfer<{name}>)
+                                                               -> {name} {{
+                    {name}(transfer.finish(facade))
+                }}
+        ", name = name)).unwrap();
+
     // writing the 'new_from_fd' function
     (writeln!(dest, r#"
                 /// Builds a new texture reference from an existing texture, externally created by a foreign
@@ -885,6 +1078,36 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             ")).unwrap();
     }
 
+    // writing the `as_surface_for_face_layer` function
+    if dimensions == TextureDimensions::CubemapArray &&
+       (ty == TextureType::Regular || ty == TextureType::Integral ||
+        ty == TextureType::Unsigned || ty == TextureType::Depth)
+    {
+        let constructor = if ty == TextureType::Depth { "depth_only" } else { "new" };
+
+        (write!(dest, "
+                /// Starts drawing on a single face of a layer of the main mipmap level.
+                ///
+                /// Returns `None` if the layer is out of range. This is typically used to render
+                /// the six faces of omnidirectional shadow maps.
+                #[inline]
+                pub fn as_surface_for_face_layer<'a>(&'a self, layer: u32, face: CubeLayer)
+                                                     -> Option<framebuffer::SimpleFrameBuffer<'a>>
+                {{
+                    use crate::ContextExt;
+
+                    let image = self.main_level().layer(layer)?.image(face);
+
+                    let mut ctxt = self.0.get_context().make_current();
+                    self.0.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
+                    drop(ctxt);
+
+                    Some(framebuffer::SimpleFrameBuffer::{constructor}(self.0.get_context(), image)
+                            .unwrap())
+                }}
+            ", constructor = constructor)).unwrap();
+    }
+
     // writing the `get_mipmap_levels` function
     (write!(dest, "
             /// Returns the number of mipmap levels of the texture.
@@ -896,6 +1119,20 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             }}
         ")).unwrap();
 
+    // writing the `generate_mipmaps` function
+    if (ty == TextureType::Regular || ty == TextureType::Srgb) && !dimensions.is_multisample() {
+        (write!(dest, "
+                /// Generates the content of all the mipmap levels from the main level.
+                ///
+                /// This does nothing if the texture has a single level.
+                #[inline]
+                pub fn generate_mipmaps(&self) {{
+                    // at worst, a format that isn't filterable triggers an OpenGL error
+                    unsafe {{ self.0.generate_mipmaps() }}
+                }}
+            ")).unwrap();
+    }
+
     // writing the `read` functions
     // TODO: implement for other types too
     if dimensions == TextureDimensions::Texture2d &&
@@ -964,6 +1201,235 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                     pb
                 }}
             "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Copies the content of the texture into an arbitrary buffer in video memory,
+                /// for example a buffer that is then used as a shader storage buffer. This method
+                /// may only read `U8U8U8U8` data, as it is the only format guaranteed to be
+                /// supported across all OpenGL versions.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the buffer is not large enough.
+                #[inline]
+                pub fn copy_to_buffer(&self, dest: BufferSlice<'_, [(u8, u8, u8, u8)]>) {{
+                    unsafe {{ self.unchecked_copy_to_buffer(dest) }}
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Unsafely copies the content of the texture into an arbitrary buffer in video
+                /// memory. It is possible that the current OpenGL context does not support the
+                /// given format, in which case the copied data will be invalid.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the buffer is not large enough.
+                #[inline]
+                pub unsafe fn unchecked_copy_to_buffer<P>(&self, dest: BufferSlice<'_, [P]>) where P: PixelValue {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    self.0.main_level().first_layer().into_image(None).unwrap()
+                          .raw_read_to_buffer(&rect, dest);
+                }}
+            "#)).unwrap();
+    }
+
+    // writing the `read` functions of cubemaps
+    if dimensions == TextureDimensions::Cubemap &&
+       (ty == TextureType::Regular || ty == TextureType::Srgb || ty == TextureType::Unsigned)
+    {
+        (write!(dest, r#"
+                /// Reads the content of a face of the texture to RAM. This method may only read
+                /// `U8U8U8U8` data, as it is the only format guaranteed to be supported across
+                /// all OpenGL versions.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                /// Use `read_to_pixel_buffer` instead.
+                #[inline]
+                pub fn read<T>(&self, face: CubeLayer) -> T
+                               where T: Texture2dDataSink<(u8, u8, u8, u8)>
+                {{
+                    unsafe {{ self.unchecked_read(face) }}
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Reads the content of a face of the texture into a buffer in video memory. This
+                /// method may only read `U8U8U8U8` data, as it is the only format guaranteed to be
+                /// supported across all OpenGL versions.
+                ///
+                /// This operation copies the texture's data into a buffer in video memory
+                /// (a pixel buffer). Contrary to the `read` function, this operation is
+                /// done asynchronously and doesn't need a synchronization.
+                #[inline]
+                pub fn read_to_pixel_buffer(&self, face: CubeLayer) -> PixelBuffer<(u8, u8, u8, u8)> {{
+                    unsafe {{ self.unchecked_read_to_pixel_buffer(face) }}
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Unsafely reads the content of a face of the texture to RAM in the specified
+                /// pixel format. It is possible that the current OpenGL context does not support
+                /// the given format, in which case the returned data will be invalid.
+                #[inline]
+                pub unsafe fn unchecked_read<T, P>(&self, face: CubeLayer) -> T
+                                                   where T: Texture2dDataSink<P>, P: PixelValue
+                {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    self.0.main_level().first_layer().into_image(Some(face)).unwrap().raw_read(&rect)
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Unsafely reads the content of a face of the texture into a buffer in video
+                /// memory. It is possible that the current OpenGL context does not support the
+                /// given format, in which case the returned data will be invalid.
+                #[inline]
+                pub unsafe fn unchecked_read_to_pixel_buffer<P>(&self, face: CubeLayer) -> PixelBuffer<P>
+                                                                where P: PixelValue
+                {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    let pb = PixelBuffer::new_empty(self.0.get_context(),
+                                                    rect.width as usize * rect.height as usize);
+                    self.0.main_level().first_layer().into_image(Some(face)).unwrap()
+                          .raw_read_to_pixel_buffer(&rect, &pb);
+                    pb
+                }}
+            "#)).unwrap();
+    }
+
+    // writing the `read` functions of 3D textures
+    if dimensions == TextureDimensions::Texture3d &&
+       (ty == TextureType::Regular || ty == TextureType::Srgb || ty == TextureType::Unsigned)
+    {
+        (write!(dest, r#"
+                /// Reads the content of the texture to RAM, one layer after the other. This
+                /// method may only read `U8U8U8U8` data, as it is the only format guaranteed to
+                /// be supported across all OpenGL versions.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                /// Use `read_to_pixel_buffer` instead.
+                #[inline]
+                pub fn read<T>(&self) -> T where T: Texture3dDataSink<(u8, u8, u8, u8)> {{
+                    unsafe {{ self.unchecked_read() }}
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Reads the content of the texture into a buffer in video memory, one layer after
+                /// the other. This method may only read `U8U8U8U8` data, as it is the only format
+                /// guaranteed to be supported across all OpenGL versions.
+                ///
+                /// This operation copies the texture's data into a buffer in video memory
+                /// (a pixel buffer). Contrary to the `read` function, this operation is
+                /// done asynchronously and doesn't need a synchronization.
+                #[inline]
+                pub fn read_to_pixel_buffer(&self) -> PixelBuffer<(u8, u8, u8, u8)> {{
+                    unsafe {{ self.unchecked_read_to_pixel_buffer() }}
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Unsafely reads the content of the texture to RAM in the specified pixel format.
+                /// It is possible that the current OpenGL context does not support the given
+                /// format, in which case the returned data will be invalid.
+                pub unsafe fn unchecked_read<T, P>(&self) -> T
+                                                   where T: Texture3dDataSink<P>, P: PixelValue
+                {{
+                    let (width, height, depth) = (self.get_width(), self.get_height().unwrap(),
+                                                  self.get_depth().unwrap());
+                    let rect = Rect {{ left: 0, bottom: 0, width, height }};
+
+                    let mut data = Vec::with_capacity(width as usize * height as usize *
+                                                      depth as usize);
+                    for layer in 0 .. depth {{
+                        let image = self.0.main_level().layer(layer).unwrap()
+                                        .into_image(None).unwrap();
+                        let rows: Vec<Vec<P>> = image.raw_read(&rect);
+                        data.extend(rows.into_iter().flatten());
+                    }}
+
+                    T::from_raw(Cow::Owned(data), width, height, depth)
+                }}
+            "#)).unwrap();
+
+        (write!(dest, r#"
+                /// Unsafely reads the content of the texture into a buffer in video memory. It is
+                /// possible that the current OpenGL context does not support the given format, in
+                /// which case the returned data will be invalid.
+                pub unsafe fn unchecked_read_to_pixel_buffer<P>(&self) -> PixelBuffer<P>
+                                                                where P: PixelValue
+                {{
+                    let (width, height, depth) = (self.get_width(), self.get_height().unwrap(),
+                                                  self.get_depth().unwrap());
+                    let rect = Rect {{ left: 0, bottom: 0, width, height }};
+                    let layer_len = width as usize * height as usize;
+
+                    let pb = PixelBuffer::new_empty(self.0.get_context(),
+                                                    layer_len * depth as usize);
+                    for layer in 0 .. depth {{
+                        let dest = pb.slice(layer as usize * layer_len ..
+                                            (layer as usize + 1) * layer_len).unwrap();
+                        self.0.main_level().layer(layer).unwrap().into_image(None).unwrap()
+                              .raw_read_to_buffer(&rect, dest);
+                    }}
+                    pb
+                }}
+            "#)).unwrap();
+    }
+
+    // writing the `copy_from_buffer` function
+    if !is_compressed && !dimensions.is_multisample() && !dimensions.is_cube() {
+        (write!(dest, r#"
+                /// Uploads the content of an arbitrary buffer in video memory to the main mipmap
+                /// level of the texture, without going through the CPU.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the buffer is not large enough.
+                #[inline]
+                pub fn copy_from_buffer<P>(&self, source: BufferSlice<'_, [P]>) where P: PixelValue {{
+                    let level = self.0.main_level();
+                    let (width, height, depth) = level.get_mipmap_dimensions();
+                    level.raw_upload_from_pixel_buffer(source, 0 .. width, 0 .. height, 0 .. depth);
+                }}
+            "#)).unwrap();
+    }
+
+    // writing the `resolve_to` function
+    if dimensions == TextureDimensions::Texture2dMultisample {
+        (write!(dest, r#"
+                /// Resolves the samples of this texture into a `{target}` of the same dimensions.
+                ///
+                /// The framebuffers that are needed to perform the blit are built internally and
+                /// kept in a cache, so calling this every frame is cheap.
+                ///
+                /// Returns `BlitError::ValidationError(ValidationError::DimensionsMismatch)` if
+                /// the dimensions of the two textures are not the same.
+                pub fn resolve_to(&self, target: &crate::texture::{target})
+                                  -> Result<(), crate::BlitError>
+                {{
+                    let source = self.0.main_level().first_layer().into_image(None).unwrap();
+                    let target: &TextureAny = target;
+                    let target = target.main_level().first_layer().into_image(None).unwrap();
+
+                    let (width, height) = (source.get_width(), source.get_height().unwrap());
+                    if (width, height) != (target.get_width(), target.get_height().unwrap()) {{
+                        return Err(crate::BlitError::ValidationError(
+                            framebuffer::ValidationError::DimensionsMismatch));
+                    }}
+
+                    source.blit_to(&Rect {{ left: 0, bottom: 0, width, height }}, target,
+                                   &crate::BlitTarget {{ left: 0, bottom: 0, width: width as i32,
+                                                         height: height as i32 }},
+                                   crate::uniforms::MagnifySamplerFilter::Nearest)
+                }}
+            "#, target = name.replace("Multisample", ""))).unwrap();
     }
 
     // writing the `read_compressed_data` function
@@ -1020,6 +1486,70 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 compressed_restrictions = compressed_restrictions)).unwrap();
     }
 
+    // writing the `write_from_pixel_buffer` function
+    if dimensions == TextureDimensions::Texture2d &&
+            (ty == TextureType::Regular || ty == TextureType::Srgb)
+    {
+        (write!(dest, r#"
+                /// Uploads the content of a pixel buffer to a part of the texture, without going
+                /// through the CPU.
+                ///
+                /// The upload is executed asynchronously by the GPU. Writing to the pixel buffer
+                /// afterwards waits for the upload to be finished, so the same buffer can be used
+                /// to stream a new image at each frame.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the `Rect` is outside of the texture or if the buffer is too small.
+                #[inline]
+                pub fn write_from_pixel_buffer<P>(&self, rect: Rect, source: &PixelBuffer<P>)
+                                                  where P: PixelValue
+                {{
+                    self.main_level().write_from_pixel_buffer(rect, source)
+                }}
+
+                /// Uploads the content of a slice of any buffer to a part of the texture, without
+                /// going through the CPU.
+                ///
+                /// The pixels must be tightly packed, row by row starting from the bottom of the
+                /// `Rect`. This is meant for data that is already in a buffer, for example
+                /// written through a persistent mapping by a streaming system.
+                ///
+                /// The upload is executed asynchronously by the GPU. If the buffer is
+                /// persistently mapped, a fence is inserted so that mapping the slice again
+                /// waits for the upload to be finished.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the `Rect` is outside of the texture or if the slice is too small.
+                #[inline]
+                pub fn write_from_buffer_slice<P>(&self, rect: Rect, source: BufferSlice<'_, [P]>)
+                                                  where P: PixelValue
+                {{
+                    self.main_level().write_from_buffer_slice(rect, source)
+                }}
+
+                /// Uploads a part of a larger image to a part of the texture.
+                ///
+                /// The rows of `data` start every `stride_in_pixels` pixels, from the bottom of
+                /// the `Rect` to its top. The sub-rectangle is uploaded without being copied to a
+                /// temporary buffer when the backend supports it.
+                ///
+                /// Contrary to `write`, the mipmaps are not regenerated.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the `Rect` is outside of the texture, if `stride_in_pixels` is
+                /// smaller than the width of the `Rect` or if `data` is too small.
+                #[inline]
+                pub fn write_with_stride<P>(&self, rect: Rect, data: &[P], stride_in_pixels: u32)
+                                            where P: PixelValue
+                {{
+                    self.main_level().write_with_stride(rect, data, stride_in_pixels)
+                }}
+            "#)).unwrap();
+    }
+
     // writing the `write_compressed_data` function
     // TODO: implement for other types too
     if dimensions == TextureDimensions::Texture2d && is_compressed
@@ -1232,6 +1762,70 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                     compressed_restrictions = compressed_restrictions)).unwrap();
         }
 
+        // writing the `write_from_pixel_buffer` function for mipmaps.
+        if dimensions == TextureDimensions::Texture2d &&
+                (ty == TextureType::Regular || ty == TextureType::Srgb)
+        {
+            (write!(dest, r#"
+                    /// Uploads the content of a pixel buffer to a part of the texture level,
+                    /// without going through the CPU.
+                    ///
+                    /// Contrary to `write`, the other mipmap levels are not regenerated.
+                    ///
+                    /// ## Panic
+                    ///
+                    /// Panics if the `Rect` is outside of the texture level or if the buffer is
+                    /// too small.
+                    #[inline]
+                    pub fn write_from_pixel_buffer<P>(&self, rect: Rect, source: &PixelBuffer<P>)
+                                                      where P: PixelValue
+                    {{
+                        self.0.raw_upload_from_pixel_buffer(source.as_slice(),
+                                                            rect.left .. rect.left + rect.width,
+                                                            rect.bottom .. rect.bottom + rect.height,
+                                                            0 .. 1);
+                    }}
+
+                    /// Uploads the content of a slice of any buffer to a part of the texture
+                    /// level, without going through the CPU.
+                    ///
+                    /// See `write_from_buffer_slice` on the texture for more details.
+                    ///
+                    /// ## Panic
+                    ///
+                    /// Panics if the `Rect` is outside of the texture level or if the slice is
+                    /// too small.
+                    #[inline]
+                    pub fn write_from_buffer_slice<P>(&self, rect: Rect,
+                                                      source: BufferSlice<'_, [P]>)
+                                                      where P: PixelValue
+                    {{
+                        self.0.raw_upload_from_pixel_buffer(source,
+                                                            rect.left .. rect.left + rect.width,
+                                                            rect.bottom .. rect.bottom + rect.height,
+                                                            0 .. 1);
+                    }}
+
+                    /// Uploads a part of a larger image to a part of the texture level.
+                    ///
+                    /// See `write_with_stride` on the texture for more details.
+                    ///
+                    /// ## Panic
+                    ///
+                    /// Panics if the `Rect` is outside of the texture level, if `stride_in_pixels`
+                    /// is smaller than the width of the `Rect` or if `data` is too small.
+                    #[inline]
+                    pub fn write_with_stride<P>(&self, rect: Rect, data: &[P],
+                                                stride_in_pixels: u32)
+                                                where P: PixelValue
+                    {{
+                        self.0.raw_upload_with_stride(data, stride_in_pixels,
+                                                      rect.left .. rect.left + rect.width,
+                                                      rect.bottom .. rect.bottom + rect.height);
+                    }}
+                "#)).unwrap();
+        }
+
         // writing the `write_compressed_data` function for mipmaps.
         // TODO: implement for other types too
         if dimensions == TextureDimensions::Texture2d && is_compressed
@@ -1567,3 +2161,56 @@ fn write_dimensions_getters<W: Write>(dest: &mut W, dimensions: TextureDimension
         _ => ()
     };
 }
+
+/// Writes the implementations of `TextureBufferContent` for all the Rust types that can be
+/// interpreted by a buffer texture.
+pub fn build_buffer_texture_file<W: Write>(dest: &mut W) {
+    // the Rust type, the name of its variant in `TextureBufferContentType`, and the feature
+    // required to use the type
+    let scalars = [
+        ("u8", "U8", None),
+        ("i8", "I8", None),
+        ("u16", "U16", None),
+        ("i16", "I16", None),
+        ("u32", "U32", None),
+        ("i32", "I32", None),
+        ("half::f16", "F16", Some("half")),
+        ("f32", "F32", None),
+    ];
+
+    for &(ty, variant, feature) in scalars.iter() {
+        let cfg = match feature {
+            Some(feature) => format!("#[cfg(feature = \"{}\")]\n", feature),
+            None => String::new(),
+        };
+
+        for &components in [1, 2, 3, 4].iter() {
+            // OpenGL only supports three-component buffer textures with 32-bit components
+            if components == 3 && !variant.ends_with("32") {
+                continue;
+            }
+
+            let content_type = variant.repeat(components);
+
+            let rust_types = if components == 1 {
+                vec![ty.to_owned()]
+            } else {
+                vec![
+                    format!("({})", vec![ty; components].join(", ")),
+                    format!("[{}; {}]", ty, components),
+                ]
+            };
+
+            for rust_type in rust_types {
+                writeln!(dest, "
+                    {cfg}unsafe impl TextureBufferContent for {rust_type} {{
+                        #[inline]
+                        fn get_type() -> TextureBufferContentType {{
+                            TextureBufferContentType::{content_type}
+                        }}
+                    }}
+                ", cfg = cfg, rust_type = rust_type, content_type = content_type).unwrap();
+            }
+        }
+    }
+}
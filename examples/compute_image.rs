@@ -160,9 +160,9 @@ impl ApplicationContext for Application {
         let frame = display.draw();
         let image_unit = self
             .fract_texture
-            .image_unit(glium::uniforms::ImageUnitFormat::RGBA8UI)
-            .unwrap()
-            .set_access(glium::uniforms::ImageUnitAccess::Write);
+            .image_unit_with_access(glium::uniforms::ImageUnitFormat::RGBA8UI,
+                                    glium::uniforms::ImageUnitAccess::Write)
+            .unwrap();
         self.fractal_shader.execute(
             uniform! {
                 uWidth: self.fract_texture.width(),
@@ -173,7 +173,7 @@ impl ApplicationContext for Application {
             self.fract_texture.width(),
             self.fract_texture.height(),
             1,
-        );
+        ).unwrap();
 
         let image_unit = self
             .fract_texture
@@ -189,7 +189,7 @@ impl ApplicationContext for Application {
             self.fract_texture.width(),
             self.fract_texture.height(),
             1,
-        );
+        ).unwrap();
 
         let fract_unit = self
             .fract_texture
@@ -210,7 +210,7 @@ impl ApplicationContext for Application {
             self.fract_texture.width(),
             self.fract_texture.height(),
             1,
-        );
+        ).unwrap();
 
         // drawing a frame
         self.final_texture
@@ -0,0 +1,96 @@
+/*!
+
+This example demonstrates how to use glium with a window and an OpenGL context created by SDL2
+instead of glutin.
+
+Run it with `cargo run --example sdl2 --features sdl2_backend`. The SDL2 library must be
+installed on the system.
+
+*/
+
+#[macro_use]
+extern crate glium;
+
+use glium::backend::sdl2::sdl2;
+use glium::backend::sdl2::Display;
+use glium::index::PrimitiveType;
+use glium::Surface;
+use sdl2::event::Event;
+
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 2],
+    color: [f32; 3],
+}
+implement_vertex!(Vertex, position, color);
+
+fn main() {
+    let sdl = sdl2::init().unwrap();
+    let video = sdl.video().unwrap();
+
+    // the attributes of the OpenGL context must be set before creating the window
+    let gl_attr = video.gl_attr();
+    gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
+    gl_attr.set_context_version(3, 3);
+
+    let window = video.window("Glium SDL2 example", 800, 600)
+        .opengl()
+        .resizable()
+        .build()
+        .unwrap();
+
+    let display = Display::new(window).unwrap();
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-0.5, -0.5], color: [0.0, 1.0, 0.0] },
+        Vertex { position: [ 0.0,  0.5], color: [0.0, 0.0, 1.0] },
+        Vertex { position: [ 0.5, -0.5], color: [1.0, 0.0, 0.0] },
+    ]).unwrap();
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                               &[0u16, 1, 2]).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+                in vec3 color;
+                out vec3 vColor;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    vColor = color;
+                }
+            ",
+
+            fragment: "
+                #version 140
+
+                in vec3 vColor;
+                out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(vColor, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let mut event_pump = sdl.event_pump().unwrap();
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                   &Default::default()).unwrap();
+        frame.finish().unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+/*!
+
+This example demonstrates how to use glium in a web browser with the WebGL 2 backend.
+
+Build it with `cargo build --example websys --target wasm32-unknown-unknown --no-default-features
+--features websys_backend`, then generate the JavaScript bindings with `wasm-bindgen`. The page
+that loads the module must contain a `<canvas id="glium">` element.
+
+*/
+
+#[cfg(target_arch = "wasm32")]
+#[macro_use]
+extern crate glium;
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Copy, Clone)]
+struct Vertex {
+    position: [f32; 2],
+    color: [f32; 3],
+}
+#[cfg(target_arch = "wasm32")]
+implement_vertex!(Vertex, position, color);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use glium::backend::websys::Display;
+    use glium::index::PrimitiveType;
+    use glium::Surface;
+    use wasm_bindgen::JsCast;
+
+    let canvas = web_sys::window().unwrap()
+        .document().unwrap()
+        .get_element_by_id("glium").unwrap()
+        .dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
+
+    let display = Display::new(canvas).unwrap();
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-0.5, -0.5], color: [0.0, 1.0, 0.0] },
+        Vertex { position: [ 0.0,  0.5], color: [0.0, 0.0, 1.0] },
+        Vertex { position: [ 0.5, -0.5], color: [1.0, 0.0, 0.0] },
+    ]).unwrap();
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                               &[0u16, 1, 2]).unwrap();
+
+    // WebGL 2 only supports GLSL ES 3.00
+    let program = program!(&display,
+        300 es => {
+            vertex: "
+                #version 300 es
+
+                in vec2 position;
+                in vec3 color;
+                out vec3 vColor;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    vColor = color;
+                }
+            ",
+
+            fragment: "
+                #version 300 es
+
+                precision mediump float;
+                in vec3 vColor;
+                out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(vColor, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    // the browser presents the drawing buffer once `main` returns
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    frame.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+               &Default::default()).unwrap();
+    frame.finish().unwrap();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    println!("This example only runs in a web browser, build it for wasm32-unknown-unknown");
+}
@@ -0,0 +1,168 @@
+/*!
+Derive macros for glium.
+
+These macros are re-exported by glium when its `derive` feature is enabled, and shouldn't be
+used directly.
+*/
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Implements `UniformBlock` and `Std140` for a struct, and checks at compile time that each of
+/// its fields is at the offset that the `std140` layout rules give it.
+///
+/// The struct must be `#[repr(C)]`. Fields that are only there to add padding must be marked
+/// with `#[uniform_block(padding)]`; they are ignored when comparing the struct with the block
+/// of the program.
+///
+/// If a field isn't at the right offset, or doesn't have the same size in Rust and in GLSL,
+/// compilation fails with an error that names the field.
+#[proc_macro_derive(UniformBlock, attributes(uniform_block))]
+pub fn derive_uniform_block(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match uniform_block(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn uniform_block(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(input.generics.span(),
+                                   "`UniformBlock` can't be derived for generic structs"));
+    }
+
+    if !is_repr_c(input)? {
+        return Err(syn::Error::new(name.span(),
+                                   "`UniformBlock` can only be derived for `#[repr(C)]` structs"));
+    }
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(syn::Error::new(name.span(),
+                                            "`UniformBlock` can only be derived for structs with named fields")),
+        },
+        _ => return Err(syn::Error::new(name.span(),
+                                        "`UniformBlock` can only be derived for structs")),
+    };
+
+    let mut members = Vec::new();
+    for field in fields {
+        if !is_padding(field)? {
+            members.push((field.ident.as_ref().unwrap(), &field.ty));
+        }
+    }
+
+    if members.is_empty() {
+        return Err(syn::Error::new(name.span(),
+                                   "`UniformBlock` can't be derived for structs without members"));
+    }
+
+    let field_names = members.iter().map(|&(ident, _)| ident);
+
+    let alignments = members.iter().map(|&(_, ty)| {
+        quote! {
+            if <#ty as ::glium::uniforms::Std140>::ALIGNMENT > alignment {
+                alignment = <#ty as ::glium::uniforms::Std140>::ALIGNMENT;
+            }
+        }
+    });
+
+    let sizes = members.iter().map(|&(_, ty)| {
+        quote! {
+            offset = ::glium::uniforms::std140_round_up(offset,
+                                                         <#ty as ::glium::uniforms::Std140>::ALIGNMENT)
+                     + <#ty as ::glium::uniforms::Std140>::SIZE;
+        }
+    });
+
+    let checks = members.iter().map(|&(ident, ty)| {
+        let offset_message = format!("field `{}` of `{}` is not at its std140 offset, \
+                                      padding must be added before it", ident, name);
+        let size_message = format!("field `{}` of `{}` doesn't have the same size in Rust \
+                                    and in the std140 layout", ident, name);
+
+        quote_spanned! {ty.span()=>
+            offset = ::glium::uniforms::std140_round_up(offset,
+                                                         <#ty as ::glium::uniforms::Std140>::ALIGNMENT);
+            assert!(::core::mem::offset_of!(#name, #ident) == offset, #offset_message);
+            assert!(::core::mem::size_of::<#ty>() == <#ty as ::glium::uniforms::Std140>::SIZE,
+                    #size_message);
+            offset += <#ty as ::glium::uniforms::Std140>::SIZE;
+        }
+    });
+
+    Ok(quote! {
+        ::glium::implement_uniform_block!(#name, #(#field_names),*);
+
+        impl ::glium::uniforms::Std140 for #name {
+            const ALIGNMENT: usize = {
+                // structs are aligned like a `vec4`
+                let mut alignment = 16;
+                #(#alignments)*
+                alignment
+            };
+
+            const SIZE: usize = {
+                let mut offset = 0;
+                #(#sizes)*
+                ::glium::uniforms::std140_round_up(offset, Self::ALIGNMENT)
+            };
+        }
+
+        const _: () = {
+            let mut offset = 0;
+            #(#checks)*
+            let _ = offset;
+        };
+    })
+}
+
+fn is_repr_c(input: &DeriveInput) -> syn::Result<bool> {
+    let mut repr_c = false;
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("repr") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("C") {
+                    repr_c = true;
+                }
+
+                // skips the arguments of `align(N)` and `packed(N)`
+                if meta.input.peek(syn::token::Paren) {
+                    let _content;
+                    syn::parenthesized!(_content in meta.input);
+                }
+
+                Ok(())
+            })?;
+        }
+    }
+
+    Ok(repr_c)
+}
+
+fn is_padding(field: &syn::Field) -> syn::Result<bool> {
+    let mut padding = false;
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("uniform_block") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("padding") {
+                    padding = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `uniform_block` attribute"))
+                }
+            })?;
+        }
+    }
+
+    Ok(padding)
+}
@@ -0,0 +1,116 @@
+use glium::program::BlockLayout;
+use glium::uniforms::{Std140, UniformBlock, UniformType};
+
+#[derive(Copy, Clone, glium_macros::UniformBlock)]
+#[repr(C)]
+struct Light {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    #[uniform_block(padding)]
+    _padding: f32,
+}
+
+#[derive(Copy, Clone, glium_macros::UniformBlock)]
+#[repr(C)]
+struct Scene {
+    view: [[f32; 4]; 4],
+    lights: [Light; 8],
+    weights: [[f32; 4]; 5],
+    count: u32,
+}
+
+#[derive(Copy, Clone, glium_macros::UniformBlock)]
+#[repr(C)]
+struct ShortArrays {
+    cascades: [[f32; 4]; 2],
+    corners: [[f32; 4]; 3],
+    lights: [Light; 2],
+    single: [Light; 1],
+    samples: [[f32; 4]; 33],
+}
+
+#[test]
+fn std140_of_struct() {
+    assert_eq!(<Light as Std140>::ALIGNMENT, 16);
+    assert_eq!(<Light as Std140>::SIZE, 32);
+
+    assert_eq!(<Scene as Std140>::ALIGNMENT, 16);
+    assert_eq!(<Scene as Std140>::SIZE, 64 + 8 * 32 + 5 * 16 + 16);
+}
+
+#[test]
+fn layout_of_struct() {
+    let members = match <Light as UniformBlock>::build_layout(0) {
+        BlockLayout::Struct { members } => members,
+        _ => panic!()
+    };
+
+    // padding fields are not part of the block
+    assert_eq!(members.len(), 3);
+    assert_eq!(members[1].0, "intensity");
+
+    match members[2].1 {
+        BlockLayout::BasicType { ty: UniformType::FloatVec3, offset_in_buffer: 16 } => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn matches_own_layout() {
+    let layout = <Scene as UniformBlock>::build_layout(0);
+    <Scene as UniformBlock>::matches(&layout, 0).unwrap();
+}
+
+#[test]
+fn std140_of_short_arrays() {
+    assert_eq!(<[[f32; 4]; 2] as Std140>::SIZE, 32);
+    assert_eq!(<[[f32; 4]; 3] as Std140>::SIZE, 48);
+    assert_eq!(<[Light; 2] as Std140>::SIZE, 64);
+
+    // arrays of two to four scalars are vectors
+    assert_eq!(<[f32; 3] as Std140>::ALIGNMENT, 16);
+    assert_eq!(<[f32; 3] as Std140>::SIZE, 12);
+    assert_eq!(<[f64; 2] as Std140>::SIZE, 16);
+
+    assert_eq!(<ShortArrays as Std140>::SIZE, 32 + 48 + 64 + 32 + 33 * 16);
+}
+
+#[test]
+fn layout_of_short_arrays() {
+    let members = match <ShortArrays as UniformBlock>::build_layout(0) {
+        BlockLayout::Struct { members } => members,
+        _ => panic!()
+    };
+
+    match members[0].1 {
+        BlockLayout::Array { ref content, length: 2 } => match **content {
+            BlockLayout::BasicType { ty: UniformType::FloatVec4, offset_in_buffer: 0 } => (),
+            _ => panic!()
+        },
+        _ => panic!()
+    }
+
+    match members[2].1 {
+        BlockLayout::Array { ref content, length: 2 } => match **content {
+            BlockLayout::Struct { .. } => (),
+            _ => panic!()
+        },
+        _ => panic!()
+    }
+
+    <ShortArrays as UniformBlock>::matches(&BlockLayout::Struct { members }, 0).unwrap();
+}
+
+#[test]
+fn matrices_are_not_arrays() {
+    match <[[f32; 4]; 4] as UniformBlock>::build_layout(0) {
+        BlockLayout::BasicType { ty: UniformType::FloatMat4, offset_in_buffer: 0 } => (),
+        _ => panic!()
+    }
+
+    match <[f32; 4] as UniformBlock>::build_layout(0) {
+        BlockLayout::BasicType { ty: UniformType::FloatVec4, offset_in_buffer: 0 } => (),
+        _ => panic!()
+    }
+}
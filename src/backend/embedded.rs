@@ -0,0 +1,113 @@
+/*!
+
+Backend for OpenGL contexts that are owned by a UI toolkit.
+
+Toolkits like GTK (with `GtkGLArea`), Qt (with `QOpenGLWidget`) or wxWidgets create the OpenGL
+context themselves, make it current before asking the application to render, and present the
+result on their own. Some of them also render the widget into a framebuffer object instead of
+the framebuffer of the window, in which case this framebuffer object must be used wherever glium
+would use the default framebuffer.
+
+A `ForeignContext` gets all this information from functions provided by the application.
+
+# Example
+
+```no_run
+# fn example(get_proc_address: fn(&str) -> *const std::os::raw::c_void,
+#            widget_size: fn() -> (u32, u32), widget_fbo: fn() -> u32) {
+use glium::backend::embedded::ForeignContext;
+
+let backend = unsafe {
+    ForeignContext::new(get_proc_address, widget_size, widget_fbo)
+};
+let context = unsafe { glium::backend::Context::new(backend, false, Default::default()) }
+    .unwrap();
+# }
+```
+
+*/
+use std::fmt;
+use std::os::raw::c_void;
+
+use crate::backend::Backend;
+use crate::SwapBuffersError;
+
+/// An implementation of the `Backend` trait for an OpenGL context that is managed by someone
+/// else.
+///
+/// The context is assumed to be current whenever glium is used, and swapping buffers does
+/// nothing. Create the `Context` with `check_current_context` set to `false`.
+pub struct ForeignContext {
+    get_proc_address: Box<dyn Fn(&str) -> *const c_void>,
+    get_framebuffer_dimensions: Box<dyn Fn() -> (u32, u32)>,
+    get_default_framebuffer: Box<dyn Fn() -> u32>,
+}
+
+impl ForeignContext {
+    /// Builds a backend from the functions that return the address of an OpenGL function, the
+    /// dimensions of the default framebuffer, and the id of the framebuffer object to use as
+    /// the default framebuffer (`0` for the framebuffer of the window).
+    ///
+    /// The dimensions and the id of the default framebuffer are queried each time they are
+    /// needed, so they can change, for example when the widget is resized.
+    ///
+    /// # Safety
+    ///
+    /// `get_proc_address` must return valid function pointers for the OpenGL context, and the
+    /// framebuffer object must exist whenever glium uses it.
+    pub unsafe fn new<P, D, F>(get_proc_address: P, get_framebuffer_dimensions: D,
+                               get_default_framebuffer: F) -> ForeignContext
+        where P: Fn(&str) -> *const c_void + 'static,
+              D: Fn() -> (u32, u32) + 'static,
+              F: Fn() -> u32 + 'static
+    {
+        ForeignContext {
+            get_proc_address: Box::new(get_proc_address),
+            get_framebuffer_dimensions: Box::new(get_framebuffer_dimensions),
+            get_default_framebuffer: Box::new(get_default_framebuffer),
+        }
+    }
+}
+
+impl fmt::Debug for ForeignContext {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "[glium::backend::embedded::ForeignContext]")
+    }
+}
+
+unsafe impl Backend for ForeignContext {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the toolkit presents the framebuffer by itself
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        (self.get_proc_address)(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        (self.get_framebuffer_dimensions)()
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // the size is controlled by the toolkit
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+    }
+
+    #[inline]
+    fn get_default_framebuffer(&self) -> u32 {
+        (self.get_default_framebuffer)()
+    }
+}
@@ -0,0 +1,321 @@
+/*!
+
+Rendering without a window.
+
+A `Headless` renders in renderbuffers that it owns instead of a window. These renderbuffers are
+attached to a framebuffer object that glium uses as the default framebuffer, which means that
+`draw` and `read_front_buffer` work as with a `Display`. The size of the renderbuffers can be
+changed with `resize`.
+
+The OpenGL context can be made current with a pbuffer surface, or with no surface at all on EGL
+implementations that support surfaceless contexts. The latter requires the `surfaceless` feature.
+
+# Example
+
+```no_run
+# #[cfg(feature = "surfaceless")]
+# fn example() {
+use glium::Surface;
+use glium::backend::glutin::headless::Headless;
+
+let headless = Headless::new_surfaceless((256, 256)).unwrap();
+
+let mut target = headless.draw();
+target.clear_color(0.0, 0.0, 1.0, 1.0);
+target.finish().unwrap();
+
+let image: glium::texture::RawImage2d<'_, u8> = headless.read_front_buffer().unwrap();
+# }
+```
+
+*/
+use std::cell::Cell;
+use std::ffi::CString;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::context;
+use crate::debug;
+use crate::gl;
+use crate::glutin::context::{NotCurrentContext, PossiblyCurrentContext};
+use crate::glutin::display::GetGlDisplay;
+use crate::glutin::prelude::*;
+use crate::glutin::surface::{PbufferSurface, Surface};
+use crate::version::{self, Api, Version};
+use crate::ContextExt;
+use crate::SwapBuffersError;
+use crate::{Frame, IncompatibleOpenGl};
+
+use super::DisplayCreationError;
+
+/// A GL context that renders in offscreen renderbuffers, combined with a facade for drawing
+/// upon.
+///
+/// The renderbuffers have the `RGBA8` and `DEPTH24_STENCIL8` formats.
+pub struct Headless {
+    // contains everything related to the current glium context and its state
+    context: Rc<context::Context>,
+    // The renderbuffers used as the default framebuffer, shared with the backend.
+    target: Rc<OffscreenTarget>,
+}
+
+/// An implementation of the `Backend` trait for `Headless`.
+struct HeadlessBackend {
+    context: HeadlessContext,
+    target: Rc<OffscreenTarget>,
+}
+
+/// The glutin context of a `Headless`, and the surface it is made current with.
+enum HeadlessContext {
+    Pbuffer(PossiblyCurrentContext, Surface<PbufferSurface>),
+    #[cfg(feature = "surfaceless")]
+    Surfaceless(glutin::api::egl::context::PossiblyCurrentContext),
+}
+
+/// A framebuffer object with a color renderbuffer and a depth-stencil renderbuffer.
+///
+/// The objects are never deleted explicitly, as they live as long as the OpenGL context.
+struct OffscreenTarget {
+    framebuffer: gl::types::GLuint,
+    color: gl::types::GLuint,
+    depth_stencil: gl::types::GLuint,
+    dimensions: Cell<(u32, u32)>,
+}
+
+impl Headless {
+    /// Builds a `Headless` from a context and a pbuffer surface to make it current with.
+    ///
+    /// The size of the pbuffer doesn't matter, as nothing is drawn on it. `dimensions` is the
+    /// initial size of the renderbuffers.
+    pub fn new(
+        context: NotCurrentContext,
+        surface: Surface<PbufferSurface>,
+        dimensions: (u32, u32),
+    ) -> Result<Headless, DisplayCreationError> {
+        let context = context.make_current(&surface)?;
+        Headless::new_inner(HeadlessContext::Pbuffer(context, surface), dimensions)
+    }
+
+    /// Builds a `Headless` from an EGL context, which is made current without any surface.
+    ///
+    /// The EGL implementation must support `EGL_KHR_surfaceless_context`.
+    #[cfg(feature = "surfaceless")]
+    pub fn from_surfaceless_context(
+        context: glutin::api::egl::context::NotCurrentContext,
+        dimensions: (u32, u32),
+    ) -> Result<Headless, DisplayCreationError> {
+        let context = context.make_current_surfaceless()?;
+        Headless::new_inner(HeadlessContext::Surfaceless(context), dimensions)
+    }
+
+    /// Builds a `Headless` with a surfaceless context on the first EGL device of the system.
+    ///
+    /// No window system is needed, which makes this suitable for tests running on servers and
+    /// continuous integration.
+    #[cfg(feature = "surfaceless")]
+    pub fn new_surfaceless(dimensions: (u32, u32)) -> Result<Headless, DisplayCreationError> {
+        use glutin::api::egl::device::Device;
+        use glutin::api::egl::display::Display;
+        use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+        use glutin::context::ContextAttributesBuilder;
+        use glutin::error::ErrorKind;
+
+        let device = Device::query_devices()?
+            .next()
+            .ok_or_else(|| glutin::error::Error::from(ErrorKind::NotFound))?;
+        let display = unsafe { Display::with_device(&device, None)? };
+
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .build();
+        let config = unsafe { display.find_configs(template)? }
+            .next()
+            .ok_or_else(|| glutin::error::Error::from(ErrorKind::BadConfig))?;
+
+        let attributes = ContextAttributesBuilder::new().build(None);
+        let context = unsafe { display.create_context(&config, &attributes)? };
+
+        Headless::from_surfaceless_context(context, dimensions)
+    }
+
+    fn new_inner(
+        context: HeadlessContext,
+        dimensions: (u32, u32),
+    ) -> Result<Headless, DisplayCreationError> {
+        // the framebuffer object must exist before the glium context is created, so that the
+        // capabilities of the default framebuffer are the ones of the renderbuffers
+        let target = unsafe {
+            let gl = gl::Gl::load_with(|symbol| context.get_proc_address(symbol) as *const _);
+            Rc::new(OffscreenTarget::new(&gl, dimensions)?)
+        };
+
+        let backend = HeadlessBackend { context, target: target.clone() };
+        let context = unsafe {
+            context::Context::new(backend, true, debug::DebugCallbackBehavior::default())
+        }?;
+
+        Ok(Headless { context, target })
+    }
+
+    /// Changes the size of the renderbuffers.
+    ///
+    /// Their content is undefined afterwards.
+    pub fn resize(&self, new_size: (u32, u32)) {
+        let mut ctxt = self.context.make_current();
+        unsafe { self.target.resize(ctxt.gl, new_size) };
+        ctxt.state.renderbuffer = self.target.depth_stencil;
+    }
+
+    /// Start drawing on the renderbuffers.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. Destroying the `Frame`
+    /// doesn't present anything, and the renderbuffers can be read afterwards, for example with
+    /// `read_front_buffer`.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        Frame::new(self.context.clone(), self.target.dimensions.get())
+    }
+}
+
+impl fmt::Debug for Headless {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::glutin::headless::Headless]")
+    }
+}
+
+impl Deref for Headless {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Headless {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+impl HeadlessContext {
+    fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        match self {
+            HeadlessContext::Pbuffer(context, _) => context.display().get_proc_address(&symbol),
+            #[cfg(feature = "surfaceless")]
+            HeadlessContext::Surfaceless(context) => context.display().get_proc_address(&symbol),
+        }
+    }
+}
+
+impl OffscreenTarget {
+    /// Creates the renderbuffers and the framebuffer object.
+    ///
+    /// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread, and
+    /// the bindings of framebuffers and renderbuffers must be the default ones.
+    unsafe fn new(gl: &gl::Gl, dimensions: (u32, u32))
+                  -> Result<OffscreenTarget, IncompatibleOpenGl>
+    {
+        let version = version::get_gl_version(gl);
+        if !(version >= Version(Api::Gl, 3, 0) || version >= Version(Api::GlEs, 3, 0)) {
+            return Err(IncompatibleOpenGl("OpenGL 3.0 or OpenGL ES 3.0 is required to render \
+                                           without a window".to_owned()));
+        }
+
+        let mut framebuffer = 0;
+        let mut renderbuffers = [0; 2];
+        gl.GenFramebuffers(1, &mut framebuffer);
+        gl.GenRenderbuffers(2, renderbuffers.as_mut_ptr());
+
+        let target = OffscreenTarget {
+            framebuffer,
+            color: renderbuffers[0],
+            depth_stencil: renderbuffers[1],
+            dimensions: Cell::new(dimensions),
+        };
+        target.resize(gl, dimensions);
+
+        gl.BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+        gl.FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER,
+                                   target.color);
+        gl.FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT,
+                                   gl::RENDERBUFFER, target.depth_stencil);
+        let status = gl.CheckFramebufferStatus(gl::FRAMEBUFFER);
+
+        gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+        gl.BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(IncompatibleOpenGl(format!("The offscreen framebuffer is incomplete \
+                                                   (status 0x{:x})", status)));
+        }
+
+        Ok(target)
+    }
+
+    /// Allocates the storage of the renderbuffers. Leaves the depth-stencil renderbuffer bound.
+    unsafe fn resize(&self, gl: &gl::Gl, new_size: (u32, u32)) {
+        // renderbuffers can't be empty
+        let width = new_size.0.max(1) as gl::types::GLsizei;
+        let height = new_size.1.max(1) as gl::types::GLsizei;
+
+        gl.BindRenderbuffer(gl::RENDERBUFFER, self.color);
+        gl.RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width, height);
+        gl.BindRenderbuffer(gl::RENDERBUFFER, self.depth_stencil);
+        gl.RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
+
+        self.dimensions.set((width as u32, height as u32));
+    }
+}
+
+unsafe impl Backend for HeadlessBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // there is nothing to present
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        self.context.get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.target.dimensions.get()
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // the renderbuffers are resized by `Headless::resize`, which has access to the state
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        match &self.context {
+            HeadlessContext::Pbuffer(context, _) => context.is_current(),
+            #[cfg(feature = "surfaceless")]
+            HeadlessContext::Surfaceless(context) => context.is_current(),
+        }
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        match &self.context {
+            HeadlessContext::Pbuffer(context, surface) => context.make_current(surface).unwrap(),
+            #[cfg(feature = "surfaceless")]
+            HeadlessContext::Surfaceless(context) => context.make_current_surfaceless().unwrap(),
+        }
+    }
+
+    #[inline]
+    fn get_default_framebuffer(&self) -> u32 {
+        self.target.framebuffer
+    }
+}
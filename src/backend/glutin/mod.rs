@@ -16,10 +16,13 @@ use crate::backend::Backend;
 use crate::backend::Context;
 use crate::context;
 use crate::debug;
-use crate::glutin::context::PossiblyCurrentContext;
+use crate::framebuffer::TransientPool;
+use crate::glutin::config::GetGlConfig;
+use crate::glutin::context::{ContextAttributesBuilder, PossiblyCurrentContext};
 use crate::glutin::display::GetGlDisplay;
 use crate::glutin::prelude::*;
-use crate::glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+use crate::glutin::surface::{PbufferSurface, ResizeableSurface, SurfaceAttributesBuilder, SurfaceTypeTrait};
+use crate::upload::ResourceUploader;
 use crate::SwapBuffersError;
 use crate::{Frame, IncompatibleOpenGl};
 use std::cell::RefCell;
@@ -32,11 +35,13 @@ use std::os::raw::c_void;
 use std::rc::Rc;
 
 #[cfg(feature = "simple_window_builder")]
-pub use self::simple_window_builder::SimpleWindowBuilder;
+pub use self::simple_window_builder::{SimpleWindowBuilder, SimpleWindowConfig};
 
 #[cfg(feature = "simple_window_builder")]
 pub mod simple_window_builder;
 
+pub mod headless;
+
 /// Wraps a glutin context together with the corresponding Surface.
 /// This is necessary so that we can swap buffers and determine the framebuffer size within glium.
 pub struct ContextSurfacePair<T: SurfaceTypeTrait + ResizeableSurface> {
@@ -93,6 +98,8 @@ pub struct Display<T: SurfaceTypeTrait + ResizeableSurface + 'static> {
     context: Rc<context::Context>,
     // The glutin Surface alongside its associated glutin Context.
     gl_context: Rc<RefCell<Option<ContextSurfacePair<T>>>>,
+    // Scratch render targets, recycled at each call to `draw`.
+    transient_pool: Rc<TransientPool>,
 }
 
 /// An implementation of the `Backend` trait for glutin.
@@ -101,6 +108,12 @@ pub struct GlutinBackend<T: SurfaceTypeTrait + ResizeableSurface>(
     Rc<RefCell<Option<ContextSurfacePair<T>>>>,
 );
 
+/// Backend of the contexts created by `Display::new_shared_upload_context`.
+struct UploadBackend {
+    context: PossiblyCurrentContext,
+    surface: Surface<PbufferSurface>,
+}
+
 /// Error that can happen while creating a glium display.
 #[derive(Debug)]
 pub enum DisplayCreationError {
@@ -180,10 +193,51 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
         let context = unsafe { context::Context::new(glutin_backend, checked, debug) }?;
         Ok(Display {
             gl_context: gl_window,
+            transient_pool: Rc::new(TransientPool::new(&context)),
             context,
         })
     }
 
+    /// Replaces the context and surface of this display, for example after the window has been
+    /// recreated.
+    ///
+    /// The new context must share its lists with the old one, for example by creating it with
+    /// the attributes returned by `shared_context_attributes`. The buffers, textures and
+    /// programs that were created with the display keep working, and the bindless handles that
+    /// were resident are made resident again. The framebuffer objects and vertex array objects
+    /// that glium caches internally are destroyed, and are created again in the new context when
+    /// they are needed.
+    ///
+    /// Queries, transform feedback objects and program pipelines aren't shared between contexts.
+    /// The ones that are still alive are returned, and must be destroyed and created again by
+    /// the user. Destroying them doesn't affect the new context.
+    pub fn rebuild(
+        &self,
+        context: PossiblyCurrentContext,
+        surface: Surface<T>,
+    ) -> Result<Vec<context::NonSharedObject>, IncompatibleOpenGl> {
+        // the caches must be purged while the old context is still alive
+        self.context.purge_non_shared_caches();
+        let lost_objects = self.context.get_non_shared_objects();
+
+        *self.gl_context.borrow_mut() = Some(ContextSurfacePair::new(context, surface));
+        unsafe { self.context.rebuild_after_purge(GlutinBackend(self.gl_context.clone())) }?;
+
+        Ok(lost_objects)
+    }
+
+    /// Returns a builder for the attributes of a new context that shares its lists with the
+    /// context of this display, and that uses the same API. This is the context to pass to
+    /// `rebuild`.
+    pub fn shared_context_attributes(&self) -> ContextAttributesBuilder {
+        let pair = self.gl_context.borrow();
+        let pair = pair.as_ref().unwrap();
+
+        ContextAttributesBuilder::new()
+            .with_context_api(pair.context.context_api())
+            .with_sharing(&pair.context)
+    }
+
     /// Resize the underlying surface.
     #[inline]
     pub fn resize(&self, new_size: (u32, u32)) {
@@ -198,9 +252,54 @@ impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
     /// Note that destroying a `Frame` is immediate, even if vsync is enabled.
     #[inline]
     pub fn draw(&self) -> Frame {
+        self.transient_pool.next_frame();
         let dimensions = self.get_framebuffer_dimensions();
         Frame::new(self.context.clone(), dimensions)
     }
+
+    /// Returns the pool of scratch render targets of this display.
+    ///
+    /// The pool advances to the next frame and trims its unused targets at each call to `draw`.
+    #[inline]
+    pub fn get_transient_pool(&self) -> &TransientPool {
+        &self.transient_pool
+    }
+}
+
+impl<T: SurfaceTypeTrait + ResizeableSurface> Display<T> {
+    /// Creates a second context that shares its objects with this one, and starts a
+    /// `ResourceUploader` that uses it from a background thread.
+    ///
+    /// The new context is created with the same config and API as the context of this display,
+    /// and is made current with a 1x1 pbuffer surface on the upload thread.
+    /// If it can't be made current, the uploads fail with `UploadError::ContextCreationError`.
+    pub fn new_shared_upload_context(&self) -> Result<ResourceUploader, DisplayCreationError> {
+        let (context, surface) = {
+            let pair = self.gl_context.borrow();
+            let pair = pair.as_ref().unwrap();
+            let display = pair.context.display();
+            let config = pair.context.config();
+
+            let attributes = ContextAttributesBuilder::new()
+                .with_context_api(pair.context.context_api())
+                .with_sharing(&pair.context)
+                .build(None);
+            let context = unsafe { display.create_context(&config, &attributes)? };
+
+            let attributes = SurfaceAttributesBuilder::<PbufferSurface>::new()
+                .build(NonZeroU32::new(1).unwrap(), NonZeroU32::new(1).unwrap());
+            let surface = unsafe { display.create_pbuffer_surface(&config, &attributes)? };
+
+            (context, surface)
+        };
+
+        Ok(unsafe {
+            ResourceUploader::new(self, move || {
+                let context = context.make_current(&surface)?;
+                Ok::<_, glutin::error::Error>(UploadBackend { context, surface })
+            })
+        })
+    }
 }
 
 impl fmt::Display for DisplayCreationError {
@@ -307,3 +406,35 @@ unsafe impl<T: SurfaceTypeTrait + ResizeableSurface> Backend for GlutinBackend<T
             .unwrap();
     }
 }
+
+unsafe impl Backend for UploadBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.context.display().get_proc_address(&symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.context.make_current(&self.surface).unwrap();
+    }
+}
@@ -17,6 +17,7 @@ use crate::glutin::config::{Config, ConfigTemplateBuilder};
 use crate::winit::window::Window;
 use crate::winit::window::WindowAttributes;
 use glutin_winit::DisplayBuilder;
+use std::cmp::Reverse;
 use std::error::Error;
 use std::num::NonZeroU32;
 
@@ -76,7 +77,35 @@ impl GliumEventLoop for winit::event_loop::ActiveEventLoop {
 pub struct SimpleWindowBuilder {
     attributes: WindowAttributes,
     config_template_builder: ConfigTemplateBuilder,
-    vsync: bool
+    vsync: bool,
+    multisampling: u8,
+    depth_bits: u8,
+    stencil_bits: u8,
+    srgb: Option<bool>,
+    gl_version: Option<crate::Version>,
+    gl_profile: Option<crate::Profile>,
+}
+
+/// What was actually obtained by [`SimpleWindowBuilder::build_with_config`].
+///
+/// The requested values are preferences, and the platform is free to give something different.
+#[derive(Debug, Clone)]
+pub struct SimpleWindowConfig {
+    /// Number of samples of the default framebuffer. `0` if it isn't multisampled.
+    pub samples: u8,
+    /// Number of bits of the depth buffer. `0` if there is none.
+    pub depth_bits: u8,
+    /// Number of bits of the stencil buffer. `0` if there is none.
+    pub stencil_bits: u8,
+    /// True if the window surface was created with the sRGB attribute.
+    pub srgb: bool,
+    /// Version of the context.
+    pub version: crate::Version,
+    /// Profile of the context. `None` if it is not applicable.
+    pub profile: Option<crate::Profile>,
+    /// True if vsync was requested and successfully enabled. If setting the swap interval
+    /// failed, this is `false` and the platform's default swap interval is used.
+    pub vsync: bool,
 }
 
 impl SimpleWindowBuilder {
@@ -87,8 +116,13 @@ impl SimpleWindowBuilder {
                 .with_title("Simple Glium Window")
                 .with_inner_size(winit::dpi::PhysicalSize::new(800, 480)),
             config_template_builder: ConfigTemplateBuilder::new(),
-            vsync: true
-
+            vsync: true,
+            multisampling: 0,
+            depth_bits: 0,
+            stencil_bits: 0,
+            srgb: None,
+            gl_version: None,
+            gl_profile: None,
         }
     }
 
@@ -127,38 +161,126 @@ impl SimpleWindowBuilder {
     }
 
     /// Replace the used vsync configuration
+    ///
+    /// This is only a request: if the platform refuses it, the default swap interval is kept.
     pub fn with_vsync(mut self, vsync: bool) -> Self {
         self.vsync = vsync;
         self
     }
 
+    /// Requests a multisampled default framebuffer with this number of samples.
+    ///
+    /// The configuration with the closest number of samples that is not lower than the requested
+    /// one is preferred. `0` means no preference, which is the default.
+    pub fn with_multisampling(mut self, samples: u8) -> Self {
+        self.multisampling = samples;
+        self
+    }
+
+    /// Requests a depth buffer with at least this number of bits. Defaults to `0`.
+    pub fn with_depth_buffer(mut self, bits: u8) -> Self {
+        self.depth_bits = bits;
+        self
+    }
+
+    /// Requests a stencil buffer with at least this number of bits. Defaults to `0`.
+    pub fn with_stencil_buffer(mut self, bits: u8) -> Self {
+        self.stencil_bits = bits;
+        self
+    }
+
+    /// Requests an sRGB-capable default framebuffer, or explicitly requests a linear one.
+    /// If this is not set, a linear framebuffer is used.
+    pub fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = Some(srgb);
+        self
+    }
+
+    /// Requests a specific version of OpenGL or OpenGL ES.
+    /// If this is not set, glutin picks the version.
+    pub fn with_gl_version(mut self, version: crate::Version) -> Self {
+        self.gl_version = Some(version);
+        self
+    }
+
+    /// Requests a specific OpenGL profile.
+    /// If this is not set, glutin picks the profile.
+    pub fn with_gl_profile(mut self, profile: crate::Profile) -> Self {
+        self.gl_profile = Some(profile);
+        self
+    }
+
     /// Create a new [`Window`] and [`Display`]
     /// with the specified parameters.
+    ///
+    /// If the platform refuses the swap interval requested with
+    /// [`with_vsync`](SimpleWindowBuilder::with_vsync), the window is still created with the
+    /// platform's default swap interval instead of panicking. Use
+    /// [`build_with_config`](SimpleWindowBuilder::build_with_config) to find out whether vsync
+    /// was enabled.
     pub fn build(
         self,
         event_loop: &impl GliumEventLoop,
     ) -> (
         Window,
         Display<glutin::surface::WindowSurface>,
+    ) {
+        let (window, display, _) = self.build_with_config(event_loop);
+        (window, display)
+    }
+
+    /// Same as [`build`](SimpleWindowBuilder::build), but also returns what was actually
+    /// obtained from the platform.
+    pub fn build_with_config(
+        self,
+        event_loop: &impl GliumEventLoop,
+    ) -> (
+        Window,
+        Display<glutin::surface::WindowSurface>,
+        SimpleWindowConfig,
     ) {
         use glutin::prelude::*;
         use raw_window_handle::HasWindowHandle;
 
+        let multisampling = self.multisampling;
+        let depth_bits = self.depth_bits;
+        let stencil_bits = self.stencil_bits;
+        let srgb = self.srgb;
+
         // First we start by opening a new Window
         let display_builder =
             DisplayBuilder::new().with_window_attributes(Some(self.attributes));
-        let config_template_builder = ConfigTemplateBuilder::new();
-        let (window, gl_config) = event_loop.build(display_builder, self.config_template_builder, |mut configs| {
-                // Just use the first configuration since we don't have any special preferences here
-                configs.next().unwrap()
+        let (window, gl_config) = event_loop.build(display_builder, self.config_template_builder, |configs| {
+                // The requirements are only preferences, so that we always get a configuration.
+                // Ties are broken in favor of the first configuration.
+                configs.enumerate().max_by_key(|(index, config)| {
+                    let samples = config.num_samples();
+                    let samples_score = if samples >= multisampling {
+                        512 - u16::from(samples - multisampling)
+                    } else {
+                        u16::from(samples)
+                    };
+
+                    (
+                        config.depth_size() >= depth_bits,
+                        config.stencil_size() >= stencil_bits,
+                        srgb.map_or(true, |srgb| config.srgb_capable() == srgb),
+                        samples_score,
+                        Reverse(*index),
+                    )
+                }).map(|(_, config)| config).unwrap()
             })
             .unwrap();
         let window = window.unwrap();
 
+        // The sRGB attribute is only honored if the configuration supports it
+        let srgb = srgb.unwrap_or(false) && gl_config.srgb_capable();
+
         // Now we get the window size to use as the initial size of the Surface
         let (width, height): (u32, u32) = window.inner_size().into();
         let attrs =
             glutin::surface::SurfaceAttributesBuilder::<glutin::surface::WindowSurface>::new()
+                .with_srgb(Some(srgb))
                 .build(
                     window.window_handle().expect("couldn't obtain raw window handle").into(),
                     NonZeroU32::new(width).unwrap(),
@@ -172,7 +294,21 @@ impl SimpleWindowBuilder {
                 .create_window_surface(&gl_config, &attrs)
                 .unwrap()
         };
-        let context_attributes = glutin::context::ContextAttributesBuilder::new()
+        let mut context_attributes = glutin::context::ContextAttributesBuilder::new();
+        if let Some(crate::Version(api, major, minor)) = self.gl_version {
+            let version = Some(glutin::context::Version::new(major, minor));
+            context_attributes = context_attributes.with_context_api(match api {
+                crate::Api::Gl => glutin::context::ContextApi::OpenGl(version),
+                crate::Api::GlEs => glutin::context::ContextApi::Gles(version),
+            });
+        }
+        if let Some(profile) = self.gl_profile {
+            context_attributes = context_attributes.with_profile(match profile {
+                crate::Profile::Core => glutin::context::GlProfile::Core,
+                crate::Profile::Compatibility => glutin::context::GlProfile::Compatibility,
+            });
+        }
+        let context_attributes = context_attributes
             .build(Some(window.window_handle().expect("couldn't obtain raw window handle").into()));
         let current_context = Some(unsafe {
             gl_config
@@ -189,10 +325,20 @@ impl SimpleWindowBuilder {
         } else {
             glutin::surface::SwapInterval::DontWait
         };
-        surface.set_swap_interval(&current_context, swap_interval).unwrap();
+        let vsync = surface.set_swap_interval(&current_context, swap_interval).is_ok() && self.vsync;
 
         let display = Display::from_context_surface(current_context, surface).unwrap();
 
-        (window, display)
+        let config = SimpleWindowConfig {
+            samples: gl_config.num_samples(),
+            depth_bits: gl_config.depth_size(),
+            stencil_bits: gl_config.stencil_size(),
+            srgb,
+            version: *display.get_opengl_version(),
+            profile: display.get_opengl_profile(),
+            vsync,
+        };
+
+        (window, display, config)
     }
 }
@@ -24,11 +24,20 @@ use crate::context::ExtensionsList;
 use crate::version::Version;
 
 pub use crate::context::Context;
+pub use crate::context::DebugGroupGuard;
 pub use crate::context::ReleaseBehavior;
 
+pub mod embedded;
+
 #[cfg(feature = "glutin")]
 pub mod glutin;
 
+#[cfg(feature = "sdl2_backend")]
+pub mod sdl2;
+
+#[cfg(all(feature = "websys_backend", target_arch = "wasm32"))]
+pub mod websys;
+
 #[cfg(feature = "simple_window_builder")]
 pub use winit;
 
@@ -56,6 +65,15 @@ pub unsafe trait Backend {
 
     /// Makes the OpenGL context the current context in the current thread.
     unsafe fn make_current(&self);
+
+    /// Returns the id of the framebuffer object that glium must use as the default framebuffer.
+    ///
+    /// This is `0`, the framebuffer of the window, unless the OpenGL context is owned by a UI
+    /// toolkit that renders into a framebuffer object of its own.
+    #[inline]
+    fn get_default_framebuffer(&self) -> u32 {
+        0
+    }
 }
 
 unsafe impl<T> Backend for Rc<T> where T: Backend {
@@ -82,6 +100,10 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
     unsafe fn make_current(&self) {
         self.deref().make_current();
     }
+
+    fn get_default_framebuffer(&self) -> u32 {
+        self.deref().get_default_framebuffer()
+    }
 }
 
 /// Trait for types that provide a safe access for glium functions.
@@ -0,0 +1,239 @@
+#![cfg(feature = "sdl2_backend")]
+/*!
+
+Backend implementation for the SDL2 library
+
+# Features
+
+Only available if the 'sdl2_backend' feature is enabled.
+
+*/
+pub use sdl2;
+use sdl2::video::{GLContext, Window};
+
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::context;
+use crate::debug;
+use crate::framebuffer::TransientPool;
+use crate::SwapBuffersError;
+use crate::{Frame, IncompatibleOpenGl};
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+/// A GL context combined with a facade for drawing upon.
+///
+/// The `Display` uses an **SDL2** window and an OpenGL context created for it. The attributes
+/// of the context, like its version and profile, are the ones set with
+/// `VideoSubsystem::gl_attr` before creating the window.
+#[derive(Clone)]
+pub struct Display {
+    // contains everything related to the current glium context and its state
+    context: Rc<context::Context>,
+    // The SDL window and its OpenGL context.
+    backend: Rc<Sdl2Backend>,
+    // Scratch render targets, recycled at each call to `draw`.
+    transient_pool: Rc<TransientPool>,
+}
+
+/// An implementation of the `Backend` trait for SDL2.
+pub struct Sdl2Backend {
+    window: Window,
+    gl_context: GLContext,
+}
+
+/// Error that can happen while creating a glium display.
+#[derive(Debug)]
+pub enum DisplayCreationError {
+    /// SDL couldn't create the OpenGL context.
+    SdlError(String),
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Debug for Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::sdl2::Display]")
+    }
+}
+
+impl Display {
+    /// Creates an OpenGL context for the window and a glium `Display` that uses it.
+    ///
+    /// The window must have been built with `WindowBuilder::opengl`.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(window: Window) -> Result<Self, DisplayCreationError> {
+        let gl_context = window.gl_create_context().map_err(DisplayCreationError::SdlError)?;
+        Self::from_gl_context(window, gl_context).map_err(From::from)
+    }
+
+    /// Create a new glium `Display` from the given window and OpenGL context.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn from_gl_context(window: Window, gl_context: GLContext)
+                           -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::with_debug(window, gl_context, Default::default())
+    }
+
+    /// Create a new glium `Display` from the given window and OpenGL context.
+    ///
+    /// This function does the same as `from_gl_context`, except that the resulting context
+    /// will assume that the current OpenGL context will never change.
+    pub unsafe fn unchecked(window: Window, gl_context: GLContext)
+                            -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::unchecked_with_debug(window, gl_context, Default::default())
+    }
+
+    /// The same as the `from_gl_context` constructor, but allows for specifying debug callback
+    /// behaviour.
+    pub fn with_debug(window: Window, gl_context: GLContext,
+                      debug: debug::DebugCallbackBehavior) -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::new_inner(window, gl_context, debug, true)
+    }
+
+    /// The same as the `unchecked` constructor, but allows for specifying debug callback
+    /// behaviour.
+    pub unsafe fn unchecked_with_debug(window: Window, gl_context: GLContext,
+                                       debug: debug::DebugCallbackBehavior)
+                                       -> Result<Self, IncompatibleOpenGl>
+    {
+        Self::new_inner(window, gl_context, debug, false)
+    }
+
+    fn new_inner(window: Window, gl_context: GLContext, debug: debug::DebugCallbackBehavior,
+                 checked: bool) -> Result<Self, IncompatibleOpenGl>
+    {
+        let backend = Rc::new(Sdl2Backend { window, gl_context });
+        let context = unsafe { context::Context::new(backend.clone(), checked, debug) }?;
+        Ok(Display {
+            transient_pool: Rc::new(TransientPool::new(&context)),
+            backend,
+            context,
+        })
+    }
+
+    /// Returns the SDL window.
+    #[inline]
+    pub fn window(&self) -> &Window {
+        &self.backend.window
+    }
+
+    /// Start drawing on the backbuffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. When the `Frame` is
+    /// destroyed, the buffers are swapped.
+    ///
+    /// Note that destroying a `Frame` is immediate, even if vsync is enabled.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        self.transient_pool.next_frame();
+        let dimensions = self.get_framebuffer_dimensions();
+        Frame::new(self.context.clone(), dimensions)
+    }
+
+    /// Returns the pool of scratch render targets of this display.
+    ///
+    /// The pool advances to the next frame and trims its unused targets at each call to `draw`.
+    #[inline]
+    pub fn get_transient_pool(&self) -> &TransientPool {
+        &self.transient_pool
+    }
+}
+
+impl fmt::Display for DisplayCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DisplayCreationError::SdlError(err) => write!(fmt, "{}", err),
+            DisplayCreationError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for DisplayCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DisplayCreationError::SdlError(_) => None,
+            DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for DisplayCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> DisplayCreationError {
+        DisplayCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl Deref for Display {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Display {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+impl Sdl2Backend {
+    /// Returns the SDL window.
+    #[inline]
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    /// Returns the OpenGL context of the window.
+    #[inline]
+    pub fn gl_context(&self) -> &GLContext {
+        &self.gl_context
+    }
+}
+
+unsafe impl Backend for Sdl2Backend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        self.window.gl_swap_window();
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        self.window.subsystem().gl_get_proc_address(symbol) as *const _
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.window.drawable_size()
+    }
+
+    #[inline]
+    fn resize(&self, _: (u32, u32)) {
+        // the default framebuffer of SDL follows the size of the window
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        self.gl_context.is_current()
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        self.window.gl_make_current(&self.gl_context).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+/*!
+
+Backend implementation for WebGL 2 in a web browser.
+
+The OpenGL functions are implemented on top of a `WebGl2RenderingContext` obtained from a
+`<canvas>` element. WebGL 2 corresponds to OpenGL ES 3.0, and the capabilities of the context are
+the limits reported by the browser.
+
+# Features
+
+Only available if the 'websys_backend' feature is enabled and when compiling for `wasm32`.
+
+*/
+use crate::backend;
+use crate::backend::Backend;
+use crate::backend::Context;
+use crate::context;
+use crate::framebuffer::TransientPool;
+use crate::gl::websys;
+use crate::SwapBuffersError;
+use crate::{Frame, IncompatibleOpenGl};
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext};
+
+/// A WebGL 2 context combined with a facade for drawing upon.
+///
+/// The default framebuffer is the drawing buffer of the canvas. The browser presents it when
+/// control returns to the event loop, so there is nothing to do when a `Frame` is finished.
+#[derive(Clone)]
+pub struct Display {
+    // contains everything related to the current glium context and its state
+    context: Rc<context::Context>,
+    // The canvas that the WebGL context draws to.
+    canvas: HtmlCanvasElement,
+    // Scratch render targets, recycled at each call to `draw`.
+    transient_pool: Rc<TransientPool>,
+}
+
+/// An implementation of the `Backend` trait for WebGL 2.
+#[derive(Clone)]
+pub struct WebSysBackend {
+    context: Rc<websys::Context>,
+    canvas: HtmlCanvasElement,
+}
+
+/// Error that can happen while creating a glium display.
+#[derive(Debug)]
+pub enum DisplayCreationError {
+    /// The browser couldn't create a WebGL 2 context for the canvas.
+    WebGl2NotSupported,
+    /// The WebGL implementation lacks something that glium requires.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+}
+
+impl fmt::Debug for Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[glium::backend::websys::Display]")
+    }
+}
+
+impl Display {
+    /// Creates a WebGL 2 context for the canvas and a glium `Display` that uses it.
+    ///
+    /// Performs a compatibility check to make sure that all core elements of glium are supported
+    /// by the implementation.
+    pub fn new(canvas: HtmlCanvasElement) -> Result<Self, DisplayCreationError> {
+        let gl = canvas.get_context("webgl2").ok().flatten()
+                       .and_then(|gl| gl.dyn_into::<WebGl2RenderingContext>().ok())
+                       .ok_or(DisplayCreationError::WebGl2NotSupported)?;
+
+        Self::from_context(canvas, gl).map_err(From::from)
+    }
+
+    /// Creates a glium `Display` from a WebGL 2 context that was created for the canvas.
+    ///
+    /// Use this function if you need to pass context attributes to `getContext`. glium
+    /// assumes that nothing else uses the WebGL context afterwards.
+    pub fn from_context(canvas: HtmlCanvasElement, gl: WebGl2RenderingContext)
+                        -> Result<Self, IncompatibleOpenGl>
+    {
+        let backend = WebSysBackend {
+            context: websys::Context::new(gl),
+            canvas: canvas.clone(),
+        };
+
+        let context = unsafe { context::Context::new(backend, true, Default::default()) }?;
+        Ok(Display {
+            transient_pool: Rc::new(TransientPool::new(&context)),
+            context,
+            canvas,
+        })
+    }
+
+    /// Returns the canvas that the display draws to.
+    #[inline]
+    pub fn canvas(&self) -> &HtmlCanvasElement {
+        &self.canvas
+    }
+
+    /// Resizes the drawing buffer of the canvas.
+    #[inline]
+    pub fn resize(&self, new_size: (u32, u32)) {
+        self.canvas.set_width(new_size.0);
+        self.canvas.set_height(new_size.1);
+    }
+
+    /// Start drawing on the drawing buffer.
+    ///
+    /// This function returns a `Frame`, which can be used to draw on it. The browser presents
+    /// the content of the drawing buffer after the current task is finished.
+    #[inline]
+    pub fn draw(&self) -> Frame {
+        self.transient_pool.next_frame();
+        let dimensions = self.get_framebuffer_dimensions();
+        Frame::new(self.context.clone(), dimensions)
+    }
+
+    /// Returns the pool of scratch render targets of this display.
+    ///
+    /// The pool advances to the next frame and trims its unused targets at each call to `draw`.
+    #[inline]
+    pub fn get_transient_pool(&self) -> &TransientPool {
+        &self.transient_pool
+    }
+}
+
+impl fmt::Display for DisplayCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DisplayCreationError::WebGl2NotSupported =>
+                write!(fmt, "WebGL 2 is not supported by the browser"),
+            DisplayCreationError::IncompatibleOpenGl(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for DisplayCreationError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DisplayCreationError::WebGl2NotSupported => None,
+            DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for DisplayCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> DisplayCreationError {
+        DisplayCreationError::IncompatibleOpenGl(err)
+    }
+}
+
+impl Deref for Display {
+    type Target = Context;
+    #[inline]
+    fn deref(&self) -> &Context {
+        &self.context
+    }
+}
+
+impl backend::Facade for Display {
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+unsafe impl Backend for WebSysBackend {
+    #[inline]
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        // the browser presents the drawing buffer by itself
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        websys::get_proc_address(symbol)
+    }
+
+    #[inline]
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        let gl = self.context.webgl();
+        (gl.drawing_buffer_width() as u32, gl.drawing_buffer_height() as u32)
+    }
+
+    #[inline]
+    fn resize(&self, new_size: (u32, u32)) {
+        self.canvas.set_width(new_size.0);
+        self.canvas.set_height(new_size.1);
+    }
+
+    #[inline]
+    fn is_current(&self) -> bool {
+        websys::is_current(&self.context)
+    }
+
+    #[inline]
+    unsafe fn make_current(&self) {
+        websys::make_current(&self.context)
+    }
+}
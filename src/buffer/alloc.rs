@@ -13,10 +13,12 @@ use std::rc::Rc;
 use std::ops::{Deref, DerefMut, Range};
 use crate::GlObject;
 use crate::TransformFeedbackSessionExt;
+use crate::memory_object::MemoryObject;
 
 use crate::buffer::{Content, BufferType, BufferMode, BufferCreationError};
 use crate::vertex::TransformFeedbackSession;
 use crate::vertex_array_object::VertexAttributesSystem;
+use crate::sync::{self, LinearSyncFence};
 
 use crate::version::Api;
 
@@ -48,6 +50,12 @@ impl Error for ReadError {}
 pub enum CopyError {
     /// The backend doesn't support copying between buffers.
     NotSupported,
+
+    /// The source and destination are the same buffer and their ranges overlap.
+    OverlappingRanges,
+
+    /// The source or destination range is out of the bounds of its buffer.
+    OutOfBounds,
 }
 
 impl fmt::Display for CopyError {
@@ -55,6 +63,8 @@ impl fmt::Display for CopyError {
         use self::CopyError::*;
         let desc = match *self {
             NotSupported => "The backend doesn't support copying between buffers",
+            OverlappingRanges => "The source and destination ranges of the copy overlap",
+            OutOfBounds => "The source or destination range of the copy is out of bounds",
         };
         fmt.write_str(desc)
     }
@@ -95,6 +105,36 @@ pub struct Alloc {
 
     /// ID of the draw call where the buffer was last written as an SSBO.
     latest_shader_write: Cell<u64>,
+
+    /// If this buffer was created in Vulkan for example, it is backed by external memory.
+    memory: Option<MemoryObject>,
+}
+
+/// A buffer that has been detached from its context. Private object.
+pub struct AllocTransfer {
+    id: gl::types::GLuint,
+    ty: BufferType,
+    size: usize,
+    persistent_mapping: Option<*mut raw::c_void>,
+    immutable: bool,
+    creation_mode: BufferMode,
+    created_with_buffer_storage: bool,
+    fence: Option<LinearSyncFence>,
+    orphans: sync::TransferOrphans,
+}
+
+// the persistent mapping stays valid in the other contexts of the share group
+unsafe impl Send for AllocTransfer {}
+
+impl Drop for AllocTransfer {
+    fn drop(&mut self) {
+        if self.id == 0 {
+            return;     // the transfer has been consumed
+        }
+
+        let id = self.id;
+        self.orphans.push(self.fence.take(), move |ctxt| unsafe { destroy_buffer(ctxt, id) });
+    }
 }
 
 impl Alloc {
@@ -123,6 +163,7 @@ impl Alloc {
             creation_mode: mode,
             mapped: Cell::new(false),
             latest_shader_write: Cell::new(0),
+            memory: None,
         })
     }
 
@@ -147,6 +188,49 @@ impl Alloc {
             creation_mode: mode,
             mapped: Cell::new(false),
             latest_shader_write: Cell::new(0),
+            memory: None,
+        })
+    }
+
+    /// Builds a buffer whose storage is `size` bytes of a memory object, starting at `offset`.
+    ///
+    /// The storage is immutable, and the buffer can only be modified with `glCopyBufferSubData`
+    /// or by the foreign API.
+    pub unsafe fn from_memory_object<F: ?Sized>(facade: &F, memory: MemoryObject, offset: u64,
+                                                ty: BufferType, size: usize)
+                                                -> Result<Alloc, BufferCreationError>
+                                                where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        if !is_buffer_type_supported(&mut ctxt, ty) {
+            return Err(BufferCreationError::BufferTypeNotSupported);
+        }
+
+        let mut id: gl::types::GLuint = 0;
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+            ctxt.gl.CreateBuffers(1, &mut id);
+            ctxt.gl.NamedBufferStorageMemEXT(id, size as gl::types::GLsizeiptr, memory.get_id(),
+                                             offset);
+        } else {
+            ctxt.gl.GenBuffers(1, &mut id);
+            let bind = bind_buffer(&mut ctxt, id, ty);
+            ctxt.gl.BufferStorageMemEXT(bind, size as gl::types::GLsizeiptr, memory.get_id(),
+                                        offset);
+        }
+
+        Ok(Alloc {
+            context: facade.get_context().clone(),
+            id,
+            ty,
+            size,
+            persistent_mapping: None,
+            immutable: true,
+            created_with_buffer_storage: true,
+            creation_mode: BufferMode::Immutable,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+            memory: Some(memory),
         })
     }
 
@@ -168,6 +252,80 @@ impl Alloc {
         self.persistent_mapping.is_some()
     }
 
+    /// Detaches this buffer from its context, so that it can be used by another context of the
+    /// same share group. See `Buffer::into_transfer`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is backed by a memory object imported from a foreign API.
+    pub fn into_transfer(mut self) -> AllocTransfer {
+        assert!(self.memory.is_none(), "Buffers backed by external memory can't be transferred");
+
+        let fence = unsafe {
+            let mut ctxt = self.context.make_current();
+            self.assert_unmapped(&mut ctxt);
+            self.assert_not_transform_feedback(&mut ctxt);
+            VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
+            unbind_buffer(&mut ctxt, self.id);
+            sync::new_transfer_fence(&mut ctxt)
+        };
+
+        let transfer = AllocTransfer {
+            id: self.id,
+            ty: self.ty,
+            size: self.size,
+            persistent_mapping: self.persistent_mapping,
+            immutable: self.immutable,
+            creation_mode: self.creation_mode,
+            created_with_buffer_storage: self.created_with_buffer_storage,
+            fence,
+            orphans: self.context.get_transfer_orphans().clone(),
+        };
+
+        // the buffer must not be destroyed, but the context must still be released
+        self.id = 0;
+
+        transfer
+    }
+
+    /// Replaces the buffer with a new one of the same size, type and mode, after its context
+    /// has been lost. The content of the new buffer is undefined.
+    pub fn recreate(&mut self) -> Result<(), BufferCreationError> {
+        let new = Alloc::empty(&self.context, self.ty, self.size, self.creation_mode)?;
+        let mut old = mem::replace(self, new);
+
+        // the old buffer belonged to the lost context and must not be destroyed
+        old.id = 0;
+
+        Ok(())
+    }
+
+    /// Attaches a buffer that was detached with `into_transfer` to the context of `facade`.
+    ///
+    /// # Safety
+    ///
+    /// The context of `facade` must share lists with the context the buffer was detached from.
+    pub unsafe fn from_transfer<F: ?Sized>(facade: &F, mut transfer: AllocTransfer) -> Alloc
+        where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+        sync::wait_transfer_fence(&mut ctxt, transfer.fence.take());
+
+        Alloc {
+            context: facade.get_context().clone(),
+            id: mem::replace(&mut transfer.id, 0),
+            ty: transfer.ty,
+            size: transfer.size,
+            persistent_mapping: transfer.persistent_mapping,
+            immutable: transfer.immutable,
+            creation_mode: transfer.creation_mode,
+            created_with_buffer_storage: transfer.created_with_buffer_storage,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+            memory: None,
+        }
+    }
+
     /// Changes the type of the buffer. Returns `Err` if this is forbidden.
     pub fn set_type(mut self, ty: BufferType) -> Result<Alloc, Alloc> {
         // FIXME: return Err for GLES2
@@ -470,7 +628,9 @@ impl Alloc {
                 ctxt.gl.InvalidateBufferData(self.id);
             }
 
-            if ctxt.version >= &Version(Api::Gl, 4, 5) {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
                 ctxt.gl.NamedBufferSubData(self.id, offset_bytes as gl::types::GLintptr,
                                            mem::size_of_val(data) as gl::types::GLsizeiptr,
                                            data.to_void_ptr() as *const _)
@@ -805,11 +965,18 @@ impl Alloc {
             self.barrier_for_buffer_update(&mut ctxt);
 
             <D as Content>::read(size_to_read, |output| {
-                if ctxt.version >= &Version(Api::Gl, 4, 5) {
+                if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                   ctxt.extensions.gl_arb_direct_state_access
+                {
                     ctxt.gl.GetNamedBufferSubData(self.id, range.start as gl::types::GLintptr,
                                                   size_to_read as gl::types::GLsizeiptr,
                                                   output as *mut _ as *mut _);
 
+                } else if ctxt.extensions.gl_ext_direct_state_access {
+                    ctxt.gl.GetNamedBufferSubDataEXT(self.id, range.start as gl::types::GLintptr,
+                                                     size_to_read as gl::types::GLsizeiptr,
+                                                     output as *mut _ as *mut _);
+
                 } else if ctxt.version >= &Version(Api::Gl, 1, 5) {
                     let bind = bind_buffer(&mut ctxt, self.id, self.ty);
                     ctxt.gl.GetBufferSubData(bind, range.start as gl::types::GLintptr,
@@ -852,6 +1019,12 @@ impl Alloc {
         assert!(range.end <= self.size);
         assert!(dest_offset + range.end - range.start <= target.size);
 
+        if self.id == target.id && range.start < dest_offset + range.end - range.start &&
+           dest_offset < range.end
+        {
+            return Err(CopyError::OverlappingRanges);
+        }
+
         let mut ctxt = self.context.make_current();
 
         unsafe {
@@ -869,6 +1042,11 @@ impl fmt::Debug for Alloc {
 
 impl Drop for Alloc {
     fn drop(&mut self) {
+        // the buffer has been transferred, or belonged to a context that has been lost
+        if self.id == 0 {
+            return;
+        }
+
         unsafe {
             let mut ctxt = self.context.make_current();
             self.assert_unmapped(&mut ctxt);
@@ -1274,7 +1452,9 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext<'_>, size: usiz
 
     let persistent_mapping = if let BufferMode::Persistent = mode {
         if immutable {
-            let ptr = if ctxt.version >= &Version(Api::Gl, 4, 5) {
+            let ptr = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                         ctxt.extensions.gl_arb_direct_state_access
+            {
                 ctxt.gl.MapNamedBufferRange(id, 0, size as gl::types::GLsizeiptr,
                                             gl::MAP_READ_BIT | gl::MAP_WRITE_BIT |
                                             gl::MAP_PERSISTENT_BIT | gl::MAP_FLUSH_EXPLICIT_BIT)
@@ -1622,6 +1802,21 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     // FIXME: uncomment this and move it from Buffer's destructor
     //self.context.vertex_array_objects.purge_buffer(&mut ctxt, id);
 
+    unbind_buffer(ctxt, id);
+
+    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+        ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
+    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
+    } else {
+        unreachable!();
+    }
+}
+
+/// Removes a buffer from all the bind points in the state cache.
+unsafe fn unbind_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
     if ctxt.state.array_buffer_binding == id {
         ctxt.state.array_buffer_binding = 0;
     }
@@ -1694,16 +1889,6 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
             point.buffer = 0;
         }
     }
-
-    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-        ctxt.version >= &Version(Api::GlEs, 2, 0)
-    {
-        ctxt.gl.DeleteBuffers(1, [id].as_ptr());
-    } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-        ctxt.gl.DeleteBuffersARB(1, [id].as_ptr());
-    } else {
-        unreachable!();
-    }
 }
 
 /// Flushes a range of a mapped buffer.
@@ -1744,11 +1929,18 @@ unsafe fn map_buffer(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, t
         (false, false) => 0,
     };
 
-    if ctxt.version >= &Version(Api::Gl, 4, 5) {
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
         Some(ctxt.gl.MapNamedBufferRange(id, range.start as gl::types::GLintptr,
                                          (range.end - range.start) as gl::types::GLsizeiptr,
                                          flags) as *mut ())
 
+    } else if ctxt.extensions.gl_ext_direct_state_access &&
+              (ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_map_buffer_range)
+    {
+        Some(ctxt.gl.MapNamedBufferRangeEXT(id, range.start as gl::types::GLintptr,
+                                            (range.end - range.start) as gl::types::GLsizeiptr,
+                                            flags) as *mut ())
+
     } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
         ctxt.version >= &Version(Api::GlEs, 3, 0) ||
         ctxt.extensions.gl_arb_map_buffer_range
@@ -1769,9 +1961,12 @@ unsafe fn map_buffer(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, t
 ///
 /// Assumes that the buffer exists, that it is of the right type, and that it is already mapped.
 unsafe fn unmap_buffer(mut ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, ty: BufferType) {
-    if ctxt.version >= &Version(Api::Gl, 4, 5) {
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
         ctxt.gl.UnmapNamedBuffer(id);
 
+    } else if ctxt.extensions.gl_ext_direct_state_access {
+        ctxt.gl.UnmapNamedBufferEXT(id);
+
     } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
               ctxt.version >= &Version(Api::GlEs, 3, 0)
     {
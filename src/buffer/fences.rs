@@ -57,6 +57,14 @@ impl Fences {
             unsafe { sync::destroy_linear_sync_fence(ctxt, sync) };
         }
     }
+
+    /// Releases all the fences without destroying them. Used when the context has been lost.
+    pub fn forget(&mut self) {
+        let mut fences = self.fences.borrow_mut();
+        for (_, sync) in fences.drain(..) {
+            sync::forget_linear_sync_fence(sync);
+        }
+    }
 }
 
 /// Allows inserting a fence in the list.
@@ -45,11 +45,18 @@
 //! # }
 //! ```
 //!
-pub use self::view::{Buffer, BufferAny, BufferMutSlice};
+//! # Streaming
+//!
+//! Data that changes every frame, like dynamic vertices or per-frame uniforms, should be
+//! written to a `RingBuffer`. It rotates between several regions of a persistent-mapped buffer
+//! so that writing the data of a frame doesn't wait for the GPU to finish the previous one.
+//!
+pub use self::view::{Buffer, BufferAny, BufferMutSlice, BufferTransfer};
 pub use self::view::{BufferSlice, BufferAnySlice};
 pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError};
 pub use self::alloc::{is_buffer_read_supported};
 pub use self::fences::Inserter;
+pub use self::ring::RingBuffer;
 
 /// DEPRECATED. Only here for backwards compatibility.
 #[deprecated(note = "Only here for backwards compatibility")]
@@ -75,6 +82,7 @@ use std::slice;
 
 mod alloc;
 mod fences;
+mod ring;
 mod view;
 
 /// Trait for types of data that can be put inside buffers.
@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferType, BufferMode};
+use crate::buffer::BufferCreationError;
+use crate::vertex::{Vertex, VerticesSource};
+
+/// A buffer split in multiple regions that are written to in turn, to stream data that changes
+/// every frame without waiting for the GPU.
+///
+/// Each call to `next_region` moves to the following region and returns it for writing. The
+/// data is then used with `current_region`, or by passing the ring buffer directly to a draw
+/// command if it contains vertices. With three regions, the CPU writes the data of one frame
+/// while the GPU still reads the data of the two previous frames.
+///
+/// # Implementation
+///
+/// The buffer is created with `BufferMode::Persistent`. Sync fences are inserted after each
+/// command that uses a region, and `next_region` only blocks if the GPU is still using the
+/// region that it returns.
+///
+/// If persistent mapping is not supported, the buffer has a single region and `next_region`
+/// orphans it with `glBufferData` before returning it. The driver then allocates new storage
+/// while the GPU keeps using the old one.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # #[derive(Copy, Clone)] struct Vertex { position: [f32; 2] }
+/// # implement_vertex!(Vertex, position);
+/// # fn example<T>(display: glium::Display<T>, program: glium::Program,
+/// #               vertices: Vec<Vertex>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// # use glium::Surface;
+/// use glium::buffer::{BufferType, RingBuffer};
+///
+/// let mut ring = RingBuffer::<Vertex>::new(&display, BufferType::ArrayBuffer, 1024, 3).unwrap();
+///
+/// // every frame
+/// ring.next_region().slice(0 .. vertices.len()).unwrap().write(&vertices);
+///
+/// let mut frame = display.draw();
+/// frame.draw(&ring, glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+///            &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+/// frame.finish().unwrap();
+/// # }
+/// # fn main() {}
+/// ```
+pub struct RingBuffer<T> where T: Copy {
+    buffer: Buffer<[T]>,
+    region_len: usize,
+    regions: usize,
+    current: usize,
+}
+
+impl<T> RingBuffer<T> where T: Copy {
+    /// Builds a new ring buffer with `regions` regions of `region_len` elements each.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `region_len` or `regions` is equal to `0`.
+    pub fn new<F: ?Sized>(facade: &F, ty: BufferType, region_len: usize, regions: usize)
+                          -> Result<RingBuffer<T>, BufferCreationError> where F: Facade
+    {
+        assert!(region_len >= 1);
+        assert!(regions >= 1);
+
+        let len = region_len.checked_mul(regions).ok_or(BufferCreationError::OutOfMemory)?;
+        let buffer = Buffer::empty_array(facade, ty, len, BufferMode::Persistent)?;
+
+        if buffer.is_persistent() {
+            return Ok(RingBuffer {
+                buffer,
+                region_len,
+                regions,
+                current: regions - 1,
+            });
+        }
+
+        // without persistent mapping, a single region is enough since it is orphaned every time
+        drop(buffer);
+        let buffer = Buffer::empty_array(facade, ty, region_len, BufferMode::Dynamic)?;
+
+        Ok(RingBuffer {
+            buffer,
+            region_len,
+            regions: 1,
+            current: 0,
+        })
+    }
+
+    /// Returns true if the buffer uses persistent mapping.
+    ///
+    /// If false, the buffer falls back to orphaning and has a single region.
+    #[inline]
+    pub fn is_persistent(&self) -> bool {
+        self.buffer.is_persistent()
+    }
+
+    /// Returns the number of elements of each region.
+    #[inline]
+    pub fn region_len(&self) -> usize {
+        self.region_len
+    }
+
+    /// Returns the number of regions.
+    #[inline]
+    pub fn regions(&self) -> usize {
+        self.regions
+    }
+
+    /// Moves to the next region and returns it for writing.
+    ///
+    /// The content of the region is undefined. Blocks if the GPU is still using the region.
+    pub fn next_region(&mut self) -> BufferMutSlice<'_, [T]> {
+        self.current = (self.current + 1) % self.regions;
+
+        if !self.buffer.is_persistent() {
+            self.buffer.invalidate();
+        }
+
+        let range = self.current_range();
+        self.buffer.slice_mut(range).unwrap()
+    }
+
+    /// Moves to the next region and writes `data` at its start.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `data` is longer than a region.
+    #[inline]
+    pub fn write(&mut self, data: &[T]) {
+        assert!(data.len() <= self.region_len);
+        self.next_region().slice(0 .. data.len()).unwrap().write(data);
+    }
+
+    /// Returns the region that was last returned by `next_region`.
+    #[inline]
+    pub fn current_region(&self) -> BufferSlice<'_, [T]> {
+        self.buffer.slice(self.current_range()).unwrap()
+    }
+
+    /// Returns the range of elements of the current region within the whole buffer.
+    #[inline]
+    fn current_range(&self) -> Range<usize> {
+        let start = self.current * self.region_len;
+        start .. start + self.region_len
+    }
+}
+
+impl<'a, T> From<&'a RingBuffer<T>> for VerticesSource<'a> where T: Vertex {
+    #[inline]
+    fn from(ring: &'a RingBuffer<T>) -> VerticesSource<'a> {
+        VerticesSource::VertexBuffer(ring.current_region().as_slice_any(), T::build_bindings(),
+                                     false)
+    }
+}
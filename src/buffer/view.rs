@@ -3,6 +3,7 @@ use std::mem;
 use std::borrow::Cow;
 use crate::utils::range::RangeArgument;
 use std::marker::PhantomData;
+use std::ops::Range;
 
 use crate::texture::{PixelValue, Texture1dDataSink};
 use crate::gl;
@@ -11,6 +12,7 @@ use crate::backend::Facade;
 use crate::BufferExt;
 use crate::BufferSliceExt;
 use crate::GlObject;
+use crate::Recreatable;
 
 use crate::context::Context;
 use crate::context::CommandContext;
@@ -24,12 +26,14 @@ use crate::buffer::Content;
 use crate::buffer::fences::Fences;
 use crate::buffer::fences::Inserter;
 use crate::buffer::alloc::Alloc;
+use crate::buffer::alloc::AllocTransfer;
 use crate::buffer::alloc::Mapping;
 use crate::buffer::alloc::ReadMapping;
 use crate::buffer::alloc::WriteMapping;
 use crate::buffer::alloc::ReadError;
 use crate::buffer::alloc::CopyError;
 use crate::field::Field;
+use crate::memory_object::MemoryObject;
 
 /// Represents a view of a buffer.
 pub struct Buffer<T: ?Sized> where T: Content {
@@ -40,6 +44,18 @@ pub struct Buffer<T: ?Sized> where T: Content {
     marker: PhantomData<T>,
 }
 
+/// A buffer that has been detached from its context with `Buffer::into_transfer`, waiting to be
+/// attached to another context of the same share group with `Buffer::from_transfer`.
+///
+/// This object can be sent to another thread. If it is dropped instead of being attached to a
+/// context, the buffer is destroyed the next time the context it was detached from is flushed
+/// or swaps its buffers.
+#[must_use]
+pub struct BufferTransfer<T: ?Sized> where T: Content {
+    alloc: AllocTransfer,
+    marker: PhantomData<fn(&T)>,
+}
+
 impl<T: ?Sized> GlObject for Buffer<T> where T: Content {
     type Id = gl::types::GLuint;
 
@@ -66,6 +82,39 @@ impl<T: ?Sized> Buffer<T> where T: Content {
             })
     }
 
+    /// Detaches this buffer from its context, so that it can be used by another context that
+    /// shares lists with it.
+    ///
+    /// A fence is inserted after the commands that have been submitted so far, and
+    /// `from_transfer` waits for this fence before returning the buffer.
+    pub fn into_transfer(mut self) -> BufferTransfer<T> {
+        let alloc = self.alloc.take().unwrap();
+        let mut fence = self.fence.take().unwrap();
+        fence.clean(&mut alloc.get_context().make_current());
+
+        BufferTransfer {
+            alloc: alloc.into_transfer(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Attaches a buffer that was detached with `into_transfer` to the context of `facade`.
+    ///
+    /// Blocks until the commands that were submitted before the transfer have finished.
+    ///
+    /// # Safety
+    ///
+    /// The context of `facade` must share lists with the context the buffer was detached from.
+    pub unsafe fn from_transfer<F: ?Sized>(facade: &F, transfer: BufferTransfer<T>) -> Buffer<T>
+        where F: Facade
+    {
+        Buffer {
+            alloc: Some(Alloc::from_transfer(facade, transfer.alloc)),
+            fence: Some(Fences::new()),
+            marker: PhantomData,
+        }
+    }
+
     /// Builds a new buffer of the given size.
     pub fn empty_unsized<F: ?Sized>(facade: &F, ty: BufferType, size: usize, mode: BufferMode)
                             -> Result<Buffer<T>, BufferCreationError> where F: Facade
@@ -82,6 +131,33 @@ impl<T: ?Sized> Buffer<T> where T: Content {
             })
     }
 
+    /// Builds a buffer from memory created by a foreign API like Vulkan. The storage of the
+    /// buffer is `size` bytes of `memory`, starting at `offset`.
+    ///
+    /// The storage of the buffer is immutable, so writing it goes through a temporary buffer.
+    /// Use a `Semaphore` to synchronize the accesses with the foreign API.
+    ///
+    /// # Safety
+    ///
+    /// The range must be inside of the memory object, and `offset` must respect the alignment
+    /// requirements of the foreign API.
+    pub unsafe fn from_memory_object<F: ?Sized>(facade: &F, memory: MemoryObject, offset: u64,
+                                                ty: BufferType, size: usize)
+                                                -> Result<Buffer<T>, BufferCreationError>
+                                                where F: Facade
+    {
+        assert!(<T as Content>::is_size_suitable(size));
+
+        Alloc::from_memory_object(facade, memory, offset, ty, size)
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
     /// Returns the context corresponding to this buffer.
     #[inline]
     pub fn get_context(&self) -> &Rc<Context> {
@@ -371,6 +447,33 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
     pub fn slice_mut<R: RangeArgument<usize>>(&mut self, range: R) -> Option<BufferMutSlice<'_, [T]>> {
         self.as_mut_slice().slice(range)
     }
+
+    /// Copies the elements in `range` to another buffer, starting at element `dest_offset`.
+    ///
+    /// The copy is done entirely by the GPU, without reading the data back. The target can be
+    /// the same buffer as the source, in which case the two ranges must not overlap.
+    ///
+    /// Returns `CopyError::OutOfBounds` if the source or destination range is out of bounds.
+    pub fn copy_range_to(&self, range: Range<usize>, target: &Buffer<[T]>, dest_offset: usize)
+                         -> Result<(), CopyError>
+    {
+        let len = range.end.checked_sub(range.start).ok_or(CopyError::OutOfBounds)?;
+        let dest_end = dest_offset.checked_add(len).ok_or(CopyError::OutOfBounds)?;
+
+        let source = self.slice(range).ok_or(CopyError::OutOfBounds)?;
+        let target = target.slice(dest_offset .. dest_end).ok_or(CopyError::OutOfBounds)?;
+
+        source.copy_to(target)
+    }
+
+    /// Invalidates the elements in `range`. Their content becomes undefined.
+    ///
+    /// This is the equivalent of `slice(range).invalidate()`. See `BufferSlice::invalidate`.
+    /// Returns `None` if the range is out of bounds.
+    #[inline]
+    pub fn invalidate_range<R: RangeArgument<usize>>(&self, range: R) -> Option<()> {
+        self.as_slice().invalidate_range(range)
+    }
 }
 
 impl<T> Buffer<[T]> where T: PixelValue {
@@ -398,6 +501,14 @@ impl<T: ?Sized> Drop for Buffer<T> where T: Content {
     }
 }
 
+impl<T: ?Sized> Recreatable for Buffer<T> where T: Content {
+    fn recreate(&mut self) -> Result<(), crate::Error> {
+        self.fence.as_mut().unwrap().forget();
+        self.alloc.as_mut().unwrap().recreate()?;
+        Ok(())
+    }
+}
+
 impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
     #[inline]
     fn get_offset_bytes(&self) -> usize {
@@ -543,7 +654,8 @@ impl<'a, T: ?Sized> BufferSlice<'a, T> where T: Content + 'a {
     ///
     /// # Implementation
     ///
-    /// Calls `glInvalidateBufferSubData` if supported.
+    /// Calls `glInvalidateBufferSubData` if supported. If the slice covers the whole buffer,
+    /// behaves like `Buffer::invalidate`.
     ///
     #[inline]
     pub fn invalidate(&self) {
@@ -665,6 +777,18 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
             marker: PhantomData,
         })
     }
+
+    /// Invalidates the elements in `range`, relative to the start of this slice. Their content
+    /// becomes undefined.
+    ///
+    /// Streaming code can call this before writing new data to a part of a buffer, so that
+    /// the driver doesn't need to wait until the GPU has finished reading the old content.
+    ///
+    /// Returns `None` if the range is out of bounds, like `slice`.
+    #[inline]
+    pub fn invalidate_range<R: RangeArgument<usize>>(&self, range: R) -> Option<()> {
+        self.slice(range).map(|slice| slice.invalidate())
+    }
 }
 
 impl<'a, T> BufferSlice<'a, [T]> where T: PixelValue + 'a {
@@ -1110,6 +1234,17 @@ impl BufferAny {
         self.elements_size
     }
 
+    /// Considers that the buffer contains elements of `elements_size` bytes.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the size of the buffer is not a multiple of `elements_size`.
+    #[inline]
+    pub(crate) fn set_elements_size(&mut self, elements_size: usize) {
+        assert!(elements_size != 0 && self.size % elements_size == 0);
+        self.elements_size = elements_size;
+    }
+
     /// Returns the number of elements in the buffer.
     // TODO: clumsy, remove this function
     #[inline]
@@ -1319,6 +1454,17 @@ impl<'a> BufferAnySlice<'a> {
     pub fn get_context(&self) -> &Rc<Context> {
         self.alloc.get_context()
     }
+
+    /// Considers that the slice is filled with elements of type `T` and reads them.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the size of the slice is not a multiple of the size of the data.
+    pub unsafe fn read<T>(&self) -> Result<T::Owned, ReadError> where T: ?Sized + Content {
+        self.fence.wait(&mut self.alloc.get_context().make_current(),
+                        self.bytes_start .. self.bytes_end);
+        self.alloc.read::<T>(self.bytes_start .. self.bytes_end)
+    }
 }
 
 impl<'a> fmt::Debug for BufferAnySlice<'a> {
@@ -0,0 +1,380 @@
+/*!
+Recording of the frames drawn to a window, for screenshots or video encoding.
+
+A `FrameRecorder` copies the back buffer of each frame into one of a ring of pixel buffers right
+before the buffers are swapped. The copy is done by the GPU in the background, and the pixels
+are sent on a channel as a `CapturedFrame` a few frames later, once they are available. This
+means that rendering isn't stalled unless all the pixel buffers of the ring are still in use.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::capture::{FrameRecorder, RecorderOptions};
+
+let (mut recorder, frames) = FrameRecorder::new(&display, RecorderOptions::default());
+
+let frame = display.draw();
+// draw things here
+recorder.finish(frame).unwrap();
+
+// for example in an encoding thread
+for captured in frames.try_iter() {
+    println!("{}x{} at {:?}", captured.width, captured.height, captured.timestamp);
+}
+# }
+```
+*/
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+use crate::backend::Facade;
+use crate::buffer::ReadError as BufferReadError;
+use crate::context::Context;
+use crate::fbo;
+use crate::gl;
+use crate::ops::{self, ReadError};
+use crate::sync::SyncFence;
+use crate::texture::{TextureCreationError, Texture2d};
+use crate::texture::pixel_buffer::PixelBuffer;
+use crate::uniforms::MagnifySamplerFilter;
+use crate::{BlitTarget, ContextExt, Frame, Rect, Surface, SwapBuffersError};
+
+/// Error that can happen when capturing a frame.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// Error while creating the texture used for downscaling.
+    TextureCreationError(TextureCreationError),
+    /// Error while reading the back buffer.
+    ReadError(ReadError),
+    /// Error while reading the pixel buffer of a captured frame.
+    BufferReadError(BufferReadError),
+    /// Error while swapping the buffers after the capture.
+    SwapBuffersError(SwapBuffersError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::TextureCreationError(err) => write!(fmt, "{}", err),
+            CaptureError::ReadError(err) => write!(fmt, "{}", err),
+            CaptureError::BufferReadError(err) => write!(fmt, "{}", err),
+            CaptureError::SwapBuffersError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for CaptureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CaptureError::TextureCreationError(err) => Some(err),
+            CaptureError::ReadError(err) => Some(err),
+            CaptureError::BufferReadError(err) => Some(err),
+            CaptureError::SwapBuffersError(err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for CaptureError {
+    #[inline]
+    fn from(err: TextureCreationError) -> CaptureError {
+        CaptureError::TextureCreationError(err)
+    }
+}
+
+impl From<ReadError> for CaptureError {
+    #[inline]
+    fn from(err: ReadError) -> CaptureError {
+        CaptureError::ReadError(err)
+    }
+}
+
+impl From<BufferReadError> for CaptureError {
+    #[inline]
+    fn from(err: BufferReadError) -> CaptureError {
+        CaptureError::BufferReadError(err)
+    }
+}
+
+impl From<SwapBuffersError> for CaptureError {
+    #[inline]
+    fn from(err: SwapBuffersError) -> CaptureError {
+        CaptureError::SwapBuffersError(err)
+    }
+}
+
+/// Layout of the pixels of a `CapturedFrame`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// Four bytes per pixel, in the red, green, blue, alpha order.
+    Rgba8,
+    /// Three bytes per pixel, in the red, green, blue order. The alpha channel is dropped.
+    Rgb8,
+    /// Four bytes per pixel, in the blue, green, red, alpha order.
+    Bgra8,
+}
+
+impl CaptureFormat {
+    /// Returns the number of bytes of a pixel in this format.
+    #[inline]
+    pub fn get_size(&self) -> usize {
+        match *self {
+            CaptureFormat::Rgba8 | CaptureFormat::Bgra8 => 4,
+            CaptureFormat::Rgb8 => 3,
+        }
+    }
+}
+
+/// Options of a `FrameRecorder`.
+#[derive(Debug, Copy, Clone)]
+pub struct RecorderOptions {
+    /// Number of pixel buffers in the ring. Default value is `3`.
+    ///
+    /// This is the maximum number of frames whose pixels can be in flight at the same time.
+    /// When all of them are in use, capturing a frame waits for the oldest one.
+    pub ring_size: usize,
+
+    /// The dimensions of the frames are divided by this value. Default value is `1`.
+    ///
+    /// Downscaling is done by the GPU with linear filtering, before reading the pixels.
+    pub downscale: u32,
+
+    /// Layout of the pixels that are sent. Default value is `Rgba8`.
+    pub format: CaptureFormat,
+
+    /// If `true`, the first row of the pixels is the top of the frame, which is what most image
+    /// and video encoders expect. Otherwise it is the bottom, like in OpenGL. Default value is
+    /// `true`.
+    pub flip_vertically: bool,
+}
+
+impl Default for RecorderOptions {
+    #[inline]
+    fn default() -> RecorderOptions {
+        RecorderOptions {
+            ring_size: 3,
+            downscale: 1,
+            format: CaptureFormat::Rgba8,
+            flip_vertically: true,
+        }
+    }
+}
+
+/// A frame that has been captured by a `FrameRecorder`.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    /// Number of the frame, starting at `0` for the first frame captured by the recorder.
+    pub index: u64,
+    /// Moment when the frame was captured.
+    pub timestamp: Instant,
+    /// Width of the frame in pixels, after downscaling.
+    pub width: u32,
+    /// Height of the frame in pixels, after downscaling.
+    pub height: u32,
+    /// Layout of the pixels.
+    pub format: CaptureFormat,
+    /// The pixels, row after row.
+    pub data: Vec<u8>,
+}
+
+/// A capture whose pixels are being copied by the GPU.
+struct PendingCapture {
+    buffer: PixelBuffer<(u8, u8, u8, u8)>,
+    fence: Option<SyncFence>,
+    index: u64,
+    timestamp: Instant,
+    width: u32,
+    height: u32,
+}
+
+/// Captures the frames drawn to a window and sends them on a channel.
+pub struct FrameRecorder {
+    context: Rc<Context>,
+    options: RecorderOptions,
+    sender: Sender<CapturedFrame>,
+    pending: VecDeque<PendingCapture>,
+    free_buffers: Vec<PixelBuffer<(u8, u8, u8, u8)>>,
+    downscale_target: Option<Texture2d>,
+    next_index: u64,
+}
+
+impl FrameRecorder {
+    /// Builds a new `FrameRecorder`, and returns it with the receiving end of the channel on
+    /// which the frames are sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `ring_size` or `downscale` is `0`.
+    pub fn new<F: ?Sized>(facade: &F, options: RecorderOptions)
+                          -> (FrameRecorder, Receiver<CapturedFrame>) where F: Facade
+    {
+        assert!(options.ring_size >= 1, "The ring of a frame recorder must not be empty");
+        assert!(options.downscale >= 1, "The downscale factor must not be 0");
+
+        let (sender, receiver) = mpsc::channel();
+
+        let recorder = FrameRecorder {
+            context: facade.get_context().clone(),
+            options,
+            sender,
+            pending: VecDeque::with_capacity(options.ring_size),
+            free_buffers: Vec::with_capacity(options.ring_size),
+            downscale_target: None,
+            next_index: 0,
+        };
+
+        (recorder, receiver)
+    }
+
+    /// Returns the options of this recorder.
+    #[inline]
+    pub fn get_options(&self) -> &RecorderOptions {
+        &self.options
+    }
+
+    /// Captures the frame, then swaps the buffers.
+    ///
+    /// This is the function to call instead of `Frame::finish`.
+    pub fn finish(&mut self, mut frame: Frame) -> Result<(), CaptureError> {
+        self.capture(&frame)?;
+        frame.set_finish()?;
+        Ok(())
+    }
+
+    /// Starts copying the current content of the back buffer of the frame, and sends the
+    /// frames whose copy has finished.
+    ///
+    /// If all the pixel buffers of the ring are in use, waits for the oldest one.
+    pub fn capture(&mut self, frame: &Frame) -> Result<(), CaptureError> {
+        self.poll()?;
+
+        if self.pending.len() >= self.options.ring_size {
+            let oldest = self.pending.pop_front().unwrap();
+            self.send(oldest)?;
+        }
+
+        let (width, height) = frame.get_dimensions();
+        let (width, height) = ((width / self.options.downscale).max(1),
+                               (height / self.options.downscale).max(1));
+        let rect = Rect { left: 0, bottom: 0, width, height };
+
+        let buffer = match self.free_buffers.pop() {
+            Some(buffer) if buffer.len() == width as usize * height as usize => buffer,
+            _ => PixelBuffer::new_empty(&self.context, width as usize * height as usize),
+        };
+
+        if self.options.downscale == 1 {
+            let source = ops::Source::DefaultFramebuffer(gl::BACK_LEFT, frame.get_dimensions());
+            let mut ctxt = self.context.make_current();
+            ops::read(&mut ctxt, source, &rect, &buffer, false)?;
+
+        } else {
+            self.resize_downscale_target(width, height)?;
+            let target = self.downscale_target.as_ref().unwrap();
+            frame.blit_whole_color_to(&target.as_surface(),
+                                      &BlitTarget { left: 0, bottom: 0, width: width as i32,
+                                                    height: height as i32 },
+                                      MagnifySamplerFilter::Linear);
+
+            let image = target.main_level().first_layer().into_image(None).unwrap();
+            let mut ctxt = self.context.make_current();
+            ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(image), &rect, &buffer,
+                      false)?;
+        }
+
+        // if fences are not supported, reading the buffer will simply block
+        let fence = SyncFence::new(&self.context).ok();
+
+        self.pending.push_back(PendingCapture {
+            buffer,
+            fence,
+            index: self.next_index,
+            timestamp: Instant::now(),
+            width,
+            height,
+        });
+        self.next_index += 1;
+
+        Ok(())
+    }
+
+    /// Sends the frames whose copy has finished, without waiting for the others.
+    pub fn poll(&mut self) -> Result<(), CaptureError> {
+        while let Some(front) = self.pending.front() {
+            match front.fence {
+                Some(ref fence) if !fence.is_signaled() => break,
+                _ => ()
+            }
+
+            let capture = self.pending.pop_front().unwrap();
+            self.send(capture)?;
+        }
+
+        Ok(())
+    }
+
+    /// Waits for all the frames that are being copied and sends them.
+    pub fn flush(&mut self) -> Result<(), CaptureError> {
+        while let Some(capture) = self.pending.pop_front() {
+            self.send(capture)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize_downscale_target(&mut self, width: u32, height: u32) -> Result<(), CaptureError> {
+        let recreate = match self.downscale_target {
+            Some(ref target) => (target.get_width(), target.get_height()) != (width, Some(height)),
+            None => true,
+        };
+
+        if recreate {
+            self.downscale_target = Some(Texture2d::empty(&self.context, width, height)?);
+        }
+
+        Ok(())
+    }
+
+    fn send(&mut self, mut capture: PendingCapture) -> Result<(), CaptureError> {
+        if let Some(fence) = capture.fence.take() {
+            fence.wait();
+        }
+
+        let pixels = capture.buffer.read()?;
+        self.free_buffers.push(capture.buffer);
+
+        let format = self.options.format;
+        let mut data = Vec::with_capacity(pixels.len() * format.get_size());
+        let mut push_row = |row: &[(u8, u8, u8, u8)]| {
+            for &(r, g, b, a) in row {
+                match format {
+                    CaptureFormat::Rgba8 => data.extend_from_slice(&[r, g, b, a]),
+                    CaptureFormat::Rgb8 => data.extend_from_slice(&[r, g, b]),
+                    CaptureFormat::Bgra8 => data.extend_from_slice(&[b, g, r, a]),
+                }
+            }
+        };
+
+        let rows = pixels.chunks(capture.width as usize);
+        if self.options.flip_vertically {
+            rows.rev().for_each(&mut push_row);
+        } else {
+            rows.for_each(&mut push_row);
+        }
+
+        // the receiver may have been dropped, in which case the frame is discarded
+        let _ = self.sender.send(CapturedFrame {
+            index: capture.index,
+            timestamp: capture.timestamp,
+            width: capture.width,
+            height: capture.height,
+            format,
+            data,
+        });
+
+        Ok(())
+    }
+}
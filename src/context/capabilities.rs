@@ -79,6 +79,21 @@ pub struct Capabilities {
     /// Number of bits in the default framebuffer's stencil buffer
     pub stencil_bits: Option<u16>,
 
+    /// Number of samples of the default framebuffer. `None` if it isn't multisampled.
+    pub samples: Option<u32>,
+
+    /// List of compressed formats reported by `GL_COMPRESSED_TEXTURE_FORMATS`, in the order
+    /// returned by the backend.
+    ///
+    /// Only formats that glium knows about are listed here. Note that the backend is free to
+    /// omit formats that it supports but doesn't recommend for general-purpose usage, so use
+    /// `is_supported_for_textures` to check a specific format.
+    pub compressed_texture_formats: Vec<TextureFormat>,
+
+    /// Raw values reported by `GL_COMPRESSED_TEXTURE_FORMATS`, including the ones that glium
+    /// doesn't know about (for example ETC2 or ASTC).
+    pub compressed_texture_formats_raw: Vec<gl::types::GLenum>,
+
     /// Informations about formats when used to create textures.
     pub internal_formats_textures: HashMap<TextureFormat, FormatInfos, BuildHasherDefault<FnvHasher>>,
 
@@ -101,15 +116,35 @@ pub struct Capabilities {
     /// Maximum size of a buffer texture. `None` if this is not supported.
     pub max_texture_buffer_size: Option<gl::types::GLint>,
 
+    /// Maximum width and height of a sparse texture. `None` if sparse textures are not
+    /// supported.
+    pub max_sparse_texture_size: Option<gl::types::GLint>,
+
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
+    /// Maximum number of viewports and scissor boxes that can be set at once. `1` if viewport
+    /// arrays are not supported.
+    pub max_viewports: gl::types::GLint,
+
     /// Maximum number of elements that can be passed with `glDrawBuffers`.
     pub max_draw_buffers: gl::types::GLint,
 
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
+    /// Maximum number of vertices that a geometry shader can output. `None` if geometry shaders
+    /// are not supported.
+    pub max_geometry_output_vertices: Option<gl::types::GLint>,
+
+    /// Maximum number of components of a single vertex output by a geometry shader. `None` if
+    /// geometry shaders are not supported.
+    pub max_geometry_output_components: Option<gl::types::GLint>,
+
+    /// Maximum number of components of all the vertices output by a single invocation of a
+    /// geometry shader. `None` if geometry shaders are not supported.
+    pub max_geometry_total_output_components: Option<gl::types::GLint>,
+
     /// Number of available buffer bind points for `GL_ATOMIC_COUNTER_BUFFER`.
     pub max_indexed_atomic_counter_buffer: gl::types::GLint,
 
@@ -162,14 +197,27 @@ pub enum ReleaseBehavior {
 ///
 /// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
 ///
+/// The properties of the default framebuffer, like `depth_bits`, are the ones of
+/// `default_framebuffer`, which is bound by this function if it is not `0`.
+///
 /// ## Panic
 ///
 /// Can panic if the version number or extensions list don't match the backend, leading to
 /// unloaded functions being called.
 ///
-pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList)
-                               -> Capabilities
+pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList,
+                               default_framebuffer: gl::types::GLuint) -> Capabilities
 {
+    if default_framebuffer != 0 {
+        if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 2, 0) ||
+           extensions.gl_arb_framebuffer_object
+        {
+            gl.BindFramebuffer(gl::FRAMEBUFFER, default_framebuffer);
+        } else {
+            gl.BindFramebufferEXT(gl::FRAMEBUFFER_EXT, default_framebuffer);
+        }
+    }
+
     // GL_CONTEXT_FLAGS are only available from GL 3.0 onwards
     let (debug, forward_compatible) = if version >= &Version(Api::Gl, 3, 0) {
         let mut val = 0;
@@ -189,6 +237,24 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
                                     .expect("glGetString(GL_RENDERER) returned a non-UTF8 string")
     };
 
+    // getting the list of compressed formats, available since GL 1.3 and GLES 2.0
+    let compressed_texture_formats = if version >= &Version(Api::Gl, 1, 3) ||
+                                        version >= &Version(Api::GlEs, 2, 0)
+    {
+        let mut num = 0;
+        gl.GetIntegerv(gl::NUM_COMPRESSED_TEXTURE_FORMATS, &mut num);
+
+        if num > 0 {
+            let mut formats: Vec<gl::types::GLint> = vec![0; num as usize];
+            gl.GetIntegerv(gl::COMPRESSED_TEXTURE_FORMATS, formats.as_mut_ptr());
+            formats.into_iter().map(|f| f as gl::types::GLenum).collect()
+        } else {
+            Vec::new()
+        }
+    } else {
+        Vec::new()
+    };
+
     Capabilities {
         supported_glsl_versions: {
             get_supported_glsl(gl, version, extensions)
@@ -301,9 +367,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             // `glGetFramebufferAttachmentParameteriv` incorrectly returns GL_INVALID_ENUM on some
             // drivers, so we prefer using `glGetIntegerv` if possible.
             if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_ext_framebuffer_srgb {
-                let mut fb = 0;
-                gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut fb);
-                let attachment = if fb == 0 { gl::FRONT_LEFT } else { gl::COLOR_ATTACHMENT0 };
+                let attachment = if default_framebuffer == 0 { gl::FRONT_LEFT }
+                                 else { gl::COLOR_ATTACHMENT0 };
                 let mut value = 0;
                 gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
                                                        gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING,
@@ -330,9 +395,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             // doesn't even though it provides this extension. I'm not sure whether this is a bug
             // with OS/X or just the extension actually not providing it.
             if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility {
-                let mut fb = 0;
-                gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut fb);
-                let attachment = if fb == 0 { gl::DEPTH } else { gl::DEPTH_ATTACHMENT };
+                let attachment = if default_framebuffer == 0 { gl::DEPTH }
+                                 else { gl::DEPTH_ATTACHMENT };
                 let mut ty = 0;
                 gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
                                                        gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
@@ -366,9 +430,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             // doesn't even though it provides this extension. I'm not sure whether this is a bug
             // with OS/X or just the extension actually not providing it.
             if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility {
-                let mut fb = 0;
-                gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut fb);
-                let attachment = if fb == 0 { gl::STENCIL } else { gl::STENCIL_ATTACHMENT };
+                let attachment = if default_framebuffer == 0 { gl::STENCIL }
+                                 else { gl::STENCIL_ATTACHMENT };
                 let mut ty = 0;
                 gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
                                                        gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
@@ -392,6 +455,22 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        samples: {
+            let mut value = 0;
+            gl.GetIntegerv(gl::SAMPLES, &mut value);
+
+            match value {
+                0 => None,
+                v => Some(v as u32),
+            }
+        },
+
+        compressed_texture_formats: compressed_texture_formats.iter()
+            .filter_map(|&f| TextureFormat::from_compressed_glenum(f))
+            .collect(),
+
+        compressed_texture_formats_raw: compressed_texture_formats,
+
         internal_formats_textures: get_internal_formats(gl, version, extensions, false),
         internal_formats_renderbuffers: get_internal_formats(gl, version, extensions, true),
 
@@ -408,7 +487,10 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             val
         },
 
-        max_texture_max_anisotropy: if !extensions.gl_ext_texture_filter_anisotropic {
+        max_texture_max_anisotropy: if !(version >= &Version(Api::Gl, 4, 6) ||
+                                         extensions.gl_arb_texture_filter_anisotropic ||
+                                         extensions.gl_ext_texture_filter_anisotropic)
+        {
             None
 
         } else {
@@ -441,12 +523,30 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_sparse_texture_size: {
+            if extensions.gl_arb_sparse_texture {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_SPARSE_TEXTURE_SIZE_ARB, &mut val);
+                Some(val)
+            } else {
+                None
+            }
+        },
+
         max_viewport_dims: {
             let mut val: [gl::types::GLint; 2] = [ 0, 0 ];
             gl.GetIntegerv(gl::MAX_VIEWPORT_DIMS, val.as_mut_ptr());
             (val[0], val[1])
         },
 
+        max_viewports: if version >= &Version(Api::Gl, 4, 1) || extensions.gl_arb_viewport_array {
+            let mut val = 1;
+            gl.GetIntegerv(gl::MAX_VIEWPORTS, &mut val);
+            val
+        } else {
+            1
+        },
+
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
@@ -473,6 +573,38 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             None
         },
 
+        max_geometry_output_vertices: if is_geometry_shader_supported(version, extensions) {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_GEOMETRY_OUTPUT_VERTICES, &mut val);
+            Some(val)
+        } else {
+            None
+        },
+
+        max_geometry_output_components: if is_geometry_shader_supported(version, extensions) {
+            let mut val = 0;
+            if version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 2) ||
+               extensions.gl_ext_geometry_shader || extensions.gl_oes_geometry_shader
+            {
+                gl.GetIntegerv(gl::MAX_GEOMETRY_OUTPUT_COMPONENTS, &mut val);
+            } else {
+                // `GL_ARB_geometry_shader4` and `GL_EXT_geometry_shader4` name this limit
+                // differently
+                gl.GetIntegerv(gl::MAX_GEOMETRY_VARYING_COMPONENTS_ARB, &mut val);
+            }
+            Some(val)
+        } else {
+            None
+        },
+
+        max_geometry_total_output_components: if is_geometry_shader_supported(version, extensions) {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_GEOMETRY_TOTAL_OUTPUT_COMPONENTS, &mut val);
+            Some(val)
+        } else {
+            None
+        },
+
         max_indexed_atomic_counter_buffer: if version >= &Version(Api::Gl, 4, 2) {      // TODO: ARB_shader_atomic_counters   // TODO: GLES
             let mut val = 0;
             gl.GetIntegerv(gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS, &mut val);
@@ -762,3 +894,10 @@ pub fn get_internal_format(gl: &gl::Gl, version: &Version, extensions: &Extensio
         }
     }
 }
+
+/// Returns true if geometry shaders are supported. Mirrors the check done when compiling shaders.
+fn is_geometry_shader_supported(version: &Version, extensions: &ExtensionsList) -> bool {
+    version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 2) ||
+        extensions.gl_arb_geometry_shader4 || extensions.gl_ext_geometry_shader4 ||
+        extensions.gl_ext_geometry_shader || extensions.gl_oes_geometry_shader
+}
@@ -55,7 +55,9 @@ extensions! {
     "GL_APPLE_framebuffer_multisample" => gl_apple_framebuffer_multisample,
     "GL_APPLE_sync" => gl_apple_sync,
     "GL_APPLE_vertex_array_object" => gl_apple_vertex_array_object,
+    "GL_ARB_base_instance" => gl_arb_base_instance,
     "GL_ARB_bindless_texture" => gl_arb_bindless_texture,
+    "GL_ARB_blend_func_extended" => gl_arb_blend_func_extended,
     "GL_ARB_buffer_storage" => gl_arb_buffer_storage,
     "GL_ARB_clip_control" => gl_arb_clip_control,
     "GL_ARB_compute_shader" => gl_arb_compute_shader,
@@ -85,6 +87,7 @@ extensions! {
     "GL_ARB_invalidate_subdata" => gl_arb_invalidate_subdata,
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
+    "GL_ARB_pipeline_statistics_query" => gl_arb_pipeline_statistics_query,
     "GL_ARB_pixel_buffer_object" => gl_arb_pixel_buffer_object,
     "GL_ARB_program_interface_query" => gl_arb_program_interface_query,
     "GL_ARB_query_buffer_object" => gl_arb_query_buffer_object,
@@ -93,13 +96,17 @@ extensions! {
     "GL_ARB_provoking_vertex" => gl_arb_provoking_vertex,
     "GL_ARB_robustness" => gl_arb_robustness,
     "GL_ARB_robust_buffer_access_behavior" => gl_arb_robust_buffer_access_behavior,
+    "GL_ARB_sample_shading" => gl_arb_sample_shading,
     "GL_ARB_sampler_objects" => gl_arb_sampler_objects,
     "GL_ARB_seamless_cube_map" => gl_arb_seamless_cube_map,
+    "GL_ARB_separate_shader_objects" => gl_arb_separate_shader_objects,
     "GL_ARB_shader_atomic_counters" => gl_arb_shader_atomic_counters,
     "GL_ARB_shader_image_load_store" => gl_arb_shader_image_load_store,
     "GL_ARB_shader_objects" => gl_arb_shader_objects,
     "GL_ARB_shader_storage_buffer_object" => gl_arb_shader_storage_buffer_object,
     "GL_ARB_shader_subroutine" => gl_arb_shader_subroutine,
+    "GL_ARB_sparse_texture" => gl_arb_sparse_texture,
+    "GL_ARB_stencil_texturing" => gl_arb_stencil_texturing,
     "GL_ARB_sync" => gl_arb_sync,
     "GL_ARB_tessellation_shader" => gl_arb_tessellation_shader,
     "GL_ARB_texture_buffer_object" => gl_arb_texture_buffer_object,
@@ -107,6 +114,7 @@ extensions! {
     "GL_ARB_texture_compression_bptc" => gl_arb_texture_compression_bptc,
     "GL_ARB_texture_cube_map" => gl_arb_texture_cube_map,
     "GL_ARB_texture_cube_map_array" => gl_arb_texture_cube_map_array,
+    "GL_ARB_texture_filter_anisotropic" => gl_arb_texture_filter_anisotropic,
     "GL_ARB_texture_float" => gl_arb_texture_float,
     "GL_ARB_texture_multisample" => gl_arb_texture_multisample,
     "GL_ARB_texture_non_power_of_two" => gl_arb_texture_non_power_of_two,
@@ -115,24 +123,30 @@ extensions! {
     "GL_ARB_texture_stencil8" => gl_arb_texture_stencil8,
     "GL_ARB_texture_storage" => gl_arb_texture_storage,
     "GL_ARB_timer_query" => gl_arb_timer_query,
+    "GL_ARB_transform_feedback2" => gl_arb_transform_feedback2,
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
+    "GL_ARB_vertex_attrib_binding" => gl_arb_vertex_attrib_binding,
     "GL_ARB_vertex_buffer_object" => gl_arb_vertex_buffer_object,
     "GL_ARB_vertex_half_float" => gl_arb_vertex_half_float,
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
     "GL_ARB_vertex_type_10f_11f_11f_rev" => gl_arb_vertex_type_10f_11f_11f_rev,
     "GL_ARB_vertex_type_2_10_10_10_rev" => gl_arb_vertex_type_2_10_10_10_rev,
+    "GL_ARB_viewport_array" => gl_arb_viewport_array,
     "GL_ARM_rgba8" => gl_arm_rgba8,
     "GL_ATI_meminfo" => gl_ati_meminfo,
     "GL_ATI_draw_buffers" => gl_ati_draw_buffers,
     "GL_ATI_texture_float" => gl_ati_texture_float,
+    "GL_EXT_base_instance" => gl_ext_base_instance,
+    "GL_EXT_blend_func_extended" => gl_ext_blend_func_extended,
     "GL_EXT_blend_minmax" => gl_ext_blend_minmax,
     "GL_EXT_buffer_storage" => gl_ext_buffer_storage,
     "GL_EXT_debug_marker" => gl_ext_debug_marker,
     "GL_EXT_direct_state_access" => gl_ext_direct_state_access,
     "GL_EXT_memory_object" => gl_ext_memory_object,
     "GL_EXT_memory_object_fd" => gl_ext_memory_object_fd,
+    "GL_EXT_memory_object_win32" => gl_ext_memory_object_win32,
     "GL_EXT_disjoint_timer_query" => gl_ext_disjoint_timer_query,
     "GL_EXT_framebuffer_blit" => gl_ext_framebuffer_blit,
     "GL_EXT_framebuffer_object" => gl_ext_framebuffer_object,
@@ -141,6 +155,7 @@ extensions! {
     "GL_EXT_geometry_shader" => gl_ext_geometry_shader,
     "GL_EXT_geometry_shader4" => gl_ext_geometry_shader4,
     "GL_EXT_gpu_shader4" => gl_ext_gpu_shader4,
+    "GL_EXT_multi_draw_arrays" => gl_ext_multi_draw_arrays,
     "GL_EXT_multi_draw_indirect" => gl_ext_multi_draw_indirect,
     "GL_EXT_multisampled_render_to_texture" => gl_ext_multisampled_render_to_texture,
     "GL_EXT_occlusion_query_boolean" => gl_ext_occlusion_query_boolean,
@@ -148,11 +163,13 @@ extensions! {
     "GL_EXT_packed_float" => gl_ext_packed_float,
     "GL_EXT_primitive_bounding_box" => gl_ext_primitive_bounding_box,
     "GL_EXT_provoking_vertex" => gl_ext_provoking_vertex,
+    "GL_EXT_read_format_bgra" => gl_ext_read_format_bgra,
     "GL_EXT_robustness" => gl_ext_robustness,
     "GL_EXT_sRGB_write_control" => gl_ext_srgb_write_control,
     "GL_EXT_texture3D" => gl_ext_texture3d,
     "GL_EXT_semaphore" => gl_ext_semaphore,
     "GL_EXT_semaphore_fd" => gl_ext_semaphore_fd,
+    "GL_EXT_semaphore_win32" => gl_ext_semaphore_win32,
     "GL_EXT_texture_array" => gl_ext_texture_array,
     "GL_EXT_texture_buffer" => gl_ext_texture_buffer,
     "GL_EXT_texture_buffer_object" => gl_ext_texture_buffer_object,
@@ -165,11 +182,13 @@ extensions! {
     "GL_EXT_texture_snorm" => gl_ext_texture_snorm,
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
+    "GL_EXT_unpack_subimage" => gl_ext_unpack_subimage,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
+    "GL_KHR_texture_compression_astc_ldr" => gl_khr_texture_compression_astc_ldr,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
     "GL_NV_conditional_render" => gl_nv_conditional_render,
     "GL_NV_copy_buffer" => gl_nv_copy_buffer,
@@ -189,12 +208,14 @@ extensions! {
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
     "GL_OES_depth_texture" => gl_oes_depth_texture,
     "GL_OES_draw_elements_base_vertex" => gl_oes_draw_elements_base_vertex,
+    "GL_OES_EGL_image" => gl_oes_egl_image,
     "GL_OES_element_index_uint" => gl_oes_element_index_uint,
     "GL_OES_fixed_point" => gl_oes_fixed_point,
     "GL_OES_geometry_shader" => gl_oes_geometry_shader,
     "GL_OES_packed_depth_stencil" => gl_oes_packed_depth_stencil,
     "GL_OES_primitive_bounding_box" => gl_oes_primitive_bounding_box,
     "GL_OES_rgb8_rgba8" => gl_oes_rgb8_rgba8,
+    "GL_OES_sample_shading" => gl_oes_sample_shading,
     "GL_OES_stencil1" => gl_oes_stencil1,
     "GL_OES_stencil4" => gl_oes_stencil4,
     "GL_OES_tessellation_shader" => gl_oes_tessellation_shader,
@@ -3,6 +3,7 @@
 use crate::gl;
 
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::mem;
 use std::ptr;
 use std::str;
@@ -10,14 +11,17 @@ use std::borrow::Cow;
 use std::cell::{Cell, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
+use std::path::PathBuf;
 
 use fnv::FnvHasher;
 
 use crate::IncompatibleOpenGl;
+use crate::Recreatable;
 use crate::SwapBuffersError;
+use crate::FrameDropPolicy;
 use crate::CapabilitiesSource;
 use crate::ContextExt;
 use crate::backend::Backend;
@@ -29,17 +33,20 @@ use crate::debug;
 use crate::fbo;
 use crate::ops;
 use crate::sampler_object;
+use crate::sync;
 use crate::texture;
 use crate::uniforms;
 use crate::vertex_array_object;
 
 pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
 pub use self::extensions::ExtensionsList;
-pub use self::state::GlState;
+pub use self::scratch::ScratchBuffers;
+pub use self::state::{GlState, StateGroups};
 pub use self::uuid::UuidError;
 
 mod capabilities;
 mod extensions;
+mod scratch;
 mod state;
 mod uuid;
 
@@ -97,6 +104,64 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// Vectors that are reused between draw calls.
+    scratch_buffers: ScratchBuffers,
+
+    /// Directory where program binaries are cached, if any.
+    program_cache_directory: RefCell<Option<PathBuf>>,
+
+    /// Drop policy of the frames created from now on.
+    frame_drop_policy: Cell<FrameDropPolicy>,
+
+    /// Maximum number of frames that can be queued on the GPU, or `None` if unlimited.
+    max_frames_in_flight: Cell<Option<usize>>,
+
+    /// Fences inserted after each swap, oldest first. Only used if `max_frames_in_flight`
+    /// is `Some`.
+    frame_fences: RefCell<VecDeque<sync::LinearSyncFence>>,
+
+    /// Resources re-created when the context is rebuilt after being lost. Each function
+    /// returns `None` once its resource has been destroyed.
+    recreatable_resources: RefCell<Vec<Box<dyn FnMut() -> Option<Result<(), crate::Error>>>>>,
+
+    /// Functions called when the context is rebuilt after being lost, after the resources have
+    /// been re-created.
+    reset_callbacks: RefCell<Vec<(ResetCallbackId, Box<dyn FnMut()>)>>,
+
+    /// Id of the next callback registered with `on_context_reset`.
+    next_reset_callback_id: Cell<usize>,
+
+    /// Errors that happened while re-creating resources.
+    recreation_errors: RefCell<Vec<crate::Error>>,
+
+    /// Objects that are alive and that aren't shared between contexts.
+    non_shared_objects: RefCell<Vec<NonSharedObject>>,
+
+    /// Number of times `rebuild` has been called. The non-shared objects created before the
+    /// last rebuild don't exist in the current OpenGL context.
+    generation: Cell<usize>,
+
+    /// Objects of the transfers detached from this context that have been dropped.
+    transfer_orphans: sync::TransferOrphans,
+}
+
+/// Identifies a callback registered with `Context::on_context_reset`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ResetCallbackId(usize);
+
+/// An object created by the user that isn't shared between OpenGL contexts.
+///
+/// These objects can't be used anymore once the context has been rebuilt, even if the new
+/// context shares its lists with the old one. They must be destroyed and created again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NonSharedObject {
+    /// A query, such as `SamplesPassedQuery` or `TimeElapsedQuery`, with its id.
+    Query(gl::types::GLuint),
+    /// A `TransformFeedbackObject`, with its id.
+    TransformFeedback(gl::types::GLuint),
+    /// A `ProgramPipeline`, with its id.
+    ProgramPipeline(gl::types::GLuint),
 }
 
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
@@ -117,6 +182,9 @@ pub struct CommandContext<'a> {
     /// Capabilities of the backend.
     pub capabilities: &'a Capabilities,
 
+    /// Id of the framebuffer object that is used as the default framebuffer. Usually `0`.
+    pub default_framebuffer: gl::types::GLuint,
+
     /// Whether or not errors triggered by ARB_debug_output (and similar extensions) should be
     /// reported to the user (by panicking).
     pub report_debug_output_errors: &'a Cell<bool>,
@@ -136,6 +204,9 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// Vectors that are reused between draw calls in order to avoid allocating.
+    pub scratch_buffers: &'a ScratchBuffers,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -171,7 +242,17 @@ impl Context {
         let extensions = extensions::get_extensions(&gl, &version);
         check_gl_compatibility(&version, &extensions)?;
 
-        let capabilities = capabilities::get_capabilities(&gl, &version, &extensions);
+        let default_framebuffer = backend.get_default_framebuffer();
+        let capabilities = capabilities::get_capabilities(&gl, &version, &extensions,
+                                                          default_framebuffer);
+
+        // `get_capabilities` has bound the default framebuffer
+        {
+            let mut state = gl_state.borrow_mut();
+            state.draw_framebuffer = default_framebuffer;
+            state.read_framebuffer = default_framebuffer;
+        }
+
         let report_debug_output_errors = Cell::new(true);
 
         let vertex_array_objects = vertex_array_object::VertexAttributesSystem::new();
@@ -212,6 +293,18 @@ impl Context {
             samplers,
             resident_texture_handles,
             resident_image_handles,
+            scratch_buffers: ScratchBuffers::new(),
+            program_cache_directory: RefCell::new(None),
+            frame_drop_policy: Cell::new(FrameDropPolicy::default()),
+            max_frames_in_flight: Cell::new(None),
+            frame_fences: RefCell::new(VecDeque::new()),
+            recreatable_resources: RefCell::new(Vec::new()),
+            reset_callbacks: RefCell::new(Vec::new()),
+            next_reset_callback_id: Cell::new(0),
+            recreation_errors: RefCell::new(Vec::new()),
+            non_shared_objects: RefCell::new(Vec::new()),
+            generation: Cell::new(0),
+            transfer_orphans: sync::TransferOrphans::default(),
         });
 
         if context.debug_callback.is_some() {
@@ -230,6 +323,7 @@ impl Context {
                      this error: https://github.com/glium/glium/issues");*/
             if ctxt.version >= &Version(Api::Gl, 3, 2) && ctxt.extensions.gl_arb_seamless_cube_map {
                 ctxt.gl.Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+                ctxt.state.enabled_texture_cube_map_seamless = true;
             }
         }
 
@@ -244,17 +338,28 @@ impl Context {
 
     /// Changes the OpenGL context associated with this context.
     ///
-    /// The new context **must** have lists shared with the old one.
+    /// The new context **must** have lists shared with the old one, unless the old context
+    /// has been lost. In this case, the resources registered with `register_recreatable` are
+    /// re-created in the new context, then the callbacks registered with `on_context_reset`
+    /// are called.
     pub unsafe fn rebuild<B>(&self, new_backend: B) -> Result<(), IncompatibleOpenGl>
         where B: Backend + 'static
     {
-        // framebuffer objects and vertex array objects aren't shared,
-        // so we have to destroy them
-        {
-            let mut ctxt = self.make_current();
-            fbo::FramebuffersContainer::purge_all(&mut ctxt);
-            vertex_array_object::VertexAttributesSystem::purge_all(&mut ctxt);
-        }
+        self.purge_non_shared_caches();
+        self.rebuild_after_purge(new_backend)
+    }
+
+    /// Same as `rebuild`, for backends that must switch to the new context before calling this
+    /// function. `purge_non_shared_caches` must have been called before switching.
+    pub(crate) unsafe fn rebuild_after_purge<B>(&self, new_backend: B)
+                                                -> Result<(), IncompatibleOpenGl>
+        where B: Backend + 'static
+    {
+        let was_lost = self.state.borrow().lost_context;
+
+        // the non-shared objects that are still alive don't exist in the new context
+        self.non_shared_objects.borrow_mut().clear();
+        self.generation.set(self.generation.get() + 1);
 
         new_backend.make_current();
 
@@ -262,24 +367,154 @@ impl Context {
         // FIXME: verify version, capabilities and extensions
         *self.backend.borrow_mut() = Box::new(new_backend);
 
+        if was_lost {
+            // the samplers and the bindless handles have been destroyed with the lost context
+            for (_, sampler) in self.samplers.borrow_mut().drain() {
+                sampler.forget();
+            }
+
+            self.resident_texture_handles.borrow_mut().clear();
+            self.resident_image_handles.borrow_mut().clear();
+        }
+
         // making textures resident
-        let textures = self.resident_texture_handles.borrow();
-        for &texture in textures.iter() {
+        for &texture in self.resident_texture_handles.borrow().iter() {
             self.gl.MakeTextureHandleResidentARB(texture);
         }
 
         // making images resident
-        let images = self.resident_image_handles.borrow();
-        for &(image, access) in images.iter() {
+        for &(image, access) in self.resident_image_handles.borrow().iter() {
             self.gl.MakeImageHandleResidentARB(image, access);
         }
 
+        if was_lost {
+            // the fences of the lost context can't be waited upon anymore
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                sync::forget_linear_sync_fence(fence);
+            }
+
+            self.recreate_resources();
+        }
+
         Ok(())
     }
 
+    /// Destroys the framebuffer objects and vertex array objects cached by glium, which aren't
+    /// shared between contexts. They are created again when they are needed.
+    ///
+    /// Must be called while the old context is still alive, before switching to a new one.
+    pub(crate) fn purge_non_shared_caches(&self) {
+        let mut ctxt = self.make_current();
+        fbo::FramebuffersContainer::purge_all(&mut ctxt);
+        vertex_array_object::VertexAttributesSystem::purge_all(&mut ctxt);
+    }
+
+    /// Returns the objects created by the user that are alive and that aren't shared between
+    /// contexts. They can't survive a call to `rebuild`.
+    #[inline]
+    pub fn get_non_shared_objects(&self) -> Vec<NonSharedObject> {
+        self.non_shared_objects.borrow().clone()
+    }
+
+    /// Adds an object to the list returned by `get_non_shared_objects`.
+    ///
+    /// Returns a value that must be passed to `unregister_non_shared_object`.
+    #[inline]
+    pub(crate) fn register_non_shared_object(&self, object: NonSharedObject) -> usize {
+        self.non_shared_objects.borrow_mut().push(object);
+        self.generation.get()
+    }
+
+    /// Removes an object from the list returned by `get_non_shared_objects`.
+    ///
+    /// Returns `false` if the context has been rebuilt since the object was registered. The
+    /// object doesn't exist in the current context then, and must not be destroyed, as its
+    /// name may have been reused by another object.
+    #[inline]
+    pub(crate) fn unregister_non_shared_object(&self, object: NonSharedObject,
+                                               generation: usize) -> bool
+    {
+        if generation != self.generation.get() {
+            return false;
+        }
+
+        self.non_shared_objects.borrow_mut().retain(|&o| o != object);
+        true
+    }
+
+    /// Registers a resource to re-create when the context is rebuilt after being lost.
+    ///
+    /// Only a weak reference to the resource is kept, and it is unregistered once destroyed.
+    /// The content of a re-created resource is undefined, and should be uploaded again by a
+    /// callback registered with `on_context_reset`.
+    pub fn register_recreatable<R>(&self, resource: &Rc<RefCell<R>>)
+        where R: Recreatable + 'static
+    {
+        let resource = Rc::downgrade(resource);
+        self.recreatable_resources.borrow_mut().push(Box::new(move || {
+            Weak::upgrade(&resource).map(|resource| resource.borrow_mut().recreate())
+        }));
+    }
+
+    /// Registers a function to call when the context is rebuilt after being lost.
+    ///
+    /// The function is called after the resources registered with `register_recreatable`
+    /// have been re-created, and can be used to upload their content again.
+    ///
+    /// The function is kept until the context is destroyed or `remove_context_reset_callback`
+    /// is called. It must not hold a strong reference to the context, or the context is
+    /// never destroyed.
+    pub fn on_context_reset<C>(&self, callback: C) -> ResetCallbackId where C: FnMut() + 'static {
+        let id = ResetCallbackId(self.next_reset_callback_id.get());
+        self.next_reset_callback_id.set(id.0 + 1);
+        self.reset_callbacks.borrow_mut().push((id, Box::new(callback)));
+        id
+    }
+
+    /// Unregisters a function registered with `on_context_reset`.
+    pub fn remove_context_reset_callback(&self, id: ResetCallbackId) {
+        self.reset_callbacks.borrow_mut().retain(|&(i, _)| i != id);
+    }
+
+    /// Returns and clears the errors that happened while re-creating the resources registered
+    /// with `register_recreatable`.
+    pub fn take_recreation_errors(&self) -> Vec<crate::Error> {
+        mem::take(&mut *self.recreation_errors.borrow_mut())
+    }
+
+    /// Re-creates the registered resources, then calls the reset callbacks.
+    fn recreate_resources(&self) {
+        // the lists are taken out during the calls, so that the callbacks can register more
+        let mut resources = mem::take(&mut *self.recreatable_resources.borrow_mut());
+        resources.retain_mut(|recreate| {
+            match recreate() {
+                Some(Ok(())) => true,
+                Some(Err(err)) => {
+                    self.recreation_errors.borrow_mut().push(err);
+                    true
+                },
+                None => false,
+            }
+        });
+        let mut registered = self.recreatable_resources.borrow_mut();
+        resources.append(&mut registered);
+        *registered = resources;
+        drop(registered);
+
+        let mut callbacks = mem::take(&mut *self.reset_callbacks.borrow_mut());
+        for (_, callback) in callbacks.iter_mut() {
+            callback();
+        }
+        let mut registered = self.reset_callbacks.borrow_mut();
+        callbacks.append(&mut registered);
+        *registered = callbacks;
+    }
+
     /// Swaps the buffers in the backend.
     pub fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
-        if self.state.borrow().lost_context {
+        if self.state.borrow().lost_context ||
+           (self.is_context_loss_possible() && self.is_context_lost())
+        {
             return Err(SwapBuffersError::ContextLost);
         }
 
@@ -287,28 +522,33 @@ impl Context {
         //       The Fraps software calls `glClear` with scissoring and reads the image of the
         //       current framebuffer.
         //       Therefore we need to bind the default framebuffer before swapping.
-        if self.state.borrow().draw_framebuffer != 0 || self.state.borrow().read_framebuffer != 0 {
+        let default_framebuffer = self.backend.borrow().get_default_framebuffer();
+        if self.state.borrow().draw_framebuffer != default_framebuffer ||
+           self.state.borrow().read_framebuffer != default_framebuffer
+        {
             let mut ctxt = self.make_current();
 
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                ctxt.extensions.gl_arb_framebuffer_object
             {
-                unsafe { ctxt.gl.BindFramebuffer(gl::FRAMEBUFFER, 0); }
-                ctxt.state.draw_framebuffer = 0;
-                ctxt.state.read_framebuffer = 0;
+                unsafe { ctxt.gl.BindFramebuffer(gl::FRAMEBUFFER, default_framebuffer); }
+                ctxt.state.draw_framebuffer = default_framebuffer;
+                ctxt.state.read_framebuffer = default_framebuffer;
             } else if ctxt.version >= &Version(Api::GlEs, 2, 0) {
-                unsafe { ctxt.gl.BindFramebuffer(gl::FRAMEBUFFER, 0); }
-                ctxt.state.draw_framebuffer = 0;
-                ctxt.state.read_framebuffer = 0;
+                unsafe { ctxt.gl.BindFramebuffer(gl::FRAMEBUFFER, default_framebuffer); }
+                ctxt.state.draw_framebuffer = default_framebuffer;
+                ctxt.state.read_framebuffer = default_framebuffer;
             } else if ctxt.extensions.gl_ext_framebuffer_object {
-                unsafe { ctxt.gl.BindFramebufferEXT(gl::FRAMEBUFFER_EXT, 0); }
-                ctxt.state.draw_framebuffer = 0;
-                ctxt.state.read_framebuffer = 0;
+                unsafe { ctxt.gl.BindFramebufferEXT(gl::FRAMEBUFFER_EXT, default_framebuffer); }
+                ctxt.state.draw_framebuffer = default_framebuffer;
+                ctxt.state.read_framebuffer = default_framebuffer;
             } else {
                 unreachable!();
             }
         }
 
+        self.transfer_orphans.destroy(&mut self.make_current());
+
         let backend = self.backend.borrow();
         if self.check_current_context && !backend.is_current() {
             unsafe { backend.make_current() };
@@ -319,9 +559,34 @@ impl Context {
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
         }
+        drop(backend);
+
+        if err.is_ok() {
+            if let Some(max) = self.max_frames_in_flight.get() {
+                self.pace_frames(max);
+            }
+        }
+
         err
     }
 
+    /// Inserts a fence after the frame that has just been swapped, then waits until at most
+    /// `max` frames are still being processed by the GPU.
+    fn pace_frames(&self, max: usize) {
+        let mut ctxt = self.make_current();
+        let mut fences = self.frame_fences.borrow_mut();
+
+        match unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+            Ok(fence) => fences.push_back(fence),
+            Err(_) => return,
+        };
+
+        while fences.len() > max {
+            let fence = fences.pop_front().unwrap();
+            unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt) };
+        }
+    }
+
     /// Returns the OpenGL version
     #[inline]
     #[deprecated(note = "use `get_opengl_version` instead.")]
@@ -454,6 +719,20 @@ impl Context {
         self.capabilities().max_texture_max_anisotropy.map(|v| v as u16)
     }
 
+    /// Returns the maximum number of texels of a buffer texture, or `None` if buffer textures
+    /// are not supported.
+    #[inline]
+    pub fn get_max_texture_buffer_size(&self) -> Option<usize> {
+        self.capabilities().max_texture_buffer_size.map(|v| v as usize)
+    }
+
+    /// Returns the maximum width and height of a sparse texture, or `None` if sparse textures
+    /// are not supported.
+    #[inline]
+    pub fn get_max_sparse_texture_size(&self) -> Option<u32> {
+        self.capabilities().max_sparse_texture_size.map(|v| v as u32)
+    }
+
     /// Returns the maximum dimensions of the viewport.
     ///
     /// Glium will panic if you request a larger viewport than this when drawing.
@@ -477,6 +756,145 @@ impl Context {
         }
     }
 
+    /// Sets the directory where program binaries are cached.
+    ///
+    /// Once a directory is set, programs created from source code or from SPIR-V are looked up
+    /// in this directory before being compiled, and stored in it after being linked. The cache
+    /// is keyed on the driver strings and on the sources, so updating the driver or modifying a
+    /// shader automatically invalidates the corresponding entry.
+    ///
+    /// Pass `None` to disable the cache. This has no effect if the backend doesn't support
+    /// retrieving program binaries.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// display.set_program_cache_directory(Some("shader-cache".into()));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_program_cache_directory(&self, directory: Option<PathBuf>) {
+        *self.program_cache_directory.borrow_mut() = directory;
+    }
+
+    /// Returns the directory where program binaries are cached, if any.
+    #[inline]
+    pub fn get_program_cache_directory(&self) -> Option<PathBuf> {
+        self.program_cache_directory.borrow().clone()
+    }
+
+    /// Sets the drop policy of the `Frame`s that are created from now on.
+    ///
+    /// The default policy is `FrameDropPolicy::Panic`. Existing frames are not affected, and
+    /// each frame can override the policy with `Frame::set_drop_policy`.
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// display.set_frame_drop_policy(glium::FrameDropPolicy::Finish);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_frame_drop_policy(&self, policy: FrameDropPolicy) {
+        self.frame_drop_policy.set(policy);
+    }
+
+    /// Returns the drop policy of the `Frame`s that are created from now on.
+    #[inline]
+    pub fn get_frame_drop_policy(&self) -> FrameDropPolicy {
+        self.frame_drop_policy.get()
+    }
+
+    /// Sets the maximum number of frames that the GPU can be processing at the same time.
+    ///
+    /// Drivers are allowed to queue several frames, which increases the delay between the input
+    /// of the user and the moment the frame is displayed. When this is `Some`, glium inserts a
+    /// fence after each buffer swap and, if more than `max` frames are queued, blocks in
+    /// `Frame::finish` until the oldest one has been processed. `Some(0)` waits for each frame
+    /// to be processed before returning from `Frame::finish`.
+    ///
+    /// The default value is `None`, which means no limit. This has no effect if the backend
+    /// doesn't support sync fences.
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// display.set_max_frames_in_flight(Some(2));
+    /// # }
+    /// ```
+    pub fn set_max_frames_in_flight(&self, max: Option<usize>) {
+        self.max_frames_in_flight.set(max);
+
+        // waiting for the frames that exceed the new limit, or dropping all the fences
+        let mut ctxt = self.make_current();
+        let mut fences = self.frame_fences.borrow_mut();
+        let max = max.unwrap_or(0);
+        while fences.len() > max {
+            let fence = fences.pop_front().unwrap();
+            if self.max_frames_in_flight.get().is_some() {
+                unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt) };
+            } else {
+                unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+            }
+        }
+    }
+
+    /// Returns the maximum number of frames that the GPU can be processing at the same time.
+    ///
+    /// See `set_max_frames_in_flight`.
+    #[inline]
+    pub fn get_max_frames_in_flight(&self) -> Option<usize> {
+        self.max_frames_in_flight.get()
+    }
+
+    /// Returns true if filtering across the faces of cubemaps can be enabled or disabled with
+    /// `set_seamless_cubemaps`.
+    ///
+    /// OpenGL ES 3.0 and above always filter seamlessly, and don't allow changing it.
+    #[inline]
+    pub fn is_seamless_cubemap_supported(&self) -> bool {
+        self.version >= Version(Api::Gl, 3, 2) || self.extensions.gl_arb_seamless_cube_map
+    }
+
+    /// Enables or disables seamless filtering of cubemaps.
+    ///
+    /// When enabled, samples near the edge of a face are blended with the texels of the
+    /// neighbouring faces instead of being clamped. This is enabled by default when supported.
+    ///
+    /// Does nothing if `is_seamless_cubemap_supported` returns false.
+    pub fn set_seamless_cubemaps(&self, enabled: bool) {
+        if !self.is_seamless_cubemap_supported() {
+            return;
+        }
+
+        let mut ctxt = self.make_current();
+
+        if ctxt.state.enabled_texture_cube_map_seamless != enabled {
+            unsafe {
+                if enabled {
+                    ctxt.gl.Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+                } else {
+                    ctxt.gl.Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+                }
+            }
+
+            ctxt.state.enabled_texture_cube_map_seamless = enabled;
+        }
+    }
+
+    /// Returns true if seamless filtering of cubemaps is enabled.
+    #[inline]
+    pub fn is_seamless_cubemaps_enabled(&self) -> bool {
+        if self.version >= Version(Api::GlEs, 3, 0) {
+            return true;
+        }
+
+        let ctxt = self.make_current();
+        ctxt.state.enabled_texture_cube_map_seamless
+    }
+
     /// Returns an estimate of the amount of video memory available in bytes.
     ///
     /// Returns `None` if no estimate is available.
@@ -523,8 +941,40 @@ impl Context {
         let rect = crate::Rect { left: 0, bottom: 0, width: dimensions.0, height: dimensions.1 };
 
         let mut data = Vec::with_capacity(0);
-        ops::read(&mut ctxt, ops::Source::DefaultFramebuffer(gl::FRONT_LEFT), &rect,
-                          &mut data, false)?;
+        ops::read(&mut ctxt, ops::Source::DefaultFramebuffer(gl::FRONT_LEFT, dimensions), &rect,
+                  &mut data, false)?;
+        Ok(T::from_raw(Cow::Owned(data), dimensions.0, dimensions.1))
+    }
+
+    /// Reads the content of the front buffer, with a specific pixel format and layout.
+    ///
+    /// Contrary to `read_front_buffer`, any type of pixel can be requested, for example
+    /// `(f32, f32, f32, f32)` or `(u16, u16, u16)`, and `options` can ask for BGRA pixels or
+    /// for rows ordered from top to bottom. This avoids converting the pixels afterwards, for
+    /// example when taking screenshots or encoding videos.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// let options = glium::ReadOptions { bgra: true, top_to_bottom: true };
+    /// let pixels: Vec<Vec<(u8, u8, u8, u8)>> =
+    ///     display.read_front_buffer_with_options(&options).unwrap();
+    /// # }
+    /// ```
+    pub fn read_front_buffer_with_options<T, P>(&self, options: &ops::ReadOptions)
+                                                -> Result<T, ops::ReadError>
+        where T: texture::Texture2dDataSink<P>, P: texture::PixelValue
+    {
+        let mut ctxt = self.make_current();
+        let dimensions = self.get_framebuffer_dimensions();
+        let rect = crate::Rect { left: 0, bottom: 0, width: dimensions.0, height: dimensions.1 };
+
+        let mut data = Vec::with_capacity(0);
+        ops::read_with_options(&mut ctxt,
+                               ops::Source::DefaultFramebuffer(gl::FRONT_LEFT, dimensions),
+                               &rect, &mut data, false, options)?;
         Ok(T::from_raw(Cow::Owned(data), dimensions.0, dimensions.1))
     }
 
@@ -542,6 +992,40 @@ impl Context {
         action()
     }
 
+    /// Execute an arbitrary closure with the OpenGL context active, then consider that the
+    /// closure may have modified any OpenGL state.
+    ///
+    /// Contrary to `exec_in_context`, `action` doesn't need to restore the state. Instead glium
+    /// forgets what it knows about the state, and sets each value again the next time it needs
+    /// it. This is useful when calling into a library that uses OpenGL directly.
+    ///
+    /// **`action` must not modify or delete objects owned by glium, and must not leave a
+    /// transform feedback or a query active.** Capabilities rarely touched by other libraries,
+    /// such as depth clamping, clip planes, primitive restart or smoothing, must still be restored.
+    #[inline]
+    pub unsafe fn exec_with_state_invalidation<'a, T, F>(&self, action: F) -> T
+                                                         where T: Send + 'static,
+                                                         F: FnOnce() -> T + 'a
+    {
+        self.exec_with_selective_state_invalidation(StateGroups::all(), action)
+    }
+
+    /// Same as `exec_with_state_invalidation`, except that only the given groups of state are
+    /// considered modified. `action` must restore the other groups.
+    pub unsafe fn exec_with_selective_state_invalidation<'a, T, F>(&self, groups: StateGroups,
+                                                                   action: F) -> T
+                                                                   where T: Send + 'static,
+                                                                   F: FnOnce() -> T + 'a
+    {
+        let mut ctxt = self.make_current();
+        let result = action();
+
+        ctxt.state.invalidate(groups);
+        restore_cached_capabilities(&mut ctxt, groups);
+
+        result
+    }
+
     /// Asserts that there are no OpenGL errors pending.
     ///
     /// This function should be used in tests.
@@ -571,7 +1055,8 @@ impl Context {
     /// You normally don't need to call this function manually, except for debugging purposes.
     #[inline]
     pub fn finish(&self) {
-        let ctxt = self.make_current();
+        let mut ctxt = self.make_current();
+        self.transfer_orphans.destroy(&mut ctxt);
         unsafe { ctxt.gl.Finish(); }
     }
 
@@ -586,10 +1071,18 @@ impl Context {
     /// takes from your OpenGL driver to process commands.
     #[inline]
     pub fn flush(&self) {
-        let ctxt = self.make_current();
+        let mut ctxt = self.make_current();
+        self.transfer_orphans.destroy(&mut ctxt);
         unsafe { ctxt.gl.Flush(); }
     }
 
+    /// Returns the queue where the transfers detached from this context put their objects
+    /// when they are dropped.
+    #[inline]
+    pub(crate) fn get_transfer_orphans(&self) -> &sync::TransferOrphans {
+        &self.transfer_orphans
+    }
+
     /// Inserts a debugging string in the commands queue. If you use an OpenGL debugger, you will
     /// be able to see that string.
     ///
@@ -627,6 +1120,78 @@ impl Context {
             Ok(())
         }
     }
+
+    /// Starts a debug group with the given name. The group ends when the returned guard is
+    /// dropped.
+    ///
+    /// OpenGL debuggers use debug groups to organize the commands of a frame in a hierarchy.
+    /// Groups can be nested by calling this function again while a guard is alive.
+    ///
+    /// If the backend doesn't support `GL_KHR_debug`, this function and the guard do nothing.
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// let _shadows = display.debug_group("Shadows");
+    /// // draw the shadow maps here
+    /// # }
+    /// ```
+    pub fn debug_group(&self, name: &str) -> DebugGroupGuard<'_> {
+        let ctxt = self.make_current();
+
+        let active = if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                        ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                        (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            let name = name.as_bytes();
+            unsafe { ctxt.gl.PushDebugGroup(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                            name.len() as gl::types::GLsizei,
+                                            name.as_ptr() as *const _) };
+            true
+
+        } else if ctxt.extensions.gl_khr_debug {
+            let name = name.as_bytes();
+            unsafe { ctxt.gl.PushDebugGroupKHR(gl::DEBUG_SOURCE_APPLICATION, 0,
+                                               name.len() as gl::types::GLsizei,
+                                               name.as_ptr() as *const _) };
+            true
+
+        } else {
+            false
+        };
+
+        DebugGroupGuard {
+            context: self,
+            active,
+        }
+    }
+}
+
+/// Guard returned by `Context::debug_group`. Ends the debug group when dropped.
+#[must_use = "the debug group ends as soon as the guard is dropped"]
+pub struct DebugGroupGuard<'a> {
+    context: &'a Context,
+    // false if the backend doesn't support debug groups
+    active: bool,
+}
+
+impl<'a> Drop for DebugGroupGuard<'a> {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            unsafe { ctxt.gl.PopDebugGroup() };
+        } else {
+            unsafe { ctxt.gl.PopDebugGroupKHR() };
+        }
+    }
 }
 
 impl ContextExt for Context {
@@ -650,12 +1215,14 @@ impl ContextExt for Context {
             version: &self.version,
             extensions: &self.extensions,
             capabilities: &self.capabilities,
+            default_framebuffer: self.backend.borrow().get_default_framebuffer(),
             report_debug_output_errors: &self.report_debug_output_errors,
             vertex_array_objects: &self.vertex_array_objects,
             framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            scratch_buffers: &self.scratch_buffers,
             marker: PhantomData,
         }
     }
@@ -666,6 +1233,65 @@ impl ContextExt for Context {
     }
 }
 
+/// Sets the values that `GlState::invalidate` can't mark as unknown back to what glium has
+/// in its cache.
+unsafe fn restore_cached_capabilities(ctxt: &mut CommandContext<'_>, groups: StateGroups) {
+    unsafe fn set_capability(ctxt: &CommandContext<'_>, cap: gl::types::GLenum, enabled: bool) {
+        if enabled {
+            ctxt.gl.Enable(cap);
+        } else {
+            ctxt.gl.Disable(cap);
+        }
+    }
+
+    if groups.textures {
+        ctxt.gl.ActiveTexture(gl::TEXTURE0 + ctxt.state.active_texture);
+    }
+
+    if groups.depth_stencil {
+        set_capability(ctxt, gl::DEPTH_TEST, ctxt.state.enabled_depth_test);
+        set_capability(ctxt, gl::STENCIL_TEST, ctxt.state.enabled_stencil_test);
+        ctxt.gl.DepthMask(if ctxt.state.depth_mask { gl::TRUE } else { gl::FALSE });
+
+        if ctxt.state.stencil_mask_front == ctxt.state.stencil_mask_back {
+            ctxt.gl.StencilMask(ctxt.state.stencil_mask_front);
+        } else {
+            ctxt.gl.StencilMaskSeparate(gl::FRONT, ctxt.state.stencil_mask_front);
+            ctxt.gl.StencilMaskSeparate(gl::BACK, ctxt.state.stencil_mask_back);
+        }
+    }
+
+    if groups.rasterizer {
+        set_capability(ctxt, gl::CULL_FACE, ctxt.state.enabled_cull_face);
+        set_capability(ctxt, gl::SCISSOR_TEST, ctxt.state.enabled_scissor_test);
+        set_capability(ctxt, gl::DITHER, ctxt.state.enabled_dither);
+        set_capability(ctxt, gl::POLYGON_OFFSET_FILL, ctxt.state.enabled_polygon_offset_fill);
+        set_capability(ctxt, gl::SAMPLE_ALPHA_TO_COVERAGE,
+                       ctxt.state.enabled_sample_alpha_to_coverage);
+        set_capability(ctxt, gl::SAMPLE_COVERAGE, ctxt.state.enabled_sample_coverage);
+
+        if ctxt.version.0 == Api::Gl {
+            set_capability(ctxt, gl::MULTISAMPLE, ctxt.state.enabled_multisample);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           ctxt.extensions.gl_arb_sample_shading || ctxt.extensions.gl_oes_sample_shading
+        {
+            set_capability(ctxt, gl::SAMPLE_SHADING, ctxt.state.enabled_sample_shading);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            set_capability(ctxt, gl::RASTERIZER_DISCARD, ctxt.state.enabled_rasterizer_discard);
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+           ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+        {
+            set_capability(ctxt, gl::FRAMEBUFFER_SRGB, ctxt.state.enabled_framebuffer_srgb);
+        }
+    }
+}
+
 impl CapabilitiesSource for Context {
     #[inline]
     fn get_version(&self) -> &Version {
@@ -701,18 +1327,25 @@ impl Drop for Context {
                 version: &self.version,
                 extensions: &self.extensions,
                 capabilities: &self.capabilities,
+                default_framebuffer: self.backend.borrow().get_default_framebuffer(),
                 report_debug_output_errors: &self.report_debug_output_errors,
                 vertex_array_objects: &self.vertex_array_objects,
                 framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                scratch_buffers: &self.scratch_buffers,
                 marker: PhantomData,
             };
 
+            self.transfer_orphans.close(&mut ctxt);
             fbo::FramebuffersContainer::cleanup(&mut ctxt);
             vertex_array_object::VertexAttributesSystem::cleanup(&mut ctxt);
 
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+            }
+
             for (_, s) in mem::replace(&mut *ctxt.samplers, HashMap::with_hasher(Default::default())) {
                 s.destroy(&mut ctxt);
             }
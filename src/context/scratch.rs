@@ -0,0 +1,68 @@
+use crate::gl;
+
+use std::cell::RefCell;
+use std::mem;
+
+use crate::buffer::Inserter;
+
+/// Vectors that are reused between draw calls, so that drawing doesn't allocate once the
+/// vectors have reached their final capacity.
+///
+/// Each vector is taken out of the cell while in use and put back afterwards. If a vector is
+/// requested while it is already taken, for example by a nested draw call, a new empty vector
+/// is returned instead.
+pub struct ScratchBuffers {
+    /// Fences to insert after a draw command.
+    fences: RefCell<Vec<Inserter<'static>>>,
+
+    /// Indices passed to `glUniformSubroutinesuiv`.
+    subroutine_indices: RefCell<Vec<gl::types::GLuint>>,
+}
+
+impl ScratchBuffers {
+    /// Builds a new empty set of vectors.
+    #[inline]
+    pub fn new() -> ScratchBuffers {
+        ScratchBuffers {
+            fences: RefCell::new(Vec::new()),
+            subroutine_indices: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Takes the empty list of fences.
+    #[inline]
+    pub fn take_fences<'a>(&self) -> Vec<Inserter<'a>> {
+        recycle(mem::take(&mut *self.fences.borrow_mut()))
+    }
+
+    /// Gives back a list of fences obtained with `take_fences`. The list is cleared.
+    #[inline]
+    pub fn give_back_fences(&self, fences: Vec<Inserter<'_>>) {
+        *self.fences.borrow_mut() = recycle(fences);
+    }
+
+    /// Takes the empty list of subroutine indices.
+    #[inline]
+    pub fn take_subroutine_indices(&self) -> Vec<gl::types::GLuint> {
+        mem::take(&mut *self.subroutine_indices.borrow_mut())
+    }
+
+    /// Gives back a list obtained with `take_subroutine_indices`. The list is cleared.
+    #[inline]
+    pub fn give_back_subroutine_indices(&self, mut indices: Vec<gl::types::GLuint>) {
+        indices.clear();
+        *self.subroutine_indices.borrow_mut() = indices;
+    }
+}
+
+/// Clears a vector and turns it into a vector of another type with the same layout, keeping
+/// its allocation.
+///
+/// This is used to store vectors of elements that borrow data between two calls.
+#[inline]
+fn recycle<T, U>(mut vec: Vec<T>) -> Vec<U> {
+    vec.clear();
+    // the standard library reuses the allocation when collecting into a vector whose elements
+    // have the same size and alignment
+    vec.into_iter().map(|_| unreachable!()).collect()
+}
@@ -42,6 +42,9 @@ pub struct GlState {
     /// Whether GL_MULTISAMPLE is enabled
     pub enabled_multisample: bool,
 
+    /// Whether GL_TEXTURE_CUBE_MAP_SEAMLESS is enabled
+    pub enabled_texture_cube_map_seamless: bool,
+
     /// Whether GL_POLYGON_OFFSET_FILL is enabled
     pub enabled_polygon_offset_fill: bool,
 
@@ -63,6 +66,9 @@ pub struct GlState {
     /// Whether GL_SAMPLE_COVERAGE is enabled
     pub enabled_sample_coverage: bool,
 
+    /// Whether GL_SAMPLE_SHADING is enabled
+    pub enabled_sample_shading: bool,
+
     /// Whether GL_SCISSOR_TEST is enabled
     pub enabled_scissor_test: bool,
 
@@ -84,6 +90,9 @@ pub struct GlState {
     /// The latest value passed to `glUseProgram`.
     pub program: Handle,
 
+    /// The latest value passed to `glBindProgramPipeline`.
+    pub program_pipeline: gl::types::GLuint,
+
     /// The latest value passed to `glBindVertexArray`.
     pub vertex_array: gl::types::GLuint,
 
@@ -173,6 +182,18 @@ pub struct GlState {
     pub blend_color: (gl::types::GLclampf, gl::types::GLclampf,
                       gl::types::GLclampf, gl::types::GLclampf),
 
+    /// True if blending has been set with `glEnablei`, `glBlendEquationSeparatei` and
+    /// `glBlendFuncSeparatei`, in which case `enabled_blend`, `blend_equation` and `blend_func`
+    /// don't reflect the state of every draw buffer.
+    pub blend_indexed: bool,
+
+    /// The latest values passed to `glBlendEquationSeparatei` and `glBlendFuncSeparatei` for
+    /// each draw buffer, or `None` if blending is disabled for this buffer. Empty if
+    /// `blend_indexed` is false.
+    pub blend_per_buffer: Vec<Option<((gl::types::GLenum, gl::types::GLenum),
+                                      (gl::types::GLenum, gl::types::GLenum,
+                                       gl::types::GLenum, gl::types::GLenum))>>,
+
     /// The latest value passed to `glDepthFunc`.
     pub depth_func: gl::types::GLenum,
 
@@ -208,6 +229,14 @@ pub struct GlState {
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glViewportArrayv`, starting at index 0. Empty if unknown
+    /// or if `glViewport` has been called since.
+    pub viewport_array: Vec<[gl::types::GLfloat; 4]>,
+
+    /// The latest values passed to `glScissorArrayv`, starting at index 0. Empty if unknown
+    /// or if `glScissor` has been called since.
+    pub scissor_array: Vec<[gl::types::GLint; 4]>,
+
     /// The latest value passed to `glLineWidth`.
     pub line_width: gl::types::GLfloat,
 
@@ -244,6 +273,15 @@ pub struct GlState {
     /// The latest value passed to `glPatchParameter` with `GL_PATCH_VERTICES`.
     pub patch_patch_vertices: gl::types::GLint,
 
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_OUTER_LEVEL`.
+    pub patch_default_outer_level: [f32; 4],
+
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_INNER_LEVEL`.
+    pub patch_default_inner_level: [f32; 2],
+
+    /// The latest value passed to `glMinSampleShading`.
+    pub min_sample_shading: f32,
+
     /// The id of the active texture unit.
     /// IMPORTANT: this is a raw number (0, 1, 2, ...), not an
     ///            enumeration (GL_TEXTURE0, GL_TEXTURE1, ...).
@@ -270,9 +308,16 @@ pub struct GlState {
     /// Current query being used for GL_TIME_ELAPSED​.
     pub time_elapsed_query: gl::types::GLuint,
 
+    /// The latest queries passed to `glBeginQuery` for each pipeline statistic, indexed by
+    /// `PipelineStatistic::index`.
+    pub pipeline_statistics_queries: [gl::types::GLuint; 10],
+
     /// Latest value passed to `glBeginConditionalRender​`.
     pub conditional_render: Option<(gl::types::GLuint, gl::types::GLenum)>,
 
+    /// The transform feedback object bound to `GL_TRANSFORM_FEEDBACK`.
+    pub transform_feedback_object: gl::types::GLuint,
+
     /// If `glBeginTransformFeedback​` has been called, the current primitive types. Otherwise None.
     // TODO: move this inside transform feedback objects
     pub transform_feedback_enabled: Option<gl::types::GLenum>,
@@ -345,6 +390,168 @@ pub struct GlState {
     pub latest_memory_barrier_query_buffer: u64,
 }
 
+/// Groups of OpenGL state cached by glium.
+///
+/// Used with `Context::exec_with_selective_state_invalidation` to indicate which parts of the
+/// state may have been modified by external code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StateGroups {
+    /// The active texture unit, the textures and the samplers bound to each unit.
+    pub textures: bool,
+
+    /// The buffers bound to each buffer target, including the indexed targets.
+    pub buffers: bool,
+
+    /// The bound vertex array object.
+    pub vertex_array: bool,
+
+    /// The current program and program pipeline.
+    pub program: bool,
+
+    /// The bound framebuffers and renderbuffer.
+    pub framebuffers: bool,
+
+    /// Blending, including the per-draw-buffer blending functions.
+    pub blend: bool,
+
+    /// Depth and stencil test, functions, masks and depth range.
+    pub depth_stencil: bool,
+
+    /// Viewport, scissor, face culling, polygon mode and offset, line width, point size,
+    /// color mask, clear values and pixel storage.
+    pub rasterizer: bool,
+}
+
+impl StateGroups {
+    /// Returns a `StateGroups` containing every group.
+    #[inline]
+    pub fn all() -> StateGroups {
+        StateGroups {
+            textures: true,
+            buffers: true,
+            vertex_array: true,
+            program: true,
+            framebuffers: true,
+            blend: true,
+            depth_stencil: true,
+            rasterizer: true,
+        }
+    }
+
+    /// Returns a `StateGroups` containing no group.
+    #[inline]
+    pub fn none() -> StateGroups {
+        StateGroups {
+            textures: false,
+            buffers: false,
+            vertex_array: false,
+            program: false,
+            framebuffers: false,
+            blend: false,
+            depth_stencil: false,
+            rasterizer: false,
+        }
+    }
+}
+
+/// Value that is never used as an object name or enum by glium. Compares unequal to everything
+/// glium may want to set, which forces the next synchronization to call OpenGL.
+const UNKNOWN: gl::types::GLuint = gl::types::GLuint::MAX;
+
+impl GlState {
+    /// Marks the given groups of state as unknown, so that the next use of each value calls
+    /// OpenGL again.
+    ///
+    /// Boolean values (capabilities enabled with `glEnable`, the depth mask and the stencil
+    /// masks) can't be marked as unknown and are left untouched. The active texture unit is
+    /// left untouched as well, as it is used to index `texture_units`. The caller must set them
+    /// back in OpenGL to the values cached here.
+    pub fn invalidate(&mut self, groups: StateGroups) {
+        let nan = f32::NAN;
+
+        if groups.textures {
+            for unit in self.texture_units.iter_mut() {
+                unit.texture = UNKNOWN;
+                unit.sampler = UNKNOWN;
+            }
+        }
+
+        if groups.buffers {
+            self.array_buffer_binding = UNKNOWN;
+            self.pixel_pack_buffer_binding = UNKNOWN;
+            self.pixel_unpack_buffer_binding = UNKNOWN;
+            self.uniform_buffer_binding = UNKNOWN;
+            self.copy_read_buffer_binding = UNKNOWN;
+            self.copy_write_buffer_binding = UNKNOWN;
+            self.dispatch_indirect_buffer_binding = UNKNOWN;
+            self.draw_indirect_buffer_binding = UNKNOWN;
+            self.query_buffer_binding = UNKNOWN;
+            self.texture_buffer_binding = UNKNOWN;
+            self.atomic_counter_buffer_binding = UNKNOWN;
+            self.shader_storage_buffer_binding = UNKNOWN;
+
+            for binding in self.indexed_uniform_buffer_bindings.iter_mut()
+                .chain(self.indexed_atomic_counter_buffer_bindings.iter_mut())
+                .chain(self.indexed_shader_storage_buffer_bindings.iter_mut())
+                .chain(self.indexed_transform_feedback_buffer_bindings.iter_mut())
+            {
+                binding.buffer = UNKNOWN;
+            }
+        }
+
+        if groups.vertex_array {
+            self.vertex_array = UNKNOWN;
+        }
+
+        if groups.program {
+            self.program = Handle::Id(UNKNOWN);
+            self.program_pipeline = UNKNOWN;
+        }
+
+        if groups.framebuffers {
+            self.read_framebuffer = UNKNOWN;
+            self.draw_framebuffer = UNKNOWN;
+            self.default_framebuffer_read = None;
+            self.renderbuffer = UNKNOWN;
+        }
+
+        if groups.blend {
+            // forces the next draw call to set the blending of every draw buffer
+            self.blend_indexed = true;
+            self.blend_per_buffer.clear();
+            self.blend_color = (nan, nan, nan, nan);
+        }
+
+        if groups.depth_stencil {
+            self.depth_func = UNKNOWN;
+            self.depth_range = (nan, nan);
+            self.stencil_func_front = (UNKNOWN, 0, 0);
+            self.stencil_func_back = (UNKNOWN, 0, 0);
+            self.stencil_op_front = (UNKNOWN, UNKNOWN, UNKNOWN);
+            self.stencil_op_back = (UNKNOWN, UNKNOWN, UNKNOWN);
+            self.clear_depth = nan;
+            self.clear_stencil = gl::types::GLint::MIN;
+        }
+
+        if groups.rasterizer {
+            self.viewport = None;
+            self.scissor = None;
+            self.viewport_array.clear();
+            self.scissor_array.clear();
+            self.line_width = nan;
+            self.point_size = nan;
+            self.cull_face = UNKNOWN;
+            self.polygon_mode = UNKNOWN;
+            self.polygon_offset = (nan, nan);
+            self.min_sample_shading = nan;
+            self.color_mask = (2, 2, 2, 2);
+            self.clear_color = (nan, nan, nan, nan);
+            self.pixel_store_unpack_alignment = -1;
+            self.pixel_store_pack_alignment = -1;
+        }
+    }
+}
+
 /// State of a texture unit (the one designated by `glActiveTexture`).
 #[derive(Copy, Clone, Debug)]
 pub struct TextureUnitState {
@@ -390,12 +597,14 @@ impl Default for GlState {
             enabled_dither: false,
             enabled_framebuffer_srgb: false,
             enabled_multisample: true,
+            enabled_texture_cube_map_seamless: false,
             enabled_polygon_offset_fill: false,
             enabled_polygon_offset_line: false,
             enabled_polygon_offset_point: false,
             enabled_rasterizer_discard: false,
             enabled_sample_alpha_to_coverage: false,
             enabled_sample_coverage: false,
+            enabled_sample_shading: false,
             enabled_scissor_test: false,
             enabled_stencil_test: false,
             enabled_line_smooth: false,
@@ -405,6 +614,7 @@ impl Default for GlState {
             enabled_clip_planes: 0,
 
             program: Handle::Id(0),
+            program_pipeline: 0,
             vertex_array: 0,
             clear_color: (0.0, 0.0, 0.0, 0.0),
             clear_depth: 1.0,
@@ -442,8 +652,12 @@ impl Default for GlState {
             blend_equation: (gl::FUNC_ADD, gl::FUNC_ADD),
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
             blend_color: (0.0, 0.0, 0.0, 0.0),
+            blend_indexed: false,
+            blend_per_buffer: Vec::new(),
             viewport: None,
             scissor: None,
+            viewport_array: Vec::new(),
+            scissor_array: Vec::new(),
             line_width: 1.0,
             point_size: 1.0,
             cull_face: gl::BACK,
@@ -454,6 +668,9 @@ impl Default for GlState {
             pixel_store_pack_alignment: 4,
             clamp_color: gl::FIXED_ONLY,
             patch_patch_vertices: 3,
+            patch_default_outer_level: [1.0; 4],
+            min_sample_shading: 0.0,
+            patch_default_inner_level: [1.0; 2],
             active_texture: 0,
             texture_units: small_vec_one(),
             samples_passed_query: 0,
@@ -462,7 +679,9 @@ impl Default for GlState {
             primitives_generated_query: 0,
             transform_feedback_primitives_written_query: 0,
             time_elapsed_query: 0,
+            pipeline_statistics_queries: [0; 10],
             conditional_render: None,
+            transform_feedback_object: 0,
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
@@ -6,9 +6,16 @@
 use crate::backend::Facade;
 use crate::context::Context;
 use crate::ContextExt;
+use crate::QueryExt;
 use crate::version::Api;
 use crate::version::Version;
 use crate::gl;
+use crate::draw_parameters::{QueryCreationError, QueryPool, QueryType, RawQuery};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 
 pub use crate::context::DebugCallbackBehavior;
@@ -210,3 +217,259 @@ impl TimestampQuery {
         }
     }
 }
+
+/// Measures how long the GPU spends in named scopes of a frame.
+///
+/// Each scope records a timestamp query when it starts and another one when its guard is
+/// dropped. The queries are resolved a few frames later, once the GPU has executed the
+/// commands, so measuring doesn't stall the pipeline. Queries are taken from a pool and reused
+/// once resolved.
+///
+/// Scopes can be nested.
+///
+/// With `GL_EXT_disjoint_timer_query`, the frames during which the GPU reported a disjoint
+/// operation are discarded instead of being reported with wrong timings.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let profiler = glium::debug::GpuProfiler::new(&display).unwrap();
+///
+/// {
+///     let _scope = profiler.scope("shadow pass");
+///     // draw the shadow maps here
+/// }
+/// {
+///     let _scope = profiler.scope("main pass");
+///     // draw the scene here
+/// }
+///
+/// profiler.end_frame().unwrap();
+///
+/// if let Some(report) = profiler.last_report() {
+///     for scope in &report.scopes {
+///         println!("{}: {} ns", scope.name, scope.duration);
+///     }
+/// }
+/// # }
+/// ```
+pub struct GpuProfiler {
+    state: RefCell<ProfilerState>,
+}
+
+struct ProfilerState {
+    pool: QueryPool,
+    current: Vec<RecordedScope>,
+    depth: u32,
+    pending: VecDeque<PendingFrame>,
+    last_report: Option<FrameReport>,
+    next_frame: u64,
+}
+
+struct RecordedScope {
+    name: Cow<'static, str>,
+    depth: u32,
+    begin: RawQuery,
+    end: Option<RawQuery>,
+}
+
+struct PendingFrame {
+    index: u64,
+    scopes: Vec<RecordedScope>,
+}
+
+/// Time spent by the GPU in a scope of a `GpuProfiler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeReport {
+    /// Name of the scope.
+    pub name: Cow<'static, str>,
+    /// Number of scopes this scope is nested in. `0` for the outermost scopes.
+    pub depth: u32,
+    /// Time in nanoseconds between the start of the first scope of the frame and the start of
+    /// this scope.
+    pub start: u64,
+    /// Time in nanoseconds spent in this scope.
+    pub duration: u64,
+}
+
+/// Timings of all the scopes of a frame, in the order in which they started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameReport {
+    /// Number of the frame, starting at `0`.
+    pub frame: u64,
+    /// The scopes of the frame.
+    pub scopes: Vec<ScopeReport>,
+}
+
+/// Error that can happen when ending the frame of a `GpuProfiler`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfilerError {
+    /// The guard of a scope is still alive.
+    ScopeStillActive,
+}
+
+impl fmt::Display for ProfilerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match *self {
+            ProfilerError::ScopeStillActive =>
+                "A scope of the profiler is still active at the end of the frame",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for ProfilerError {}
+
+impl GpuProfiler {
+    /// Builds a new profiler. Returns an error if the backend doesn't support timestamp queries.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<GpuProfiler, QueryCreationError> where F: Facade {
+        Ok(GpuProfiler {
+            state: RefCell::new(ProfilerState {
+                pool: QueryPool::new(facade, QueryType::Timestamp)?,
+                current: Vec::new(),
+                depth: 0,
+                pending: VecDeque::new(),
+                last_report: None,
+                next_frame: 0,
+            }),
+        })
+    }
+
+    /// Starts a scope. The scope ends when the returned guard is dropped.
+    pub fn scope<N>(&self, name: N) -> ProfilerScope<'_> where N: Into<Cow<'static, str>> {
+        let mut state = self.state.borrow_mut();
+
+        let begin = state.pool.acquire();
+        begin.query_counter();
+
+        let depth = state.depth;
+        state.current.push(RecordedScope {
+            name: name.into(),
+            depth,
+            begin,
+            end: None,
+        });
+        state.depth += 1;
+
+        ProfilerScope {
+            profiler: self,
+            index: state.current.len() - 1,
+        }
+    }
+
+    /// Marks the end of the frame, and resolves the previous frames whose timings are
+    /// available.
+    ///
+    /// Returns an error if a scope is still active, in which case the frame doesn't end.
+    pub fn end_frame(&self) -> Result<(), ProfilerError> {
+        let mut state = self.state.borrow_mut();
+        if state.depth != 0 {
+            return Err(ProfilerError::ScopeStillActive);
+        }
+
+        let frame = PendingFrame {
+            index: state.next_frame,
+            scopes: state.current.drain(..).collect(),
+        };
+        state.pending.push_back(frame);
+        state.next_frame += 1;
+
+        while let Some(frame) = state.pending.front() {
+            if !frame.is_ready() {
+                break;
+            }
+
+            let frame = state.pending.pop_front().unwrap();
+
+            // the timings of the frame can't be trusted if the GPU was disjoint in the meantime
+            if state.pool.is_disjoint() {
+                state.discard(frame);
+                continue;
+            }
+
+            let report = state.resolve(frame);
+            state.last_report = Some(report);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the report of the most recent frame whose timings are available.
+    #[inline]
+    pub fn last_report(&self) -> Option<FrameReport> {
+        self.state.borrow().last_report.clone()
+    }
+
+    /// Returns the number of frames that have ended but whose timings are not available yet.
+    #[inline]
+    pub fn pending_frames(&self) -> usize {
+        self.state.borrow().pending.len()
+    }
+}
+
+impl PendingFrame {
+    fn is_ready(&self) -> bool {
+        // a query that was never recorded has nothing to wait for
+        let query_ready = |query: &RawQuery| query.is_unused() || query.is_ready();
+
+        self.scopes.iter().all(|scope| {
+            query_ready(&scope.begin) && query_ready(scope.end.as_ref().unwrap())
+        })
+    }
+}
+
+impl ProfilerState {
+    fn resolve(&mut self, frame: PendingFrame) -> FrameReport {
+        let mut frame_start = None;
+        let mut scopes = Vec::with_capacity(frame.scopes.len());
+
+        for scope in frame.scopes {
+            let end_query = scope.end.unwrap();
+            let begin = scope.begin.get_u64();
+            let end = end_query.get_u64();
+            let frame_start = *frame_start.get_or_insert(begin);
+
+            scopes.push(ScopeReport {
+                name: scope.name,
+                depth: scope.depth,
+                start: begin.saturating_sub(frame_start),
+                duration: end.saturating_sub(begin),
+            });
+
+            self.pool.release(scope.begin);
+            self.pool.release(end_query);
+        }
+
+        FrameReport {
+            frame: frame.index,
+            scopes,
+        }
+    }
+
+    fn discard(&mut self, frame: PendingFrame) {
+        for scope in frame.scopes {
+            self.pool.release(scope.begin);
+            self.pool.release(scope.end.unwrap());
+        }
+    }
+}
+
+/// Guard returned by `GpuProfiler::scope`. The scope ends when it is dropped.
+pub struct ProfilerScope<'a> {
+    profiler: &'a GpuProfiler,
+    index: usize,
+}
+
+impl<'a> Drop for ProfilerScope<'a> {
+    fn drop(&mut self) {
+        let mut state = self.profiler.state.borrow_mut();
+
+        let end = state.pool.acquire();
+        end.query_counter();
+
+        state.current[self.index].end = Some(end);
+        state.depth -= 1;
+    }
+}
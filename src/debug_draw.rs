@@ -0,0 +1,425 @@
+/*!
+Immediate-mode drawing of lines and wireframe shapes, for debugging purposes.
+
+Shapes are accumulated in memory by `DebugDraw`, then drawn all at once with a single draw call
+when `flush` is called. The vertex buffer is kept between flushes and only grows when needed.
+
+A `DebugDrawList` keeps its lines between frames instead, and is drawn with `render`. It is
+meant for shapes that rarely change, such as a grid or the bounding boxes of static objects.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+# let view_projection = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0f32]];
+let mut debug_draw = glium::debug_draw::DebugDraw::new(&display).unwrap();
+
+let mut frame = display.draw();
+debug_draw.axes([0.0, 0.0, 0.0], 1.0);
+debug_draw.wire_box([-0.5, -0.5, -0.5], [0.5, 0.5, 0.5], [1.0, 1.0, 0.0, 1.0]);
+debug_draw.flush(&mut frame, view_projection, &Default::default()).unwrap();
+frame.finish().unwrap();
+# }
+```
+*/
+use std::error::Error;
+use std::f32::consts::PI;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramChooserCreationError};
+use crate::vertex::{BufferCreationError, VertexBuffer};
+use crate::{DrawError, DrawParameters, Surface};
+
+/// Number of segments used to approximate a circle.
+const CIRCLE_SEGMENTS: usize = 32;
+
+#[derive(Copy, Clone, Debug)]
+struct DebugVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+implement_vertex!(DebugVertex, position, color);
+
+/// Error that can happen when flushing a `DebugDraw` or rendering a `DebugDrawList`.
+#[derive(Debug)]
+pub enum FlushError {
+    /// Error while creating the vertex buffer that holds the lines.
+    BufferCreationError(BufferCreationError),
+    /// Error while drawing the lines.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for FlushError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlushError::BufferCreationError(err) => write!(fmt, "{}", err),
+            FlushError::DrawError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for FlushError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FlushError::BufferCreationError(err) => Some(err),
+            FlushError::DrawError(err) => Some(err),
+        }
+    }
+}
+
+impl From<BufferCreationError> for FlushError {
+    #[inline]
+    fn from(err: BufferCreationError) -> FlushError {
+        FlushError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for FlushError {
+    #[inline]
+    fn from(err: DrawError) -> FlushError {
+        FlushError::DrawError(err)
+    }
+}
+
+/// Compiles the program shared by `DebugDraw` and `DebugDrawList`.
+fn build_program<F: ?Sized>(facade: &F) -> Result<Program, ProgramChooserCreationError>
+    where F: Facade
+{
+    program!(facade,
+        140 => {
+            vertex: "
+                #version 140
+
+                uniform mat4 matrix;
+                in vec3 position;
+                in vec4 color;
+                out vec4 v_color;
+
+                void main() {
+                    gl_Position = matrix * vec4(position, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 140
+
+                in vec4 v_color;
+                out vec4 f_color;
+
+                void main() {
+                    f_color = v_color;
+                }
+            "
+        },
+
+        110 => {
+            vertex: "
+                #version 110
+
+                uniform mat4 matrix;
+                attribute vec3 position;
+                attribute vec4 color;
+                varying vec4 v_color;
+
+                void main() {
+                    gl_Position = matrix * vec4(position, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            "
+        },
+
+        100 es => {
+            vertex: "
+                #version 100
+
+                uniform highp mat4 matrix;
+                attribute highp vec3 position;
+                attribute lowp vec4 color;
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_Position = matrix * vec4(position, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            "
+        },
+    )
+}
+
+/// Adds the edges of an axis-aligned box to a list of vertices.
+fn wire_box(vertices: &mut Vec<DebugVertex>, min: [f32; 3], max: [f32; 3], color: [f32; 4]) {
+    let corner = |i: usize| {
+        [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ]
+    };
+
+    // each edge joins two corners whose indices differ by exactly one bit
+    for i in 0 .. 8 {
+        for bit in [1, 2, 4] {
+            if i & bit == 0 {
+                vertices.push(DebugVertex { position: corner(i), color });
+                vertices.push(DebugVertex { position: corner(i | bit), color });
+            }
+        }
+    }
+}
+
+/// Adds the X, Y and Z axes in red, green and blue to a list of vertices.
+fn axes(vertices: &mut Vec<DebugVertex>, origin: [f32; 3], size: f32) {
+    let [x, y, z] = origin;
+    for (end, color) in [([x + size, y, z], [1.0, 0.0, 0.0, 1.0]),
+                         ([x, y + size, z], [0.0, 1.0, 0.0, 1.0]),
+                         ([x, y, z + size], [0.0, 0.0, 1.0, 1.0])]
+    {
+        vertices.push(DebugVertex { position: origin, color });
+        vertices.push(DebugVertex { position: end, color });
+    }
+}
+
+/// Accumulates lines and wireframe shapes, and draws them in a single draw call.
+pub struct DebugDraw {
+    context: Rc<Context>,
+    program: Program,
+    vertices: Vec<DebugVertex>,
+    buffer: Option<VertexBuffer<DebugVertex>>,
+}
+
+impl DebugDraw {
+    /// Builds a new `DebugDraw` and compiles its program.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<DebugDraw, ProgramChooserCreationError>
+        where F: Facade
+    {
+        let program = build_program(facade)?;
+
+        Ok(DebugDraw {
+            context: facade.get_context().clone(),
+            program,
+            vertices: Vec::new(),
+            buffer: None,
+        })
+    }
+
+    /// Adds a line between two points.
+    #[inline]
+    pub fn line(&mut self, from: [f32; 3], to: [f32; 3], color: [f32; 4]) {
+        self.vertices.push(DebugVertex { position: from, color });
+        self.vertices.push(DebugVertex { position: to, color });
+    }
+
+    /// Adds the edges of an axis-aligned box.
+    #[inline]
+    pub fn wire_box(&mut self, min: [f32; 3], max: [f32; 3], color: [f32; 4]) {
+        wire_box(&mut self.vertices, min, max, color)
+    }
+
+    /// Adds a wireframe sphere, drawn as three circles around the X, Y and Z axes.
+    pub fn sphere(&mut self, center: [f32; 3], radius: f32, color: [f32; 4]) {
+        for axis in 0 .. 3 {
+            let point = |segment: usize| {
+                let angle = segment as f32 * 2.0 * PI / CIRCLE_SEGMENTS as f32;
+                let (sin, cos) = angle.sin_cos();
+                let mut position = center;
+                position[(axis + 1) % 3] += cos * radius;
+                position[(axis + 2) % 3] += sin * radius;
+                position
+            };
+
+            for segment in 0 .. CIRCLE_SEGMENTS {
+                self.line(point(segment), point(segment + 1), color);
+            }
+        }
+    }
+
+    /// Adds the X, Y and Z axes in red, green and blue.
+    #[inline]
+    pub fn axes(&mut self, origin: [f32; 3], size: f32) {
+        axes(&mut self.vertices, origin, size)
+    }
+
+    /// Returns the number of lines waiting to be drawn.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// Returns true if there is no line waiting to be drawn.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Discards the lines without drawing them.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Draws all the lines on the surface, then clears the list.
+    ///
+    /// `matrix` transforms the positions into clip space, and is usually the product of the
+    /// projection and view matrices.
+    pub fn flush<S>(&mut self, surface: &mut S, matrix: [[f32; 4]; 4],
+                    draw_parameters: &DrawParameters<'_>) -> Result<(), FlushError>
+        where S: Surface
+    {
+        if self.vertices.is_empty() {
+            return Ok(());
+        }
+
+        let len = self.vertices.len();
+        if self.buffer.as_ref().map_or(true, |buffer| buffer.len() < len) {
+            self.buffer = Some(VertexBuffer::empty_dynamic(&self.context, len.next_power_of_two())?);
+        }
+
+        let buffer = self.buffer.as_ref().unwrap();
+        let slice = buffer.slice(0 .. len).unwrap();
+        slice.write(&self.vertices);
+        self.vertices.clear();
+
+        surface.draw(slice, NoIndices(PrimitiveType::LinesList), &self.program,
+                     &uniform! { matrix: matrix }, draw_parameters)?;
+        Ok(())
+    }
+}
+
+/// A list of lines and wireframe shapes that is kept between frames.
+///
+/// Unlike `DebugDraw`, the lines aren't cleared when they are drawn, and are only uploaded
+/// again after the list has been modified. This is better suited to shapes that don't change
+/// every frame, such as a grid or the bounding boxes of static objects.
+///
+/// The lines are drawn over the content of the surface, without depth testing.
+pub struct DebugDrawList {
+    context: Rc<Context>,
+    program: Program,
+    vertices: Vec<DebugVertex>,
+    buffer: Option<VertexBuffer<DebugVertex>>,
+    // true if `vertices` has been modified since the last upload
+    dirty: bool,
+}
+
+impl DebugDrawList {
+    /// Builds a new empty `DebugDrawList` and compiles its program.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<DebugDrawList, ProgramChooserCreationError>
+        where F: Facade
+    {
+        let program = build_program(facade)?;
+
+        Ok(DebugDrawList {
+            context: facade.get_context().clone(),
+            program,
+            vertices: Vec::new(),
+            buffer: None,
+            dirty: false,
+        })
+    }
+
+    /// Adds a line between two points.
+    #[inline]
+    pub fn push_line(&mut self, from: [f32; 3], to: [f32; 3], color: [f32; 4]) {
+        self.vertices.push(DebugVertex { position: from, color });
+        self.vertices.push(DebugVertex { position: to, color });
+        self.dirty = true;
+    }
+
+    /// Adds the edges of an axis-aligned box.
+    #[inline]
+    pub fn push_wire_box(&mut self, min: [f32; 3], max: [f32; 3], color: [f32; 4]) {
+        wire_box(&mut self.vertices, min, max, color);
+        self.dirty = true;
+    }
+
+    /// Adds the X, Y and Z axes in red, green and blue.
+    #[inline]
+    pub fn push_axes(&mut self, origin: [f32; 3], size: f32) {
+        axes(&mut self.vertices, origin, size);
+        self.dirty = true;
+    }
+
+    /// Adds a square grid on the XZ plane.
+    ///
+    /// `size` is the length of the sides of the grid, and `divisions` the number of cells along
+    /// each side.
+    pub fn push_grid(&mut self, center: [f32; 3], size: f32, divisions: u32, color: [f32; 4]) {
+        let [x, y, z] = center;
+        let half = size / 2.0;
+        let divisions = divisions.max(1);
+
+        for i in 0 ..= divisions {
+            let offset = i as f32 * size / divisions as f32 - half;
+            self.push_line([x + offset, y, z - half], [x + offset, y, z + half], color);
+            self.push_line([x - half, y, z + offset], [x + half, y, z + offset], color);
+        }
+    }
+
+    /// Returns the number of lines in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// Returns true if the list doesn't contain any line.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Removes all the lines.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.dirty = true;
+    }
+
+    /// Draws all the lines on the surface. The list is left unchanged.
+    ///
+    /// `view_projection` transforms the positions into clip space.
+    pub fn render<S>(&mut self, surface: &mut S, view_projection: [[f32; 4]; 4])
+                     -> Result<(), FlushError> where S: Surface
+    {
+        if self.vertices.is_empty() {
+            return Ok(());
+        }
+
+        let len = self.vertices.len();
+        if self.dirty {
+            if self.buffer.as_ref().map_or(true, |buffer| buffer.len() < len) {
+                self.buffer = Some(VertexBuffer::empty_dynamic(&self.context,
+                                                               len.next_power_of_two())?);
+            }
+
+            self.buffer.as_ref().unwrap().slice(0 .. len).unwrap().write(&self.vertices);
+            self.dirty = false;
+        }
+
+        let slice = self.buffer.as_ref().unwrap().slice(0 .. len).unwrap();
+        surface.draw(slice, NoIndices(PrimitiveType::LinesList), &self.program,
+                     &uniform! { matrix: view_projection }, &Default::default())?;
+        Ok(())
+    }
+}
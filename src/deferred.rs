@@ -0,0 +1,363 @@
+/*!
+Creation and modification of resources from any thread.
+
+All the OpenGL objects of glium belong to the thread of their context. A `CommandQueue` lets
+other threads create and modify textures and buffers anyway: it hands out `DeferredContext`s,
+which can be sent to other threads and create thread-safe handles such as `SharedTexture2d` and
+`SharedVertexBuffer`. Every operation on these handles is sent as a command to the queue, and
+the thread of the context runs the pending commands when it calls `process`, usually once per
+frame.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::deferred::CommandQueue;
+
+let mut queue = CommandQueue::new(&display);
+let deferred = queue.deferred_context();
+
+let texture = std::thread::spawn(move || {
+    let image = glium::texture::RawImage2d::from_raw_rgba(vec![0u8; 64 * 64 * 4], (64, 64));
+    deferred.create_texture_2d(image, glium::texture::MipmapsOption::NoMipmap)
+}).join().unwrap();
+
+// in the render loop
+for error in queue.process() {
+    println!("{}", error);
+}
+
+if let Some(texture) = queue.texture_2d(&texture) {
+    // draw with the texture
+}
+# }
+```
+
+*/
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::texture::{MipmapsOption, RawImage2d, Texture2d, Texture2dDataSource, TextureCreationError};
+use crate::vertex::{BufferCreationError, Vertex, VertexBuffer};
+use crate::Rect;
+
+/// Error that happened while running a command of a `CommandQueue`.
+#[derive(Debug)]
+pub enum DeferredError {
+    /// Error while creating a texture.
+    TextureCreationError(TextureCreationError),
+    /// Error while creating a buffer.
+    BufferCreationError(BufferCreationError),
+    /// A write was out of the bounds of the resource. The write has been ignored.
+    OutOfRange,
+    /// The size of the data of a write doesn't match the area being written. The write has been
+    /// ignored.
+    DataSizeMismatch,
+}
+
+impl fmt::Display for DeferredError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeferredError::TextureCreationError(err) => write!(fmt, "{}", err),
+            DeferredError::BufferCreationError(err) => write!(fmt, "{}", err),
+            DeferredError::OutOfRange => write!(fmt, "The write is out of the bounds of the resource"),
+            DeferredError::DataSizeMismatch =>
+                write!(fmt, "The size of the data doesn't match the area being written"),
+        }
+    }
+}
+
+impl Error for DeferredError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeferredError::TextureCreationError(err) => Some(err),
+            DeferredError::BufferCreationError(err) => Some(err),
+            DeferredError::OutOfRange => None,
+            DeferredError::DataSizeMismatch => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for DeferredError {
+    #[inline]
+    fn from(err: TextureCreationError) -> DeferredError {
+        DeferredError::TextureCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for DeferredError {
+    #[inline]
+    fn from(err: BufferCreationError) -> DeferredError {
+        DeferredError::BufferCreationError(err)
+    }
+}
+
+type Resources = HashMap<u64, Box<dyn Any>>;
+type Command = Box<dyn FnOnce(&Rc<Context>, &mut Resources) -> Result<(), DeferredError> + Send>;
+
+/// Queue of commands sent from other threads, run by the thread of the context.
+pub struct CommandQueue {
+    context: Rc<Context>,
+    deferred: DeferredContext,
+    receiver: Receiver<Command>,
+    resources: Resources,
+}
+
+impl CommandQueue {
+    /// Builds a new empty queue for the context of `facade`.
+    pub fn new<F: ?Sized>(facade: &F) -> CommandQueue where F: Facade {
+        let (sender, receiver) = mpsc::channel();
+
+        CommandQueue {
+            context: facade.get_context().clone(),
+            deferred: DeferredContext {
+                sender,
+                next_id: Arc::new(AtomicU64::new(0)),
+            },
+            receiver,
+            resources: HashMap::new(),
+        }
+    }
+
+    /// Returns an object that sends commands to this queue, and that can be sent to other
+    /// threads.
+    #[inline]
+    pub fn deferred_context(&self) -> DeferredContext {
+        self.deferred.clone()
+    }
+
+    /// Runs all the commands that have been sent so far, in order.
+    ///
+    /// Returns the errors of the commands that have failed. If the creation of a resource
+    /// fails, the operations on its handle are ignored.
+    pub fn process(&mut self) -> Vec<DeferredError> {
+        let mut errors = Vec::new();
+
+        for command in self.receiver.try_iter() {
+            if let Err(err) = command(&self.context, &mut self.resources) {
+                errors.push(err);
+            }
+        }
+
+        errors
+    }
+
+    /// Returns the texture of a handle, or `None` if it hasn't been created yet or if its
+    /// creation has failed.
+    #[inline]
+    pub fn texture_2d(&self, handle: &SharedTexture2d) -> Option<&Texture2d> {
+        self.resources.get(&handle.handle.id).and_then(|r| r.downcast_ref())
+    }
+
+    /// Returns the vertex buffer of a handle, or `None` if it hasn't been created yet or if its
+    /// creation has failed.
+    #[inline]
+    pub fn vertex_buffer<T>(&self, handle: &SharedVertexBuffer<T>) -> Option<&VertexBuffer<T>>
+        where T: Vertex + Send + 'static
+    {
+        self.resources.get(&handle.handle.id).and_then(|r| r.downcast_ref())
+    }
+}
+
+impl fmt::Debug for CommandQueue {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "CommandQueue {{ resources: {} }}", self.resources.len())
+    }
+}
+
+/// Sends commands to a `CommandQueue` from any thread.
+#[derive(Clone)]
+pub struct DeferredContext {
+    sender: Sender<Command>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl DeferredContext {
+    /// Creates a texture the next time the queue is processed.
+    pub fn create_texture_2d<D>(&self, data: D, mipmaps: MipmapsOption) -> SharedTexture2d
+        where D: Texture2dDataSource<'static> + Send + 'static
+    {
+        let handle = self.new_handle();
+        let id = handle.id;
+
+        handle.send(move |context, resources| {
+            let texture = Texture2d::with_mipmaps(context, data, mipmaps)?;
+            resources.insert(id, Box::new(texture));
+            Ok(())
+        });
+
+        SharedTexture2d { handle: Arc::new(handle) }
+    }
+
+    /// Creates a vertex buffer the next time the queue is processed.
+    ///
+    /// The buffer is created with `VertexBuffer::dynamic`, as it is meant to be written to.
+    pub fn create_vertex_buffer<T>(&self, data: Vec<T>) -> SharedVertexBuffer<T>
+        where T: Vertex + Send + 'static
+    {
+        let handle = self.new_handle();
+        let id = handle.id;
+
+        handle.send(move |context, resources| {
+            let buffer = VertexBuffer::dynamic(context, &data)?;
+            resources.insert(id, Box::new(buffer));
+            Ok(())
+        });
+
+        SharedVertexBuffer { handle: Arc::new(handle), marker: PhantomData }
+    }
+
+    fn new_handle(&self) -> Handle {
+        Handle {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for DeferredContext {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "DeferredContext")
+    }
+}
+
+/// Identifies a resource of the queue. Destroys the resource when dropped.
+struct Handle {
+    id: u64,
+    sender: Sender<Command>,
+}
+
+impl Handle {
+    fn send<G>(&self, command: G)
+        where G: FnOnce(&Rc<Context>, &mut Resources) -> Result<(), DeferredError> + Send + 'static
+    {
+        // if the queue has been destroyed, so have its resources
+        let _ = self.sender.send(Box::new(command));
+    }
+
+    /// Sends a command that runs only if the resource exists.
+    fn modify<R, G>(&self, command: G)
+        where R: 'static, G: FnOnce(&R) -> Result<(), DeferredError> + Send + 'static
+    {
+        let id = self.id;
+        self.send(move |_, resources| {
+            match resources.get(&id).and_then(|r| r.downcast_ref()) {
+                Some(resource) => command(resource),
+                None => Ok(()),
+            }
+        });
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        let id = self.id;
+        self.send(move |_, resources| {
+            resources.remove(&id);
+            Ok(())
+        });
+    }
+}
+
+/// Handle to a `Texture2d` of a `CommandQueue`. Can be used from any thread.
+///
+/// The texture is destroyed when all the clones of the handle are dropped.
+#[derive(Clone)]
+pub struct SharedTexture2d {
+    handle: Arc<Handle>,
+}
+
+impl SharedTexture2d {
+    /// Writes some pixels to the texture the next time the queue is processed.
+    ///
+    /// Writes out of the bounds of the texture produce a `DeferredError::OutOfRange`, and data
+    /// whose dimensions or size don't match `rect` produce a `DeferredError::DataSizeMismatch`.
+    pub fn write<D>(&self, rect: Rect, data: D)
+        where D: Texture2dDataSource<'static> + Send + 'static
+    {
+        self.handle.modify(move |texture: &Texture2d| {
+            let right = rect.left.checked_add(rect.width).ok_or(DeferredError::OutOfRange)?;
+            let top = rect.bottom.checked_add(rect.height).ok_or(DeferredError::OutOfRange)?;
+            if right > texture.width() || top > texture.height() {
+                return Err(DeferredError::OutOfRange);
+            }
+
+            let data = data.into_raw();
+            if data.width != rect.width || data.height != rect.height ||
+               data.data.len() * mem::size_of::<D::Data>() !=
+                   data.width as usize * data.height as usize * data.format.get_size()
+            {
+                return Err(DeferredError::DataSizeMismatch);
+            }
+
+            texture.write(rect, RawData(data));
+            Ok(())
+        });
+    }
+}
+
+/// Data that has already been converted with `into_raw`.
+struct RawData<T: Clone + 'static>(RawImage2d<'static, T>);
+
+impl<T> Texture2dDataSource<'static> for RawData<T> where T: Send + Copy + Clone + 'static {
+    type Data = T;
+
+    #[inline]
+    fn into_raw(self) -> RawImage2d<'static, T> {
+        self.0
+    }
+}
+
+impl fmt::Debug for SharedTexture2d {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "SharedTexture2d({})", self.handle.id)
+    }
+}
+
+/// Handle to a `VertexBuffer` of a `CommandQueue`. Can be used from any thread.
+///
+/// The buffer is destroyed when all the clones of the handle are dropped.
+pub struct SharedVertexBuffer<T> {
+    handle: Arc<Handle>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> SharedVertexBuffer<T> where T: Vertex + Send + 'static {
+    /// Writes some vertices starting at `offset` the next time the queue is processed.
+    ///
+    /// Writes out of the bounds of the buffer produce a `DeferredError::OutOfRange`.
+    pub fn write(&self, offset: usize, data: Vec<T>) {
+        self.handle.modify(move |buffer: &VertexBuffer<T>| {
+            let end = offset.checked_add(data.len()).ok_or(DeferredError::OutOfRange)?;
+            match buffer.slice(offset .. end) {
+                Some(slice) => {
+                    slice.write(&data);
+                    Ok(())
+                },
+                None => Err(DeferredError::OutOfRange),
+            }
+        });
+    }
+}
+
+impl<T> Clone for SharedVertexBuffer<T> {
+    #[inline]
+    fn clone(&self) -> SharedVertexBuffer<T> {
+        SharedVertexBuffer { handle: self.handle.clone(), marker: PhantomData }
+    }
+}
+
+impl<T> fmt::Debug for SharedVertexBuffer<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "SharedVertexBuffer({})", self.handle.id)
+    }
+}
@@ -223,9 +223,61 @@ impl LinearBlendingFactor {
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(), DrawError> {
+/// The values passed to `glBlendEquationSeparate` and `glBlendFuncSeparate`, or `None` if
+/// blending is disabled.
+type BlendState = Option<((gl::types::GLenum, gl::types::GLenum),
+                          (gl::types::GLenum, gl::types::GLenum,
+                           gl::types::GLenum, gl::types::GLenum))>;
+
+pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend,
+                     blend_per_buffer: Option<&[Blend]>) -> Result<(), DrawError>
+{
+    let blends = match blend_per_buffer {
+        Some(blends) => blends,
+        None => {
+            let (state, uses_constant) = blend_state(ctxt, blend)?;
+            if uses_constant {
+                sync_blend_color(ctxt, blend.constant_value);
+            }
+            sync_global_blending(ctxt, state);
+            return Ok(());
+        },
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2)) {
+        return Err(DrawError::BlendPerBufferNotSupported);
+    }
+
+    let max_buffers = ctxt.capabilities.max_draw_buffers as usize;
+    if blends.len() > max_buffers {
+        return Err(DrawError::TooManyBlendBuffers);
+    }
+
+    let mut states = Vec::with_capacity(max_buffers);
+    let mut uses_constant = false;
+    for &buffer_blend in blends {
+        let (state, constant) = blend_state(ctxt, buffer_blend)?;
+        states.push(state);
+        uses_constant |= constant;
+    }
+
+    // buffers without a blending function don't blend
+    states.resize(max_buffers, None);
+
+    // the blending color is shared by all draw buffers
+    if uses_constant {
+        sync_blend_color(ctxt, blend.constant_value);
+    }
+
+    sync_indexed_blending(ctxt, &states);
+    Ok(())
+}
+
+/// Turns a `Blend` into the values to pass to OpenGL. The second value is true if the
+/// blending color is used by the factors.
+fn blend_state(ctxt: &CommandContext<'_>, blend: Blend) -> Result<(BlendState, bool), DrawError> {
     #[inline(always)]
-    fn blend_eq(ctxt: &mut CommandContext<'_>, blending_function: BlendingFunction)
+    fn blend_eq(ctxt: &CommandContext<'_>, blending_function: BlendingFunction)
                 -> Result<gl::types::GLenum, DrawError>
     {
         match blending_function {
@@ -273,73 +325,121 @@ pub fn sync_blending(ctxt: &mut CommandContext<'_>, blend: Blend) -> Result<(),
         }
     }
 
+    #[inline(always)]
+    fn is_constant(factor: LinearBlendingFactor) -> bool {
+        matches!(factor, LinearBlendingFactor::ConstantColor |
+                         LinearBlendingFactor::OneMinusConstantColor |
+                         LinearBlendingFactor::ConstantAlpha |
+                         LinearBlendingFactor::OneMinusConstantAlpha)
+    }
+
     if let (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) =
            (blend.color, blend.alpha)
     {
         // Both color and alpha always replace. This equals no blending.
-        if ctxt.state.enabled_blend {
-            unsafe { ctxt.gl.Disable(gl::BLEND); }
-            ctxt.state.enabled_blend = false;
-        }
+        return Ok((None, false));
+    }
 
-    } else {
-        if !ctxt.state.enabled_blend {
-            unsafe { ctxt.gl.Enable(gl::BLEND); }
-            ctxt.state.enabled_blend = true;
-        }
+    let (color_eq, alpha_eq) = (blend_eq(ctxt, blend.color)?,
+                                blend_eq(ctxt, blend.alpha)?);
 
-        let (color_eq, alpha_eq) = (blend_eq(ctxt, blend.color)?,
-                                    blend_eq(ctxt, blend.alpha)?);
-        if ctxt.state.blend_equation != (color_eq, alpha_eq) {
-            unsafe { ctxt.gl.BlendEquationSeparate(color_eq, alpha_eq); }
-            ctxt.state.blend_equation = (color_eq, alpha_eq);
-        }
+    // Map to dummy factors if the blending equation does not use the factors.
+    let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
+        .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
+    let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
+        .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
 
-        // Map to dummy factors if the blending equation does not use the factors.
-        let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-        let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-
-        // Updating the blending color if necessary.
-        if (color_factor_src == LinearBlendingFactor::ConstantColor ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_dst == LinearBlendingFactor::ConstantColor ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha) && ctxt.state.blend_color != blend.constant_value {
-            let (r, g, b, a) = blend.constant_value;
-            unsafe { ctxt.gl.BlendColor(r, g, b, a); }
-            ctxt.state.blend_color = blend.constant_value;
-        }
+    let uses_constant = is_constant(color_factor_src) || is_constant(color_factor_dst) ||
+                        is_constant(alpha_factor_src) || is_constant(alpha_factor_dst);
+
+    let func = (color_factor_src.to_glenum(), color_factor_dst.to_glenum(),
+                alpha_factor_src.to_glenum(), alpha_factor_dst.to_glenum());
+
+    Ok((Some(((color_eq, alpha_eq), func)), uses_constant))
+}
+
+/// Updates the blending color if necessary.
+fn sync_blend_color(ctxt: &mut CommandContext<'_>, value: (f32, f32, f32, f32)) {
+    if ctxt.state.blend_color != value {
+        let (r, g, b, a) = value;
+        unsafe { ctxt.gl.BlendColor(r, g, b, a); }
+        ctxt.state.blend_color = value;
+    }
+}
+
+/// Sets the same blending function for all draw buffers.
+fn sync_global_blending(ctxt: &mut CommandContext<'_>, state: BlendState) {
+    // the cached values don't match the actual state if blending was last set per draw buffer
+    let force = ctxt.state.blend_indexed;
+    if force {
+        ctxt.state.blend_indexed = false;
+        ctxt.state.blend_per_buffer.clear();
+    }
 
-        // Updating the blending function if necessary.
-        let color_factor_src = color_factor_src.to_glenum();
-        let color_factor_dst = color_factor_dst.to_glenum();
-        let alpha_factor_src = alpha_factor_src.to_glenum();
-        let alpha_factor_dst = alpha_factor_dst.to_glenum();
-        if ctxt.state.blend_func != (color_factor_src, color_factor_dst,
-                                     alpha_factor_src, alpha_factor_dst)
-        {
-            unsafe {
-                ctxt.gl.BlendFuncSeparate(color_factor_src, color_factor_dst,
-                                          alpha_factor_src, alpha_factor_dst);
+    match state {
+        None => {
+            if ctxt.state.enabled_blend || force {
+                unsafe { ctxt.gl.Disable(gl::BLEND); }
+                ctxt.state.enabled_blend = false;
             }
+        },
 
-            ctxt.state.blend_func = (color_factor_src, color_factor_dst,
-                                     alpha_factor_src, alpha_factor_dst);
-        }
+        Some((equation, func)) => {
+            if !ctxt.state.enabled_blend || force {
+                unsafe { ctxt.gl.Enable(gl::BLEND); }
+                ctxt.state.enabled_blend = true;
+            }
+
+            if ctxt.state.blend_equation != equation || force {
+                unsafe { ctxt.gl.BlendEquationSeparate(equation.0, equation.1); }
+                ctxt.state.blend_equation = equation;
+            }
+
+            if ctxt.state.blend_func != func || force {
+                unsafe { ctxt.gl.BlendFuncSeparate(func.0, func.1, func.2, func.3); }
+                ctxt.state.blend_func = func;
+            }
+        },
     }
+}
 
-    Ok(())
+/// Sets a blending function for each draw buffer.
+fn sync_indexed_blending(ctxt: &mut CommandContext<'_>, states: &[BlendState]) {
+    ctxt.state.blend_indexed = true;
+
+    for (index, &state) in states.iter().enumerate() {
+        let cached = ctxt.state.blend_per_buffer.get(index).copied();
+        if cached == Some(state) {
+            continue;
+        }
+
+        let buffer = index as gl::types::GLuint;
+        let cached = cached.flatten();
+
+        match state {
+            None => unsafe {
+                ctxt.gl.Disablei(gl::BLEND, buffer);
+            },
+
+            Some((equation, func)) => unsafe {
+                if cached.is_none() {
+                    ctxt.gl.Enablei(gl::BLEND, buffer);
+                }
+
+                if cached.map(|c| c.0) != Some(equation) {
+                    ctxt.gl.BlendEquationSeparatei(buffer, equation.0, equation.1);
+                }
+
+                if cached.map(|c| c.1) != Some(func) {
+                    ctxt.gl.BlendFuncSeparatei(buffer, func.0, func.1, func.2, func.3);
+                }
+            },
+        }
+
+        if index < ctxt.state.blend_per_buffer.len() {
+            ctxt.state.blend_per_buffer[index] = state;
+        } else {
+            ctxt.state.blend_per_buffer.push(state);
+        }
+    }
 }
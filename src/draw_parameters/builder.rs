@@ -0,0 +1,327 @@
+use crate::CapabilitiesSource;
+use crate::DrawError;
+use crate::Rect;
+use crate::vertex::TransformFeedbackSession;
+
+use std::ops::Range;
+
+use super::{DrawParameters, Depth, Stencil, Blend};
+use super::{BackfaceCullingMode, PolygonMode, PolygonOffset, Smooth};
+use super::{ProvokingVertex, ClipControlOrigin, ClipControlDepth, ConditionalRendering};
+use super::{SamplesQueryParam, TimeElapsedQuery, PrimitivesGeneratedQuery};
+use super::{TransformFeedbackPrimitivesWrittenQuery, PipelineStatisticsQueries};
+
+/// Builds a `DrawParameters` and checks it against the capabilities of a context.
+///
+/// Some parameters, like the provoking vertex or depth clamping, are not supported by every
+/// backend. When filling a `DrawParameters` directly, these are only detected when drawing and
+/// the error is returned by every call to `draw`. The builder instead performs these checks
+/// once in `build`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let params = glium::draw_parameters::DrawParametersBuilder::new()
+///     .with_depth(glium::Depth {
+///         test: glium::DepthTest::IfLess,
+///         write: true,
+///         .. Default::default()
+///     })
+///     .with_provoking_vertex(glium::draw_parameters::ProvokingVertex::FirstVertex)
+///     .build(&*display)
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DrawParametersBuilder<'a> {
+    params: DrawParameters<'a>,
+}
+
+impl<'a> DrawParametersBuilder<'a> {
+    /// Initializes a new builder with the default parameters.
+    #[inline]
+    pub fn new() -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder {
+            params: Default::default(),
+        }
+    }
+
+    /// Initializes a new builder starting from existing parameters.
+    #[inline]
+    pub fn from_parameters(params: DrawParameters<'a>) -> DrawParametersBuilder<'a> {
+        DrawParametersBuilder {
+            params,
+        }
+    }
+
+    /// Sets how the fragment will interact with the depth buffer.
+    #[inline]
+    pub fn with_depth(mut self, depth: Depth) -> Self {
+        self.params.depth = depth;
+        self
+    }
+
+    /// Sets how the fragment will interact with the stencil buffer.
+    #[inline]
+    pub fn with_stencil(mut self, stencil: Stencil) -> Self {
+        self.params.stencil = stencil;
+        self
+    }
+
+    /// Sets the blending effect.
+    #[inline]
+    pub fn with_blend(mut self, blend: Blend) -> Self {
+        self.params.blend = blend;
+        self
+    }
+
+    /// Sets a different blending effect for each draw buffer.
+    #[inline]
+    pub fn with_blend_per_buffer(mut self, blends: &'a [Blend]) -> Self {
+        self.params.blend_per_buffer = Some(blends);
+        self
+    }
+
+    /// Sets which color components are written, in the order red, green, blue, alpha.
+    #[inline]
+    pub fn with_color_mask(mut self, color_mask: (bool, bool, bool, bool)) -> Self {
+        self.params.color_mask = color_mask;
+        self
+    }
+
+    /// Sets the width in pixels of the lines to draw.
+    #[inline]
+    pub fn with_line_width(mut self, line_width: Option<f32>) -> Self {
+        self.params.line_width = line_width;
+        self
+    }
+
+    /// Sets the diameter in pixels of the points to draw.
+    #[inline]
+    pub fn with_point_size(mut self, point_size: Option<f32>) -> Self {
+        self.params.point_size = point_size;
+        self
+    }
+
+    /// Sets the bitmask of the enabled `GL_CLIP_DISTANCEi`.
+    #[inline]
+    pub fn with_clip_planes_bitmask(mut self, clip_planes_bitmask: u32) -> Self {
+        self.params.clip_planes_bitmask = clip_planes_bitmask;
+        self
+    }
+
+    /// Sets which faces are filtered out.
+    #[inline]
+    pub fn with_backface_culling(mut self, backface_culling: BackfaceCullingMode) -> Self {
+        self.params.backface_culling = backface_culling;
+        self
+    }
+
+    /// Sets how polygons are rendered.
+    #[inline]
+    pub fn with_polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+        self.params.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Sets whether multisample antialiasing is used.
+    #[inline]
+    pub fn with_multisampling(mut self, multisampling: bool) -> Self {
+        self.params.multisampling = multisampling;
+        self
+    }
+
+    /// Sets the minimum fraction of the samples for which the fragment shader runs.
+    #[inline]
+    pub fn with_sample_shading(mut self, sample_shading: Option<f32>) -> Self {
+        self.params.sample_shading = sample_shading;
+        self
+    }
+
+    /// Sets whether dithering is activated.
+    #[inline]
+    pub fn with_dithering(mut self, dithering: bool) -> Self {
+        self.params.dithering = dithering;
+        self
+    }
+
+    /// Sets the viewport to use when drawing.
+    #[inline]
+    pub fn with_viewport(mut self, viewport: Option<Rect>) -> Self {
+        self.params.viewport = viewport;
+        self
+    }
+
+    /// Sets the scissor box.
+    #[inline]
+    pub fn with_scissor(mut self, scissor: Option<Rect>) -> Self {
+        self.params.scissor = scissor;
+        self
+    }
+
+    /// Sets multiple viewports at once, starting at index 0.
+    #[inline]
+    pub fn with_viewports(mut self, viewports: &'a [Rect]) -> Self {
+        self.params.viewports = Some(viewports);
+        self
+    }
+
+    /// Sets one scissor box per viewport, starting at index 0.
+    #[inline]
+    pub fn with_scissors(mut self, scissors: &'a [Rect]) -> Self {
+        self.params.scissors = Some(scissors);
+        self
+    }
+
+    /// Sets whether the pipeline continues after the primitives generation stage.
+    #[inline]
+    pub fn with_draw_primitives(mut self, draw_primitives: bool) -> Self {
+        self.params.draw_primitives = draw_primitives;
+        self
+    }
+
+    /// Sets the query that counts the samples written to the output.
+    #[inline]
+    pub fn with_samples_passed_query(mut self, query: Option<SamplesQueryParam<'a>>) -> Self {
+        self.params.samples_passed_query = query;
+        self
+    }
+
+    /// Sets the query that measures the time it took to execute the draw command.
+    #[inline]
+    pub fn with_time_elapsed_query(mut self, query: Option<&'a TimeElapsedQuery>) -> Self {
+        self.params.time_elapsed_query = query;
+        self
+    }
+
+    /// Sets the query that counts the number of primitives generated.
+    #[inline]
+    pub fn with_primitives_generated_query(mut self, query: Option<&'a PrimitivesGeneratedQuery>)
+                                           -> Self
+    {
+        self.params.primitives_generated_query = query;
+        self
+    }
+
+    /// Sets the query that counts the number of primitives written by transform feedback.
+    #[inline]
+    pub fn with_transform_feedback_primitives_written_query(
+        mut self, query: Option<&'a TransformFeedbackPrimitivesWrittenQuery>) -> Self
+    {
+        self.params.transform_feedback_primitives_written_query = query;
+        self
+    }
+
+    /// Sets the queries that count the work done by each stage of the pipeline.
+    #[inline]
+    pub fn with_pipeline_statistics_queries(mut self, queries: PipelineStatisticsQueries<'a>)
+                                            -> Self
+    {
+        self.params.pipeline_statistics_queries = queries;
+        self
+    }
+
+    /// Sets the condition for the commands to be executed.
+    #[inline]
+    pub fn with_condition(mut self, condition: Option<ConditionalRendering<'a>>) -> Self {
+        self.params.condition = condition;
+        self
+    }
+
+    /// Sets the transform feedback session the generated primitives are written to.
+    #[inline]
+    pub fn with_transform_feedback(mut self, session: Option<&'a TransformFeedbackSession<'a>>)
+                                   -> Self
+    {
+        self.params.transform_feedback = session;
+        self
+    }
+
+    /// Sets whether the generated primitives are smoothed.
+    #[inline]
+    pub fn with_smooth(mut self, smooth: Option<Smooth>) -> Self {
+        self.params.smooth = smooth;
+        self
+    }
+
+    /// Sets which vertex is used for `flat` varyings.
+    #[inline]
+    pub fn with_provoking_vertex(mut self, provoking_vertex: ProvokingVertex) -> Self {
+        self.params.provoking_vertex = provoking_vertex;
+        self
+    }
+
+    /// Sets the hint for the bounding box of the geometry.
+    #[inline]
+    pub fn with_primitive_bounding_box(mut self, bounding_box: (Range<f32>, Range<f32>,
+                                                                Range<f32>, Range<f32>)) -> Self
+    {
+        self.params.primitive_bounding_box = bounding_box;
+        self
+    }
+
+    /// Sets whether primitive restarting with the maximum index value is enabled.
+    #[inline]
+    pub fn with_primitive_restart_index(mut self, primitive_restart_index: bool) -> Self {
+        self.params.primitive_restart_index = primitive_restart_index;
+        self
+    }
+
+    /// Sets the depth offset.
+    #[inline]
+    pub fn with_polygon_offset(mut self, polygon_offset: PolygonOffset) -> Self {
+        self.params.polygon_offset = polygon_offset;
+        self
+    }
+
+    /// Sets whether the output of the fragment shader is converted to sRGB.
+    #[inline]
+    pub fn with_srgb(mut self, srgb: bool) -> Self {
+        self.params.srgb = Some(srgb);
+        self
+    }
+
+    /// Sets the clip control origin.
+    #[inline]
+    pub fn with_clip_control_origin(mut self, origin: ClipControlOrigin) -> Self {
+        self.params.clip_control_origin = origin;
+        self
+    }
+
+    /// Sets the clip control depth mode.
+    #[inline]
+    pub fn with_clip_control_depth(mut self, depth: ClipControlDepth) -> Self {
+        self.params.clip_control_depth = depth;
+        self
+    }
+
+    /// Sets the outer and inner tessellation levels to use when there is no tessellation
+    /// control shader.
+    #[inline]
+    pub fn with_tessellation_default_levels(mut self, outer: [f32; 4], inner: [f32; 2]) -> Self {
+        self.params.tessellation_default_levels = Some((outer, inner));
+        self
+    }
+
+    /// Sets whether the indices are read and checked against the number of vertices before
+    /// drawing.
+    #[inline]
+    pub fn with_validate_indices(mut self, validate_indices: bool) -> Self {
+        self.params.validate_indices = validate_indices;
+        self
+    }
+
+    /// Checks the parameters against the capabilities of the context and returns them.
+    ///
+    /// The errors are the same as the ones that `draw` would return with these parameters.
+    /// Errors that depend on the draw call itself, like using a query in a wrong way or
+    /// smoothing points, are not detected.
+    pub fn build<C: ?Sized>(self, ctxt: &C) -> Result<DrawParameters<'a>, DrawError>
+        where C: CapabilitiesSource
+    {
+        super::validate(ctxt, &self.params)?;
+        Ok(self.params)
+    }
+}
@@ -67,7 +67,6 @@
 
 use crate::gl;
 use crate::context;
-use crate::context::Context;
 use crate::version::Version;
 use crate::version::Api;
 
@@ -83,13 +82,22 @@ use crate::vertex::TransformFeedbackSession;
 use std::ops::Range;
 
 pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
+pub use self::builder::DrawParametersBuilder;
 pub use self::depth::{Depth, DepthTest, DepthClamp};
-pub use self::query::{QueryCreationError};
+pub use self::query::{QueryCreationError, ToBufferError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
 pub use self::query::{AnySamplesPassedQuery, TransformFeedbackPrimitivesWrittenQuery};
+pub use self::query::{VerticesSubmittedQuery, PrimitivesSubmittedQuery};
+pub use self::query::{VertexShaderInvocationsQuery, TessControlShaderPatchesQuery};
+pub use self::query::{TessEvaluationShaderInvocationsQuery, GeometryShaderInvocationsQuery};
+pub use self::query::{GeometryShaderPrimitivesEmittedQuery, FragmentShaderInvocationsQuery};
+pub use self::query::{ClippingInputPrimitivesQuery, ClippingOutputPrimitivesQuery};
+pub use self::query::PipelineStatisticsQueries;
+pub(crate) use self::query::{PipelineStatistic, QueryPool, QueryType, RawQuery};
 pub use self::stencil::{StencilTest, StencilOperation, Stencil};
 
 mod blend;
+mod builder;
 mod depth;
 mod query;
 mod stencil;
@@ -258,6 +266,13 @@ pub struct DrawParameters<'a> {
     /// being written.
     pub blend: Blend,
 
+    /// If specified, the blending function to use for each draw buffer, in the order of the
+    /// draw buffers of the framebuffer. Draw buffers after the end of the list don't blend.
+    ///
+    /// Overrides `blend`, except for its constant value which is shared by all draw buffers.
+    /// Requires OpenGL 4.0 or OpenGL ES 3.2.
+    pub blend_per_buffer: Option<&'a [Blend]>,
+
     /// Allows you to disable some color components.
     ///
     /// This affects all attachments to the framebuffer. It's at the same level as the
@@ -305,6 +320,18 @@ pub struct DrawParameters<'a> {
     /// creating the window.
     pub multisampling: bool,
 
+    /// Forces the fragment shader to run for a minimum fraction of the samples of each pixel
+    /// when multisampling. Default value is `None`.
+    ///
+    /// With `Some(1.0)`, the fragment shader runs once per sample instead of once per pixel,
+    /// which antialiases the edges produced inside the shader, for example by alpha-tested
+    /// foliage, at the cost of more fragment shader invocations. The value is clamped between
+    /// `0.0` and `1.0` by OpenGL.
+    ///
+    /// Drawing with a value other than `None` returns an error if the backend doesn't support
+    /// per-sample shading.
+    pub sample_shading: Option<f32>,
+
     /// Whether dithering is activated. Default value is `true`.
     ///
     /// Dithering will smoothen the transition between colors in your color buffer.
@@ -327,6 +354,27 @@ pub struct DrawParameters<'a> {
     /// not the scissor box.
     pub scissor: Option<Rect>,
 
+    /// If specified, sets multiple viewports at once, starting at index 0. Takes precedence
+    /// over `viewport`. Default is `None`.
+    ///
+    /// The geometry shader chooses the viewport of each primitive by writing to
+    /// `gl_ViewportIndex`. Primitives that don't write it use the first viewport. This allows
+    /// rendering stereo views or cascaded shadow maps in a single pass.
+    ///
+    /// Drawing returns `ViewportArrayNotSupported` if the backend doesn't support viewport
+    /// arrays, and `TooManyViewports` if the slice is longer than `max_viewports`.
+    pub viewports: Option<&'a [Rect]>,
+
+    /// If specified, sets one scissor box per viewport, starting at index 0. Takes precedence
+    /// over `scissor`. Default is `None`.
+    ///
+    /// Scissor boxes beyond the length of the slice keep their previous value, so you usually
+    /// want to pass as many of them as there are viewports.
+    ///
+    /// Drawing returns `ViewportArrayNotSupported` if the backend doesn't support viewport
+    /// arrays, and `TooManyViewports` if the slice is longer than `max_viewports`.
+    pub scissors: Option<&'a [Rect]>,
+
     /// If `false`, the pipeline will stop after the primitives generation stage. The default
     /// value is `true`.
     ///
@@ -354,6 +402,10 @@ pub struct DrawParameters<'a> {
     pub transform_feedback_primitives_written_query:
                                     Option<&'a TransformFeedbackPrimitivesWrittenQuery>,
 
+    /// The queries that count the work done by each stage of the pipeline during this draw
+    /// command. See `PipelineStatisticsQueries`.
+    pub pipeline_statistics_queries: PipelineStatisticsQueries<'a>,
+
     /// If set, the commands will only be executed if the specified query contains `true` or
     /// a number different than 0.
     pub condition: Option<ConditionalRendering<'a>>,
@@ -401,11 +453,44 @@ pub struct DrawParameters<'a> {
     /// drawing decals and wireframes, for example.
     pub polygon_offset: PolygonOffset,
 
+    /// If specified, whether the output of the fragment shader is converted from linear to sRGB
+    /// when it is written to an sRGB attachment, overriding the `outputs_srgb` setting of
+    /// the program and the setting of the framebuffer.
+    ///
+    /// `Some(true)` enables the conversion and `Some(false)` disables it. The default value
+    /// is `None`.
+    pub srgb: Option<bool>,
+
     /// Clip control origin. The default value is `LowerLeft`.
+    ///
+    /// Non-default values require OpenGL 4.5 or `GL_ARB_clip_control`, otherwise drawing
+    /// returns `DrawError::ClipControlNotSupported`.
     pub clip_control_origin: ClipControlOrigin,
 
     /// Clip control depth mode. The default value is `NegativeOneToOne`.
+    ///
+    /// `ZeroToOne` maps the clip-space depth directly to the depth buffer, which combined with
+    /// a reversed projection and `DepthTest::IfMore` avoids most precision artifacts of
+    /// floating-point depth buffers. Same requirements as `clip_control_origin`.
     pub clip_control_depth: ClipControlDepth,
+
+    /// The outer and inner tessellation levels to use when drawing patches with a program that
+    /// has a tessellation evaluation shader but no tessellation control shader.
+    ///
+    /// If `None`, the levels are left unchanged. The initial value in OpenGL is `1.0` for all
+    /// the levels.
+    ///
+    /// Drawing with a value other than `None` returns an error if the backend doesn't support
+    /// tessellation shaders.
+    pub tessellation_default_levels: Option<([f32; 4], [f32; 2])>,
+
+    /// If true, the indices of the index buffer are read and checked against the number of
+    /// vertices before drawing, and drawing returns `DrawError::IndexValidationError` if one of
+    /// them is out of range.
+    ///
+    /// Reading the indices is slow, so this should only be enabled for debugging. The default
+    /// value is `false`.
+    pub validate_indices: bool,
 }
 
 /// Condition whether to render or not.
@@ -499,6 +584,7 @@ impl<'a> Default for DrawParameters<'a> {
             depth: Depth::default(),
             stencil: Default::default(),
             blend: Default::default(),
+            blend_per_buffer: None,
             color_mask: (true, true, true, true),
             line_width: None,
             point_size: None,
@@ -506,14 +592,18 @@ impl<'a> Default for DrawParameters<'a> {
             polygon_mode: PolygonMode::Fill,
             clip_planes_bitmask: 0,
             multisampling: true,
+            sample_shading: None,
             dithering: true,
             viewport: None,
             scissor: None,
+            viewports: None,
+            scissors: None,
             draw_primitives: true,
             samples_passed_query: None,
             time_elapsed_query: None,
             primitives_generated_query: None,
             transform_feedback_primitives_written_query: None,
+            pipeline_statistics_queries: Default::default(),
             condition: None,
             transform_feedback: None,
             smooth: None,
@@ -521,58 +611,187 @@ impl<'a> Default for DrawParameters<'a> {
             primitive_bounding_box: (-1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0),
             primitive_restart_index: false,
             polygon_offset: Default::default(),
+            srgb: None,
             clip_control_origin: ClipControlOrigin::LowerLeft,
             clip_control_depth: ClipControlDepth::NegativeOneToOne,
+            tessellation_default_levels: None,
+            validate_indices: false,
         }
     }
 }
 
-/// DEPRECATED. Checks parameters and returns an error if something is wrong.
-pub fn validate(context: &Context, params: &DrawParameters<'_>) -> Result<(), DrawError> {
+/// Checks the parameters against the capabilities of a context and returns an error if
+/// something is wrong.
+///
+/// This is called by `draw` and by `DrawParametersBuilder::build`.
+pub fn validate<C: ?Sized>(ctxt: &C, params: &DrawParameters<'_>) -> Result<(), DrawError>
+    where C: CapabilitiesSource
+{
+    let version = ctxt.get_version();
+    let extensions = ctxt.get_extensions();
+
     if params.depth.range.0 < 0.0 || params.depth.range.0 > 1.0 ||
        params.depth.range.1 < 0.0 || params.depth.range.1 > 1.0
     {
         return Err(DrawError::InvalidDepthRange);
     }
 
-    if !params.draw_primitives && context.get_opengl_version() < &Version(Api::Gl, 3, 0) &&
-        !context.get_extensions().gl_ext_transform_feedback
+    match params.depth.clamp {
+        DepthClamp::NoClamp => (),
+        DepthClamp::Clamp => {
+            if !(version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_depth_clamp ||
+                 extensions.gl_nv_depth_clamp)
+            {
+                return Err(DrawError::DepthClampNotSupported);
+            }
+        },
+        DepthClamp::ClampNear | DepthClamp::ClampFar => {
+            if !extensions.gl_amd_depth_clamp_separate {
+                return Err(DrawError::DepthClampNotSupported);
+            }
+        },
+    }
+
+    if let Some(blends) = params.blend_per_buffer {
+        if !(version >= &Version(Api::Gl, 4, 0) || version >= &Version(Api::GlEs, 3, 2)) {
+            return Err(DrawError::BlendPerBufferNotSupported);
+        }
+
+        if blends.len() > ctxt.get_capabilities().max_draw_buffers as usize {
+            return Err(DrawError::TooManyBlendBuffers);
+        }
+    }
+
+    let blends = std::iter::once(&params.blend).chain(params.blend_per_buffer.unwrap_or(&[]));
+    for function in blends.flat_map(|blend| [blend.color, blend.alpha]) {
+        match function {
+            BlendingFunction::Min | BlendingFunction::Max => {
+                if version <= &Version(Api::GlEs, 2, 0) && !extensions.gl_ext_blend_minmax {
+                    return Err(DrawError::BlendingParameterNotSupported);
+                }
+            },
+            BlendingFunction::Addition { source, destination } |
+            BlendingFunction::Subtraction { source, destination } |
+            BlendingFunction::ReverseSubtraction { source, destination } => {
+                if (is_dual_source(source) || is_dual_source(destination)) &&
+                   !(version >= &Version(Api::Gl, 3, 3) || extensions.gl_arb_blend_func_extended ||
+                     extensions.gl_ext_blend_func_extended)
+                {
+                    return Err(DrawError::BlendingParameterNotSupported);
+                }
+            },
+            BlendingFunction::AlwaysReplace => (),
+        }
+    }
+
+    if !params.draw_primitives && !(version >= &Version(Api::Gl, 3, 0) ||
+                                    extensions.gl_ext_transform_feedback)
     {
         return Err(DrawError::RasterizerDiscardNotSupported);
     }
 
+    if params.smooth.is_some() && !(version >= &Version(Api::Gl, 1, 0)) {
+        return Err(DrawError::SmoothingNotSupported);
+    }
+
+    if params.provoking_vertex != ProvokingVertex::LastVertex &&
+       !(version >= &Version(Api::Gl, 3, 2) || extensions.gl_arb_provoking_vertex ||
+         extensions.gl_ext_provoking_vertex)
+    {
+        return Err(DrawError::ProvokingVertexNotSupported);
+    }
+
+    if params.primitive_restart_index &&
+       !(version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+         extensions.gl_arb_es3_compatibility)
+    {
+        return Err(DrawError::FixedIndexRestartingNotSupported);
+    }
+
+    if params.srgb.is_some() &&
+       !(version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_framebuffer_srgb ||
+         extensions.gl_ext_framebuffer_srgb || extensions.gl_ext_srgb_write_control)
+    {
+        return Err(DrawError::SrgbConversionNotSupported);
+    }
+
+    if (params.clip_control_origin != ClipControlOrigin::LowerLeft ||
+        params.clip_control_depth != ClipControlDepth::NegativeOneToOne) &&
+       !(version >= &Version(Api::Gl, 4, 5) || extensions.gl_arb_clip_control)
+    {
+        return Err(DrawError::ClipControlNotSupported);
+    }
+
+    if params.tessellation_default_levels.is_some() &&
+       !(version >= &Version(Api::Gl, 4, 0) || extensions.gl_arb_tessellation_shader)
+    {
+        return Err(DrawError::TessellationDefaultLevelsNotSupported);
+    }
+
+    if params.sample_shading.is_some() &&
+       !(version >= &Version(Api::Gl, 4, 0) || version >= &Version(Api::GlEs, 3, 2) ||
+         extensions.gl_arb_sample_shading || extensions.gl_oes_sample_shading)
+    {
+        return Err(DrawError::SampleShadingNotSupported);
+    }
+
+    if params.viewports.is_some() || params.scissors.is_some() {
+        if !(version >= &Version(Api::Gl, 4, 1) || extensions.gl_arb_viewport_array) {
+            return Err(DrawError::ViewportArrayNotSupported);
+        }
+
+        let max = ctxt.get_capabilities().max_viewports as usize;
+        if params.viewports.map_or(0, |v| v.len()) > max ||
+           params.scissors.map_or(0, |s| s.len()) > max
+        {
+            return Err(DrawError::TooManyViewports);
+        }
+    }
+
     Ok(())
 }
 
+#[inline]
+fn is_dual_source(factor: LinearBlendingFactor) -> bool {
+    matches!(factor, LinearBlendingFactor::SourceOneColor |
+                     LinearBlendingFactor::OneMinusSourceOneColor |
+                     LinearBlendingFactor::SourceOneAlpha |
+                     LinearBlendingFactor::OneMinusSourceOneAlpha)
+}
+
 #[doc(hidden)]
 pub fn sync(ctxt: &mut context::CommandContext<'_>, draw_parameters: &DrawParameters<'_>,
             dimensions: (u32, u32), primitives_types: PrimitiveType) -> Result<(), DrawError>
 {
     depth::sync_depth(ctxt, &draw_parameters.depth)?;
     stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    blend::sync_blending(ctxt, draw_parameters.blend)?;
+    blend::sync_blending(ctxt, draw_parameters.blend, draw_parameters.blend_per_buffer)?;
     sync_color_mask(ctxt, draw_parameters.color_mask);
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     sync_clip_planes_bitmask(ctxt, draw_parameters.clip_planes_bitmask)?;
     sync_multisampling(ctxt, draw_parameters.multisampling);
+    sync_sample_shading(ctxt, draw_parameters.sample_shading)?;
     sync_dithering(ctxt, draw_parameters.dithering);
     sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+                          draw_parameters.viewports, draw_parameters.scissors, dimensions)?;
     sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives)?;
     sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
                       draw_parameters.primitives_generated_query,
                       draw_parameters.transform_feedback_primitives_written_query)?;
+    draw_parameters.pipeline_statistics_queries.sync(ctxt)?;
     sync_conditional_render(ctxt, draw_parameters.condition);
     sync_smooth(ctxt, draw_parameters.smooth, primitives_types)?;
     sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex)?;
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
     sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index)?;
     sync_polygon_offset(ctxt, draw_parameters.polygon_offset);
+    sync_srgb(ctxt, draw_parameters.srgb)?;
     sync_clip_control(ctxt, draw_parameters.clip_control_origin,
                       draw_parameters.clip_control_depth)?;
+    sync_tessellation_default_levels(ctxt, draw_parameters.tessellation_default_levels)?;
 
     Ok(())
 }
@@ -703,6 +922,48 @@ fn sync_multisampling(ctxt: &mut context::CommandContext<'_>, multisampling: boo
     }
 }
 
+fn sync_sample_shading(ctxt: &mut context::CommandContext<'_>, sample_shading: Option<f32>)
+                       -> Result<(), DrawError>
+{
+    let rate = match sample_shading {
+        Some(rate) => rate,
+        None => {
+            if ctxt.state.enabled_sample_shading {
+                unsafe { ctxt.gl.Disable(gl::SAMPLE_SHADING); }
+                ctxt.state.enabled_sample_shading = false;
+            }
+            return Ok(());
+        },
+    };
+
+    let core = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2);
+
+    if !core && !ctxt.extensions.gl_arb_sample_shading && !ctxt.extensions.gl_oes_sample_shading {
+        return Err(DrawError::SampleShadingNotSupported);
+    }
+
+    if !ctxt.state.enabled_sample_shading {
+        unsafe { ctxt.gl.Enable(gl::SAMPLE_SHADING); }
+        ctxt.state.enabled_sample_shading = true;
+    }
+
+    if ctxt.state.min_sample_shading != rate {
+        unsafe {
+            if core {
+                ctxt.gl.MinSampleShading(rate);
+            } else if ctxt.extensions.gl_arb_sample_shading {
+                ctxt.gl.MinSampleShadingARB(rate);
+            } else {
+                ctxt.gl.MinSampleShadingOES(rate);
+            }
+        }
+        ctxt.state.min_sample_shading = rate;
+    }
+
+    Ok(())
+}
+
 fn sync_dithering(ctxt: &mut context::CommandContext<'_>, dithering: bool) {
     if ctxt.state.enabled_dither != dithering {
         unsafe {
@@ -718,41 +979,91 @@ fn sync_dithering(ctxt: &mut context::CommandContext<'_>, dithering: bool) {
 }
 
 fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Option<Rect>,
-                         scissor: Option<Rect>, surface_dimensions: (u32, u32))
+                         scissor: Option<Rect>, viewports: Option<&[Rect]>,
+                         scissors: Option<&[Rect]>, surface_dimensions: (u32, u32))
+                         -> Result<(), DrawError>
 {
-    // viewport
-    if let Some(viewport) = viewport {
-        assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
+    if viewports.is_some() || scissors.is_some() {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.extensions.gl_arb_viewport_array) {
+            return Err(DrawError::ViewportArrayNotSupported);
+        }
 
-        let viewport = (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
-                        viewport.width as gl::types::GLsizei,
-                        viewport.height as gl::types::GLsizei);
+        let max = ctxt.capabilities.max_viewports as usize;
+        if viewports.map_or(0, |v| v.len()) > max || scissors.map_or(0, |s| s.len()) > max {
+            return Err(DrawError::TooManyViewports);
+        }
+    }
 
-        if ctxt.state.viewport != Some(viewport) {
-            unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
-            ctxt.state.viewport = Some(viewport);
+    // viewport
+    if let Some(viewports) = viewports {
+        let viewports = viewports.iter().map(|viewport| {
+            assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                    "Viewport dimensions are too large");
+            assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                    "Viewport dimensions are too large");
+
+            [viewport.left as gl::types::GLfloat, viewport.bottom as gl::types::GLfloat,
+             viewport.width as gl::types::GLfloat, viewport.height as gl::types::GLfloat]
+        }).collect::<Vec<_>>();
+
+        if ctxt.state.viewport_array != viewports {
+            unsafe {
+                ctxt.gl.ViewportArrayv(0, viewports.len() as gl::types::GLsizei,
+                                       viewports.as_ptr() as *const _);
+            }
+            ctxt.state.viewport_array = viewports;
+            ctxt.state.viewport = None;
         }
 
     } else {
-        assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
+        let viewport = if let Some(viewport) = viewport {
+            assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                    "Viewport dimensions are too large");
+            assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                    "Viewport dimensions are too large");
 
-        let viewport = (0, 0, surface_dimensions.0 as gl::types::GLsizei,
-                        surface_dimensions.1 as gl::types::GLsizei);
+            (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
+             viewport.width as gl::types::GLsizei, viewport.height as gl::types::GLsizei)
+
+        } else {
+            assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                    "Viewport dimensions are too large");
+            assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                    "Viewport dimensions are too large");
+
+            (0, 0, surface_dimensions.0 as gl::types::GLsizei,
+             surface_dimensions.1 as gl::types::GLsizei)
+        };
 
         if ctxt.state.viewport != Some(viewport) {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewport_array.clear();
         }
     }
 
     // scissor
-    if let Some(scissor) = scissor {
+    if let Some(scissors) = scissors {
+        let scissors = scissors.iter().map(|scissor| {
+            [scissor.left as gl::types::GLint, scissor.bottom as gl::types::GLint,
+             scissor.width as gl::types::GLsizei, scissor.height as gl::types::GLsizei]
+        }).collect::<Vec<_>>();
+
+        unsafe {
+            if ctxt.state.scissor_array != scissors {
+                ctxt.gl.ScissorArrayv(0, scissors.len() as gl::types::GLsizei,
+                                      scissors.as_ptr() as *const _);
+                ctxt.state.scissor_array = scissors;
+                ctxt.state.scissor = None;
+            }
+
+            if !ctxt.state.enabled_scissor_test {
+                ctxt.gl.Enable(gl::SCISSOR_TEST);
+                ctxt.state.enabled_scissor_test = true;
+            }
+        }
+
+    } else if let Some(scissor) = scissor {
         let scissor = (scissor.left as gl::types::GLint, scissor.bottom as gl::types::GLint,
                        scissor.width as gl::types::GLsizei,
                        scissor.height as gl::types::GLsizei);
@@ -761,6 +1072,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
             if ctxt.state.scissor != Some(scissor) {
                 ctxt.gl.Scissor(scissor.0, scissor.1, scissor.2, scissor.3);
                 ctxt.state.scissor = Some(scissor);
+                ctxt.state.scissor_array.clear();
             }
 
             if !ctxt.state.enabled_scissor_test {
@@ -776,6 +1088,8 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext<'_>, viewport: Optio
             }
         }
     }
+
+    Ok(())
 }
 
 fn sync_rasterizer_discard(ctxt: &mut context::CommandContext<'_>, draw_primitives: bool)
@@ -1056,6 +1370,34 @@ fn sync_polygon_offset(ctxt: &mut context::CommandContext<'_>, offset: PolygonOf
     }
 }
 
+fn sync_srgb(ctxt: &mut context::CommandContext<'_>, srgb: Option<bool>) -> Result<(), DrawError> {
+    // when not specified, the value has been set when binding the program
+    let srgb = match srgb {
+        Some(srgb) => srgb,
+        None => return Ok(()),
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+         ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control)
+    {
+        return Err(DrawError::SrgbConversionNotSupported);
+    }
+
+    if ctxt.state.enabled_framebuffer_srgb != srgb {
+        unsafe {
+            if srgb {
+                ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
+            } else {
+                ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+            }
+        }
+
+        ctxt.state.enabled_framebuffer_srgb = srgb;
+    }
+
+    Ok(())
+}
+
 fn sync_clip_control(ctxt: &mut context::CommandContext<'_>,
                      origin: ClipControlOrigin,
                      depth: ClipControlDepth)
@@ -1082,3 +1424,29 @@ fn sync_clip_control(ctxt: &mut context::CommandContext<'_>,
 
     Ok(())
 }
+
+fn sync_tessellation_default_levels(ctxt: &mut context::CommandContext<'_>,
+                                    levels: Option<([f32; 4], [f32; 2])>)
+                                    -> Result<(), DrawError>
+{
+    let (outer, inner) = match levels {
+        Some(levels) => levels,
+        None => return Ok(()),
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_tessellation_shader) {
+        return Err(DrawError::TessellationDefaultLevelsNotSupported);
+    }
+
+    if ctxt.state.patch_default_outer_level != outer {
+        unsafe { ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_OUTER_LEVEL, outer.as_ptr()); }
+        ctxt.state.patch_default_outer_level = outer;
+    }
+
+    if ctxt.state.patch_default_inner_level != inner {
+        unsafe { ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_INNER_LEVEL, inner.as_ptr()); }
+        ctxt.state.patch_default_inner_level = inner;
+    }
+
+    Ok(())
+}
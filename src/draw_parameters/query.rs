@@ -1,5 +1,5 @@
 use crate::backend::Facade;
-use crate::context::Context;
+use crate::context::{Context, NonSharedObject};
 use crate::context::CommandContext;
 use crate::ContextExt;
 use crate::DrawError;
@@ -25,12 +25,15 @@ pub struct RawQuery {
     context: Rc<Context>,
     id: gl::types::GLuint,
     ty: QueryType,
+    // value returned by `register_non_shared_object`
+    generation: usize,
 
     // true means that this query has already been used or is being used to get data
     // this is important to know because we want to avoid erasing data
     has_been_used: Cell<bool>,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum QueryType {
     SamplesPassed,
     AnySamplesPassed,
@@ -39,6 +42,55 @@ pub enum QueryType {
     Timestamp,
     PrimitivesGenerated,
     TransformFeedbackPrimitivesWritten,
+    PipelineStatistics(PipelineStatistic),
+}
+
+/// One of the counters of `ARB_pipeline_statistics_query`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PipelineStatistic {
+    VerticesSubmitted,
+    PrimitivesSubmitted,
+    VertexShaderInvocations,
+    TessControlShaderPatches,
+    TessEvaluationShaderInvocations,
+    GeometryShaderInvocations,
+    GeometryShaderPrimitivesEmitted,
+    FragmentShaderInvocations,
+    ClippingInputPrimitives,
+    ClippingOutputPrimitives,
+}
+
+impl PipelineStatistic {
+    /// Number of different counters.
+    pub const COUNT: usize = 10;
+
+    /// Returns the index of the counter in `GlState::pipeline_statistics_queries`.
+    #[inline]
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl ToGlEnum for PipelineStatistic {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            PipelineStatistic::VerticesSubmitted => gl::VERTICES_SUBMITTED,
+            PipelineStatistic::PrimitivesSubmitted => gl::PRIMITIVES_SUBMITTED,
+            PipelineStatistic::VertexShaderInvocations => gl::VERTEX_SHADER_INVOCATIONS,
+            PipelineStatistic::TessControlShaderPatches => gl::TESS_CONTROL_SHADER_PATCHES,
+            PipelineStatistic::TessEvaluationShaderInvocations => {
+                gl::TESS_EVALUATION_SHADER_INVOCATIONS
+            },
+            PipelineStatistic::GeometryShaderInvocations => gl::GEOMETRY_SHADER_INVOCATIONS,
+            PipelineStatistic::GeometryShaderPrimitivesEmitted => {
+                gl::GEOMETRY_SHADER_PRIMITIVES_EMITTED
+            },
+            PipelineStatistic::FragmentShaderInvocations => gl::FRAGMENT_SHADER_INVOCATIONS,
+            PipelineStatistic::ClippingInputPrimitives => gl::CLIPPING_INPUT_PRIMITIVES,
+            PipelineStatistic::ClippingOutputPrimitives => gl::CLIPPING_OUTPUT_PRIMITIVES,
+        }
+    }
 }
 
 impl ToGlEnum for QueryType {
@@ -54,6 +106,7 @@ impl ToGlEnum for QueryType {
             QueryType::TransformFeedbackPrimitivesWritten => {
                 gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN
             },
+            QueryType::PipelineStatistics(statistic) => statistic.to_glenum(),
         }
     }
 }
@@ -82,6 +135,8 @@ impl Error for QueryCreationError {}
 pub enum ToBufferError {
     /// Writing the result to a buffer is not supported.
     NotSupported,
+    /// The query has never been used, so it doesn't have a result yet.
+    QueryNotUsed,
 }
 
 impl fmt::Display for ToBufferError {
@@ -89,6 +144,7 @@ impl fmt::Display for ToBufferError {
         use self::ToBufferError::*;
         let desc = match *self {
             NotSupported => "Writing the result to a buffer is not supported",
+            QueryNotUsed => "The query has never been used",
         };
         fmt.write_str(desc)
     }
@@ -104,8 +160,6 @@ impl RawQuery {
         let context = facade.get_context().clone();
         let ctxt = facade.get_context().make_current();
 
-        // FIXME: handle Timestamp separately
-
         let id = unsafe {
             let mut id = 0;
 
@@ -113,15 +167,21 @@ impl RawQuery {
                 match ty {
                     QueryType::AnySamplesPassed | QueryType::SamplesPassed |
                     QueryType::PrimitivesGenerated | QueryType::TimeElapsed |
-                    QueryType::TransformFeedbackPrimitivesWritten => (),
+                    QueryType::Timestamp | QueryType::TransformFeedbackPrimitivesWritten => (),
                     QueryType::AnySamplesPassedConservative if
                             ctxt.extensions.gl_arb_es3_compatibility ||
                             ctxt.version >= &Version(Api:: Gl, 4, 3) => (),
+                    QueryType::PipelineStatistics(_) if
+                            ctxt.extensions.gl_arb_pipeline_statistics_query ||
+                            ctxt.version >= &Version(Api:: Gl, 4, 6) => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
-                if ctxt.version >= &Version(Api:: Gl, 4, 5) ||
-                   ctxt.extensions.gl_arb_direct_state_access
+                // some drivers (e.g. Mesa) reject the pipeline statistics targets in
+                // `glCreateQueries`, so these queries are created by `glBeginQuery` instead
+                if (ctxt.version >= &Version(Api:: Gl, 4, 5) ||
+                    ctxt.extensions.gl_arb_direct_state_access) &&
+                   !matches!(ty, QueryType::PipelineStatistics(_))
                 {
                     ctxt.gl.CreateQueries(ty.to_glenum(), 1, &mut id);
                 } else {
@@ -135,6 +195,9 @@ impl RawQuery {
                     QueryType::AnySamplesPassed if ctxt.extensions.gl_arb_occlusion_query2 => (),
                     QueryType::AnySamplesPassedConservative if ctxt.extensions.gl_arb_es3_compatibility => (),
                     QueryType::TimeElapsed if ctxt.extensions.gl_arb_timer_query => (),
+                    QueryType::Timestamp if ctxt.extensions.gl_arb_timer_query => (),
+                    QueryType::PipelineStatistics(_) if
+                            ctxt.extensions.gl_arb_pipeline_statistics_query => (),
 
                     _ => return Err(QueryCreationError::NotSupported)
                 };
@@ -149,6 +212,7 @@ impl RawQuery {
                     QueryType::PrimitivesGenerated if ctxt.extensions.gl_ext_transform_feedback => (),
                     QueryType::TransformFeedbackPrimitivesWritten if ctxt.extensions.gl_ext_transform_feedback => (),
                     QueryType::TimeElapsed if ctxt.extensions.gl_arb_timer_query => (),
+                    QueryType::Timestamp if ctxt.extensions.gl_arb_timer_query => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
@@ -164,6 +228,7 @@ impl RawQuery {
                 match ty {
                     QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative |
                     QueryType::TransformFeedbackPrimitivesWritten => (),
+                    QueryType::Timestamp if ctxt.extensions.gl_ext_disjoint_timer_query => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
@@ -184,10 +249,13 @@ impl RawQuery {
             id
         };
 
+        let generation = context.register_non_shared_object(NonSharedObject::Query(id));
+
         Ok(RawQuery {
             context,
             id,
             ty,
+            generation,
             has_been_used: Cell::new(false),
         })
     }
@@ -214,7 +282,9 @@ impl RawQuery {
             } else if ctxt.extensions.gl_arb_occlusion_query {
                 ctxt.gl.GetQueryObjectuivARB(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 ctxt.gl.GetQueryObjectuivEXT(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
             } else {
@@ -248,7 +318,31 @@ impl RawQuery {
     }
 
     /// Writes the value of the query to a buffer.
+    #[inline]
     pub fn write_u32_to_buffer(&self, target: BufferSlice<'_, u32>) -> Result<(), ToBufferError> {
+        self.write_to_buffer(target, gl::QUERY_RESULT)
+    }
+
+    /// Writes the value of the query to a buffer if it is available, or leaves the buffer
+    /// untouched otherwise.
+    #[inline]
+    pub fn write_u32_to_buffer_no_wait(&self, target: BufferSlice<'_, u32>)
+                                       -> Result<(), ToBufferError>
+    {
+        self.write_to_buffer(target, gl::QUERY_RESULT_NO_WAIT)
+    }
+
+    /// Writes `1` to a buffer if the value of the query is available, or `0` otherwise.
+    #[inline]
+    pub fn write_availability_to_buffer(&self, target: BufferSlice<'_, u32>)
+                                        -> Result<(), ToBufferError>
+    {
+        self.write_to_buffer(target, gl::QUERY_RESULT_AVAILABLE)
+    }
+
+    fn write_to_buffer(&self, target: BufferSlice<'_, u32>, pname: gl::types::GLenum)
+                       -> Result<(), ToBufferError>
+    {
         let mut ctxt = self.context.make_current();
 
         if !(ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_query_buffer_object ||
@@ -260,13 +354,13 @@ impl RawQuery {
         self.deactivate(&mut ctxt);
 
         if !self.has_been_used.get() {
-            panic!();
+            return Err(ToBufferError::QueryNotUsed);
         }
 
         assert!(target.get_offset_bytes() % 4 == 0);
 
         target.prepare_and_bind_for_query(&mut ctxt);
-        unsafe { self.raw_get_u32(&mut ctxt, target.get_offset_bytes() as *mut _); }
+        unsafe { ctxt.gl.GetQueryObjectuiv(self.id, pname, target.get_offset_bytes() as *mut _); }
 
         if let Some(fence) = target.add_fence() {
             fence.insert(&mut ctxt);
@@ -320,10 +414,14 @@ impl RawQuery {
     unsafe fn raw_get_u64(&self, ctxt: &mut CommandContext<'_>, target: *mut gl::types::GLuint64)
                           -> Result<(), ()>
     {
-        if ctxt.version >= &Version(Api::Gl, 3, 3) {
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, target);
             Ok(())
 
+        } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+            ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT, target);
+            Ok(())
+
         } else {
             Err(())
         }
@@ -337,6 +435,29 @@ impl RawQuery {
         self.get_u32() != 0
     }
 
+    /// Records the GPU time in the query once all the previous commands have been executed.
+    /// The query must be a `Timestamp` query.
+    ///
+    /// Contrary to other queries, a timestamp query can be recorded again once its result has
+    /// been retrieved.
+    pub fn query_counter(&self) {
+        assert!(matches!(self.ty, QueryType::Timestamp), "Only timestamp queries can record a counter");
+
+        let ctxt = self.context.make_current();
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
+                ctxt.gl.QueryCounter(self.id, gl::TIMESTAMP);
+            } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+                ctxt.gl.QueryCounterEXT(self.id, gl::TIMESTAMP);
+            } else {
+                unreachable!();
+            }
+        }
+
+        self.has_been_used.set(true);
+    }
+
     /// If the query is active, unactivates it.
     fn deactivate(&self, ctxt: &mut CommandContext<'_>) {
         if ctxt.state.samples_passed_query == self.id {
@@ -368,11 +489,88 @@ impl RawQuery {
             unsafe { raw_end_query(ctxt, gl::TIME_ELAPSED) };
             ctxt.state.time_elapsed_query = 0;
         }
+
+        if let QueryType::PipelineStatistics(statistic) = self.ty {
+            if ctxt.state.pipeline_statistics_queries[statistic.index()] == self.id {
+                unsafe { raw_end_query(ctxt, statistic.to_glenum()) };
+                ctxt.state.pipeline_statistics_queries[statistic.index()] = 0;
+            }
+        }
+    }
+}
+
+/// Keeps the queries whose result has been retrieved, so that they can be recorded again
+/// instead of creating new ones.
+///
+/// Only timestamp queries can be reused this way.
+pub struct QueryPool {
+    context: Rc<Context>,
+    ty: QueryType,
+    free: Vec<RawQuery>,
+}
+
+impl QueryPool {
+    /// Builds a new pool. Returns an error if the backend doesn't support this type of query.
+    pub fn new<F: ?Sized>(facade: &F, ty: QueryType) -> Result<QueryPool, QueryCreationError>
+                          where F: Facade
+    {
+        assert!(matches!(ty, QueryType::Timestamp), "Only timestamp queries can be pooled");
+
+        // creating a first query checks that the type is supported
+        let first = RawQuery::new(facade, ty)?;
+
+        Ok(QueryPool {
+            context: facade.get_context().clone(),
+            ty,
+            free: vec![first],
+        })
+    }
+
+    /// Returns a query of the pool, or creates a new one if all of them are in use.
+    pub fn acquire(&mut self) -> RawQuery {
+        match self.free.pop() {
+            Some(query) => query,
+            None => RawQuery::new(&self.context, self.ty).unwrap(),
+        }
+    }
+
+    /// Gives back a query to the pool.
+    #[inline]
+    pub fn release(&mut self, query: RawQuery) {
+        self.free.push(query);
+    }
+
+    /// Returns true if the GPU has been in a state that made the timer results unreliable since
+    /// the last call, for example because its frequency changed. Also resets this flag.
+    ///
+    /// Only `GL_EXT_disjoint_timer_query` reports this. Always returns false with the other
+    /// backends.
+    pub fn is_disjoint(&self) -> bool {
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query ||
+           !ctxt.extensions.gl_ext_disjoint_timer_query
+        {
+            return false;
+        }
+
+        unsafe {
+            let mut value = 0;
+            ctxt.gl.GetIntegerv(gl::GPU_DISJOINT_EXT, &mut value);
+            value != 0
+        }
     }
 }
 
 impl Drop for RawQuery {
     fn drop(&mut self) {
+        if !self.context.unregister_non_shared_object(NonSharedObject::Query(self.id),
+                                                      self.generation)
+        {
+            // the query belonged to a context that has been replaced with `rebuild`
+            return;
+        }
+
         let mut ctxt = self.context.make_current();
         self.deactivate(&mut ctxt);
 
@@ -543,6 +741,25 @@ impl QueryExt for RawQuery {
                     ctxt.state.transform_feedback_primitives_written_query = self.id;
                 }
             },
+
+            QueryType::PipelineStatistics(statistic) => {
+                let active = ctxt.state.pipeline_statistics_queries[statistic.index()];
+                if active != self.id {
+                    if self.has_been_used.get() {
+                        return Err(DrawError::WrongQueryOperation);
+                    }
+
+                    unsafe {
+                        if active != 0 {
+                            raw_end_query(ctxt, statistic.to_glenum());
+                        }
+                        raw_begin_query(ctxt, statistic.to_glenum(), self.id);
+                    }
+
+                    self.has_been_used.set(true);
+                    ctxt.state.pipeline_statistics_queries[statistic.index()] = self.id;
+                }
+            },
         };
 
         Ok(())
@@ -589,6 +806,14 @@ impl QueryExt for RawQuery {
         }
     }
 
+    #[inline]
+    fn end_pipeline_statistics_query(ctxt: &mut CommandContext<'_>, statistic: PipelineStatistic) {
+        if ctxt.state.pipeline_statistics_queries[statistic.index()] != 0 {
+            ctxt.state.pipeline_statistics_queries[statistic.index()] = 0;
+            unsafe { raw_end_query(ctxt, statistic.to_glenum()); }
+        }
+    }
+
     fn begin_conditional_render(&self, ctxt: &mut CommandContext<'_>, wait: bool, per_region: bool) {
         let new_mode = match (wait, per_region) {
             (true, true) => gl::QUERY_BY_REGION_WAIT,
@@ -749,6 +974,33 @@ macro_rules! impl_helper {
             {
                 self.query.write_u32_to_buffer(target)
             }
+
+            /// Writes the result of the query to a buffer if it is already available, or leaves
+            /// the buffer untouched otherwise.
+            ///
+            /// Contrary to `to_buffer_u32`, the GPU doesn't wait for the result. Combine this with
+            /// `availability_to_buffer_u32` to know whether the value has been written.
+            ///
+            /// This operation is not necessarily supported everywhere.
+            #[inline]
+            pub fn to_buffer_u32_no_wait(&self, target: BufferSlice<'_, u32>)
+                                         -> Result<(), ToBufferError>
+            {
+                self.query.write_u32_to_buffer_no_wait(target)
+            }
+
+            /// Writes `1` to a buffer if the result of the query is available, or `0` otherwise.
+            ///
+            /// This is the equivalent of `is_ready`, except that the value can be consumed by
+            /// shaders or read back later without blocking.
+            ///
+            /// This operation is not necessarily supported everywhere.
+            #[inline]
+            pub fn availability_to_buffer_u32(&self, target: BufferSlice<'_, u32>)
+                                              -> Result<(), ToBufferError>
+            {
+                self.query.write_availability_to_buffer(target)
+            }
         }
 
         impl GlObject for $name {
@@ -786,6 +1038,13 @@ macro_rules! impl_helper {
                 RawQuery::end_transform_feedback_primitives_written_query(ctxt)
             }
 
+            #[inline]
+            fn end_pipeline_statistics_query(ctxt: &mut CommandContext<'_>,
+                                             statistic: PipelineStatistic)
+            {
+                RawQuery::end_pipeline_statistics_query(ctxt, statistic)
+            }
+
             #[inline]
             fn begin_conditional_render(&self, ctxt: &mut CommandContext<'_>, wait: bool, per_region: bool) {
                 self.query.begin_conditional_render(ctxt, wait, per_region)
@@ -924,3 +1183,130 @@ impl TransformFeedbackPrimitivesWrittenQuery {
 }
 
 impl_helper!(TransformFeedbackPrimitivesWrittenQuery, u32, get_u32);
+
+macro_rules! pipeline_statistics_query {
+    ($(#[$attr:meta])* $name:ident, $statistic:ident) => {
+        $(#[$attr])*
+        ///
+        /// Requires OpenGL 4.6 or `GL_ARB_pipeline_statistics_query`.
+        #[derive(Debug)]
+        pub struct $name {
+            query: RawQuery,
+        }
+
+        impl $name {
+            /// Builds a new query.
+            #[inline]
+            pub fn new<F: ?Sized>(facade: &F) -> Result<$name, QueryCreationError>
+                where F: Facade
+            {
+                RawQuery::new(facade, QueryType::PipelineStatistics(PipelineStatistic::$statistic))
+                    .map(|q| $name { query: q })
+            }
+        }
+
+        impl_helper!($name, u32, get_u32);
+    };
+}
+
+pipeline_statistics_query!(
+    /// A query that counts the number of vertices submitted to the primitive assembler.
+    VerticesSubmittedQuery, VerticesSubmitted);
+pipeline_statistics_query!(
+    /// A query that counts the number of primitives submitted to the primitive assembler.
+    PrimitivesSubmittedQuery, PrimitivesSubmitted);
+pipeline_statistics_query!(
+    /// A query that counts the number of times the vertex shader has been invoked.
+    VertexShaderInvocationsQuery, VertexShaderInvocations);
+pipeline_statistics_query!(
+    /// A query that counts the number of patches processed by the tessellation control shader.
+    TessControlShaderPatchesQuery, TessControlShaderPatches);
+pipeline_statistics_query!(
+    /// A query that counts the number of times the tessellation evaluation shader has been
+    /// invoked.
+    TessEvaluationShaderInvocationsQuery, TessEvaluationShaderInvocations);
+pipeline_statistics_query!(
+    /// A query that counts the number of times the geometry shader has been invoked.
+    GeometryShaderInvocationsQuery, GeometryShaderInvocations);
+pipeline_statistics_query!(
+    /// A query that counts the number of primitives emitted by the geometry shader.
+    GeometryShaderPrimitivesEmittedQuery, GeometryShaderPrimitivesEmitted);
+pipeline_statistics_query!(
+    /// A query that counts the number of times the fragment shader has been invoked.
+    FragmentShaderInvocationsQuery, FragmentShaderInvocations);
+pipeline_statistics_query!(
+    /// A query that counts the number of primitives that reached the clipping stage.
+    ClippingInputPrimitivesQuery, ClippingInputPrimitives);
+pipeline_statistics_query!(
+    /// A query that counts the number of primitives that came out of the clipping stage.
+    ///
+    /// Primitives that are clipped against the clip volume can produce more than one
+    /// primitive, and primitives that are entirely outside of it produce none.
+    ClippingOutputPrimitivesQuery, ClippingOutputPrimitives);
+
+/// The pipeline statistics queries to update during a draw command. See
+/// `DrawParameters::pipeline_statistics_queries`.
+///
+/// All the fields default to `None`. Each query that is set counts one statistic of the
+/// pipeline, and several can be active at the same time.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PipelineStatisticsQueries<'a> {
+    /// Counts the vertices submitted to the primitive assembler.
+    pub vertices_submitted: Option<&'a VerticesSubmittedQuery>,
+    /// Counts the primitives submitted to the primitive assembler.
+    pub primitives_submitted: Option<&'a PrimitivesSubmittedQuery>,
+    /// Counts the invocations of the vertex shader.
+    pub vertex_shader_invocations: Option<&'a VertexShaderInvocationsQuery>,
+    /// Counts the patches processed by the tessellation control shader.
+    pub tess_control_shader_patches: Option<&'a TessControlShaderPatchesQuery>,
+    /// Counts the invocations of the tessellation evaluation shader.
+    pub tess_evaluation_shader_invocations: Option<&'a TessEvaluationShaderInvocationsQuery>,
+    /// Counts the invocations of the geometry shader.
+    pub geometry_shader_invocations: Option<&'a GeometryShaderInvocationsQuery>,
+    /// Counts the primitives emitted by the geometry shader.
+    pub geometry_shader_primitives_emitted: Option<&'a GeometryShaderPrimitivesEmittedQuery>,
+    /// Counts the invocations of the fragment shader.
+    pub fragment_shader_invocations: Option<&'a FragmentShaderInvocationsQuery>,
+    /// Counts the primitives that reached the clipping stage.
+    pub clipping_input_primitives: Option<&'a ClippingInputPrimitivesQuery>,
+    /// Counts the primitives that came out of the clipping stage.
+    pub clipping_output_primitives: Option<&'a ClippingOutputPrimitivesQuery>,
+}
+
+impl<'a> PipelineStatisticsQueries<'a> {
+    /// Starts the queries that are set and stops the other ones.
+    pub(crate) fn sync(&self, ctxt: &mut CommandContext<'_>) -> Result<(), DrawError> {
+        fn sync_one<Q>(ctxt: &mut CommandContext<'_>, query: Option<&Q>,
+                       statistic: PipelineStatistic) -> Result<(), DrawError>
+            where Q: QueryExt
+        {
+            match query {
+                Some(query) => query.begin_query(ctxt),
+                None => {
+                    RawQuery::end_pipeline_statistics_query(ctxt, statistic);
+                    Ok(())
+                },
+            }
+        }
+
+        sync_one(ctxt, self.vertices_submitted, PipelineStatistic::VerticesSubmitted)?;
+        sync_one(ctxt, self.primitives_submitted, PipelineStatistic::PrimitivesSubmitted)?;
+        sync_one(ctxt, self.vertex_shader_invocations,
+                 PipelineStatistic::VertexShaderInvocations)?;
+        sync_one(ctxt, self.tess_control_shader_patches,
+                 PipelineStatistic::TessControlShaderPatches)?;
+        sync_one(ctxt, self.tess_evaluation_shader_invocations,
+                 PipelineStatistic::TessEvaluationShaderInvocations)?;
+        sync_one(ctxt, self.geometry_shader_invocations,
+                 PipelineStatistic::GeometryShaderInvocations)?;
+        sync_one(ctxt, self.geometry_shader_primitives_emitted,
+                 PipelineStatistic::GeometryShaderPrimitivesEmitted)?;
+        sync_one(ctxt, self.fragment_shader_invocations,
+                 PipelineStatistic::FragmentShaderInvocations)?;
+        sync_one(ctxt, self.clipping_input_primitives,
+                 PipelineStatistic::ClippingInputPrimitives)?;
+        sync_one(ctxt, self.clipping_output_primitives,
+                 PipelineStatistic::ClippingOutputPrimitives)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+/*!
+A single error type that every error of glium converts into.
+
+Functions that create objects, draw and read back data all return their own error type, which
+is the most precise information available. When this level of detail isn't needed, `Error`
+lets a function return any of them with the `?` operator.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) -> Result<(), glium::Error>
+# where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::Surface;
+
+let texture = glium::Texture2d::empty(&display, 1024, 1024)?;
+let program = glium::Program::from_source(&display, "", "", None)?;
+let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture)?;
+# let _ = (program, &mut framebuffer);
+# Ok(())
+# }
+```
+*/
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::buffer;
+use crate::capture;
+use crate::debug;
+use crate::debug_draw;
+use crate::draw_parameters;
+use crate::framebuffer;
+use crate::index;
+use crate::memory_object;
+use crate::picking;
+use crate::program;
+use crate::semaphore;
+use crate::texture;
+use crate::uniforms;
+use crate::vertex;
+use crate::{BlitError, DrawError, IncompatibleOpenGl, ReadError, SwapBuffersError, UuidError};
+
+#[cfg(feature = "glutin")]
+use crate::backend::glutin::DisplayCreationError;
+
+#[cfg(feature = "sdl2_backend")]
+use crate::backend::sdl2::DisplayCreationError as Sdl2DisplayCreationError;
+
+#[cfg(all(feature = "websys_backend", target_arch = "wasm32"))]
+use crate::backend::websys::DisplayCreationError as WebSysDisplayCreationError;
+
+macro_rules! error_enum {
+    ($($(#[cfg($cfg:meta)])* $name:ident($ty:ty) => $desc:expr, $doc:expr;)+) => {
+        /// Any error that can be returned by glium.
+        ///
+        /// Each variant wraps the error returned by a specific function, which is also
+        /// available through `std::error::Error::source`.
+        #[derive(Debug)]
+        pub enum Error {
+            $(
+                $(#[cfg($cfg)])*
+                #[doc = $doc]
+                $name($ty),
+            )+
+        }
+
+        impl fmt::Display for Error {
+            fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let desc = match *self {
+                    $(
+                        $(#[cfg($cfg)])*
+                        Error::$name(_) => $desc,
+                    )+
+                };
+                fmt.write_str(desc)
+            }
+        }
+
+        impl StdError for Error {
+            fn source(&self) -> Option<&(dyn StdError + 'static)> {
+                match *self {
+                    $(
+                        $(#[cfg($cfg)])*
+                        Error::$name(ref err) => Some(err),
+                    )+
+                }
+            }
+        }
+
+        $(
+            $(#[cfg($cfg)])*
+            impl From<$ty> for Error {
+                #[inline]
+                fn from(err: $ty) -> Error {
+                    Error::$name(err)
+                }
+            }
+        )+
+    };
+}
+
+error_enum! {
+    #[cfg(feature = "glutin")]
+    DisplayCreationError(DisplayCreationError) =>
+        "Error while creating the display",
+        "Error while creating the display.";
+    #[cfg(feature = "sdl2_backend")]
+    Sdl2DisplayCreationError(Sdl2DisplayCreationError) =>
+        "Error while creating the display",
+        "Error while creating the SDL2 display.";
+    #[cfg(all(feature = "websys_backend", target_arch = "wasm32"))]
+    WebSysDisplayCreationError(WebSysDisplayCreationError) =>
+        "Error while creating the display",
+        "Error while creating the WebGL display.";
+    IncompatibleOpenGl(IncompatibleOpenGl) =>
+        "The OpenGL implementation is too old to work with glium",
+        "The OpenGL implementation is too old.";
+    SwapBuffersError(SwapBuffersError) =>
+        "Error while swapping buffers",
+        "Error while swapping buffers.";
+    DrawError(DrawError) =>
+        "Error while drawing",
+        "Error while drawing.";
+    ReadError(ReadError) =>
+        "Error while reading pixels",
+        "Error while reading pixels from a texture or a framebuffer.";
+    BlitError(BlitError) =>
+        "Error while blitting",
+        "Error while blitting the buffers of a surface.";
+    UuidError(UuidError) =>
+        "Error while retrieving the UUID of the device or driver",
+        "Error while retrieving the UUID of the device or driver.";
+    BufferCreationError(buffer::BufferCreationError) =>
+        "Error while creating a buffer",
+        "Error while creating a buffer.";
+    BufferReadError(buffer::ReadError) =>
+        "Error while reading a buffer",
+        "Error while reading the content of a buffer.";
+    BufferCopyError(buffer::CopyError) =>
+        "Error while copying a buffer",
+        "Error while copying between buffers.";
+    VertexBufferCreationError(vertex::BufferCreationError) =>
+        "Error while creating a vertex buffer",
+        "Error while creating a vertex buffer.";
+    TransformFeedbackSessionCreationError(vertex::TransformFeedbackSessionCreationError) =>
+        "Error while creating a transform feedback session",
+        "Error while creating a transform feedback session.";
+    TransformFeedbackPauseError(vertex::TransformFeedbackPauseError) =>
+        "Error while pausing or resuming a transform feedback session",
+        "Error while pausing or resuming a transform feedback session.";
+    IndexBufferCreationError(index::BufferCreationError) =>
+        "Error while creating an index buffer",
+        "Error while creating an index buffer.";
+    ProgramCreationError(program::ProgramCreationError) =>
+        "Error while creating a program",
+        "Error while creating a program.";
+    ProgramChooserCreationError(program::ProgramChooserCreationError) =>
+        "Error while creating a program with `program!`",
+        "Error while creating a program with the `program!` macro.";
+    IncludeError(program::IncludeError) =>
+        "Error while expanding the includes of a shader",
+        "Error while expanding the `#include` directives of a shader.";
+    ComputeError(program::ComputeError) =>
+        "Error while executing a compute shader",
+        "Error while executing a compute shader.";
+    GetBinaryError(program::GetBinaryError) =>
+        "Error while retrieving the binary of a program",
+        "Error while retrieving the binary of a program.";
+    TextureCreationError(texture::TextureCreationError) =>
+        "Error while creating a texture",
+        "Error while creating a texture.";
+    BufferTextureCreationError(texture::buffer_texture::CreationError) =>
+        "Error while creating a buffer texture",
+        "Error while creating a buffer texture.";
+    TextureImportError(texture::TextureImportError) =>
+        "Error while importing a texture",
+        "Error while importing a texture from external memory.";
+    GetFormatError(texture::GetFormatError) =>
+        "Error while retrieving the format of a texture",
+        "Error while retrieving the internal format of a texture.";
+    SamplerObjectCreationError(uniforms::SamplerObjectCreationError) =>
+        "Error while creating a sampler object",
+        "Error while creating a sampler object.";
+    ImageUnitError(uniforms::ImageUnitError) =>
+        "Error while binding a texture to an image unit",
+        "Error while binding a texture to an image unit.";
+    RenderBufferCreationError(framebuffer::RenderBufferCreationError) =>
+        "Error while creating a render buffer",
+        "Error while creating a render buffer.";
+    ValidationError(framebuffer::ValidationError) =>
+        "Error while validating the attachments of a framebuffer",
+        "Error while validating the attachments of a framebuffer.";
+    QueryCreationError(draw_parameters::QueryCreationError) =>
+        "Error while creating a query",
+        "Error while creating a query.";
+    ProfilerError(debug::ProfilerError) =>
+        "Error while ending the frame of a profiler",
+        "Error while ending the frame of a GPU profiler.";
+    MemoryObjectCreationError(memory_object::MemoryObjectCreationError) =>
+        "Error while importing a memory object",
+        "Error while importing a memory object.";
+    SemaphoreCreationError(semaphore::SemaphoreCreationError) =>
+        "Error while importing a semaphore",
+        "Error while importing a semaphore.";
+    PickerCreationError(picking::PickerCreationError) =>
+        "Error while creating a picker",
+        "Error while creating a picker.";
+    PickError(picking::PickError) =>
+        "Error while picking an object",
+        "Error while querying the object under a pixel.";
+    CaptureError(capture::CaptureError) =>
+        "Error while capturing a frame",
+        "Error while capturing a frame with a `FrameRecorder`.";
+    DebugDrawFlushError(debug_draw::FlushError) =>
+        "Error while drawing debug shapes",
+        "Error while drawing the shapes of a debug renderer.";
+}
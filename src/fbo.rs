@@ -64,10 +64,12 @@ use crate::GlObject;
 use crate::TextureExt;
 
 use crate::texture::CubeLayer;
+use crate::texture::Dimensions;
+use crate::texture::InternalFormat;
 use crate::texture::TextureAnyImage;
 use crate::texture::TextureAnyMipmap;
 use crate::texture::TextureKind;
-use crate::framebuffer::RenderBufferAny;
+use crate::framebuffer::{ColorAttachmentInfo, RenderBufferAny};
 
 use crate::gl;
 use crate::context::CommandContext;
@@ -85,6 +87,30 @@ pub fn is_dimensions_mismatch_supported<C: ?Sized>(context: &C) -> bool where C:
     context.get_extensions().gl_arb_framebuffer_object
 }
 
+/// Returns true if the backend supports rendering with multisampling to a regular texture, the
+/// samples being resolved implicitly when the rendering is done.
+///
+/// This is supported on OpenGL ES with the `GL_EXT_multisampled_render_to_texture` extension.
+#[inline]
+pub fn is_implicit_multisampling_supported<C: ?Sized>(context: &C) -> bool
+                                           where C: CapabilitiesSource
+{
+    context.get_extensions().gl_ext_multisampled_render_to_texture
+}
+
+/// Returns true if the backend supports attaching whole texture arrays, cubemaps and 3D textures
+/// to a framebuffer, so that a geometry shader can select the layer to draw to.
+#[inline]
+pub fn is_layered_rendering_supported<C: ?Sized>(context: &C) -> bool
+                                      where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::Gl, 3, 2) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_arb_direct_state_access ||
+    (context.get_extensions().gl_ext_direct_state_access &&
+     context.get_extensions().gl_ext_geometry_shader4)
+}
+
 /// Represents the attachments to use for an OpenGL framebuffer.
 #[derive(Clone)]
 pub enum FramebufferAttachments<'a> {
@@ -125,8 +151,27 @@ impl<'a> RegularAttachment<'a> {
 }
 
 /// Describes a single layered framebuffer attachment.
+///
+/// The mipmap must belong to a texture array, a cubemap, a cubemap array or a 3D texture.
 #[derive(Copy, Clone)]
-pub struct LayeredAttachment<'a>(TextureAnyMipmap<'a>);
+pub struct LayeredAttachment<'a>(pub TextureAnyMipmap<'a>);
+
+impl<'a> LayeredAttachment<'a> {
+    /// Returns the number of layers of the attachment, or `None` if the texture isn't layered.
+    ///
+    /// Each face of a cubemap counts as a layer.
+    pub fn get_layers(&self) -> Option<u32> {
+        match self.0.get_texture().get_texture_type() {
+            Dimensions::Texture1dArray { array_size, .. } |
+            Dimensions::Texture2dArray { array_size, .. } |
+            Dimensions::Texture2dMultisampleArray { array_size, .. } => Some(array_size),
+            Dimensions::Texture3d { .. } => self.0.get_depth(),
+            Dimensions::Cubemap { .. } => Some(6),
+            Dimensions::CubemapArray { array_size, .. } => Some(6 * array_size),
+            _ => None,
+        }
+    }
+}
 
 /// Depth and/or stencil attachment to use.
 #[derive(Copy, Clone)]
@@ -161,13 +206,72 @@ pub struct FramebufferSpecificAttachments<T> {
 impl<'a> FramebufferAttachments<'a> {
     /// After building a `FramebufferAttachments` struct, you must use this function
     /// to "compile" the attachments and make sure that they are valid together.
+    ///
+    /// Attachments of different dimensions are handled with `DimensionsMismatchPolicy::Intersect`.
     #[inline]
     pub fn validate<C: ?Sized>(self, context: &C) -> Result<ValidatedAttachments<'a>, ValidationError>
                        where C: CapabilitiesSource
+    {
+        self.validate_with_policy(context, DimensionsMismatchPolicy::Intersect)
+    }
+
+    /// Same as `validate`, but lets you choose how attachments of different dimensions are
+    /// handled.
+    #[inline]
+    pub fn validate_with_policy<C: ?Sized>(self, context: &C, policy: DimensionsMismatchPolicy)
+                                           -> Result<ValidatedAttachments<'a>, ValidationError>
+                                           where C: CapabilitiesSource
+    {
+        let mut attachments = self.validate_impl(context, None)?;
+        attachments.apply_dimensions_policy(policy)?;
+        Ok(attachments)
+    }
+
+    /// Same as `validate`, but the color attachment is rendered to with the given number of
+    /// samples and resolved implicitly.
+    ///
+    /// The attachments must be regular, and the only color attachment must be a non-multisampled
+    /// two-dimensional texture at index 0. The other attachments must be render buffers that
+    /// have the same number of samples.
+    ///
+    /// See `is_implicit_multisampling_supported`.
+    #[inline]
+    pub fn validate_with_implicit_multisampling<C: ?Sized>(self, context: &C, samples: u32)
+                                                           -> Result<ValidatedAttachments<'a>,
+                                                                     ValidationError>
+                                                           where C: CapabilitiesSource
+    {
+        self.validate_impl(context, Some(samples))
+    }
+
+    /// Same as `validate` for regular attachments, but can be called while the context is
+    /// borrowed by `ctxt`. The formats of the color attachments are reported as unknown.
+    fn validate_in_command_context(self, ctxt: &CommandContext<'_>)
+                                   -> Result<ValidatedAttachments<'a>, ValidationError>
+    {
+        match self {
+            FramebufferAttachments::Regular(a) => {
+                FramebufferAttachments::validate_regular(ctxt, a, None, false)
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn validate_impl<C: ?Sized>(self, context: &C, implicit_samples: Option<u32>)
+                                -> Result<ValidatedAttachments<'a>, ValidationError>
+                                where C: CapabilitiesSource
     {
         match self {
-            FramebufferAttachments::Regular(a) => FramebufferAttachments::validate_regular(context, a),
+            FramebufferAttachments::Regular(a) => {
+                FramebufferAttachments::validate_regular(context, a, implicit_samples, true)
+            },
+            FramebufferAttachments::Layered(_) if implicit_samples.is_some() => {
+                Err(ValidationError::ImplicitMultisamplingNotSupported)
+            },
             FramebufferAttachments::Layered(a) => FramebufferAttachments::validate_layered(context, a),
+            FramebufferAttachments::Empty { .. } if implicit_samples.is_some() => {
+                Err(ValidationError::ImplicitMultisamplingNotSupported)
+            },
 
             FramebufferAttachments::Empty { width, height, layers, samples, fixed_samples } => {
                 if context.get_version() >= &Version(Api::Gl, 4, 3) ||
@@ -191,7 +295,7 @@ impl<'a> FramebufferAttachments<'a> {
 
                     Ok(ValidatedAttachments {
                         raw: RawAttachments {
-                            color: Vec::new(),
+                            color: SmallVec::new(),
                             depth: None,
                             stencil: None,
                             depth_stencil: None,
@@ -200,10 +304,14 @@ impl<'a> FramebufferAttachments<'a> {
                             default_layers: if context.get_version() <= &Version(Api::GlEs, 3, 1) { None } else { Some(layers.unwrap_or(0)) },
                             default_samples: Some(samples.unwrap_or(0)),
                             default_samples_fixed: Some(fixed_samples),
+                            implicit_samples: None,
                         },
+                        color_attachments: SmallVec::new(),
                         dimensions: (width, height),
+                        attachments_dimensions: SmallVec::from_slice(&[(width, height)]),
                         layers,
                         depth_buffer_bits: None,
+                        depth_buffer_format: None,
                         stencil_buffer_bits: None,
                         marker: PhantomData,
                     })
@@ -220,19 +328,29 @@ impl<'a> FramebufferAttachments<'a> {
                            -> Result<ValidatedAttachments<'a>, ValidationError>
                            where C: CapabilitiesSource
     {
-        // TODO: make sure that all attachments are layered
+        if !is_layered_rendering_supported(context) {
+            return Err(ValidationError::LayeredFramebuffersNotSupported);
+        }
 
         macro_rules! handle_tex {
-            ($tex:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
+            ($tex:ident, $dim:ident, $sizes:ident, $layers:ident, $samples:ident, $num_bits:ident) => ({
                 $num_bits = Some($tex.get_texture().get_internal_format()
                                      .map(|f| f.get_total_bits()).ok().unwrap_or(24) as u16);     // TODO: how to handle this?
-                handle_tex!($tex, $dim, $samples)
+                handle_tex!($tex, $dim, $sizes, $layers, $samples)
             });
 
-            ($tex:ident, $dim:ident, $samples:ident) => ({
+            ($tex:ident, $dim:ident, $sizes:ident, $layers:ident, $samples:ident) => ({
                 // TODO: check that internal format is renderable
                 let context = $tex.get_texture().get_context();
 
+                // the number of layers of the framebuffer is the smallest number of layers
+                // among the attachments
+                let tex_layers = match LayeredAttachment(*$tex).get_layers() {
+                    Some(l) => l,
+                    None => return Err(ValidationError::NonLayeredAttachment),
+                };
+                $layers = Some($layers.map_or(tex_layers, |l: u32| cmp::min(l, tex_layers)));
+
                 match &mut $samples {
                     &mut Some(samples) => {
                         if samples != $tex.get_samples().unwrap_or(0) {
@@ -244,6 +362,8 @@ impl<'a> FramebufferAttachments<'a> {
                     }
                 }
 
+                $sizes.push(($tex.get_width(), $tex.get_height().unwrap_or(1)));
+
                 match &mut $dim {
                     &mut Some((ref mut w, ref mut h)) => {
                         let height = $tex.get_height().unwrap_or(1);
@@ -282,7 +402,7 @@ impl<'a> FramebufferAttachments<'a> {
         }
 
         let mut raw_attachments = RawAttachments {
-            color: Vec::with_capacity(colors.len()),
+            color: SmallVec::with_capacity(colors.len()),
             depth: None,
             stencil: None,
             depth_stencil: None,
@@ -291,12 +411,17 @@ impl<'a> FramebufferAttachments<'a> {
             default_layers: None,
             default_samples: None,
             default_samples_fixed: None,
+            implicit_samples: None,
         };
 
+        let mut color_attachments = SmallVec::with_capacity(colors.len());
         let mut dimensions = None;
+        let mut sizes = SmallVec::new();
         let mut depth_bits = None;
+        let mut depth_format = None;
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
+        let mut layers = None;
 
         for &(index, LayeredAttachment(ref attachment)) in colors.iter() {
             if index >= max_color_attachments as u32 {
@@ -305,28 +430,36 @@ impl<'a> FramebufferAttachments<'a> {
                     obtained: index as usize,
                 });
             }
-            raw_attachments.color.push((index, handle_tex!(attachment, dimensions, samples)));
+            raw_attachments.color.push((index, handle_tex!(attachment, dimensions, sizes, layers, samples)));
+            color_attachments.push(ColorAttachmentInfo {
+                index,
+                format: attachment.get_texture().get_internal_format().ok(),
+                samples: attachment.get_samples(),
+            });
         }
 
         match depth_stencil {
             DepthStencilAttachments::None => (),
             DepthStencilAttachments::DepthAttachment(LayeredAttachment(ref d)) => {
-                raw_attachments.depth = Some(handle_tex!(d, dimensions, samples, depth_bits));
+                raw_attachments.depth = Some(handle_tex!(d, dimensions, sizes, layers, samples, depth_bits));
+                depth_format = d.get_texture().get_internal_format().ok();
             },
             DepthStencilAttachments::StencilAttachment(LayeredAttachment(ref s)) => {
-                raw_attachments.stencil = Some(handle_tex!(s, dimensions, samples, stencil_bits));
+                raw_attachments.stencil = Some(handle_tex!(s, dimensions, sizes, layers, samples, stencil_bits));
             },
             DepthStencilAttachments::DepthAndStencilAttachments(LayeredAttachment(ref d),
                                                                  LayeredAttachment(ref s))
             => {
-                raw_attachments.depth = Some(handle_tex!(d, dimensions, samples, depth_bits));
-                raw_attachments.stencil = Some(handle_tex!(s, dimensions, samples, stencil_bits));
+                raw_attachments.depth = Some(handle_tex!(d, dimensions, sizes, layers, samples, depth_bits));
+                raw_attachments.stencil = Some(handle_tex!(s, dimensions, sizes, layers, samples, stencil_bits));
+                depth_format = d.get_texture().get_internal_format().ok();
             },
             DepthStencilAttachments::DepthStencilAttachment(LayeredAttachment(ref ds)) => {
                 let depth_stencil_bits = ds.get_texture().get_depth_stencil_bits();
                 depth_bits = Some(depth_stencil_bits.0);
+                depth_format = ds.get_texture().get_internal_format().ok();
                 stencil_bits = Some(depth_stencil_bits.1);
-                raw_attachments.depth_stencil = Some(handle_tex!(ds, dimensions, samples));
+                raw_attachments.depth_stencil = Some(handle_tex!(ds, dimensions, sizes, layers, samples));
             },
         }
 
@@ -342,41 +475,58 @@ impl<'a> FramebufferAttachments<'a> {
 
         Ok(ValidatedAttachments {
             raw: raw_attachments,
+            color_attachments,
             dimensions,
-            layers: None,       // FIXME: count layers
+            attachments_dimensions: sizes,
+            layers,
             depth_buffer_bits: depth_bits,
+            depth_buffer_format: depth_format,
             stencil_buffer_bits: stencil_bits,
             marker: PhantomData,
         })
     }
 
     fn validate_regular<C: ?Sized>(context: &C, FramebufferSpecificAttachments { colors, depth_stencil }:
-                        FramebufferSpecificAttachments<RegularAttachment<'a>>)
+                        FramebufferSpecificAttachments<RegularAttachment<'a>>,
+                        implicit_samples: Option<u32>, query_formats: bool)
                         -> Result<ValidatedAttachments<'a>, ValidationError>
                         where C: CapabilitiesSource
     {
+        if implicit_samples.is_some() {
+            check_implicit_multisampling(context, &colors, &depth_stencil)?;
+        }
+
         macro_rules! handle_tex {
-            ($tex:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
+            ($tex:ident, $dim:ident, $sizes:ident, $samples:ident, $num_bits:ident) => ({
                 $num_bits = Some($tex.get_texture().get_internal_format()
                                      .map(|f| f.get_total_bits()).ok().unwrap_or(24) as u16);     // TODO: how to handle this?
-                handle_tex!($tex, $dim, $samples)
+                handle_tex!($tex, $dim, $sizes, $samples)
             });
 
-            ($tex:ident, $dim:ident, $samples:ident) => ({
+            ($tex:ident, $dim:ident, $sizes:ident, $samples:ident) => ({
                 // TODO: check that internal format is renderable
                 let context = $tex.get_texture().get_context();
 
+                // with implicit multisampling, the samples of the texture are the ones of
+                // the framebuffer
+                let tex_samples = match (implicit_samples, $tex.get_samples()) {
+                    (Some(samples), None) => samples,
+                    (_, samples) => samples.unwrap_or(0),
+                };
+
                 match &mut $samples {
                     &mut Some(samples) => {
-                        if samples != $tex.get_samples().unwrap_or(0) {
+                        if samples != tex_samples {
                             return Err(ValidationError::SamplesCountMismatch);
                         }
                     },
                     s @ &mut None => {
-                        *s = Some($tex.get_samples().unwrap_or(0));
+                        *s = Some(tex_samples);
                     }
                 }
 
+                $sizes.push(($tex.get_width(), $tex.get_height().unwrap_or(1)));
+
                 match &mut $dim {
                     &mut Some((ref mut w, ref mut h)) => {
                         let height = $tex.get_height().unwrap_or(1);
@@ -407,12 +557,12 @@ impl<'a> FramebufferAttachments<'a> {
         }
 
         macro_rules! handle_rb {
-            ($rb:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
+            ($rb:ident, $dim:ident, $sizes:ident, $samples:ident, $num_bits:ident) => ({
                 $num_bits = Some(24);       // FIXME: totally arbitrary
-                handle_rb!($rb, $dim, $samples)
+                handle_rb!($rb, $dim, $sizes, $samples)
             });
 
-            ($rb:ident, $dim:ident, $samples:ident) => ({
+            ($rb:ident, $dim:ident, $sizes:ident, $samples:ident) => ({
                 // TODO: check that internal format is renderable
                 let context = $rb.get_context();
                 let dimensions = $rb.get_dimensions();
@@ -428,6 +578,8 @@ impl<'a> FramebufferAttachments<'a> {
                     }
                 }
 
+                $sizes.push(dimensions);
+
                 match &mut $dim {
                     &mut Some((ref mut w, ref mut h)) => {
                         if *w != dimensions.0 || *h != dimensions.1 {
@@ -468,7 +620,7 @@ impl<'a> FramebufferAttachments<'a> {
         }
 
         let mut raw_attachments = RawAttachments {
-            color: Vec::with_capacity(colors.len()),
+            color: SmallVec::with_capacity(colors.len()),
             depth: None,
             stencil: None,
             depth_stencil: None,
@@ -477,10 +629,14 @@ impl<'a> FramebufferAttachments<'a> {
             default_layers: None,
             default_samples: None,
             default_samples_fixed: None,
+            implicit_samples,
         };
 
+        let mut color_attachments = SmallVec::with_capacity(colors.len());
         let mut dimensions = None;
+        let mut sizes = SmallVec::new();
         let mut depth_bits = None;
+        let mut depth_format = None;
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
 
@@ -491,20 +647,38 @@ impl<'a> FramebufferAttachments<'a> {
                     obtained: index as usize,
                 });
             }
-            raw_attachments.color.push((index, handle_atch!(attachment, dimensions, samples)));
+            raw_attachments.color.push((index, handle_atch!(attachment, dimensions, sizes, samples)));
+            color_attachments.push(match *attachment {
+                RegularAttachment::Texture(ref tex) => ColorAttachmentInfo {
+                    index,
+                    format: if query_formats {
+                        tex.get_texture().get_internal_format().ok()
+                    } else {
+                        None
+                    },
+                    samples: tex.get_samples(),
+                },
+                RegularAttachment::RenderBuffer(rb) => ColorAttachmentInfo {
+                    index,
+                    format: None,
+                    samples: rb.get_samples(),
+                },
+            });
         }
 
         match depth_stencil {
             DepthStencilAttachments::None => (),
             DepthStencilAttachments::DepthAttachment(ref d) => {
-                raw_attachments.depth = Some(handle_atch!(d, dimensions, samples, depth_bits));
+                raw_attachments.depth = Some(handle_atch!(d, dimensions, sizes, samples, depth_bits));
+                depth_format = get_depth_format(d);
             },
             DepthStencilAttachments::StencilAttachment(ref s) => {
-                raw_attachments.stencil = Some(handle_atch!(s, dimensions, samples, stencil_bits));
+                raw_attachments.stencil = Some(handle_atch!(s, dimensions, sizes, samples, stencil_bits));
             },
             DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
-                raw_attachments.depth = Some(handle_atch!(d, dimensions, samples, depth_bits));
-                raw_attachments.stencil = Some(handle_atch!(s, dimensions, samples, stencil_bits));
+                raw_attachments.depth = Some(handle_atch!(d, dimensions, sizes, samples, depth_bits));
+                raw_attachments.stencil = Some(handle_atch!(s, dimensions, sizes, samples, stencil_bits));
+                depth_format = get_depth_format(d);
             },
             DepthStencilAttachments::DepthStencilAttachment(ref ds) => {
                 let depth_stencil_bits = match ds {
@@ -515,7 +689,8 @@ impl<'a> FramebufferAttachments<'a> {
                 };
                 depth_bits = Some(depth_stencil_bits.0);
                 stencil_bits = Some(depth_stencil_bits.1);
-                raw_attachments.depth_stencil = Some(handle_atch!(ds, dimensions, samples));
+                depth_format = get_depth_format(ds);
+                raw_attachments.depth_stencil = Some(handle_atch!(ds, dimensions, sizes, samples));
             },
         }
 
@@ -531,22 +706,78 @@ impl<'a> FramebufferAttachments<'a> {
 
         Ok(ValidatedAttachments {
             raw: raw_attachments,
+            color_attachments,
             dimensions,
+            attachments_dimensions: sizes,
             layers: None,
             depth_buffer_bits: depth_bits,
+            depth_buffer_format: depth_format,
             stencil_buffer_bits: stencil_bits,
             marker: PhantomData,
         })
     }
 }
 
+/// Returns the format of a depth or depth-stencil attachment, if it is known.
+fn get_depth_format(attachment: &RegularAttachment<'_>) -> Option<InternalFormat> {
+    match *attachment {
+        RegularAttachment::Texture(ref tex) => tex.get_texture().get_internal_format().ok(),
+        RegularAttachment::RenderBuffer(rb) => rb.get_depth_format(),
+    }
+}
+
+/// Checks that the attachments can be used with `validate_with_implicit_multisampling`.
+fn check_implicit_multisampling<C: ?Sized>(context: &C, colors: &[(u32, RegularAttachment<'_>)],
+                                           depth_stencil: &DepthStencilAttachments<RegularAttachment<'_>>)
+                                           -> Result<(), ValidationError>
+                                           where C: CapabilitiesSource
+{
+    if !is_implicit_multisampling_supported(context) {
+        return Err(ValidationError::ImplicitMultisamplingNotSupported);
+    }
+
+    // the extension only allows a single color attachment
+    match colors {
+        &[(0, RegularAttachment::Texture(ref tex))] => {
+            if tex.get_texture().get_bind_point() != gl::TEXTURE_2D || tex.get_level() != 0 ||
+               tex.get_samples().is_some()
+            {
+                return Err(ValidationError::ImplicitMultisamplingNotSupported);
+            }
+        },
+        _ => return Err(ValidationError::ImplicitMultisamplingNotSupported),
+    }
+
+    // depth and stencil textures can't be implicitly multisampled, and non-multisampled textures
+    // would be mistaken for implicitly multisampled ones during the validation
+    let is_texture = |a: &RegularAttachment<'_>| matches!(a, &RegularAttachment::Texture(_));
+    let has_texture = match depth_stencil {
+        DepthStencilAttachments::None => false,
+        DepthStencilAttachments::DepthAttachment(ref d) => is_texture(d),
+        DepthStencilAttachments::StencilAttachment(ref s) => is_texture(s),
+        DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
+            is_texture(d) || is_texture(s)
+        },
+        DepthStencilAttachments::DepthStencilAttachment(ref ds) => is_texture(ds),
+    };
+
+    if has_texture {
+        return Err(ValidationError::ImplicitMultisamplingNotSupported);
+    }
+
+    Ok(())
+}
+
 /// Represents attachments that have been validated and are usable.
 #[derive(Clone)]
 pub struct ValidatedAttachments<'a> {
     raw: RawAttachments,
+    color_attachments: SmallVec<[ColorAttachmentInfo; 5]>,
     dimensions: (u32, u32),
+    attachments_dimensions: SmallVec<[(u32, u32); 6]>,
     layers: Option<u32>,
     depth_buffer_bits: Option<u16>,
+    depth_buffer_format: Option<InternalFormat>,
     stencil_buffer_bits: Option<u16>,
     marker: PhantomData<&'a ()>,
 }
@@ -558,12 +789,61 @@ impl<'a> ValidatedAttachments<'a> {
         self.layers.is_some()
     }
 
+    /// Returns the number of layers of the framebuffer, or `None` if it isn't layered.
+    #[inline]
+    pub fn get_layers(&self) -> Option<u32> {
+        self.layers
+    }
+
     /// Returns the dimensions that the framebuffer will have if you use these attachments.
     #[inline]
     pub fn get_dimensions(&self) -> (u32, u32) {
         self.dimensions
     }
 
+    /// Returns the dimensions of each attachment, in the order in which they were given.
+    #[inline]
+    pub fn get_attachments_dimensions(&self) -> &[(u32, u32)] {
+        &self.attachments_dimensions
+    }
+
+    /// Recomputes the dimensions of the framebuffer according to the policy.
+    ///
+    /// Returns an error and leaves the dimensions untouched if the attachments don't satisfy
+    /// the policy.
+    pub fn apply_dimensions_policy(&mut self, policy: DimensionsMismatchPolicy)
+                                   -> Result<(), ValidationError>
+    {
+        // the per-axis minimum, which is what OpenGL renders to
+        let intersection = self.attachments_dimensions.iter()
+                               .fold((u32::MAX, u32::MAX), |(w, h), &(aw, ah)| {
+                                   (cmp::min(w, aw), cmp::min(h, ah))
+                               });
+
+        let mismatch = self.attachments_dimensions.iter().any(|&d| d != intersection);
+
+        match policy {
+            DimensionsMismatchPolicy::Error if mismatch => {
+                return Err(ValidationError::DimensionsMismatch);
+            },
+            DimensionsMismatchPolicy::Smallest => {
+                if !self.attachments_dimensions.iter().any(|&d| d == intersection) {
+                    return Err(ValidationError::DimensionsMismatch);
+                }
+            },
+            _ => ()
+        }
+
+        self.dimensions = intersection;
+        Ok(())
+    }
+
+    /// Returns the list of color attachments.
+    #[inline]
+    pub fn get_color_attachments(&self) -> &[ColorAttachmentInfo] {
+        &self.color_attachments
+    }
+
     /// Returns the number of bits of precision of the depth buffer, or `None` if there is no
     /// depth buffer. Also works for depth-stencil buffers.
     #[inline]
@@ -571,6 +851,13 @@ impl<'a> ValidatedAttachments<'a> {
         self.depth_buffer_bits
     }
 
+    /// Returns the format of the depth buffer, or `None` if there is no depth buffer or if its
+    /// format is unknown. Also works for depth-stencil buffers.
+    #[inline]
+    pub fn get_depth_buffer_format(&self) -> Option<InternalFormat> {
+        self.depth_buffer_format
+    }
+
     /// Returns the number of bits of precision of the stencil buffer, or `None` if there is no
     /// stencil buffer. Also works for depth-stencil buffers.
     #[inline]
@@ -579,6 +866,31 @@ impl<'a> ValidatedAttachments<'a> {
     }
 }
 
+/// How to handle attachments whose dimensions are not all the same.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DimensionsMismatchPolicy {
+    /// Attachments must all have the same dimensions.
+    Error,
+
+    /// The framebuffer covers the intersection of all the attachments, in other words the
+    /// minimum width and the minimum height. This is the default.
+    Intersect,
+
+    /// One of the attachments must be the smallest both in width and in height, and the
+    /// framebuffer has its dimensions.
+    ///
+    /// Contrary to `Intersect`, this guarantees that the framebuffer covers the whole of at
+    /// least one attachment.
+    Smallest,
+}
+
+impl Default for DimensionsMismatchPolicy {
+    #[inline]
+    fn default() -> DimensionsMismatchPolicy {
+        DimensionsMismatchPolicy::Intersect
+    }
+}
+
 /// An error that can happen while validating attachments.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ValidationError {
@@ -594,9 +906,24 @@ pub enum ValidationError {
     /// Only very old versions don't.
     DimensionsMismatchNotSupported,
 
+    /// The dimensions of the attachments don't satisfy the requested
+    /// `DimensionsMismatchPolicy`.
+    DimensionsMismatch,
+
     /// All attachments must have the same number of samples.
     SamplesCountMismatch,
 
+    /// Implicit multisampling is not supported by the backend, or the attachments can't be
+    /// implicitly multisampled.
+    ImplicitMultisamplingNotSupported,
+
+    /// Layered framebuffers are not supported by the backend.
+    LayeredFramebuffersNotSupported,
+
+    /// One of the attachments of a layered framebuffer is not a texture array, a cubemap or a
+    /// 3D texture.
+    NonLayeredAttachment,
+
     /// Backends only support a certain number of color attachments.
     TooManyColorAttachments {
         /// Maximum number of attachments.
@@ -616,8 +943,16 @@ impl fmt::Display for ValidationError {
                 "The requested characteristics of an empty framebuffer object are out of range",
             DimensionsMismatchNotSupported =>
                 "The backend doesn't support attachments with various dimensions",
+            DimensionsMismatch =>
+                "The dimensions of the attachments don't satisfy the requested policy",
             SamplesCountMismatch =>
                 "All attachments must have the same number of samples",
+            ImplicitMultisamplingNotSupported =>
+                "Implicit multisampling is not supported by the backend or by the attachments",
+            LayeredFramebuffersNotSupported =>
+                "Layered framebuffers are not supported by the backend",
+            NonLayeredAttachment =>
+                "An attachment of a layered framebuffer is not a texture array, cubemap or 3D texture",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
         };
@@ -638,7 +973,7 @@ impl Error for ValidationError {}
 #[derive(Hash, Clone, Eq, PartialEq)]
 struct RawAttachments {
     // for each frag output the location, the attachment to use
-    color: Vec<(u32, RawAttachment)>,
+    color: SmallVec<[(u32, RawAttachment); 5]>,
     depth: Option<RawAttachment>,
     stencil: Option<RawAttachment>,
     depth_stencil: Option<RawAttachment>,
@@ -649,6 +984,10 @@ struct RawAttachments {
     default_layers: Option<u32>,
     default_samples: Option<u32>,
     default_samples_fixed: Option<bool>,
+
+    // if `Some`, the color attachments are attached with `glFramebufferTexture2DMultisampleEXT`
+    // and this number of samples
+    implicit_samples: Option<u32>,
 }
 
 /// Single attachment of `RawAttachments`.
@@ -817,7 +1156,7 @@ impl FramebuffersContainer {
         if let Some(attachments) = attachments {
             FramebuffersContainer::get_framebuffer(ctxt, attachments)
         } else {
-            0
+            ctxt.default_framebuffer
         }
     }
 
@@ -828,7 +1167,15 @@ impl FramebuffersContainer {
     pub fn bind_default_framebuffer_for_reading(ctxt: &mut CommandContext<'_>,
                                                 read_buffer: gl::types::GLenum)
     {
-        unsafe { bind_framebuffer(ctxt, 0, false, true) };
+        // a framebuffer object used as the default framebuffer has no front or back buffer
+        let read_buffer = if ctxt.default_framebuffer != 0 {
+            gl::COLOR_ATTACHMENT0
+        } else {
+            read_buffer
+        };
+
+        let default_framebuffer = ctxt.default_framebuffer;
+        unsafe { bind_framebuffer(ctxt, default_framebuffer, false, true) };
         unsafe { ctxt.gl.ReadBuffer(read_buffer) };     // TODO: cache
     }
 
@@ -852,13 +1199,30 @@ impl FramebuffersContainer {
         let attachments = FramebufferAttachments::Regular(FramebufferSpecificAttachments {
             colors: { let mut v = SmallVec::new(); v.push((0, *attachment)); v },
             depth_stencil: DepthStencilAttachments::None,
-        }).validate(ctxt).unwrap();
+        }).validate_in_command_context(ctxt).unwrap();
 
         let framebuffer = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(&attachments));
         bind_framebuffer(ctxt, framebuffer, false, true);
         ctxt.gl.ReadBuffer(gl::COLOR_ATTACHMENT0);     // TODO: cache
     }
 
+    /// Binds the framebuffer of a list of attachments to `GL_READ_FRAMEBUFFER` or
+    /// `GL_FRAMEBUFFER`, and selects the color attachment with the given index as the source of
+    /// `glReadPixels`.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub unsafe fn bind_attachments_for_reading(ctxt: &mut CommandContext<'_>,
+                                               attachments: &ValidatedAttachments<'_>,
+                                               index: u32)
+    {
+        let framebuffer = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(attachments));
+        bind_framebuffer(ctxt, framebuffer, false, true);
+        ctxt.gl.ReadBuffer(gl::COLOR_ATTACHMENT0 + index);     // TODO: cache
+    }
+
     /// Calls `glClearBuffer` on a framebuffer that contains the attachment.
     ///
     /// # Panic
@@ -880,7 +1244,7 @@ impl FramebuffersContainer {
         let fb = FramebufferAttachments::Regular(FramebufferSpecificAttachments {
             colors: { let mut v = SmallVec::new(); v.push((0, *attachment)); v },
             depth_stencil: DepthStencilAttachments::None,
-        }).validate(ctxt).unwrap();
+        }).validate_in_command_context(ctxt).unwrap();
         let fb = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(&fb));
 
         // TODO: use DSA if supported
@@ -1065,7 +1429,12 @@ impl FrameBufferObject {
                 panic!("Trying to attach a color buffer to slot {}, but the hardware only supports {} bind points",
                     attachment_pos, ctxt.capabilities.max_color_attachments);
             }
-            unsafe { attach(&mut ctxt, gl::COLOR_ATTACHMENT0 + attachment_pos as u32, id, atchmnt) };
+            if let Some(samples) = attachments.implicit_samples {
+                unsafe { attach_implicit_multisample(&mut ctxt, gl::COLOR_ATTACHMENT0 + attachment_pos as u32,
+                                                     id, atchmnt, samples) };
+            } else {
+                unsafe { attach(&mut ctxt, gl::COLOR_ATTACHMENT0 + attachment_pos as u32, id, atchmnt) };
+            }
 
             while raw_attachments.len() <= pos_in_drawbuffers as usize { raw_attachments.push(gl::NONE); }
             raw_attachments[pos_in_drawbuffers as usize] = gl::COLOR_ATTACHMENT0 + attachment_pos as u32;
@@ -1244,6 +1613,25 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext<'_>, fbo_id: gl::types:
 ///
 /// All parameters must be valid.
 ///
+/// Attaches a two-dimensional texture so that it is rendered to with multisampling and resolved
+/// implicitly.
+unsafe fn attach_implicit_multisample(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
+                                      id: gl::types::GLuint, attachment: RawAttachment,
+                                      samples: u32)
+{
+    match attachment {
+        RawAttachment::Texture { texture, level, bind_point: gl::TEXTURE_2D, .. } => {
+            debug_assert!(ctxt.extensions.gl_ext_multisampled_render_to_texture);
+            bind_framebuffer(ctxt, id, true, true);
+            ctxt.gl.FramebufferTexture2DMultisampleEXT(gl::FRAMEBUFFER, slot, gl::TEXTURE_2D,
+                                                       texture, level as gl::types::GLint,
+                                                       samples as gl::types::GLsizei);
+        },
+        // enforced by `check_implicit_multisampling`
+        _ => unreachable!()
+    }
+}
+
 unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
                  id: gl::types::GLuint, attachment: RawAttachment)
 {
@@ -1413,7 +1801,9 @@ unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
@@ -1468,7 +1858,9 @@ unsafe fn attach(ctxt: &mut CommandContext<'_>, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
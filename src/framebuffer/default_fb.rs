@@ -5,9 +5,10 @@ use std::rc::Rc;
 use crate::backend::Facade;
 use crate::context::Context;
 
-use crate::{DrawParameters, BlitMask};
+use crate::{DrawParameters, BlitMask, BlitError};
 use crate::FboAttachments;
 use crate::Rect;
+use crate::ReadError;
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
@@ -15,13 +16,16 @@ use crate::ops;
 use crate::uniforms;
 
 use crate::{Program, Surface};
+use crate::program;
 use crate::DrawError;
 
 use crate::fbo;
+use crate::gl;
 use crate::index;
 use crate::vertex;
-use crate::framebuffer::{SimpleFrameBuffer, MultiOutputFrameBuffer};
+use crate::framebuffer::{ColorAttachmentInfo, SimpleFrameBuffer, MultiOutputFrameBuffer};
 use crate::uniforms::MagnifySamplerFilter;
+use crate::texture::pixel_buffer::PendingRead;
 
 /// One of the color attachments on the default framebuffer.
 #[derive(Copy, Clone, Debug)]
@@ -75,11 +79,41 @@ impl Surface for DefaultFramebuffer {
         self.context.capabilities().stencil_bits
     }
 
-    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
-                         index_buffer: I, program: &Program, uniforms: &U,
+    fn get_color_attachments(&self) -> Vec<ColorAttachmentInfo> {
+        vec![ColorAttachmentInfo {
+            index: 0,
+            format: None,
+            samples: self.context.capabilities().samples,
+        }]
+    }
+
+    fn read_to_pixel_buffer(&self, rect: &Rect) -> Result<PendingRead<(u8, u8, u8, u8)>, ReadError> {
+        let read_buffer = match self.attachment {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+        };
+
+        let source = ops::Source::DefaultFramebuffer(read_buffer, self.get_dimensions());
+        PendingRead::new(&self.context, source, rect)
+    }
+
+    #[inline]
+    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &DrawParameters<'_>)
+        -> Result<(), DrawError>
+        where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
+        V: vertex::MultiVerticesSource<'b>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'a, 'b, V, I, U, P>(&mut self, vertex_buffer: V,
+                         index_buffer: I, program: &P, uniforms: &U,
                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
-                         V: vertex::MultiVerticesSource<'b>
+                         V: vertex::MultiVerticesSource<'b>, P: program::DrawProgram + ?Sized
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -101,17 +135,36 @@ impl Surface for DefaultFramebuffer {
         }
 
         // TODO: wrong attachment
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
+        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program.as_draw_program(),
                   uniforms, draw_parameters, self.get_dimensions())
     }
 
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[vertex::VerticesSource<'_>],
+                indices: index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn uniforms::DynUniforms, draw_parameters: &DrawParameters<'_>)
+                -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized
     {
         target.blit_from_frame(source_rect, target_rect, filter)
     }
 
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        crate::check_blit_buffers(self, target, filter, mask)?;
+        target.blit_buffers_from_frame(source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
     #[inline]
     fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
         ops::blit(&self.context, None, self.get_attachments(),
@@ -59,6 +59,14 @@ Some restrictions apply when you use framebuffers:
    framebuffer that you are using. This is not enforced by glium as it depends on your shader's
    source code.
 
+# Implicit multisampling
+
+On OpenGL ES, the `GL_EXT_multisampled_render_to_texture` extension allows rendering with
+multisampling directly to a regular texture. The samples are resolved when the rendering is
+done, without allocating a multisample texture. Use
+`SimpleFrameBuffer::with_implicit_multisampling` and check whether this is supported with
+`is_implicit_multisampling_supported`.
+
 # Empty framebuffers
 
 Modern OpenGL implementations support empty framebuffers. This is handled by glium with the
@@ -68,22 +76,30 @@ You can check whether they are supported by calling `EmptyFrameBuffer::is_suppor
 
 # Layered framebuffers
 
-Not yet supported
+A `LayeredFrameBuffer` attaches whole texture arrays, cubemaps, cubemap arrays or 3D textures
+instead of a single image. A geometry shader chooses the layer that each primitive is drawn to
+by writing to `gl_Layer`. For cubemaps, layers `0` to `5` correspond to the faces in the order
+of `CubeLayer`.
+
+Layered framebuffers require OpenGL 3.2 or OpenGL ES 3.2. You can check whether they are
+supported with `is_layered_rendering_supported`.
 
 */
 use std::rc::Rc;
 use smallvec::SmallVec;
 
-use crate::texture::TextureAnyImage;
+use crate::texture::{CubeLayer, InternalFormat, TextureAnyImage, TextureAnyMipmap};
+use crate::texture::pixel_buffer::PendingRead;
 
 use crate::backend::Facade;
 use crate::context::Context;
-use crate::{CapabilitiesSource, BlitMask};
+use crate::{CapabilitiesSource, BlitMask, BlitError};
 use crate::version::Version;
 use crate::version::Api;
 
 use crate::FboAttachments;
 use crate::Rect;
+use crate::ReadError;
 use crate::BlitTarget;
 use crate::ContextExt;
 use crate::ToGlEnum;
@@ -91,6 +107,7 @@ use crate::ops;
 use crate::uniforms;
 
 use crate::{Program, Surface};
+use crate::program;
 use crate::DrawError;
 
 use crate::fbo;
@@ -99,17 +116,42 @@ pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
+pub use self::transient_pool::{Transient, TransientPool};
 pub use crate::fbo::is_dimensions_mismatch_supported;
+pub use crate::fbo::is_implicit_multisampling_supported;
+pub use crate::fbo::is_layered_rendering_supported;
 pub use crate::fbo::ValidationError;
+pub use crate::fbo::DimensionsMismatchPolicy;
 use crate::uniforms::MagnifySamplerFilter;
 
 mod default_fb;
 mod render_buffer;
+mod transient_pool;
+
+/// Describes one of the color attachments of a `Surface`.
+///
+/// Returned by `Surface::get_color_attachments`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ColorAttachmentInfo {
+    /// Index of the attachment, which is the location of the fragment shader output that
+    /// writes to it.
+    pub index: u32,
+
+    /// Internal format of the attachment.
+    ///
+    /// `None` if the format couldn't be determined. This is always the case for render buffers
+    /// and for the default framebuffer.
+    pub format: Option<InternalFormat>,
+
+    /// Number of samples, or `None` if the attachment isn't multisampled.
+    pub samples: Option<u32>,
+}
 
 /// A framebuffer which has only one color attachment.
 pub struct SimpleFrameBuffer<'a> {
     context: Rc<Context>,
     attachments: fbo::ValidatedAttachments<'a>,
+    srgb_conversion: Option<bool>,
 }
 
 impl<'a> SimpleFrameBuffer<'a> {
@@ -119,7 +161,7 @@ impl<'a> SimpleFrameBuffer<'a> {
     pub fn new<F: ?Sized, C>(facade: &F, color: C) -> Result<SimpleFrameBuffer<'a>, ValidationError>
                      where C: ToColorAttachment<'a>, F: Facade
     {
-        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None)
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth
@@ -131,7 +173,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                             D: ToDepthAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
-                                    Some(depth.to_depth_attachment()), None, None)
+                                    Some(depth.to_depth_attachment()), None, None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a depth buffer and no color attachment
@@ -141,7 +183,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                             -> Result<SimpleFrameBuffer<'a>, ValidationError>
         where D: ToDepthAttachment<'a>, F: Facade
     {
-        SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()), None, None)
+        SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()), None, None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment, a depth
@@ -157,7 +199,7 @@ impl<'a> SimpleFrameBuffer<'a> {
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
                                     Some(depth.to_depth_attachment()),
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a depth buffer and a stencil buffer,
@@ -169,7 +211,7 @@ impl<'a> SimpleFrameBuffer<'a> {
               S: ToStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()),
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a stencil
@@ -181,7 +223,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                               F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None,
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a stencil buffer and no color attachment
@@ -192,7 +234,7 @@ impl<'a> SimpleFrameBuffer<'a> {
         where S: ToStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, None, Some(stencil.to_stencil_attachment()),
-                                    None)
+                                    None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth-stencil buffer.
@@ -203,7 +245,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                                     D: ToDepthStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None,
-                                    Some(depthstencil.to_depth_stencil_attachment()))
+                                    Some(depthstencil.to_depth_stencil_attachment()), None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a depth-stencil buffer and no color attachment.
@@ -213,13 +255,96 @@ impl<'a> SimpleFrameBuffer<'a> {
         where D: ToDepthStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, None, None,
-                                    Some(depthstencil.to_depth_stencil_attachment()))
+                                    Some(depthstencil.to_depth_stencil_attachment()), None)
     }
 
+    /// Creates a `SimpleFrameBuffer` that renders with multisampling to a regular texture.
+    ///
+    /// The samples are resolved implicitly into the texture, without having to allocate a
+    /// multisample texture and blit it. This is the most efficient way to do multisampling on
+    /// tile-based GPUs.
+    ///
+    /// The texture must be a non-multisampled two-dimensional texture. Use
+    /// `is_implicit_multisampling_supported` to check whether this is supported.
+    #[inline]
+    pub fn with_implicit_multisampling<F: ?Sized, C>(facade: &F, color: C, samples: u32)
+                                                     -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                                     where C: ToColorAttachment<'a>, F: Facade
+    {
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None,
+                                    Some(samples))
+    }
+
+    /// Same as `with_implicit_multisampling`, but with a depth buffer.
+    ///
+    /// The depth buffer must be a render buffer with the same number of samples, for example
+    /// created with `DepthRenderBuffer::new_multisample`.
+    #[inline]
+    pub fn with_implicit_multisampling_and_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C,
+                                                                         depth: D, samples: u32)
+                                                                         -> Result<SimpleFrameBuffer<'a>,
+                                                                                   ValidationError>
+                                                                         where C: ToColorAttachment<'a>,
+                                                                               D: ToDepthAttachment<'a>,
+                                                                               F: Facade
+    {
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
+                                    Some(depth.to_depth_attachment()), None, None, Some(samples))
+    }
+
+    /// Applies a policy for handling attachments of different dimensions.
+    ///
+    /// By default, the framebuffer covers the intersection of all its attachments. After
+    /// calling this function, `get_dimensions` returns the dimensions chosen by the policy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>, color: glium::texture::Texture2d,
+    /// #               depth: glium::framebuffer::DepthRenderBuffer)
+    /// #     where T: SurfaceTypeTrait + ResizeableSurface {
+    /// use glium::framebuffer::{DimensionsMismatchPolicy, SimpleFrameBuffer};
+    ///
+    /// let framebuffer = SimpleFrameBuffer::with_depth_buffer(&display, &color, &depth).unwrap()
+    ///     .with_dimensions_policy(DimensionsMismatchPolicy::Error)
+    ///     .expect("the color and depth attachments must have the same dimensions");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with_dimensions_policy(mut self, policy: DimensionsMismatchPolicy)
+                                  -> Result<SimpleFrameBuffer<'a>, ValidationError>
+    {
+        self.attachments.apply_dimensions_policy(policy)?;
+        Ok(self)
+    }
+
+    /// Returns the dimensions of each attachment.
+    #[inline]
+    pub fn get_attachments_dimensions(&self) -> &[(u32, u32)] {
+        self.attachments.get_attachments_dimensions()
+    }
+
+    /// Sets whether the output of the fragment shader is converted from linear to sRGB when
+    /// drawing to this framebuffer, instead of relying on the `outputs_srgb` setting of
+    /// the program.
+    ///
+    /// The `srgb` member of the draw parameters takes precedence over this setting.
+    #[inline]
+    pub fn set_srgb_conversion(&mut self, enabled: bool) {
+        self.srgb_conversion = Some(enabled);
+    }
+
+    /// Returns the value passed to `set_srgb_conversion`, if any.
+    #[inline]
+    pub fn get_srgb_conversion(&self) -> Option<bool> {
+        self.srgb_conversion
+    }
 
     fn new_impl<F: ?Sized>(facade: &F, color: Option<ColorAttachment<'a>>,
                    depth: Option<DepthAttachment<'a>>, stencil: Option<StencilAttachment<'a>>,
-                   depthstencil: Option<DepthStencilAttachment<'a>>)
+                   depthstencil: Option<DepthStencilAttachment<'a>>,
+                   implicit_samples: Option<u32>)
                    -> Result<SimpleFrameBuffer<'a>, ValidationError> where F: Facade
     {
         let color = color.map(|color| match color {
@@ -261,11 +386,15 @@ impl<'a> SimpleFrameBuffer<'a> {
             }
         });
 
-        let attachments = attachments.validate(facade)?;
+        let attachments = match implicit_samples {
+            Some(samples) => attachments.validate_with_implicit_multisampling(facade, samples)?,
+            None => attachments.validate(facade)?,
+        };
 
         Ok(SimpleFrameBuffer {
             context: facade.get_context().clone(),
             attachments,
+            srgb_conversion: None,
         })
     }
 }
@@ -288,15 +417,40 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         self.attachments.get_depth_buffer_bits()
     }
 
+    #[inline]
+    fn get_depth_buffer_format(&self) -> Option<InternalFormat> {
+        self.attachments.get_depth_buffer_format()
+    }
+
     #[inline]
     fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.attachments.get_stencil_buffer_bits()
     }
 
-    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
-        uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+    #[inline]
+    fn get_color_attachments(&self) -> Vec<ColorAttachmentInfo> {
+        self.attachments.get_color_attachments().to_vec()
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer(&self, rect: &Rect) -> Result<PendingRead<(u8, u8, u8, u8)>, ReadError> {
+        PendingRead::new(&self.context, ops::Source::Framebuffer(&self.attachments), rect)
+    }
+
+    #[inline]
+    fn draw<'b, 'v, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &crate::DrawParameters<'_>)
+        -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
         V: crate::vertex::MultiVerticesSource<'v>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'b, 'v, V, I, U, P>(&mut self, vb: V, ib: I, program: &P,
+        uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+        where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
+        V: crate::vertex::MultiVerticesSource<'v>, P: program::DrawProgram + ?Sized
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                         draw_parameters.depth.write)
@@ -317,17 +471,47 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
             }
         }
 
+        if let (None, Some(srgb)) = (draw_parameters.srgb, self.srgb_conversion) {
+            let draw_parameters = crate::DrawParameters {
+                srgb: Some(srgb),
+                .. draw_parameters.clone()
+            };
+
+            return ops::draw(&self.context, Some(&self.attachments), vb, ib.into(),
+                             program.as_draw_program(), uniforms, &draw_parameters,
+                             self.get_dimensions());
+        }
+
         ops::draw(&self.context, Some(&self.attachments), vb,
-                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+                  ib.into(), program.as_draw_program(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[crate::vertex::VerticesSource<'_>],
+                indices: crate::index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn crate::uniforms::DynUniforms,
+                draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
     }
 
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized
     {
         target.blit_from_simple_framebuffer(self, source_rect, target_rect, filter)
     }
 
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        crate::check_blit_buffers(self, target, filter, mask)?;
+        target.blit_buffers_from_simple_framebuffer(self, source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
     #[inline]
     fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
         ops::blit(&self.context, None, self.get_attachments(),
@@ -360,6 +544,7 @@ pub struct MultiOutputFrameBuffer<'a> {
     example_attachments: fbo::ValidatedAttachments<'a>,
     color_attachments: Vec<(String, fbo::RegularAttachment<'a>)>,
     depth_stencil_attachments: fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>,
+    dimensions_policy: fbo::DimensionsMismatchPolicy,
 }
 
 impl<'a> MultiOutputFrameBuffer<'a> {
@@ -507,9 +692,28 @@ impl<'a> MultiOutputFrameBuffer<'a> {
             example_attachments,
             color_attachments: color,
             depth_stencil_attachments,
+            dimensions_policy: Default::default(),
         })
     }
 
+    /// Applies a policy for handling attachments of different dimensions.
+    ///
+    /// See `SimpleFrameBuffer::with_dimensions_policy`.
+    #[inline]
+    pub fn with_dimensions_policy(mut self, policy: DimensionsMismatchPolicy)
+                                  -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
+    {
+        self.example_attachments.apply_dimensions_policy(policy)?;
+        self.dimensions_policy = policy;
+        Ok(self)
+    }
+
+    /// Returns the dimensions of each attachment.
+    #[inline]
+    pub fn get_attachments_dimensions(&self) -> &[(u32, u32)] {
+        self.example_attachments.get_attachments_dimensions()
+    }
+
     fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments<'_> {
         let mut colors = SmallVec::new();
 
@@ -525,7 +729,7 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         fbo::FramebufferAttachments::Regular(fbo::FramebufferSpecificAttachments {
             colors,
             depth_stencil: self.depth_stencil_attachments,
-        }).validate(&self.context).unwrap()
+        }).validate_with_policy(&self.context, self.dimensions_policy).unwrap()
     }
 }
 
@@ -548,15 +752,45 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
         self.example_attachments.get_depth_buffer_bits()
     }
 
+    #[inline]
+    fn get_depth_buffer_format(&self) -> Option<InternalFormat> {
+        self.example_attachments.get_depth_buffer_format()
+    }
+
     #[inline]
     fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.example_attachments.get_stencil_buffer_bits()
     }
 
-    fn draw<'i, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
+    #[inline]
+    fn get_color_attachments(&self) -> Vec<ColorAttachmentInfo> {
+        self.example_attachments.get_color_attachments().to_vec()
+    }
+
+    fn read_to_pixel_buffer(&self, rect: &Rect) -> Result<PendingRead<(u8, u8, u8, u8)>, ReadError> {
+        let attachment = match self.color_attachments.first() {
+            Some((_, attachment)) => attachment,
+            None => return Err(ReadError::NoColorAttachment),
+        };
+
+        PendingRead::new(&self.context, ops::Source::Attachment(attachment), rect)
+    }
+
+    #[inline]
+    fn draw<'i, 'v, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &crate::DrawParameters<'_>)
+        -> Result<(), DrawError>
+        where I: Into<crate::index::IndicesSource<'i>>, U: crate::uniforms::Uniforms,
+        V: crate::vertex::MultiVerticesSource<'v>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'i, 'v, V, I, U, P>(&mut self, vb: V, ib: I, program: &P,
         uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'i>>,
-        U: crate::uniforms::Uniforms, V: crate::vertex::MultiVerticesSource<'v>
+        U: crate::uniforms::Uniforms, V: crate::vertex::MultiVerticesSource<'v>,
+        P: program::DrawProgram + ?Sized
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -577,17 +811,36 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
             }
         }
 
-        ops::draw(&self.context, Some(&self.build_attachments(program)), vb,
-                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+        ops::draw(&self.context, Some(&self.build_attachments(program.as_draw_program().fragment_program())), vb,
+                  ib.into(), program.as_draw_program(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[crate::vertex::VerticesSource<'_>],
+                indices: crate::index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn crate::uniforms::DynUniforms,
+                draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
     }
 
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized
     {
         target.blit_from_multioutput_framebuffer(self, source_rect, target_rect, filter)
     }
 
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        crate::check_blit_buffers(self, target, filter, mask)?;
+        target.blit_buffers_from_multioutput_framebuffer(self, source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
     #[inline]
     fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
         ops::blit(&self.context, None, self.get_attachments(),
@@ -714,10 +967,277 @@ impl Surface for EmptyFrameBuffer {
         None
     }
 
-    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &crate::Program,
+    #[inline]
+    fn read_to_pixel_buffer(&self, _: &Rect) -> Result<PendingRead<(u8, u8, u8, u8)>, ReadError> {
+        Err(ReadError::NoColorAttachment)
+    }
+
+    #[inline]
+    fn draw<'b, 'v, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &crate::DrawParameters<'_>)
+        -> Result<(), DrawError>
+        where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
+        V: crate::vertex::MultiVerticesSource<'v>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'b, 'v, V, I, U, P>(&mut self, vb: V, ib: I, program: &P,
         uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
         where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
+        V: crate::vertex::MultiVerticesSource<'v>, P: program::DrawProgram + ?Sized
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                        draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        if let Some(viewport) = draw_parameters.viewport {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as u32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as u32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
+        ops::draw(&self.context, Some(&self.attachments), vb,
+                  ib.into(), program.as_draw_program(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[crate::vertex::VerticesSource<'_>],
+                indices: crate::index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn crate::uniforms::DynUniforms,
+                draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
+    }
+
+    #[inline]
+    fn blit_color<S>(&self, _: &Rect, _: &S, _: &BlitTarget, _: uniforms::MagnifySamplerFilter)
+                     where S: Surface + ?Sized
+    {
+        // there is no color buffer to copy from
+    }
+
+    #[inline]
+    fn blit_buffers<S>(&self, _: &Rect, target: &S, _: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        if mask.color {
+            return Err(BlitError::NoColorBuffer);
+        }
+
+        // an empty framebuffer doesn't have depth or stencil buffers either, so this always
+        // returns an error if something was requested
+        crate::check_blit_buffers(self, target, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        ops::blit(&self.context, None, self.get_attachments(),
+                  mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>,
+                                    source_rect: &Rect, target_rect: &BlitTarget,
+                                    filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                  mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                         source_rect: &Rect, target_rect: &BlitTarget,
+                                         filter: uniforms::MagnifySamplerFilter,
+                                         mask: BlitMask)
+    {
+        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                  mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
+    }
+}
+
+impl FboAttachments for EmptyFrameBuffer {
+    #[inline]
+    fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments<'_>> {
+        Some(&self.attachments)
+    }
+}
+
+/// A framebuffer whose attachments are entire texture arrays, cubemaps or 3D textures.
+///
+/// Draw commands render to the layer selected by the geometry shader through `gl_Layer`, or
+/// to the first layer if the program doesn't write to `gl_Layer`.
+pub struct LayeredFrameBuffer<'a> {
+    context: Rc<Context>,
+    attachments: fbo::ValidatedAttachments<'a>,
+    color: Option<TextureAnyMipmap<'a>>,
+    depth: Option<TextureAnyMipmap<'a>>,
+}
+
+impl<'a> LayeredFrameBuffer<'a> {
+    /// Creates a `LayeredFrameBuffer` with a single color attachment and no depth buffer.
+    #[inline]
+    pub fn new<F: ?Sized, C>(facade: &F, color: C)
+                             -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                             where C: ToLayeredColorAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()), None)
+    }
+
+    /// Creates a `LayeredFrameBuffer` with a single color attachment and a depth buffer.
+    ///
+    /// The depth texture doesn't need to be of the same kind as the color texture. For example
+    /// a cubemap can be paired with a depth texture array of six layers.
+    #[inline]
+    pub fn with_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C, depth: D)
+                                              -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                                              where C: ToLayeredColorAttachment<'a>,
+                                                    D: ToLayeredDepthAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()),
+                                     Some(depth.to_layered_depth_attachment()))
+    }
+
+    /// Creates a `LayeredFrameBuffer` with a depth buffer and no color attachment.
+    ///
+    /// This is typically used to render all the faces of a shadow cubemap in a single pass.
+    #[inline]
+    pub fn depth_only<F: ?Sized, D>(facade: &F, depth: D)
+                                    -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                                    where D: ToLayeredDepthAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, None, Some(depth.to_layered_depth_attachment()))
+    }
+
+    /// Returns the number of layers of the framebuffer.
+    ///
+    /// This is the smallest number of layers among the attachments. Each face of a cubemap
+    /// counts as a layer.
+    #[inline]
+    pub fn get_layers(&self) -> u32 {
+        self.attachments.get_layers().unwrap()
+    }
+
+    fn new_impl<F: ?Sized>(facade: &F, color: Option<LayeredColorAttachment<'a>>,
+                           depth: Option<LayeredDepthAttachment<'a>>)
+                           -> Result<LayeredFrameBuffer<'a>, ValidationError> where F: Facade
+    {
+        let color = color.map(|color| match color {
+            LayeredColorAttachment::Texture(tex) => tex,
+        });
+
+        let depth = depth.map(|depth| match depth {
+            LayeredDepthAttachment::Texture(tex) => tex,
+        });
+
+        let attachments = fbo::FramebufferAttachments::Layered(fbo::FramebufferSpecificAttachments {
+            colors: if let Some(color) = color {
+                let mut v = SmallVec::new(); v.push((0, fbo::LayeredAttachment(color))); v
+            } else {
+                SmallVec::new()
+            },
+            depth_stencil: if let Some(depth) = depth {
+                fbo::DepthStencilAttachments::DepthAttachment(fbo::LayeredAttachment(depth))
+            } else {
+                fbo::DepthStencilAttachments::None
+            },
+        });
+
+        let attachments = attachments.validate(facade)?;
+
+        Ok(LayeredFrameBuffer {
+            context: facade.get_context().clone(),
+            attachments,
+            color,
+            depth,
+        })
+    }
+
+    /// Builds a framebuffer whose attachments are the first layer of the attachments of this
+    /// one.
+    ///
+    /// Blitting from a layered framebuffer reads from its first layer, so this framebuffer is
+    /// used as the source of blits.
+    fn first_layer(&self) -> SimpleFrameBuffer<'a> {
+        // the first layer of a cubemap is its positive X face
+        let image = |mipmap: TextureAnyMipmap<'a>| {
+            let layer = mipmap.first_layer();
+            layer.into_image(None).or_else(|| layer.into_image(Some(CubeLayer::PositiveX)))
+                 .unwrap()
+        };
+
+        let color = self.color.map(|c| ColorAttachment::Texture(image(c)));
+        let depth = self.depth.map(|d| DepthAttachment::Texture(image(d)));
+
+        SimpleFrameBuffer::new_impl(&self.context, color, depth, None, None, None)
+            .expect("The first layer of a valid layered framebuffer is always valid")
+    }
+}
+
+impl<'a> Surface for LayeredFrameBuffer<'a> {
+    #[inline]
+    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
+             depth: Option<f32>, stencil: Option<i32>)
+    {
+        ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
+    }
+
+    #[inline]
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.attachments.get_dimensions()
+    }
+
+    #[inline]
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.attachments.get_depth_buffer_bits()
+    }
+
+    #[inline]
+    fn get_depth_buffer_format(&self) -> Option<InternalFormat> {
+        self.attachments.get_depth_buffer_format()
+    }
+
+    #[inline]
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.attachments.get_stencil_buffer_bits()
+    }
+
+    #[inline]
+    fn get_color_attachments(&self) -> Vec<ColorAttachmentInfo> {
+        self.attachments.get_color_attachments().to_vec()
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer(&self, rect: &Rect) -> Result<PendingRead<(u8, u8, u8, u8)>, ReadError> {
+        PendingRead::new(&self.context, ops::Source::Framebuffer(&self.attachments), rect)
+    }
+
+    #[inline]
+    fn draw<'b, 'v, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &crate::DrawParameters<'_>)
+        -> Result<(), DrawError>
+        where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
         V: crate::vertex::MultiVerticesSource<'v>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'b, 'v, V, I, U, P>(&mut self, vb: V, ib: I, program: &P,
+        uniforms: &U, draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+        where I: Into<crate::index::IndicesSource<'b>>, U: crate::uniforms::Uniforms,
+        V: crate::vertex::MultiVerticesSource<'v>, P: program::DrawProgram + ?Sized
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                         draw_parameters.depth.write)
@@ -739,14 +1259,34 @@ impl Surface for EmptyFrameBuffer {
         }
 
         ops::draw(&self.context, Some(&self.attachments), vb,
-                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+                  ib.into(), program.as_draw_program(), uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[crate::vertex::VerticesSource<'_>],
+                indices: crate::index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn crate::uniforms::DynUniforms,
+                draw_parameters: &crate::DrawParameters<'_>) -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
     }
 
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized
     {
-        unimplemented!()        // TODO:
+        target.blit_from_simple_framebuffer(&self.first_layer(), source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        crate::check_blit_buffers(self, target, filter, mask)?;
+        target.blit_buffers_from_simple_framebuffer(&self.first_layer(), source_rect, target_rect,
+                                                    filter, mask);
+        Ok(())
     }
 
     #[inline]
@@ -777,7 +1317,7 @@ impl Surface for EmptyFrameBuffer {
     }
 }
 
-impl FboAttachments for EmptyFrameBuffer {
+impl<'a> FboAttachments for LayeredFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments<'_>> {
         Some(&self.attachments)
@@ -828,6 +1368,46 @@ impl<'a> ToDepthAttachment<'a> for DepthAttachment<'a> {
     }
 }
 
+/// Describes a layered attachment for a color buffer.
+#[derive(Copy, Clone)]
+pub enum LayeredColorAttachment<'a> {
+    /// A mipmap level of a texture array, cubemap, cubemap array or 3D texture.
+    Texture(TextureAnyMipmap<'a>),
+}
+
+/// Trait for objects that can be used as layered color attachments.
+pub trait ToLayeredColorAttachment<'a> {
+    /// Builds the `LayeredColorAttachment`.
+    fn to_layered_color_attachment(self) -> LayeredColorAttachment<'a>;
+}
+
+impl<'a> ToLayeredColorAttachment<'a> for LayeredColorAttachment<'a> {
+    #[inline]
+    fn to_layered_color_attachment(self) -> LayeredColorAttachment<'a> {
+        self
+    }
+}
+
+/// Describes a layered attachment for a depth buffer.
+#[derive(Copy, Clone)]
+pub enum LayeredDepthAttachment<'a> {
+    /// A mipmap level of a texture array, cubemap, cubemap array or 3D texture.
+    Texture(TextureAnyMipmap<'a>),
+}
+
+/// Trait for objects that can be used as layered depth attachments.
+pub trait ToLayeredDepthAttachment<'a> {
+    /// Builds the `LayeredDepthAttachment`.
+    fn to_layered_depth_attachment(self) -> LayeredDepthAttachment<'a>;
+}
+
+impl<'a> ToLayeredDepthAttachment<'a> for LayeredDepthAttachment<'a> {
+    #[inline]
+    fn to_layered_depth_attachment(self) -> LayeredDepthAttachment<'a> {
+        self
+    }
+}
+
 /// Describes an attachment for a stencil buffer.
 #[derive(Copy, Clone)]
 pub enum StencilAttachment<'a> {
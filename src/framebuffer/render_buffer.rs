@@ -17,6 +17,7 @@ use crate::framebuffer::{DepthAttachment, ToDepthAttachment};
 use crate::framebuffer::{StencilAttachment, ToStencilAttachment};
 use crate::framebuffer::{DepthStencilAttachment, ToDepthStencilAttachment};
 use crate::texture::{UncompressedFloatFormat, DepthFormat, StencilFormat, DepthStencilFormat, TextureKind};
+use crate::texture::{InternalFormat, InternalFormatType};
 
 use crate::image_format;
 
@@ -317,6 +318,7 @@ pub struct RenderBufferAny {
     height: u32,
     samples: Option<u32>,
     kind: TextureKind,
+    format: gl::types::GLenum,
 }
 
 impl RenderBufferAny {
@@ -441,6 +443,7 @@ impl RenderBufferAny {
                 height,
                 samples,
                 kind,
+                format,
             }
         }
     }
@@ -470,6 +473,20 @@ impl RenderBufferAny {
         self.kind
     }
 
+    /// Returns the format of the depth component of this render buffer, or `None` if it doesn't
+    /// have one.
+    pub(crate) fn get_depth_format(&self) -> Option<InternalFormat> {
+        let (ty, bits) = match self.format {
+            gl::DEPTH_COMPONENT16 => (InternalFormatType::UnsignedNormalized, 16),
+            gl::DEPTH_COMPONENT24 | gl::DEPTH24_STENCIL8 => (InternalFormatType::UnsignedNormalized, 24),
+            gl::DEPTH_COMPONENT32 => (InternalFormatType::UnsignedNormalized, 32),
+            gl::DEPTH_COMPONENT32F | gl::DEPTH32F_STENCIL8 => (InternalFormatType::Float, 32),
+            _ => return None,
+        };
+
+        Some(InternalFormat::OneComponent { ty1: ty, bits1: bits })
+    }
+
     /// Determines the number of depth and stencil bits in the format of this render buffer.
     pub fn get_depth_stencil_bits(&self) -> (u16, u16) {
         unsafe {
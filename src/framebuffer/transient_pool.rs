@@ -0,0 +1,250 @@
+/*!
+
+A pool of scratch render targets that are recycled across frames.
+
+Post-processing passes often need intermediate textures that only live for the duration of a
+frame. Instead of creating and destroying them every frame, you can ask the `TransientPool`
+for a target with a given format and size. The target is given back to the pool when the
+`Transient` is dropped, and is reused by the next request with the same parameters.
+
+Targets that haven't been used for a few frames are destroyed when `next_frame` is called.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::texture::UncompressedFloatFormat;
+
+let pool = display.get_transient_pool();
+let blur = pool.texture_2d(UncompressedFloatFormat::F16F16F16F16, 800, 600).unwrap();
+// ... render to `blur` and sample from it ...
+# }
+```
+
+*/
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::framebuffer::{RenderBuffer, DepthRenderBuffer, RenderBufferCreationError};
+use crate::texture::{DepthTexture2d, Texture2d, Texture2dMultisample, TextureCreationError};
+use crate::texture::{DepthFormat, MipmapsOption, UncompressedFloatFormat};
+
+/// Parameters of a target stored in the pool.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Key {
+    Texture2d(UncompressedFloatFormat, u32, u32),
+    Texture2dMultisample(UncompressedFloatFormat, u32, u32, u32),
+    DepthTexture2d(DepthFormat, u32, u32),
+    RenderBuffer(UncompressedFloatFormat, u32, u32, Option<u32>),
+    DepthRenderBuffer(DepthFormat, u32, u32, Option<u32>),
+}
+
+/// A target that is not in use.
+struct Entry {
+    key: Key,
+    resource: Box<dyn Any>,
+    /// Value of the frame counter when the target was given back.
+    last_used: u64,
+}
+
+/// Hands out scratch textures and render buffers, and recycles them across frames.
+pub struct TransientPool {
+    context: Rc<Context>,
+    free: RefCell<Vec<Entry>>,
+    frame: Cell<u64>,
+    max_unused_frames: Cell<u64>,
+}
+
+impl TransientPool {
+    /// Builds a new empty pool.
+    ///
+    /// Unused targets are kept for 3 frames by default.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> TransientPool where F: Facade {
+        TransientPool {
+            context: facade.get_context().clone(),
+            free: RefCell::new(Vec::new()),
+            frame: Cell::new(0),
+            max_unused_frames: Cell::new(3),
+        }
+    }
+
+    /// Returns a color texture with the given format and dimensions.
+    ///
+    /// The content of the texture is undefined.
+    pub fn texture_2d(&self, format: UncompressedFloatFormat, width: u32, height: u32)
+                      -> Result<Transient<'_, Texture2d>, TextureCreationError>
+    {
+        self.acquire(Key::Texture2d(format, width, height), || {
+            Texture2d::empty_with_format(&self.context, format, MipmapsOption::NoMipmap,
+                                         width, height)
+        })
+    }
+
+    /// Returns a multisample color texture with the given format, dimensions and samples.
+    ///
+    /// The content of the texture is undefined.
+    pub fn texture_2d_multisample(&self, format: UncompressedFloatFormat, width: u32, height: u32,
+                                  samples: u32)
+                                  -> Result<Transient<'_, Texture2dMultisample>, TextureCreationError>
+    {
+        self.acquire(Key::Texture2dMultisample(format, width, height, samples), || {
+            Texture2dMultisample::empty_with_format(&self.context, format,
+                                                    MipmapsOption::NoMipmap, width, height,
+                                                    samples)
+        })
+    }
+
+    /// Returns a depth texture with the given format and dimensions.
+    ///
+    /// The content of the texture is undefined.
+    pub fn depth_texture_2d(&self, format: DepthFormat, width: u32, height: u32)
+                            -> Result<Transient<'_, DepthTexture2d>, TextureCreationError>
+    {
+        self.acquire(Key::DepthTexture2d(format, width, height), || {
+            DepthTexture2d::empty_with_format(&self.context, format, MipmapsOption::NoMipmap,
+                                              width, height)
+        })
+    }
+
+    /// Returns a render buffer with the given format and dimensions. If `samples` is `Some`,
+    /// the render buffer is multisampled.
+    ///
+    /// The content of the render buffer is undefined.
+    pub fn render_buffer(&self, format: UncompressedFloatFormat, width: u32, height: u32,
+                         samples: Option<u32>)
+                         -> Result<Transient<'_, RenderBuffer>, RenderBufferCreationError>
+    {
+        self.acquire(Key::RenderBuffer(format, width, height, samples), || {
+            match samples {
+                Some(samples) => RenderBuffer::new_multisample(&self.context, format, width,
+                                                               height, samples),
+                None => RenderBuffer::new(&self.context, format, width, height),
+            }
+        })
+    }
+
+    /// Returns a depth render buffer with the given format and dimensions. If `samples` is
+    /// `Some`, the render buffer is multisampled.
+    ///
+    /// The content of the render buffer is undefined.
+    pub fn depth_render_buffer(&self, format: DepthFormat, width: u32, height: u32,
+                               samples: Option<u32>)
+                               -> Result<Transient<'_, DepthRenderBuffer>, RenderBufferCreationError>
+    {
+        self.acquire(Key::DepthRenderBuffer(format, width, height, samples), || {
+            match samples {
+                Some(samples) => DepthRenderBuffer::new_multisample(&self.context, format, width,
+                                                                    height, samples),
+                None => DepthRenderBuffer::new(&self.context, format, width, height),
+            }
+        })
+    }
+
+    /// Advances the frame counter and destroys the targets that haven't been used for more
+    /// than the configured number of frames.
+    ///
+    /// This is called automatically by `Display::draw`.
+    pub fn next_frame(&self) {
+        self.frame.set(self.frame.get() + 1);
+        self.trim();
+    }
+
+    /// Destroys the targets that haven't been used for more than the configured number of frames.
+    pub fn trim(&self) {
+        let frame = self.frame.get();
+        let max = self.max_unused_frames.get();
+        self.free.borrow_mut().retain(|entry| frame - entry.last_used <= max);
+    }
+
+    /// Destroys all the targets that are not in use.
+    #[inline]
+    pub fn clear(&self) {
+        self.free.borrow_mut().clear();
+    }
+
+    /// Sets the number of frames after which an unused target is destroyed.
+    #[inline]
+    pub fn set_max_unused_frames(&self, frames: u64) {
+        self.max_unused_frames.set(frames);
+    }
+
+    /// Returns the number of frames after which an unused target is destroyed.
+    #[inline]
+    pub fn get_max_unused_frames(&self) -> u64 {
+        self.max_unused_frames.get()
+    }
+
+    /// Returns the number of targets that are waiting to be reused.
+    #[inline]
+    pub fn get_free_count(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    fn acquire<T: 'static, E, C>(&self, key: Key, create: C) -> Result<Transient<'_, T>, E>
+        where C: FnOnce() -> Result<T, E>
+    {
+        let existing = {
+            let mut free = self.free.borrow_mut();
+            free.iter().position(|entry| entry.key == key).map(|index| free.swap_remove(index))
+        };
+
+        let resource = match existing {
+            Some(entry) => *entry.resource.downcast::<T>().unwrap(),
+            None => create()?,
+        };
+
+        Ok(Transient {
+            pool: self,
+            key,
+            resource: Some(resource),
+        })
+    }
+}
+
+impl fmt::Debug for TransientPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(fmt, "TransientPool {{ free: {}, frame: {} }}", self.free.borrow().len(),
+               self.frame.get())
+    }
+}
+
+/// A target borrowed from a `TransientPool`. Gives the target back to the pool when dropped.
+pub struct Transient<'a, T: 'static> {
+    pool: &'a TransientPool,
+    key: Key,
+    // this `Option` is here because we need to be able to move out in the destructor
+    resource: Option<T>,
+}
+
+impl<'a, T: 'static> Deref for Transient<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.resource.as_ref().unwrap()
+    }
+}
+
+impl<'a, T: 'static> DerefMut for Transient<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.resource.as_mut().unwrap()
+    }
+}
+
+impl<'a, T: 'static> Drop for Transient<'a, T> {
+    fn drop(&mut self) {
+        if let Some(resource) = self.resource.take() {
+            self.pool.free.borrow_mut().push(Entry {
+                key: self.key,
+                resource: Box::new(resource),
+                last_used: self.pool.frame.get(),
+            });
+        }
+    }
+}
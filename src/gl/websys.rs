@@ -0,0 +1,1924 @@
+//! OpenGL entry points implemented on top of a `WebGl2RenderingContext`.
+//!
+//! WebGL has no function pointers to load, so `get_proc_address` returns pointers to the
+//! functions of this module instead. Each of them forwards the call to the WebGL context that
+//! is current on this thread, after translating the integer names of OpenGL objects to the
+//! JavaScript objects of WebGL and back.
+//!
+//! Only the subset of OpenGL ES 3.0 that has a WebGL 2 equivalent is implemented. The loader
+//! gets a null pointer for the other functions, exactly like with a driver that doesn't
+//! support them.
+#![allow(non_snake_case)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use js_sys::{Array, ArrayBuffer, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{WebGl2RenderingContext, WebGlActiveInfo, WebGlBuffer, WebGlFramebuffer};
+use web_sys::{WebGlProgram, WebGlQuery, WebGlRenderbuffer, WebGlSampler, WebGlShader};
+use web_sys::{WebGlSync, WebGlTexture, WebGlTransformFeedback, WebGlUniformLocation};
+use web_sys::WebGlVertexArrayObject;
+
+use crate::gl;
+use crate::gl::types::*;
+
+/// WebGL extensions that are enabled when available, and the name of the OpenGL extension that
+/// they correspond to.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("EXT_color_buffer_float", "GL_EXT_color_buffer_float"),
+    ("EXT_color_buffer_half_float", "GL_EXT_color_buffer_half_float"),
+    ("EXT_float_blend", "GL_EXT_float_blend"),
+    ("EXT_texture_compression_bptc", "GL_EXT_texture_compression_bptc"),
+    ("EXT_texture_compression_rgtc", "GL_EXT_texture_compression_rgtc"),
+    ("EXT_texture_filter_anisotropic", "GL_EXT_texture_filter_anisotropic"),
+    ("OES_texture_float_linear", "GL_OES_texture_float_linear"),
+    ("WEBGL_compressed_texture_s3tc", "GL_EXT_texture_compression_s3tc"),
+];
+
+/// `MAX_CLIENT_WAIT_TIMEOUT_WEBGL`
+const MAX_CLIENT_WAIT_TIMEOUT_WEBGL: GLenum = 0x9247;
+
+/// A WebGL context, together with the tables that map OpenGL names to WebGL objects.
+pub struct Context {
+    gl: WebGl2RenderingContext,
+    state: RefCell<State>,
+}
+
+impl Context {
+    /// Wraps a WebGL context and enables the WebGL extensions that have an OpenGL equivalent.
+    pub fn new(gl: WebGl2RenderingContext) -> Rc<Context> {
+        let mut extensions = Vec::new();
+        if let Some(supported) = gl.get_supported_extensions() {
+            for name in supported.iter().filter_map(|name| name.as_string()) {
+                let gl_name = match EXTENSIONS.iter().find(|&&(n, _)| n == name) {
+                    Some(&(_, gl_name)) => gl_name,
+                    None => continue,
+                };
+
+                // WebGL extensions have no effect until they are enabled
+                if let Ok(Some(_)) = gl.get_extension(&name) {
+                    extensions.push(CString::new(gl_name).unwrap());
+                }
+            }
+        }
+
+        let parameter = |pname| gl.get_parameter(pname).ok().and_then(|v| v.as_string())
+                                                       .unwrap_or_default();
+        let all_extensions = extensions.iter().map(|e| e.to_str().unwrap())
+                                       .collect::<Vec<_>>().join(" ");
+
+        let mut strings = HashMap::new();
+        strings.insert(gl::VERSION, CString::new("OpenGL ES 3.0 (WebGL 2.0)").unwrap());
+        strings.insert(gl::SHADING_LANGUAGE_VERSION,
+                       CString::new("OpenGL ES GLSL ES 3.00 (WebGL GLSL ES 3.00)").unwrap());
+        strings.insert(gl::VENDOR, CString::new(parameter(gl::VENDOR)).unwrap_or_default());
+        strings.insert(gl::RENDERER, CString::new(parameter(gl::RENDERER)).unwrap_or_default());
+        strings.insert(gl::EXTENSIONS, CString::new(all_extensions).unwrap());
+
+        let max_client_wait_timeout = gl.get_parameter(MAX_CLIENT_WAIT_TIMEOUT_WEBGL).ok()
+                                        .and_then(|v| v.as_f64()).unwrap_or(0.0) as u32;
+
+        Rc::new(Context {
+            gl,
+            state: RefCell::new(State {
+                buffers: Objects::default(),
+                framebuffers: Objects::default(),
+                programs: Objects::default(),
+                queries: Objects::default(),
+                renderbuffers: Objects::default(),
+                samplers: Objects::default(),
+                syncs: Objects::default(),
+                textures: Objects::default(),
+                transform_feedbacks: Objects::default(),
+                vertex_arrays: Objects::default(),
+                locations: HashMap::new(),
+                current_program: 0,
+                pack_buffer: 0,
+                unpack_buffer: 0,
+                pack: PixelStore::default(),
+                unpack: PixelStore::default(),
+                mappings: Vec::new(),
+                strings,
+                extensions,
+                max_client_wait_timeout,
+                pending_error: gl::NO_ERROR,
+            }),
+        })
+    }
+
+    /// Returns the underlying WebGL context.
+    #[inline]
+    pub fn webgl(&self) -> &WebGl2RenderingContext {
+        &self.gl
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Context>>> = const { RefCell::new(None) };
+}
+
+/// Makes the context current on this thread. The shims use the current context.
+pub fn make_current(context: &Rc<Context>) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(context.clone()));
+}
+
+/// Returns true if the context is current on this thread.
+pub fn is_current(context: &Rc<Context>) -> bool {
+    CURRENT.with(|current| {
+        current.borrow().as_ref().map_or(false, |current| Rc::ptr_eq(current, context))
+    })
+}
+
+/// Calls `f` with the current context.
+fn with<R, F>(f: F) -> R where F: FnOnce(&WebGl2RenderingContext, &mut State) -> R {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        let current = current.as_ref().expect("No WebGL context is current");
+        let mut state = current.state.borrow_mut();
+        f(&current.gl, &mut state)
+    })
+}
+
+struct State {
+    buffers: Objects,
+    framebuffers: Objects,
+    /// Shaders and programs, which share the same names in OpenGL.
+    programs: Objects,
+    queries: Objects,
+    renderbuffers: Objects,
+    samplers: Objects,
+    syncs: Objects,
+    textures: Objects,
+    transform_feedbacks: Objects,
+    vertex_arrays: Objects,
+
+    /// Uniform locations of each program. The OpenGL location is the index in the list.
+    locations: HashMap<GLuint, Vec<(String, WebGlUniformLocation)>>,
+    current_program: GLuint,
+
+    /// Buffers bound to `GL_PIXEL_PACK_BUFFER` and `GL_PIXEL_UNPACK_BUFFER`, in which case the
+    /// pointers passed to the pixel transfer functions are offsets.
+    pack_buffer: GLuint,
+    unpack_buffer: GLuint,
+    pack: PixelStore,
+    unpack: PixelStore,
+
+    /// WebGL can't map buffers. Mapping copies the content to memory instead.
+    mappings: Vec<Mapping>,
+
+    /// Strings returned by `glGetString` and `glGetStringi`, which must stay alive.
+    strings: HashMap<GLenum, CString>,
+    extensions: Vec<CString>,
+
+    max_client_wait_timeout: u32,
+
+    /// Error that was retrieved from WebGL but not yet by `glGetError`.
+    pending_error: GLenum,
+}
+
+impl State {
+    /// Returns the OpenGL name of a WebGL object.
+    fn name_of(&self, object: &JsValue) -> GLuint {
+        [&self.buffers, &self.framebuffers, &self.programs, &self.queries, &self.renderbuffers,
+         &self.samplers, &self.textures, &self.transform_feedbacks, &self.vertex_arrays]
+            .iter().find_map(|objects| objects.find(object)).unwrap_or(0)
+    }
+
+    /// Converts a value returned by WebGL to a list of numbers.
+    fn numbers(&self, value: &JsValue) -> Vec<f64> {
+        if let Some(value) = value.as_bool() {
+            vec![if value { 1.0 } else { 0.0 }]
+        } else if let Some(value) = value.as_f64() {
+            vec![value]
+        } else if value.is_null() || value.is_undefined() {
+            Vec::new()
+        } else if Array::is_array(value) || ArrayBuffer::is_view(value) {
+            Array::from(value).iter()
+                              .map(|v| self.numbers(&v).first().cloned().unwrap_or(0.0))
+                              .collect()
+        } else {
+            vec![self.name_of(value) as f64]
+        }
+    }
+
+    /// Records a WebGL error so that it's returned by the next call to `glGetError`.
+    fn set_error(&mut self, error: GLenum) {
+        if self.pending_error == gl::NO_ERROR {
+            self.pending_error = error;
+        }
+    }
+
+    /// Calls `getParameter`. Returns null without generating an error if WebGL doesn't know the
+    /// parameter.
+    fn parameter(&mut self, gl: &WebGl2RenderingContext, pname: GLenum) -> JsValue {
+        let value = gl.get_parameter(pname).unwrap_or(JsValue::NULL);
+        if value.is_null() {
+            match gl.get_error() {
+                gl::NO_ERROR | gl::INVALID_ENUM => (),
+                error => self.set_error(error),
+            }
+        }
+        value
+    }
+
+    /// Returns the location of a uniform of the current program.
+    fn location(&self, location: GLint) -> Option<&WebGlUniformLocation> {
+        let location = usize::try_from(location).ok()?;
+        self.locations.get(&self.current_program)?.get(location).map(|(_, l)| l)
+    }
+
+    /// Returns the name of the buffer bound to `target`.
+    fn bound_buffer(&mut self, gl: &WebGl2RenderingContext, target: GLenum) -> GLuint {
+        let binding = match target {
+            gl::ARRAY_BUFFER => gl::ARRAY_BUFFER_BINDING,
+            gl::ELEMENT_ARRAY_BUFFER => gl::ELEMENT_ARRAY_BUFFER_BINDING,
+            gl::COPY_READ_BUFFER => gl::COPY_READ_BUFFER_BINDING,
+            gl::COPY_WRITE_BUFFER => gl::COPY_WRITE_BUFFER_BINDING,
+            gl::PIXEL_PACK_BUFFER => gl::PIXEL_PACK_BUFFER_BINDING,
+            gl::PIXEL_UNPACK_BUFFER => gl::PIXEL_UNPACK_BUFFER_BINDING,
+            gl::TRANSFORM_FEEDBACK_BUFFER => gl::TRANSFORM_FEEDBACK_BUFFER_BINDING,
+            gl::UNIFORM_BUFFER => gl::UNIFORM_BUFFER_BINDING,
+            _ => return 0,
+        };
+
+        let buffer = self.parameter(gl, binding);
+        self.name_of(&buffer)
+    }
+}
+
+/// Table of WebGL objects, indexed by their OpenGL name minus one.
+#[derive(Default)]
+struct Objects {
+    objects: Vec<Option<JsValue>>,
+    free: Vec<usize>,
+}
+
+impl Objects {
+    fn insert(&mut self, object: JsValue) -> GLuint {
+        match self.free.pop() {
+            Some(index) => {
+                self.objects[index] = Some(object);
+                index as GLuint + 1
+            },
+            None => {
+                self.objects.push(Some(object));
+                self.objects.len() as GLuint
+            },
+        }
+    }
+
+    fn get<T: JsCast>(&self, name: GLuint) -> Option<&T> {
+        let index = (name as usize).checked_sub(1)?;
+        self.objects.get(index)?.as_ref().map(|object| object.unchecked_ref())
+    }
+
+    fn remove(&mut self, name: GLuint) -> Option<JsValue> {
+        let index = (name as usize).checked_sub(1)?;
+        let object = self.objects.get_mut(index)?.take()?;
+        self.free.push(index);
+        Some(object)
+    }
+
+    fn find(&self, object: &JsValue) -> Option<GLuint> {
+        self.objects.iter().position(|o| o.as_ref() == Some(object)).map(|i| i as GLuint + 1)
+    }
+}
+
+/// The pixel storage parameters that affect the size of the data of a transfer.
+struct PixelStore {
+    alignment: usize,
+    row_length: usize,
+    image_height: usize,
+    skip_pixels: usize,
+    skip_rows: usize,
+    skip_images: usize,
+}
+
+impl Default for PixelStore {
+    fn default() -> PixelStore {
+        PixelStore {
+            alignment: 4,
+            row_length: 0,
+            image_height: 0,
+            skip_pixels: 0,
+            skip_rows: 0,
+            skip_images: 0,
+        }
+    }
+}
+
+impl PixelStore {
+    /// Returns the number of bytes that a transfer of this size reads or writes.
+    fn image_size(&self, width: GLsizei, height: GLsizei, depth: GLsizei, format: GLenum,
+                  ty: GLenum) -> usize
+    {
+        if width <= 0 || height <= 0 || depth <= 0 {
+            return 0;
+        }
+
+        let (width, height, depth) = (width as usize, height as usize, depth as usize);
+        let pixel = pixel_size(format, ty);
+        let row_length = if self.row_length > 0 { self.row_length } else { width };
+        let row_stride = (row_length * pixel).div_ceil(self.alignment) * self.alignment;
+        let image_height = if self.image_height > 0 { self.image_height } else { height };
+        let image_stride = image_height * row_stride;
+
+        self.skip_images * image_stride + self.skip_rows * row_stride +
+            self.skip_pixels * pixel + (depth - 1) * image_stride + (height - 1) * row_stride +
+            width * pixel
+    }
+}
+
+/// Returns the size in bytes of a pixel.
+fn pixel_size(format: GLenum, ty: GLenum) -> usize {
+    match ty {
+        gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_5_5_5_1 => 2,
+        gl::UNSIGNED_INT_2_10_10_10_REV | gl::UNSIGNED_INT_10F_11F_11F_REV |
+        gl::UNSIGNED_INT_5_9_9_9_REV | gl::UNSIGNED_INT_24_8 => 4,
+        gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+        _ => {
+            let components = match format {
+                gl::RG | gl::RG_INTEGER | gl::LUMINANCE_ALPHA => 2,
+                gl::RGB | gl::RGB_INTEGER => 3,
+                gl::RGBA | gl::RGBA_INTEGER => 4,
+                _ => 1,
+            };
+
+            let component = match ty {
+                gl::UNSIGNED_SHORT | gl::SHORT | gl::HALF_FLOAT => 2,
+                gl::UNSIGNED_INT | gl::INT | gl::FLOAT => 4,
+                _ => 1,
+            };
+
+            components * component
+        },
+    }
+}
+
+struct Mapping {
+    buffer: GLuint,
+    offset: i32,
+    data: Box<[u8]>,
+    access: GLbitfield,
+}
+
+/// Returns the string at `ptr`.
+unsafe fn c_str<'a>(ptr: *const GLchar) -> &'a str {
+    CStr::from_ptr(ptr).to_str().unwrap_or("")
+}
+
+/// Writes a string in a buffer provided by the caller, the way `glGetShaderInfoLog` and
+/// similar functions do.
+unsafe fn write_string(string: &str, buf_size: GLsizei, length: *mut GLsizei, out: *mut GLchar) {
+    let len = if buf_size <= 0 { 0 } else { string.len().min(buf_size as usize - 1) };
+
+    if !out.is_null() && buf_size > 0 {
+        ptr::copy_nonoverlapping(string.as_ptr(), out as *mut u8, len);
+        *out.add(len) = 0;
+    }
+
+    if !length.is_null() {
+        *length = len as GLsizei;
+    }
+}
+
+/// Writes a list of numbers returned by WebGL. Writes `0` if the list is empty.
+unsafe fn write_numbers<T, F>(numbers: &[f64], out: *mut T, convert: F) where F: Fn(f64) -> T {
+    if numbers.is_empty() {
+        *out = convert(0.0);
+    }
+
+    for (index, &number) in numbers.iter().enumerate() {
+        *out.add(index) = convert(number);
+    }
+}
+
+fn to_int(value: f64) -> GLint {
+    value as i64 as GLint
+}
+
+fn to_boolean(value: GLboolean) -> bool {
+    value != gl::FALSE
+}
+
+/// Returns the length plus one of the longest name returned by `f`, or `0` if there is none.
+fn max_name_length<F>(count: u32, f: F) -> GLint where F: Fn(u32) -> Option<String> {
+    (0 .. count).filter_map(f).map(|name| name.len() as GLint + 1).max().unwrap_or(0)
+}
+
+/// Calls `create` `n` times and writes the names of the objects to `names`.
+unsafe fn generate<F>(objects: &mut Objects, n: GLsizei, names: *mut GLuint, mut create: F)
+    where F: FnMut() -> Option<JsValue>
+{
+    for index in 0 .. n.max(0) as usize {
+        *names.add(index) = match create() {
+            Some(object) => objects.insert(object),
+            None => 0,
+        };
+    }
+}
+
+/// Removes the objects from the table and calls `delete` with each of them.
+unsafe fn delete<F>(objects: &mut Objects, n: GLsizei, names: *const GLuint, mut delete: F)
+    where F: FnMut(JsValue)
+{
+    for &name in slice::from_raw_parts(names, n.max(0) as usize) {
+        if let Some(object) = objects.remove(name) {
+            delete(object);
+        }
+    }
+}
+
+macro_rules! shims {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? $body:block)+) => {
+        $(
+            unsafe extern "system" fn $name($($arg: $ty),*) $(-> $ret)? $body
+        )+
+
+        /// Returns a pointer to the function that implements the given OpenGL function, or null
+        /// if WebGL has no equivalent.
+        pub fn get_proc_address(symbol: &str) -> *const c_void {
+            match symbol.strip_prefix("gl") {
+                $(Some(stringify!($name)) => $name as *const c_void,)+
+                _ => ptr::null(),
+            }
+        }
+    };
+}
+
+shims! {
+    // errors and queries
+
+    fn GetError() -> GLenum {
+        with(|gl, s| {
+            match std::mem::replace(&mut s.pending_error, gl::NO_ERROR) {
+                gl::NO_ERROR => gl.get_error(),
+                error => error,
+            }
+        })
+    }
+
+    fn GetString(name: GLenum) -> *const GLubyte {
+        with(|_, s| {
+            match s.strings.get(&name) {
+                Some(string) => string.as_ptr() as *const GLubyte,
+                None => {
+                    s.set_error(gl::INVALID_ENUM);
+                    ptr::null()
+                },
+            }
+        })
+    }
+
+    fn GetStringi(name: GLenum, index: GLuint) -> *const GLubyte {
+        with(|_, s| {
+            match (name, s.extensions.get(index as usize)) {
+                (gl::EXTENSIONS, Some(extension)) => extension.as_ptr() as *const GLubyte,
+                _ => {
+                    s.set_error(gl::INVALID_VALUE);
+                    ptr::null()
+                },
+            }
+        })
+    }
+
+    fn GetIntegerv(pname: GLenum, data: *mut GLint) {
+        with(|gl, s| {
+            let numbers = match pname {
+                gl::NUM_EXTENSIONS => vec![s.extensions.len() as f64],
+                gl::CONTEXT_FLAGS | gl::CONTEXT_PROFILE_MASK | gl::NUM_PROGRAM_BINARY_FORMATS |
+                gl::NUM_SHADER_BINARY_FORMATS => vec![0.0],
+                _ => {
+                    let value = s.parameter(gl, pname);
+                    s.numbers(&value)
+                },
+            };
+            write_numbers(&numbers, data, to_int);
+        })
+    }
+
+    fn GetIntegeri_v(target: GLenum, index: GLuint, data: *mut GLint) {
+        with(|gl, s| {
+            let value = gl.get_indexed_parameter(target, index).unwrap_or(JsValue::NULL);
+            write_numbers(&s.numbers(&value), data, to_int);
+        })
+    }
+
+    fn GetBooleanv(pname: GLenum, data: *mut GLboolean) {
+        with(|gl, s| {
+            let numbers = match pname {
+                gl::SHADER_COMPILER => vec![1.0],
+                _ => {
+                    let value = s.parameter(gl, pname);
+                    s.numbers(&value)
+                },
+            };
+            write_numbers(&numbers, data, |v| if v != 0.0 { gl::TRUE } else { gl::FALSE });
+        })
+    }
+
+    fn GetFloatv(pname: GLenum, data: *mut GLfloat) {
+        with(|gl, s| {
+            let value = s.parameter(gl, pname);
+            write_numbers(&s.numbers(&value), data, |v| v as GLfloat);
+        })
+    }
+
+    fn GetInternalformativ(target: GLenum, internalformat: GLenum, pname: GLenum,
+                           buf_size: GLsizei, params: *mut GLint)
+    {
+        with(|gl, s| {
+            let samples = gl.get_internalformat_parameter(target, internalformat, gl::SAMPLES)
+                            .unwrap_or(JsValue::NULL);
+            let samples = s.numbers(&samples);
+
+            match pname {
+                gl::NUM_SAMPLE_COUNTS if buf_size >= 1 => *params = samples.len() as GLint,
+                gl::SAMPLES => {
+                    for (index, &value) in samples.iter().take(buf_size.max(0) as usize)
+                                                  .enumerate()
+                    {
+                        *params.add(index) = to_int(value);
+                    }
+                },
+                _ => s.set_error(gl::INVALID_ENUM),
+            }
+        })
+    }
+
+    fn Finish() {
+        with(|gl, _| gl.finish())
+    }
+
+    fn Flush() {
+        with(|gl, _| gl.flush())
+    }
+
+    // state
+
+    fn Enable(cap: GLenum) {
+        with(|gl, _| gl.enable(cap))
+    }
+
+    fn Disable(cap: GLenum) {
+        with(|gl, _| gl.disable(cap))
+    }
+
+    fn IsEnabled(cap: GLenum) -> GLboolean {
+        with(|gl, _| if gl.is_enabled(cap) { gl::TRUE } else { gl::FALSE })
+    }
+
+    fn Hint(target: GLenum, mode: GLenum) {
+        with(|gl, _| gl.hint(target, mode))
+    }
+
+    fn PixelStorei(pname: GLenum, param: GLint) {
+        with(|gl, s| {
+            let value = param.max(0) as usize;
+            match pname {
+                gl::PACK_ALIGNMENT => s.pack.alignment = value.max(1),
+                gl::PACK_ROW_LENGTH => s.pack.row_length = value,
+                gl::PACK_SKIP_PIXELS => s.pack.skip_pixels = value,
+                gl::PACK_SKIP_ROWS => s.pack.skip_rows = value,
+                gl::UNPACK_ALIGNMENT => s.unpack.alignment = value.max(1),
+                gl::UNPACK_ROW_LENGTH => s.unpack.row_length = value,
+                gl::UNPACK_IMAGE_HEIGHT => s.unpack.image_height = value,
+                gl::UNPACK_SKIP_PIXELS => s.unpack.skip_pixels = value,
+                gl::UNPACK_SKIP_ROWS => s.unpack.skip_rows = value,
+                gl::UNPACK_SKIP_IMAGES => s.unpack.skip_images = value,
+                _ => (),
+            }
+            gl.pixel_storei(pname, param);
+        })
+    }
+
+    fn BlendColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+        with(|gl, _| gl.blend_color(red, green, blue, alpha))
+    }
+
+    fn BlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum) {
+        with(|gl, _| gl.blend_equation_separate(mode_rgb, mode_alpha))
+    }
+
+    fn BlendFuncSeparate(src_rgb: GLenum, dst_rgb: GLenum, src_alpha: GLenum,
+                         dst_alpha: GLenum)
+    {
+        with(|gl, _| gl.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha))
+    }
+
+    fn ColorMask(red: GLboolean, green: GLboolean, blue: GLboolean, alpha: GLboolean) {
+        with(|gl, _| gl.color_mask(to_boolean(red), to_boolean(green), to_boolean(blue),
+                                   to_boolean(alpha)))
+    }
+
+    fn CullFace(mode: GLenum) {
+        with(|gl, _| gl.cull_face(mode))
+    }
+
+    fn FrontFace(mode: GLenum) {
+        with(|gl, _| gl.front_face(mode))
+    }
+
+    fn DepthFunc(func: GLenum) {
+        with(|gl, _| gl.depth_func(func))
+    }
+
+    fn DepthMask(flag: GLboolean) {
+        with(|gl, _| gl.depth_mask(to_boolean(flag)))
+    }
+
+    fn DepthRange(near: GLdouble, far: GLdouble) {
+        with(|gl, _| gl.depth_range(near as f32, far as f32))
+    }
+
+    fn DepthRangef(near: GLfloat, far: GLfloat) {
+        with(|gl, _| gl.depth_range(near, far))
+    }
+
+    fn LineWidth(width: GLfloat) {
+        with(|gl, _| gl.line_width(width))
+    }
+
+    fn PolygonOffset(factor: GLfloat, units: GLfloat) {
+        with(|gl, _| gl.polygon_offset(factor, units))
+    }
+
+    fn SampleCoverage(value: GLfloat, invert: GLboolean) {
+        with(|gl, _| gl.sample_coverage(value, to_boolean(invert)))
+    }
+
+    fn Scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        with(|gl, _| gl.scissor(x, y, width, height))
+    }
+
+    fn Viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        with(|gl, _| gl.viewport(x, y, width, height))
+    }
+
+    fn StencilFunc(func: GLenum, reference: GLint, mask: GLuint) {
+        with(|gl, _| gl.stencil_func(func, reference, mask))
+    }
+
+    fn StencilFuncSeparate(face: GLenum, func: GLenum, reference: GLint, mask: GLuint) {
+        with(|gl, _| gl.stencil_func_separate(face, func, reference, mask))
+    }
+
+    fn StencilMask(mask: GLuint) {
+        with(|gl, _| gl.stencil_mask(mask))
+    }
+
+    fn StencilMaskSeparate(face: GLenum, mask: GLuint) {
+        with(|gl, _| gl.stencil_mask_separate(face, mask))
+    }
+
+    fn StencilOp(fail: GLenum, zfail: GLenum, zpass: GLenum) {
+        with(|gl, _| gl.stencil_op(fail, zfail, zpass))
+    }
+
+    fn StencilOpSeparate(face: GLenum, fail: GLenum, zfail: GLenum, zpass: GLenum) {
+        with(|gl, _| gl.stencil_op_separate(face, fail, zfail, zpass))
+    }
+
+    // clearing
+
+    fn Clear(mask: GLbitfield) {
+        with(|gl, _| gl.clear(mask))
+    }
+
+    fn ClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+        with(|gl, _| gl.clear_color(red, green, blue, alpha))
+    }
+
+    fn ClearDepth(depth: GLdouble) {
+        with(|gl, _| gl.clear_depth(depth as f32))
+    }
+
+    fn ClearDepthf(depth: GLfloat) {
+        with(|gl, _| gl.clear_depth(depth))
+    }
+
+    fn ClearStencil(stencil: GLint) {
+        with(|gl, _| gl.clear_stencil(stencil))
+    }
+
+    fn ClearBufferfv(buffer: GLenum, drawbuffer: GLint, value: *const GLfloat) {
+        let len = if buffer == gl::COLOR { 4 } else { 1 };
+        with(|gl, _| gl.clear_bufferfv_with_f32_array(buffer, drawbuffer,
+                                                      slice::from_raw_parts(value, len)))
+    }
+
+    fn ClearBufferiv(buffer: GLenum, drawbuffer: GLint, value: *const GLint) {
+        let len = if buffer == gl::COLOR { 4 } else { 1 };
+        with(|gl, _| gl.clear_bufferiv_with_i32_array(buffer, drawbuffer,
+                                                      slice::from_raw_parts(value, len)))
+    }
+
+    fn ClearBufferuiv(buffer: GLenum, drawbuffer: GLint, value: *const GLuint) {
+        with(|gl, _| gl.clear_bufferuiv_with_u32_array(buffer, drawbuffer,
+                                                       slice::from_raw_parts(value, 4)))
+    }
+
+    fn ClearBufferfi(buffer: GLenum, drawbuffer: GLint, depth: GLfloat, stencil: GLint) {
+        with(|gl, _| gl.clear_bufferfi(buffer, drawbuffer, depth, stencil))
+    }
+
+    // buffers
+
+    fn GenBuffers(n: GLsizei, buffers: *mut GLuint) {
+        with(|gl, s| generate(&mut s.buffers, n, buffers, || gl.create_buffer().map(Into::into)))
+    }
+
+    fn DeleteBuffers(n: GLsizei, buffers: *const GLuint) {
+        with(|gl, s| {
+            for &name in slice::from_raw_parts(buffers, n.max(0) as usize) {
+                if s.pack_buffer == name { s.pack_buffer = 0; }
+                if s.unpack_buffer == name { s.unpack_buffer = 0; }
+                s.mappings.retain(|m| m.buffer != name);
+            }
+            delete(&mut s.buffers, n, buffers, |b| gl.delete_buffer(Some(b.unchecked_ref())))
+        })
+    }
+
+    fn BindBuffer(target: GLenum, buffer: GLuint) {
+        with(|gl, s| {
+            match target {
+                gl::PIXEL_PACK_BUFFER => s.pack_buffer = buffer,
+                gl::PIXEL_UNPACK_BUFFER => s.unpack_buffer = buffer,
+                _ => (),
+            }
+            gl.bind_buffer(target, s.buffers.get::<WebGlBuffer>(buffer))
+        })
+    }
+
+    fn BindBufferBase(target: GLenum, index: GLuint, buffer: GLuint) {
+        with(|gl, s| gl.bind_buffer_base(target, index, s.buffers.get::<WebGlBuffer>(buffer)))
+    }
+
+    fn BindBufferRange(target: GLenum, index: GLuint, buffer: GLuint, offset: GLintptr,
+                       size: GLsizeiptr)
+    {
+        with(|gl, s| gl.bind_buffer_range_with_i32_and_i32(target, index,
+                                                           s.buffers.get::<WebGlBuffer>(buffer),
+                                                           offset as i32, size as i32))
+    }
+
+    fn BufferData(target: GLenum, size: GLsizeiptr, data: *const c_void, usage: GLenum) {
+        with(|gl, _| {
+            if data.is_null() {
+                gl.buffer_data_with_i32(target, size as i32, usage);
+            } else {
+                let data = slice::from_raw_parts(data as *const u8, size as usize);
+                gl.buffer_data_with_u8_array(target, data, usage);
+            }
+        })
+    }
+
+    fn BufferSubData(target: GLenum, offset: GLintptr, size: GLsizeiptr, data: *const c_void) {
+        with(|gl, _| {
+            let data = slice::from_raw_parts(data as *const u8, size as usize);
+            gl.buffer_sub_data_with_i32_and_u8_array(target, offset as i32, data)
+        })
+    }
+
+    fn GetBufferSubData(target: GLenum, offset: GLintptr, size: GLsizeiptr, data: *mut c_void) {
+        with(|gl, _| {
+            let data = slice::from_raw_parts_mut(data as *mut u8, size as usize);
+            gl.get_buffer_sub_data_with_i32_and_u8_array(target, offset as i32, data)
+        })
+    }
+
+    fn CopyBufferSubData(read_target: GLenum, write_target: GLenum, read_offset: GLintptr,
+                         write_offset: GLintptr, size: GLsizeiptr)
+    {
+        with(|gl, _| gl.copy_buffer_sub_data_with_i32_and_i32_and_i32(read_target, write_target,
+                                                                      read_offset as i32,
+                                                                      write_offset as i32,
+                                                                      size as i32))
+    }
+
+    fn GetBufferParameteriv(target: GLenum, pname: GLenum, params: *mut GLint) {
+        with(|gl, s| {
+            let numbers = match pname {
+                gl::BUFFER_SIZE | gl::BUFFER_USAGE => s.numbers(&gl.get_buffer_parameter(target,
+                                                                                        pname)),
+                gl::BUFFER_MAPPED => {
+                    let buffer = s.bound_buffer(gl, target);
+                    vec![s.mappings.iter().any(|m| m.buffer == buffer) as u8 as f64]
+                },
+                _ => Vec::new(),
+            };
+            write_numbers(&numbers, params, to_int);
+        })
+    }
+
+    fn MapBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr,
+                      access: GLbitfield) -> *mut c_void
+    {
+        with(|gl, s| {
+            let buffer = s.bound_buffer(gl, target);
+            if buffer == 0 || s.mappings.iter().any(|m| m.buffer == buffer) {
+                s.set_error(gl::INVALID_OPERATION);
+                return ptr::null_mut();
+            }
+
+            let mut data = vec![0u8; length.max(0) as usize].into_boxed_slice();
+            if access & gl::MAP_READ_BIT != 0 {
+                gl.get_buffer_sub_data_with_i32_and_u8_array(target, offset as i32, &mut data);
+            }
+
+            let ptr = data.as_mut_ptr();
+            s.mappings.push(Mapping { buffer, offset: offset as i32, data, access });
+            ptr as *mut c_void
+        })
+    }
+
+    fn FlushMappedBufferRange(target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+        with(|gl, s| {
+            let buffer = s.bound_buffer(gl, target);
+            if let Some(mapping) = s.mappings.iter().find(|m| m.buffer == buffer) {
+                let range = offset as usize .. (offset + length) as usize;
+                gl.buffer_sub_data_with_i32_and_u8_array(target, mapping.offset + offset as i32,
+                                                         &mapping.data[range]);
+            }
+        })
+    }
+
+    fn UnmapBuffer(target: GLenum) -> GLboolean {
+        with(|gl, s| {
+            let buffer = s.bound_buffer(gl, target);
+            let mapping = match s.mappings.iter().position(|m| m.buffer == buffer) {
+                Some(index) => s.mappings.remove(index),
+                None => {
+                    s.set_error(gl::INVALID_OPERATION);
+                    return gl::FALSE;
+                },
+            };
+
+            if mapping.access & gl::MAP_WRITE_BIT != 0 &&
+               mapping.access & gl::MAP_FLUSH_EXPLICIT_BIT == 0
+            {
+                gl.buffer_sub_data_with_i32_and_u8_array(target, mapping.offset, &mapping.data);
+            }
+
+            gl::TRUE
+        })
+    }
+
+    // vertex arrays
+
+    fn GenVertexArrays(n: GLsizei, arrays: *mut GLuint) {
+        with(|gl, s| generate(&mut s.vertex_arrays, n, arrays,
+                              || gl.create_vertex_array().map(Into::into)))
+    }
+
+    fn DeleteVertexArrays(n: GLsizei, arrays: *const GLuint) {
+        with(|gl, s| delete(&mut s.vertex_arrays, n, arrays,
+                            |a| gl.delete_vertex_array(Some(a.unchecked_ref()))))
+    }
+
+    fn BindVertexArray(array: GLuint) {
+        with(|gl, s| gl.bind_vertex_array(s.vertex_arrays.get::<WebGlVertexArrayObject>(array)))
+    }
+
+    fn EnableVertexAttribArray(index: GLuint) {
+        with(|gl, _| gl.enable_vertex_attrib_array(index))
+    }
+
+    fn DisableVertexAttribArray(index: GLuint) {
+        with(|gl, _| gl.disable_vertex_attrib_array(index))
+    }
+
+    fn VertexAttribPointer(index: GLuint, size: GLint, ty: GLenum, normalized: GLboolean,
+                           stride: GLsizei, pointer: *const c_void)
+    {
+        with(|gl, _| gl.vertex_attrib_pointer_with_i32(index, size, ty, to_boolean(normalized),
+                                                       stride, pointer as i32))
+    }
+
+    fn VertexAttribIPointer(index: GLuint, size: GLint, ty: GLenum, stride: GLsizei,
+                            pointer: *const c_void)
+    {
+        with(|gl, _| gl.vertex_attrib_i_pointer_with_i32(index, size, ty, stride, pointer as i32))
+    }
+
+    fn VertexAttribDivisor(index: GLuint, divisor: GLuint) {
+        with(|gl, _| gl.vertex_attrib_divisor(index, divisor))
+    }
+
+    // drawing
+
+    fn DrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
+        with(|gl, _| gl.draw_arrays(mode, first, count))
+    }
+
+    fn DrawArraysInstanced(mode: GLenum, first: GLint, count: GLsizei, instances: GLsizei) {
+        with(|gl, _| gl.draw_arrays_instanced(mode, first, count, instances))
+    }
+
+    fn DrawElements(mode: GLenum, count: GLsizei, ty: GLenum, indices: *const c_void) {
+        with(|gl, _| gl.draw_elements_with_i32(mode, count, ty, indices as i32))
+    }
+
+    fn DrawElementsInstanced(mode: GLenum, count: GLsizei, ty: GLenum, indices: *const c_void,
+                             instances: GLsizei)
+    {
+        with(|gl, _| gl.draw_elements_instanced_with_i32(mode, count, ty, indices as i32,
+                                                         instances))
+    }
+
+    fn DrawRangeElements(mode: GLenum, start: GLuint, end: GLuint, count: GLsizei, ty: GLenum,
+                         indices: *const c_void)
+    {
+        with(|gl, _| gl.draw_range_elements_with_i32(mode, start, end, count, ty,
+                                                     indices as i32))
+    }
+
+    // textures
+
+    fn GenTextures(n: GLsizei, textures: *mut GLuint) {
+        with(|gl, s| generate(&mut s.textures, n, textures, || gl.create_texture().map(Into::into)))
+    }
+
+    fn DeleteTextures(n: GLsizei, textures: *const GLuint) {
+        with(|gl, s| delete(&mut s.textures, n, textures,
+                            |t| gl.delete_texture(Some(t.unchecked_ref()))))
+    }
+
+    fn BindTexture(target: GLenum, texture: GLuint) {
+        with(|gl, s| gl.bind_texture(target, s.textures.get::<WebGlTexture>(texture)))
+    }
+
+    fn ActiveTexture(texture: GLenum) {
+        with(|gl, _| gl.active_texture(texture))
+    }
+
+    fn TexParameteri(target: GLenum, pname: GLenum, param: GLint) {
+        with(|gl, _| gl.tex_parameteri(target, pname, param))
+    }
+
+    fn TexParameterf(target: GLenum, pname: GLenum, param: GLfloat) {
+        with(|gl, _| gl.tex_parameterf(target, pname, param))
+    }
+
+    fn TexStorage2D(target: GLenum, levels: GLsizei, internalformat: GLenum, width: GLsizei,
+                    height: GLsizei)
+    {
+        with(|gl, _| gl.tex_storage_2d(target, levels, internalformat, width, height))
+    }
+
+    fn TexStorage3D(target: GLenum, levels: GLsizei, internalformat: GLenum, width: GLsizei,
+                    height: GLsizei, depth: GLsizei)
+    {
+        with(|gl, _| gl.tex_storage_3d(target, levels, internalformat, width, height, depth))
+    }
+
+    fn TexImage2D(target: GLenum, level: GLint, internalformat: GLint, width: GLsizei,
+                  height: GLsizei, border: GLint, format: GLenum, ty: GLenum,
+                  pixels: *const c_void)
+    {
+        with(|gl, s| {
+            let result = if s.unpack_buffer != 0 {
+                gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_i32(
+                    target, level, internalformat, width, height, border, format, ty,
+                    pixels as i32)
+            } else {
+                let data = unpack_data(s, pixels, width, height, 1, format, ty);
+                gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                    target, level, internalformat, width, height, border, format, ty, data)
+            };
+
+            if result.is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    fn TexImage3D(target: GLenum, level: GLint, internalformat: GLint, width: GLsizei,
+                  height: GLsizei, depth: GLsizei, border: GLint, format: GLenum, ty: GLenum,
+                  pixels: *const c_void)
+    {
+        with(|gl, s| {
+            let result = if s.unpack_buffer != 0 {
+                gl.tex_image_3d_with_i32(target, level, internalformat, width, height, depth,
+                                         border, format, ty, pixels as i32)
+            } else {
+                let data = unpack_data(s, pixels, width, height, depth, format, ty);
+                gl.tex_image_3d_with_opt_u8_array(target, level, internalformat, width, height,
+                                                  depth, border, format, ty, data)
+            };
+
+            if result.is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    fn TexSubImage2D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                     width: GLsizei, height: GLsizei, format: GLenum, ty: GLenum,
+                     pixels: *const c_void)
+    {
+        with(|gl, s| {
+            let result = if s.unpack_buffer != 0 {
+                gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_i32(
+                    target, level, xoffset, yoffset, width, height, format, ty, pixels as i32)
+            } else {
+                let data = unpack_data(s, pixels, width, height, 1, format, ty);
+                gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+                    target, level, xoffset, yoffset, width, height, format, ty, data)
+            };
+
+            if result.is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    fn TexSubImage3D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                     zoffset: GLint, width: GLsizei, height: GLsizei, depth: GLsizei,
+                     format: GLenum, ty: GLenum, pixels: *const c_void)
+    {
+        with(|gl, s| {
+            let result = if s.unpack_buffer != 0 {
+                gl.tex_sub_image_3d_with_i32(target, level, xoffset, yoffset, zoffset, width,
+                                             height, depth, format, ty, pixels as i32)
+            } else {
+                let data = unpack_data(s, pixels, width, height, depth, format, ty);
+                gl.tex_sub_image_3d_with_opt_u8_array(target, level, xoffset, yoffset, zoffset,
+                                                      width, height, depth, format, ty, data)
+            };
+
+            if result.is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    fn CompressedTexImage2D(target: GLenum, level: GLint, internalformat: GLenum,
+                            width: GLsizei, height: GLsizei, border: GLint, size: GLsizei,
+                            data: *const c_void)
+    {
+        with(|gl, s| {
+            if s.unpack_buffer != 0 {
+                gl.compressed_tex_image_2d_with_i32_and_i32(target, level, internalformat, width,
+                                                            height, border, size, data as i32);
+            } else {
+                let data = slice::from_raw_parts(data as *const u8, size.max(0) as usize);
+                gl.compressed_tex_image_2d_with_u8_array(target, level, internalformat, width,
+                                                         height, border, data);
+            }
+        })
+    }
+
+    fn CompressedTexImage3D(target: GLenum, level: GLint, internalformat: GLenum,
+                            width: GLsizei, height: GLsizei, depth: GLsizei, border: GLint,
+                            size: GLsizei, data: *const c_void)
+    {
+        with(|gl, s| {
+            if s.unpack_buffer != 0 {
+                gl.compressed_tex_image_3d_with_i32_and_i32(target, level, internalformat, width,
+                                                            height, depth, border, size,
+                                                            data as i32);
+            } else {
+                let data = slice::from_raw_parts(data as *const u8, size.max(0) as usize);
+                gl.compressed_tex_image_3d_with_u8_array(target, level, internalformat, width,
+                                                         height, depth, border, data);
+            }
+        })
+    }
+
+    fn CompressedTexSubImage2D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                               width: GLsizei, height: GLsizei, format: GLenum, size: GLsizei,
+                               data: *const c_void)
+    {
+        with(|gl, s| {
+            if s.unpack_buffer != 0 {
+                gl.compressed_tex_sub_image_2d_with_i32_and_i32(target, level, xoffset, yoffset,
+                                                                width, height, format, size,
+                                                                data as i32);
+            } else {
+                let data = slice::from_raw_parts(data as *const u8, size.max(0) as usize);
+                gl.compressed_tex_sub_image_2d_with_array_buffer_view(
+                    target, level, xoffset, yoffset, width, height, format,
+                    &Uint8Array::view(data));
+            }
+        })
+    }
+
+    fn CompressedTexSubImage3D(target: GLenum, level: GLint, xoffset: GLint, yoffset: GLint,
+                               zoffset: GLint, width: GLsizei, height: GLsizei, depth: GLsizei,
+                               format: GLenum, size: GLsizei, data: *const c_void)
+    {
+        with(|gl, s| {
+            if s.unpack_buffer != 0 {
+                gl.compressed_tex_sub_image_3d_with_i32_and_i32(target, level, xoffset, yoffset,
+                                                                zoffset, width, height, depth,
+                                                                format, size, data as i32);
+            } else {
+                let data = slice::from_raw_parts(data as *const u8, size.max(0) as usize);
+                gl.compressed_tex_sub_image_3d_with_array_buffer_view(
+                    target, level, xoffset, yoffset, zoffset, width, height, depth, format,
+                    &Uint8Array::view(data));
+            }
+        })
+    }
+
+    fn GenerateMipmap(target: GLenum) {
+        with(|gl, _| gl.generate_mipmap(target))
+    }
+
+    fn ReadPixels(x: GLint, y: GLint, width: GLsizei, height: GLsizei, format: GLenum,
+                  ty: GLenum, pixels: *mut c_void)
+    {
+        with(|gl, s| {
+            let result = if s.pack_buffer != 0 {
+                gl.read_pixels_with_i32(x, y, width, height, format, ty, pixels as i32)
+            } else {
+                let len = s.pack.image_size(width, height, 1, format, ty);
+                let data = slice::from_raw_parts_mut(pixels as *mut u8, len);
+                gl.read_pixels_with_opt_u8_array(x, y, width, height, format, ty, Some(data))
+            };
+
+            if result.is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    // samplers
+
+    fn GenSamplers(n: GLsizei, samplers: *mut GLuint) {
+        with(|gl, s| generate(&mut s.samplers, n, samplers, || gl.create_sampler().map(Into::into)))
+    }
+
+    fn DeleteSamplers(n: GLsizei, samplers: *const GLuint) {
+        with(|gl, s| delete(&mut s.samplers, n, samplers,
+                            |o| gl.delete_sampler(Some(o.unchecked_ref()))))
+    }
+
+    fn BindSampler(unit: GLuint, sampler: GLuint) {
+        with(|gl, s| gl.bind_sampler(unit, s.samplers.get::<WebGlSampler>(sampler)))
+    }
+
+    fn SamplerParameteri(sampler: GLuint, pname: GLenum, param: GLint) {
+        with(|gl, s| {
+            if let Some(sampler) = s.samplers.get::<WebGlSampler>(sampler) {
+                gl.sampler_parameteri(sampler, pname, param);
+            }
+        })
+    }
+
+    fn SamplerParameterf(sampler: GLuint, pname: GLenum, param: GLfloat) {
+        with(|gl, s| {
+            if let Some(sampler) = s.samplers.get::<WebGlSampler>(sampler) {
+                gl.sampler_parameterf(sampler, pname, param);
+            }
+        })
+    }
+
+    // framebuffers and renderbuffers
+
+    fn GenFramebuffers(n: GLsizei, framebuffers: *mut GLuint) {
+        with(|gl, s| generate(&mut s.framebuffers, n, framebuffers,
+                              || gl.create_framebuffer().map(Into::into)))
+    }
+
+    fn DeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint) {
+        with(|gl, s| delete(&mut s.framebuffers, n, framebuffers,
+                            |f| gl.delete_framebuffer(Some(f.unchecked_ref()))))
+    }
+
+    fn BindFramebuffer(target: GLenum, framebuffer: GLuint) {
+        with(|gl, s| gl.bind_framebuffer(target, s.framebuffers.get::<WebGlFramebuffer>(framebuffer)))
+    }
+
+    fn CheckFramebufferStatus(target: GLenum) -> GLenum {
+        with(|gl, _| gl.check_framebuffer_status(target))
+    }
+
+    fn FramebufferTexture2D(target: GLenum, attachment: GLenum, textarget: GLenum,
+                            texture: GLuint, level: GLint)
+    {
+        with(|gl, s| gl.framebuffer_texture_2d(target, attachment, textarget,
+                                               s.textures.get::<WebGlTexture>(texture), level))
+    }
+
+    fn FramebufferTextureLayer(target: GLenum, attachment: GLenum, texture: GLuint,
+                               level: GLint, layer: GLint)
+    {
+        with(|gl, s| gl.framebuffer_texture_layer(target, attachment,
+                                                  s.textures.get::<WebGlTexture>(texture),
+                                                  level, layer))
+    }
+
+    fn FramebufferRenderbuffer(target: GLenum, attachment: GLenum, renderbuffertarget: GLenum,
+                               renderbuffer: GLuint)
+    {
+        with(|gl, s| gl.framebuffer_renderbuffer(target, attachment, renderbuffertarget,
+                                                 s.renderbuffers
+                                                  .get::<WebGlRenderbuffer>(renderbuffer)))
+    }
+
+    fn GetFramebufferAttachmentParameteriv(target: GLenum, attachment: GLenum, pname: GLenum,
+                                           params: *mut GLint)
+    {
+        // the default framebuffer of WebGL only has a back buffer
+        let attachment = match attachment {
+            gl::FRONT | gl::FRONT_LEFT | gl::BACK_LEFT => gl::BACK,
+            attachment => attachment,
+        };
+
+        with(|gl, s| {
+            let value = gl.get_framebuffer_attachment_parameter(target, attachment, pname)
+                          .unwrap_or(JsValue::NULL);
+            write_numbers(&s.numbers(&value), params, to_int);
+        })
+    }
+
+    fn DrawBuffers(n: GLsizei, bufs: *const GLenum) {
+        with(|gl, _| {
+            let buffers = slice::from_raw_parts(bufs, n.max(0) as usize).iter()
+                               .map(|&b| JsValue::from(b)).collect::<Array>();
+            gl.draw_buffers(&buffers)
+        })
+    }
+
+    fn ReadBuffer(src: GLenum) {
+        with(|gl, _| gl.read_buffer(src))
+    }
+
+    fn BlitFramebuffer(src_x0: GLint, src_y0: GLint, src_x1: GLint, src_y1: GLint,
+                       dst_x0: GLint, dst_y0: GLint, dst_x1: GLint, dst_y1: GLint,
+                       mask: GLbitfield, filter: GLenum)
+    {
+        with(|gl, _| gl.blit_framebuffer(src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1,
+                                         dst_y1, mask, filter))
+    }
+
+    fn InvalidateFramebuffer(target: GLenum, num_attachments: GLsizei,
+                             attachments: *const GLenum)
+    {
+        with(|gl, s| {
+            let attachments = slice::from_raw_parts(attachments, num_attachments.max(0) as usize)
+                                   .iter().map(|&a| JsValue::from(a)).collect::<Array>();
+            if gl.invalidate_framebuffer(target, &attachments).is_err() {
+                s.set_error(gl::INVALID_OPERATION);
+            }
+        })
+    }
+
+    fn GenRenderbuffers(n: GLsizei, renderbuffers: *mut GLuint) {
+        with(|gl, s| generate(&mut s.renderbuffers, n, renderbuffers,
+                              || gl.create_renderbuffer().map(Into::into)))
+    }
+
+    fn DeleteRenderbuffers(n: GLsizei, renderbuffers: *const GLuint) {
+        with(|gl, s| delete(&mut s.renderbuffers, n, renderbuffers,
+                            |r| gl.delete_renderbuffer(Some(r.unchecked_ref()))))
+    }
+
+    fn BindRenderbuffer(target: GLenum, renderbuffer: GLuint) {
+        with(|gl, s| gl.bind_renderbuffer(target,
+                                          s.renderbuffers.get::<WebGlRenderbuffer>(renderbuffer)))
+    }
+
+    fn RenderbufferStorage(target: GLenum, internalformat: GLenum, width: GLsizei,
+                           height: GLsizei)
+    {
+        with(|gl, _| gl.renderbuffer_storage(target, internalformat, width, height))
+    }
+
+    fn RenderbufferStorageMultisample(target: GLenum, samples: GLsizei, internalformat: GLenum,
+                                      width: GLsizei, height: GLsizei)
+    {
+        with(|gl, _| gl.renderbuffer_storage_multisample(target, samples, internalformat, width,
+                                                         height))
+    }
+
+    fn GetRenderbufferParameteriv(target: GLenum, pname: GLenum, params: *mut GLint) {
+        with(|gl, s| write_numbers(&s.numbers(&gl.get_renderbuffer_parameter(target, pname)),
+                                   params, to_int))
+    }
+
+    // shaders and programs
+
+    fn CreateShader(ty: GLenum) -> GLuint {
+        with(|gl, s| gl.create_shader(ty).map_or(0, |shader| s.programs.insert(shader.into())))
+    }
+
+    fn DeleteShader(shader: GLuint) {
+        with(|gl, s| {
+            if let Some(shader) = s.programs.remove(shader) {
+                gl.delete_shader(Some(shader.unchecked_ref()));
+            }
+        })
+    }
+
+    fn ShaderSource(shader: GLuint, count: GLsizei, strings: *const *const GLchar,
+                    lengths: *const GLint)
+    {
+        let mut source = String::new();
+        for index in 0 .. count.max(0) as usize {
+            let string = *strings.add(index);
+            let length = if lengths.is_null() { -1 } else { *lengths.add(index) };
+
+            if length < 0 {
+                source.push_str(c_str(string));
+            } else {
+                let bytes = slice::from_raw_parts(string as *const u8, length as usize);
+                source.push_str(&String::from_utf8_lossy(bytes));
+            }
+        }
+
+        with(|gl, s| {
+            if let Some(shader) = s.programs.get::<WebGlShader>(shader) {
+                gl.shader_source(shader, &source);
+            }
+        })
+    }
+
+    fn CompileShader(shader: GLuint) {
+        with(|gl, s| {
+            if let Some(shader) = s.programs.get::<WebGlShader>(shader) {
+                gl.compile_shader(shader);
+            }
+        })
+    }
+
+    fn GetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint) {
+        with(|gl, s| {
+            let shader = match s.programs.get::<WebGlShader>(shader) {
+                Some(shader) => shader,
+                None => return s.set_error(gl::INVALID_VALUE),
+            };
+
+            *params = match pname {
+                gl::INFO_LOG_LENGTH => {
+                    gl.get_shader_info_log(shader).map_or(0, |log| log.len() as GLint + 1)
+                },
+                gl::SHADER_SOURCE_LENGTH => {
+                    gl.get_shader_source(shader).map_or(0, |src| src.len() as GLint + 1)
+                },
+                _ => {
+                    let value = gl.get_shader_parameter(shader, pname);
+                    s.numbers(&value).first().cloned().map_or(0, to_int)
+                },
+            };
+        })
+    }
+
+    fn GetShaderInfoLog(shader: GLuint, buf_size: GLsizei, length: *mut GLsizei,
+                        info_log: *mut GLchar)
+    {
+        with(|gl, s| {
+            let log = s.programs.get::<WebGlShader>(shader)
+                       .and_then(|shader| gl.get_shader_info_log(shader)).unwrap_or_default();
+            write_string(&log, buf_size, length, info_log);
+        })
+    }
+
+    fn CreateProgram() -> GLuint {
+        with(|gl, s| gl.create_program().map_or(0, |program| s.programs.insert(program.into())))
+    }
+
+    fn DeleteProgram(program: GLuint) {
+        with(|gl, s| {
+            s.locations.remove(&program);
+            if let Some(program) = s.programs.remove(program) {
+                gl.delete_program(Some(program.unchecked_ref()));
+            }
+        })
+    }
+
+    fn AttachShader(program: GLuint, shader: GLuint) {
+        with(|gl, s| {
+            if let (Some(program), Some(shader)) = (s.programs.get::<WebGlProgram>(program),
+                                                    s.programs.get::<WebGlShader>(shader))
+            {
+                gl.attach_shader(program, shader);
+            }
+        })
+    }
+
+    fn DetachShader(program: GLuint, shader: GLuint) {
+        with(|gl, s| {
+            if let (Some(program), Some(shader)) = (s.programs.get::<WebGlProgram>(program),
+                                                    s.programs.get::<WebGlShader>(shader))
+            {
+                gl.detach_shader(program, shader);
+            }
+        })
+    }
+
+    fn BindAttribLocation(program: GLuint, index: GLuint, name: *const GLchar) {
+        with(|gl, s| {
+            if let Some(program) = s.programs.get::<WebGlProgram>(program) {
+                gl.bind_attrib_location(program, index, c_str(name));
+            }
+        })
+    }
+
+    fn TransformFeedbackVaryings(program: GLuint, count: GLsizei,
+                                 varyings: *const *const GLchar, buffer_mode: GLenum)
+    {
+        with(|gl, s| {
+            if let Some(program) = s.programs.get::<WebGlProgram>(program) {
+                let varyings = (0 .. count.max(0) as usize)
+                    .map(|i| JsValue::from_str(c_str(*varyings.add(i))))
+                    .collect::<Array>();
+                gl.transform_feedback_varyings(program, &varyings, buffer_mode);
+            }
+        })
+    }
+
+    fn LinkProgram(program: GLuint) {
+        with(|gl, s| {
+            // linking invalidates the uniform locations
+            s.locations.remove(&program);
+            if let Some(program) = s.programs.get::<WebGlProgram>(program) {
+                gl.link_program(program);
+            }
+        })
+    }
+
+    fn UseProgram(program: GLuint) {
+        with(|gl, s| {
+            s.current_program = program;
+            gl.use_program(s.programs.get::<WebGlProgram>(program))
+        })
+    }
+
+    fn GetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint) {
+        with(|gl, s| {
+            let program = match s.programs.get::<WebGlProgram>(program) {
+                Some(program) => program,
+                None => return s.set_error(gl::INVALID_VALUE),
+            };
+
+            // the number of objects that the `*_MAX_LENGTH` parameters apply to
+            let count = |pname| {
+                let value = gl.get_program_parameter(program, pname);
+                value.as_f64().unwrap_or(0.0) as u32
+            };
+
+            *params = match pname {
+                gl::INFO_LOG_LENGTH => {
+                    gl.get_program_info_log(program).map_or(0, |log| log.len() as GLint + 1)
+                },
+                gl::ACTIVE_ATTRIBUTE_MAX_LENGTH => {
+                    max_name_length(count(gl::ACTIVE_ATTRIBUTES),
+                                    |i| gl.get_active_attrib(program, i).map(|a| a.name()))
+                },
+                gl::ACTIVE_UNIFORM_MAX_LENGTH => {
+                    max_name_length(count(gl::ACTIVE_UNIFORMS),
+                                    |i| gl.get_active_uniform(program, i).map(|u| u.name()))
+                },
+                gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH => {
+                    max_name_length(count(gl::ACTIVE_UNIFORM_BLOCKS),
+                                    |i| gl.get_active_uniform_block_name(program, i))
+                },
+                gl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH => {
+                    max_name_length(count(gl::TRANSFORM_FEEDBACK_VARYINGS),
+                                    |i| gl.get_transform_feedback_varying(program, i)
+                                          .map(|v| v.name()))
+                },
+                gl::DELETE_STATUS | gl::LINK_STATUS | gl::VALIDATE_STATUS |
+                gl::ATTACHED_SHADERS | gl::ACTIVE_ATTRIBUTES | gl::ACTIVE_UNIFORMS |
+                gl::ACTIVE_UNIFORM_BLOCKS | gl::TRANSFORM_FEEDBACK_BUFFER_MODE |
+                gl::TRANSFORM_FEEDBACK_VARYINGS => {
+                    let value = gl.get_program_parameter(program, pname);
+                    s.numbers(&value).first().cloned().map_or(0, to_int)
+                },
+                _ => 0,
+            };
+        })
+    }
+
+    fn GetProgramInfoLog(program: GLuint, buf_size: GLsizei, length: *mut GLsizei,
+                         info_log: *mut GLchar)
+    {
+        with(|gl, s| {
+            let log = s.programs.get::<WebGlProgram>(program)
+                       .and_then(|program| gl.get_program_info_log(program)).unwrap_or_default();
+            write_string(&log, buf_size, length, info_log);
+        })
+    }
+
+    fn GetActiveAttrib(program: GLuint, index: GLuint, buf_size: GLsizei, length: *mut GLsizei,
+                       size: *mut GLint, ty: *mut GLenum, name: *mut GLchar)
+    {
+        with(|gl, s| {
+            let info = s.programs.get::<WebGlProgram>(program)
+                        .and_then(|program| gl.get_active_attrib(program, index));
+            write_active_info(s, info, buf_size, length, size, ty, name);
+        })
+    }
+
+    fn GetActiveUniform(program: GLuint, index: GLuint, buf_size: GLsizei,
+                        length: *mut GLsizei, size: *mut GLint, ty: *mut GLenum,
+                        name: *mut GLchar)
+    {
+        with(|gl, s| {
+            let info = s.programs.get::<WebGlProgram>(program)
+                        .and_then(|program| gl.get_active_uniform(program, index));
+            write_active_info(s, info, buf_size, length, size, ty, name);
+        })
+    }
+
+    fn GetTransformFeedbackVarying(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                   length: *mut GLsizei, size: *mut GLsizei, ty: *mut GLenum,
+                                   name: *mut GLchar)
+    {
+        with(|gl, s| {
+            let info = s.programs.get::<WebGlProgram>(program)
+                        .and_then(|program| gl.get_transform_feedback_varying(program, index));
+            write_active_info(s, info, buf_size, length, size, ty, name);
+        })
+    }
+
+    fn GetActiveUniformName(program: GLuint, index: GLuint, buf_size: GLsizei,
+                            length: *mut GLsizei, name: *mut GLchar)
+    {
+        with(|gl, s| {
+            let info = s.programs.get::<WebGlProgram>(program)
+                        .and_then(|program| gl.get_active_uniform(program, index));
+            write_string(&info.map(|i| i.name()).unwrap_or_default(), buf_size, length, name);
+        })
+    }
+
+    fn GetActiveUniformsiv(program: GLuint, count: GLsizei, indices: *const GLuint,
+                           pname: GLenum, params: *mut GLint)
+    {
+        with(|gl, s| {
+            let program = match s.programs.get::<WebGlProgram>(program) {
+                Some(program) => program,
+                None => return s.set_error(gl::INVALID_VALUE),
+            };
+            let indices = slice::from_raw_parts(indices, count.max(0) as usize);
+
+            let numbers = if pname == gl::UNIFORM_NAME_LENGTH {
+                indices.iter().map(|&index| {
+                    gl.get_active_uniform(program, index)
+                      .map_or(0.0, |info| info.name().len() as f64 + 1.0)
+                }).collect()
+            } else {
+                let indices = indices.iter().map(|&i| JsValue::from(i)).collect::<Array>();
+                s.numbers(&gl.get_active_uniforms(program, &indices, pname))
+            };
+
+            for (index, &value) in numbers.iter().enumerate() {
+                *params.add(index) = to_int(value);
+            }
+        })
+    }
+
+    fn GetActiveUniformBlockiv(program: GLuint, index: GLuint, pname: GLenum,
+                               params: *mut GLint)
+    {
+        with(|gl, s| {
+            let program = match s.programs.get::<WebGlProgram>(program) {
+                Some(program) => program,
+                None => return s.set_error(gl::INVALID_VALUE),
+            };
+
+            let numbers = if pname == gl::UNIFORM_BLOCK_NAME_LENGTH {
+                let name = gl.get_active_uniform_block_name(program, index);
+                vec![name.map_or(0.0, |name| name.len() as f64 + 1.0)]
+            } else {
+                let value = gl.get_active_uniform_block_parameter(program, index, pname)
+                              .unwrap_or(JsValue::NULL);
+                s.numbers(&value)
+            };
+
+            write_numbers(&numbers, params, to_int);
+        })
+    }
+
+    fn GetActiveUniformBlockName(program: GLuint, index: GLuint, buf_size: GLsizei,
+                                 length: *mut GLsizei, name: *mut GLchar)
+    {
+        with(|gl, s| {
+            let block = s.programs.get::<WebGlProgram>(program)
+                         .and_then(|program| gl.get_active_uniform_block_name(program, index));
+            write_string(&block.unwrap_or_default(), buf_size, length, name);
+        })
+    }
+
+    fn GetUniformBlockIndex(program: GLuint, name: *const GLchar) -> GLuint {
+        with(|gl, s| {
+            s.programs.get::<WebGlProgram>(program)
+             .map_or(gl::INVALID_INDEX, |p| gl.get_uniform_block_index(p, c_str(name)))
+        })
+    }
+
+    fn UniformBlockBinding(program: GLuint, index: GLuint, binding: GLuint) {
+        with(|gl, s| {
+            if let Some(program) = s.programs.get::<WebGlProgram>(program) {
+                gl.uniform_block_binding(program, index, binding);
+            }
+        })
+    }
+
+    fn GetAttribLocation(program: GLuint, name: *const GLchar) -> GLint {
+        with(|gl, s| {
+            s.programs.get::<WebGlProgram>(program)
+             .map_or(-1, |program| gl.get_attrib_location(program, c_str(name)))
+        })
+    }
+
+    fn GetFragDataLocation(program: GLuint, name: *const GLchar) -> GLint {
+        with(|gl, s| {
+            s.programs.get::<WebGlProgram>(program)
+             .map_or(-1, |program| gl.get_frag_data_location(program, c_str(name)))
+        })
+    }
+
+    fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+        with(|gl, s| {
+            let name = c_str(name);
+            let location = match s.programs.get::<WebGlProgram>(program)
+                                  .and_then(|p| gl.get_uniform_location(p, name))
+            {
+                Some(location) => location,
+                None => return -1,
+            };
+
+            let locations = s.locations.entry(program).or_default();
+            match locations.iter().position(|(n, _)| n == name) {
+                Some(index) => index as GLint,
+                None => {
+                    locations.push((name.to_owned(), location));
+                    locations.len() as GLint - 1
+                },
+            }
+        })
+    }
+
+    // uniforms of the current program
+
+    fn Uniform1i(location: GLint, v0: GLint) {
+        with(|gl, s| gl.uniform1i(s.location(location), v0))
+    }
+
+    fn Uniform1ui(location: GLint, v0: GLuint) {
+        with(|gl, s| gl.uniform1ui(s.location(location), v0))
+    }
+
+    fn Uniform1f(location: GLint, v0: GLfloat) {
+        with(|gl, s| gl.uniform1f(s.location(location), v0))
+    }
+
+    fn Uniform1fv(location: GLint, count: GLsizei, value: *const GLfloat) {
+        with(|gl, s| gl.uniform1fv_with_f32_array(s.location(location),
+                                                  slice::from_raw_parts(value, count as usize)))
+    }
+
+    fn Uniform2fv(location: GLint, count: GLsizei, value: *const GLfloat) {
+        with(|gl, s| gl.uniform2fv_with_f32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 2 * count as usize)))
+    }
+
+    fn Uniform3fv(location: GLint, count: GLsizei, value: *const GLfloat) {
+        with(|gl, s| gl.uniform3fv_with_f32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 3 * count as usize)))
+    }
+
+    fn Uniform4fv(location: GLint, count: GLsizei, value: *const GLfloat) {
+        with(|gl, s| gl.uniform4fv_with_f32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 4 * count as usize)))
+    }
+
+    fn Uniform1iv(location: GLint, count: GLsizei, value: *const GLint) {
+        with(|gl, s| gl.uniform1iv_with_i32_array(s.location(location),
+                                                  slice::from_raw_parts(value, count as usize)))
+    }
+
+    fn Uniform2iv(location: GLint, count: GLsizei, value: *const GLint) {
+        with(|gl, s| gl.uniform2iv_with_i32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 2 * count as usize)))
+    }
+
+    fn Uniform3iv(location: GLint, count: GLsizei, value: *const GLint) {
+        with(|gl, s| gl.uniform3iv_with_i32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 3 * count as usize)))
+    }
+
+    fn Uniform4iv(location: GLint, count: GLsizei, value: *const GLint) {
+        with(|gl, s| gl.uniform4iv_with_i32_array(s.location(location),
+                                                  slice::from_raw_parts(value, 4 * count as usize)))
+    }
+
+    fn Uniform1uiv(location: GLint, count: GLsizei, value: *const GLuint) {
+        with(|gl, s| gl.uniform1uiv_with_u32_array(s.location(location),
+                                                   slice::from_raw_parts(value, count as usize)))
+    }
+
+    fn Uniform2uiv(location: GLint, count: GLsizei, value: *const GLuint) {
+        with(|gl, s| gl.uniform2uiv_with_u32_array(s.location(location),
+                                                   slice::from_raw_parts(value,
+                                                                         2 * count as usize)))
+    }
+
+    fn Uniform3uiv(location: GLint, count: GLsizei, value: *const GLuint) {
+        with(|gl, s| gl.uniform3uiv_with_u32_array(s.location(location),
+                                                   slice::from_raw_parts(value,
+                                                                         3 * count as usize)))
+    }
+
+    fn Uniform4uiv(location: GLint, count: GLsizei, value: *const GLuint) {
+        with(|gl, s| gl.uniform4uiv_with_u32_array(s.location(location),
+                                                   slice::from_raw_parts(value,
+                                                                         4 * count as usize)))
+    }
+
+    fn UniformMatrix2fv(location: GLint, count: GLsizei, transpose: GLboolean,
+                        value: *const GLfloat)
+    {
+        with(|gl, s| gl.uniform_matrix2fv_with_f32_array(s.location(location),
+                                                         to_boolean(transpose),
+                                                         slice::from_raw_parts(value,
+                                                                               4 * count as usize)))
+    }
+
+    fn UniformMatrix3fv(location: GLint, count: GLsizei, transpose: GLboolean,
+                        value: *const GLfloat)
+    {
+        with(|gl, s| gl.uniform_matrix3fv_with_f32_array(s.location(location),
+                                                         to_boolean(transpose),
+                                                         slice::from_raw_parts(value,
+                                                                               9 * count as usize)))
+    }
+
+    fn UniformMatrix4fv(location: GLint, count: GLsizei, transpose: GLboolean,
+                        value: *const GLfloat)
+    {
+        with(|gl, s| gl.uniform_matrix4fv_with_f32_array(s.location(location),
+                                                         to_boolean(transpose),
+                                                         slice::from_raw_parts(value,
+                                                                               16 * count as usize)))
+    }
+
+    // queries
+
+    fn GenQueries(n: GLsizei, ids: *mut GLuint) {
+        with(|gl, s| generate(&mut s.queries, n, ids, || gl.create_query().map(Into::into)))
+    }
+
+    fn DeleteQueries(n: GLsizei, ids: *const GLuint) {
+        with(|gl, s| delete(&mut s.queries, n, ids, |q| gl.delete_query(Some(q.unchecked_ref()))))
+    }
+
+    fn BeginQuery(target: GLenum, id: GLuint) {
+        with(|gl, s| {
+            if let Some(query) = s.queries.get::<WebGlQuery>(id) {
+                gl.begin_query(target, query);
+            }
+        })
+    }
+
+    fn EndQuery(target: GLenum) {
+        with(|gl, _| gl.end_query(target))
+    }
+
+    fn GetQueryiv(target: GLenum, pname: GLenum, params: *mut GLint) {
+        with(|gl, s| write_numbers(&s.numbers(&gl.get_query(target, pname)), params, to_int))
+    }
+
+    fn GetQueryObjectuiv(id: GLuint, pname: GLenum, params: *mut GLuint) {
+        with(|gl, s| {
+            let numbers = match s.queries.get::<WebGlQuery>(id) {
+                Some(query) => s.numbers(&gl.get_query_parameter(query, pname)),
+                None => Vec::new(),
+            };
+            write_numbers(&numbers, params, |v| v as GLuint);
+        })
+    }
+
+    // sync objects
+
+    fn FenceSync(condition: GLenum, flags: GLbitfield) -> GLsync {
+        with(|gl, s| {
+            gl.fence_sync(condition, flags)
+              .map_or(ptr::null(), |sync| s.syncs.insert(sync.into()) as usize as GLsync)
+        })
+    }
+
+    fn DeleteSync(sync: GLsync) {
+        with(|gl, s| {
+            if let Some(sync) = s.syncs.remove(sync as usize as GLuint) {
+                gl.delete_sync(Some(sync.unchecked_ref()));
+            }
+        })
+    }
+
+    fn ClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+        with(|gl, s| {
+            let sync = match s.syncs.get::<WebGlSync>(sync as usize as GLuint) {
+                Some(sync) => sync,
+                None => return gl::WAIT_FAILED,
+            };
+
+            // the browser only signals fences between two tasks, so waiting here would never
+            // end; since all the commands have completed after `finish`, so has the fence
+            if timeout > s.max_client_wait_timeout as GLuint64 {
+                gl.finish();
+                return gl::CONDITION_SATISFIED;
+            }
+
+            gl.client_wait_sync_with_u32(sync, flags, timeout as u32)
+        })
+    }
+
+    fn WaitSync(sync: GLsync, flags: GLbitfield, _timeout: GLuint64) {
+        with(|gl, s| {
+            if let Some(sync) = s.syncs.get::<WebGlSync>(sync as usize as GLuint) {
+                gl.wait_sync_with_i32(sync, flags, -1);
+            }
+        })
+    }
+
+    fn GetSynciv(sync: GLsync, pname: GLenum, buf_size: GLsizei, length: *mut GLsizei,
+                 values: *mut GLint)
+    {
+        with(|gl, s| {
+            let numbers = match s.syncs.get::<WebGlSync>(sync as usize as GLuint) {
+                Some(sync) => s.numbers(&gl.get_sync_parameter(sync, pname)),
+                None => Vec::new(),
+            };
+
+            let written = numbers.len().min(buf_size.max(0) as usize);
+            for (index, &value) in numbers.iter().take(written).enumerate() {
+                *values.add(index) = to_int(value);
+            }
+            if !length.is_null() {
+                *length = written as GLsizei;
+            }
+        })
+    }
+
+    // transform feedback
+
+    fn GenTransformFeedbacks(n: GLsizei, ids: *mut GLuint) {
+        with(|gl, s| generate(&mut s.transform_feedbacks, n, ids,
+                              || gl.create_transform_feedback().map(Into::into)))
+    }
+
+    fn DeleteTransformFeedbacks(n: GLsizei, ids: *const GLuint) {
+        with(|gl, s| delete(&mut s.transform_feedbacks, n, ids,
+                            |t| gl.delete_transform_feedback(Some(t.unchecked_ref()))))
+    }
+
+    fn BindTransformFeedback(target: GLenum, id: GLuint) {
+        with(|gl, s| gl.bind_transform_feedback(target,
+                                                s.transform_feedbacks
+                                                 .get::<WebGlTransformFeedback>(id)))
+    }
+
+    fn BeginTransformFeedback(primitive_mode: GLenum) {
+        with(|gl, _| gl.begin_transform_feedback(primitive_mode))
+    }
+
+    fn EndTransformFeedback() {
+        with(|gl, _| gl.end_transform_feedback())
+    }
+
+    fn PauseTransformFeedback() {
+        with(|gl, _| gl.pause_transform_feedback())
+    }
+
+    fn ResumeTransformFeedback() {
+        with(|gl, _| gl.resume_transform_feedback())
+    }
+}
+
+/// Returns the data of a pixel transfer from client memory, or `None` if `pixels` is null.
+unsafe fn unpack_data<'a>(s: &State, pixels: *const c_void, width: GLsizei, height: GLsizei,
+                          depth: GLsizei, format: GLenum, ty: GLenum) -> Option<&'a [u8]>
+{
+    if pixels.is_null() {
+        return None;
+    }
+
+    let len = s.unpack.image_size(width, height, depth, format, ty);
+    Some(slice::from_raw_parts(pixels as *const u8, len))
+}
+
+/// Writes the content of a `WebGLActiveInfo` the way `glGetActiveUniform` does.
+unsafe fn write_active_info(s: &mut State, info: Option<WebGlActiveInfo>, buf_size: GLsizei,
+                            length: *mut GLsizei, size: *mut GLint, ty: *mut GLenum,
+                            name: *mut GLchar)
+{
+    let info = match info {
+        Some(info) => info,
+        None => return s.set_error(gl::INVALID_VALUE),
+    };
+
+    write_string(&info.name(), buf_size, length, name);
+    *size = info.size();
+    *ty = info.type_();
+}
@@ -6,7 +6,7 @@ use std::fmt;
 use std::error::Error;
 
 use crate::gl;
-use crate::context::Context;
+use crate::context::{Context, ExtensionsList};
 
 use crate::CapabilitiesSource;
 use crate::ToGlEnum;
@@ -1042,6 +1042,24 @@ pub enum CompressedFormat {
     S3tcDxt3Alpha,
     /// S3TC DXT5, see <https://www.opengl.org/wiki/S3_Texture_Compression>.
     S3tcDxt5Alpha,
+
+    /// ETC2 format with three components (no alpha).
+    Etc2Rgb8,
+    /// ETC2 format with three components and 1-bit alpha.
+    Etc2Rgb8A1,
+    /// ETC2 format with four components. The alpha is compressed with EAC.
+    Etc2Rgba8,
+    /// EAC format with one unsigned component.
+    EacR11U,
+    /// EAC format with one signed component.
+    EacR11I,
+    /// EAC format with two unsigned components.
+    EacRg11U,
+    /// EAC format with two signed components.
+    EacRg11I,
+
+    /// ASTC format with four components, with the given block size.
+    Astc(AstcBlockSize),
 }
 
 impl CompressedFormat {
@@ -1060,7 +1078,15 @@ impl CompressedFormat {
             CompressedFormat::S3tcDxt1Alpha,
             CompressedFormat::S3tcDxt3Alpha,
             CompressedFormat::S3tcDxt5Alpha,
-        ]
+            CompressedFormat::Etc2Rgb8,
+            CompressedFormat::Etc2Rgb8A1,
+            CompressedFormat::Etc2Rgba8,
+            CompressedFormat::EacR11U,
+            CompressedFormat::EacR11I,
+            CompressedFormat::EacRg11U,
+            CompressedFormat::EacRg11I,
+        ].into_iter().chain(AstcBlockSize::get_sizes_list().into_iter().map(CompressedFormat::Astc))
+         .collect()
     }
 
     /// Turns this format into a more generic `TextureFormat`.
@@ -1108,6 +1134,15 @@ impl CompressedFormat {
             CompressedFormat::S3tcDxt5Alpha => {
                 extensions.gl_ext_texture_compression_s3tc
             },
+            CompressedFormat::Etc2Rgb8 | CompressedFormat::Etc2Rgb8A1 |
+            CompressedFormat::Etc2Rgba8 | CompressedFormat::EacR11U |
+            CompressedFormat::EacR11I | CompressedFormat::EacRg11U |
+            CompressedFormat::EacRg11I => {
+                is_etc2_supported(version, extensions)
+            },
+            CompressedFormat::Astc(_) => {
+                is_astc_supported(version, extensions)
+            },
         }
     }
 
@@ -1124,6 +1159,53 @@ impl CompressedFormat {
             CompressedFormat::S3tcDxt1Alpha => gl::COMPRESSED_RGBA_S3TC_DXT1_EXT,
             CompressedFormat::S3tcDxt3Alpha => gl::COMPRESSED_RGBA_S3TC_DXT3_EXT,
             CompressedFormat::S3tcDxt5Alpha => gl::COMPRESSED_RGBA_S3TC_DXT5_EXT,
+            CompressedFormat::Etc2Rgb8 => gl::COMPRESSED_RGB8_ETC2,
+            CompressedFormat::Etc2Rgb8A1 => gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            CompressedFormat::Etc2Rgba8 => gl::COMPRESSED_RGBA8_ETC2_EAC,
+            CompressedFormat::EacR11U => gl::COMPRESSED_R11_EAC,
+            CompressedFormat::EacR11I => gl::COMPRESSED_SIGNED_R11_EAC,
+            CompressedFormat::EacRg11U => gl::COMPRESSED_RG11_EAC,
+            CompressedFormat::EacRg11I => gl::COMPRESSED_SIGNED_RG11_EAC,
+            CompressedFormat::Astc(size) => match size {
+                AstcBlockSize::Block4x4 => gl::COMPRESSED_RGBA_ASTC_4x4,
+                AstcBlockSize::Block5x4 => gl::COMPRESSED_RGBA_ASTC_5x4,
+                AstcBlockSize::Block5x5 => gl::COMPRESSED_RGBA_ASTC_5x5,
+                AstcBlockSize::Block6x5 => gl::COMPRESSED_RGBA_ASTC_6x5,
+                AstcBlockSize::Block6x6 => gl::COMPRESSED_RGBA_ASTC_6x6,
+                AstcBlockSize::Block8x5 => gl::COMPRESSED_RGBA_ASTC_8x5,
+                AstcBlockSize::Block8x6 => gl::COMPRESSED_RGBA_ASTC_8x6,
+                AstcBlockSize::Block8x8 => gl::COMPRESSED_RGBA_ASTC_8x8,
+                AstcBlockSize::Block10x5 => gl::COMPRESSED_RGBA_ASTC_10x5,
+                AstcBlockSize::Block10x6 => gl::COMPRESSED_RGBA_ASTC_10x6,
+                AstcBlockSize::Block10x8 => gl::COMPRESSED_RGBA_ASTC_10x8,
+                AstcBlockSize::Block10x10 => gl::COMPRESSED_RGBA_ASTC_10x10,
+                AstcBlockSize::Block12x10 => gl::COMPRESSED_RGBA_ASTC_12x10,
+                AstcBlockSize::Block12x12 => gl::COMPRESSED_RGBA_ASTC_12x12,
+            },
+        }
+    }
+
+    /// Returns the width and height in texels of a block of this format.
+    fn get_block_dimensions(&self) -> (u32, u32) {
+        match self {
+            CompressedFormat::Astc(size) => size.get_dimensions(),
+            _ => (4, 4),
+        }
+    }
+
+    /// Returns the number of bytes of a block of this format.
+    fn get_block_size(&self) -> usize {
+        match self {
+            CompressedFormat::RgtcFormatU | CompressedFormat::RgtcFormatI |
+            CompressedFormat::S3tcDxt1NoAlpha | CompressedFormat::S3tcDxt1Alpha |
+            CompressedFormat::Etc2Rgb8 | CompressedFormat::Etc2Rgb8A1 |
+            CompressedFormat::EacR11U | CompressedFormat::EacR11I => 8,
+            CompressedFormat::RgtcFormatUU | CompressedFormat::RgtcFormatII |
+            CompressedFormat::BptcUnorm4 | CompressedFormat::BptcSignedFloat3 |
+            CompressedFormat::BptcUnsignedFloat3 | CompressedFormat::S3tcDxt3Alpha |
+            CompressedFormat::S3tcDxt5Alpha | CompressedFormat::Etc2Rgba8 |
+            CompressedFormat::EacRg11U | CompressedFormat::EacRg11I |
+            CompressedFormat::Astc(_) => 16,
         }
     }
 }
@@ -1138,6 +1220,14 @@ pub enum CompressedSrgbFormat {
     S3tcDxt1Alpha,
     S3tcDxt3Alpha,
     S3tcDxt5Alpha,
+    /// ETC2 format. sRGB without alpha.
+    Etc2Rgb8,
+    /// ETC2 format. sRGB with 1-bit alpha.
+    Etc2Rgb8A1,
+    /// ETC2 format. sRGB with alpha compressed with EAC.
+    Etc2Rgba8,
+    /// ASTC format with the given block size. sRGB with alpha.
+    Astc(AstcBlockSize),
 }
 
 impl CompressedSrgbFormat {
@@ -1150,7 +1240,12 @@ impl CompressedSrgbFormat {
             CompressedSrgbFormat::S3tcDxt1Alpha,
             CompressedSrgbFormat::S3tcDxt3Alpha,
             CompressedSrgbFormat::S3tcDxt5Alpha,
-        ]
+            CompressedSrgbFormat::Etc2Rgb8,
+            CompressedSrgbFormat::Etc2Rgb8A1,
+            CompressedSrgbFormat::Etc2Rgba8,
+        ].into_iter().chain(AstcBlockSize::get_sizes_list().into_iter()
+                                                          .map(CompressedSrgbFormat::Astc))
+         .collect()
     }
 
     /// Turns this format into a more generic `TextureFormat`.
@@ -1180,6 +1275,13 @@ impl CompressedSrgbFormat {
             CompressedSrgbFormat::S3tcDxt5Alpha => {
                 extensions.gl_ext_texture_compression_s3tc && extensions.gl_ext_texture_srgb
             },
+            CompressedSrgbFormat::Etc2Rgb8 | CompressedSrgbFormat::Etc2Rgb8A1 |
+            CompressedSrgbFormat::Etc2Rgba8 => {
+                is_etc2_supported(version, extensions)
+            },
+            CompressedSrgbFormat::Astc(_) => {
+                is_astc_supported(version, extensions)
+            },
         }
     }
 
@@ -1190,10 +1292,123 @@ impl CompressedSrgbFormat {
             CompressedSrgbFormat::S3tcDxt1Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT,
             CompressedSrgbFormat::S3tcDxt3Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT,
             CompressedSrgbFormat::S3tcDxt5Alpha => gl::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
+            CompressedSrgbFormat::Etc2Rgb8 => gl::COMPRESSED_SRGB8_ETC2,
+            CompressedSrgbFormat::Etc2Rgb8A1 => gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            CompressedSrgbFormat::Etc2Rgba8 => gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            CompressedSrgbFormat::Astc(size) => match size {
+                AstcBlockSize::Block4x4 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4,
+                AstcBlockSize::Block5x4 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x4,
+                AstcBlockSize::Block5x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_5x5,
+                AstcBlockSize::Block6x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x5,
+                AstcBlockSize::Block6x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_6x6,
+                AstcBlockSize::Block8x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x5,
+                AstcBlockSize::Block8x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x6,
+                AstcBlockSize::Block8x8 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_8x8,
+                AstcBlockSize::Block10x5 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x5,
+                AstcBlockSize::Block10x6 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x6,
+                AstcBlockSize::Block10x8 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x8,
+                AstcBlockSize::Block10x10 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_10x10,
+                AstcBlockSize::Block12x10 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x10,
+                AstcBlockSize::Block12x12 => gl::COMPRESSED_SRGB8_ALPHA8_ASTC_12x12,
+            },
+        }
+    }
+
+    /// Returns the width and height in texels of a block of this format.
+    fn get_block_dimensions(&self) -> (u32, u32) {
+        match self {
+            CompressedSrgbFormat::Astc(size) => size.get_dimensions(),
+            _ => (4, 4),
+        }
+    }
+
+    /// Returns the number of bytes of a block of this format.
+    fn get_block_size(&self) -> usize {
+        match self {
+            CompressedSrgbFormat::S3tcDxt1NoAlpha | CompressedSrgbFormat::S3tcDxt1Alpha |
+            CompressedSrgbFormat::Etc2Rgb8 | CompressedSrgbFormat::Etc2Rgb8A1 => 8,
+            CompressedSrgbFormat::Bptc | CompressedSrgbFormat::S3tcDxt3Alpha |
+            CompressedSrgbFormat::S3tcDxt5Alpha | CompressedSrgbFormat::Etc2Rgba8 |
+            CompressedSrgbFormat::Astc(_) => 16,
+        }
+    }
+}
+
+/// Size of the blocks of texels of an ASTC format, in texels.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum AstcBlockSize {
+    Block4x4,
+    Block5x4,
+    Block5x5,
+    Block6x5,
+    Block6x6,
+    Block8x5,
+    Block8x6,
+    Block8x8,
+    Block10x5,
+    Block10x6,
+    Block10x8,
+    Block10x10,
+    Block12x10,
+    Block12x12,
+}
+
+impl AstcBlockSize {
+    /// Returns a list of all the possible values of this enumeration.
+    #[inline]
+    pub fn get_sizes_list() -> Vec<AstcBlockSize> {
+        vec![
+            AstcBlockSize::Block4x4,
+            AstcBlockSize::Block5x4,
+            AstcBlockSize::Block5x5,
+            AstcBlockSize::Block6x5,
+            AstcBlockSize::Block6x6,
+            AstcBlockSize::Block8x5,
+            AstcBlockSize::Block8x6,
+            AstcBlockSize::Block8x8,
+            AstcBlockSize::Block10x5,
+            AstcBlockSize::Block10x6,
+            AstcBlockSize::Block10x8,
+            AstcBlockSize::Block10x10,
+            AstcBlockSize::Block12x10,
+            AstcBlockSize::Block12x12,
+        ]
+    }
+
+    /// Returns the width and height of a block.
+    #[inline]
+    pub fn get_dimensions(self) -> (u32, u32) {
+        match self {
+            AstcBlockSize::Block4x4 => (4, 4),
+            AstcBlockSize::Block5x4 => (5, 4),
+            AstcBlockSize::Block5x5 => (5, 5),
+            AstcBlockSize::Block6x5 => (6, 5),
+            AstcBlockSize::Block6x6 => (6, 6),
+            AstcBlockSize::Block8x5 => (8, 5),
+            AstcBlockSize::Block8x6 => (8, 6),
+            AstcBlockSize::Block8x8 => (8, 8),
+            AstcBlockSize::Block10x5 => (10, 5),
+            AstcBlockSize::Block10x6 => (10, 6),
+            AstcBlockSize::Block10x8 => (10, 8),
+            AstcBlockSize::Block10x10 => (10, 10),
+            AstcBlockSize::Block12x10 => (12, 10),
+            AstcBlockSize::Block12x12 => (12, 12),
         }
     }
 }
 
+/// ETC2 and EAC formats are core in OpenGL ES 3.0 and OpenGL 4.3.
+fn is_etc2_supported(version: &Version, extensions: &ExtensionsList) -> bool {
+    version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 0) ||
+    extensions.gl_arb_es3_compatibility
+}
+
+/// ASTC formats are core in OpenGL ES 3.2.
+fn is_astc_supported(version: &Version, extensions: &ExtensionsList) -> bool {
+    version >= &Version(Api::GlEs, 3, 2) || extensions.gl_khr_texture_compression_astc_ldr
+}
+
 /// List of formats available for depth textures.
 ///
 /// `I16`, `I24` and `I32` are still treated as if they were floating points.
@@ -1431,6 +1646,19 @@ impl TextureFormat {
         result
     }
 
+    /// Returns the compressed format that corresponds to a value returned by
+    /// `GL_COMPRESSED_TEXTURE_FORMATS`, if glium knows about it.
+    pub(crate) fn from_compressed_glenum(value: gl::types::GLenum) -> Option<TextureFormat> {
+        CompressedFormat::get_formats_list().into_iter()
+            .find(|f| f.to_glenum() == value)
+            .map(|f| f.to_texture_format())
+            .or_else(|| {
+                CompressedSrgbFormat::get_formats_list().into_iter()
+                    .find(|f| f.to_glenum() == value)
+                    .map(|f| f.to_texture_format())
+            })
+    }
+
     /// Returns true if this format is supported by the backend for textures.
     #[inline]
     pub fn is_supported_for_textures<C: ?Sized>(&self, c: &C) -> bool where C: CapabilitiesSource {
@@ -1479,6 +1707,130 @@ impl TextureFormat {
             TextureFormat::DepthStencilFormat(_) => true,
         }
     }
+
+    /// Returns the number of bytes required to store an image of the given dimensions in this
+    /// format.
+    ///
+    /// Compressed formats are stored as blocks of texels, so their width and height are
+    /// rounded up to the next multiple of the block dimensions. Keep in mind that this is only an
+    /// estimate, as the backend is free to add padding or to store the data in a larger format.
+    pub fn get_image_size(&self, width: u32, height: u32, depth: u32) -> usize {
+        let depth = depth as usize;
+
+        if let Some((block_width, block_height, block_size)) = self.get_block_infos() {
+            let blocks_w = width.div_ceil(block_width) as usize;
+            let blocks_h = height.div_ceil(block_height) as usize;
+            return blocks_w * blocks_h * depth * block_size;
+        }
+
+        let texel_size = (self.get_texel_bits() + 7) / 8;
+        texel_size * width as usize * height as usize * depth
+    }
+
+    /// Returns the width, height and number of bytes of a block, or `None` if the format isn't
+    /// compressed.
+    fn get_block_infos(&self) -> Option<(u32, u32, usize)> {
+        match *self {
+            TextureFormat::CompressedFormat(f) => {
+                let (width, height) = f.get_block_dimensions();
+                Some((width, height, f.get_block_size()))
+            },
+            TextureFormat::CompressedSrgbFormat(f) => {
+                let (width, height) = f.get_block_dimensions();
+                Some((width, height, f.get_block_size()))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bits of a texel of an uncompressed format.
+    fn get_texel_bits(&self) -> usize {
+        match *self {
+            TextureFormat::UncompressedFloat(f) => match f {
+                UncompressedFloatFormat::U8 | UncompressedFloatFormat::I8 => 8,
+                UncompressedFloatFormat::U16 | UncompressedFloatFormat::I16 => 16,
+                UncompressedFloatFormat::U8U8 | UncompressedFloatFormat::I8I8 => 16,
+                UncompressedFloatFormat::U16U16 | UncompressedFloatFormat::I16I16 => 32,
+                UncompressedFloatFormat::U3U3U2 => 8,
+                UncompressedFloatFormat::U4U4U4 => 12,
+                UncompressedFloatFormat::U5U5U5 => 15,
+                UncompressedFloatFormat::U8U8U8 | UncompressedFloatFormat::I8I8I8 => 24,
+                UncompressedFloatFormat::U10U10U10 => 30,
+                UncompressedFloatFormat::U12U12U12 => 36,
+                UncompressedFloatFormat::U16U16U16 | UncompressedFloatFormat::I16I16I16 => 48,
+                UncompressedFloatFormat::U2U2U2U2 => 8,
+                UncompressedFloatFormat::U4U4U4U4 => 16,
+                UncompressedFloatFormat::U5U5U5U1 => 16,
+                UncompressedFloatFormat::U8U8U8U8 | UncompressedFloatFormat::I8I8I8I8 => 32,
+                UncompressedFloatFormat::U10U10U10U2 => 32,
+                UncompressedFloatFormat::U12U12U12U12 => 48,
+                UncompressedFloatFormat::U16U16U16U16 |
+                UncompressedFloatFormat::I16I16I16I16 => 64,
+                UncompressedFloatFormat::F16 => 16,
+                UncompressedFloatFormat::F16F16 => 32,
+                UncompressedFloatFormat::F16F16F16 => 48,
+                UncompressedFloatFormat::F16F16F16F16 => 64,
+                UncompressedFloatFormat::F32 => 32,
+                UncompressedFloatFormat::F32F32 => 64,
+                UncompressedFloatFormat::F32F32F32 => 96,
+                UncompressedFloatFormat::F32F32F32F32 => 128,
+                UncompressedFloatFormat::F11F11F10 => 32,
+                UncompressedFloatFormat::F9F9F9 => 32,
+            },
+            TextureFormat::UncompressedIntegral(f) => match f {
+                UncompressedIntFormat::I8 => 8,
+                UncompressedIntFormat::I16 => 16,
+                UncompressedIntFormat::I32 => 32,
+                UncompressedIntFormat::I8I8 => 16,
+                UncompressedIntFormat::I16I16 => 32,
+                UncompressedIntFormat::I32I32 => 64,
+                UncompressedIntFormat::I8I8I8 => 24,
+                UncompressedIntFormat::I16I16I16 => 48,
+                UncompressedIntFormat::I32I32I32 => 96,
+                UncompressedIntFormat::I8I8I8I8 => 32,
+                UncompressedIntFormat::I16I16I16I16 => 64,
+                UncompressedIntFormat::I32I32I32I32 => 128,
+            },
+            TextureFormat::UncompressedUnsigned(f) => match f {
+                UncompressedUintFormat::U8 => 8,
+                UncompressedUintFormat::U16 => 16,
+                UncompressedUintFormat::U32 => 32,
+                UncompressedUintFormat::U8U8 => 16,
+                UncompressedUintFormat::U16U16 => 32,
+                UncompressedUintFormat::U32U32 => 64,
+                UncompressedUintFormat::U8U8U8 => 24,
+                UncompressedUintFormat::U16U16U16 => 48,
+                UncompressedUintFormat::U32U32U32 => 96,
+                UncompressedUintFormat::U8U8U8U8 => 32,
+                UncompressedUintFormat::U16U16U16U16 => 64,
+                UncompressedUintFormat::U32U32U32U32 => 128,
+                UncompressedUintFormat::U10U10U10U2 => 32,
+            },
+            TextureFormat::Srgb(f) => match f {
+                SrgbFormat::U8U8U8 => 24,
+                SrgbFormat::U8U8U8U8 => 32,
+            },
+            TextureFormat::DepthFormat(f) => match f {
+                DepthFormat::I16 => 16,
+                DepthFormat::I24 => 24,
+                DepthFormat::I32 => 32,
+                DepthFormat::F32 => 32,
+            },
+            TextureFormat::StencilFormat(f) => match f {
+                StencilFormat::I1 => 1,
+                StencilFormat::I4 => 4,
+                StencilFormat::I8 => 8,
+                StencilFormat::I16 => 16,
+            },
+            TextureFormat::DepthStencilFormat(f) => match f {
+                DepthStencilFormat::I24I8 => 32,
+                DepthStencilFormat::F32I8 => 64,
+            },
+            TextureFormat::CompressedFormat(_) | TextureFormat::CompressedSrgbFormat(_) => {
+                unreachable!()
+            },
+        }
+    }
 }
 
 impl ToGlEnum for TextureFormat {
@@ -1578,6 +1930,27 @@ impl ClientFormatAny {
                                             depth.unwrap_or(1) as usize * array_size.unwrap_or(1) as usize;
                 uncompressed_bit_size / 4   // Apply 4:1 compression ratio
             },
+
+            // ETC2, EAC and ASTC, the last blocks of a row or column can be partially used
+            ClientFormatAny::CompressedFormat(_) | ClientFormatAny::CompressedSrgbFormat(_) => {
+                let (block_width, block_height, block_size) = match *self {
+                    ClientFormatAny::CompressedFormat(f) => {
+                        let (w, h) = f.get_block_dimensions();
+                        (w, h, f.get_block_size())
+                    },
+                    ClientFormatAny::CompressedSrgbFormat(f) => {
+                        let (w, h) = f.get_block_dimensions();
+                        (w, h, f.get_block_size())
+                    },
+                    _ => unreachable!(),
+                };
+
+                // textures that aren't 2D are rejected by `new_texture` before reaching this point
+                let height = height.unwrap_or(1);
+
+                width.div_ceil(block_width) as usize * height.div_ceil(block_height) as usize *
+                    block_size * depth.unwrap_or(1) as usize * array_size.unwrap_or(1) as usize
+            },
         }
     }
 
@@ -1608,7 +1981,16 @@ impl ClientFormatAny {
             gl::COMPRESSED_SIGNED_RED_RGTC1 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI)),
             gl::COMPRESSED_RG_RGTC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU)),
             gl::COMPRESSED_SIGNED_RG_RGTC2 => Some(ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII)),
-            _ => None,
+            _ => {
+                let format = CompressedFormat::get_formats_list().into_iter()
+                                 .find(|f| f.to_glenum() == internal)
+                                 .map(ClientFormatAny::CompressedFormat);
+                format.or_else(|| {
+                    CompressedSrgbFormat::get_formats_list().into_iter()
+                        .find(|f| f.to_glenum() == internal)
+                        .map(ClientFormatAny::CompressedSrgbFormat)
+                })
+            },
         }
     }
 }
@@ -2238,14 +2620,14 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
         TextureFormatRequest::Specific(TextureFormat::StencilFormat(_)) =>
         {
             match client {
-                ClientFormatAny::ClientFormat(ClientFormat::U8) => Ok((gl::RED_INTEGER, gl::UNSIGNED_BYTE)),
-                ClientFormatAny::ClientFormat(ClientFormat::I8) => Ok((gl::RED_INTEGER, gl::BYTE)),
-                ClientFormatAny::ClientFormat(ClientFormat::U16) => Ok((gl::RED_INTEGER, gl::UNSIGNED_SHORT)),
-                ClientFormatAny::ClientFormat(ClientFormat::I16) => Ok((gl::RED_INTEGER, gl::SHORT)),
-                ClientFormatAny::ClientFormat(ClientFormat::U32) => Ok((gl::RED_INTEGER, gl::UNSIGNED_INT)),
-                ClientFormatAny::ClientFormat(ClientFormat::I32) => Ok((gl::RED_INTEGER, gl::INT)),
-                ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::RED_INTEGER, gl::HALF_FLOAT)),
-                ClientFormatAny::ClientFormat(ClientFormat::F32) => Ok((gl::RED_INTEGER, gl::FLOAT)),
+                ClientFormatAny::ClientFormat(ClientFormat::U8) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_BYTE)),
+                ClientFormatAny::ClientFormat(ClientFormat::I8) => Ok((gl::STENCIL_INDEX, gl::BYTE)),
+                ClientFormatAny::ClientFormat(ClientFormat::U16) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_SHORT)),
+                ClientFormatAny::ClientFormat(ClientFormat::I16) => Ok((gl::STENCIL_INDEX, gl::SHORT)),
+                ClientFormatAny::ClientFormat(ClientFormat::U32) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_INT)),
+                ClientFormatAny::ClientFormat(ClientFormat::I32) => Ok((gl::STENCIL_INDEX, gl::INT)),
+                ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::STENCIL_INDEX, gl::HALF_FLOAT)),
+                ClientFormatAny::ClientFormat(ClientFormat::F32) => Ok((gl::STENCIL_INDEX, gl::FLOAT)),
                 _ => panic!("Can't upload to a stencil texture with more than one channel")
             }
         }
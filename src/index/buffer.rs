@@ -1,7 +1,8 @@
-use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType};
-use crate::buffer::{BufferMode, BufferCreationError};
+use crate::buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferAnySlice, BufferType};
+use crate::buffer::{BufferMode, BufferCreationError, ReadError};
 use crate::gl;
 use crate::GlObject;
+use crate::Recreatable;
 
 use crate::backend::Facade;
 
@@ -12,6 +13,7 @@ use crate::index::PrimitiveType;
 
 use std::ops::{Deref, DerefMut};
 use std::fmt;
+use std::mem;
 use std::error::Error;
 use crate::utils::range::RangeArgument;
 
@@ -26,6 +28,9 @@ pub enum CreationError {
 
     /// An error happened while creating the buffer.
     BufferCreationError(BufferCreationError),
+
+    /// The requested buffer is not valid.
+    ValidationError(IndexValidationError),
 }
 
 impl fmt::Display for CreationError {
@@ -38,6 +43,8 @@ impl fmt::Display for CreationError {
                 "The type of primitives is not supported by the backend",
             BufferCreationError(_) =>
                 "An error happened while creating the buffer",
+            ValidationError(_) =>
+                "The requested buffer is not valid",
         };
         fmt.write_str(desc)
     }
@@ -48,6 +55,7 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -60,6 +68,122 @@ impl From<BufferCreationError> for CreationError {
     }
 }
 
+impl From<IndexValidationError> for CreationError {
+    #[inline]
+    fn from(err: IndexValidationError) -> CreationError {
+        CreationError::ValidationError(err)
+    }
+}
+
+/// Error that can happen when validating the size or the content of an index buffer.
+#[derive(Debug, Copy, Clone)]
+pub enum IndexValidationError {
+    /// The size in bytes of the buffer would be larger than `isize::MAX`.
+    BufferTooLarge {
+        /// Number of indices that were requested.
+        len: usize,
+    },
+
+    /// An index refers to a vertex that is not in the vertices source.
+    IndexOutOfRange {
+        /// Position of the index in the buffer.
+        position: usize,
+        /// Value of the index, base vertex included.
+        vertex: i64,
+        /// Number of vertices in the vertices source.
+        vertices_count: usize,
+    },
+
+    /// The indices couldn't be read from the buffer.
+    ReadError(ReadError),
+}
+
+impl fmt::Display for IndexValidationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            IndexValidationError::BufferTooLarge { len } =>
+                write!(fmt, "A buffer of {} indices would be larger than isize::MAX bytes", len),
+            IndexValidationError::IndexOutOfRange { position, vertex, vertices_count } =>
+                write!(fmt, "Index #{} refers to vertex {}, but the vertices source only \
+                             contains {} vertices", position, vertex, vertices_count),
+            IndexValidationError::ReadError(_) =>
+                write!(fmt, "The indices couldn't be read from the buffer"),
+        }
+    }
+}
+
+impl Error for IndexValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            IndexValidationError::ReadError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReadError> for IndexValidationError {
+    #[inline]
+    fn from(err: ReadError) -> IndexValidationError {
+        IndexValidationError::ReadError(err)
+    }
+}
+
+/// Checks that each index, offset by `base_vertex`, refers to one of the `vertices_count`
+/// vertices. Indices equal to `restart_index` are ignored.
+fn validate_indices<T>(indices: &[T], base_vertex: i32, vertices_count: usize,
+                       restart_index: Option<u32>) -> Result<(), IndexValidationError>
+    where T: Copy + Into<u32>
+{
+    for (position, &index) in indices.iter().enumerate() {
+        let index = index.into();
+        if Some(index) == restart_index {
+            continue;
+        }
+
+        let vertex = index as i64 + base_vertex as i64;
+        if vertex < 0 || vertex >= vertices_count as i64 {
+            return Err(IndexValidationError::IndexOutOfRange { position, vertex,
+                                                               vertices_count });
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the indices of a buffer and validates them with `validate_indices`.
+///
+/// If `primitive_restart` is true, the maximum value of the index type is ignored.
+pub(crate) fn validate_indices_buffer(buffer: &BufferAnySlice<'_>, data_type: IndexType,
+                                      base_vertex: i32, vertices_count: usize,
+                                      primitive_restart: bool)
+                                      -> Result<(), IndexValidationError>
+{
+    unsafe {
+        match data_type {
+            IndexType::U8 => {
+                let restart = if primitive_restart { Some(u8::MAX as u32) } else { None };
+                validate_indices(&buffer.read::<[u8]>()?, base_vertex, vertices_count, restart)
+            },
+            IndexType::U16 => {
+                let restart = if primitive_restart { Some(u16::MAX as u32) } else { None };
+                validate_indices(&buffer.read::<[u16]>()?, base_vertex, vertices_count, restart)
+            },
+            IndexType::U32 => {
+                let restart = if primitive_restart { Some(u32::MAX) } else { None };
+                validate_indices(&buffer.read::<[u32]>()?, base_vertex, vertices_count, restart)
+            },
+        }
+    }
+}
+
+/// Checks that a buffer of `len` indices of type `T` isn't larger than `isize::MAX` bytes.
+fn validate_len<T>(len: usize) -> Result<(), IndexValidationError> {
+    match len.checked_mul(mem::size_of::<T>()) {
+        Some(size) if size <= isize::MAX as usize => Ok(()),
+        _ => Err(IndexValidationError::BufferTooLarge { len }),
+    }
+}
+
 /// A list of indices loaded in the graphics card's memory.
 #[derive(Debug)]
 pub struct IndexBuffer<T> where T: Index {
@@ -117,6 +241,8 @@ impl<T> IndexBuffer<T> where T: Index {
             return Err(CreationError::IndexTypeNotSupported);
         }
 
+        validate_len::<T>(data.len())?;
+
         Ok(IndexBuffer {
             buffer: Buffer::new(facade, data, BufferType::ElementArrayBuffer, mode)?,
             primitives: prim,
@@ -172,6 +298,8 @@ impl<T> IndexBuffer<T> where T: Index {
             return Err(CreationError::IndexTypeNotSupported);
         }
 
+        validate_len::<T>(len)?;
+
         Ok(IndexBuffer {
             buffer: Buffer::empty_array(facade, BufferType::ElementArrayBuffer, len,
                                                  mode)?,
@@ -185,6 +313,16 @@ impl<T> IndexBuffer<T> where T: Index {
         self.primitives
     }
 
+    /// Reads the indices and checks that each of them refers to one of the `vertices_count`
+    /// vertices of the vertices source.
+    ///
+    /// This reads the content of the buffer and is therefore slow.
+    #[inline]
+    pub fn validate(&self, vertices_count: usize) -> Result<(), IndexValidationError> {
+        validate_indices_buffer(&self.buffer.as_slice_any(), T::get_type(), 0, vertices_count,
+                                false)
+    }
+
     /// Returns the data type of the indices inside this index buffer.
     #[inline]
     pub fn get_indices_type(&self) -> IndexType {
@@ -198,6 +336,8 @@ impl<T> IndexBuffer<T> where T: Index {
             IndexBufferSlice {
                 buffer: b,
                 primitives: self.primitives,
+                base_vertex: 0,
+                base_instance: 0,
             }
         })
     }
@@ -235,6 +375,13 @@ impl<'a, T> From<&'a mut IndexBuffer<T>> for BufferMutSlice<'a, [T]> where T: In
     }
 }
 
+impl<T> Recreatable for IndexBuffer<T> where T: Index {
+    #[inline]
+    fn recreate(&mut self) -> Result<(), crate::Error> {
+        self.buffer.recreate()
+    }
+}
+
 // TODO: remove this
 impl<T> GlObject for IndexBuffer<T> where T: Index {
     type Id = gl::types::GLuint;
@@ -252,6 +399,8 @@ impl<'a, T> From<&'a IndexBuffer<T>> for IndicesSource<'a> where T: Index {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            base_vertex: 0,
+            base_instance: 0,
         }
     }
 }
@@ -261,6 +410,8 @@ impl<'a, T> From<&'a IndexBuffer<T>> for IndicesSource<'a> where T: Index {
 pub struct IndexBufferSlice<'a, T> where T: Index {
     buffer: BufferSlice<'a, [T]>,
     primitives: PrimitiveType,
+    base_vertex: i32,
+    base_instance: u32,
 }
 
 impl<'a, T: 'a> IndexBufferSlice<'a, T> where T: Index {
@@ -276,13 +427,62 @@ impl<'a, T: 'a> IndexBufferSlice<'a, T> where T: Index {
         <T as Index>::get_type()
     }
 
+    /// Returns the value that is added to each index before fetching the vertex.
+    #[inline]
+    pub fn get_base_vertex(&self) -> i32 {
+        self.base_vertex
+    }
+
+    /// Returns the index of the first instance whose per-instance attributes are fetched.
+    #[inline]
+    pub fn get_base_instance(&self) -> u32 {
+        self.base_instance
+    }
+
+    /// Reads the indices and checks that each of them, plus the base vertex, refers to one of
+    /// the `vertices_count` vertices of the vertices source.
+    ///
+    /// This reads the content of the buffer and is therefore slow.
+    #[inline]
+    pub fn validate(&self, vertices_count: usize) -> Result<(), IndexValidationError> {
+        validate_indices_buffer(&self.buffer.as_slice_any(), T::get_type(), self.base_vertex,
+                                vertices_count, false)
+    }
+
+    /// Adds `base_vertex` to each index before fetching the vertex.
+    ///
+    /// This allows drawing a mesh whose vertices are stored in the middle of a vertex buffer
+    /// shared with other meshes, without having to offset its indices. Drawing requires
+    /// OpenGL 3.2, OpenGL ES 3.2, `GL_ARB_draw_elements_base_vertex` or
+    /// `GL_OES_draw_elements_base_vertex` if the value is not 0.
+    #[inline]
+    pub fn with_base_vertex(mut self, base_vertex: i32) -> IndexBufferSlice<'a, T> {
+        self.base_vertex = base_vertex;
+        self
+    }
+
+    /// Starts fetching the per-instance attributes at the instance `base_instance`.
+    ///
+    /// The number of instances that are drawn is the number of elements of the per-instance
+    /// sources minus `base_instance`, or 1 if there is no per-instance source. Drawing requires
+    /// OpenGL 4.2, `GL_ARB_base_instance` or `GL_EXT_base_instance` if the value is not 0.
+    #[inline]
+    pub fn with_base_instance(mut self, base_instance: u32) -> IndexBufferSlice<'a, T> {
+        self.base_instance = base_instance;
+        self
+    }
+
     /// Returns `None` if out of range.
+    ///
+    /// The base vertex and base instance are kept.
     #[inline]
     pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<IndexBufferSlice<'a, T>> {
         self.buffer.slice(range).map(|b| {
             IndexBufferSlice {
                 buffer: b,
                 primitives: self.primitives,
+                base_vertex: self.base_vertex,
+                base_instance: self.base_instance,
             }
         })
     }
@@ -318,6 +518,8 @@ impl<'a, T> From<IndexBufferSlice<'a, T>> for IndicesSource<'a> where T: Index {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            base_vertex: buf.base_vertex,
+            base_instance: buf.base_instance,
         }
     }
 }
@@ -329,6 +531,8 @@ impl<'a, 'r, T> From<&'r IndexBufferSlice<'a, T>> for IndicesSource<'a> where T:
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.get_indices_type(),
             primitives: buf.primitives,
+            base_vertex: buf.base_vertex,
+            base_instance: buf.base_instance,
         }
     }
 }
@@ -393,6 +597,8 @@ impl<'a> From<&'a IndexBufferAny> for IndicesSource<'a> {
             buffer: buf.buffer.as_slice_any(),
             data_type: buf.data_type,
             primitives: buf.primitives,
+            base_vertex: 0,
+            base_instance: 0,
         }
     }
 }
@@ -16,11 +16,13 @@ There are eleven types of primitives, each one with a corresponding struct:
  - `TriangleFan`
  - `Patches`
 
-There are two ways to specify the indices that must be used:
+There are three ways to specify the indices that must be used:
 
  - Passing a reference to an `IndexBuffer`, which contains a list of indices.
  - `NoIndices`, in which case the vertices will be used in the order in which they are in the
    vertex buffer.
+ - `MultiRange`, which is the same as `NoIndices` but only uses some ranges of the vertex
+   buffer. All the ranges are drawn with a single call to `glMultiDrawArrays`.
 
 ## Multidraw indirect
 
@@ -38,11 +40,16 @@ use crate::version::Version;
 use std::mem;
 
 use crate::buffer::BufferAnySlice;
+pub(crate) use self::buffer::validate_indices_buffer;
+use crate::vertex::TransformFeedbackObject;
 
 pub use self::buffer::{IndexBuffer, IndexBufferSlice, IndexBufferAny};
 pub use self::buffer::CreationError as BufferCreationError;
+pub use self::buffer::IndexValidationError;
 pub use self::multidraw::{DrawCommandsNoIndicesBuffer, DrawCommandNoIndices};
 pub use self::multidraw::{DrawCommandsIndicesBuffer, DrawCommandIndices};
+pub use self::multidraw::{DrawArraysIndirectCommand, DrawElementsIndirectCommand};
+pub use self::multidraw::{DrawCommandsNoIndicesBuilder, DrawCommandsIndicesBuilder, DrawCommandError};
 
 mod buffer;
 mod multidraw;
@@ -58,6 +65,10 @@ pub enum IndicesSource<'a> {
         data_type: IndexType,
         /// Type of primitives contained in the vertex source.
         primitives: PrimitiveType,
+        /// Value added to each index before fetching the vertex.
+        base_vertex: i32,
+        /// Index of the first instance whose per-instance attributes are fetched.
+        base_instance: u32,
     },
 
     /// Use a multidraw indirect buffer without indices.
@@ -86,6 +97,23 @@ pub enum IndicesSource<'a> {
         /// Type of primitives contained in the vertex source.
         primitives: PrimitiveType,
     },
+
+    /// Don't use indices, and draw several ranges of the vertices source.
+    MultiRange {
+        /// The first vertex and the number of vertices of each range.
+        ranges: &'a [(u32, u32)],
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
+    /// Don't use indices, and draw the number of vertices written by the latest transform
+    /// feedback session that used the object.
+    TransformFeedback {
+        /// The transform feedback object.
+        object: &'a TransformFeedbackObject,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
 }
 
 impl<'a> IndicesSource<'a> {
@@ -97,6 +125,8 @@ impl<'a> IndicesSource<'a> {
             IndicesSource::MultidrawArray { primitives, .. } => primitives,
             IndicesSource::MultidrawElement { primitives, .. } => primitives,
             IndicesSource::NoIndices { primitives } => primitives,
+            IndicesSource::MultiRange { primitives, .. } => primitives,
+            IndicesSource::TransformFeedback { primitives, .. } => primitives,
         }
     }
 }
@@ -261,6 +291,48 @@ impl<'a, 'b> From<&'b NoIndices> for IndicesSource<'a> {
     }
 }
 
+/// Marker that can be passed instead of a buffer to indicate that several ranges of the vertex
+/// buffer must be drawn, each one as if it was drawn with `NoIndices`.
+///
+/// The second member is the list of ranges, as the index of the first vertex and the number of
+/// vertices. The primitives of different ranges are never connected, which means that each range
+/// can for example be a separate triangle strip.
+///
+/// ```no_run
+/// # fn example<S: glium::Surface>(surface: &mut S, program: &glium::Program,
+/// #            terrain: &glium::vertex::VertexBufferAny) {
+/// use glium::index::{MultiRange, PrimitiveType};
+///
+/// // the visible chunks of the terrain, stored one after the other in the same buffer
+/// let chunks = [(0, 1024), (4096, 1024), (8192, 512)];
+///
+/// surface.draw(terrain, MultiRange(PrimitiveType::TriangleStrip, &chunks), program,
+///              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct MultiRange<'a>(pub PrimitiveType, pub &'a [(u32, u32)]);
+
+impl<'a> From<MultiRange<'a>> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: MultiRange<'a>) -> IndicesSource<'a> {
+        IndicesSource::MultiRange {
+            ranges: marker.1,
+            primitives: marker.0,
+        }
+    }
+}
+
+impl<'a, 'b> From<&'b MultiRange<'a>> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: &'b MultiRange<'a>) -> IndicesSource<'a> {
+        IndicesSource::MultiRange {
+            ranges: marker.1,
+            primitives: marker.0,
+        }
+    }
+}
+
 /// Type of the indices in an index source.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]    // GLenum
@@ -1,5 +1,7 @@
 //! Allows one to draw multiple geometry located in the same buffer.
 //!
+use std::error::Error;
+use std::fmt;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::os::raw;
@@ -8,6 +10,7 @@ use crate::backend::Facade;
 use crate::buffer::{BufferCreationError, BufferType, BufferMode, Buffer};
 use crate::buffer::{BufferSlice, BufferMutSlice};
 use crate::index::{IndicesSource, PrimitiveType, IndexBuffer, Index};
+use crate::vertex::VertexBuffer;
 
 /// Represents an element in a list of draw commands.
 #[repr(C)]
@@ -26,6 +29,10 @@ pub struct DrawCommandNoIndices {
 implement_uniform_block!(DrawCommandNoIndices, count, instance_count,
                          first_index, base_instance);
 
+/// Name of `DrawCommandNoIndices` that matches the `DrawArraysIndirectCommand` structure of
+/// the OpenGL specification.
+pub type DrawArraysIndirectCommand = DrawCommandNoIndices;
+
 /// Represents an element in a list of draw commands.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -45,12 +52,66 @@ pub struct DrawCommandIndices {
 implement_uniform_block!(DrawCommandIndices, count, instance_count, first_index,
                          base_vertex, base_instance);
 
+/// Name of `DrawCommandIndices` that matches the `DrawElementsIndirectCommand` structure of
+/// the OpenGL specification.
+pub type DrawElementsIndirectCommand = DrawCommandIndices;
+
+/// Error that can happen when adding a command to a draw commands builder.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DrawCommandError {
+    /// The command reads past the end of the vertex buffer or of the index buffer.
+    OutOfBounds {
+        /// Position of the command in the list.
+        command: usize,
+        /// Number of elements that the command needs in the buffer.
+        end: usize,
+        /// Number of elements in the buffer.
+        len: usize,
+    },
+}
+
+impl fmt::Display for DrawCommandError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DrawCommandError::OutOfBounds { command, end, len } =>
+                write!(fmt, "Draw command #{} reads {} elements, but the buffer only contains {}",
+                       command, end, len),
+        }
+    }
+}
+
+impl Error for DrawCommandError {}
+
+/// Checks that a command reading `count` elements from `first` stays within `len` elements.
+fn check_bounds(command: usize, first: raw::c_uint, count: raw::c_uint, len: usize)
+                -> Result<(), DrawCommandError>
+{
+    let end = (first as usize).saturating_add(count as usize);
+
+    if end > len {
+        return Err(DrawCommandError::OutOfBounds { command, end, len });
+    }
+
+    Ok(())
+}
+
 /// A buffer containing a list of draw commands.
 pub struct DrawCommandsNoIndicesBuffer {
     buffer: Buffer<[DrawCommandNoIndices]>,
 }
 
 impl DrawCommandsNoIndicesBuffer {
+    /// Builds a new buffer containing the given commands.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, commands: &[DrawCommandNoIndices])
+                  -> Result<DrawCommandsNoIndicesBuffer, BufferCreationError>
+                  where F: Facade
+    {
+        let buf = Buffer::new(facade, commands, BufferType::DrawIndirectBuffer,
+                              BufferMode::Default)?;
+        Ok(DrawCommandsNoIndicesBuffer { buffer: buf })
+    }
+
     /// Builds an empty buffer.
     ///
     /// The parameter indicates the number of elements.
@@ -152,6 +213,17 @@ pub struct DrawCommandsIndicesBuffer {
 }
 
 impl DrawCommandsIndicesBuffer {
+    /// Builds a new buffer containing the given commands.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, commands: &[DrawCommandIndices])
+                  -> Result<DrawCommandsIndicesBuffer, BufferCreationError>
+                  where F: Facade
+    {
+        let buf = Buffer::new(facade, commands, BufferType::DrawIndirectBuffer,
+                              BufferMode::Default)?;
+        Ok(DrawCommandsIndicesBuffer { buffer: buf })
+    }
+
     /// Builds an empty buffer.
     ///
     /// The parameter indicates the number of elements.
@@ -250,3 +322,107 @@ impl<'a> From<&'a mut DrawCommandsIndicesBuffer> for BufferMutSlice<'a, [DrawCom
         b.as_mut_slice()
     }
 }
+
+/// Builds a list of draw commands without indices, and checks each of them against the number
+/// of vertices they will be drawn from before uploading them.
+#[derive(Debug, Clone)]
+pub struct DrawCommandsNoIndicesBuilder {
+    vertices_count: usize,
+    commands: Vec<DrawCommandNoIndices>,
+}
+
+impl DrawCommandsNoIndicesBuilder {
+    /// Starts building a list of commands that draw from `vertices_count` vertices.
+    #[inline]
+    pub fn new(vertices_count: usize) -> DrawCommandsNoIndicesBuilder {
+        DrawCommandsNoIndicesBuilder {
+            vertices_count,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Starts building a list of commands that draw from a vertex buffer.
+    #[inline]
+    pub fn for_vertex_buffer<T>(vertex_buffer: &VertexBuffer<T>) -> DrawCommandsNoIndicesBuilder
+                                where T: Copy
+    {
+        DrawCommandsNoIndicesBuilder::new(vertex_buffer.len())
+    }
+
+    /// Adds a command to the list.
+    ///
+    /// Returns an error if the command draws vertices past the end of the vertices source.
+    pub fn push(&mut self, command: DrawCommandNoIndices) -> Result<(), DrawCommandError> {
+        check_bounds(self.commands.len(), command.first_index, command.count,
+                     self.vertices_count)?;
+        self.commands.push(command);
+        Ok(())
+    }
+
+    /// Returns the list of commands that have been added.
+    #[inline]
+    pub fn commands(&self) -> &[DrawCommandNoIndices] {
+        &self.commands
+    }
+
+    /// Uploads the commands to a new buffer.
+    #[inline]
+    pub fn build<F: ?Sized>(&self, facade: &F)
+                            -> Result<DrawCommandsNoIndicesBuffer, BufferCreationError>
+                            where F: Facade
+    {
+        DrawCommandsNoIndicesBuffer::new(facade, &self.commands)
+    }
+}
+
+/// Builds a list of draw commands with indices, and checks each of them against the number
+/// of indices they will be drawn from before uploading them.
+#[derive(Debug, Clone)]
+pub struct DrawCommandsIndicesBuilder {
+    indices_count: usize,
+    commands: Vec<DrawCommandIndices>,
+}
+
+impl DrawCommandsIndicesBuilder {
+    /// Starts building a list of commands that draw from `indices_count` indices.
+    #[inline]
+    pub fn new(indices_count: usize) -> DrawCommandsIndicesBuilder {
+        DrawCommandsIndicesBuilder {
+            indices_count,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Starts building a list of commands that draw from an index buffer.
+    #[inline]
+    pub fn for_index_buffer<T>(index_buffer: &IndexBuffer<T>) -> DrawCommandsIndicesBuilder
+                               where T: Index
+    {
+        DrawCommandsIndicesBuilder::new(index_buffer.len())
+    }
+
+    /// Adds a command to the list.
+    ///
+    /// Returns an error if the command reads indices past the end of the index buffer.
+    pub fn push(&mut self, command: DrawCommandIndices) -> Result<(), DrawCommandError> {
+        check_bounds(self.commands.len(), command.first_index, command.count,
+                     self.indices_count)?;
+        self.commands.push(command);
+        Ok(())
+    }
+
+    /// Returns the list of commands that have been added.
+    #[inline]
+    pub fn commands(&self) -> &[DrawCommandIndices] {
+        &self.commands
+    }
+
+    /// Uploads the commands to a new buffer.
+    #[inline]
+    pub fn build<F: ?Sized>(&self, facade: &F)
+                            -> Result<DrawCommandsIndicesBuffer, BufferCreationError>
+                            where F: Facade
+    {
+        DrawCommandsIndicesBuffer::new(facade, &self.commands)
+    }
+}
@@ -112,7 +112,8 @@ result to the user.
 pub use crate::backend::glutin::glutin;
 #[cfg(feature = "simple_window_builder")]
 pub use crate::backend::winit;
-pub use crate::context::{Capabilities, ExtensionsList, Profile, UuidError};
+pub use crate::context::{Capabilities, ExtensionsList, NonSharedObject, Profile, ResetCallbackId};
+pub use crate::context::{StateGroups, UuidError};
 pub use crate::draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
 pub use crate::draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
 pub use crate::draw_parameters::Smooth;
@@ -123,11 +124,12 @@ pub use crate::program::ProgramCreationError::{CompilationError, LinkingError, S
 pub use crate::sync::{LinearSyncFence, SyncFence};
 pub use crate::texture::Texture2d;
 pub use crate::version::{Api, Version, get_supported_glsl_version};
-pub use crate::ops::ReadError;
+pub use crate::ops::{ReadError, ReadOptions};
+pub use crate::error::Error;
 
 use std::rc::Rc;
 use std::thread;
-use std::error::Error;
+use std::error::Error as StdError;
 use std::fmt;
 use std::hash::BuildHasherDefault;
 use std::collections::HashMap;
@@ -142,18 +144,29 @@ mod macros;
 
 pub mod backend;
 pub mod buffer;
+pub mod capture;
 pub mod debug;
+pub mod debug_draw;
+pub mod deferred;
 pub mod draw_parameters;
+pub mod error;
 pub mod framebuffer;
 pub mod index;
 pub mod memory_object;
+pub mod picking;
+pub mod postprocess;
 pub mod pixel_buffer;
 pub mod program;
 pub mod uniforms;
 pub mod vertex;
 pub mod semaphore;
+#[cfg(feature = "sprite")]
+pub mod sprite;
 pub mod texture;
+#[cfg(feature = "text")]
+pub mod text;
 pub mod field;
+pub mod upload;
 
 mod context;
 mod fbo;
@@ -168,6 +181,9 @@ mod vertex_array_object;
 mod gl {
     #![allow(clippy::all)]
     include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
+
+    #[cfg(all(feature = "websys_backend", target_arch = "wasm32"))]
+    pub mod websys;
 }
 
 #[doc(hidden)]
@@ -204,6 +220,17 @@ pub trait GlObject {
     fn get_id(&self) -> Self::Id;
 }
 
+/// Trait for objects that can be re-created after their context has been lost.
+///
+/// See `Context::register_recreatable`.
+pub trait Recreatable {
+    /// Replaces the OpenGL object with a new one that has the same parameters (dimensions,
+    /// format, size...). The content of the new object is undefined.
+    ///
+    /// This must only be called after the context of the object has been lost and rebuilt.
+    fn recreate(&mut self) -> Result<(), Error>;
+}
+
 /// Handle to a shader or a program.
 // TODO: Handle(null()) is equal to Id(0)
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
@@ -312,6 +339,10 @@ trait ProgramExt {
     fn set_uniform(&self, ctxt: &mut context::CommandContext<'_>, uniform_location: gl::types::GLint,
                    value: &RawUniformValue);
 
+    /// Changes the values of the first elements of a uniform array of the program.
+    fn set_uniform_array(&self, ctxt: &mut context::CommandContext<'_>,
+                         uniform_location: gl::types::GLint, value: &RawUniformArray<'_>);
+
     /// Changes the uniform block binding of the program.
     fn set_uniform_block_binding(&self, ctxt: &mut context::CommandContext<'_>,
                                  block_location: gl::types::GLuint, value: gl::types::GLuint);
@@ -328,6 +359,10 @@ trait ProgramExt {
 
     fn get_uniform(&self, name: &str) -> Option<&program::Uniform>;
 
+    /// Returns the uniform array with the given name, without the `[0]` suffix. The `size` of
+    /// the returned uniform is always `Some`.
+    fn get_uniform_array(&self, name: &str) -> Option<&program::Uniform>;
+
     fn get_uniform_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_shader_storage_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
@@ -349,6 +384,9 @@ trait QueryExt {
 
     fn end_transform_feedback_primitives_written_query(ctxt: &mut CommandContext<'_>);
 
+    fn end_pipeline_statistics_query(ctxt: &mut CommandContext<'_>,
+                                     statistic: draw_parameters::PipelineStatistic);
+
     fn begin_conditional_render(&self, ctxt: &mut CommandContext<'_>, wait: bool, per_region: bool);
 
     fn end_conditional_render(ctxt: &mut CommandContext<'_>);
@@ -385,6 +423,8 @@ enum TextureAccess {
     ImageUnit { will_write: bool },
     /// Texture being used as a framebuffer object
     Framebuffer,
+    /// Texture being modified or read with `glTexSubImage*`, `glGetTexImage` and similar
+    TextureUpdate,
 }
 
 /// Internal trait for textures.
@@ -416,11 +456,19 @@ trait TransformFeedbackSessionExt {
 
 /// Internal trait for uniforms handling.
 trait UniformsExt {
-    /// Binds the uniforms to a given program.
+    /// Binds the uniforms to the given programs, which are either a single program or the
+    /// stages of a program pipeline.
     ///
     /// Will replace texture and buffer bind points.
-    fn bind_uniforms<'a, P>(&'a self, _: &mut CommandContext<'_>, _: &P, _: &mut Vec<buffer::Inserter<'a>>)
+    fn bind_uniforms<'a, P>(&'a self, _: &mut CommandContext<'_>, _: &[&P], _: &mut Vec<buffer::Inserter<'a>>)
                             -> Result<(), DrawError> where P: ProgramExt;
+
+    /// Uploads the values that are passed with `glUniform` to a given program, without touching
+    /// textures, images, blocks or subroutines.
+    ///
+    /// The program doesn't need to be current if `glProgramUniform` is supported.
+    fn stage_uniform_values<P>(&self, _: &mut CommandContext<'_>, _: &P)
+                               -> Result<(), DrawError> where P: ProgramExt;
 }
 
 
@@ -466,6 +514,52 @@ pub enum RawUniformValue {
     UnsignedInt64Vec2([gl::types::GLuint64; 2]),
     UnsignedInt64Vec3([gl::types::GLuint64; 3]),
     UnsignedInt64Vec4([gl::types::GLuint64; 4]),
+    /// Handle of a resident texture, passed with `glUniformHandleui64ARB`.
+    Handle(gl::types::GLuint64),
+}
+
+/// The elements of a uniform array, passed with a single `glUniform*v` call.
+#[derive(Copy, Clone, Debug)]
+enum RawUniformArray<'a> {
+    SignedInt(&'a [gl::types::GLint]),
+    UnsignedInt(&'a [gl::types::GLuint]),
+    Float(&'a [gl::types::GLfloat]),
+    Mat2(&'a [[[gl::types::GLfloat; 2]; 2]]),
+    Mat3(&'a [[[gl::types::GLfloat; 3]; 3]]),
+    Mat4(&'a [[[gl::types::GLfloat; 4]; 4]]),
+    Vec2(&'a [[gl::types::GLfloat; 2]]),
+    Vec3(&'a [[gl::types::GLfloat; 3]]),
+    Vec4(&'a [[gl::types::GLfloat; 4]]),
+    IntVec2(&'a [[gl::types::GLint; 2]]),
+    IntVec3(&'a [[gl::types::GLint; 3]]),
+    IntVec4(&'a [[gl::types::GLint; 4]]),
+    UnsignedIntVec2(&'a [[gl::types::GLuint; 2]]),
+    UnsignedIntVec3(&'a [[gl::types::GLuint; 3]]),
+    UnsignedIntVec4(&'a [[gl::types::GLuint; 4]]),
+}
+
+impl<'a> RawUniformArray<'a> {
+    /// Returns the number of elements in the array.
+    #[inline]
+    fn len(&self) -> usize {
+        match *self {
+            RawUniformArray::SignedInt(v) => v.len(),
+            RawUniformArray::UnsignedInt(v) => v.len(),
+            RawUniformArray::Float(v) => v.len(),
+            RawUniformArray::Mat2(v) => v.len(),
+            RawUniformArray::Mat3(v) => v.len(),
+            RawUniformArray::Mat4(v) => v.len(),
+            RawUniformArray::Vec2(v) => v.len(),
+            RawUniformArray::Vec3(v) => v.len(),
+            RawUniformArray::Vec4(v) => v.len(),
+            RawUniformArray::IntVec2(v) => v.len(),
+            RawUniformArray::IntVec3(v) => v.len(),
+            RawUniformArray::IntVec4(v) => v.len(),
+            RawUniformArray::UnsignedIntVec2(v) => v.len(),
+            RawUniformArray::UnsignedIntVec3(v) => v.len(),
+            RawUniformArray::UnsignedIntVec4(v) => v.len(),
+        }
+    }
 }
 
 /// Area of a surface in pixels.
@@ -485,6 +579,63 @@ pub struct Rect {
     pub height: u32,
 }
 
+impl Rect {
+    /// Builds a `Rect` from coordinates where (0,0) is the top-left hand corner of the surface,
+    /// like window and UI coordinates usually are.
+    ///
+    /// `surface_height` is the height of the surface the rectangle refers to.
+    ///
+    /// ```
+    /// let rect = glium::Rect::from_top_left(600, 10, 20, 100, 50);
+    /// assert_eq!(rect, glium::Rect { left: 10, bottom: 530, width: 100, height: 50 });
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rectangle goes below the bottom of the surface.
+    #[inline]
+    pub fn from_top_left(surface_height: u32, left: u32, top: u32, width: u32, height: u32) -> Rect {
+        assert!(top.checked_add(height).map_or(false, |bottom| bottom <= surface_height),
+                "The rectangle is out of the surface");
+
+        Rect {
+            left,
+            bottom: surface_height - top - height,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the distance in pixels between the top border of the surface and the top border
+    /// of the rectangle. This is the opposite of `from_top_left`.
+    ///
+    /// ```
+    /// let rect = glium::Rect { left: 10, bottom: 530, width: 100, height: 50 };
+    /// assert_eq!(rect.get_top(600), 20);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rectangle goes above the top of the surface.
+    #[inline]
+    pub fn get_top(&self, surface_height: u32) -> u32 {
+        assert!(self.bottom.checked_add(self.height).map_or(false, |top| top <= surface_height),
+                "The rectangle is out of the surface");
+        surface_height - self.bottom - self.height
+    }
+
+    /// Returns true if the given point is inside of the rectangle.
+    ///
+    /// The coordinates use the same convention as the rectangle, in other words (0,0) is the
+    /// bottom-left hand corner. Use `from_top_left` to build the rectangle if you are working with
+    /// top-left coordinates.
+    #[inline]
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.left && (x as u64) < self.left as u64 + self.width as u64 &&
+        y >= self.bottom && (y as u64) < self.bottom as u64 + self.height as u64
+    }
+}
+
 /// Area of a surface in pixels. Similar to a `Rect` except that dimensions can be negative.
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
@@ -516,6 +667,41 @@ pub struct BlitMask {
     pub stencil: bool,
 }
 
+impl BlitTarget {
+    /// Builds a `BlitTarget` from coordinates where (0,0) is the top-left hand corner of the
+    /// surface. See `Rect::from_top_left`.
+    ///
+    /// ```
+    /// let target = glium::BlitTarget::from_top_left(600, 10, 20, 100, 50);
+    /// assert_eq!(target, glium::BlitTarget { left: 10, bottom: 530, width: 100, height: 50 });
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rectangle goes below the bottom of the surface, or if its dimensions don't
+    /// fit in an `i32`.
+    #[inline]
+    pub fn from_top_left(surface_height: u32, left: u32, top: u32, width: u32, height: u32)
+                         -> BlitTarget
+    {
+        assert!(width <= i32::MAX as u32 && height <= i32::MAX as u32,
+                "The dimensions of the rectangle are too large");
+        Rect::from_top_left(surface_height, left, top, width, height).into()
+    }
+}
+
+impl From<Rect> for BlitTarget {
+    #[inline]
+    fn from(rect: Rect) -> BlitTarget {
+        BlitTarget {
+            left: rect.left,
+            bottom: rect.bottom,
+            width: rect.width as i32,
+            height: rect.height as i32,
+        }
+    }
+}
+
 impl BlitMask {
 
     /// Constructs a bit mask, that will only copy the color buffer
@@ -843,11 +1029,28 @@ pub trait Surface {
     /// Returns the dimensions in pixels of the target.
     fn get_dimensions(&self) -> (u32, u32);
 
+    /// Builds a `Rect` on this surface from coordinates where (0,0) is the top-left hand corner.
+    ///
+    /// This is convenient to build viewports and scissor boxes from window coordinates. See
+    /// `Rect::from_top_left`.
+    #[inline]
+    fn rect_from_top_left(&self, left: u32, top: u32, width: u32, height: u32) -> Rect {
+        Rect::from_top_left(self.get_dimensions().1, left, top, width, height)
+    }
+
     /// Returns the number of bits of each pixel of the depth buffer.
     ///
     /// Returns `None` if there is no depth buffer.
     fn get_depth_buffer_bits(&self) -> Option<u16>;
 
+    /// Returns the format of the depth buffer.
+    ///
+    /// Returns `None` if there is no depth buffer or if its format is unknown.
+    #[inline]
+    fn get_depth_buffer_format(&self) -> Option<texture::InternalFormat> {
+        None
+    }
+
     /// Returns true if the surface has a depth buffer available.
     fn has_depth_buffer(&self) -> bool {
         self.get_depth_buffer_bits().is_some()
@@ -863,6 +1066,55 @@ pub trait Surface {
         self.get_stencil_buffer_bits().is_some()
     }
 
+    /// Returns the list of color attachments of the surface.
+    ///
+    /// This can be used by generic code to adapt to whatever render target it is given, for
+    /// example to choose a fragment shader with the right number of outputs.
+    ///
+    /// The default implementation returns an empty list.
+    #[inline]
+    fn get_color_attachments(&self) -> Vec<framebuffer::ColorAttachmentInfo> {
+        Vec::new()
+    }
+
+    /// Returns the number of color attachments of the surface.
+    #[inline]
+    fn get_color_attachments_count(&self) -> usize {
+        self.get_color_attachments().len()
+    }
+
+    /// Starts reading a rectangle of the first color attachment into a pixel buffer, without
+    /// waiting for the GPU.
+    ///
+    /// The returned `PendingRead` can be polled with `is_ready` once per frame, which makes it
+    /// possible to take screenshots without stalling the pipeline.
+    ///
+    /// The default implementation returns `ReadError::ReadNotSupported`.
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// use glium::Surface;
+    ///
+    /// let frame = display.draw();
+    /// let (width, height) = frame.get_dimensions();
+    /// let rect = glium::Rect { left: 0, bottom: 0, width, height };
+    /// let screenshot = frame.read_to_pixel_buffer(&rect).unwrap();
+    /// frame.finish().unwrap();
+    ///
+    /// // a few frames later
+    /// if screenshot.is_ready() {
+    ///     let pixels: Vec<Vec<(u8, u8, u8, u8)>> = screenshot.read().unwrap();
+    /// }
+    /// # }
+    /// ```
+    fn read_to_pixel_buffer(&self, rect: &Rect)
+                            -> Result<texture::pixel_buffer::PendingRead<(u8, u8, u8, u8)>, ReadError>
+    {
+        let _ = rect;
+        Err(ReadError::ReadNotSupported)
+    }
+
     /// Draws.
     ///
     /// This is probably the most complex function of glium. Check out the rest of the
@@ -872,7 +1124,98 @@ pub trait Surface {
     fn draw<'a, 'b, V, I, U>(&mut self, _: V, _: I, program: &Program, uniforms: &U,
         draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError> where
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
-        U: uniforms::Uniforms;
+        U: uniforms::Uniforms, Self: Sized;
+
+    /// Draws with either a `Program` or a `ProgramPipeline`.
+    ///
+    /// This is the same as `draw`, except that it also accepts program pipelines. The default
+    /// implementation calls `draw` for programs and returns `DrawError::DrawMethodNotSupported`
+    /// for pipelines. All the surfaces of glium override it.
+    ///
+    /// This is a separate method so that the implementations of `Surface` outside of glium,
+    /// which only implement `draw`, keep compiling.
+    fn draw_with_program<'a, 'b, V, I, U, P>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &P, uniforms: &U, draw_parameters: &DrawParameters<'_>)
+        -> Result<(), DrawError> where
+        V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
+        U: uniforms::Uniforms, P: program::DrawProgram + ?Sized, Self: Sized
+    {
+        match program.as_draw_program() {
+            program::DrawProgramSource::Program(program) =>
+                self.draw(vertex_buffer, index_buffer, program, uniforms, draw_parameters),
+            program::DrawProgramSource::Pipeline(_) => Err(DrawError::DrawMethodNotSupported),
+        }
+    }
+
+    /// Draws, with type-erased parameters.
+    ///
+    /// This is the same as `draw_with_program`, except that it can be called on a
+    /// `&mut dyn Surface`. This allows writing code that renders to any target without being
+    /// generic over it.
+    ///
+    /// The default implementation returns `DrawError::DrawMethodNotSupported`. All the surfaces
+    /// of glium override it.
+    ///
+    /// ```no_run
+    /// # fn example(surface: &mut dyn glium::Surface, program: &glium::Program,
+    /// #            vertex_buffer: &glium::vertex::VertexBufferAny) {
+    /// surface.draw_dyn(&[vertex_buffer.into()],
+    ///                  glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList).into(),
+    ///                  program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+    /// # }
+    /// ```
+    fn draw_dyn(&mut self, vertices: &[vertex::VerticesSource<'_>],
+                indices: index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn uniforms::DynUniforms, draw_parameters: &DrawParameters<'_>)
+                -> Result<(), DrawError>
+    {
+        let _ = (vertices, indices, program, uniforms, draw_parameters);
+        Err(DrawError::DrawMethodNotSupported)
+    }
+
+    /// Draws a list of commands, after reordering them to reduce the number of state changes.
+    ///
+    /// The commands are sorted by program, then by texture, then by draw parameters, so that
+    /// consecutive commands share as much state as possible. Glium skips the program binds,
+    /// texture binds and state changes that aren't needed, which makes this faster than
+    /// calling `draw` in an arbitrary order when there are many commands. The sort is stable,
+    /// which means that commands with the same state are drawn in order.
+    ///
+    /// Since the commands are reordered, this function shouldn't be used for commands whose
+    /// order matters, such as transparent objects drawn with blending.
+    ///
+    /// Stops at the first error, in which case some commands may not have been drawn.
+    ///
+    /// ```no_run
+    /// # fn example<S: glium::Surface>(surface: &mut S, program: &glium::Program,
+    /// #            meshes: &[(glium::vertex::VertexBufferAny, glium::Texture2d)]) {
+    /// use glium::DrawCommand;
+    /// use glium::index::{NoIndices, PrimitiveType};
+    ///
+    /// let uniforms = meshes.iter().map(|(_, texture)| glium::uniform! { tex: texture })
+    ///                      .collect::<Vec<_>>();
+    /// let params = Default::default();
+    ///
+    /// surface.draw_batch(meshes.iter().zip(uniforms.iter()).map(|((vertices, _), uniforms)| {
+    ///     DrawCommand::new(vertices, NoIndices(PrimitiveType::TrianglesList), program,
+    ///                      uniforms, &params)
+    /// })).unwrap();
+    /// # }
+    /// ```
+    fn draw_batch<'a, C>(&mut self, commands: C) -> Result<(), DrawError>
+        where C: IntoIterator<Item = DrawCommand<'a>>, Self: Sized
+    {
+        let mut commands = commands.into_iter().map(|command| (command.sort_key(), command))
+                                   .collect::<Vec<_>>();
+        commands.sort_by_key(|(key, _)| *key);
+
+        for (_, command) in commands {
+            self.draw_dyn(&command.vertices, command.indices, command.program, command.uniforms,
+                          command.draw_parameters)?;
+        }
+
+        Ok(())
+    }
 
     /// Blits from the default framebuffer.
     #[inline]
@@ -932,13 +1275,57 @@ pub trait Surface {
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface;
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized, Self: Sized;
 
 
+    /// Copies a rectangle of the buffers selected by `mask` from this surface to another
+    /// surface. See `blit_color`.
+    ///
+    /// Blitting the depth or stencil buffer requires both surfaces to have a depth or stencil
+    /// buffer of the same format, and `filter` to be `Nearest`. This makes it possible to
+    /// resolve a multisampled depth buffer for example.
+    ///
+    /// The default implementation only knows how to copy the color buffer and returns
+    /// `BlitError::NotSupported` if the depth or stencil buffer is requested.
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized, Self: Sized
+    {
+        if mask.depth || mask.stencil {
+            return Err(BlitError::NotSupported);
+        }
+
+        if mask.color {
+            self.blit_color(source_rect, target, target_rect, filter);
+        }
+
+        Ok(())
+    }
+
+    /// Copies a rectangle of the depth buffer from this surface to another surface. See
+    /// `blit_buffers`.
+    #[inline]
+    fn blit_depth<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget)
+                     -> Result<(), BlitError> where S: Surface + ?Sized, Self: Sized
+    {
+        self.blit_buffers(source_rect, target, target_rect,
+                          uniforms::MagnifySamplerFilter::Nearest, BlitMask::depth())
+    }
+
+    /// Copies a rectangle of the stencil buffer from this surface to another surface. See
+    /// `blit_buffers`.
+    #[inline]
+    fn blit_stencil<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget)
+                       -> Result<(), BlitError> where S: Surface + ?Sized, Self: Sized
+    {
+        self.blit_buffers(source_rect, target, target_rect,
+                          uniforms::MagnifySamplerFilter::Nearest, BlitMask::stencil())
+    }
+
     /// Copies the entire surface to a target surface. See `blit_color`.
     #[inline]
     fn blit_whole_color_to<S>(&self, target: &S, target_rect: &BlitTarget,
-        filter: uniforms::MagnifySamplerFilter) where S: Surface
+        filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized, Self: Sized
     {
         let src_dim = self.get_dimensions();
         let src_rect = Rect { left: 0, bottom: 0, width: src_dim.0 as u32, height: src_dim.1 as u32 };
@@ -947,7 +1334,9 @@ pub trait Surface {
 
     /// Copies the entire surface to the entire target. See `blit_color`.
     #[inline]
-    fn fill<S>(&self, target: &S, filter: uniforms::MagnifySamplerFilter) where S: Surface {
+    fn fill<S>(&self, target: &S, filter: uniforms::MagnifySamplerFilter)
+        where S: Surface + ?Sized, Self: Sized
+    {
         let src_dim = self.get_dimensions();
         let src_rect = Rect { left: 0, bottom: 0, width: src_dim.0 as u32, height: src_dim.1 as u32 };
         let target_dim = target.get_dimensions();
@@ -956,6 +1345,84 @@ pub trait Surface {
     }
 }
 
+/// A draw command that is part of a batch. See `Surface::draw_batch`.
+pub struct DrawCommand<'a> {
+    vertices: smallvec::SmallVec<[vertex::VerticesSource<'a>; 2]>,
+    indices: index::IndicesSource<'a>,
+    program: &'a dyn program::DrawProgram,
+    uniforms: &'a dyn uniforms::DynUniforms,
+    draw_parameters: &'a DrawParameters<'a>,
+}
+
+impl<'a> DrawCommand<'a> {
+    /// Builds a draw command. The parameters are the same as the ones of `Surface::draw`.
+    pub fn new<V, I, P, U>(vertices: V, indices: I, program: &'a P, uniforms: &'a U,
+                           draw_parameters: &'a DrawParameters<'a>) -> DrawCommand<'a>
+        where V: vertex::MultiVerticesSource<'a>, I: Into<index::IndicesSource<'a>>,
+              P: program::DrawProgram, U: uniforms::Uniforms
+    {
+        DrawCommand {
+            vertices: vertices.iter().collect(),
+            indices: indices.into(),
+            program,
+            uniforms,
+            draw_parameters,
+        }
+    }
+
+    /// Returns the key that the commands of a batch are sorted with.
+    fn sort_key(&self) -> (usize, Option<gl::types::GLuint>, usize) {
+        let mut texture = None;
+        self.uniforms.visit_values_dyn(&mut |_, value| {
+            if let Some(id) = value.sampled_texture_id() {
+                texture = Some(texture.map_or(id, |t: gl::types::GLuint| t.min(id)));
+            }
+        });
+
+        // commands that use the same program or parameters object are grouped together
+        (self.program as *const dyn program::DrawProgram as *const () as usize, texture,
+         self.draw_parameters as *const DrawParameters<'_> as usize)
+    }
+}
+
+/// Checks that the buffers selected by `mask` can be blitted from `source` to `target`.
+fn check_blit_buffers<S, T>(source: &S, target: &T, filter: uniforms::MagnifySamplerFilter,
+                            mask: BlitMask) -> Result<(), BlitError>
+    where S: Surface + ?Sized, T: Surface + ?Sized
+{
+    if (mask.depth || mask.stencil) && filter != uniforms::MagnifySamplerFilter::Nearest {
+        return Err(BlitError::FilterNotSupported);
+    }
+
+    if mask.depth {
+        match (source.get_depth_buffer_bits(), target.get_depth_buffer_bits()) {
+            (Some(a), Some(b)) if a == b => (),
+            (Some(_), Some(_)) => return Err(BlitError::DepthFormatMismatch),
+            _ => return Err(BlitError::NoDepthBuffer),
+        }
+
+        // two buffers with the same number of bits can still have different formats, for
+        // example `F32` and `I32`
+        if let (Some(a), Some(b)) = (source.get_depth_buffer_format(),
+                                     target.get_depth_buffer_format())
+        {
+            if a != b {
+                return Err(BlitError::DepthFormatMismatch);
+            }
+        }
+    }
+
+    if mask.stencil {
+        match (source.get_stencil_buffer_bits(), target.get_stencil_buffer_bits()) {
+            (Some(a), Some(b)) if a == b => (),
+            (Some(_), Some(_)) => return Err(BlitError::StencilFormatMismatch),
+            _ => return Err(BlitError::NoStencilBuffer),
+        }
+    }
+
+    Ok(())
+}
+
 /// Private trait for framebuffer-like objects that provide attachments.
 trait FboAttachments {
     /// Returns the list of attachments of this FBO, or `None` if it is the default framebuffer.
@@ -992,6 +1459,16 @@ pub enum DrawError {
         expected: uniforms::UniformType,
     },
 
+    /// Tried to set more elements than a uniform array contains.
+    UniformArrayTooLong {
+        /// Name of the uniform array you are trying to set.
+        name: String,
+        /// Number of elements that you gave.
+        len: usize,
+        /// Number of elements of the array in the program.
+        max: usize,
+    },
+
     /// Tried to bind a uniform buffer to a single uniform value.
     UniformBufferToValue {
         /// Name of the uniform you are trying to bind.
@@ -1076,24 +1553,91 @@ pub enum DrawError {
     /// One of the blending parameters is not supported by the backend.
     BlendingParameterNotSupported,
 
+    /// Setting a different blending function for each draw buffer is not supported by the
+    /// backend.
+    BlendPerBufferNotSupported,
+
+    /// Tried to set more blending functions than the number of draw buffers supported by the
+    /// backend.
+    TooManyBlendBuffers,
+
+    /// Controlling the conversion of the fragment shader output to sRGB is not supported by
+    /// the backend.
+    SrgbConversionNotSupported,
+
     /// Restarting indices (multiple objects per draw call) is not supported by the backend.
     FixedIndexRestartingNotSupported,
 
     /// Changing the clip volume definition (origin and depth mode) is not supported by the backend.
     ClipControlNotSupported,
 
+    /// Setting the default tessellation levels is not supported by the backend.
+    TessellationDefaultLevelsNotSupported,
+
+    /// Sampling the stencil component of a depth-stencil texture is not supported by the backend.
+    StencilTexturingNotSupported,
+
+    /// Setting multiple viewports or scissor boxes is not supported by the backend.
+    ViewportArrayNotSupported,
+
+    /// Tried to set more viewports or scissor boxes than the backend supports.
+    TooManyViewports,
+
     /// Tried to enable a clip plane that does not exist.
     ClipPlaneIndexOutOfBounds,
 
     /// Tried to use too many image units simultaneously
     InsufficientImageUnits,
+
+    /// Tried to use a texture handle, but bindless textures are not supported by the backend.
+    BindlessTexturesNotSupported,
+
+    /// A texture handle was used while the texture is not resident in the current context.
+    NonResidentTextureHandle {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// Tried to draw the vertices written by transform feedback, but this is not supported by
+    /// the backend.
+    DrawTransformFeedbackNotSupported,
+
+    /// Per-sample shading is not supported by the backend.
+    SampleShadingNotSupported,
+
+    /// The textures of the draw command don't fit in the texture units of the backend.
+    ///
+    /// Several textures can be packed in a single texture unit with a
+    /// `uniforms::TextureBatch`.
+    TooManyTextureUnits {
+        /// Number of texture units that the draw command requires, at least.
+        used: usize,
+        /// Number of texture units supported by the backend.
+        max: usize,
+    },
+
+    /// A range of `index::MultiRange` is out of the bounds of the vertices source.
+    VertexRangeOutOfBounds,
+
+    /// An index buffer slice has a base vertex, but this is not supported by the backend.
+    BaseVertexNotSupported,
+
+    /// An index buffer slice has a base instance, but this is not supported by the backend.
+    BaseInstanceNotSupported,
+
+    /// The indices failed the validation requested with `DrawParameters::validate_indices`.
+    IndexValidationError(index::IndexValidationError),
+
+    /// The surface doesn't implement this draw method.
+    DrawMethodNotSupported,
 }
 
-impl Error for DrawError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
+impl StdError for DrawError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use self::DrawError::*;
         match *self {
             UniformBlockLayoutMismatch { ref err, .. } => Some(err),
+            IndexValidationError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -1116,6 +1660,8 @@ impl fmt::Display for DrawError {
                 "The depth range is outside of the `(0, 1)` range",
             UniformTypeMismatch { .. } =>
                 "The type of a uniform doesn't match what the program requires",
+            UniformArrayTooLong { .. } =>
+                "Tried to set more elements than a uniform array contains",
             UniformBufferToValue { .. } =>
                 "Tried to bind a uniform buffer to a single uniform value",
             UniformValueToBlock { .. } =>
@@ -1154,14 +1700,48 @@ impl fmt::Display for DrawError {
                 "The depth clamp mode is not supported by the backend",
             BlendingParameterNotSupported =>
                 "One the blending parameters is not supported by the backend",
+            BlendPerBufferNotSupported =>
+                "Setting a different blending function for each draw buffer is not supported by the backend",
+            TooManyBlendBuffers =>
+                "Tried to set more blending functions than the number of draw buffers supported by the backend",
+            SrgbConversionNotSupported =>
+                "Controlling the conversion of the fragment shader output to sRGB is not supported by the backend",
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
             ClipControlNotSupported =>
                 "Changing the clip volume definition (origin and depth mode) is not supported by the backend",
+            TessellationDefaultLevelsNotSupported =>
+                "Setting the default tessellation levels is not supported by the backend",
+            StencilTexturingNotSupported =>
+                "Sampling the stencil component of a depth-stencil texture is not supported by the backend",
+            ViewportArrayNotSupported =>
+                "Setting multiple viewports or scissor boxes is not supported by the backend",
+            TooManyViewports =>
+                "Tried to set more viewports or scissor boxes than the backend supports",
             ClipPlaneIndexOutOfBounds =>
                 "Tried to enable a clip plane that does not exist.",
             InsufficientImageUnits =>
                 "Tried to use more image uniforms that the implementation has support for",
+            BindlessTexturesNotSupported =>
+                "Tried to use a texture handle, but bindless textures are not supported by the backend",
+            NonResidentTextureHandle { .. } =>
+                "A texture handle was used while the texture is not resident in the current context",
+            DrawTransformFeedbackNotSupported =>
+                "Drawing the vertices written by transform feedback is not supported by the backend",
+            SampleShadingNotSupported =>
+                "Per-sample shading is not supported by the backend",
+            TooManyTextureUnits { .. } =>
+                "The textures of the draw command don't fit in the texture units of the backend",
+            VertexRangeOutOfBounds =>
+                "A range of vertices is out of the bounds of the vertices source",
+            BaseVertexNotSupported =>
+                "Drawing with a base vertex is not supported by the backend",
+            BaseInstanceNotSupported =>
+                "Drawing with a base instance is not supported by the backend",
+            IndexValidationError(_) =>
+                "The validation of the indices failed",
+            DrawMethodNotSupported =>
+                "The surface doesn't implement this draw method",
         };
         match self {
             UniformTypeMismatch { ref name, ref expected } =>
@@ -1172,6 +1752,15 @@ impl fmt::Display for DrawError {
                     name,
                     expected,
                 ),
+            UniformArrayTooLong { name, len, max } =>
+                write!(
+                    fmt,
+                    "{}: {}, got {} elements, expected at most {}",
+                    desc,
+                    name,
+                    len,
+                    max,
+                ),
             UniformBufferToValue { name } =>
                 write!(
                     fmt,
@@ -1186,6 +1775,21 @@ impl fmt::Display for DrawError {
                     desc,
                     name,
                 ),
+            NonResidentTextureHandle { name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    name,
+                ),
+            TooManyTextureUnits { used, max } =>
+                write!(
+                    fmt,
+                    "{}, {} texture units are required but only {} are supported",
+                    desc,
+                    used,
+                    max,
+                ),
             UniformBlockLayoutMismatch { name, err } =>
                 write!(
                     fmt,
@@ -1194,12 +1798,85 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            IndexValidationError(err) =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    desc,
+                    err,
+                ),
             _ =>
                 fmt.write_str(desc),
         }
     }
 }
 
+/// Error that can happen when blitting the buffers of a surface.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlitError {
+    /// The depth buffer must be blitted but one of the surfaces doesn't have one.
+    NoDepthBuffer,
+    /// The stencil buffer must be blitted but one of the surfaces doesn't have one.
+    NoStencilBuffer,
+    /// The depth buffers of the two surfaces don't have the same format.
+    DepthFormatMismatch,
+    /// The stencil buffers of the two surfaces don't have the same format.
+    StencilFormatMismatch,
+    /// The depth and stencil buffers can only be blitted with the `Nearest` filter.
+    FilterNotSupported,
+    /// The color buffer must be blitted but the source surface doesn't have one.
+    NoColorBuffer,
+    /// The source surface doesn't support blitting the requested buffers.
+    NotSupported,
+    /// The two images are not of the same kind, for example a color image and a depth image.
+    ImageKindMismatch,
+    /// The framebuffer of one of the images couldn't be built.
+    ValidationError(fbo::ValidationError),
+}
+
+impl StdError for BlitError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            BlitError::ValidationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BlitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        use self::BlitError::*;
+        let desc = match *self {
+            NoDepthBuffer =>
+                "One of the surfaces doesn't have a depth buffer",
+            NoStencilBuffer =>
+                "One of the surfaces doesn't have a stencil buffer",
+            DepthFormatMismatch =>
+                "The depth buffers of the surfaces don't have the same format",
+            StencilFormatMismatch =>
+                "The stencil buffers of the surfaces don't have the same format",
+            FilterNotSupported =>
+                "The depth and stencil buffers can only be blitted with the `Nearest` filter",
+            NoColorBuffer =>
+                "The source surface doesn't have a color buffer",
+            NotSupported =>
+                "The source surface doesn't support blitting the requested buffers",
+            ImageKindMismatch =>
+                "The two images are not of the same kind",
+            ValidationError(_) =>
+                "The framebuffer of one of the images couldn't be built",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl From<fbo::ValidationError> for BlitError {
+    #[inline]
+    fn from(err: fbo::ValidationError) -> BlitError {
+        BlitError::ValidationError(err)
+    }
+}
+
 /// Error that can happen when swapping buffers.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SwapBuffersError {
@@ -1221,7 +1898,7 @@ pub enum SwapBuffersError {
     AlreadySwapped,
 }
 
-impl Error for SwapBuffersError {}
+impl StdError for SwapBuffersError {}
 
 impl fmt::Display for SwapBuffersError {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -1236,28 +1913,69 @@ impl fmt::Display for SwapBuffersError {
     }
 }
 
+/// What happens when a `Frame` is dropped without `finish` or `set_finish` having been called.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameDropPolicy {
+    /// The destructor panics. This is the default, as forgetting to call `finish` is usually
+    /// a bug.
+    Panic,
+
+    /// The destructor swaps the buffers as if `set_finish` had been called. Errors are ignored.
+    ///
+    /// This is useful if you return early from your drawing code, for example with the `?`
+    /// operator.
+    Finish,
+}
+
+impl Default for FrameDropPolicy {
+    #[inline]
+    fn default() -> FrameDropPolicy {
+        FrameDropPolicy::Panic
+    }
+}
+
 /// Implementation of `Surface`, targeting the default framebuffer.
 ///
 /// The back- and front-buffers are swapped when you call `finish`.
 ///
-/// You **must** call either `finish` or `set_finish` or else the destructor will panic.
+/// You **must** call either `finish` or `set_finish` or else the destructor will panic, unless
+/// the drop policy has been set to `FrameDropPolicy::Finish`. See `set_drop_policy` and
+/// `Context::set_frame_drop_policy`.
 pub struct Frame {
     context: Rc<Context>,
     dimensions: (u32, u32),
     destroyed: bool,        // TODO: use a linear type instead.
+    drop_policy: FrameDropPolicy,
 }
 
 impl Frame {
     /// Builds a new `Frame`. Use the `draw` function on `Display` instead of this function.
+    ///
+    /// The drop policy of the frame is the one of the context.
     #[inline]
     pub fn new(context: Rc<Context>, dimensions: (u32, u32)) -> Frame {
+        let drop_policy = context.get_frame_drop_policy();
+
         Frame {
             context,
             dimensions,
             destroyed: false,
+            drop_policy,
         }
     }
 
+    /// Changes what happens when this frame is dropped without having been finished.
+    #[inline]
+    pub fn set_drop_policy(&mut self, policy: FrameDropPolicy) {
+        self.drop_policy = policy;
+    }
+
+    /// Returns what happens when this frame is dropped without having been finished.
+    #[inline]
+    pub fn get_drop_policy(&self) -> FrameDropPolicy {
+        self.drop_policy
+    }
+
     /// Stop drawing, swap the buffers, and consume the Frame.
     ///
     /// See the documentation of `SwapBuffersError` about what is being returned.
@@ -1301,11 +2019,37 @@ impl Surface for Frame {
         self.context.capabilities().stencil_bits
     }
 
-    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V,
-                         index_buffer: I, program: &Program, uniforms: &U,
+    fn get_color_attachments(&self) -> Vec<framebuffer::ColorAttachmentInfo> {
+        vec![framebuffer::ColorAttachmentInfo {
+            index: 0,
+            format: None,
+            samples: self.context.capabilities().samples,
+        }]
+    }
+
+    #[inline]
+    fn read_to_pixel_buffer(&self, rect: &Rect)
+                            -> Result<texture::pixel_buffer::PendingRead<(u8, u8, u8, u8)>, ReadError>
+    {
+        let source = ops::Source::DefaultFramebuffer(gl::BACK_LEFT, self.dimensions);
+        texture::pixel_buffer::PendingRead::new(&self.context, source, rect)
+    }
+
+    #[inline]
+    fn draw<'a, 'b, V, I, U>(&mut self, vertex_buffer: V, index_buffer: I,
+        program: &Program, uniforms: &U, draw_parameters: &DrawParameters<'_>)
+        -> Result<(), DrawError>
+        where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
+        V: vertex::MultiVerticesSource<'b>
+    {
+        self.draw_with_program(vertex_buffer, index_buffer, program, uniforms, draw_parameters)
+    }
+
+    fn draw_with_program<'a, 'b, V, I, U, P>(&mut self, vertex_buffer: V,
+                         index_buffer: I, program: &P, uniforms: &U,
                          draw_parameters: &DrawParameters<'_>) -> Result<(), DrawError>
                          where I: Into<index::IndicesSource<'a>>, U: uniforms::Uniforms,
-                         V: vertex::MultiVerticesSource<'b>
+                         V: vertex::MultiVerticesSource<'b>, P: program::DrawProgram + ?Sized
     {
         if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
                 draw_parameters.depth.write)
@@ -1326,17 +2070,36 @@ impl Surface for Frame {
             }
         }
 
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
+        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program.as_draw_program(),
                   uniforms, draw_parameters, self.dimensions)
     }
 
+    #[inline]
+    fn draw_dyn(&mut self, vertices: &[vertex::VerticesSource<'_>],
+                indices: index::IndicesSource<'_>, program: &dyn program::DrawProgram,
+                uniforms: &dyn uniforms::DynUniforms, draw_parameters: &DrawParameters<'_>)
+                -> Result<(), DrawError>
+    {
+        self.draw_with_program(vertices, indices, program, &uniforms, draw_parameters)
+    }
+
     #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface + ?Sized
     {
         target.blit_from_frame(source_rect, target_rect, filter)
     }
 
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                       -> Result<(), BlitError> where S: Surface + ?Sized
+    {
+        check_blit_buffers(self, target, filter, mask)?;
+        target.blit_buffers_from_frame(source_rect, target_rect, filter, mask);
+        Ok(())
+    }
+
     fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter, mask: BlitMask) {
         ops::blit(&self.context, None, self.get_attachments(),
                   mask.to_glenum(), source_rect, target_rect, filter.to_glenum())
@@ -1370,9 +2133,19 @@ impl FboAttachments for Frame {
 impl Drop for Frame {
     #[inline]
     fn drop(&mut self) {
-        if !thread::panicking() {
-            assert!(self.destroyed, "The `Frame` object must be explicitly destroyed \
-                                     by calling `.finish()`");
+        if self.destroyed {
+            return;
+        }
+
+        match self.drop_policy {
+            FrameDropPolicy::Panic => {
+                if !thread::panicking() {
+                    panic!("The `Frame` object must be explicitly destroyed by calling `.finish()`");
+                }
+            },
+            FrameDropPolicy::Finish => {
+                let _ = self.set_finish();
+            },
         }
     }
 }
@@ -1387,7 +2160,7 @@ impl fmt::Display for IncompatibleOpenGl {
     }
 }
 
-impl Error for IncompatibleOpenGl {}
+impl StdError for IncompatibleOpenGl {}
 
 #[allow(dead_code)]
 #[inline]
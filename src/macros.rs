@@ -412,12 +412,20 @@ macro_rules! implement_uniform_block {
     (__as_item $i:item) => {$i};
 
     (__impl $struct_name:ident [$($gs:tt)*], $($field_name:ident),+) => (
-        implement_uniform_block! { __as_item
+        $crate::implement_uniform_block! { __as_item
             impl<$($gs)*> $crate::uniforms::UniformBlock for $struct_name<$($gs)*> {
+                #[inline]
                 fn matches(layout: &$crate::program::BlockLayout, base_offset: usize)
                            -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
                 {
-                    use std::mem;
+                    <Self as $crate::uniforms::UniformBlock>::matches_memory_layout(layout, base_offset,
+                        $crate::program::BlockMemoryLayout::Std140)
+                }
+
+                fn matches_memory_layout(layout: &$crate::program::BlockLayout, base_offset: usize,
+                                         memory_layout: $crate::program::BlockMemoryLayout)
+                                         -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
+                {
                     use $crate::program::BlockLayout;
                     use $crate::uniforms::LayoutMismatchError;
 
@@ -431,11 +439,13 @@ macro_rules! implement_uniform_block {
                             }
                         }
 
-                        fn matches_from_ty<T: $crate::uniforms::UniformBlock + ?Sized>(_: &T,
-                            layout: &$crate::program::BlockLayout, base_offset: usize)
+                        fn matches_from_ty<T: $crate::uniforms::UniformBlock + ?Sized>(_: Option<&T>,
+                            layout: &$crate::program::BlockLayout, base_offset: usize,
+                            memory_layout: $crate::program::BlockMemoryLayout)
                             -> ::std::result::Result<(), $crate::uniforms::LayoutMismatchError>
                         {
-                            <T as $crate::uniforms::UniformBlock>::matches(layout, base_offset)
+                            <T as $crate::uniforms::UniformBlock>::matches_memory_layout(layout, base_offset,
+                                                                                         memory_layout)
                         }
 
                         // checking that each field of the input struct is correct in the reflection
@@ -449,15 +459,11 @@ macro_rules! implement_uniform_block {
                                     name: stringify!($field_name).to_owned(),
                                 })
                             };
-                            let dummy: *const $struct_name = unsafe { mem::zeroed() };
-                            let input_offset = {
-                                let possibly_fat_pointer_to_field=unsafe{&(*dummy).$field_name};
-                                let pointer_to_possibly_fat_pointer_to_field:&u64=unsafe{mem::transmute( &possibly_fat_pointer_to_field )};
-                                let pointer_to_field=*pointer_to_possibly_fat_pointer_to_field;
-                                pointer_to_field as usize
-                            };
+                            let input_offset = $crate::__glium_offset_of!($struct_name, $field_name);
+                            let field_option = None::<&$struct_name>.map(|v| &v.$field_name);
 
-                            match matches_from_ty(unsafe{&(*dummy).$field_name}, reflected_ty, input_offset) {
+                            match matches_from_ty(field_option, reflected_ty, input_offset + base_offset,
+                                                  memory_layout) {
                                 Ok(_) => (),
                                 Err(e) => return Err(LayoutMismatchError::MemberMismatch {
                                     member: stringify!($field_name).to_owned(),
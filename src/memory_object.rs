@@ -2,8 +2,6 @@
 Contains everything related to external API memory objects.
 */
 
-// TODO: Add Windows support via EXT_external_objects_win32
-
 use crate::GlObject;
 use crate::context::CommandContext;
 use crate::gl;
@@ -22,6 +20,8 @@ pub enum MemoryObjectCreationError {
     MemoryObjectNotSupported,
     /// Driver does not support EXT_memory_object_fd
     MemoryObjectFdNotSupported,
+    /// Driver does not support EXT_memory_object_win32
+    MemoryObjectWin32NotSupported,
     /// OpenGL returned a null pointer when creating memory object
     NullResult,
 }
@@ -33,6 +33,7 @@ impl std::fmt::Display for MemoryObjectCreationError {
         let desc = match *self {
             MemoryObjectNotSupported => "Driver does not support EXT_memory_object",
             MemoryObjectFdNotSupported => "Driver does not support EXT_memory_object_fd",
+            MemoryObjectWin32NotSupported => "Driver does not support EXT_memory_object_win32",
             NullResult => "OpenGL returned a null pointer when creating memory object",
         };
         f.write_str(desc)
@@ -92,6 +93,44 @@ impl MemoryObject {
         }
     }
 
+    /// Creates a memory object from an opaque Win32 handle. Contrary to file descriptors, the
+    /// handle isn't consumed and must be closed by the caller.
+    #[cfg(target_os = "windows")]
+    pub unsafe fn new_from_win32_handle<F: Facade + ?Sized>(
+        facade: &F,
+        dedicated: bool,
+        handle: *mut std::os::raw::c_void,
+        size: u64,
+    ) -> Result<Self, MemoryObjectCreationError> {
+        let ctxt = facade.get_context().make_current();
+        let mem_obj: Self = Self::new(facade, &ctxt)?;
+
+        if !ctxt.extensions.gl_ext_memory_object_win32 {
+            Err(MemoryObjectCreationError::MemoryObjectWin32NotSupported)
+        } else {
+            let dedicated: gl::types::GLint = if dedicated {
+                gl::TRUE as i32
+            } else {
+                gl::FALSE as i32
+            };
+
+            ctxt.gl.MemoryObjectParameterivEXT(
+                mem_obj.id,
+                gl::DEDICATED_MEMORY_OBJECT_EXT,
+                &dedicated as *const i32,
+            );
+
+            ctxt.gl.ImportMemoryWin32HandleEXT(
+                mem_obj.id,
+                size,
+                gl::HANDLE_TYPE_OPAQUE_WIN32_EXT,
+                handle,
+            );
+
+            Ok(mem_obj)
+        }
+    }
+
     fn new<F: Facade + ?Sized>(
         facade: &F,
         ctxt: &CommandContext<'_>,
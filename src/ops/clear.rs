@@ -54,6 +54,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
             if ctxt.state.scissor != Some(rect) {
                 ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
                 ctxt.state.scissor = Some(rect);
+                ctxt.state.scissor_array.clear();
             }
 
             if !ctxt.state.enabled_scissor_test {
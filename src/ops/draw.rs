@@ -1,19 +1,22 @@
 use std::ptr;
 
+use smallvec::SmallVec;
+
 use crate::BufferExt;
 use crate::BufferSliceExt;
-use crate::ProgramExt;
 use crate::DrawError;
 use crate::UniformsExt;
 
 use crate::context::Context;
 use crate::ContextExt;
+use crate::GlObject;
 use crate::TransformFeedbackSessionExt;
 
 use crate::fbo::{self, ValidatedAttachments};
 
 use crate::uniforms::Uniforms;
-use crate::{Program, ToGlEnum};
+use crate::ToGlEnum;
+use crate::program::DrawProgramSource;
 use crate::index::{self, IndicesSource};
 use crate::vertex::{MultiVerticesSource, VerticesSource, TransformFeedbackSession};
 use crate::vertex_array_object::VertexAttributesSystem;
@@ -27,13 +30,11 @@ use crate::version::Api;
 /// Draws everything.
 pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachments<'_>>,
                       vertex_buffers: V, indices: IndicesSource<'_>,
-                      program: &Program, uniforms: &U, draw_parameters: &DrawParameters<'_>,
+                      program: DrawProgramSource<'_>, uniforms: &U, draw_parameters: &DrawParameters<'_>,
                       dimensions: (u32, u32)) -> Result<(), DrawError>
                       where U: Uniforms, V: MultiVerticesSource<'a>
 {
-    // this contains the list of fences that will need to be fulfilled after the draw command
-    // has started
-    let mut fences = Vec::with_capacity(0);
+    draw_parameters::validate(context, draw_parameters)?;
 
     // handling tessellation
     let vertices_per_patch = match indices.get_primitives_type() {
@@ -65,9 +66,55 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         },
     };
 
+    let vertex_buffers: SmallVec<[VerticesSource<'_>; 4]> = vertex_buffers.iter().collect();
+
+    // reading the indices back, before the context is borrowed for the state changes
+    if draw_parameters.validate_indices {
+        if let IndicesSource::IndexBuffer { ref buffer, data_type, base_vertex, .. } = indices {
+            let vertices_count = vertex_buffers.iter().filter_map(|src| match src {
+                VerticesSource::VertexBuffer(buffer, _, false) => Some(buffer.get_elements_count()),
+                VerticesSource::Marker { len, per_instance: false } => Some(*len),
+                _ => None,
+            }).min();
+
+            if let Some(vertices_count) = vertices_count {
+                index::validate_indices_buffer(buffer, data_type, base_vertex, vertices_count,
+                                               draw_parameters.primitive_restart_index)
+                    .map_err(DrawError::IndexValidationError)?;
+            }
+        }
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
+    if let IndicesSource::TransformFeedback { .. } = indices {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 0)) && !ctxt.extensions.gl_arb_transform_feedback2 {
+            return Err(DrawError::DrawTransformFeedbackNotSupported);
+        }
+    }
+
+    if let IndicesSource::IndexBuffer { base_vertex, base_instance, .. } = indices {
+        if base_vertex != 0 && !(ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                                 ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                                 ctxt.extensions.gl_arb_draw_elements_base_vertex ||
+                                 ctxt.extensions.gl_oes_draw_elements_base_vertex)
+        {
+            return Err(DrawError::BaseVertexNotSupported);
+        }
+
+        if base_instance != 0 && !(ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                                   ctxt.extensions.gl_arb_base_instance ||
+                                   ctxt.extensions.gl_ext_base_instance)
+        {
+            return Err(DrawError::BaseInstanceNotSupported);
+        }
+    }
+
+    // this contains the list of fences that will need to be fulfilled after the draw command
+    // has started
+    let mut fences = ctxt.scratch_buffers.take_fences();
+
     // handling vertices source
     let (vertices_count, instances_count, base_vertex) = {
         let index_buffer = match indices {
@@ -75,13 +122,17 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             IndicesSource::MultidrawArray { .. } => None,
             IndicesSource::MultidrawElement { indices, .. } => Some(indices),
             IndicesSource::NoIndices { .. } => None,
+            IndicesSource::MultiRange { .. } => None,
+            IndicesSource::TransformFeedback { .. } => None,
         };
 
         // determining whether we can use the `base_vertex` variants for drawing
         let use_base_vertex = match indices {
             IndicesSource::MultidrawArray { .. } => false,
             IndicesSource::MultidrawElement { .. } => false,
+            IndicesSource::TransformFeedback { .. } => false,
             IndicesSource::NoIndices { .. } => true,
+            IndicesSource::MultiRange { .. } => true,
             _ => ctxt.version >= &Version(Api::Gl, 3, 2) ||
                  ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                  ctxt.extensions.gl_arb_draw_elements_base_vertex ||
@@ -89,14 +140,14 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         };
 
         // object that is used to build the bindings
-        let mut binder = VertexAttributesSystem::start(&mut ctxt, program, index_buffer,
+        let mut binder = VertexAttributesSystem::start(&mut ctxt, program.vertex_program(), index_buffer,
                                                        use_base_vertex);
         // number of vertices in the vertices sources, or `None` if there is a mismatch
         let mut vertices_count: Option<usize> = None;
         // number of instances to draw
         let mut instances_count: Option<usize> = None;
 
-        for src in vertex_buffers.iter() {
+        for src in vertex_buffers {
             // Allow single match for consistency with the match below.
             // Integrating the two matches wouldn't improve the code either.
             #[allow(clippy::single_match)]
@@ -156,7 +207,13 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             }
         }
 
-        (vertices_count, instances_count, binder.bind().unwrap_or(0))
+        // the base vertex of the index buffer slice is added to the one of the vertices source
+        let base_vertex = match indices {
+            IndicesSource::IndexBuffer { base_vertex, .. } => base_vertex,
+            _ => 0,
+        };
+
+        (vertices_count, instances_count, binder.bind().unwrap_or(0) + base_vertex)
     };
 
     // binding the FBO to draw upon
@@ -165,9 +222,19 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         unsafe { fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false) };
     };
 
+    // ending the current transform feedback session before changing the program, unless it is
+    // paused or used again by this draw command
+    let end_transform_feedback = match draw_parameters.transform_feedback {
+        Some(tf) => !tf.is_current(&ctxt),
+        None => !ctxt.state.transform_feedback_paused,
+    };
+    if end_transform_feedback {
+        TransformFeedbackSession::unbind(&mut ctxt);
+    }
+
     // binding the program and uniforms
     program.use_program(&mut ctxt);
-    uniforms.bind_uniforms(&mut ctxt, program, &mut fences)?;
+    uniforms.bind_uniforms(&mut ctxt, &program.programs(), &mut fences)?;
 
     // sync-ing draw_parameters
     unsafe {
@@ -178,8 +245,6 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         // TODO: changing the current transform feedback requires pausing/unbinding before changing the program
         if let Some(ref tf) = draw_parameters.transform_feedback {
             tf.bind(&mut ctxt, indices.get_primitives_type());
-        } else {
-            TransformFeedbackSession::unbind(&mut ctxt);
         }
     }
 
@@ -187,7 +252,7 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     // TODO: make this code more readable
     {
         match &indices {
-            IndicesSource::IndexBuffer { ref buffer, data_type, primitives } => {
+            IndicesSource::IndexBuffer { ref buffer, data_type, primitives, base_instance, .. } => {
                 let ptr: *const u8 = ptr::null_mut();
                 let ptr = unsafe { ptr.add(buffer.get_offset_bytes()) };
 
@@ -196,7 +261,34 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                 }
 
                 unsafe {
-                    if let Some(instances_count) = instances_count {
+                    if *base_instance != 0 {
+                        // the instances before `base_instance` are skipped
+                        let instances_count = instances_count
+                            .map_or(1, |count| count.saturating_sub(*base_instance as usize));
+
+                        if ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                           ctxt.extensions.gl_arb_base_instance
+                        {
+                            ctxt.gl.DrawElementsInstancedBaseVertexBaseInstance(
+                                primitives.to_glenum(),
+                                buffer.get_elements_count() as gl::types::GLsizei,
+                                data_type.to_glenum(), ptr as *const _,
+                                instances_count as gl::types::GLsizei, base_vertex,
+                                *base_instance);
+
+                        } else if ctxt.extensions.gl_ext_base_instance {
+                            ctxt.gl.DrawElementsInstancedBaseVertexBaseInstanceEXT(
+                                primitives.to_glenum(),
+                                buffer.get_elements_count() as gl::types::GLsizei,
+                                data_type.to_glenum(), ptr as *const _,
+                                instances_count as gl::types::GLsizei, base_vertex,
+                                *base_instance);
+
+                        } else {
+                            unreachable!();     // enforced earlier in this function
+                        }
+
+                    } else if let Some(instances_count) = instances_count {
                         if base_vertex != 0 {
                             if ctxt.version >= &Version(Api::Gl, 3, 2) ||
                                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
@@ -322,15 +414,85 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                     }
                 }
             },
+
+            IndicesSource::MultiRange { ranges, primitives } => {
+                let vertices_count = match vertices_count {
+                    Some(c) => c,
+                    None => return Err(DrawError::VerticesSourcesLengthMismatch)
+                };
+
+                if ranges.iter().any(|&(first, count)| first as usize + count as usize > vertices_count) {
+                    return Err(DrawError::VertexRangeOutOfBounds);
+                }
+
+                unsafe {
+                    if let Some(instances_count) = instances_count {
+                        // there is no instanced version of `glMultiDrawArrays`
+                        for &(first, count) in ranges.iter() {
+                            ctxt.gl.DrawArraysInstanced(primitives.to_glenum(),
+                                                        base_vertex + first as gl::types::GLint,
+                                                        count as gl::types::GLsizei,
+                                                        instances_count as gl::types::GLsizei);
+                        }
+
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 4) ||
+                              ctxt.extensions.gl_ext_multi_draw_arrays
+                    {
+                        let firsts = ranges.iter()
+                                           .map(|&(first, _)| base_vertex + first as gl::types::GLint)
+                                           .collect::<Vec<_>>();
+                        let counts = ranges.iter()
+                                           .map(|&(_, count)| count as gl::types::GLsizei)
+                                           .collect::<Vec<_>>();
+
+                        if ctxt.version >= &Version(Api::Gl, 1, 4) {
+                            ctxt.gl.MultiDrawArrays(primitives.to_glenum(), firsts.as_ptr(),
+                                                    counts.as_ptr(),
+                                                    ranges.len() as gl::types::GLsizei);
+                        } else {
+                            ctxt.gl.MultiDrawArraysEXT(primitives.to_glenum(), firsts.as_ptr(),
+                                                       counts.as_ptr(),
+                                                       ranges.len() as gl::types::GLsizei);
+                        }
+
+                    } else {
+                        for &(first, count) in ranges.iter() {
+                            ctxt.gl.DrawArrays(primitives.to_glenum(),
+                                               base_vertex + first as gl::types::GLint,
+                                               count as gl::types::GLsizei);
+                        }
+                    }
+                }
+            },
+
+            IndicesSource::TransformFeedback { object, primitives } => {
+                debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+
+                if instances_count.is_some() && ctxt.version < &Version(Api::Gl, 4, 2) {
+                    return Err(DrawError::DrawTransformFeedbackNotSupported);
+                }
+
+                unsafe {
+                    if let Some(instances_count) = instances_count {
+                        ctxt.gl.DrawTransformFeedbackInstanced(primitives.to_glenum(),
+                                                               object.get_id(),
+                                                               instances_count as
+                                                                    gl::types::GLsizei);
+                    } else {
+                        ctxt.gl.DrawTransformFeedback(primitives.to_glenum(), object.get_id());
+                    }
+                }
+            },
         };
     };
 
     ctxt.state.next_draw_call_id += 1;
 
     // fulfilling the fences
-    for fence in fences.into_iter() {
+    for fence in fences.drain(..) {
         fence.insert(&mut ctxt);
     }
+    ctxt.scratch_buffers.give_back_fences(fences);
 
     Ok(())
 }
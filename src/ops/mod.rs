@@ -1,7 +1,7 @@
 pub use self::blit::blit;
 pub use self::clear::clear;
 pub use self::draw::draw;
-pub use self::read::{read, ReadError, Source};
+pub use self::read::{read, read_with_options, pixels_to_read, ReadError, ReadOptions, Source};
 
 mod blit;
 mod clear;
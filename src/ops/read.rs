@@ -1,17 +1,19 @@
-use std::ptr;
 use std::fmt;
+use std::mem;
 use std::error::Error;
 
 use crate::pixel_buffer::PixelBuffer;
 use crate::texture::ClientFormat;
 use crate::texture::PixelValue;
+use crate::texture::{InternalFormat, InternalFormatType};
 use crate::image_format::{TextureFormatRequest, TextureFormat};
 
 use crate::fbo;
 use crate::fbo::FramebuffersContainer;
 
-use crate::buffer::BufferAny;
+use crate::buffer::{BufferAny, BufferSlice};
 use crate::BufferExt;
+use crate::BufferSliceExt;
 use crate::Rect;
 use crate::context::CommandContext;
 use crate::gl;
@@ -23,8 +25,11 @@ use crate::version::Api;
 pub enum Source<'a> {
     /// A regular framebuffer attachment.
     Attachment(&'a fbo::RegularAttachment<'a>),
+    /// A buffer of the default framebuffer, with the dimensions of the framebuffer.
     // TODO: use a Rust enum
-    DefaultFramebuffer(gl::types::GLenum),
+    DefaultFramebuffer(gl::types::GLenum, (u32, u32)),
+    /// The first color attachment of a framebuffer object.
+    Framebuffer(&'a fbo::ValidatedAttachments<'a>),
 }
 
 impl<'a> From<&'a fbo::RegularAttachment<'a>> for Source<'a> {
@@ -38,6 +43,7 @@ impl<'a> From<&'a fbo::RegularAttachment<'a>> for Source<'a> {
 pub enum Destination<'a, P> where P: PixelValue {
     Memory(&'a mut Vec<P>),
     PixelBuffer(&'a PixelBuffer<P>),
+    Buffer(BufferSlice<'a, [P]>),
     // TODO: texture with glCopyTexSubImage2D
 }
 
@@ -55,13 +61,21 @@ impl<'a, P> From<&'a PixelBuffer<P>> for Destination<'a, P> where P: PixelValue
     }
 }
 
+impl<'a, P> From<BufferSlice<'a, [P]>> for Destination<'a, P> where P: PixelValue {
+    #[inline]
+    fn from(buffer: BufferSlice<'a, [P]>) -> Destination<'a, P> {
+        Destination::Buffer(buffer)
+    }
+}
+
 /// Error that can happen while reading.
 #[derive(Debug)]
 pub enum ReadError {
     /// The implementation doesn't support converting to the requested output format.
     ///
-    /// OpenGL supports every possible format, but OpenGL ES only supports `(u8, u8, u8, u8)` and
-    /// an implementation-defined format.
+    /// OpenGL supports every possible format, but OpenGL ES only supports `(u8, u8, u8, u8)`
+    /// (or `(i32, i32, i32, i32)` and `(u32, u32, u32, u32)` for integral attachments) and an
+    /// implementation-defined format.
     OutputFormatNotSupported,
 
     /// The implementation doesn't support reading a depth, depth-stencil or stencil attachment.
@@ -73,6 +87,19 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The rectangle to read is not entirely inside of the source.
+    RectOutOfBounds,
+
+    /// The number of pixels to read, or the size in bytes of the output, can't be represented
+    /// on this platform.
+    DimensionsOverflow,
+
+    /// The source doesn't have any color attachment.
+    NoColorAttachment,
+
+    /// The source doesn't support this kind of read.
+    ReadNotSupported,
+
     // TODO: context lost
 }
 
@@ -86,6 +113,14 @@ impl fmt::Display for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            RectOutOfBounds =>
+                "The rectangle to read is not entirely inside of the source",
+            DimensionsOverflow =>
+                "The number of pixels to read, or the size in bytes of the output, is too large",
+            NoColorAttachment =>
+                "The source doesn't have any color attachment",
+            ReadNotSupported =>
+                "The source doesn't support this kind of read",
         };
         fmt.write_str(desc)
     }
@@ -93,32 +128,54 @@ impl fmt::Display for ReadError {
 
 impl Error for ReadError {}
 
+/// Options that change the layout of the pixels produced by a read.
+///
+/// The default options produce RGBA pixels, from the bottom row of the rectangle to its top.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// If true, the red and blue components are swapped, which produces BGR or BGRA pixels. This
+    /// is the native layout of many windowing systems and video encoders.
+    ///
+    /// Only pixels with three or four components can be swapped. OpenGL ES only supports
+    /// `(u8, u8, u8, u8)`, and requires the `GL_EXT_read_format_bgra` extension.
+    pub bgra: bool,
+
+    /// If true, the rows are produced from the top of the rectangle to its bottom, which is the
+    /// order expected by most image formats.
+    pub top_to_bottom: bool,
+}
+
 /// Reads pixels from the source into the destination.
 ///
-/// Panics if the destination is not large enough.
+/// Returns an error if `rect` is not inside the source. Panics if the destination is not large
+/// enough.
 ///
-/// The `(u8, u8, u8, u8)` format is guaranteed to be supported.
-// TODO: differentiate between GL_* and GL_*_INTEGER
+/// The `(u8, u8, u8, u8)` format is guaranteed to be supported for normalized attachments,
+/// `(i32, i32, i32, i32)` for signed integral attachments and `(u32, u32, u32, u32)` for
+/// unsigned integral attachments.
 #[inline]
-pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Rect, dest: D,
+pub fn read<'a, S, D, T>(ctxt: &mut CommandContext<'_>, source: S, rect: &Rect, dest: D,
                          clamp: bool) -> Result<(), ReadError>
                          where S: Into<Source<'a>>, D: Into<Destination<'a, T>>,
                                T: PixelValue
+{
+    read_with_options(ctxt, source, rect, dest, clamp, &Default::default())
+}
+
+/// Reads pixels from the source into the destination, with a specific layout.
+///
+/// See `read` and `ReadOptions`.
+pub fn read_with_options<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Rect,
+                                      dest: D, clamp: bool, options: &ReadOptions)
+                                      -> Result<(), ReadError>
+                                      where S: Into<Source<'a>>, D: Into<Destination<'a, T>>,
+                                            T: PixelValue
 {
     let source = source.into();
     let dest = dest.into();
     let output_pixel_format = <T as PixelValue>::get_format();
 
-    let pixels_to_read = rect.width * rect.height;
-
-    // checking that the output format is supported
-    // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
-    // implementation-defined format
-    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 {
-        // TODO: GLES is guaranteed to support GL_RGBA and an implementation-defined format
-        //       queried with GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle GL_RGBA.
-        return Err(ReadError::OutputFormatNotSupported);
-    }
+    let pixels_to_read = pixels_to_read::<T>(&source, rect)?;
 
     // handling clamping
     if ctxt.version >= &Version(Api::Gl, 3, 0) {
@@ -136,44 +193,92 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
         return Err(ReadError::ClampingNotSupported);
     }
 
-    // TODO: check dimensions?
-
     // binding framebuffer
     match source {
         Source::Attachment(attachment) => {
             unsafe { FramebuffersContainer::bind_framebuffer_for_reading(&mut ctxt, attachment) };
         },
-        Source::DefaultFramebuffer(read_buffer) => {
+        Source::DefaultFramebuffer(read_buffer, _) => {
             FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt, read_buffer);
         },
+        Source::Framebuffer(attachments) => {
+            // `pixels_to_read` has checked that there is a color attachment
+            let index = attachments.get_color_attachments()[0].index;
+            unsafe {
+                FramebuffersContainer::bind_attachments_for_reading(&mut ctxt, attachments, index)
+            };
+        },
     };
 
     // determining what kind of data we are reading
     enum ReadSourceType { Color, Depth, Stencil, DepthStencil }
-    let (integer, read_src_type) = match source {
+    enum ReadSourceValues { Float, Integral, Unsigned }
+    let (values, read_src_type) = match source {
         Source::Attachment(attachment) => {
             match attachment {
                 fbo::RegularAttachment::Texture(ref tex) => {
-                    let integer = match tex.get_texture().get_requested_format() {
-                        TextureFormatRequest::Specific(TextureFormat::UncompressedIntegral(_)) => true,
-                        TextureFormatRequest::Specific(TextureFormat::UncompressedUnsigned(_)) => true,
-                        TextureFormatRequest::AnyIntegral => true,
-                        TextureFormatRequest::AnyUnsigned => true,
-                        _ => false,
+                    let values = match tex.get_texture().get_requested_format() {
+                        TextureFormatRequest::Specific(TextureFormat::UncompressedIntegral(_)) => ReadSourceValues::Integral,
+                        TextureFormatRequest::Specific(TextureFormat::UncompressedUnsigned(_)) => ReadSourceValues::Unsigned,
+                        TextureFormatRequest::AnyIntegral => ReadSourceValues::Integral,
+                        TextureFormatRequest::AnyUnsigned => ReadSourceValues::Unsigned,
+                        _ => ReadSourceValues::Float,
                     };
 
-                    (integer, ReadSourceType::Color)       // FIXME: wrong
+                    (values, ReadSourceType::Color)       // FIXME: wrong
                 },
                 fbo::RegularAttachment::RenderBuffer(ref rb) => {
-                    (false, ReadSourceType::Color)       // FIXME: wrong
+                    (ReadSourceValues::Float, ReadSourceType::Color)       // FIXME: wrong
                 },
             }
         },
-        Source::DefaultFramebuffer(read_buffer) => {
-            (false, ReadSourceType::Color)       // FIXME: wrong
+        Source::DefaultFramebuffer(..) => {
+            (ReadSourceValues::Float, ReadSourceType::Color)       // FIXME: wrong
         },
+        Source::Framebuffer(attachments) => {
+            let values = match attachments.get_color_attachments()[0].format {
+                Some(InternalFormat::OneComponent { ty1, .. }) |
+                Some(InternalFormat::TwoComponents { ty1, .. }) |
+                Some(InternalFormat::ThreeComponents { ty1, .. }) |
+                Some(InternalFormat::FourComponents { ty1, .. }) => match ty1 {
+                    InternalFormatType::Int => ReadSourceValues::Integral,
+                    InternalFormatType::UnsignedInt => ReadSourceValues::Unsigned,
+                    _ => ReadSourceValues::Float,
+                },
+                None => ReadSourceValues::Float,
+            };
+
+            (values, ReadSourceType::Color)
+        },
+    };
+
+    let integer = match values {
+        ReadSourceValues::Float => false,
+        ReadSourceValues::Integral | ReadSourceValues::Unsigned => true,
     };
 
+    // checking that the output format is supported
+    // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 for normalized
+    // attachments, I32I32I32I32 for signed integral attachments and U32U32U32U32 for unsigned
+    // integral attachments, plus an additional implementation-defined format
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+        // TODO: GLES is guaranteed to support an implementation-defined format queried with
+        //       GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle the mandatory formats.
+        let required_format = match values {
+            ReadSourceValues::Float => ClientFormat::U8U8U8U8,
+            ReadSourceValues::Integral => ClientFormat::I32I32I32I32,
+            ReadSourceValues::Unsigned => ClientFormat::U32U32U32U32,
+        };
+
+        if output_pixel_format != required_format {
+            return Err(ReadError::OutputFormatNotSupported);
+        }
+
+        if options.bgra && (integer || !ctxt.extensions.gl_ext_read_format_bgra) {
+            return Err(ReadError::OutputFormatNotSupported);
+        }
+    }
+
     // OpenGL ES doesn't support reading from depth, stencil or depth-stencil attachments by default
     if ctxt.version >= &Version(Api::GlEs, 2, 0) {
         match read_src_type {
@@ -207,11 +312,23 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
         },
     };
 
+    let format = if options.bgra {
+        match format {
+            gl::RGB => gl::BGR,
+            gl::RGBA => gl::BGRA,
+            gl::RGB_INTEGER => gl::BGR_INTEGER,
+            gl::RGBA_INTEGER => gl::BGRA_INTEGER,
+            _ => return Err(ReadError::OutputFormatNotSupported),
+        }
+    } else {
+        format
+    };
+
     // reading
     unsafe {
         match dest {
             Destination::Memory(dest) => {
-                let mut buf = Vec::with_capacity(pixels_to_read as usize);
+                let mut buf = Vec::with_capacity(pixels_to_read);
 
                 BufferAny::unbind_pixel_pack(ctxt);
 
@@ -234,28 +351,119 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext<'_>, source: S, rect: &Re
                                    rect.width as gl::types::GLsizei,
                                    rect.height as gl::types::GLsizei, format, gltype,
                                    buf.as_mut_ptr() as *mut _);
-                buf.set_len(pixels_to_read as usize);
+                buf.set_len(pixels_to_read);
+
+                if options.top_to_bottom {
+                    flip_rows(&mut buf, rect.width as usize);
+                }
 
                 *dest = buf;
             },
 
             Destination::PixelBuffer(pixel_buffer) => {
-                assert!(pixel_buffer.len() >= pixels_to_read as usize);
+                assert!(pixel_buffer.len() >= pixels_to_read);
 
                 pixel_buffer.prepare_and_bind_for_pixel_pack(&mut ctxt);
-                ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
-                                   rect.width as gl::types::GLsizei,
-                                   rect.height as gl::types::GLsizei, format, gltype,
-                                   ptr::null_mut());
+                read_pixels_to_buffer::<T>(ctxt, rect, format, gltype, 0, options.top_to_bottom);
 
                 crate::pixel_buffer::store_infos(pixel_buffer, (rect.width, rect.height));
-            }
+            },
+
+            Destination::Buffer(buffer) => {
+                assert!(buffer.len() >= pixels_to_read);
+
+                buffer.prepare_and_bind_for_pixel_pack(&mut ctxt);
+                read_pixels_to_buffer::<T>(ctxt, rect, format, gltype, buffer.get_offset_bytes(),
+                                           options.top_to_bottom);
+
+                // handling synchronization for the buffer
+                if let Some(fence) = buffer.add_fence() {
+                    fence.insert(ctxt);
+                }
+            },
         }
     };
 
     Ok(())
 }
 
+/// Calls `glReadPixels` with the buffer bound to `GL_PIXEL_PACK_BUFFER` as destination.
+///
+/// When the rows must be stored from top to bottom, they are read one by one so that the buffer
+/// doesn't have to be flipped by the CPU afterwards.
+unsafe fn read_pixels_to_buffer<T>(ctxt: &mut CommandContext<'_>, rect: &Rect,
+                                   format: gl::types::GLenum, gltype: gl::types::GLenum,
+                                   offset: usize, top_to_bottom: bool)
+{
+    if !top_to_bottom || rect.height <= 1 {
+        ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                           rect.width as gl::types::GLsizei,
+                           rect.height as gl::types::GLsizei, format, gltype,
+                           offset as *mut _);
+        return;
+    }
+
+    let row_size = rect.width as usize * mem::size_of::<T>();
+    for row in 0 .. rect.height {
+        let row_offset = offset + (rect.height - 1 - row) as usize * row_size;
+        ctxt.gl.ReadPixels(rect.left as gl::types::GLint,
+                           (rect.bottom + row) as gl::types::GLint,
+                           rect.width as gl::types::GLsizei, 1, format, gltype,
+                           row_offset as *mut _);
+    }
+}
+
+/// Reverses the order of the rows of an image.
+fn flip_rows<T>(data: &mut [T], width: usize) {
+    if width == 0 {
+        return;
+    }
+
+    let height = data.len() / width;
+    for row in 0 .. height / 2 {
+        let (top, bottom) = data.split_at_mut((height - 1 - row) * width);
+        top[row * width .. (row + 1) * width].swap_with_slice(&mut bottom[.. width]);
+    }
+}
+
+/// Checks that `rect` is inside the source and returns the number of pixels to read.
+///
+/// The size in bytes of the pixels is guaranteed to fit in an `isize`, which makes it possible
+/// to allocate them.
+pub fn pixels_to_read<T>(source: &Source<'_>, rect: &Rect) -> Result<usize, ReadError>
+                     where T: PixelValue
+{
+    let (width, height) = match *source {
+        Source::Attachment(&fbo::RegularAttachment::Texture(ref tex)) => {
+            (tex.get_width(), tex.get_height().unwrap_or(1))
+        },
+        Source::Attachment(&fbo::RegularAttachment::RenderBuffer(rb)) => rb.get_dimensions(),
+        Source::DefaultFramebuffer(_, dimensions) => dimensions,
+        Source::Framebuffer(attachments) => {
+            if attachments.get_color_attachments().is_empty() {
+                return Err(ReadError::NoColorAttachment);
+            }
+
+            attachments.get_dimensions()
+        },
+    };
+
+    let right = rect.left.checked_add(rect.width).ok_or(ReadError::RectOutOfBounds)?;
+    let top = rect.bottom.checked_add(rect.height).ok_or(ReadError::RectOutOfBounds)?;
+    if right > width || top > height {
+        return Err(ReadError::RectOutOfBounds);
+    }
+
+    let pixels = (rect.width as usize).checked_mul(rect.height as usize)
+                                      .ok_or(ReadError::DimensionsOverflow)?;
+    let bytes = pixels.checked_mul(mem::size_of::<T>()).ok_or(ReadError::DimensionsOverflow)?;
+    if bytes > isize::MAX as usize {
+        return Err(ReadError::DimensionsOverflow);
+    }
+
+    Ok(pixels)
+}
+
 fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
                             -> (gl::types::GLenum, gl::types::GLenum)
 {
@@ -314,3 +522,4 @@ fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
 
     (format, ty)
 }
+
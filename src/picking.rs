@@ -0,0 +1,278 @@
+/*!
+Object picking by rendering object IDs into an offscreen unsigned integer texture.
+
+Each object is drawn with a program that writes its ID to an `out uint` fragment output. The
+ID under a pixel can then be read back, either synchronously with `id_at`, or asynchronously
+with `request_id_at` which copies the pixel into a pixel buffer and returns without waiting for
+the GPU.
+
+The ID `0` is reserved and means that no object covers the pixel.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+# fn draw_ids<S: glium::Surface>(_: &mut S) {}
+let picker = glium::picking::Picker::new(&display, 800, 600).unwrap();
+
+picker.clear();
+draw_ids(&mut picker.framebuffer(&display).unwrap());
+
+// synchronous query
+let id = picker.id_at(400, 300).unwrap();
+
+// asynchronous query, to be resolved in a later frame
+let query = picker.request_id_at(400, 300).unwrap();
+if query.is_ready() {
+    let id = query.get().unwrap();
+}
+# }
+```
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::buffer::ReadError as BufferReadError;
+use crate::context::Context;
+use crate::fbo;
+use crate::framebuffer::{DepthRenderBuffer, RenderBufferCreationError, SimpleFrameBuffer};
+use crate::framebuffer::ValidationError;
+use crate::ops::{self, ReadError};
+use crate::sync::SyncFence;
+use crate::texture::{DepthFormat, MipmapsOption, TextureCreationError, UncompressedUintFormat};
+use crate::texture::UnsignedTexture2d;
+use crate::texture::pixel_buffer::PixelBuffer;
+use crate::{ContextExt, Rect, Surface};
+
+/// Error that can happen when creating a `Picker`.
+#[derive(Debug)]
+pub enum PickerCreationError {
+    /// Error while creating the texture that holds the IDs.
+    TextureCreationError(TextureCreationError),
+    /// Error while creating the depth buffer.
+    RenderBufferCreationError(RenderBufferCreationError),
+}
+
+impl fmt::Display for PickerCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickerCreationError::TextureCreationError(err) => write!(fmt, "{}", err),
+            PickerCreationError::RenderBufferCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for PickerCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PickerCreationError::TextureCreationError(err) => Some(err),
+            PickerCreationError::RenderBufferCreationError(err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for PickerCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> PickerCreationError {
+        PickerCreationError::TextureCreationError(err)
+    }
+}
+
+impl From<RenderBufferCreationError> for PickerCreationError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> PickerCreationError {
+        PickerCreationError::RenderBufferCreationError(err)
+    }
+}
+
+/// Error that can happen when querying the ID under a pixel.
+#[derive(Debug)]
+pub enum PickError {
+    /// The requested pixel is outside of the picking texture.
+    OutOfBounds,
+    /// Error while reading the pixel from the texture.
+    ReadError(ReadError),
+    /// Error while reading the pixel buffer of an asynchronous query.
+    BufferReadError(BufferReadError),
+}
+
+impl fmt::Display for PickError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickError::OutOfBounds => write!(fmt, "The requested pixel is outside of the picking texture"),
+            PickError::ReadError(err) => write!(fmt, "{}", err),
+            PickError::BufferReadError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for PickError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PickError::OutOfBounds => None,
+            PickError::ReadError(err) => Some(err),
+            PickError::BufferReadError(err) => Some(err),
+        }
+    }
+}
+
+impl From<ReadError> for PickError {
+    #[inline]
+    fn from(err: ReadError) -> PickError {
+        PickError::ReadError(err)
+    }
+}
+
+impl From<BufferReadError> for PickError {
+    #[inline]
+    fn from(err: BufferReadError) -> PickError {
+        PickError::BufferReadError(err)
+    }
+}
+
+/// Offscreen render target that stores one object ID per pixel.
+pub struct Picker {
+    context: Rc<Context>,
+    ids: UnsignedTexture2d,
+    depth: DepthRenderBuffer,
+}
+
+impl Picker {
+    /// Builds a new `Picker` whose ID texture and depth buffer have the given dimensions.
+    ///
+    /// The dimensions are usually the same as the ones of the window.
+    pub fn new<F: ?Sized>(facade: &F, width: u32, height: u32)
+                          -> Result<Picker, PickerCreationError> where F: Facade
+    {
+        let ids = UnsignedTexture2d::empty_with_format(facade, UncompressedUintFormat::U32,
+                                                       MipmapsOption::NoMipmap, width, height)?;
+        let depth = DepthRenderBuffer::new(facade, DepthFormat::I24, width, height)?;
+
+        Ok(Picker {
+            context: facade.get_context().clone(),
+            ids,
+            depth,
+        })
+    }
+
+    /// Returns the width and height of the picking texture.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        (self.ids.get_width(), self.ids.get_height().unwrap())
+    }
+
+    /// Returns the texture that holds the IDs.
+    #[inline]
+    pub fn get_texture(&self) -> &UnsignedTexture2d {
+        &self.ids
+    }
+
+    /// Builds a framebuffer that draws into the picking texture, with depth testing.
+    ///
+    /// The programs used with this framebuffer must write the ID of the object into an
+    /// unsigned integer output.
+    #[inline]
+    pub fn framebuffer<'a, F: ?Sized>(&'a self, facade: &F)
+                                      -> Result<SimpleFrameBuffer<'a>, ValidationError>
+        where F: Facade
+    {
+        SimpleFrameBuffer::with_depth_buffer(facade, &self.ids, &self.depth)
+    }
+
+    /// Resets every pixel to the ID `0` and the depth buffer to `1.0`.
+    ///
+    /// Integral attachments can't be cleared with `Surface::clear_color`, which is why this
+    /// function should be used instead.
+    pub fn clear(&self) {
+        let image = self.ids.main_level().first_layer().into_image(None).unwrap();
+        image.raw_clear_buffer([0u32; 4]);
+
+        self.framebuffer(&self.context).unwrap().clear_depth(1.0);
+    }
+
+    /// Returns the ID of the object under the given pixel, or `None` if there is no object.
+    ///
+    /// The coordinates are in pixels, with `(0, 0)` at the bottom-left corner.
+    ///
+    /// This function waits for all the previous draw commands to finish. Use `request_id_at`
+    /// to avoid stalling the pipeline.
+    pub fn id_at(&self, x: u32, y: u32) -> Result<Option<u32>, PickError> {
+        let rect = self.pixel_rect(x, y)?;
+
+        let image = self.ids.main_level().first_layer().into_image(None).unwrap();
+        let attachment = fbo::RegularAttachment::Texture(image);
+
+        // `(u32, u32, u32, u32)` is the only format that OpenGL ES guarantees for unsigned
+        // integral attachments
+        let mut data: Vec<(u32, u32, u32, u32)> = Vec::new();
+        let mut ctxt = self.context.make_current();
+        ops::read(&mut ctxt, &attachment, &rect, &mut data, false)?;
+
+        Ok(to_id(data[0].0))
+    }
+
+    /// Starts copying the ID under the given pixel into a pixel buffer, and returns immediately.
+    ///
+    /// The coordinates are in pixels, with `(0, 0)` at the bottom-left corner.
+    pub fn request_id_at(&self, x: u32, y: u32) -> Result<PickQuery, PickError> {
+        let rect = self.pixel_rect(x, y)?;
+
+        let image = self.ids.main_level().first_layer().into_image(None).unwrap();
+        let attachment = fbo::RegularAttachment::Texture(image);
+
+        let buffer = PixelBuffer::new_empty(&self.context, 1);
+        {
+            let mut ctxt = self.context.make_current();
+            ops::read(&mut ctxt, &attachment, &rect, &buffer, false)?;
+        }
+
+        // if fences are not supported, reading the buffer will simply block
+        let fence = SyncFence::new(&self.context).ok();
+
+        Ok(PickQuery {
+            buffer,
+            fence,
+        })
+    }
+
+    fn pixel_rect(&self, x: u32, y: u32) -> Result<Rect, PickError> {
+        let (width, height) = self.get_dimensions();
+        if x >= width || y >= height {
+            return Err(PickError::OutOfBounds);
+        }
+
+        Ok(Rect { left: x, bottom: y, width: 1, height: 1 })
+    }
+}
+
+/// Pending asynchronous picking query, returned by `Picker::request_id_at`.
+pub struct PickQuery {
+    buffer: PixelBuffer<(u32, u32, u32, u32)>,
+    fence: Option<SyncFence>,
+}
+
+impl PickQuery {
+    /// Returns true if the result is available and `get` won't block.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.fence.as_ref().map_or(true, |fence| fence.is_signaled())
+    }
+
+    /// Returns the ID of the object under the pixel, or `None` if there is no object.
+    ///
+    /// Blocks until the result is available if `is_ready` returned false.
+    pub fn get(mut self) -> Result<Option<u32>, PickError> {
+        if let Some(fence) = self.fence.take() {
+            fence.wait();
+        }
+
+        let data = self.buffer.read()?;
+        Ok(to_id(data[0].0))
+    }
+}
+
+#[inline]
+fn to_id(value: u32) -> Option<u32> {
+    if value == 0 { None } else { Some(value) }
+}
@@ -0,0 +1,299 @@
+/*!
+Chains of post-processing passes.
+
+A `FilterChain` applies a list of fragment shaders to a texture, usually the scene rendered
+offscreen, one after the other. Each pass reads the output of the previous one and writes to
+a texture owned by the chain, except for the last pass which writes to the destination surface.
+The intermediate textures have the dimensions of the destination, and are created again when
+these dimensions change.
+
+Each pass is a fragment shader that receives:
+
+ - `v_tex_coords`, a `vec2` between 0.0 and 1.0, declared as `in` or `varying` depending on
+   the GLSL version.
+ - `source`, a `sampler2D` containing the output of the previous pass, or the input texture for
+   the first pass.
+ - `resolution`, a `vec2` containing the dimensions of the destination in pixels.
+
+The vertex shader is generated with the same `#version` as the fragment shader. Other uniforms
+can be given a value with `set_uniform`.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>, scene: glium::Texture2d)
+#     where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::postprocess::FilterChain;
+
+let mut chain = FilterChain::new(&display).unwrap();
+let grayscale = chain.push_pass("
+    #version 140
+
+    uniform sampler2D source;
+    uniform float strength;
+    in vec2 v_tex_coords;
+    out vec4 color;
+
+    void main() {
+        vec4 texel = texture(source, v_tex_coords);
+        float gray = dot(texel.rgb, vec3(0.299, 0.587, 0.114));
+        color = vec4(mix(texel.rgb, vec3(gray), strength), texel.a);
+    }
+").unwrap();
+chain.set_uniform(grayscale, "strength", 0.8f32);
+
+let mut frame = display.draw();
+chain.draw(&scene, &mut frame).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramCreationError};
+use crate::texture::{MipmapsOption, Texture2d, TextureCreationError, UncompressedFloatFormat};
+use crate::uniforms::{AsUniformValue, DynamicUniforms, MagnifySamplerFilter};
+use crate::uniforms::{MinifySamplerFilter, SamplerWrapFunction};
+use crate::vertex::{BufferCreationError, VertexBuffer};
+use crate::{DrawError, Surface};
+
+#[derive(Copy, Clone, Debug)]
+struct QuadVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(QuadVertex, position);
+
+/// Error that can happen when drawing a `FilterChain`.
+#[derive(Debug)]
+pub enum FilterChainError {
+    /// Error while creating the intermediate textures.
+    TextureCreationError(TextureCreationError),
+    /// Error while drawing a pass.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for FilterChainError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterChainError::TextureCreationError(err) => write!(fmt, "{}", err),
+            FilterChainError::DrawError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for FilterChainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FilterChainError::TextureCreationError(err) => Some(err),
+            FilterChainError::DrawError(err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for FilterChainError {
+    #[inline]
+    fn from(err: TextureCreationError) -> FilterChainError {
+        FilterChainError::TextureCreationError(err)
+    }
+}
+
+impl From<DrawError> for FilterChainError {
+    #[inline]
+    fn from(err: DrawError) -> FilterChainError {
+        FilterChainError::DrawError(err)
+    }
+}
+
+/// Identifier of a pass of a `FilterChain`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PassId(usize);
+
+/// A fragment shader and the values of its uniforms.
+struct Pass {
+    program: Program,
+    uniforms: Vec<(String, Box<dyn AsUniformValue>)>,
+}
+
+/// A list of post-processing passes, and the textures that hold their intermediate results.
+pub struct FilterChain {
+    context: Rc<Context>,
+    format: UncompressedFloatFormat,
+    quad: VertexBuffer<QuadVertex>,
+    passes: Vec<Pass>,
+    // the two textures that the passes write to in turn
+    targets: Vec<Texture2d>,
+}
+
+impl FilterChain {
+    /// Builds a new empty chain whose intermediate textures have the `U8U8U8U8` format.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> Result<FilterChain, BufferCreationError>
+        where F: Facade
+    {
+        FilterChain::with_format(facade, UncompressedFloatFormat::U8U8U8U8)
+    }
+
+    /// Builds a new empty chain whose intermediate textures have the given format.
+    ///
+    /// A floating-point format such as `F16F16F16F16` keeps values above 1.0 between the
+    /// passes, which is necessary for HDR effects such as bloom or tone mapping.
+    pub fn with_format<F: ?Sized>(facade: &F, format: UncompressedFloatFormat)
+                                  -> Result<FilterChain, BufferCreationError> where F: Facade
+    {
+        let quad = VertexBuffer::immutable(facade, &[
+            QuadVertex { position: [-1.0, -1.0] }, QuadVertex { position: [1.0, -1.0] },
+            QuadVertex { position: [-1.0, 1.0] }, QuadVertex { position: [1.0, 1.0] },
+        ])?;
+
+        Ok(FilterChain {
+            context: facade.get_context().clone(),
+            format,
+            quad,
+            passes: Vec::new(),
+            targets: Vec::new(),
+        })
+    }
+
+    /// Compiles a fragment shader and adds it at the end of the chain.
+    pub fn push_pass(&mut self, fragment_shader: &str) -> Result<PassId, ProgramCreationError> {
+        let vertex_shader = vertex_shader_for(fragment_shader);
+        let program = Program::from_source(&self.context, &vertex_shader, fragment_shader, None)?;
+
+        self.passes.push(Pass {
+            program,
+            uniforms: Vec::new(),
+        });
+
+        Ok(PassId(self.passes.len() - 1))
+    }
+
+    /// Sets the value of a uniform of a pass. The value is kept until it is set again.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the pass doesn't belong to this chain.
+    pub fn set_uniform<U>(&mut self, pass: PassId, name: &str, value: U)
+        where U: AsUniformValue + 'static
+    {
+        let uniforms = &mut self.passes[pass.0].uniforms;
+
+        match uniforms.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = Box::new(value),
+            None => uniforms.push((name.to_owned(), Box::new(value))),
+        }
+    }
+
+    /// Returns the number of passes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns true if the chain doesn't have any pass.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Applies the passes to `input` and writes the result to `surface`.
+    ///
+    /// If the chain is empty, `input` is copied to `surface`.
+    pub fn draw<S>(&mut self, input: &Texture2d, surface: &mut S) -> Result<(), FilterChainError>
+        where S: Surface
+    {
+        if self.passes.is_empty() {
+            input.as_surface().fill(surface, MagnifySamplerFilter::Linear);
+            return Ok(());
+        }
+
+        let (width, height) = surface.get_dimensions();
+        let needed = (self.passes.len() - 1).min(2);
+        if self.targets.first().is_some_and(|t| t.dimensions() != (width, height)) {
+            self.targets.clear();
+        }
+        while self.targets.len() < needed {
+            self.targets.push(Texture2d::empty_with_format(&self.context, self.format,
+                                                           MipmapsOption::NoMipmap,
+                                                           width, height)?);
+        }
+
+        let resolution = [width as f32, height as f32];
+        let last = self.passes.len() - 1;
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            let source = if index == 0 { input } else { &self.targets[(index - 1) % 2] };
+            let source = source.sampled()
+                               .minify_filter(MinifySamplerFilter::Linear)
+                               .magnify_filter(MagnifySamplerFilter::Linear)
+                               .wrap_function(SamplerWrapFunction::Clamp);
+
+            let mut uniforms = DynamicUniforms::new();
+            uniforms.add("source", &source);
+            uniforms.add("resolution", &resolution);
+            for (name, value) in pass.uniforms.iter() {
+                uniforms.add(name, &**value);
+            }
+
+            let vertices = &self.quad;
+            let indices = NoIndices(PrimitiveType::TriangleStrip);
+            let parameters = Default::default();
+
+            if index == last {
+                surface.draw(vertices, indices, &pass.program, &uniforms, &parameters)?;
+            } else {
+                self.targets[index % 2].as_surface().draw(vertices, indices, &pass.program,
+                                                           &uniforms, &parameters)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FilterChain {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "FilterChain {{ passes: {} }}", self.passes.len())
+    }
+}
+
+/// Generates a vertex shader with the same GLSL version as a fragment shader.
+fn vertex_shader_for(fragment_shader: &str) -> String {
+    let version = fragment_shader.lines()
+                                 .map(str::trim)
+                                 .find_map(|line| line.strip_prefix("#version"))
+                                 .map(str::trim);
+
+    // shaders without a `#version` directive are GLSL 1.10
+    let (number, es) = match version {
+        Some(version) => {
+            let mut words = version.split_whitespace();
+            let number = words.next().and_then(|n| n.parse().ok()).unwrap_or(110u32);
+            (number, words.next() == Some("es"))
+        },
+        None => (110, false),
+    };
+
+    let (input, output) = if (es && number >= 300) || (!es && number >= 130) {
+        ("in", "out")
+    } else {
+        ("attribute", "varying")
+    };
+
+    format!("
+        #version {}{}
+
+        {} vec2 position;
+        {} vec2 v_tex_coords;
+
+        void main() {{
+            gl_Position = vec4(position, 0.0, 1.0);
+            v_tex_coords = position * 0.5 + 0.5;
+        }}
+    ", number, if es { " es" } else { "" }, input, output)
+}
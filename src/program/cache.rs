@@ -0,0 +1,171 @@
+//! On-disk cache of program binaries.
+//!
+//! When a cache directory has been set on the context, programs built from source code or from
+//! SPIR-V are stored in this directory after being linked. The next time the same program is
+//! requested, the binary is loaded instead of compiling the shaders again.
+//!
+//! Each file is named after a hash of the driver strings and of everything that is passed to
+//! the compiler, and starts with the full list of these inputs. This list is compared when
+//! loading the file, so that a hash collision can't load the binary of another program. Changing
+//! the driver or one of the sources thus automatically results in a cache miss.
+
+use std::fs;
+use std::hash::Hasher;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use fnv::FnvHasher;
+
+use crate::context::Context;
+use crate::program::{Binary, ProgramCreationInput, SpirvEntryPoint, TransformFeedbackMode};
+
+/// Extension of the files written in the cache directory.
+const EXTENSION: &str = "glium-program";
+
+/// Entry of the cache corresponding to a program.
+pub struct CacheEntry {
+    path: PathBuf,
+
+    /// The driver strings and the compiler inputs, serialized. Stored at the start of the file
+    /// and compared on load.
+    key: Vec<u8>,
+}
+
+/// Computes the cache entry corresponding to the given input.
+///
+/// Returns `None` if the input is already a binary.
+pub fn get_cache_entry(directory: &Path, context: &Context, input: &ProgramCreationInput<'_>)
+                       -> Option<CacheEntry>
+{
+    let mut key = Vec::new();
+
+    write_str(&mut key, context.get_opengl_vendor_string());
+    write_str(&mut key, context.get_opengl_renderer_string());
+    write_str(&mut key, context.get_opengl_version_string());
+
+    match *input {
+        ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
+                                           tessellation_evaluation_shader, geometry_shader,
+                                           fragment_shader, ref transform_feedback_varyings, .. } =>
+        {
+            key.push(0);
+            write_str(&mut key, vertex_shader);
+            write_opt(&mut key, tessellation_control_shader, write_str);
+            write_opt(&mut key, tessellation_evaluation_shader, write_str);
+            write_opt(&mut key, geometry_shader, write_str);
+            write_str(&mut key, fragment_shader);
+            write_varyings(&mut key, transform_feedback_varyings);
+        },
+
+        ProgramCreationInput::SpirV(ref program) => {
+            key.push(1);
+            write_spirv(&mut key, &program.vertex_shader);
+            write_opt(&mut key, program.tessellation_control_shader.as_ref(), write_spirv);
+            write_opt(&mut key, program.tessellation_evaluation_shader.as_ref(), write_spirv);
+            write_opt(&mut key, program.geometry_shader.as_ref(), write_spirv);
+            write_spirv(&mut key, &program.fragment_shader);
+            write_varyings(&mut key, &program.transform_feedback_varyings);
+        },
+
+        ProgramCreationInput::Binary { .. } => return None,
+    }
+
+    let mut hasher = FnvHasher::default();
+    hasher.write(&key);
+
+    Some(CacheEntry {
+        path: directory.join(format!("{:016x}.{}", hasher.finish(), EXTENSION)),
+        key,
+    })
+}
+
+/// Loads a binary from the cache. Returns `None` if the file doesn't exist, is invalid, or
+/// belongs to another program whose inputs have the same hash.
+pub fn load(entry: &CacheEntry) -> Option<Binary> {
+    let data = fs::read(&entry.path).ok()?;
+
+    let key_len = read_u64(&data)? as usize;
+    let data = &data[8 ..];
+    if data.len() < key_len || data[.. key_len] != entry.key[..] {
+        return None;
+    }
+
+    let data = &data[key_len ..];
+    if data.len() <= 4 {
+        return None;
+    }
+
+    let mut format = [0; 4];
+    format.copy_from_slice(&data[.. 4]);
+
+    Some(Binary {
+        format: u32::from_le_bytes(format),
+        content: data[4 ..].to_vec(),
+    })
+}
+
+/// Stores a binary in the cache. Errors are ignored, as the cache is only an optimization.
+pub fn store(entry: &CacheEntry, binary: &Binary) {
+    if let Some(parent) = entry.path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    // writing to a temporary file first, so that a concurrent reader never sees a partial file
+    let tmp_path = entry.path.with_extension("tmp");
+    let result = fs::File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(&(entry.key.len() as u64).to_le_bytes())?;
+        file.write_all(&entry.key)?;
+        file.write_all(&binary.format.to_le_bytes())?;
+        file.write_all(&binary.content)?;
+        file.sync_all()
+    });
+
+    match result {
+        Ok(()) => { let _ = fs::rename(&tmp_path, &entry.path); },
+        Err(_) => { let _ = fs::remove_file(&tmp_path); },
+    }
+}
+
+/// Removes an invalid entry from the cache.
+pub fn remove(entry: &CacheEntry) {
+    let _ = fs::remove_file(&entry.path);
+}
+
+fn read_u64(data: &[u8]) -> Option<u64> {
+    let mut value = [0; 8];
+    value.copy_from_slice(data.get(.. 8)?);
+    Some(u64::from_le_bytes(value))
+}
+
+fn write_str(key: &mut Vec<u8>, value: &str) {
+    key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    key.extend_from_slice(value.as_bytes());
+}
+
+fn write_spirv(key: &mut Vec<u8>, entry_point: &SpirvEntryPoint<'_>) {
+    key.extend_from_slice(&(entry_point.binary.len() as u64).to_le_bytes());
+    key.extend_from_slice(entry_point.binary);
+    write_str(key, entry_point.entry_point);
+}
+
+fn write_opt<T, F>(key: &mut Vec<u8>, value: Option<T>, f: F)
+    where F: FnOnce(&mut Vec<u8>, T)
+{
+    match value {
+        Some(value) => { key.push(1); f(key, value); },
+        None => key.push(0),
+    }
+}
+
+fn write_varyings(key: &mut Vec<u8>, varyings: &Option<(Vec<String>, TransformFeedbackMode)>) {
+    write_opt(key, varyings.as_ref(), |key, &(ref names, mode)| {
+        key.extend_from_slice(&(names.len() as u64).to_le_bytes());
+        for name in names {
+            write_str(key, name);
+        }
+        key.push(match mode {
+            TransformFeedbackMode::Interleaved => 0,
+            TransformFeedbackMode::Separate => 1,
+        });
+    });
+}
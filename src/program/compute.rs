@@ -4,6 +4,7 @@ use crate::context::CommandContext;
 use crate::backend::Facade;
 
 use std::fmt;
+use std::error::Error;
 use std::collections::hash_map::{self, HashMap};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
@@ -15,6 +16,8 @@ use crate::GlObject;
 use crate::ProgramExt;
 use crate::Handle;
 use crate::RawUniformValue;
+use crate::RawUniformArray;
+use crate::DrawError;
 
 use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationError, Binary, GetBinaryError, SpirvEntryPoint};
 
@@ -28,6 +31,36 @@ use crate::buffer::BufferSlice;
 use crate::uniforms::Uniforms;
 
 /// A combination of compute shaders linked together.
+///
+/// Memory barriers are handled automatically. If a shader storage buffer, an atomic counter
+/// buffer or an image unit bound to the compute shader is written to, the next command that
+/// accesses the buffer or the texture, including the next compute shader execution, calls
+/// `glMemoryBarrier` with the appropriate bits first.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+/// let shader = glium::program::ComputeShader::from_source(&display, r#"
+///     #version 430
+///     layout(local_size_x = 64) in;
+///
+///     buffer Values {
+///         float values[];
+///     };
+///
+///     void main() {
+///         values[gl_GlobalInvocationID.x] *= 2.0;
+///     }
+/// "#).unwrap();
+///
+/// let buffer = glium::uniforms::UniformBuffer::<[f32]>::empty_unsized(&display, 4096 * 4).unwrap();
+/// shader.execute(uniform! { Values: &*buffer }, 4096 / 64, 1, 1).unwrap();
+/// # }
+/// # fn main() {}
+/// ```
 pub struct ComputeShader {
     raw: RawProgram,
 }
@@ -49,7 +82,7 @@ impl ComputeShader {
         let shader = build_shader(facade, gl::COMPUTE_SHADER, src)?;
 
         Ok(ComputeShader {
-            raw: RawProgram::from_shaders(facade, &[shader], false, false, false, None)?
+            raw: RawProgram::from_shaders(facade, &[shader], false, false, false, None, false)?
         })
     }
 
@@ -63,7 +96,7 @@ impl ComputeShader {
         let shader = build_spirv_shader(facade, gl::COMPUTE_SHADER, spirv)?;
 
         Ok(ComputeShader {
-            raw: RawProgram::from_shaders(facade, &[shader], false, false, false, None)?
+            raw: RawProgram::from_shaders(facade, &[shader], false, false, false, None, false)?
         })
     }
 
@@ -84,19 +117,26 @@ impl ComputeShader {
     /// `x * y * z` work groups will be started. The current work group can be retrieved with
     /// `gl_WorkGroupID`. Inside each work group, additional local work groups can be started
     /// depending on the attributes of the compute shader itself.
+    ///
+    /// Returns an error if one of `x`, `y` or `z` is above the maximum supported by the
+    /// backend, which is at least 65535.
     #[inline]
-    pub fn execute<U>(&self, uniforms: U, x: u32, y: u32, z: u32) where U: Uniforms {
-        unsafe { self.raw.dispatch_compute(uniforms, x, y, z) }.unwrap();       // FIXME: return error
+    pub fn execute<U>(&self, uniforms: U, x: u32, y: u32, z: u32) -> Result<(), ComputeError>
+                      where U: Uniforms
+    {
+        unsafe { self.raw.dispatch_compute(uniforms, x, y, z) }
     }
 
     /// Executes the compute shader.
     ///
-    /// This is similar to `execute`, except that the parameters are stored in a buffer.
+    /// This is similar to `execute`, except that the parameters are stored in a buffer. This
+    /// allows a previous compute shader to choose the number of work groups without reading
+    /// it back on the CPU.
     #[inline]
     pub fn execute_indirect<U>(&self, uniforms: U, buffer: BufferSlice<'_, ComputeCommand>)
-                               where U: Uniforms
+                               -> Result<(), ComputeError> where U: Uniforms
     {
-        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer) }.unwrap();       // FIXME: return error
+        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer) }
     }
 
     /// Returns the program's compiled binary.
@@ -194,6 +234,13 @@ impl ProgramExt for ComputeShader {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray<'_>)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -222,6 +269,11 @@ impl ProgramExt for ComputeShader {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_uniform_array(&self, name: &str) -> Option<&Uniform> {
+        self.raw.get_uniform_array(name)
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -245,6 +297,53 @@ impl ProgramExt for ComputeShader {
     }
 }
 
+/// Error that can happen when executing a compute shader.
+#[derive(Clone, Debug)]
+pub enum ComputeError {
+    /// Compute shaders are not supported by the backend.
+    ComputeShadersNotSupported,
+
+    /// The number of work groups is above the maximum supported by the backend.
+    WorkGroupCountTooLarge {
+        /// Number of work groups that were requested.
+        requested: (u32, u32, u32),
+        /// Maximum number of work groups in each dimension.
+        maximum: (u32, u32, u32),
+    },
+
+    /// Error while binding the uniforms of the compute shader.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::ComputeShadersNotSupported =>
+                write!(fmt, "Compute shaders are not supported by the backend"),
+            ComputeError::WorkGroupCountTooLarge { requested, maximum } =>
+                write!(fmt, "The number of work groups is above the maximum supported by the \
+                             backend: requested {:?}, maximum: {:?}", requested, maximum),
+            ComputeError::DrawError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for ComputeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ComputeError::DrawError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DrawError> for ComputeError {
+    #[inline]
+    fn from(err: DrawError) -> ComputeError {
+        ComputeError::DrawError(err)
+    }
+}
+
 /// Represents a compute shader command waiting to be dispatched.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -258,3 +357,7 @@ pub struct ComputeCommand {
 }
 
 implement_uniform_block!(ComputeCommand, num_groups_x, num_groups_y, num_groups_z);
+
+/// Name of `ComputeCommand` that matches the `DispatchIndirectCommand` structure of the OpenGL
+/// specification.
+pub type DispatchIndirectCommand = ComputeCommand;
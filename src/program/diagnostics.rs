@@ -0,0 +1,245 @@
+//! Parsing of the info logs returned by the shader compilers.
+//!
+//! There is no standard format for these logs. This module recognizes the formats used by the
+//! most common drivers, and falls back to reporting the raw line otherwise:
+//!
+//! - NVIDIA: `0(12) : error C0000: syntax error`
+//! - Mesa (including Intel and AMD on Linux): `0:12(5): error: syntax error`
+//! - AMD, Intel on Windows, Apple: `ERROR: 0:12: 'foo' : undeclared identifier`
+
+use crate::program::ShaderType;
+
+/// A single message emitted by the shader compiler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderDiagnostic {
+    /// Stage of the shader that produced this message.
+    pub stage: ShaderType,
+    /// Number of the source string, as set by `#line` directives. `0` unless the shader
+    /// contains `#line` directives or was expanded with `SourceCode::with_includes`. `None` if
+    /// the driver didn't report it.
+    pub source_string: Option<u32>,
+    /// Line in the source code, starting from 1. `None` if the driver didn't report it.
+    pub line: Option<u32>,
+    /// Column in the source code. `None` if the driver didn't report it.
+    pub column: Option<u32>,
+    /// Severity of the message.
+    pub severity: DiagnosticSeverity,
+    /// The message itself, without the location and severity prefix.
+    pub message: String,
+}
+
+/// Severity of a `ShaderDiagnostic`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// The shader failed to compile because of this message.
+    Error,
+    /// The message doesn't prevent compilation.
+    Warning,
+    /// Informative message.
+    Info,
+}
+
+/// Parses an info log into a list of diagnostics.
+///
+/// Lines that can't be parsed are appended to the previous diagnostic, or reported as an error
+/// without a location if there is none.
+pub fn parse_info_log(log: &str, stage: ShaderType) -> Vec<ShaderDiagnostic> {
+    let mut diagnostics: Vec<ShaderDiagnostic> = Vec::new();
+
+    for line in log.lines() {
+        let line = line.trim_end_matches('\0').trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let parsed = parse_nvidia(line)
+            .or_else(|| parse_mesa(line))
+            .or_else(|| parse_prefixed(line));
+
+        match parsed {
+            Some((source_string, line, column, severity, message)) => {
+                diagnostics.push(ShaderDiagnostic {
+                    stage,
+                    source_string,
+                    line,
+                    column,
+                    severity,
+                    message: message.to_owned(),
+                });
+            },
+            None => {
+                if let Some(last) = diagnostics.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                } else {
+                    diagnostics.push(ShaderDiagnostic {
+                        stage,
+                        source_string: None,
+                        line: None,
+                        column: None,
+                        severity: DiagnosticSeverity::Error,
+                        message: line.to_owned(),
+                    });
+                }
+            },
+        }
+    }
+
+    diagnostics
+}
+
+type ParsedLine<'a> = (Option<u32>, Option<u32>, Option<u32>, DiagnosticSeverity, &'a str);
+
+/// Parses `0(12) : error C0000: message`.
+fn parse_nvidia(line: &str) -> Option<ParsedLine<'_>> {
+    let (source_string, rest) = split_number(line)?;
+    let rest = rest.strip_prefix('(')?;
+    let (line_number, rest) = split_number(rest)?;
+    let rest = rest.strip_prefix(')')?.trim_start().strip_prefix(':')?.trim_start();
+    let (severity, rest) = split_severity(rest)?;
+    let rest = rest.trim_start();
+
+    // skipping the error code
+    let message = match rest.find(':') {
+        Some(pos) if !rest[.. pos].contains(' ') => &rest[pos + 1 ..],
+        _ => rest,
+    };
+
+    Some((Some(source_string), Some(line_number), None, severity, message.trim()))
+}
+
+/// Parses `0:12(5): error: message`.
+fn parse_mesa(line: &str) -> Option<ParsedLine<'_>> {
+    let (source_string, rest) = split_number(line)?;
+    let rest = rest.strip_prefix(':')?;
+    let (line_number, rest) = split_number(rest)?;
+    let (column, rest) = match rest.strip_prefix('(') {
+        Some(rest) => {
+            let (column, rest) = split_number(rest)?;
+            (Some(column), rest.strip_prefix(')')?)
+        },
+        None => (None, rest),
+    };
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let (severity, rest) = split_severity(rest)?;
+    let message = rest.trim_start().strip_prefix(':').unwrap_or(rest);
+
+    Some((Some(source_string), Some(line_number), column, severity, message.trim()))
+}
+
+/// Parses `ERROR: 0:12: message` or `ERROR: message`.
+fn parse_prefixed(line: &str) -> Option<ParsedLine<'_>> {
+    let (severity, rest) = split_severity(line)?;
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+
+    let location = split_number(rest).and_then(|(source_string, rest)| {
+        let rest = rest.strip_prefix(':')?;
+        let (line_number, rest) = split_number(rest)?;
+        Some((source_string, line_number, rest.strip_prefix(':')?))
+    });
+
+    match location {
+        Some((source_string, line_number, message)) => {
+            Some((Some(source_string), Some(line_number), None, severity, message.trim()))
+        },
+        None => Some((None, None, None, severity, rest)),
+    }
+}
+
+fn split_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+    Some((s[.. end].parse().ok()?, &s[end ..]))
+}
+
+fn split_severity(s: &str) -> Option<(DiagnosticSeverity, &str)> {
+    const PREFIXES: &[(&str, DiagnosticSeverity)] = &[
+        ("error", DiagnosticSeverity::Error),
+        ("warning", DiagnosticSeverity::Warning),
+        ("info", DiagnosticSeverity::Info),
+        ("note", DiagnosticSeverity::Info),
+    ];
+
+    for &(prefix, severity) in PREFIXES {
+        if s.len() >= prefix.len() && s.is_char_boundary(prefix.len())
+            && s[.. prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            return Some((severity, &s[prefix.len() ..]));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_info_log, DiagnosticSeverity};
+    use crate::program::ShaderType;
+
+    #[test]
+    fn nvidia() {
+        let log = "0(12) : error C0000: syntax error, unexpected '}'\n\
+                   0(3) : warning C7022: unrecognized profile specifier \"foo\"\n";
+        let diagnostics = parse_info_log(log, ShaderType::Fragment);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].stage, ShaderType::Fragment);
+        assert_eq!(diagnostics[0].line, Some(12));
+        assert_eq!(diagnostics[0].column, None);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].message, "syntax error, unexpected '}'");
+        assert_eq!(diagnostics[1].line, Some(3));
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+    }
+
+    #[test]
+    fn mesa() {
+        let log = "0:7(14): error: `foo' undeclared\n0:9(2): warning: unused variable\0";
+        let diagnostics = parse_info_log(log, ShaderType::Vertex);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, Some(7));
+        assert_eq!(diagnostics[0].column, Some(14));
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].message, "`foo' undeclared");
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[1].message, "unused variable");
+    }
+
+    #[test]
+    fn source_string() {
+        let log = "2:7(14): error: `foo' undeclared\n3(4) : error C0000: syntax error\n";
+        let diagnostics = parse_info_log(log, ShaderType::Vertex);
+
+        assert_eq!(diagnostics[0].source_string, Some(2));
+        assert_eq!(diagnostics[0].line, Some(7));
+        assert_eq!(diagnostics[1].source_string, Some(3));
+        assert_eq!(diagnostics[1].line, Some(4));
+    }
+
+    #[test]
+    fn amd_intel() {
+        let log = "ERROR: 0:5: 'foo' : undeclared identifier\n\
+                   ERROR: 1 compilation errors.  No code generated.\n";
+        let diagnostics = parse_info_log(log, ShaderType::Geometry);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, Some(5));
+        assert_eq!(diagnostics[0].message, "'foo' : undeclared identifier");
+        assert_eq!(diagnostics[1].line, None);
+        assert_eq!(diagnostics[1].message, "1 compilation errors.  No code generated.");
+    }
+
+    #[test]
+    fn unknown_format() {
+        let log = "something went wrong\nmore details";
+        let diagnostics = parse_info_log(log, ShaderType::Vertex);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, None);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].message, "something went wrong\nmore details");
+    }
+}
@@ -0,0 +1,349 @@
+//! Expansion of the `#include` directives of GLSL source code.
+//!
+//! The expansion is done on the CPU, so it works with every backend. Each included file gets
+//! its own source string number, and `#line` directives are inserted around its content so that
+//! the messages of the compiler point at the right file and line.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+use crate::program::{ProgramCreationInput, SourceCode};
+
+/// Error that can happen when expanding the `#include` directives of a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IncludeError {
+    /// A file isn't in the list of includes.
+    NotFound {
+        /// Name of the file.
+        name: String,
+    },
+
+    /// A file includes itself, directly or through other files.
+    Recursive {
+        /// Name of the file.
+        name: String,
+    },
+
+    /// An `#include` directive isn't followed by a name between quotes or angle brackets.
+    Malformed {
+        /// The line containing the directive.
+        line: String,
+    },
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            IncludeError::NotFound { ref name } =>
+                write!(fmt, "The included file `{}` was not found", name),
+            IncludeError::Recursive { ref name } =>
+                write!(fmt, "The file `{}` includes itself", name),
+            IncludeError::Malformed { ref line } =>
+                write!(fmt, "Malformed `#include` directive: `{}`", line),
+        }
+    }
+}
+
+impl Error for IncludeError {}
+
+/// Source code of a program whose `#include` directives have been expanded.
+///
+/// Returned by `SourceCode::with_includes`, and can be passed to `Program::new`.
+pub struct ExpandedSourceCode {
+    vertex_shader: String,
+    tessellation_control_shader: Option<String>,
+    tessellation_evaluation_shader: Option<String>,
+    geometry_shader: Option<String>,
+    fragment_shader: String,
+
+    /// Names of the included files. The file at index `i` has the source string number `i + 1`.
+    files: Vec<String>,
+}
+
+impl ExpandedSourceCode {
+    /// Returns the expanded source code.
+    #[inline]
+    pub fn source_code(&self) -> SourceCode<'_> {
+        SourceCode {
+            vertex_shader: &self.vertex_shader,
+            tessellation_control_shader: self.tessellation_control_shader.as_deref(),
+            tessellation_evaluation_shader: self.tessellation_evaluation_shader.as_deref(),
+            geometry_shader: self.geometry_shader.as_deref(),
+            fragment_shader: &self.fragment_shader,
+        }
+    }
+
+    /// Returns the name of the included file with the given source string number, as found in
+    /// `ShaderDiagnostic::source_string`.
+    ///
+    /// Returns `None` for `0`, which is the source code of the shader itself, or if the number
+    /// doesn't correspond to any file.
+    #[inline]
+    pub fn file_name(&self, source_string: u32) -> Option<&str> {
+        let index = (source_string as usize).checked_sub(1)?;
+        self.files.get(index).map(|name| &name[..])
+    }
+}
+
+impl<'a> From<&'a ExpandedSourceCode> for ProgramCreationInput<'a> {
+    #[inline]
+    fn from(code: &'a ExpandedSourceCode) -> ProgramCreationInput<'a> {
+        code.source_code().into()
+    }
+}
+
+/// Expands the `#include` directives of all the shaders of `code`.
+pub fn expand(code: &SourceCode<'_>, includes: &HashMap<&str, &str>)
+              -> Result<ExpandedSourceCode, IncludeError>
+{
+    let mut expander = Expander {
+        includes,
+        files: Vec::new(),
+    };
+
+    let vertex_shader = expander.expand_shader(code.vertex_shader)?;
+    let tessellation_control_shader = code.tessellation_control_shader
+        .map(|s| expander.expand_shader(s)).transpose()?;
+    let tessellation_evaluation_shader = code.tessellation_evaluation_shader
+        .map(|s| expander.expand_shader(s)).transpose()?;
+    let geometry_shader = code.geometry_shader.map(|s| expander.expand_shader(s)).transpose()?;
+    let fragment_shader = expander.expand_shader(code.fragment_shader)?;
+
+    Ok(ExpandedSourceCode {
+        vertex_shader,
+        tessellation_control_shader,
+        tessellation_evaluation_shader,
+        geometry_shader,
+        fragment_shader,
+        files: expander.files,
+    })
+}
+
+struct Expander<'a, 'b> {
+    includes: &'a HashMap<&'b str, &'b str>,
+    files: Vec<String>,
+}
+
+/// State of the expansion of a single shader.
+struct ShaderState<'b> {
+    /// Files that are being expanded, the innermost last.
+    stack: Vec<&'b str>,
+    /// Files containing `#pragma once` that have already been included.
+    once: Vec<&'b str>,
+    /// `1` if `#line N` applies to the line after the next one, as in GLSL before 3.30.
+    line_offset: usize,
+}
+
+impl<'a, 'b> Expander<'a, 'b> {
+    fn expand_shader(&mut self, source: &str) -> Result<String, IncludeError> {
+        let mut state = ShaderState {
+            stack: Vec::new(),
+            once: Vec::new(),
+            line_offset: if line_directive_is_next_line(source) { 0 } else { 1 },
+        };
+
+        let mut output = String::with_capacity(source.len());
+        self.expand_file(source, 0, &mut state, &mut output)?;
+        Ok(output)
+    }
+
+    fn expand_file(&mut self, source: &str, source_string: usize, state: &mut ShaderState<'b>,
+                   output: &mut String) -> Result<(), IncludeError>
+    {
+        for (index, line) in source.lines().enumerate() {
+            match parse_directive(line) {
+                Some(Directive::Include(name)) => {
+                    let name = name.ok_or_else(|| IncludeError::Malformed {
+                        line: line.trim().to_owned(),
+                    })?;
+
+                    let (&name, &content) = self.includes.get_key_value(name)
+                        .ok_or_else(|| IncludeError::NotFound { name: name.to_owned() })?;
+
+                    if state.stack.contains(&name) {
+                        return Err(IncludeError::Recursive { name: name.to_owned() });
+                    }
+
+                    if state.once.contains(&name) {
+                        output.push('\n');
+                        continue;
+                    }
+
+                    let file = self.file_index(name);
+                    writeln!(output, "#line {} {}", 1 - state.line_offset, file + 1).unwrap();
+
+                    state.stack.push(name);
+                    self.expand_file(content, file + 1, state, output)?;
+                    state.stack.pop();
+
+                    writeln!(output, "#line {} {}", index + 2 - state.line_offset,
+                             source_string).unwrap();
+                },
+
+                Some(Directive::PragmaOnce) if !state.stack.is_empty() => {
+                    let current = *state.stack.last().unwrap();
+                    if !state.once.contains(&current) {
+                        state.once.push(current);
+                    }
+                    output.push('\n');
+                },
+
+                _ => {
+                    output.push_str(line);
+                    output.push('\n');
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of a file in `files`, adding it if necessary.
+    fn file_index(&mut self, name: &str) -> usize {
+        match self.files.iter().position(|f| f == name) {
+            Some(index) => index,
+            None => {
+                self.files.push(name.to_owned());
+                self.files.len() - 1
+            },
+        }
+    }
+}
+
+enum Directive<'s> {
+    /// `#include "name"` or `#include <name>`. `None` if the name couldn't be parsed.
+    Include(Option<&'s str>),
+    /// `#pragma once`
+    PragmaOnce,
+}
+
+fn parse_directive(line: &str) -> Option<Directive<'_>> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start();
+
+    if let Some(rest) = rest.strip_prefix("include") {
+        let rest = rest.trim();
+        let name = if let Some(rest) = rest.strip_prefix('"') {
+            rest.strip_suffix('"')
+        } else if let Some(rest) = rest.strip_prefix('<') {
+            rest.strip_suffix('>')
+        } else {
+            None
+        };
+
+        return Some(Directive::Include(name.filter(|n| !n.is_empty())));
+    }
+
+    let rest = rest.strip_prefix("pragma")?;
+    if rest.starts_with(char::is_whitespace) && rest.trim() == "once" {
+        return Some(Directive::PragmaOnce);
+    }
+
+    None
+}
+
+/// Returns true if `#line N` sets the number of the line that follows the directive, which is
+/// the case starting with GLSL 3.30 and GLSL ES 3.00. Before that, it sets the number of the
+/// directive itself.
+fn line_directive_is_next_line(source: &str) -> bool {
+    let version = source.lines()
+        .filter_map(|line| line.trim_start().strip_prefix('#'))
+        .filter_map(|line| line.trim_start().strip_prefix("version"))
+        .next();
+
+    let mut words = match version {
+        Some(version) => version.split_whitespace(),
+        None => return false,
+    };
+
+    let number = words.next().and_then(|n| n.parse::<u32>().ok()).unwrap_or(110);
+    let es = words.next() == Some("es");
+
+    if es { number >= 300 } else { number >= 330 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::IncludeError;
+    use crate::program::SourceCode;
+
+    fn source(vertex_shader: &str) -> SourceCode<'_> {
+        SourceCode {
+            vertex_shader,
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            geometry_shader: None,
+            fragment_shader: "#version 330\nvoid main() {}\n",
+        }
+    }
+
+    #[test]
+    fn expansion() {
+        let mut includes = HashMap::new();
+        includes.insert("common.glsl", "#include <consts.glsl>\nfloat f() { return PI; }");
+        includes.insert("consts.glsl", "#define PI 3.14");
+
+        let code = source("#version 330\n#include \"common.glsl\"\nvoid main() {}\n")
+            .with_includes(&includes).unwrap();
+
+        assert_eq!(code.source_code().vertex_shader,
+                   "#version 330\n\
+                    #line 1 1\n\
+                    #line 1 2\n\
+                    #define PI 3.14\n\
+                    #line 2 1\n\
+                    float f() { return PI; }\n\
+                    #line 3 0\n\
+                    void main() {}\n");
+        assert_eq!(code.file_name(0), None);
+        assert_eq!(code.file_name(1), Some("common.glsl"));
+        assert_eq!(code.file_name(2), Some("consts.glsl"));
+        assert_eq!(code.file_name(3), None);
+    }
+
+    #[test]
+    fn old_line_directive() {
+        let mut includes = HashMap::new();
+        includes.insert("a", "int a;");
+
+        let code = source("#version 120\n#include \"a\"\n").with_includes(&includes).unwrap();
+        assert_eq!(code.source_code().vertex_shader,
+                   "#version 120\n#line 0 1\nint a;\n#line 2 0\n");
+    }
+
+    #[test]
+    fn pragma_once() {
+        let mut includes = HashMap::new();
+        includes.insert("a", "#pragma once\nint a;");
+
+        let code = source("#version 330\n#include \"a\"\n#include \"a\"\n")
+            .with_includes(&includes).unwrap();
+        assert_eq!(code.source_code().vertex_shader,
+                   "#version 330\n#line 1 1\n\nint a;\n#line 3 0\n\n");
+    }
+
+    #[test]
+    fn errors() {
+        let mut includes = HashMap::new();
+        includes.insert("a", "#include \"b\"");
+        includes.insert("b", "#include \"a\"");
+
+        match source("#include \"c\"").with_includes(&includes) {
+            Err(IncludeError::NotFound { name }) => assert_eq!(name, "c"),
+            _ => panic!()
+        };
+
+        match source("#include \"a\"").with_includes(&includes) {
+            Err(IncludeError::Recursive { name }) => assert_eq!(name, "a"),
+            _ => panic!()
+        };
+
+        match source("#include a").with_includes(&includes) {
+            Err(IncludeError::Malformed { .. }) => (),
+            _ => panic!()
+        };
+    }
+}
@@ -1,5 +1,6 @@
 //! Items related to creating an OpenGL program.
 
+use std::collections::HashMap;
 use std::fmt;
 use std::error::Error;
 use std::sync::Mutex;
@@ -9,13 +10,21 @@ use crate::gl;
 use crate::version::Api;
 use crate::version::Version;
 
-pub use self::compute::{ComputeShader, ComputeCommand};
+pub use self::compute::{ComputeShader, ComputeCommand, ComputeError, DispatchIndirectCommand};
 pub use self::program::Program;
-pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
+pub use self::reflection::{Uniform, UniformBlock, BlockLayout, BlockMemoryLayout, OutputPrimitives};
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
-pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
+pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform, FragmentOutput};
+pub use self::diagnostics::{ShaderDiagnostic, DiagnosticSeverity};
+pub use self::include::{ExpandedSourceCode, IncludeError};
+pub use self::pipeline::{SeparableProgram, ProgramPipeline, ProgramPipelineError};
+pub use self::pipeline::{DrawProgram, DrawProgramSource};
 
+mod cache;
 mod compute;
+mod diagnostics;
+mod include;
+mod pipeline;
 mod program;
 mod raw;
 mod reflection;
@@ -55,6 +64,13 @@ pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabilitie
     ctxt.get_version() >= &Version(Api::Gl, 4, 0) || ctxt.get_extensions().gl_arb_shader_subroutine
 }
 
+/// Returns true if the backend supports separable programs and program pipelines.
+#[inline]
+pub fn is_program_pipeline_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 4, 1) || ctxt.get_version() >= &Version(Api::GlEs, 3, 1)
+        || ctxt.get_extensions().gl_arb_separate_shader_objects
+}
+
 // Some shader compilers have race-condition issues, so we lock this mutex
 // in the GL thread every time we compile a shader or link a program.
 static COMPILER_GLOBAL_LOCK: Mutex<()> = Mutex::new(());
@@ -109,7 +125,10 @@ impl ShaderType {
 #[derive(Clone, Debug)]
 pub enum ProgramCreationError {
     /// Error while compiling one of the shaders.
-    CompilationError(String, ShaderType),
+    ///
+    /// Contains the raw info log returned by the driver, the stage that failed, and the
+    /// messages of the log parsed on a best-effort basis.
+    CompilationError(String, ShaderType, Vec<ShaderDiagnostic>),
 
     /// Error while linking the program.
     LinkingError(String),
@@ -132,13 +151,29 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// The geometry shader declares more output vertices with `max_vertices` than what the
+    /// backend supports.
+    ///
+    /// This is only returned if the driver compiles and links such a shader. Most drivers
+    /// return a `CompilationError` or `LinkingError` instead.
+    GeometryShaderTooManyOutputVertices {
+        /// Value of `max_vertices` in the geometry shader.
+        requested: u32,
+        /// Maximum value supported by the backend.
+        max: u32,
+    },
+
+    /// You have requested a separable program, but program pipelines are not supported by
+    /// the backend.
+    SeparableProgramsNotSupported,
 }
 
 impl fmt::Display for ProgramCreationError {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         use self::ProgramCreationError::*;
         let desc = match *self {
-            CompilationError(_,typ) => {
+            CompilationError(_, typ, _) => {
                 match typ {
                     ShaderType::Vertex => "Compilation error in vertex shader",
                     ShaderType::Geometry => "Compilation error in geometry shader",
@@ -160,12 +195,18 @@ impl fmt::Display for ProgramCreationError {
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            GeometryShaderTooManyOutputVertices { .. } =>
+                "The geometry shader outputs more vertices than what the backend supports",
+            SeparableProgramsNotSupported =>
+                "Separable programs are not supported by the backend",
         };
         match *self {
-            CompilationError(ref s, _) =>
+            CompilationError(ref s, _, _) =>
                 write!(fmt, "{}: {}", desc, s),
             LinkingError(ref s) =>
                 write!(fmt, "{}: {}", desc, s),
+            GeometryShaderTooManyOutputVertices { requested, max } =>
+                write!(fmt, "{}: `max_vertices` is {} but the maximum is {}", desc, requested, max),
             _ =>
                 write!(fmt, "{}", desc),
         }
@@ -400,6 +441,46 @@ pub struct SourceCode<'a> {
     pub fragment_shader: &'a str,
 }
 
+impl<'a> SourceCode<'a> {
+    /// Expands the `#include "name"` and `#include <name>` directives of all the shaders, using
+    /// `includes` to find the content of each file by its name.
+    ///
+    /// Included files can include other files, and a file containing `#pragma once` is only
+    /// included once per shader. Each included file is given its own source string number with
+    /// `#line` directives, so the `source_string` and `line` of the diagnostics of the compiler
+    /// point inside of the included file. Use `ExpandedSourceCode::file_name` to get its name.
+    ///
+    /// The expansion is done by glium and doesn't need `GL_ARB_shading_language_include`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+    /// # fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+    /// use std::collections::HashMap;
+    /// use glium::program::SourceCode;
+    ///
+    /// let mut includes = HashMap::new();
+    /// includes.insert("lighting.glsl", "vec3 light(vec3 normal) { return normal; }");
+    ///
+    /// let code = SourceCode {
+    ///     vertex_shader: "#version 330\nvoid main() {}",
+    ///     tessellation_control_shader: None,
+    ///     tessellation_evaluation_shader: None,
+    ///     geometry_shader: None,
+    ///     fragment_shader: "#version 330\n#include \"lighting.glsl\"\nvoid main() {}",
+    /// }.with_includes(&includes).unwrap();
+    ///
+    /// let program = glium::Program::new(&display, &code).unwrap();
+    /// # }
+    /// ```
+    pub fn with_includes(&self, includes: &HashMap<&str, &str>)
+                         -> Result<ExpandedSourceCode, IncludeError>
+    {
+        include::expand(self, includes)
+    }
+}
+
 impl<'a> From<SourceCode<'a>> for ProgramCreationInput<'a> {
     #[inline]
     fn from(code: SourceCode<'a>) -> ProgramCreationInput<'a> {
@@ -0,0 +1,344 @@
+use crate::gl;
+
+use std::fmt;
+use std::error::Error;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use smallvec::SmallVec;
+
+use crate::backend::Facade;
+use crate::context::{Context, NonSharedObject};
+use crate::context::CommandContext;
+use crate::ContextExt;
+use crate::GlObject;
+use crate::Handle;
+use crate::ProgramExt;
+
+use crate::program::{Program, ProgramCreationError, ShaderType};
+use crate::program::is_program_pipeline_supported;
+use crate::program::program::sync_program_state;
+
+/// A program that contains a single shader stage and that can be bound to a `ProgramPipeline`.
+///
+/// Separable programs are linked on their own, which means that a vertex stage can be combined
+/// with any fragment stage whose inputs match its outputs without linking a new program.
+///
+/// Dereferences to a `Program`, which gives access to the reflection of the stage.
+pub struct SeparableProgram {
+    program: Program,
+    stage: ShaderType,
+}
+
+impl SeparableProgram {
+    /// Builds a new separable program from the GLSL source code of a single stage.
+    ///
+    /// Only vertex and fragment stages are supported.
+    pub fn new<F: ?Sized>(facade: &F, stage: ShaderType, source: &str)
+                          -> Result<SeparableProgram, ProgramCreationError> where F: Facade
+    {
+        if !is_program_pipeline_supported(facade.get_context()) {
+            return Err(ProgramCreationError::SeparableProgramsNotSupported);
+        }
+
+        match stage {
+            ShaderType::Vertex | ShaderType::Fragment => (),
+            _ => return Err(ProgramCreationError::ShaderTypeNotSupported),
+        }
+
+        Ok(SeparableProgram {
+            program: Program::new_separable(facade, stage, source)?,
+            stage,
+        })
+    }
+
+    /// Returns the stage that this program contains.
+    #[inline]
+    pub fn get_stage(&self) -> ShaderType {
+        self.stage
+    }
+}
+
+impl Deref for SeparableProgram {
+    type Target = Program;
+
+    #[inline]
+    fn deref(&self) -> &Program {
+        &self.program
+    }
+}
+
+impl fmt::Debug for SeparableProgram {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(formatter, "SeparableProgram({:?}, {:?})", self.stage, self.program)
+    }
+}
+
+/// Error that can happen when setting the stages of a `ProgramPipeline`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProgramPipelineError {
+    /// A separable program has been passed for a stage it doesn't contain.
+    WrongStage {
+        /// The stage that the pipeline expected.
+        expected: ShaderType,
+        /// The stage of the program that was passed.
+        obtained: ShaderType,
+    },
+}
+
+impl fmt::Display for ProgramPipelineError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ProgramPipelineError::WrongStage { expected, obtained } =>
+                write!(fmt, "Expected a separable program with a {:?} stage, got a {:?} stage",
+                       expected, obtained),
+        }
+    }
+}
+
+impl Error for ProgramPipelineError {}
+
+/// A combination of separable programs, one for each stage, that can be used to draw.
+///
+/// Changing the stages of a pipeline doesn't require any linking, which makes it cheap to
+/// mix and match vertex and fragment stages at draw time.
+///
+/// Uniforms passed to a draw command are set on each stage that declares them. Subroutine
+/// uniforms are not supported.
+///
+/// Pipelines are drawn with `Surface::draw_with_program`.
+pub struct ProgramPipeline<'a> {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    // value returned by `register_non_shared_object`
+    generation: usize,
+    vertex: &'a SeparableProgram,
+    fragment: &'a SeparableProgram,
+}
+
+impl<'a> ProgramPipeline<'a> {
+    /// Builds a new pipeline from a vertex stage and a fragment stage.
+    pub fn new<F: ?Sized>(facade: &F, vertex: &'a SeparableProgram, fragment: &'a SeparableProgram)
+                          -> Result<ProgramPipeline<'a>, ProgramPipelineError> where F: Facade
+    {
+        check_stage(vertex, ShaderType::Vertex)?;
+        check_stage(fragment, ShaderType::Fragment)?;
+
+        let mut ctxt = facade.get_context().make_current();
+
+        let id = unsafe {
+            let mut id = 0;
+            ctxt.gl.GenProgramPipelines(1, &mut id);
+            id
+        };
+
+        let generation = facade.get_context()
+                               .register_non_shared_object(NonSharedObject::ProgramPipeline(id));
+
+        let pipeline = ProgramPipeline {
+            context: facade.get_context().clone(),
+            id,
+            generation,
+            vertex,
+            fragment,
+        };
+
+        pipeline.use_stage(&mut ctxt, gl::VERTEX_SHADER_BIT, vertex);
+        pipeline.use_stage(&mut ctxt, gl::FRAGMENT_SHADER_BIT, fragment);
+
+        Ok(pipeline)
+    }
+
+    /// Returns the program that is used for the vertex stage.
+    #[inline]
+    pub fn get_vertex_stage(&self) -> &'a SeparableProgram {
+        self.vertex
+    }
+
+    /// Returns the program that is used for the fragment stage.
+    #[inline]
+    pub fn get_fragment_stage(&self) -> &'a SeparableProgram {
+        self.fragment
+    }
+
+    /// Replaces the program that is used for the vertex stage.
+    pub fn set_vertex_stage(&mut self, vertex: &'a SeparableProgram)
+                            -> Result<(), ProgramPipelineError>
+    {
+        check_stage(vertex, ShaderType::Vertex)?;
+
+        let mut ctxt = self.context.make_current();
+        self.use_stage(&mut ctxt, gl::VERTEX_SHADER_BIT, vertex);
+        self.vertex = vertex;
+        Ok(())
+    }
+
+    /// Replaces the program that is used for the fragment stage.
+    pub fn set_fragment_stage(&mut self, fragment: &'a SeparableProgram)
+                              -> Result<(), ProgramPipelineError>
+    {
+        check_stage(fragment, ShaderType::Fragment)?;
+
+        let mut ctxt = self.context.make_current();
+        self.use_stage(&mut ctxt, gl::FRAGMENT_SHADER_BIT, fragment);
+        self.fragment = fragment;
+        Ok(())
+    }
+
+    /// Calls `glUseProgramStages` for one of the stages.
+    fn use_stage(&self, ctxt: &mut CommandContext<'_>, stage: gl::types::GLbitfield,
+                 program: &SeparableProgram)
+    {
+        let program = match program.get_id() {
+            Handle::Id(id) => id,
+            Handle::Handle(_) => unreachable!(),
+        };
+
+        unsafe {
+            ctxt.gl.UseProgramStages(self.id, stage, program);
+        }
+    }
+
+    /// Unbinds the current program and calls `glBindProgramPipeline`. Also enables/disables
+    /// `GL_PROGRAM_POINT_SIZE` and `GL_FRAMEBUFFER_SRGB`.
+    fn use_pipeline(&self, ctxt: &mut CommandContext<'_>) {
+        sync_program_state(ctxt, self.vertex.uses_point_size(), self.fragment.has_srgb_output());
+
+        unsafe {
+            // a program bound with `glUseProgram` takes precedence over the pipeline
+            if ctxt.state.program != Handle::Id(0) {
+                ctxt.gl.UseProgram(0);
+                ctxt.state.program = Handle::Id(0);
+            }
+
+            if ctxt.state.program_pipeline != self.id {
+                ctxt.gl.BindProgramPipeline(self.id);
+                ctxt.state.program_pipeline = self.id;
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Debug for ProgramPipeline<'a> {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(formatter, "ProgramPipeline #{} (vertex: {:?}, fragment: {:?})", self.id,
+               self.vertex.get_id(), self.fragment.get_id())
+    }
+}
+
+impl<'a> GlObject for ProgramPipeline<'a> {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl<'a> Drop for ProgramPipeline<'a> {
+    fn drop(&mut self) {
+        if !self.context.unregister_non_shared_object(NonSharedObject::ProgramPipeline(self.id),
+                                                      self.generation)
+        {
+            // the pipeline belonged to a context that has been replaced with `rebuild`
+            return;
+        }
+
+        let mut ctxt = self.context.make_current();
+
+        unsafe {
+            if ctxt.state.program_pipeline == self.id {
+                ctxt.state.program_pipeline = 0;
+            }
+
+            ctxt.gl.DeleteProgramPipelines(1, &self.id);
+        }
+    }
+}
+
+fn check_stage(program: &SeparableProgram, expected: ShaderType)
+               -> Result<(), ProgramPipelineError>
+{
+    if program.get_stage() != expected {
+        return Err(ProgramPipelineError::WrongStage {
+            expected,
+            obtained: program.get_stage(),
+        });
+    }
+
+    Ok(())
+}
+
+/// The programs that are bound during a draw command.
+#[derive(Copy, Clone, Debug)]
+pub enum DrawProgramSource<'a> {
+    /// A regular program that contains all the stages.
+    Program(&'a Program),
+
+    /// A pipeline of separable programs.
+    Pipeline(&'a ProgramPipeline<'a>),
+}
+
+impl<'a> DrawProgramSource<'a> {
+    /// Returns the program whose attributes are read from the vertex buffers.
+    #[inline]
+    pub(crate) fn vertex_program(&self) -> &'a Program {
+        match *self {
+            DrawProgramSource::Program(program) => program,
+            DrawProgramSource::Pipeline(pipeline) => pipeline.vertex,
+        }
+    }
+
+    /// Returns the program whose outputs are written to the framebuffer.
+    #[inline]
+    pub(crate) fn fragment_program(&self) -> &'a Program {
+        match *self {
+            DrawProgramSource::Program(program) => program,
+            DrawProgramSource::Pipeline(pipeline) => pipeline.fragment,
+        }
+    }
+
+    /// Returns the list of programs that uniforms must be set on.
+    #[inline]
+    pub(crate) fn programs(&self) -> SmallVec<[&'a Program; 2]> {
+        match *self {
+            DrawProgramSource::Program(program) => SmallVec::from_slice(&[program]),
+            DrawProgramSource::Pipeline(pipeline) => {
+                SmallVec::from_slice(&[&**pipeline.vertex, &**pipeline.fragment])
+            },
+        }
+    }
+
+    /// Binds the program or the pipeline.
+    #[inline]
+    pub(crate) fn use_program(&self, ctxt: &mut CommandContext<'_>) {
+        match *self {
+            DrawProgramSource::Program(program) => program.use_program(ctxt),
+            DrawProgramSource::Pipeline(pipeline) => pipeline.use_pipeline(ctxt),
+        }
+    }
+}
+
+/// Objects that can be used as the program of a draw command.
+///
+/// Implemented on `Program` and `ProgramPipeline`.
+pub trait DrawProgram {
+    /// Returns the programs to bind for the draw command.
+    fn as_draw_program(&self) -> DrawProgramSource<'_>;
+}
+
+impl DrawProgram for Program {
+    #[inline]
+    fn as_draw_program(&self) -> DrawProgramSource<'_> {
+        DrawProgramSource::Program(self)
+    }
+}
+
+impl<'a> DrawProgram for ProgramPipeline<'a> {
+    #[inline]
+    fn as_draw_program(&self) -> DrawProgramSource<'_> {
+        DrawProgramSource::Pipeline(self)
+    }
+}
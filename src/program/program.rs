@@ -17,13 +17,18 @@ use crate::GlObject;
 use crate::ProgramExt;
 use crate::Handle;
 use crate::RawUniformValue;
+use crate::RawUniformArray;
+use crate::DrawError;
+use crate::uniforms::Uniforms;
 
 use crate::program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, ShaderType, Binary, SpirvProgram};
 use crate::program::GetBinaryError;
+use crate::program::is_binary_supported;
+use crate::program::cache;
 
 use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackBuffer};
-use crate::program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
+use crate::program::reflection::{SubroutineData, ShaderStage, SubroutineUniform, FragmentOutput};
 use crate::program::shader::{build_shader, build_spirv_shader};
 
 use crate::program::raw::RawProgram;
@@ -39,11 +44,64 @@ pub struct Program {
 
 impl Program {
     /// Builds a new program.
+    ///
+    /// If a program cache directory has been set on the context with
+    /// `Context::set_program_cache_directory`, programs built from source code or from SPIR-V are
+    /// loaded from and stored into this directory automatically.
     pub fn new<'a, F: ?Sized, I>(facade: &F, input: I) -> Result<Program, ProgramCreationError>
                          where I: Into<ProgramCreationInput<'a>>, F: Facade
     {
         let input = input.into();
 
+        let context = facade.get_context();
+        let cache_entry = match context.get_program_cache_directory() {
+            Some(ref dir) if is_binary_supported(context) => {
+                cache::get_cache_entry(dir, context, &input)
+            },
+            _ => None,
+        };
+
+        let cache_entry = match cache_entry {
+            Some(entry) => entry,
+            None => return Program::new_uncached(facade, input),
+        };
+
+        if let Some(binary) = cache::load(&cache_entry) {
+            let (outputs_srgb, uses_point_size) = match input {
+                ProgramCreationInput::SourceCode { outputs_srgb, uses_point_size, .. } =>
+                    (outputs_srgb, uses_point_size),
+                ProgramCreationInput::SpirV(ref program) =>
+                    (program.outputs_srgb, program.uses_point_size),
+                ProgramCreationInput::Binary { outputs_srgb, uses_point_size, .. } =>
+                    (outputs_srgb, uses_point_size),
+            };
+
+            let cached = Program::new_uncached(facade, ProgramCreationInput::Binary {
+                data: binary,
+                outputs_srgb,
+                uses_point_size,
+            });
+
+            match cached {
+                Ok(program) => return Ok(program),
+                // the binary is outdated or corrupted, compiling the program again
+                Err(_) => cache::remove(&cache_entry),
+            }
+        }
+
+        let program = Program::new_uncached(facade, input)?;
+
+        if let Ok(binary) = program.get_binary() {
+            cache::store(&cache_entry, &binary);
+        }
+
+        Ok(program)
+    }
+
+    /// Builds a new program without going through the program cache.
+    fn new_uncached<F: ?Sized>(facade: &F, input: ProgramCreationInput<'_>)
+                               -> Result<Program, ProgramCreationError> where F: Facade
+    {
         let (raw, outputs_srgb, uses_point_size) = match input {
             ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
                                                tessellation_evaluation_shader, geometry_shader,
@@ -98,7 +156,7 @@ impl Program {
 
                 (RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)?,
+                                               transform_feedback_varyings, false)?,
                  outputs_srgb, uses_point_size)
             },
 
@@ -163,10 +221,13 @@ impl Program {
 
                 (RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)?,
+                                               transform_feedback_varyings, false)?,
                  outputs_srgb, uses_point_size)
             }
         };
+
+        check_geometry_shader_limits(facade, &raw)?;
+
         Ok(Program {
             raw,
             outputs_srgb,
@@ -174,6 +235,27 @@ impl Program {
         })
     }
 
+    /// Builds a program containing a single stage, flagged with `GL_PROGRAM_SEPARABLE` so
+    /// that it can be bound to a program pipeline.
+    pub(crate) fn new_separable<F: ?Sized>(facade: &F, ty: ShaderType, source: &str)
+                                          -> Result<Program, ProgramCreationError> where F: Facade
+    {
+        let _lock = COMPILER_GLOBAL_LOCK.lock();
+        let shader = build_shader(facade, ty.to_opengl_type(), source)?;
+        let raw = RawProgram::from_shaders(facade, &[shader], ty == ShaderType::Geometry,
+                                           ty == ShaderType::TesselationControl,
+                                           ty == ShaderType::TesselationEvaluation,
+                                           None, true)?;
+
+        check_geometry_shader_limits(facade, &raw)?;
+
+        Ok(Program {
+            raw,
+            outputs_srgb: false,
+            uses_point_size: false,
+        })
+    }
+
     /// Builds a new program from GLSL source code.
     ///
     /// A program is a group of shaders linked together.
@@ -237,12 +319,90 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Returns the list of fragment outputs of the program, with their locations and types.
+    ///
+    /// This requires OpenGL 4.3, OpenGL ES 3.1 or `GL_ARB_program_interface_query`. If the
+    /// backend doesn't support it, the list is empty and you have to use
+    /// `get_frag_data_location` instead.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # fn example(program: glium::Program) {
+    /// for (name, output) in program.frag_data_outputs() {
+    ///     println!("Name: {} - Location: {} - Type: {:?}", name, output.location, output.ty);
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn frag_data_outputs(&self)
+                             -> &HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>> {
+        self.raw.frag_data_outputs()
+    }
+
+    /// Uploads the values of `uniforms` that are passed with `glUniform*` (numbers, vectors and
+    /// matrices) ahead of a draw.
+    ///
+    /// If the backend supports `glProgramUniform*` (OpenGL 4.1, OpenGL ES 3.1 or
+    /// `GL_ARB_separate_shader_objects`), the values are uploaded without switching the current
+    /// program. Otherwise the program is made current first.
+    ///
+    /// Textures, images, blocks and subroutines are ignored and are bound when drawing. If the
+    /// uniform cache is enabled with `set_uniform_cache_enabled`, passing the same values to the
+    /// draw afterwards doesn't upload them again.
+    #[inline]
+    pub fn stage_uniforms<U>(&self, uniforms: &U) -> Result<(), DrawError> where U: Uniforms {
+        self.raw.stage_uniforms(uniforms)
+    }
+
+    /// Enables or disables the elimination of redundant uniform updates.
+    ///
+    /// The cache is disabled by default. When enabled, glium remembers the last value uploaded
+    /// to each uniform location of the program and doesn't call `glUniform*` if a draw sets the
+    /// same value again. Floating-point values are compared bit by bit, so a `NaN` is considered
+    /// equal to itself.
+    ///
+    /// Disabling the cache makes every draw upload all of its uniforms again.
+    #[inline]
+    pub fn set_uniform_cache_enabled(&self, enabled: bool) {
+        self.raw.set_uniform_cache_enabled(enabled)
+    }
+
+    /// Returns true if redundant uniform updates are eliminated.
+    #[inline]
+    pub fn is_uniform_cache_enabled(&self) -> bool {
+        self.raw.is_uniform_cache_enabled()
+    }
+
+    /// Forgets the uniform values that glium thinks the program holds, so that the next draw
+    /// uploads all of its uniforms.
+    ///
+    /// You only need to call this if you modify the uniforms of the program with raw OpenGL
+    /// calls.
+    #[inline]
+    pub fn invalidate_uniform_cache(&self) {
+        self.raw.invalidate_uniform_cache()
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    /// Reads back the current value of a uniform variable from the backend.
+    ///
+    /// The value is returned in the variant that matches the reflected type of the uniform.
+    /// Booleans, samplers and images are returned as `SignedInt`. For arrays, only the first
+    /// element is returned.
+    ///
+    /// Returns `None` if the uniform doesn't exist or if its type can't be represented by a
+    /// `RawUniformValue`.
+    #[inline]
+    pub fn get_uniform_value(&self, name: &str) -> Option<RawUniformValue> {
+        self.raw.get_uniform_value(name)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -291,6 +451,31 @@ impl Program {
         self.raw.transform_feedback_matches(format, stride)
     }
 
+    /// Checks whether the attributes of this program can be fed by vertex buffers with the
+    /// specified formats.
+    ///
+    /// This performs the same verifications as a draw call, which allows you to validate your
+    /// meshes against a program ahead of time.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glium::Vertex;
+    /// # fn example(program: glium::Program) {
+    /// #[derive(Copy, Clone)]
+    /// struct MyVertex { position: [f32; 2] }
+    /// glium::implement_vertex!(MyVertex, position);
+    ///
+    /// if program.check_vertex_formats(&[MyVertex::build_bindings()]).is_err() {
+    ///     println!("The mesh can't be drawn with this program");
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn check_vertex_formats(&self, formats: &[VertexFormat]) -> Result<(), DrawError> {
+        self.raw.check_vertex_formats(formats)
+    }
+
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
     /// depends on the vertex/index data passed when drawing.
     ///
@@ -301,6 +486,15 @@ impl Program {
         self.raw.get_output_primitives()
     }
 
+    /// Returns the maximum number of vertices that the geometry shader outputs per invocation.
+    ///
+    /// This corresponds to the `max_vertices` layout qualifier of the geometry shader. Returns
+    /// `None` if the program doesn't contain a geometry shader.
+    #[inline]
+    pub fn get_geometry_vertices_out(&self) -> Option<u32> {
+        self.raw.get_geometry_vertices_out()
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -326,6 +520,9 @@ impl Program {
     }
 
     /// Returns informations about an attribute, if it exists.
+    ///
+    /// Arrays are named without the `[0]` suffix that some drivers append, but looking up
+    /// `name[0]` returns the same attribute as `name`.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
         self.raw.get_attribute(name)
@@ -333,6 +530,8 @@ impl Program {
 
     /// Returns an iterator to the list of attributes.
     ///
+    /// The names of arrays never have a `[0]` suffix.
+    ///
     /// ## Example
     ///
     /// ```no_run
@@ -434,26 +633,7 @@ impl GlObject for Program {
 
 impl ProgramExt for Program {
     fn use_program(&self, ctxt: &mut CommandContext<'_>) {
-        // compatibility was checked at program creation
-        if ctxt.version.0 == Api::Gl {
-            if self.uses_point_size && !ctxt.state.enabled_program_point_size {
-                unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
-            } else if !self.uses_point_size && ctxt.state.enabled_program_point_size {
-                unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
-            }
-        }
-
-        if (ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
-           ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control) && ctxt.state.enabled_framebuffer_srgb == self.outputs_srgb {
-            ctxt.state.enabled_framebuffer_srgb = !self.outputs_srgb;
-
-            if self.outputs_srgb {
-                unsafe { ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB) };
-            } else {
-                unsafe { ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB) };
-            }
-        }
-
+        sync_program_state(ctxt, self.uses_point_size, self.outputs_srgb);
         self.raw.use_program(ctxt)
     }
 
@@ -464,6 +644,13 @@ impl ProgramExt for Program {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray<'_>)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -492,6 +679,11 @@ impl ProgramExt for Program {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_uniform_array(&self, name: &str) -> Option<&Uniform> {
+        self.raw.get_uniform_array(name)
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -514,3 +706,48 @@ impl ProgramExt for Program {
         self.raw.get_subroutine_data()
     }
 }
+
+/// Enables or disables `GL_PROGRAM_POINT_SIZE` and `GL_FRAMEBUFFER_SRGB` before a program
+/// or a program pipeline is used.
+pub(crate) fn sync_program_state(ctxt: &mut CommandContext<'_>, uses_point_size: bool,
+                                 outputs_srgb: bool)
+{
+    // compatibility was checked at program creation
+    if ctxt.version.0 == Api::Gl {
+        if uses_point_size && !ctxt.state.enabled_program_point_size {
+            unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
+        } else if !uses_point_size && ctxt.state.enabled_program_point_size {
+            unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
+        }
+    }
+
+    if (ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+       ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control) && ctxt.state.enabled_framebuffer_srgb == outputs_srgb {
+        ctxt.state.enabled_framebuffer_srgb = !outputs_srgb;
+
+        if outputs_srgb {
+            unsafe { ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB) };
+        } else {
+            unsafe { ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB) };
+        }
+    }
+}
+
+/// Checks that the `max_vertices` declared by the geometry shader of a linked program doesn't
+/// exceed the limit of the backend. Most drivers already refuse to compile such a shader, but
+/// some of them accept it and fail later when drawing.
+fn check_geometry_shader_limits<F: ?Sized>(facade: &F, raw: &RawProgram)
+                                           -> Result<(), ProgramCreationError> where F: Facade
+{
+    let max = match facade.get_context().get_capabilities().max_geometry_output_vertices {
+        Some(max) => max as u32,
+        None => return Ok(()),
+    };
+
+    match raw.get_geometry_vertices_out() {
+        Some(requested) if requested > max => {
+            Err(ProgramCreationError::GeometryShaderTooManyOutputVertices { requested, max })
+        },
+        _ => Ok(()),
+    }
+}
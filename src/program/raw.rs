@@ -23,6 +23,7 @@ use crate::GlObject;
 use crate::ProgramExt;
 use crate::Handle;
 use crate::RawUniformValue;
+use crate::RawUniformArray;
 
 use crate::QueryExt;
 use crate::draw_parameters::TimeElapsedQuery;
@@ -32,23 +33,24 @@ use crate::BufferExt;
 use crate::BufferSliceExt;
 
 use crate::program::{ProgramCreationError, Binary, GetBinaryError};
-use crate::program::uniforms_storage::UniformsStorage;
+use crate::program::uniforms_storage::{UniformsStorage, is_program_uniform_supported};
 
-use crate::program::compute::ComputeCommand;
+use crate::program::compute::{ComputeCommand, ComputeError};
 use crate::program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use crate::program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use crate::program::reflection::{SubroutineData, ShaderStage};
 use crate::program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
 use crate::program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
+use crate::program::reflection::reflect_geometry_vertices_out;
 use crate::program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
-use crate::program::reflection::{reflect_subroutine_data};
+use crate::program::reflection::{reflect_subroutine_data, reflect_fragment_outputs, FragmentOutput};
 use crate::program::shader::Shader;
 use crate::program::binary_header::{attach_glium_header, process_glium_header};
 
 use crate::uniforms::Uniforms;
 
 use crate::vertex::VertexFormat;
-use crate::vertex_array_object::VertexAttributesSystem;
+use crate::vertex_array_object::{VertexAttributesSystem, is_binding_mode_compatible};
 
 /// A combination of shaders linked together.
 pub struct RawProgram {
@@ -56,14 +58,17 @@ pub struct RawProgram {
     id: Handle,
     uniform_values: UniformsStorage,
     uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+    uniform_arrays: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
     uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     subroutine_data: SubroutineData,
     attributes: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
     frag_data_locations: RefCell<HashMap<String, Option<u32>, BuildHasherDefault<FnvHasher>>>,
+    frag_outputs: HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>,
     tf_buffers: Vec<TransformFeedbackBuffer>,
     ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     atomic_counters: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     output_primitives: Option<OutputPrimitives>,
+    geometry_vertices_out: Option<u32>,
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
@@ -75,7 +80,8 @@ impl RawProgram {
     pub fn from_shaders<'a, F: ?Sized, I>(facade: &'a F, shaders: I, has_geometry_shader: bool,
                                   has_tessellation_control_shader: bool,
                                   has_tessellation_evaluation_shader: bool,
-                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>)
+                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>,
+                                  separable: bool)
                                   -> Result<RawProgram, ProgramCreationError>
                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
     {
@@ -139,6 +145,16 @@ impl RawProgram {
                 }
             }
 
+            // marking the program as usable in a program pipeline
+            if separable {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // checked in the frontend
+                };
+
+                ctxt.gl.ProgramParameteri(id, gl::PROGRAM_SEPARABLE, gl::TRUE as gl::types::GLint);
+            }
+
             // linking
             {
                 ctxt.report_debug_output_errors.set(false);
@@ -164,8 +180,9 @@ impl RawProgram {
             id
         };
 
-        let (uniforms, atomic_counters) = unsafe { reflect_uniforms(&mut ctxt, id) };
+        let (uniforms, uniform_arrays, atomic_counters) = unsafe { reflect_uniforms(&mut ctxt, id) };
         let attributes = unsafe { reflect_attributes(&mut ctxt, id) };
+        let frag_outputs = unsafe { reflect_fragment_outputs(&mut ctxt, id) };
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
         let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
         let ssbos = unsafe { reflect_shader_storage_blocks(&mut ctxt, id) };
@@ -183,19 +200,28 @@ impl RawProgram {
             None
         };
 
+        let geometry_vertices_out = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_vertices_out(&mut ctxt, id) })
+        } else {
+            None
+        };
+
         Ok(RawProgram {
             context: facade.get_context().clone(),
             id,
             uniforms,
+            uniform_arrays,
             uniform_values: UniformsStorage::new(),
             uniform_blocks: blocks,
             subroutine_data,
             attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            frag_outputs,
             tf_buffers,
             ssbos,
             atomic_counters,
             output_primitives,
+            geometry_vertices_out,
             has_geometry_shader,
             has_tessellation_control_shader,
             has_tessellation_evaluation_shader,
@@ -234,10 +260,12 @@ impl RawProgram {
             id
         };
 
-        let ((uniforms, atomic_counters), attributes, blocks, tf_buffers, ssbos, subroutine_data) = unsafe {
+        let ((uniforms, uniform_arrays, atomic_counters), attributes, frag_outputs, blocks, tf_buffers, ssbos,
+             subroutine_data) = unsafe {
             (
                 reflect_uniforms(&mut ctxt, id),
                 reflect_attributes(&mut ctxt, id),
+                reflect_fragment_outputs(&mut ctxt, id),
                 reflect_uniform_blocks(&mut ctxt, id),
                 reflect_transform_feedback(&mut ctxt, id),
                 reflect_shader_storage_blocks(&mut ctxt, id),
@@ -255,19 +283,28 @@ impl RawProgram {
             None
         };
 
+        let geometry_vertices_out = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_vertices_out(&mut ctxt, id) })
+        } else {
+            None
+        };
+
         Ok(RawProgram {
             context: facade.get_context().clone(),
             id,
             uniforms,
+            uniform_arrays,
             uniform_values: UniformsStorage::new(),
             uniform_blocks: blocks,
             subroutine_data,
             attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            frag_outputs,
             tf_buffers,
             ssbos,
             atomic_counters,
             output_primitives,
+            geometry_vertices_out,
             has_geometry_shader,
             has_tessellation_control_shader,
             has_tessellation_evaluation_shader,
@@ -361,12 +398,185 @@ impl RawProgram {
         location
     }
 
+    /// Returns the list of fragment outputs of the program.
+    ///
+    /// This requires OpenGL 4.3, OpenGL ES 3.1 or `GL_ARB_program_interface_query`. If the
+    /// backend doesn't support it, the list is empty and you have to use
+    /// `get_frag_data_location` instead.
+    #[inline]
+    pub fn frag_data_outputs(&self)
+                             -> &HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>> {
+        &self.frag_outputs
+    }
+
+    /// Uploads the plain values of `uniforms` to the program ahead of a draw.
+    ///
+    /// See `Program::stage_uniforms`.
+    pub fn stage_uniforms<U>(&self, uniforms: &U) -> Result<(), DrawError> where U: Uniforms {
+        let mut ctxt = self.context.make_current();
+
+        let dsa = match self.id {
+            Handle::Id(_) => is_program_uniform_supported(&ctxt),
+            Handle::Handle(_) => false,
+        };
+
+        if !dsa {
+            self.use_program(&mut ctxt);
+        }
+
+        uniforms.stage_uniform_values(&mut ctxt, self)
+    }
+
+    /// Enables or disables the elimination of redundant uniform updates.
+    ///
+    /// See `Program::set_uniform_cache_enabled`.
+    #[inline]
+    pub fn set_uniform_cache_enabled(&self, enabled: bool) {
+        self.uniform_values.set_values_cache_enabled(enabled);
+    }
+
+    /// Returns true if redundant uniform updates are eliminated.
+    #[inline]
+    pub fn is_uniform_cache_enabled(&self) -> bool {
+        self.uniform_values.is_values_cache_enabled()
+    }
+
+    /// Forgets the uniform values that glium thinks the program holds.
+    ///
+    /// See `Program::invalidate_uniform_cache`.
+    #[inline]
+    pub fn invalidate_uniform_cache(&self) {
+        self.uniform_values.invalidate_values();
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.uniforms.get(name)
     }
 
+    /// Reads back the current value of a uniform variable from the backend.
+    ///
+    /// This is mostly useful for debugging or for capturing the state of a program. The value is
+    /// returned in the variant that matches the reflected type of the uniform. Booleans, samplers
+    /// and images are returned as `SignedInt`. For arrays, only the first element is returned.
+    ///
+    /// Returns `None` if the uniform doesn't exist or if its type can't be represented by a
+    /// `RawUniformValue` (for example non-square matrices).
+    pub fn get_uniform_value(&self, name: &str) -> Option<RawUniformValue> {
+        let uniform = self.uniforms.get(name)?;
+        let location = uniform.location;
+
+        let ctxt = self.context.make_current();
+
+        macro_rules! get_uniform(
+            ($ty:ty, $n:expr, $func:ident, $func_arb:ident) => ({
+                let mut data: [$ty; $n] = [Default::default(); $n];
+                unsafe {
+                    match self.id {
+                        Handle::Id(id) => ctxt.gl.$func(id, location, data.as_mut_ptr()),
+                        Handle::Handle(id) => ctxt.gl.$func_arb(id, location, data.as_mut_ptr()),
+                    }
+                }
+                data
+            });
+            ($ty:ty, $n:expr, $func:ident) => ({
+                let mut data: [$ty; $n] = [Default::default(); $n];
+                unsafe {
+                    match self.id {
+                        Handle::Id(id) => ctxt.gl.$func(id, location, data.as_mut_ptr()),
+                        Handle::Handle(_) => return None,
+                    }
+                }
+                data
+            });
+        );
+
+        macro_rules! float(($n:expr) => (get_uniform!(gl::types::GLfloat, $n, GetUniformfv, GetUniformfvARB)));
+        macro_rules! int(($n:expr) => (get_uniform!(gl::types::GLint, $n, GetUniformiv, GetUniformivARB)));
+        macro_rules! uint(($n:expr) => (get_uniform!(gl::types::GLuint, $n, GetUniformuiv)));
+
+        macro_rules! double(($n:expr) => ({
+            if !(ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_gpu_shader_fp64) {
+                return None;
+            }
+            get_uniform!(gl::types::GLdouble, $n, GetUniformdv)
+        }));
+
+        macro_rules! int64(($ty:ty, $n:expr, $func:ident) => ({
+            if !ctxt.extensions.gl_arb_gpu_shader_int64 {
+                return None;
+            }
+            get_uniform!($ty, $n, $func)
+        }));
+
+        use crate::uniforms::UniformType;
+
+        let value = match uniform.ty {
+            UniformType::Float => RawUniformValue::Float(float!(1)[0]),
+            UniformType::FloatVec2 => RawUniformValue::Vec2(float!(2)),
+            UniformType::FloatVec3 => RawUniformValue::Vec3(float!(3)),
+            UniformType::FloatVec4 => RawUniformValue::Vec4(float!(4)),
+            UniformType::FloatMat2 => {
+                let v = float!(4);
+                RawUniformValue::Mat2([[v[0], v[1]], [v[2], v[3]]])
+            },
+            UniformType::FloatMat3 => {
+                let v = float!(9);
+                RawUniformValue::Mat3([[v[0], v[1], v[2]], [v[3], v[4], v[5]], [v[6], v[7], v[8]]])
+            },
+            UniformType::FloatMat4 => {
+                let v = float!(16);
+                RawUniformValue::Mat4([[v[0], v[1], v[2], v[3]], [v[4], v[5], v[6], v[7]],
+                                       [v[8], v[9], v[10], v[11]], [v[12], v[13], v[14], v[15]]])
+            },
+            UniformType::Double => RawUniformValue::Double(double!(1)[0]),
+            UniformType::DoubleVec2 => RawUniformValue::DoubleVec2(double!(2)),
+            UniformType::DoubleVec3 => RawUniformValue::DoubleVec3(double!(3)),
+            UniformType::DoubleVec4 => RawUniformValue::DoubleVec4(double!(4)),
+            UniformType::DoubleMat2 => {
+                let v = double!(4);
+                RawUniformValue::DoubleMat2([[v[0], v[1]], [v[2], v[3]]])
+            },
+            UniformType::DoubleMat3 => {
+                let v = double!(9);
+                RawUniformValue::DoubleMat3([[v[0], v[1], v[2]], [v[3], v[4], v[5]],
+                                             [v[6], v[7], v[8]]])
+            },
+            UniformType::DoubleMat4 => {
+                let v = double!(16);
+                RawUniformValue::DoubleMat4([[v[0], v[1], v[2], v[3]], [v[4], v[5], v[6], v[7]],
+                                             [v[8], v[9], v[10], v[11]],
+                                             [v[12], v[13], v[14], v[15]]])
+            },
+            UniformType::UnsignedInt => RawUniformValue::UnsignedInt(uint!(1)[0]),
+            UniformType::UnsignedIntVec2 => RawUniformValue::UnsignedIntVec2(uint!(2)),
+            UniformType::UnsignedIntVec3 => RawUniformValue::UnsignedIntVec3(uint!(3)),
+            UniformType::UnsignedIntVec4 => RawUniformValue::UnsignedIntVec4(uint!(4)),
+            UniformType::IntVec2 | UniformType::BoolVec2 => RawUniformValue::IntVec2(int!(2)),
+            UniformType::IntVec3 | UniformType::BoolVec3 => RawUniformValue::IntVec3(int!(3)),
+            UniformType::IntVec4 | UniformType::BoolVec4 => RawUniformValue::IntVec4(int!(4)),
+            UniformType::Int64 => RawUniformValue::Int64(int64!(gl::types::GLint64, 1, GetUniformi64vARB)[0]),
+            UniformType::Int64Vec2 => RawUniformValue::Int64Vec2(int64!(gl::types::GLint64, 2, GetUniformi64vARB)),
+            UniformType::Int64Vec3 => RawUniformValue::Int64Vec3(int64!(gl::types::GLint64, 3, GetUniformi64vARB)),
+            UniformType::Int64Vec4 => RawUniformValue::Int64Vec4(int64!(gl::types::GLint64, 4, GetUniformi64vARB)),
+            UniformType::UnsignedInt64 => RawUniformValue::UnsignedInt64(int64!(gl::types::GLuint64, 1, GetUniformui64vARB)[0]),
+            UniformType::UnsignedInt64Vec2 => RawUniformValue::UnsignedInt64Vec2(int64!(gl::types::GLuint64, 2, GetUniformui64vARB)),
+            UniformType::UnsignedInt64Vec3 => RawUniformValue::UnsignedInt64Vec3(int64!(gl::types::GLuint64, 3, GetUniformui64vARB)),
+            UniformType::UnsignedInt64Vec4 => RawUniformValue::UnsignedInt64Vec4(int64!(gl::types::GLuint64, 4, GetUniformui64vARB)),
+            UniformType::FloatMat2x3 | UniformType::FloatMat2x4 | UniformType::FloatMat3x2 |
+            UniformType::FloatMat3x4 | UniformType::FloatMat4x2 | UniformType::FloatMat4x3 |
+            UniformType::DoubleMat2x3 | UniformType::DoubleMat2x4 | UniformType::DoubleMat3x2 |
+            UniformType::DoubleMat3x4 | UniformType::DoubleMat4x2 | UniformType::DoubleMat4x3 => {
+                return None;
+            },
+            // ints, bools, samplers, images and atomic counters are all stored as integers
+            _ => RawUniformValue::SignedInt(int!(1)[0]),
+        };
+
+        Some(value)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -443,6 +653,50 @@ impl RawProgram {
         true
     }
 
+    /// Checks whether the attributes of this program can be fed by vertex buffers with the
+    /// specified formats.
+    ///
+    /// This performs the same verifications as a draw call, but without needing the buffers
+    /// themselves. Attributes are matched by location if the format specifies one, and by name
+    /// otherwise.
+    pub fn check_vertex_formats(&self, formats: &[VertexFormat]) -> Result<(), DrawError> {
+        for format in formats {
+            for &(ref name, _, location, ty, normalize) in format.iter() {
+                let attribute = if location == -1 {
+                    self.attributes.get(&**name)
+                } else {
+                    self.attributes.values().find(|a| a.location == location)
+                };
+
+                if let Some(attribute) = attribute {
+                    if ty.get_num_components() != attribute.ty.get_num_components() ||
+                       attribute.size != 1
+                    {
+                        return Err(DrawError::AttributeTypeMismatch);
+                    }
+
+                    if !is_binding_mode_compatible(attribute.ty, ty, normalize) {
+                        return Err(DrawError::AttributeTypeMismatch);
+                    }
+                }
+            }
+        }
+
+        for (name, attribute) in self.attributes.iter() {
+            let found = formats.iter().any(|format| {
+                format.iter().any(|&(ref n, _, location, _, _)| {
+                    (location != -1 && location == attribute.location) || n == name
+                })
+            });
+
+            if !found {
+                return Err(DrawError::AttributeMissing);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
     /// depends on the vertex/index data passed when drawing.
     ///
@@ -453,6 +707,15 @@ impl RawProgram {
         self.output_primitives
     }
 
+    /// Returns the maximum number of vertices that the geometry shader outputs per invocation.
+    ///
+    /// This corresponds to `GL_GEOMETRY_VERTICES_OUT`. Returns `None` if the program doesn't
+    /// contain a geometry shader.
+    #[inline]
+    pub fn get_geometry_vertices_out(&self) -> Option<u32> {
+        self.geometry_vertices_out
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -478,9 +741,13 @@ impl RawProgram {
     }
 
     /// Returns informations about an attribute, if it exists.
+    ///
+    /// The names of arrays are stored without the `[0]` suffix, but the suffixed name is
+    /// accepted too.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
         self.attributes.get(name)
+            .or_else(|| self.attributes.get(name.strip_suffix("[0]")?))
     }
 
     /// Returns an iterator to the list of attributes.
@@ -546,31 +813,36 @@ impl RawProgram {
     /// The program *must* contain a compute shader.
     /// TODO: check inside the program if it has a compute shader instead of being unsafe
     pub unsafe fn dispatch_compute<U>(&self, uniforms: U, x: u32, y: u32, z: u32)
-                                      -> Result<(), DrawError>      // TODO: other error?
+                                      -> Result<(), ComputeError>
                                       where U: Uniforms
     {
         let mut ctxt = self.context.make_current();
 
-        // TODO: return an error instead
-        assert!(x < ctxt.capabilities.max_compute_work_group_count.0 as u32);
-        assert!(y < ctxt.capabilities.max_compute_work_group_count.1 as u32);
-        assert!(z < ctxt.capabilities.max_compute_work_group_count.2 as u32);
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3) ||
+             ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+             ctxt.extensions.gl_arb_compute_shader)
+        {
+            return Err(ComputeError::ComputeShadersNotSupported);
+        }
 
-        assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
-                ctxt.version >= &Version(Api::GlEs, 3, 1) ||
-                ctxt.extensions.gl_arb_compute_shader);
+        let maximum = ctxt.capabilities.max_compute_work_group_count;
+        let maximum = (maximum.0 as u32, maximum.1 as u32, maximum.2 as u32);
+        if x > maximum.0 || y > maximum.1 || z > maximum.2 {
+            return Err(ComputeError::WorkGroupCountTooLarge { requested: (x, y, z), maximum });
+        }
 
         TimeElapsedQuery::end_conditional_render(&mut ctxt);
 
-        let mut fences = Vec::with_capacity(0);
+        let mut fences = ctxt.scratch_buffers.take_fences();
 
         self.use_program(&mut ctxt);
-        uniforms.bind_uniforms(&mut ctxt, self, &mut fences)?;
+        uniforms.bind_uniforms(&mut ctxt, &[self], &mut fences)?;
         ctxt.gl.DispatchCompute(x, y, z);
 
-        for fence in fences {
+        for fence in fences.drain(..) {
             fence.insert(&mut ctxt);
         }
+        ctxt.scratch_buffers.give_back_fences(fences);
 
         ctxt.state.next_draw_call_id += 1;
 
@@ -585,14 +857,17 @@ impl RawProgram {
     /// TODO: check inside the program if it has a compute shader instead of being unsafe
     pub unsafe fn dispatch_compute_indirect<U>(&self, uniforms: U,
                                                buffer: BufferSlice<'_, ComputeCommand>)
-                                               -> Result<(), DrawError>      // TODO: other error?
+                                               -> Result<(), ComputeError>
                                                where U: Uniforms
     {
         let mut ctxt = self.context.make_current();
 
-        assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
-                ctxt.version >= &Version(Api::GlEs, 3, 1) ||
-                ctxt.extensions.gl_arb_compute_shader);
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3) ||
+             ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+             ctxt.extensions.gl_arb_compute_shader)
+        {
+            return Err(ComputeError::ComputeShadersNotSupported);
+        }
 
         TimeElapsedQuery::end_conditional_render(&mut ctxt);
 
@@ -608,14 +883,15 @@ impl RawProgram {
 
         self.use_program(&mut ctxt);
 
-        let mut fences = Vec::with_capacity(0);
-        uniforms.bind_uniforms(&mut ctxt, self, &mut fences)?;
+        let mut fences = ctxt.scratch_buffers.take_fences();
+        uniforms.bind_uniforms(&mut ctxt, &[self], &mut fences)?;
 
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
 
-        for fence in fences {
+        for fence in fences.drain(..) {
             fence.insert(&mut ctxt);
         }
+        ctxt.scratch_buffers.give_back_fences(fences);
 
         ctxt.state.next_draw_call_id += 1;
 
@@ -662,6 +938,13 @@ impl ProgramExt for RawProgram {
         self.uniform_values.set_uniform_value(ctxt, self.id, uniform_location, value);
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext<'_>, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray<'_>)
+    {
+        self.uniform_values.set_uniform_array(ctxt, self.id, uniform_location, value);
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext<'_>, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -690,6 +973,11 @@ impl ProgramExt for RawProgram {
         self.uniforms.get(name)
     }
 
+    #[inline]
+    fn get_uniform_array(&self, name: &str) -> Option<&Uniform> {
+        self.uniform_arrays.get(name)
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         &self.uniform_blocks
@@ -52,6 +52,115 @@ pub struct UniformBlock {
 
     /// Layout of the block.
     pub layout: BlockLayout,
+
+    /// Rules that were used to compute the offsets of the members of the block.
+    ///
+    /// OpenGL doesn't report the `layout` qualifier of a block, so this is deduced from the
+    /// strides of its arrays. Blocks whose strides are the same with both rules are reported
+    /// as `Std140`.
+    pub memory_layout: BlockMemoryLayout,
+}
+
+/// Rules used to compute the offsets of the members of a uniform block or of a shader
+/// storage block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlockMemoryLayout {
+    /// The `std140` layout. The elements of arrays and matrices are aligned to 16 bytes.
+    Std140,
+
+    /// The `std430` layout, usually used with shader storage blocks. Same as `std140`, except
+    /// that the elements of arrays and matrices are only aligned like their type. An array of
+    /// `float`s, for example, is tightly packed.
+    Std430,
+}
+
+impl BlockMemoryLayout {
+    /// Returns the number of bytes between two consecutive elements of an array of `ty`.
+    ///
+    /// Returns `None` if `ty` can't be stored in a buffer, like samplers.
+    pub fn array_stride(&self, ty: UniformType) -> Option<usize> {
+        let (scalar, rows, columns) = basic_type_shape(ty)?;
+
+        let round_up = |value: usize, alignment: usize| value.div_ceil(alignment) * alignment;
+        let element_alignment = |alignment: usize| match *self {
+            BlockMemoryLayout::Std140 => round_up(alignment, 16),
+            BlockMemoryLayout::Std430 => alignment,
+        };
+
+        // alignment of a vector with `rows` components, vec3s being aligned like vec4s
+        let vector_alignment = scalar * if rows == 3 { 4 } else { rows };
+
+        // matrices are stored like arrays of column vectors
+        let (alignment, size) = if columns == 1 {
+            (vector_alignment, scalar * rows)
+        } else {
+            let column_alignment = element_alignment(vector_alignment);
+            (column_alignment, columns * round_up(scalar * rows, column_alignment))
+        };
+
+        Some(round_up(size, element_alignment(alignment)))
+    }
+
+    /// Deduces the layout of a block from the types and the reflected strides of its arrays.
+    fn from_array_strides<I>(arrays: I) -> BlockMemoryLayout
+                             where I: IntoIterator<Item = (UniformType, usize)>
+    {
+        for (ty, stride) in arrays {
+            // non-array members have a stride of 0
+            if stride == 0 {
+                continue;
+            }
+
+            if BlockMemoryLayout::Std140.array_stride(ty) != Some(stride) &&
+               BlockMemoryLayout::Std430.array_stride(ty) == Some(stride)
+            {
+                return BlockMemoryLayout::Std430;
+            }
+        }
+
+        BlockMemoryLayout::Std140
+    }
+}
+
+/// Returns the size of a scalar, the number of rows and the number of columns of a type that
+/// can be stored in a buffer.
+fn basic_type_shape(ty: UniformType) -> Option<(usize, usize, usize)> {
+    Some(match ty {
+        UniformType::Float | UniformType::Int | UniformType::UnsignedInt |
+        UniformType::Bool => (4, 1, 1),
+        UniformType::FloatVec2 | UniformType::IntVec2 | UniformType::UnsignedIntVec2 |
+        UniformType::BoolVec2 => (4, 2, 1),
+        UniformType::FloatVec3 | UniformType::IntVec3 | UniformType::UnsignedIntVec3 |
+        UniformType::BoolVec3 => (4, 3, 1),
+        UniformType::FloatVec4 | UniformType::IntVec4 | UniformType::UnsignedIntVec4 |
+        UniformType::BoolVec4 => (4, 4, 1),
+        UniformType::Double | UniformType::Int64 | UniformType::UnsignedInt64 => (8, 1, 1),
+        UniformType::DoubleVec2 | UniformType::Int64Vec2 |
+        UniformType::UnsignedInt64Vec2 => (8, 2, 1),
+        UniformType::DoubleVec3 | UniformType::Int64Vec3 |
+        UniformType::UnsignedInt64Vec3 => (8, 3, 1),
+        UniformType::DoubleVec4 | UniformType::Int64Vec4 |
+        UniformType::UnsignedInt64Vec4 => (8, 4, 1),
+        UniformType::FloatMat2 => (4, 2, 2),
+        UniformType::FloatMat3 => (4, 3, 3),
+        UniformType::FloatMat4 => (4, 4, 4),
+        UniformType::FloatMat2x3 => (4, 3, 2),
+        UniformType::FloatMat2x4 => (4, 4, 2),
+        UniformType::FloatMat3x2 => (4, 2, 3),
+        UniformType::FloatMat3x4 => (4, 4, 3),
+        UniformType::FloatMat4x2 => (4, 2, 4),
+        UniformType::FloatMat4x3 => (4, 3, 4),
+        UniformType::DoubleMat2 => (8, 2, 2),
+        UniformType::DoubleMat3 => (8, 3, 3),
+        UniformType::DoubleMat4 => (8, 4, 4),
+        UniformType::DoubleMat2x3 => (8, 3, 2),
+        UniformType::DoubleMat2x4 => (8, 4, 2),
+        UniformType::DoubleMat3x2 => (8, 2, 3),
+        UniformType::DoubleMat3x4 => (8, 4, 3),
+        UniformType::DoubleMat4x2 => (8, 2, 4),
+        UniformType::DoubleMat4x3 => (8, 3, 4),
+        _ => return None,
+    })
 }
 
 /// Layout of a shader storage buffer or a uniform buffer.
@@ -107,21 +216,68 @@ pub enum BlockLayout {
 
 /// Information about an attribute of a program (except its name).
 ///
-/// Internal struct. Not public.
+/// Matrices occupy several consecutive locations, one per column, starting at `location`.
+/// Arrays occupy `size` times as many locations.
 #[derive(Debug, Copy, Clone)]
 pub struct Attribute {
-    /// The index of the uniform.
+    /// The location of the attribute.
     ///
-    /// This is internal information, you probably don't need to use it.
+    /// This is the value set with `layout(location = ...)` in the shader, or the location
+    /// that was automatically chosen by the linker.
     pub location: i32,
 
     /// Type of the attribute.
     pub ty: AttributeType,
 
-    /// Number of elements of the attribute.
+    /// Number of elements of the attribute. This is `1` if the attribute is not an array.
     pub size: usize,
 }
 
+impl Attribute {
+    /// Returns true if the attribute is an array.
+    #[inline]
+    pub fn is_array(&self) -> bool {
+        self.size > 1
+    }
+
+    /// Returns the number of consecutive locations used by this attribute.
+    #[inline]
+    pub fn get_num_locations(&self) -> usize {
+        let per_element = match self.ty {
+            AttributeType::F16x2x2 | AttributeType::F16x2x3 | AttributeType::F16x2x4 |
+            AttributeType::F32x2x2 | AttributeType::F32x2x3 | AttributeType::F32x2x4 |
+            AttributeType::F64x2x2 | AttributeType::F64x2x3 | AttributeType::F64x2x4 => 2,
+            AttributeType::F16x3x2 | AttributeType::F16x3x3 | AttributeType::F16x3x4 |
+            AttributeType::F32x3x2 | AttributeType::F32x3x3 | AttributeType::F32x3x4 |
+            AttributeType::F64x3x2 | AttributeType::F64x3x3 | AttributeType::F64x3x4 => 3,
+            AttributeType::F16x4x2 | AttributeType::F16x4x3 | AttributeType::F16x4x4 |
+            AttributeType::F32x4x2 | AttributeType::F32x4x3 | AttributeType::F32x4x4 |
+            AttributeType::F64x4x2 | AttributeType::F64x4x3 | AttributeType::F64x4x4 => 4,
+            _ => 1,
+        };
+
+        per_element * self.size
+    }
+}
+
+/// Information about a fragment output of a program (except its name).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FragmentOutput {
+    /// The location of the output, in other words the index of the color attachment it is
+    /// written to.
+    pub location: u32,
+
+    /// The index of the output when using dual-source blending. This is always `0` if the
+    /// backend doesn't support it.
+    pub index: u32,
+
+    /// Type of the output.
+    pub ty: UniformType,
+
+    /// If it is an array, the number of elements.
+    pub size: Option<usize>,
+}
+
 /// Describes the layout of a buffer that can receive transform feedback output.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransformFeedbackBuffer {
@@ -177,8 +333,12 @@ pub enum OutputPrimitives {
 }
 
 /// Returns a list of uniforms and a list of atomic counters of a program.
+/// Returns the uniforms, with arrays flattened into one uniform per element, the uniform arrays
+/// indexed by their name without the `[0]` suffix, and the atomic counters.
 pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
-                               -> (HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>, HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>)
+                               -> (HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+                                   HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+                                   HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>)
 {
     // number of active uniforms
     let active_uniforms = {
@@ -283,6 +443,7 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
                     ty: UniformType::UnsignedInt,
                     offset_in_buffer: 0,
                 },
+                memory_layout: BlockMemoryLayout::Std140,
             });
         } else {
             uniforms.insert(uniform_name, Uniform {
@@ -295,6 +456,7 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
 
     // Flatten arrays
     let mut uniforms_flattened = HashMap::with_hasher(Default::default());
+    let mut uniform_arrays = HashMap::with_hasher(Default::default());
     for uniform in uniforms {
         // If this is a normal non-array element, just move it over
         if !uniform.0.ends_with("[0]") {
@@ -307,6 +469,9 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
         let name_base = uniform.0.split('[').next().unwrap();
         let uniform_base = uniform.1;
 
+        // the array itself is kept so that all its elements can be set at once
+        uniform_arrays.insert(uniform.0[.. uniform.0.len() - 3].to_owned(), uniform_base);
+
         // Go over all the elements in the array
         for i in 0..uniform_base.size.unwrap() {
             let uniform = Uniform {
@@ -318,7 +483,7 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext<'_>, program: Handle)
         }
     }
 
-    (uniforms_flattened, atomic_counters)
+    (uniforms_flattened, uniform_arrays, atomic_counters)
 }
 
 pub unsafe fn reflect_attributes(ctxt: &mut CommandContext<'_>, program: Handle)
@@ -386,11 +551,17 @@ pub unsafe fn reflect_attributes(ctxt: &mut CommandContext<'_>, program: Handle)
 
         attr_name_tmp.set_len(attr_name_len as usize);
 
-        let attr_name = String::from_utf8(attr_name_tmp).unwrap();
+        let mut attr_name = String::from_utf8(attr_name_tmp).unwrap();
         if attr_name.starts_with("gl_") {   // ignoring everything built-in
             continue;
         }
 
+        // some drivers append `[0]` to the name of arrays
+        if attr_name.ends_with("[0]") {
+            let len = attr_name.len();
+            attr_name.truncate(len - 3);
+        }
+
         if attr_name.is_empty() {
             // Some spirv compilers add an empty attribute to shaders. Most drivers
             // don't expose this attribute, but some do.
@@ -509,6 +680,12 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Han
         ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
                                     gl::UNIFORM_SIZE, member_size.as_mut_ptr());
 
+        // getting the array strides of the members
+        let mut member_array_strides = ::std::iter::repeat(0).take(num_members as usize)
+                                                             .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_ARRAY_STRIDE, member_array_strides.as_mut_ptr());
+
         // getting the length of the names of the members
         let mut member_name_len = ::std::iter::repeat(0).take(num_members as usize)
                                                          .collect::<Vec<gl::types::GLint>>();
@@ -535,12 +712,19 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext<'_>, program: Han
              member_size[index] as usize, None)
         });
 
+        let memory_layout = BlockMemoryLayout::from_array_strides(
+            member_types.iter().zip(member_array_strides.iter()).map(|(&ty, &stride)| {
+                (glenum_to_uniform_type(ty as gl::types::GLenum), stride as usize)
+            })
+        );
+
         // finally inserting into the blocks list
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: block_size as usize,
             layout: introspection_output_to_layout(members),
+            memory_layout,
         });
     }
 
@@ -708,6 +892,25 @@ pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext<'_>, progra
     }
 }
 
+/// Obtains the maximum number of vertices that the geometry shader outputs.
+pub unsafe fn reflect_geometry_vertices_out(ctxt: &mut CommandContext<'_>, program: Handle) -> u32 {
+    let mut value = 0;
+
+    match program {
+        Handle::Id(program) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramiv(program, gl::GEOMETRY_VERTICES_OUT, &mut value);
+        },
+        Handle::Handle(program) => {
+            assert!(ctxt.extensions.gl_arb_vertex_shader);
+            ctxt.gl.GetObjectParameterivARB(program, gl::GEOMETRY_VERTICES_OUT, &mut value);
+        }
+    };
+
+    value as u32
+}
+
 /// Obtains the type of data that the tessellation evaluation shader stage outputs.
 ///
 /// # Unsafety
@@ -739,6 +942,91 @@ pub unsafe fn reflect_tess_eval_output_type(ctxt: &mut CommandContext<'_>, progr
     }
 }
 
+/// Returns the list of fragment outputs of a program.
+///
+/// Returns an empty list if the backend doesn't support program interface queries.
+pub unsafe fn reflect_fragment_outputs(ctxt: &mut CommandContext<'_>, program: Handle)
+    -> HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_program_interface_query)
+    {
+        // not supported
+        return HashMap::with_hasher(Default::default());
+    }
+
+    let program = match program {
+        Handle::Id(program) => program,
+        Handle::Handle(_) => return HashMap::with_hasher(Default::default())
+    };
+
+    // `GL_LOCATION_INDEX` is not available on OpenGL ES
+    let has_location_index = ctxt.version.0 == Api::Gl;
+
+    // number of active outputs
+    let active_outputs = {
+        let mut active_outputs: gl::types::GLint = 0;
+        ctxt.gl.GetProgramInterfaceiv(program, gl::PROGRAM_OUTPUT,
+                                      gl::ACTIVE_RESOURCES, &mut active_outputs);
+        active_outputs as gl::types::GLuint
+    };
+
+    // the result of this function
+    let mut outputs = HashMap::with_hasher(Default::default());
+    outputs.reserve(active_outputs as usize);
+
+    for output_id in 0 .. active_outputs {
+        let (ty, array_size, location, name_len) = {
+            let mut output: [gl::types::GLint; 4] = [0; 4];
+            ctxt.gl.GetProgramResourceiv(program, gl::PROGRAM_OUTPUT, output_id, 4,
+                                         [gl::TYPE, gl::ARRAY_SIZE, gl::LOCATION,
+                                          gl::NAME_LENGTH].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as gl::types::GLenum, output[1] as usize, output[2], output[3] as usize)
+        };
+
+        let index = if has_location_index {
+            let mut index: gl::types::GLint = 0;
+            ctxt.gl.GetProgramResourceiv(program, gl::PROGRAM_OUTPUT, output_id, 1,
+                                         [gl::LOCATION_INDEX].as_ptr(), 1,
+                                         ptr::null_mut(), &mut index);
+            cmp::max(index, 0) as u32
+        } else {
+            0
+        };
+
+        let mut name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+            let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+            ctxt.gl.GetProgramResourceName(program, gl::PROGRAM_OUTPUT, output_id,
+                                           name_tmp_len, &mut name_tmp_len,
+                                           name_tmp.as_mut_ptr() as *mut _);
+            name_tmp.set_len(name_tmp_len as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        // ignoring built-in outputs such as `gl_FragDepth`, which don't have a location
+        if name.starts_with("gl_") || location < 0 {
+            continue;
+        }
+
+        if name.ends_with("[0]") {
+            let len = name.len();
+            name.truncate(len - 3);
+        }
+
+        outputs.insert(name, FragmentOutput {
+            location: location as u32,
+            index,
+            ty: glenum_to_uniform_type(ty),
+            size: if array_size == 1 { None } else { Some(array_size) },
+        });
+    }
+
+    outputs
+}
+
 /// Returns the list of shader storage blocks of a program.
 pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, program: Handle)
     -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
@@ -801,9 +1089,9 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, progr
             variables
         };
 
-        // iterator over variables
-        let members = active_variables.into_iter().map(|variable| {
-            let (ty, array_size, offset, _array_stride, name_len, top_level_array_size) = {
+        // list of variables, with their array strides
+        let (members, array_strides): (Vec<_>, Vec<_>) = active_variables.into_iter().map(|variable| {
+            let (ty, array_size, offset, array_stride, name_len, top_level_array_size) = {
                 let mut output: [gl::types::GLint; 6] = [0; 6];
                 ctxt.gl.GetProgramResourceiv(program, gl::BUFFER_VARIABLE,
                                              variable as gl::types::GLuint, 6,
@@ -827,15 +1115,16 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext<'_>, progr
                 String::from_utf8(name_tmp).unwrap()
             };
 
-            (name, offset, ty, array_size, Some(top_level_array_size))
-        });
+            ((name, offset, ty, array_size, Some(top_level_array_size)), (ty, array_stride))
+        }).unzip();
 
         // finally inserting into the blocks list
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: total_size,
-            layout: introspection_output_to_layout(members),
+            layout: introspection_output_to_layout(members.into_iter()),
+            memory_layout: BlockMemoryLayout::from_array_strides(array_strides),
         });
     }
 
@@ -908,7 +1197,13 @@ fn introspection_output_to_layout<I>(elements: I) -> BlockLayout
                     } else {
                         members.push((current_component.to_owned(), BlockLayout::Array {
                             content: Box::new(BlockLayout::Struct { members: Vec::new() }),
-                            length: if name_rest.is_some() { array } else { array_size },
+                            // only the first element of top-level arrays of shader storage
+                            // blocks is reported, so we use their reported size instead
+                            length: if name_rest.is_some() {
+                                top_level_array_size.unwrap_or(array)
+                            } else {
+                                array_size
+                            },
                         }));
                     }
 
@@ -1102,6 +1397,23 @@ fn glenum_to_attribute_type(value: gl::types::GLenum) -> AttributeType {
         gl::DOUBLE_VEC2 => AttributeType::F64F64,
         gl::DOUBLE_VEC3 => AttributeType::F64F64F64,
         gl::DOUBLE_VEC4 => AttributeType::F64F64F64F64,
+        gl::DOUBLE_MAT2 => AttributeType::F64x2x2,
+        gl::DOUBLE_MAT3 => AttributeType::F64x3x3,
+        gl::DOUBLE_MAT4 => AttributeType::F64x4x4,
+        gl::DOUBLE_MAT2x3 => AttributeType::F64x2x3,
+        gl::DOUBLE_MAT2x4 => AttributeType::F64x2x4,
+        gl::DOUBLE_MAT3x2 => AttributeType::F64x3x2,
+        gl::DOUBLE_MAT3x4 => AttributeType::F64x3x4,
+        gl::DOUBLE_MAT4x2 => AttributeType::F64x4x2,
+        gl::DOUBLE_MAT4x3 => AttributeType::F64x4x3,
+        gl::INT64_ARB => AttributeType::I64,
+        gl::INT64_VEC2_ARB => AttributeType::I64I64,
+        gl::INT64_VEC3_ARB => AttributeType::I64I64I64,
+        gl::INT64_VEC4_ARB => AttributeType::I64I64I64I64,
+        gl::UNSIGNED_INT64_ARB => AttributeType::U64,
+        gl::UNSIGNED_INT64_VEC2_ARB => AttributeType::U64U64,
+        gl::UNSIGNED_INT64_VEC3_ARB => AttributeType::U64U64U64,
+        gl::UNSIGNED_INT64_VEC4_ARB => AttributeType::U64U64U64U64,
         v => panic!("Unknown value returned by OpenGL attribute type: {}", v)
     }
 }
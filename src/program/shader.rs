@@ -14,6 +14,7 @@ use crate::GlObject;
 use crate::Handle;
 
 use crate::program::{ProgramCreationError, ShaderType, SpirvEntryPoint};
+use crate::program::diagnostics;
 
 /// A single, compiled but unlinked, shader.
 pub struct Shader {
@@ -171,13 +172,7 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
 
             error_log.set_len(error_log_size as usize);
 
-            match String::from_utf8(error_log) {
-                Ok(msg) => Err(ProgramCreationError::CompilationError(msg, ShaderType::from_opengl_type(shader_type))),
-                Err(_) => Err(
-                    ProgramCreationError::CompilationError("Could not convert the log \
-                                                            message to UTF-8".to_owned(), ShaderType::from_opengl_type(shader_type))
-                ),
-            }
+            Err(compilation_error(error_log, shader_type))
         }
     }
 }
@@ -250,17 +245,25 @@ pub fn build_spirv_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum,
 
             error_log.set_len(error_log_size as usize);
 
-            match String::from_utf8(error_log) {
-                Ok(msg) => Err(ProgramCreationError::CompilationError(msg, ShaderType::from_opengl_type(shader_type))),
-                Err(_) => Err(
-                    ProgramCreationError::CompilationError("Could not convert the log \
-                                                            message to UTF-8".to_owned(), ShaderType::from_opengl_type(shader_type))
-                ),
-            }
+            Err(compilation_error(error_log, shader_type))
         }
     }
 }
 
+/// Builds a `CompilationError` from the info log returned by the driver.
+fn compilation_error(error_log: Vec<u8>, shader_type: gl::types::GLenum) -> ProgramCreationError {
+    let stage = ShaderType::from_opengl_type(shader_type);
+
+    match String::from_utf8(error_log) {
+        Ok(msg) => {
+            let diagnostics = diagnostics::parse_info_log(&msg, stage);
+            ProgramCreationError::CompilationError(msg, stage, diagnostics)
+        },
+        Err(_) => ProgramCreationError::CompilationError("Could not convert the log \
+                                                          message to UTF-8".to_owned(), stage, Vec::new()),
+    }
+}
+
 pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::types::GLenum)
                                           -> bool where C: CapabilitiesSource
 {
@@ -1,7 +1,8 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use crate::RawUniformValue;
+use crate::RawUniformArray;
 
 use smallvec::SmallVec;
 use fnv::FnvHasher;
@@ -14,6 +15,7 @@ use crate::version::Api;
 use crate::program::reflection::ShaderStage;
 
 pub struct UniformsStorage {
+    values_cache_enabled: Cell<bool>,
     values: RefCell<HashMap<gl::types::GLint, Option<RawUniformValue>,
                             BuildHasherDefault<FnvHasher>>>,
     uniform_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
@@ -27,6 +29,7 @@ impl UniformsStorage {
     #[inline]
     pub fn new() -> UniformsStorage {
         UniformsStorage {
+            values_cache_enabled: Cell::new(false),
             values: RefCell::new(HashMap::with_hasher(Default::default())),
             uniform_blocks: RefCell::new(SmallVec::new()),
             shader_storage_blocks: RefCell::new(SmallVec::new()),
@@ -34,20 +37,56 @@ impl UniformsStorage {
         }
     }
 
+    /// Enables or disables the elimination of redundant `glUniform` calls.
+    ///
+    /// Disabling the cache also forgets all the values stored so far.
+    #[inline]
+    pub fn set_values_cache_enabled(&self, enabled: bool) {
+        self.values_cache_enabled.set(enabled);
+        if !enabled {
+            self.values.borrow_mut().clear();
+        }
+    }
+
+    /// Returns true if redundant `glUniform` calls are eliminated.
+    #[inline]
+    pub fn is_values_cache_enabled(&self) -> bool {
+        self.values_cache_enabled.get()
+    }
+
+    /// Forgets the values stored so far, so that the next call to `set_uniform_value` for each
+    /// location calls `glUniform`.
+    #[inline]
+    pub fn invalidate_values(&self) {
+        self.values.borrow_mut().clear();
+    }
+
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniform`.
+    ///
+    /// If the cache is disabled, `glUniform` is always called.
     pub fn set_uniform_value(&self, ctxt: &mut CommandContext<'_>, program: Handle,
                              location: gl::types::GLint, value: &RawUniformValue)
     {
         let mut values = self.values.borrow_mut();
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
+        // if the program is not current, the value is set with `glProgramUniform`
+        let dsa_program = if ctxt.state.program == program {
+            None
+        } else {
+            assert!(is_program_uniform_supported(ctxt));
+            match program {
+                Handle::Id(id) => Some(id),
+                Handle::Handle(_) => unreachable!(),
+            }
+        };
 
         macro_rules! uniform(
-            ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $($params:expr),+) => (
+            ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $program_uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    if let Some(id) = dsa_program {
+                        $ctxt.gl.$program_uniform(id, $($params),+)
+                    } else if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        $ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         $ctxt.gl.$uniform($($params),+)
@@ -60,64 +99,74 @@ impl UniformsStorage {
         );
 
         macro_rules! uniform_f64(
-            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
+            ($ctxt:expr, $uniform:ident, $program_uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.extensions.gl_arb_gpu_shader_fp64 {
-                        $ctxt.gl.$uniform($($params),+)
-                    } else {
+                    if !$ctxt.extensions.gl_arb_gpu_shader_fp64 {
                         panic!("Double precision floats are not supported on this system.")
+                    } else if let Some(id) = dsa_program {
+                        $ctxt.gl.$program_uniform(id, $($params),+)
+                    } else {
+                        $ctxt.gl.$uniform($($params),+)
                     }
                 }
             )
         );
 
         macro_rules! uniform_i64(
-            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
+            ($ctxt:expr, $uniform:ident, $program_uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.extensions.gl_arb_gpu_shader_int64 {
-                        $ctxt.gl.$uniform($($params),+)
-                    } else {
+                    if !$ctxt.extensions.gl_arb_gpu_shader_int64 {
                         panic!("64 bit integers are not supported on this system.")
+                    } else if let Some(id) = dsa_program {
+                        $ctxt.gl.$program_uniform(id, $($params),+)
+                    } else {
+                        $ctxt.gl.$uniform($($params),+)
                     }
                 }
             )
         );
 
-        match (value, values.entry(location).or_insert(None)) {
-            (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a == b => (),
-            (&RawUniformValue::Float(a), &mut Some(RawUniformValue::Float(b))) if a == b => (),
-            (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => (),
-            (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => (),
-            (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => (),
-            (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => (),
-            (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => (),
-            (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => (),
-            (&RawUniformValue::IntVec2(a), &mut Some(RawUniformValue::IntVec2(b))) if a == b => (),
-            (&RawUniformValue::IntVec3(a), &mut Some(RawUniformValue::IntVec3(b))) if a == b => (),
-            (&RawUniformValue::IntVec4(a), &mut Some(RawUniformValue::IntVec4(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec2(a), &mut Some(RawUniformValue::UnsignedIntVec2(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec3(a), &mut Some(RawUniformValue::UnsignedIntVec3(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec4(a), &mut Some(RawUniformValue::UnsignedIntVec4(b))) if a == b => (),
-            (&RawUniformValue::Double(a), &mut Some(RawUniformValue::Double(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a == b => (),
-            (&RawUniformValue::Int64(a), &mut Some(RawUniformValue::Int64(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec2(a), &mut Some(RawUniformValue::Int64Vec2(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec3(a), &mut Some(RawUniformValue::Int64Vec3(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec4(a), &mut Some(RawUniformValue::Int64Vec4(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64(a), &mut Some(RawUniformValue::UnsignedInt64(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => (),
+        let stored = values.entry(location).or_insert(None);
+        if !self.values_cache_enabled.get() {
+            *stored = None;
+        }
+
+        match (value, stored) {
+            (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Float(a), &mut Some(RawUniformValue::Float(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::IntVec2(a), &mut Some(RawUniformValue::IntVec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::IntVec3(a), &mut Some(RawUniformValue::IntVec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::IntVec4(a), &mut Some(RawUniformValue::IntVec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedIntVec2(a), &mut Some(RawUniformValue::UnsignedIntVec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedIntVec3(a), &mut Some(RawUniformValue::UnsignedIntVec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedIntVec4(a), &mut Some(RawUniformValue::UnsignedIntVec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Double(a), &mut Some(RawUniformValue::Double(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Int64(a), &mut Some(RawUniformValue::Int64(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Int64Vec2(a), &mut Some(RawUniformValue::Int64Vec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Int64Vec3(a), &mut Some(RawUniformValue::Int64Vec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Int64Vec4(a), &mut Some(RawUniformValue::Int64Vec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedInt64(a), &mut Some(RawUniformValue::UnsignedInt64(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a.same_bits(&b) => (),
+            (&RawUniformValue::Handle(a), &mut Some(RawUniformValue::Handle(b))) if a.same_bits(&b) => (),
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
-                uniform!(ctxt, Uniform1i, Uniform1iARB, location, v);
+                uniform!(ctxt, Uniform1i, Uniform1iARB, ProgramUniform1i, location, v);
             },
 
             (&RawUniformValue::UnsignedInt(v), target) => {
@@ -125,7 +174,9 @@ impl UniformsStorage {
 
                 // Uniform1uiARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.ProgramUniform1ui(id, location, v)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform1ui(location, v)
@@ -138,55 +189,55 @@ impl UniformsStorage {
 
             (&RawUniformValue::Float(v), target) => {
                 *target = Some(RawUniformValue::Float(v));
-                uniform!(ctxt, Uniform1f, Uniform1fARB, location, v);
+                uniform!(ctxt, Uniform1f, Uniform1fARB, ProgramUniform1f, location, v);
             },
 
             (&RawUniformValue::Mat2(v), target) => {
                 *target = Some(RawUniformValue::Mat2(v));
-                uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB,
+                uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB, ProgramUniformMatrix2fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat3(v), target) => {
                 *target = Some(RawUniformValue::Mat3(v));
-                uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB,
+                uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB, ProgramUniformMatrix3fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat4(v), target) => {
                 *target = Some(RawUniformValue::Mat4(v));
-                uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB,
+                uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB, ProgramUniformMatrix4fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Vec2(v), target) => {
                 *target = Some(RawUniformValue::Vec2(v));
-                uniform!(ctxt, Uniform2fv, Uniform2fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform2fv, Uniform2fvARB, ProgramUniform2fv, location, 1, v.as_ptr());
             },
 
             (&RawUniformValue::Vec3(v), target) => {
                 *target = Some(RawUniformValue::Vec3(v));
-                uniform!(ctxt, Uniform3fv, Uniform3fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform3fv, Uniform3fvARB, ProgramUniform3fv, location, 1, v.as_ptr());
             },
 
             (&RawUniformValue::Vec4(v), target) => {
                 *target = Some(RawUniformValue::Vec4(v));
-                uniform!(ctxt, Uniform4fv, Uniform4fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform4fv, Uniform4fvARB, ProgramUniform4fv, location, 1, v.as_ptr());
             },
 
             (&RawUniformValue::IntVec2(v), target) => {
                 *target = Some(RawUniformValue::IntVec2(v));
-                uniform!(ctxt, Uniform2iv, Uniform2ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform2iv, Uniform2ivARB, ProgramUniform2iv, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec3(v), target) => {
                 *target = Some(RawUniformValue::IntVec3(v));
-                uniform!(ctxt, Uniform3iv, Uniform3ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform3iv, Uniform3ivARB, ProgramUniform3iv, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec4(v), target) => {
                 *target = Some(RawUniformValue::IntVec4(v));
-                uniform!(ctxt, Uniform4iv, Uniform4ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform4iv, Uniform4ivARB, ProgramUniform4iv, location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::UnsignedIntVec2(v), target) => {
@@ -194,7 +245,9 @@ impl UniformsStorage {
 
                 // Uniform2uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.ProgramUniform2uiv(id, location, 1, v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform2uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
@@ -210,7 +263,9 @@ impl UniformsStorage {
 
                 // Uniform3uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.ProgramUniform3uiv(id, location, 1, v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform3uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
@@ -226,7 +281,9 @@ impl UniformsStorage {
 
                 // Uniform4uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.ProgramUniform4uiv(id, location, 1, v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform4uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
@@ -238,76 +295,210 @@ impl UniformsStorage {
             },
             (&RawUniformValue::Double(v), target) => {
                 *target = Some(RawUniformValue::Double(v));
-                uniform_f64!(ctxt, Uniform1d, location, v);
+                uniform_f64!(ctxt, Uniform1d, ProgramUniform1d, location, v);
             },
 
             (&RawUniformValue::DoubleMat2(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat2(v));
-                uniform_f64!(ctxt, UniformMatrix2dv,
+                uniform_f64!(ctxt, UniformMatrix2dv, ProgramUniformMatrix2dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat3(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat3(v));
-                uniform_f64!(ctxt, UniformMatrix3dv,
+                uniform_f64!(ctxt, UniformMatrix3dv, ProgramUniformMatrix3dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat4(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat4(v));
-                uniform_f64!(ctxt, UniformMatrix4dv,
+                uniform_f64!(ctxt, UniformMatrix4dv, ProgramUniformMatrix4dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec2(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec2(v));
-                uniform_f64!(ctxt, Uniform2dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform2dv, ProgramUniform2dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec3(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec3(v));
-                uniform_f64!(ctxt, Uniform3dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform3dv, ProgramUniform3dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec4(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec4(v));
-                uniform_f64!(ctxt, Uniform4dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform4dv, ProgramUniform4dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
             (&RawUniformValue::Int64(v), target) => {
                 *target = Some(RawUniformValue::Int64(v));
-                uniform_i64!(ctxt, Uniform1i64ARB, location, v);
+                uniform_i64!(ctxt, Uniform1i64ARB, ProgramUniform1i64ARB, location, v);
             },
             (&RawUniformValue::Int64Vec2(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec2(v));
-                uniform_i64!(ctxt, Uniform2i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform2i64vARB, ProgramUniform2i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec3(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec3(v));
-                uniform_i64!(ctxt, Uniform3i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform3i64vARB, ProgramUniform3i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec4(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec4(v));
-                uniform_i64!(ctxt, Uniform4i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform4i64vARB, ProgramUniform4i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
             (&RawUniformValue::UnsignedInt64(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64(v));
-                uniform_i64!(ctxt, Uniform1ui64ARB, location, v);
+                uniform_i64!(ctxt, Uniform1ui64ARB, ProgramUniform1ui64ARB, location, v);
             },
             (&RawUniformValue::UnsignedInt64Vec2(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec2(v));
-                uniform_i64!(ctxt, Uniform2ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform2ui64vARB, ProgramUniform2ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec3(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec3(v));
-                uniform_i64!(ctxt, Uniform3ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform3ui64vARB, ProgramUniform3ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec4(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec4(v));
-                uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform4ui64vARB, ProgramUniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+            },
+
+            (&RawUniformValue::Handle(v), target) => {
+                *target = Some(RawUniformValue::Handle(v));
+                unsafe {
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.ProgramUniformHandleui64ARB(id, location, v);
+                    } else {
+                        ctxt.gl.UniformHandleui64ARB(location, v);
+                    }
+                }
+            },
+        }
+    }
+
+    /// Sets the first elements of the uniform array at `location` with a single `glUniform*v`
+    /// call.
+    ///
+    /// Arrays are not compared with the stored values. The elements that are written are
+    /// forgotten instead, so that setting one of them individually later calls `glUniform` again.
+    pub fn set_uniform_array(&self, ctxt: &mut CommandContext<'_>, program: Handle,
+                             location: gl::types::GLint, value: &RawUniformArray<'_>)
+    {
+        let len = value.len();
+        if len == 0 {
+            return;
+        }
+
+        {
+            let mut values = self.values.borrow_mut();
+            for element in location .. location + len as gl::types::GLint {
+                values.remove(&element);
+            }
+        }
+
+        // if the program is not current, the value is set with `glProgramUniform`
+        let dsa_program = if ctxt.state.program == program {
+            None
+        } else {
+            assert!(is_program_uniform_supported(ctxt));
+            match program {
+                Handle::Id(id) => Some(id),
+                Handle::Handle(_) => unreachable!(),
+            }
+        };
+
+        let count = len as gl::types::GLsizei;
+
+        macro_rules! uniform(
+            ($uniform:ident, $uniform_arb:ident, $program_uniform:ident, $($params:expr),+) => (
+                unsafe {
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.$program_uniform(id, location, count, $($params),+)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    {
+                        ctxt.gl.$uniform(location, count, $($params),+)
+                    } else {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.$uniform_arb(location, count, $($params),+)
+                    }
+                }
+            )
+        );
+
+        // `glUniform*uivARB` don't exist
+        macro_rules! uniform_unsigned(
+            ($uniform:ident, $uniform_arb:ident, $program_uniform:ident, $ptr:expr) => (
+                unsafe {
+                    if let Some(id) = dsa_program {
+                        ctxt.gl.$program_uniform(id, location, count, $ptr as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    {
+                        ctxt.gl.$uniform(location, count, $ptr as *const gl::types::GLuint)
+                    } else {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.$uniform_arb(location, count, $ptr as *const gl::types::GLint)
+                    }
+                }
+            )
+        );
+
+        match *value {
+            RawUniformArray::SignedInt(v) => {
+                uniform!(Uniform1iv, Uniform1ivARB, ProgramUniform1iv, v.as_ptr());
+            },
+            RawUniformArray::UnsignedInt(v) => {
+                uniform_unsigned!(Uniform1uiv, Uniform1ivARB, ProgramUniform1uiv, v.as_ptr());
+            },
+            RawUniformArray::Float(v) => {
+                uniform!(Uniform1fv, Uniform1fvARB, ProgramUniform1fv, v.as_ptr());
+            },
+            RawUniformArray::Mat2(v) => {
+                uniform!(UniformMatrix2fv, UniformMatrix2fvARB, ProgramUniformMatrix2fv,
+                         gl::FALSE, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::Mat3(v) => {
+                uniform!(UniformMatrix3fv, UniformMatrix3fvARB, ProgramUniformMatrix3fv,
+                         gl::FALSE, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::Mat4(v) => {
+                uniform!(UniformMatrix4fv, UniformMatrix4fvARB, ProgramUniformMatrix4fv,
+                         gl::FALSE, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::Vec2(v) => {
+                uniform!(Uniform2fv, Uniform2fvARB, ProgramUniform2fv, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::Vec3(v) => {
+                uniform!(Uniform3fv, Uniform3fvARB, ProgramUniform3fv, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::Vec4(v) => {
+                uniform!(Uniform4fv, Uniform4fvARB, ProgramUniform4fv, v.as_ptr() as *const f32);
+            },
+            RawUniformArray::IntVec2(v) => {
+                uniform!(Uniform2iv, Uniform2ivARB, ProgramUniform2iv,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            RawUniformArray::IntVec3(v) => {
+                uniform!(Uniform3iv, Uniform3ivARB, ProgramUniform3iv,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            RawUniformArray::IntVec4(v) => {
+                uniform!(Uniform4iv, Uniform4ivARB, ProgramUniform4iv,
+                         v.as_ptr() as *const gl::types::GLint);
+            },
+            RawUniformArray::UnsignedIntVec2(v) => {
+                uniform_unsigned!(Uniform2uiv, Uniform2ivARB, ProgramUniform2uiv, v.as_ptr());
+            },
+            RawUniformArray::UnsignedIntVec3(v) => {
+                uniform_unsigned!(Uniform3uiv, Uniform3ivARB, ProgramUniform3uiv, v.as_ptr());
+            },
+            RawUniformArray::UnsignedIntVec4(v) => {
+                uniform_unsigned!(Uniform4uiv, Uniform4ivARB, ProgramUniform4uiv, v.as_ptr());
             },
         }
     }
@@ -325,9 +516,6 @@ impl UniformsStorage {
             }
         }
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
-
         match (value, &mut blocks[location as usize]) {
             (a, &mut Some(b)) if a == b => (),
 
@@ -356,9 +544,6 @@ impl UniformsStorage {
             }
         }
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
-
         match (value, &mut blocks[location as usize]) {
             (a, &mut Some(b)) if a == b => (),
 
@@ -406,3 +591,56 @@ impl UniformsStorage {
         }
     }
 }
+
+/// Returns true if `glProgramUniform*` is supported, in which case uniforms can be set without
+/// making the program current.
+#[inline]
+pub fn is_program_uniform_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+    ctxt.extensions.gl_arb_separate_shader_objects
+}
+
+/// Comparison of the values that can be stored in a `RawUniformValue`.
+///
+/// Contrary to `==`, floating-point values are compared bit by bit: a `NaN` is equal to itself
+/// and `0.0` is different from `-0.0`, which is what we want when deciding whether the backend
+/// already holds a value.
+trait SameBits {
+    fn same_bits(&self, other: &Self) -> bool;
+}
+
+macro_rules! impl_same_bits_eq {
+    ($($ty:ty),+) => {
+        $(
+            impl SameBits for $ty {
+                #[inline]
+                fn same_bits(&self, other: &$ty) -> bool {
+                    self == other
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_same_bits_float {
+    ($($ty:ty),+) => {
+        $(
+            impl SameBits for $ty {
+                #[inline]
+                fn same_bits(&self, other: &$ty) -> bool {
+                    self.to_bits() == other.to_bits()
+                }
+            }
+        )+
+    };
+}
+
+impl_same_bits_eq!(gl::types::GLint, gl::types::GLuint, gl::types::GLint64, gl::types::GLuint64);
+impl_same_bits_float!(gl::types::GLfloat, gl::types::GLdouble);
+
+impl<T: SameBits, const N: usize> SameBits for [T; N] {
+    #[inline]
+    fn same_bits(&self, other: &[T; N]) -> bool {
+        self.iter().zip(other.iter()).all(|(a, b)| a.same_bits(b))
+    }
+}
@@ -1,3 +1,5 @@
+use std::cmp;
+
 use crate::DrawError;
 
 use crate::uniforms::SamplerBehavior;
@@ -74,6 +76,13 @@ impl SamplerObject {
             ctxt.gl.DeleteSamplers(1, [self.id].as_ptr());
         }
     }
+
+    /// Releases a sampler that belonged to a context that has been lost, without calling
+    /// OpenGL.
+    #[inline]
+    pub fn forget(mut self) {
+        self.destroyed = true;
+    }
 }
 
 impl GlObject for SamplerObject {
@@ -102,14 +111,22 @@ pub fn get_sampler(ctxt: &mut CommandContext<'_>, behavior: &SamplerBehavior)
         return Err(DrawError::SamplersNotSupported);
     }
 
+    // the anisotropy is clamped when the sampler is built, so we clamp it before looking for
+    // an existing sampler in order to share samplers that end up being the same
+    let mut behavior = *behavior;
+    behavior.max_anisotropy = match ctxt.capabilities.max_texture_max_anisotropy {
+        Some(max_value) => cmp::min(behavior.max_anisotropy, max_value as u16),
+        None => 1,
+    };
+
     // looking for an existing sampler
-    if let Some(obj) = ctxt.samplers.get(behavior) {
+    if let Some(obj) = ctxt.samplers.get(&behavior) {
         return Ok(obj.get_id());
     }
 
     // builds a new sampler
-    let sampler = SamplerObject::new(ctxt, behavior);
+    let sampler = SamplerObject::new(ctxt, &behavior);
     let id = sampler.get_id();
-    ctxt.samplers.insert(*behavior, sampler);
+    ctxt.samplers.insert(behavior, sampler);
     Ok(id)
 }
@@ -1,7 +1,6 @@
 /*!
 Contains everything related to external API semaphores.
 */
-// TODO: Add Windows support via EXT_external_objects_win32
 
 use std::rc::Rc;
 
@@ -20,6 +19,8 @@ pub enum SemaphoreCreationError {
     SemaphoreObjectNotSupported,
     /// Driver does not support EXT_semaphore_fd
     SemaphoreObjectFdNotSupported,
+    /// Driver does not support EXT_semaphore_win32
+    SemaphoreObjectWin32NotSupported,
     /// OpenGL returned a null pointer when creating semaphore
     NullResult,
 }
@@ -31,6 +32,7 @@ impl std::fmt::Display for SemaphoreCreationError {
         let desc = match *self {
             SemaphoreObjectNotSupported => "Driver does not support EXT_semaphore",
             SemaphoreObjectFdNotSupported => "Driver does not support EXT_semaphore_fd",
+            SemaphoreObjectWin32NotSupported => "Driver does not support EXT_semaphore_win32",
             NullResult => "OpenGL returned a null pointer when creating semaphore",
         };
         f.write_str(desc)
@@ -117,6 +119,30 @@ impl Semaphore {
         }
     }
 
+    /// Creates a semaphore imported from an opaque Win32 handle. Contrary to file descriptors,
+    /// the handle isn't consumed and must be closed by the caller.
+    #[cfg(target_os = "windows")]
+    pub unsafe fn new_from_win32_handle<F: Facade + ?Sized>(
+        facade: &F,
+        handle: *mut std::os::raw::c_void,
+    ) -> Result<Self, SemaphoreCreationError> {
+        let ctxt = facade.get_context().make_current();
+        let sem = Self::new(facade, &ctxt)?;
+
+        if ctxt.extensions.gl_ext_semaphore_win32 {
+            ctxt.gl
+                .ImportSemaphoreWin32HandleEXT(sem.id, gl::HANDLE_TYPE_OPAQUE_WIN32_EXT, handle);
+
+            if ctxt.gl.IsSemaphoreEXT(sem.id) == gl::FALSE {
+                Err(SemaphoreCreationError::NullResult)
+            } else {
+                Ok(sem)
+            }
+        } else {
+            Err(SemaphoreCreationError::SemaphoreObjectWin32NotSupported)
+        }
+    }
+
     fn new<F: Facade + ?Sized>(
         facade: &F,
         ctxt: &CommandContext<'_>,
@@ -0,0 +1,382 @@
+#![cfg(feature = "sprite")]
+/*!
+Batched drawing of textured 2D quads.
+
+A `SpriteBatch` accumulates sprites in memory, then draws them when `flush` is called. The
+sprites are grouped by texture, and each group is drawn with a single instanced draw call. The
+order of the sprites is kept within a group, but not between groups: call `flush` between two
+sprites that overlap and use different textures if the order matters.
+
+Each sprite can use a part of its texture, which makes it possible to draw many different
+images with the same texture and thus in the same draw call. With the 'atlas' feature,
+`add_from_atlas` takes the part of the texture from a `TextureAtlas`.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>, texture: glium::Texture2d)
+#     where T: SurfaceTypeTrait + ResizeableSurface {
+# let projection = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0f32]];
+use glium::sprite::{Sprite, SpriteBatch};
+
+let mut batch = SpriteBatch::new(&display).unwrap();
+
+let mut frame = display.draw();
+batch.add(&texture, Sprite {
+    position: [0.0, 0.0],
+    size: [0.5, 0.5],
+    rotation: 0.3,
+    .. Default::default()
+});
+batch.flush(&mut frame, projection, &Default::default()).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+# Features
+
+Only available if the 'sprite' feature is enabled.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramChooserCreationError};
+use crate::texture::Texture2d;
+use crate::vertex::{BufferCreationError, VertexBuffer};
+use crate::{DrawError, DrawParameters, GlObject, Surface};
+
+#[cfg(feature = "atlas")]
+use crate::texture::atlas::{AtlasRegion, TextureAtlas};
+
+#[derive(Copy, Clone, Debug)]
+struct QuadVertex {
+    corner: [f32; 2],
+}
+
+implement_vertex!(QuadVertex, corner);
+
+#[derive(Copy, Clone, Debug)]
+struct SpriteInstance {
+    position: [f32; 2],
+    size: [f32; 2],
+    rotation: f32,
+    tex_rect: [f32; 4],
+    color: [f32; 4],
+}
+
+implement_vertex!(SpriteInstance, position, size, rotation, tex_rect, color);
+
+/// A textured quad.
+#[derive(Copy, Clone, Debug)]
+pub struct Sprite {
+    /// Position of the center of the sprite.
+    pub position: [f32; 2],
+    /// Width and height of the sprite.
+    pub size: [f32; 2],
+    /// Rotation around the center, in radians. Counter-clockwise if the Y axis goes up.
+    pub rotation: f32,
+    /// Texture coordinates of the bottom-left and top-right corners of the sprite.
+    pub tex_coords: ([f32; 2], [f32; 2]),
+    /// Color that the texture is multiplied with.
+    pub color: [f32; 4],
+}
+
+impl Default for Sprite {
+    #[inline]
+    fn default() -> Sprite {
+        Sprite {
+            position: [0.0, 0.0],
+            size: [1.0, 1.0],
+            rotation: 0.0,
+            tex_coords: ([0.0, 0.0], [1.0, 1.0]),
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
+
+/// Error that can happen when flushing a `SpriteBatch`.
+#[derive(Debug)]
+pub enum SpriteFlushError {
+    /// The backend doesn't support instancing.
+    InstancingNotSupported,
+    /// Error while creating the vertex buffer that holds the sprites.
+    BufferCreationError(BufferCreationError),
+    /// Error while drawing the sprites.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for SpriteFlushError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpriteFlushError::InstancingNotSupported => {
+                write!(fmt, "Instancing is not supported by the backend")
+            },
+            SpriteFlushError::BufferCreationError(err) => write!(fmt, "{}", err),
+            SpriteFlushError::DrawError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for SpriteFlushError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpriteFlushError::InstancingNotSupported => None,
+            SpriteFlushError::BufferCreationError(err) => Some(err),
+            SpriteFlushError::DrawError(err) => Some(err),
+        }
+    }
+}
+
+impl From<BufferCreationError> for SpriteFlushError {
+    #[inline]
+    fn from(err: BufferCreationError) -> SpriteFlushError {
+        SpriteFlushError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for SpriteFlushError {
+    #[inline]
+    fn from(err: DrawError) -> SpriteFlushError {
+        SpriteFlushError::DrawError(err)
+    }
+}
+
+/// Accumulates sprites, and draws them with one draw call per texture.
+///
+/// The textures are borrowed until the batch is destroyed, which means that they must be
+/// created before the batch.
+pub struct SpriteBatch<'t> {
+    context: Rc<Context>,
+    program: Program,
+    quad: Option<VertexBuffer<QuadVertex>>,
+    sprites: Vec<(&'t Texture2d, SpriteInstance)>,
+    buffer: Option<VertexBuffer<SpriteInstance>>,
+}
+
+impl<'t> SpriteBatch<'t> {
+    /// Builds a new `SpriteBatch` and compiles its program.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<SpriteBatch<'t>, ProgramChooserCreationError>
+        where F: Facade
+    {
+        let program = program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+                    in vec2 corner;
+                    in vec2 position;
+                    in vec2 size;
+                    in float rotation;
+                    in vec4 tex_rect;
+                    in vec4 color;
+                    out vec2 v_tex_coords;
+                    out vec4 v_color;
+
+                    void main() {
+                        vec2 offset = corner * size;
+                        float s = sin(rotation);
+                        float c = cos(rotation);
+                        offset = vec2(offset.x * c - offset.y * s, offset.x * s + offset.y * c);
+                        gl_Position = matrix * vec4(position + offset, 0.0, 1.0);
+                        v_tex_coords = mix(tex_rect.xy, tex_rect.zw, corner + 0.5);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D tex;
+                    in vec2 v_tex_coords;
+                    in vec4 v_color;
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = v_color * texture(tex, v_tex_coords);
+                    }
+                "
+            },
+
+            110 => {
+                vertex: "
+                    #version 110
+
+                    uniform mat4 matrix;
+                    attribute vec2 corner;
+                    attribute vec2 position;
+                    attribute vec2 size;
+                    attribute float rotation;
+                    attribute vec4 tex_rect;
+                    attribute vec4 color;
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        vec2 offset = corner * size;
+                        float s = sin(rotation);
+                        float c = cos(rotation);
+                        offset = vec2(offset.x * c - offset.y * s, offset.x * s + offset.y * c);
+                        gl_Position = matrix * vec4(position + offset, 0.0, 1.0);
+                        v_tex_coords = mix(tex_rect.xy, tex_rect.zw, corner + 0.5);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 110
+
+                    uniform sampler2D tex;
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = v_color * texture2D(tex, v_tex_coords);
+                    }
+                "
+            },
+
+            100 es => {
+                vertex: "
+                    #version 100
+
+                    uniform highp mat4 matrix;
+                    attribute highp vec2 corner;
+                    attribute highp vec2 position;
+                    attribute highp vec2 size;
+                    attribute highp float rotation;
+                    attribute mediump vec4 tex_rect;
+                    attribute lowp vec4 color;
+                    varying mediump vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        highp vec2 offset = corner * size;
+                        highp float s = sin(rotation);
+                        highp float c = cos(rotation);
+                        offset = vec2(offset.x * c - offset.y * s, offset.x * s + offset.y * c);
+                        gl_Position = matrix * vec4(position + offset, 0.0, 1.0);
+                        v_tex_coords = mix(tex_rect.xy, tex_rect.zw, corner + 0.5);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    uniform sampler2D tex;
+                    varying mediump vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = v_color * texture2D(tex, v_tex_coords);
+                    }
+                "
+            },
+        )?;
+
+        Ok(SpriteBatch {
+            context: facade.get_context().clone(),
+            program,
+            quad: None,
+            sprites: Vec::new(),
+            buffer: None,
+        })
+    }
+
+    /// Adds a sprite that uses a texture.
+    #[inline]
+    pub fn add(&mut self, texture: &'t Texture2d, sprite: Sprite) {
+        let (min, max) = sprite.tex_coords;
+        self.sprites.push((texture, SpriteInstance {
+            position: sprite.position,
+            size: sprite.size,
+            rotation: sprite.rotation,
+            tex_rect: [min[0], min[1], max[0], max[1]],
+            color: sprite.color,
+        }));
+    }
+
+    /// Adds a sprite that uses a region of an atlas. The `tex_coords` of the sprite are
+    /// ignored.
+    #[cfg(feature = "atlas")]
+    #[inline]
+    pub fn add_from_atlas(&mut self, atlas: &'t TextureAtlas, region: &AtlasRegion,
+                          sprite: Sprite)
+    {
+        self.add(atlas.get_texture(), Sprite {
+            tex_coords: atlas.get_tex_coords(region),
+            .. sprite
+        });
+    }
+
+    /// Returns the number of sprites waiting to be drawn.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Returns true if there is no sprite waiting to be drawn.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Discards the sprites without drawing them.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+
+    /// Draws all the sprites on the surface, then clears the list.
+    ///
+    /// `matrix` transforms the positions into clip space. The sprites are drawn with one draw
+    /// call per texture.
+    pub fn flush<S>(&mut self, surface: &mut S, matrix: [[f32; 4]; 4],
+                    draw_parameters: &DrawParameters<'_>) -> Result<(), SpriteFlushError>
+        where S: Surface
+    {
+        if self.sprites.is_empty() {
+            return Ok(());
+        }
+
+        // the sort is stable, which keeps the order of the sprites of each texture
+        self.sprites.sort_by_key(|(texture, _)| texture.get_id());
+
+        if self.quad.is_none() {
+            // two triangles covering a square of side 1 centered on the origin
+            self.quad = Some(VertexBuffer::immutable(&self.context, &[
+                QuadVertex { corner: [-0.5, -0.5] }, QuadVertex { corner: [0.5, -0.5] },
+                QuadVertex { corner: [-0.5, 0.5] }, QuadVertex { corner: [-0.5, 0.5] },
+                QuadVertex { corner: [0.5, -0.5] }, QuadVertex { corner: [0.5, 0.5] },
+            ])?);
+        }
+
+        let len = self.sprites.len();
+        if self.buffer.as_ref().map_or(true, |buffer| buffer.len() < len) {
+            self.buffer = Some(VertexBuffer::empty_dynamic(&self.context,
+                                                           len.next_power_of_two())?);
+        }
+
+        let buffer = self.buffer.as_ref().unwrap();
+        let instances = self.sprites.iter().map(|&(_, instance)| instance).collect::<Vec<_>>();
+        buffer.slice(0 .. len).unwrap().write(&instances);
+
+        let quad = self.quad.as_ref().unwrap();
+        let mut start = 0;
+        for group in self.sprites.chunk_by(|a, b| a.0.get_id() == b.0.get_id()) {
+            let slice = buffer.slice(start .. start + group.len()).unwrap();
+            let per_instance = slice.per_instance()
+                                    .map_err(|_| SpriteFlushError::InstancingNotSupported)?;
+
+            surface.draw((quad, per_instance), NoIndices(PrimitiveType::TrianglesList),
+                         &self.program, &uniform! { matrix: matrix, tex: group[0].0 },
+                         draw_parameters)?;
+            start += group.len();
+        }
+
+        self.sprites.clear();
+        Ok(())
+    }
+}
@@ -7,6 +7,8 @@ use crate::backend::Facade;
 use crate::context::Context;
 use crate::ContextExt;
 use std::rc::Rc;
+use std::mem;
+use std::sync::{Arc, Mutex};
 
 use std::thread;
 
@@ -56,6 +58,19 @@ impl SyncFence {
             _ => panic!("Could not wait for the fence")
         };
     }
+
+    /// Returns true if the operations that preceded the fence have finished on the server.
+    ///
+    /// Contrary to `wait`, this function never blocks.
+    pub fn is_signaled(&self) -> bool {
+        let sync = match self.id {
+            None => return true,
+            Some(s) => s
+        };
+
+        let mut ctxt = self.context.make_current();
+        unsafe { is_signaled(&mut ctxt, sync) }
+    }
 }
 
 impl Drop for SyncFence {
@@ -139,6 +154,104 @@ pub unsafe fn destroy_linear_sync_fence(ctxt: &mut CommandContext<'_>, mut fence
     delete_fence(ctxt, fence);
 }
 
+/// Releases a fence that belonged to a context that has been lost, without calling OpenGL.
+#[inline]
+pub fn forget_linear_sync_fence(mut fence: LinearSyncFence) {
+    fence.id.take();
+}
+
+/// Makes sure that the commands submitted so far can be waited upon by another context of the
+/// same share group.
+///
+/// Returns a fence that must be consumed with `wait_transfer_fence`. If fences are not supported,
+/// `glFinish` is called instead and `None` is returned.
+pub unsafe fn new_transfer_fence(ctxt: &mut CommandContext<'_>) -> Option<LinearSyncFence> {
+    match new_linear_sync_fence(ctxt) {
+        Ok(fence) => {
+            // the fence must be flushed, otherwise the other context could wait forever
+            ctxt.gl.Flush();
+            Some(fence)
+        },
+        Err(_) => {
+            ctxt.gl.Finish();
+            None
+        },
+    }
+}
+
+/// Waits for a fence returned by `new_transfer_fence` and destroys it.
+#[inline]
+pub unsafe fn wait_transfer_fence(ctxt: &mut CommandContext<'_>, fence: Option<LinearSyncFence>) {
+    if let Some(fence) = fence {
+        wait_linear_sync_fence_and_drop(fence, ctxt);
+    }
+}
+
+/// Function that destroys an object of a transfer that has been dropped.
+type OrphanDestructor = Box<dyn FnOnce(&mut CommandContext<'_>) + Send>;
+
+/// Objects of transfers that have been dropped instead of being attached to another context.
+///
+/// A transfer can be dropped on any thread, where no OpenGL context is current. It queues its
+/// objects here instead, and the context it was detached from destroys them the next time it
+/// is flushed or its buffers are swapped.
+#[derive(Clone, Default)]
+pub struct TransferOrphans {
+    inner: Arc<Mutex<TransferOrphansInner>>,
+}
+
+#[derive(Default)]
+struct TransferOrphansInner {
+    destructors: Vec<OrphanDestructor>,
+    // true once the context has been destroyed
+    closed: bool,
+}
+
+impl TransferOrphans {
+    /// Queues the fence of a transfer and a function that destroys its object.
+    ///
+    /// If the context has already been destroyed, the objects are leaked.
+    pub fn push<D>(&self, fence: Option<LinearSyncFence>, destructor: D)
+        where D: FnOnce(&mut CommandContext<'_>) + Send + 'static
+    {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        if inner.closed {
+            if let Some(fence) = fence {
+                forget_linear_sync_fence(fence);
+            }
+            return;
+        }
+
+        inner.destructors.push(Box::new(move |ctxt| unsafe {
+            // the commands that use the object don't need to be waited upon before deleting it
+            if let Some(fence) = fence {
+                destroy_linear_sync_fence(ctxt, fence);
+            }
+            destructor(ctxt);
+        }));
+    }
+
+    /// Destroys the objects that have been queued so far.
+    pub fn destroy(&self, ctxt: &mut CommandContext<'_>) {
+        let destructors = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            mem::take(&mut inner.destructors)
+        };
+
+        for destructor in destructors {
+            destructor(ctxt);
+        }
+    }
+
+    /// Destroys the objects that have been queued so far. The transfers that are dropped
+    /// afterwards leak their objects.
+    pub fn close(&self, ctxt: &mut CommandContext<'_>) {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).closed = true;
+        self.destroy(ctxt);
+    }
+}
+
 /// Calls `glClientWaitSync` and returns the result.
 ///
 /// Tries without flushing first, then with flushing.
@@ -182,6 +295,32 @@ unsafe fn client_wait(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) -
     }
 }
 
+/// Returns true if the fence has been signaled, without blocking.
+///
+/// The commands queue is flushed so that the fence is eventually reached.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn is_signaled(ctxt: &mut CommandContext<'_>, fence: gl::types::GLsync) -> bool {
+    let result = if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                    ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, 0)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, gl::SYNC_FLUSH_COMMANDS_BIT_APPLE, 0)
+    } else {
+        unreachable!();
+    };
+
+    match result {
+        gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+        gl::TIMEOUT_EXPIRED => false,
+        _ => panic!("Could not query the status of the fence")
+    }
+}
+
 /// Deletes a fence.
 ///
 /// # Unsafety
@@ -0,0 +1,573 @@
+#![cfg(feature = "text")]
+/*!
+Rendering of text with a glyph cache.
+
+A `FontTexture` holds a font at a given size. Its glyphs are rasterized the first time they are
+drawn, and stored in a `TextureAtlas` that starts small and doubles in size when it is full, up
+to the maximum texture size. The `TextSystem` contains the program that draws the glyphs.
+
+Glyphs are placed at whole pixel positions and sampled without filtering, which means that the
+text on the screen is exactly what the rasterizer produced.
+
+Text is laid out one character at a time, using the advances and the kerning of the font. This
+is enough for Latin, Greek, Cyrillic and the other scripts that don't need complex shaping, but
+not for scripts such as Arabic or Devanagari.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::Surface;
+use glium::text::{FontTexture, TextSystem};
+
+let system = TextSystem::new(&display).unwrap();
+let font = FontTexture::new(&display, std::fs::read("font.ttf").unwrap(), 24.0).unwrap();
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+glium::text::draw_text(&mut frame, &system, &font, "Hello, world!", [10.0, 10.0],
+                       [1.0, 1.0, 1.0, 1.0]).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+# Features
+
+Only available if the 'text' feature is enabled.
+
+*/
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use ab_glyph::{Font, FontArc, GlyphId, InvalidFont, PxScale, ScaleFont};
+
+use crate::backend::Facade;
+use crate::context::Context;
+use crate::index::{NoIndices, PrimitiveType};
+use crate::program::{Program, ProgramChooserCreationError};
+use crate::texture::atlas::{AtlasRegion, TextureAtlas};
+use crate::texture::{ClientFormat, RawImage2d, TextureCreationError, UncompressedFloatFormat};
+use crate::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+use crate::vertex::{BufferCreationError, VertexBuffer};
+use crate::{Blend, CapabilitiesSource, DrawError, DrawParameters, Surface};
+
+/// Width and height of the atlas of a new `FontTexture`, in texels.
+const INITIAL_ATLAS_SIZE: u32 = 256;
+
+#[derive(Copy, Clone, Debug)]
+struct TextVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+}
+
+implement_vertex!(TextVertex, position, tex_coords);
+
+/// Error that can happen when creating a `FontTexture`.
+#[derive(Debug)]
+pub enum FontCreationError {
+    /// The data isn't a valid TrueType or OpenType font.
+    InvalidFont(InvalidFont),
+    /// Error while creating the glyph atlas.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for FontCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontCreationError::InvalidFont(err) => write!(fmt, "{}", err),
+            FontCreationError::TextureCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for FontCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FontCreationError::InvalidFont(err) => Some(err),
+            FontCreationError::TextureCreationError(err) => Some(err),
+        }
+    }
+}
+
+impl From<InvalidFont> for FontCreationError {
+    #[inline]
+    fn from(err: InvalidFont) -> FontCreationError {
+        FontCreationError::InvalidFont(err)
+    }
+}
+
+impl From<TextureCreationError> for FontCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> FontCreationError {
+        FontCreationError::TextureCreationError(err)
+    }
+}
+
+/// Error that can happen when drawing text.
+#[derive(Debug)]
+pub enum DrawTextError {
+    /// The glyph atlas has reached the maximum texture size and can't hold all the glyphs.
+    AtlasFull,
+    /// Error while growing the glyph atlas.
+    TextureCreationError(TextureCreationError),
+    /// Error while creating the vertex buffer that holds the glyphs.
+    BufferCreationError(BufferCreationError),
+    /// Error while drawing the glyphs.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for DrawTextError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawTextError::AtlasFull => {
+                write!(fmt, "The glyph atlas has reached the maximum texture size")
+            },
+            DrawTextError::TextureCreationError(err) => write!(fmt, "{}", err),
+            DrawTextError::BufferCreationError(err) => write!(fmt, "{}", err),
+            DrawTextError::DrawError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for DrawTextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DrawTextError::AtlasFull => None,
+            DrawTextError::TextureCreationError(err) => Some(err),
+            DrawTextError::BufferCreationError(err) => Some(err),
+            DrawTextError::DrawError(err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for DrawTextError {
+    #[inline]
+    fn from(err: TextureCreationError) -> DrawTextError {
+        DrawTextError::TextureCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for DrawTextError {
+    #[inline]
+    fn from(err: BufferCreationError) -> DrawTextError {
+        DrawTextError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for DrawTextError {
+    #[inline]
+    fn from(err: DrawError) -> DrawTextError {
+        DrawTextError::DrawError(err)
+    }
+}
+
+/// Contains the program used to draw text, and the vertex buffer that holds the glyphs.
+///
+/// A single `TextSystem` can draw text with any number of fonts.
+pub struct TextSystem {
+    context: Rc<Context>,
+    program: Program,
+    buffer: RefCell<Option<VertexBuffer<TextVertex>>>,
+}
+
+impl TextSystem {
+    /// Builds a new `TextSystem` and compiles its program.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<TextSystem, ProgramChooserCreationError>
+        where F: Facade
+    {
+        let program = program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+                    in vec2 position;
+                    in vec2 tex_coords;
+                    out vec2 v_tex_coords;
+
+                    void main() {
+                        gl_Position = matrix * vec4(position, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D tex;
+                    uniform vec4 color;
+                    in vec2 v_tex_coords;
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = vec4(color.rgb, color.a * texture(tex, v_tex_coords).r);
+                    }
+                "
+            },
+
+            110 => {
+                vertex: "
+                    #version 110
+
+                    uniform mat4 matrix;
+                    attribute vec2 position;
+                    attribute vec2 tex_coords;
+                    varying vec2 v_tex_coords;
+
+                    void main() {
+                        gl_Position = matrix * vec4(position, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                    }
+                ",
+                fragment: "
+                    #version 110
+
+                    uniform sampler2D tex;
+                    uniform vec4 color;
+                    varying vec2 v_tex_coords;
+
+                    void main() {
+                        gl_FragColor = vec4(color.rgb, color.a * texture2D(tex, v_tex_coords).r);
+                    }
+                "
+            },
+
+            100 es => {
+                vertex: "
+                    #version 100
+
+                    uniform highp mat4 matrix;
+                    attribute highp vec2 position;
+                    attribute mediump vec2 tex_coords;
+                    varying mediump vec2 v_tex_coords;
+
+                    void main() {
+                        gl_Position = matrix * vec4(position, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    uniform sampler2D tex;
+                    uniform lowp vec4 color;
+                    varying mediump vec2 v_tex_coords;
+
+                    void main() {
+                        gl_FragColor = vec4(color.rgb,
+                                            color.a * texture2D(tex, v_tex_coords).r);
+                    }
+                "
+            },
+        )?;
+
+        Ok(TextSystem {
+            context: facade.get_context().clone(),
+            program,
+            buffer: RefCell::new(None),
+        })
+    }
+}
+
+/// A font at a given size, and the atlas that contains its rasterized glyphs.
+pub struct FontTexture {
+    context: Rc<Context>,
+    font: FontArc,
+    scale: PxScale,
+    cache: RefCell<GlyphCache>,
+}
+
+/// The glyphs of a `FontTexture` that have already been rasterized.
+struct GlyphCache {
+    atlas: TextureAtlas,
+    /// `None` for the glyphs that have no outline, such as spaces.
+    glyphs: HashMap<GlyphId, Option<CachedGlyph>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct CachedGlyph {
+    /// Region of the atlas that contains the glyph, surrounded by a border of one empty texel.
+    region: AtlasRegion,
+    /// Position of the top-left corner of the glyph relative to the pen, in pixels, Y down.
+    offset: [f32; 2],
+}
+
+/// A glyph rasterized in memory, with a border of one empty texel. The rows are stored from
+/// bottom to top, like in a texture.
+struct Bitmap {
+    width: u32,
+    height: u32,
+    offset: [f32; 2],
+    pixels: Vec<u8>,
+}
+
+impl FontTexture {
+    /// Builds a new `FontTexture` from the content of a TrueType or OpenType file.
+    ///
+    /// `size` is the height of a line of text in pixels, from the lowest descender to the
+    /// highest ascender.
+    pub fn new<F: ?Sized>(facade: &F, data: Vec<u8>, size: f32)
+                          -> Result<FontTexture, FontCreationError> where F: Facade
+    {
+        let font = FontArc::try_from_vec(data)?;
+        Ok(FontTexture::from_font(facade, font, size)?)
+    }
+
+    /// Builds a new `FontTexture` from a font that has already been loaded.
+    pub fn from_font<F: ?Sized>(facade: &F, font: FontArc, size: f32)
+                                -> Result<FontTexture, TextureCreationError> where F: Facade
+    {
+        let atlas = TextureAtlas::new(facade, UncompressedFloatFormat::U8, INITIAL_ATLAS_SIZE,
+                                      INITIAL_ATLAS_SIZE)?;
+
+        Ok(FontTexture {
+            context: facade.get_context().clone(),
+            font,
+            scale: PxScale::from(size),
+            cache: RefCell::new(GlyphCache {
+                atlas,
+                glyphs: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Returns the font.
+    #[inline]
+    pub fn get_font(&self) -> &FontArc {
+        &self.font
+    }
+
+    /// Returns the distance between the baselines of two lines of text, in pixels.
+    #[inline]
+    pub fn get_line_height(&self) -> f32 {
+        let font = self.font.as_scaled(self.scale);
+        (font.height() + font.line_gap()).round()
+    }
+
+    /// Returns the width and the height of a text, in pixels.
+    pub fn measure(&self, text: &str) -> (f32, f32) {
+        let [width, height] = self.layout(text, |_, _| ());
+        (width, height)
+    }
+
+    /// Returns the dimensions of the glyph atlas, in texels.
+    #[inline]
+    pub fn get_atlas_dimensions(&self) -> (u32, u32) {
+        self.cache.borrow().atlas.get_allocator().dimensions()
+    }
+
+    /// Calls `glyph` with each glyph of the text and the position of the pen, relative to the
+    /// top-left corner of the text. Returns the dimensions of the text.
+    fn layout<G>(&self, text: &str, mut glyph: G) -> [f32; 2] where G: FnMut(GlyphId, [f32; 2]) {
+        let font = self.font.as_scaled(self.scale);
+        let line_height = self.get_line_height();
+
+        let mut pen = [0.0, font.ascent().round()];
+        let mut width = 0.0f32;
+        let mut previous = None;
+
+        for c in text.chars() {
+            if c == '\n' {
+                width = width.max(pen[0]);
+                pen = [0.0, pen[1] + line_height];
+                previous = None;
+                continue;
+            }
+
+            if c.is_control() {
+                continue;
+            }
+
+            let id = font.glyph_id(c);
+            if let Some(previous) = previous {
+                pen[0] += font.kern(previous, id);
+            }
+
+            glyph(id, [pen[0].round(), pen[1]]);
+            pen[0] += font.h_advance(id);
+            previous = Some(id);
+        }
+
+        [width.max(pen[0]).ceil(), (pen[1] - font.descent()).ceil()]
+    }
+
+    /// Rasterizes a glyph, or returns `None` if it has no outline.
+    fn rasterize(&self, id: GlyphId) -> Option<Bitmap> {
+        let outline = self.font.outline_glyph(id.with_scale(self.scale))?;
+        let bounds = outline.px_bounds();
+        let width = bounds.width() as u32;
+        let height = bounds.height() as u32;
+
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let stride = width + 2;
+        let mut pixels = vec![0; (stride * (height + 2)) as usize];
+        outline.draw(|x, y, coverage| {
+            // `y` goes down while the rows go up, and the first row is the border
+            let row = height - y;
+            pixels[(row * stride + x + 1) as usize] = (coverage * 255.0).round() as u8;
+        });
+
+        Some(Bitmap {
+            width: stride,
+            height: height + 2,
+            offset: [bounds.min.x, bounds.min.y],
+            pixels,
+        })
+    }
+
+    /// Makes sure that a glyph is in the atlas, growing the atlas if necessary.
+    fn cache_glyph(&self, cache: &mut GlyphCache, id: GlyphId) -> Result<(), DrawTextError> {
+        if cache.glyphs.contains_key(&id) {
+            return Ok(());
+        }
+
+        let bitmap = match self.rasterize(id) {
+            Some(bitmap) => bitmap,
+            None => {
+                cache.glyphs.insert(id, None);
+                return Ok(());
+            }
+        };
+
+        let region = loop {
+            if let Some(region) = cache.atlas.allocate(bitmap.width, bitmap.height) {
+                break region;
+            }
+
+            self.grow_atlas(cache)?;
+        };
+
+        cache.atlas.write(&region, RawImage2d {
+            data: Cow::Owned(bitmap.pixels),
+            width: bitmap.width,
+            height: bitmap.height,
+            format: ClientFormat::U8,
+        });
+
+        cache.glyphs.insert(id, Some(CachedGlyph { region, offset: bitmap.offset }));
+        Ok(())
+    }
+
+    /// Replaces the atlas with one twice as large, and rasterizes the glyphs again.
+    fn grow_atlas(&self, cache: &mut GlyphCache) -> Result<(), DrawTextError> {
+        let max = self.context.get_capabilities().max_texture_size as u32;
+        let (width, height) = cache.atlas.get_allocator().dimensions();
+
+        let (width, height) = if width <= height && width < max {
+            (width * 2, height)
+        } else if height < max {
+            (width, height * 2)
+        } else {
+            return Err(DrawTextError::AtlasFull);
+        };
+
+        cache.atlas = TextureAtlas::new(&self.context, UncompressedFloatFormat::U8,
+                                        width.min(max), height.min(max))?;
+
+        let ids = cache.glyphs.iter().filter(|(_, glyph)| glyph.is_some()).map(|(id, _)| *id)
+                                     .collect::<Vec<_>>();
+        cache.glyphs.retain(|_, glyph| glyph.is_none());
+
+        for id in ids {
+            self.cache_glyph(cache, id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draws a text on a surface.
+///
+/// `position` is the position of the top-left corner of the text, in pixels from the top-left
+/// corner of the surface. It is rounded to whole pixels. Lines are separated with `\n`.
+pub fn draw_text<S>(surface: &mut S, system: &TextSystem, font: &FontTexture, text: &str,
+                    position: [f32; 2], color: [f32; 4]) -> Result<(), DrawTextError>
+    where S: Surface
+{
+    let mut glyphs = Vec::with_capacity(text.len());
+    font.layout(text, |id, pen| glyphs.push((id, pen)));
+
+    // all the glyphs must be cached before computing the texture coordinates, as caching one
+    // of them can move the others to a larger atlas
+    let mut cache = font.cache.borrow_mut();
+    for &(id, _) in glyphs.iter() {
+        font.cache_glyph(&mut cache, id)?;
+    }
+
+    let origin = [position[0].round(), position[1].round()];
+    let mut vertices = Vec::with_capacity(glyphs.len() * 6);
+
+    for (id, pen) in glyphs {
+        let glyph = match cache.glyphs[&id] {
+            Some(glyph) => glyph,
+            None => continue,
+        };
+
+        // the quad covers the glyph without its border
+        let region = AtlasRegion {
+            left: glyph.region.left + 1,
+            bottom: glyph.region.bottom + 1,
+            width: glyph.region.width - 2,
+            height: glyph.region.height - 2,
+        };
+        let ([u0, v0], [u1, v1]) = cache.atlas.get_tex_coords(&region);
+
+        let left = origin[0] + pen[0] + glyph.offset[0];
+        let top = origin[1] + pen[1] + glyph.offset[1];
+        let right = left + region.width as f32;
+        let bottom = top + region.height as f32;
+
+        vertices.extend_from_slice(&[
+            TextVertex { position: [left, top], tex_coords: [u0, v1] },
+            TextVertex { position: [left, bottom], tex_coords: [u0, v0] },
+            TextVertex { position: [right, top], tex_coords: [u1, v1] },
+            TextVertex { position: [right, top], tex_coords: [u1, v1] },
+            TextVertex { position: [left, bottom], tex_coords: [u0, v0] },
+            TextVertex { position: [right, bottom], tex_coords: [u1, v0] },
+        ]);
+    }
+
+    if vertices.is_empty() {
+        return Ok(());
+    }
+
+    let mut buffer = system.buffer.borrow_mut();
+    let len = vertices.len();
+    if buffer.as_ref().map_or(true, |buffer| buffer.len() < len) {
+        *buffer = Some(VertexBuffer::empty_dynamic(&system.context, len.next_power_of_two())?);
+    }
+
+    let slice = buffer.as_ref().unwrap().slice(0 .. len).unwrap();
+    slice.write(&vertices);
+
+    // maps pixels, with the origin at the top-left corner, to clip space
+    let (width, height) = surface.get_dimensions();
+    let matrix = [
+        [2.0 / width as f32, 0.0, 0.0, 0.0],
+        [0.0, -2.0 / height as f32, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [-1.0, 1.0, 0.0, 1.0f32],
+    ];
+
+    let uniforms = uniform! {
+        matrix: matrix,
+        tex: cache.atlas.get_texture().sampled()
+                   .magnify_filter(MagnifySamplerFilter::Nearest)
+                   .minify_filter(MinifySamplerFilter::Nearest),
+        color: color,
+    };
+
+    let params = DrawParameters {
+        blend: Blend::alpha_blending(),
+        .. Default::default()
+    };
+
+    surface.draw(slice, NoIndices(PrimitiveType::TrianglesList), &system.program, &uniforms,
+                 &params)?;
+    Ok(())
+}
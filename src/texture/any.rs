@@ -1,6 +1,7 @@
 
 use crate::gl;
 use crate::GlObject;
+use crate::Recreatable;
 
 use crate::backend::Facade;
 use crate::memory_object::MemoryObject;
@@ -13,11 +14,16 @@ use crate::TextureExt;
 use crate::TextureMipmapExt;
 use crate::version::Api;
 use crate::Rect;
+use crate::BlitError;
+use crate::BlitTarget;
+use crate::ToGlEnum;
+use crate::uniforms::MagnifySamplerFilter;
 
 use crate::image_format::{self, TextureFormatRequest, ClientFormatAny};
 use crate::texture::Texture2dDataSink;
 use crate::texture::TextureKind;
 use crate::texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
+use crate::texture::ty_support;
 use crate::texture::{get_format, InternalFormat, GetFormatError};
 use crate::texture::pixel::PixelValue;
 use crate::texture::pixel_buffer::PixelBuffer;
@@ -37,10 +43,14 @@ use std::borrow::Cow;
 use std::cell::Cell;
 use std::rc::Rc;
 use std::ops::Range;
+use std::marker::PhantomData;
 use std::ffi::c_void;
 
-use crate::ops;
+use smallvec::SmallVec;
+
+use crate::ops::{self, ReadError, ReadOptions};
 use crate::fbo;
+use crate::sync::{self, LinearSyncFence};
 
 /// Type of a texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -83,6 +93,9 @@ pub struct TextureAny {
 
     /// ID of the draw call where the texture was last written as a texture unit.
     latest_shader_write: Cell<u64>,
+
+    /// True if `GL_DEPTH_STENCIL_TEXTURE_MODE` has been set to `GL_STENCIL_INDEX`.
+    stencil_texture_mode: Cell<bool>,
 }
 
 fn extract_dimensions(ty: Dimensions)
@@ -116,9 +129,21 @@ fn get_bind_point(ty: Dimensions) -> gl::types::GLenum {
     }
 }
 
-unsafe fn generate_mipmaps(ctxt: &CommandContext<'_>,
+/// Returns true if textures can be created and modified with `ARB_direct_state_access`, without
+/// binding them.
+#[inline]
+fn is_dsa_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access
+}
+
+/// Generates the mipmaps of a texture.
+///
+/// Unless `is_dsa_supported` returns true, the texture must be bound to `bind_point`.
+unsafe fn generate_mipmaps(ctxt: &CommandContext<'_>, id: gl::types::GLuint,
                            bind_point: gl::types::GLenum) {
-    if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+    if is_dsa_supported(ctxt) {
+        ctxt.gl.GenerateTextureMipmap(id);
+    } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
        ctxt.version >= &Version(Api::GlEs, 2, 0)
     {
         ctxt.gl.GenerateMipmap(bind_point);
@@ -129,6 +154,40 @@ unsafe fn generate_mipmaps(ctxt: &CommandContext<'_>,
     }
 }
 
+/// Allocates the levels after the first one of a compressed 2D texture or 2D texture array that
+/// was created without texture storage, so that they can later be written to.
+///
+/// The texture must be bound to `bind_point`.
+#[allow(clippy::too_many_arguments)]
+unsafe fn allocate_compressed_mipmaps(ctxt: &CommandContext<'_>, bind_point: gl::types::GLenum,
+                                      format: ClientFormatAny,
+                                      internal_format: gl::types::GLenum,
+                                      levels: gl::types::GLsizei, width: u32, height: u32,
+                                      array_size: Option<u32>)
+{
+    for level in 1 .. levels {
+        let level_width = cmp::max(1, width >> level);
+        let level_height = cmp::max(1, height >> level);
+
+        // `glCompressedTexImage*` doesn't always accept a null pointer, so we upload zeroes
+        let zeroes = vec![0u8; format.get_buffer_size(level_width, Some(level_height),
+                                                      None, array_size)];
+
+        if let Some(array_size) = array_size {
+            ctxt.gl.CompressedTexImage3D(bind_point, level, internal_format,
+                                         level_width as gl::types::GLsizei,
+                                         level_height as gl::types::GLsizei,
+                                         array_size as gl::types::GLsizei, 0,
+                                         zeroes.len() as i32, zeroes.as_ptr() as *const c_void);
+        } else {
+            ctxt.gl.CompressedTexImage2D(bind_point, level, internal_format,
+                                         level_width as gl::types::GLsizei,
+                                         level_height as gl::types::GLsizei, 0,
+                                         zeroes.len() as i32, zeroes.as_ptr() as *const c_void);
+        }
+    }
+}
+
 /// Builds a new texture.
 pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
                              data: Option<(ClientFormatAny, Cow<'a, [P]>)>,
@@ -140,6 +199,11 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
     let (width, height, depth, array_size, samples) = extract_dimensions(ty);
     let (is_client_compressed, data_bufsize) = match data {
         Some((client_format, _)) => {
+            // compressed data can only be uploaded to 2D textures and 2D texture arrays
+            if client_format.is_compressed() && (height.is_none() || depth.is_some()) {
+                return Err(TextureCreationError::DimensionsNotSupported);
+            }
+
             (client_format.is_compressed(),
              client_format.get_buffer_size(width, height, depth, array_size))
         },
@@ -178,6 +242,8 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         (&None, TextureFormatRequest::Specific(TextureFormat::DepthFormat(_))) => (gl::DEPTH_COMPONENT, gl::FLOAT),
         (&None, TextureFormatRequest::AnyDepthStencil) => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
         (&None, TextureFormatRequest::Specific(TextureFormat::DepthStencilFormat(_))) => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
+        (&None, TextureFormatRequest::AnyStencil) => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
+        (&None, TextureFormatRequest::Specific(TextureFormat::StencilFormat(_))) => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
         (&None, _) => (gl::RGBA, gl::UNSIGNED_BYTE),
     };
 
@@ -199,6 +265,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
     let id = unsafe {
         let has_mipmaps = texture_levels > 1;
         let data = data;
+        let client_format_any = data.as_ref().map(|&(format, _)| format);
         let data_raw = if let Some((_, ref data)) = data {
             data.as_ptr() as *const c_void
         } else {
@@ -212,239 +279,262 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
 
         BufferAny::unbind_pixel_unpack(&mut ctxt);
 
-        let mut id: gl::types::GLuint = 0;
-        ctxt.gl.GenTextures(1, &mut id);
+        if storage_internal_format.is_some() && is_dsa_supported(&ctxt) {
+            new_texture_dsa(&mut ctxt, ty, storage_internal_format.unwrap() as gl::types::GLenum,
+                            texture_levels, data_raw, is_client_compressed,
+                            teximg_internal_format as gl::types::GLenum, data_bufsize,
+                            client_format, client_type, filtering, mipmap_filtering,
+                            should_generate_mipmaps)
 
-        {
-            ctxt.gl.BindTexture(bind_point, id);
-            let act = ctxt.state.active_texture as usize;
-            ctxt.state.texture_units[act].texture = id;
-        }
-
-        if !is_multisampled {
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, filtering as i32);
-        }
-
-        match ty {
-            Dimensions::Texture1d { .. } => (),
-            Dimensions::Texture2dMultisample { .. } => (),
-            Dimensions::Texture2dMultisampleArray { .. } => (),
-            _ => {
-                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-            },
-        };
-
-        match ty {
-            Dimensions::Texture1d { .. } => (),
-            Dimensions::Texture2d { .. } => (),
-            Dimensions::Texture2dMultisample { .. } => (),
-            _ => {
-                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_R, gl::REPEAT as i32);
-            },
-        };
-
-        if has_mipmaps {
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER,
-                                  mipmap_filtering as i32);
-        } else if !is_multisampled {
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER,
-                                  filtering as i32);
-        }
+        } else {
+            let mut id: gl::types::GLuint = 0;
+            ctxt.gl.GenTextures(1, &mut id);
 
-        if !has_mipmaps && (ctxt.version >= &Version(Api::Gl, 1, 2) ||
-                            ctxt.version >= &Version(Api::GlEs, 3, 0))
-        {
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_BASE_LEVEL, 0);
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL, 0);
-        }
+            {
+                ctxt.gl.BindTexture(bind_point, id);
+                let act = ctxt.state.active_texture as usize;
+                ctxt.state.texture_units[act].texture = id;
+            }
 
-        if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY ||
-           bind_point == gl::TEXTURE_CUBE_MAP_ARRAY
-        {
-            let mut data_raw = data_raw;
+            if !is_multisampled {
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, filtering as i32);
+            }
 
-            let width = match width as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
+            match ty {
+                Dimensions::Texture1d { .. } => (),
+                Dimensions::Texture2dMultisample { .. } => (),
+                Dimensions::Texture2dMultisampleArray { .. } => (),
+                _ => {
+                    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+                },
             };
 
-            let height = match height.unwrap() as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
+            match ty {
+                Dimensions::Texture1d { .. } => (),
+                Dimensions::Texture2d { .. } => (),
+                Dimensions::Texture2dMultisample { .. } => (),
+                _ => {
+                    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_R, gl::REPEAT as i32);
+                },
             };
 
-            let depth = match depth.or(array_size).unwrap() as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
-            };
+            if has_mipmaps {
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER,
+                                      mipmap_filtering as i32);
+            } else if !is_multisampled {
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER,
+                                      filtering as i32);
+            }
 
-            if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
-                ctxt.gl.TexStorage3D(bind_point, texture_levels,
-                                     storage_internal_format.unwrap() as gl::types::GLenum,
-                                     width, height, depth);
+            if !has_mipmaps && (ctxt.version >= &Version(Api::Gl, 1, 2) ||
+                                ctxt.version >= &Version(Api::GlEs, 3, 0))
+            {
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_BASE_LEVEL, 0);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL, 0);
+            }
 
-                if !data_raw.is_null() {
-                    if is_client_compressed {
-                        ctxt.gl.CompressedTexSubImage3D(bind_point, 0, 0, 0, 0, width, height, depth,
-                                                         teximg_internal_format as u32,
-                                                         data_bufsize as i32, data_raw);
-                    } else {
-                        ctxt.gl.TexSubImage3D(bind_point, 0, 0, 0, 0, width, height, depth,
-                                              client_format, client_type, data_raw);
+            if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY ||
+               bind_point == gl::TEXTURE_CUBE_MAP_ARRAY
+            {
+                let mut data_raw = data_raw;
+
+                let width = match width as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                let height = match height.unwrap() as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                let depth = match depth.or(array_size).unwrap() as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
+                    ctxt.gl.TexStorage3D(bind_point, texture_levels,
+                                         storage_internal_format.unwrap() as gl::types::GLenum,
+                                         width, height, depth);
+
+                    if !data_raw.is_null() {
+                        if is_client_compressed {
+                            ctxt.gl.CompressedTexSubImage3D(bind_point, 0, 0, 0, 0, width, height, depth,
+                                                             teximg_internal_format as u32,
+                                                             data_bufsize as i32, data_raw);
+                        } else {
+                            ctxt.gl.TexSubImage3D(bind_point, 0, 0, 0, 0, width, height, depth,
+                                                  client_format, client_type, data_raw);
+                        }
                     }
-                }
 
-            } else if is_client_compressed && !data_raw.is_null() {
-                ctxt.gl.CompressedTexImage3D(bind_point, 0, teximg_internal_format as u32,
-                                   width, height, depth, 0, data_bufsize as i32, data_raw);
-            } else {
-                ctxt.gl.TexImage3D(bind_point, 0, teximg_internal_format as i32, width,
-                                   height, depth, 0, client_format as u32, client_type,
-                                   data_raw);
-            }
+                } else if is_client_compressed && !data_raw.is_null() {
+                    ctxt.gl.CompressedTexImage3D(bind_point, 0, teximg_internal_format as u32,
+                                       width, height, depth, 0, data_bufsize as i32, data_raw);
 
-        } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY ||
-                  bind_point == gl::TEXTURE_CUBE_MAP
-        {
-            let mut data_raw = data_raw;
-
-            let width = match width as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
-            };
+                    if bind_point == gl::TEXTURE_2D_ARRAY {
+                        allocate_compressed_mipmaps(&ctxt, bind_point, client_format_any.unwrap(),
+                                                    teximg_internal_format as gl::types::GLenum,
+                                                    texture_levels, width as u32, height as u32,
+                                                    Some(depth as u32));
+                    }
+                } else {
+                    ctxt.gl.TexImage3D(bind_point, 0, teximg_internal_format as i32, width,
+                                       height, depth, 0, client_format as u32, client_type,
+                                       data_raw);
+                }
 
-            let height = match height.or(array_size).unwrap() as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
-            };
+            } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY ||
+                      bind_point == gl::TEXTURE_CUBE_MAP
+            {
+                let mut data_raw = data_raw;
+
+                let width = match width as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                let height = match height.or(array_size).unwrap() as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
+                    ctxt.gl.TexStorage2D(bind_point, texture_levels,
+                                         storage_internal_format.unwrap() as gl::types::GLenum,
+                                         width, height);
+
+                    if !data_raw.is_null() {
+                        if is_client_compressed {
+                            ctxt.gl.CompressedTexSubImage2D(bind_point, 0, 0, 0, width, height,
+                                                             teximg_internal_format as u32,
+                                                             data_bufsize as i32, data_raw);
+                        } else {
+                            ctxt.gl.TexSubImage2D(bind_point, 0, 0, 0, width, height, client_format,
+                                                  client_type, data_raw);
+                        }
+                    }
 
-            if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
-                ctxt.gl.TexStorage2D(bind_point, texture_levels,
-                                     storage_internal_format.unwrap() as gl::types::GLenum,
-                                     width, height);
+                } else if is_client_compressed && !data_raw.is_null() {
+                    ctxt.gl.CompressedTexImage2D(bind_point, 0, teximg_internal_format as u32,
+                                       width, height, 0, data_bufsize as i32, data_raw);
 
-                if !data_raw.is_null() {
-                    if is_client_compressed {
-                        ctxt.gl.CompressedTexSubImage2D(bind_point, 0, 0, 0, width, height,
-                                                         teximg_internal_format as u32,
-                                                         data_bufsize as i32, data_raw);
-                    } else {
-                        ctxt.gl.TexSubImage2D(bind_point, 0, 0, 0, width, height, client_format,
-                                              client_type, data_raw);
+                    if bind_point == gl::TEXTURE_2D {
+                        allocate_compressed_mipmaps(&ctxt, bind_point, client_format_any.unwrap(),
+                                                    teximg_internal_format as gl::types::GLenum,
+                                                    texture_levels, width as u32, height as u32,
+                                                    None);
                     }
+                } else {
+                    ctxt.gl.TexImage2D(bind_point, 0, teximg_internal_format as i32, width,
+                                       height, 0, client_format as u32, client_type, data_raw);
                 }
 
-            } else if is_client_compressed && !data_raw.is_null() {
-                ctxt.gl.CompressedTexImage2D(bind_point, 0, teximg_internal_format as u32,
-                                   width, height, 0, data_bufsize as i32, data_raw);
-            } else {
-                ctxt.gl.TexImage2D(bind_point, 0, teximg_internal_format as i32, width,
-                                   height, 0, client_format as u32, client_type, data_raw);
-            }
+            } else if bind_point == gl::TEXTURE_2D_MULTISAMPLE {
+                assert!(data_raw.is_null());
 
-        } else if bind_point == gl::TEXTURE_2D_MULTISAMPLE {
-            assert!(data_raw.is_null());
+                let width = match width as gl::types::GLsizei {
+                    0 => 1,
+                    a => a
+                };
 
-            let width = match width as gl::types::GLsizei {
-                0 => 1,
-                a => a
-            };
+                let height = match height.unwrap() as gl::types::GLsizei {
+                    0 => 1,
+                    a => a
+                };
 
-            let height = match height.unwrap() as gl::types::GLsizei {
-                0 => 1,
-                a => a
-            };
+                if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
+                    ctxt.gl.TexStorage2DMultisample(gl::TEXTURE_2D_MULTISAMPLE,
+                                                    samples.unwrap() as gl::types::GLsizei,
+                                                    storage_internal_format.unwrap() as gl::types::GLenum,
+                                                    width, height, gl::TRUE);
 
-            if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
-                ctxt.gl.TexStorage2DMultisample(gl::TEXTURE_2D_MULTISAMPLE,
-                                                samples.unwrap() as gl::types::GLsizei,
-                                                storage_internal_format.unwrap() as gl::types::GLenum,
-                                                width, height, gl::TRUE);
+                } else if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_texture_multisample {
+                    ctxt.gl.TexImage2DMultisample(gl::TEXTURE_2D_MULTISAMPLE,
+                                                  samples.unwrap() as gl::types::GLsizei,
+                                                  teximg_internal_format as gl::types::GLenum,
+                                                  width, height, gl::TRUE);
 
-            } else if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_texture_multisample {
-                ctxt.gl.TexImage2DMultisample(gl::TEXTURE_2D_MULTISAMPLE,
-                                              samples.unwrap() as gl::types::GLsizei,
-                                              teximg_internal_format as gl::types::GLenum,
-                                              width, height, gl::TRUE);
-
-            } else {
-                unreachable!();
-            }
-
-        } else if bind_point == gl::TEXTURE_2D_MULTISAMPLE_ARRAY {
-            assert!(data_raw.is_null());
+                } else {
+                    unreachable!();
+                }
 
-            let width = match width as gl::types::GLsizei {
-                0 => 1,
-                a => a
-            };
+            } else if bind_point == gl::TEXTURE_2D_MULTISAMPLE_ARRAY {
+                assert!(data_raw.is_null());
+
+                let width = match width as gl::types::GLsizei {
+                    0 => 1,
+                    a => a
+                };
+
+                let height = match height.unwrap() as gl::types::GLsizei {
+                    0 => 1,
+                    a => a
+                };
+
+                if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
+                    ctxt.gl.TexStorage3DMultisample(gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
+                                                    samples.unwrap() as gl::types::GLsizei,
+                                                    storage_internal_format.unwrap() as gl::types::GLenum,
+                                                    width, height, array_size.unwrap() as gl::types::GLsizei,
+                                                    gl::TRUE);
+
+                } else if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_texture_multisample {
+                    ctxt.gl.TexImage3DMultisample(gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
+                                                  samples.unwrap() as gl::types::GLsizei,
+                                                  teximg_internal_format as gl::types::GLenum,
+                                                  width, height, array_size.unwrap() as gl::types::GLsizei,
+                                                  gl::TRUE);
 
-            let height = match height.unwrap() as gl::types::GLsizei {
-                0 => 1,
-                a => a
-            };
+                } else {
+                    unreachable!();
+                }
 
-            if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
-                ctxt.gl.TexStorage3DMultisample(gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
-                                                samples.unwrap() as gl::types::GLsizei,
-                                                storage_internal_format.unwrap() as gl::types::GLenum,
-                                                width, height, array_size.unwrap() as gl::types::GLsizei,
-                                                gl::TRUE);
+            } else if bind_point == gl::TEXTURE_1D {
+                let mut data_raw = data_raw;
+
+                let width = match width as gl::types::GLsizei {
+                    0 => { data_raw = ptr::null(); 1 },
+                    a => a
+                };
+
+                if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
+                    ctxt.gl.TexStorage1D(bind_point, texture_levels,
+                                         storage_internal_format.unwrap() as gl::types::GLenum,
+                                         width);
+
+                    if !data_raw.is_null() {
+                        if is_client_compressed {
+                            ctxt.gl.CompressedTexSubImage1D(bind_point, 0, 0, width,
+                                                             teximg_internal_format as u32,
+                                                             data_bufsize as i32, data_raw);
+                        } else {
+                            ctxt.gl.TexSubImage1D(bind_point, 0, 0, width, client_format,
+                                                  client_type, data_raw);
+                        }
+                    }
 
-            } else if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_texture_multisample {
-                ctxt.gl.TexImage3DMultisample(gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
-                                              samples.unwrap() as gl::types::GLsizei,
-                                              teximg_internal_format as gl::types::GLenum,
-                                              width, height, array_size.unwrap() as gl::types::GLsizei,
-                                              gl::TRUE);
+                } else if is_client_compressed && !data_raw.is_null() {
+                    ctxt.gl.CompressedTexImage1D(bind_point, 0, teximg_internal_format as u32,
+                                       width, 0, data_bufsize as i32, data_raw);
+                } else {
+                    ctxt.gl.TexImage1D(bind_point, 0, teximg_internal_format as i32, width,
+                                       0, client_format as u32, client_type, data_raw);
+                }
 
             } else {
                 unreachable!();
             }
 
-        } else if bind_point == gl::TEXTURE_1D {
-            let mut data_raw = data_raw;
-
-            let width = match width as gl::types::GLsizei {
-                0 => { data_raw = ptr::null(); 1 },
-                a => a
-            };
-
-            if storage_internal_format.is_some() && (ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.extensions.gl_arb_texture_storage) {
-                ctxt.gl.TexStorage1D(bind_point, texture_levels,
-                                     storage_internal_format.unwrap() as gl::types::GLenum,
-                                     width);
-
-                if !data_raw.is_null() {
-                    if is_client_compressed {
-                        ctxt.gl.CompressedTexSubImage1D(bind_point, 0, 0, width,
-                                                         teximg_internal_format as u32,
-                                                         data_bufsize as i32, data_raw);
-                    } else {
-                        ctxt.gl.TexSubImage1D(bind_point, 0, 0, width, client_format,
-                                              client_type, data_raw);
-                    }
-                }
-
-            } else if is_client_compressed && !data_raw.is_null() {
-                ctxt.gl.CompressedTexImage1D(bind_point, 0, teximg_internal_format as u32,
-                                   width, 0, data_bufsize as i32, data_raw);
-            } else {
-                ctxt.gl.TexImage1D(bind_point, 0, teximg_internal_format as i32, width,
-                                   0, client_format as u32, client_type, data_raw);
+            // only generate mipmaps for color textures
+            if should_generate_mipmaps {
+                generate_mipmaps(&ctxt, id, bind_point);
             }
 
-        } else {
-            unreachable!();
-        }
-
-        // only generate mipmaps for color textures
-        if should_generate_mipmaps {
-            generate_mipmaps(&ctxt, bind_point);
+            id
         }
-
-        id
     };
 
     Ok(TextureAny {
@@ -454,13 +544,172 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         actual_format: Cell::new(None),
         ty,
         levels: texture_levels as u32,
-        generate_mipmaps: should_generate_mipmaps,
+        generate_mipmaps: mipmaps.should_regenerate(),
         owned: true,
         memory: None,
         latest_shader_write: Cell::new(0),
+        stencil_texture_mode: Cell::new(false),
     })
 }
 
+/// Creates the storage of a new texture with `ARB_direct_state_access`, uploads `data` if it is
+/// not null, and returns the id of the texture. Contrary to the regular path of `new_texture`,
+/// the texture is never bound.
+#[allow(clippy::too_many_arguments)]
+unsafe fn new_texture_dsa(ctxt: &mut CommandContext<'_>, ty: Dimensions,
+                          storage_internal_format: gl::types::GLenum,
+                          texture_levels: gl::types::GLsizei, data_raw: *const c_void,
+                          is_client_compressed: bool,
+                          teximg_internal_format: gl::types::GLenum, data_bufsize: usize,
+                          client_format: gl::types::GLenum, client_type: gl::types::GLenum,
+                          filtering: gl::types::GLenum, mipmap_filtering: gl::types::GLenum,
+                          should_generate_mipmaps: bool) -> gl::types::GLuint
+{
+    let (width, height, depth, array_size, samples) = extract_dimensions(ty);
+    let bind_point = get_bind_point(ty);
+    let is_multisampled = samples.is_some();
+
+    let mut id: gl::types::GLuint = 0;
+    ctxt.gl.CreateTextures(bind_point, 1, &mut id);
+
+    if !is_multisampled {
+        ctxt.gl.TextureParameteri(id, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+        ctxt.gl.TextureParameteri(id, gl::TEXTURE_MAG_FILTER, filtering as i32);
+
+        if !matches!(ty, Dimensions::Texture1d { .. }) {
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+        }
+
+        if !matches!(ty, Dimensions::Texture1d { .. } | Dimensions::Texture2d { .. }) {
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_WRAP_R, gl::REPEAT as i32);
+        }
+
+        if texture_levels > 1 {
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_MIN_FILTER, mipmap_filtering as i32);
+        } else {
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_MIN_FILTER, filtering as i32);
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_BASE_LEVEL, 0);
+            ctxt.gl.TextureParameteri(id, gl::TEXTURE_MAX_LEVEL, 0);
+        }
+    }
+
+    // a dimension of `0` is replaced with `1` and the data is ignored, like in `new_texture`
+    let mut data_raw = data_raw;
+    let mut dimension = |value: u32| match value as gl::types::GLsizei {
+        0 => { data_raw = ptr::null(); 1 },
+        a => a
+    };
+
+    match bind_point {
+        gl::TEXTURE_3D | gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP_ARRAY => {
+            let width = dimension(width);
+            let height = dimension(height.unwrap());
+            let depth = dimension(depth.or(array_size).unwrap());
+
+            ctxt.gl.TextureStorage3D(id, texture_levels, storage_internal_format,
+                                     width, height, depth);
+
+            if !data_raw.is_null() {
+                if is_client_compressed {
+                    ctxt.gl.CompressedTextureSubImage3D(id, 0, 0, 0, 0, width, height, depth,
+                                                        teximg_internal_format,
+                                                        data_bufsize as i32, data_raw);
+                } else {
+                    ctxt.gl.TextureSubImage3D(id, 0, 0, 0, 0, width, height, depth,
+                                              client_format, client_type, data_raw);
+                }
+            }
+        },
+
+        gl::TEXTURE_2D | gl::TEXTURE_1D_ARRAY | gl::TEXTURE_CUBE_MAP => {
+            let width = dimension(width);
+            let height = dimension(height.or(array_size).unwrap());
+
+            ctxt.gl.TextureStorage2D(id, texture_levels, storage_internal_format, width, height);
+
+            if !data_raw.is_null() {
+                if is_client_compressed {
+                    ctxt.gl.CompressedTextureSubImage2D(id, 0, 0, 0, width, height,
+                                                        teximg_internal_format,
+                                                        data_bufsize as i32, data_raw);
+                } else {
+                    ctxt.gl.TextureSubImage2D(id, 0, 0, 0, width, height, client_format,
+                                              client_type, data_raw);
+                }
+            }
+        },
+
+        gl::TEXTURE_2D_MULTISAMPLE => {
+            let width = dimension(width);
+            let height = dimension(height.unwrap());
+
+            ctxt.gl.TextureStorage2DMultisample(id, samples.unwrap() as gl::types::GLsizei,
+                                                storage_internal_format, width, height,
+                                                gl::TRUE);
+        },
+
+        gl::TEXTURE_2D_MULTISAMPLE_ARRAY => {
+            let width = dimension(width);
+            let height = dimension(height.unwrap());
+
+            ctxt.gl.TextureStorage3DMultisample(id, samples.unwrap() as gl::types::GLsizei,
+                                                storage_internal_format, width, height,
+                                                array_size.unwrap() as gl::types::GLsizei,
+                                                gl::TRUE);
+        },
+
+        gl::TEXTURE_1D => {
+            let width = dimension(width);
+
+            ctxt.gl.TextureStorage1D(id, texture_levels, storage_internal_format, width);
+
+            if !data_raw.is_null() {
+                if is_client_compressed {
+                    ctxt.gl.CompressedTextureSubImage1D(id, 0, 0, width, teximg_internal_format,
+                                                        data_bufsize as i32, data_raw);
+                } else {
+                    ctxt.gl.TextureSubImage1D(id, 0, 0, width, client_format, client_type,
+                                              data_raw);
+                }
+            }
+        },
+
+        _ => unreachable!(),
+    }
+
+    if should_generate_mipmaps {
+        generate_mipmaps(ctxt, id, bind_point);
+    }
+
+    id
+}
+
+/// Builds a new texture with immutable storage for exactly `levels` mipmap levels.
+///
+/// The content of every level is undefined and no mipmap is generated.
+pub fn new_texture_storage<F: ?Sized>(facade: &F, format: TextureFormatRequest, levels: u32,
+                                      ty: Dimensions)
+                                      -> Result<TextureAny, TextureCreationError>
+                                      where F: Facade
+{
+    if !ty_support::is_immutable_storage_supported(facade.get_context()) {
+        return Err(TextureCreationError::ImmutableStorageNotSupported);
+    }
+
+    // `new_texture` falls back to mutable storage if the format can't be used with
+    // `glTexStorage*`, so we need to check it here
+    image_format::format_request_to_glenum(facade.get_context(), format,
+                                           image_format::RequestType::TexStorage)?;
+
+    let (width, height, depth, _, _) = extract_dimensions(ty);
+    let max_levels = MipmapsOption::EmptyMipmaps.num_levels(width, height, depth);
+    if levels == 0 || levels > max_levels {
+        return Err(TextureCreationError::MipmapLevelsOutOfRange);
+    }
+
+    new_texture::<_, u8>(facade, format, None, MipmapsOption::EmptyMipmapsMax(levels - 1), ty)
+}
+
 /// Builds a new texture reference from an existing, externally created OpenGL texture.
 /// If `owned` is true, this reference will take ownership of the texture and be responsible
 /// for cleaning it up. Otherwise, the texture must be cleaned up externally, but only
@@ -477,7 +726,7 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
     let should_generate_mipmaps = mipmaps.should_generate();
     if should_generate_mipmaps {
         let ctxt = facade.get_context().make_current();
-        generate_mipmaps(&ctxt, get_bind_point(ty));
+        generate_mipmaps(&ctxt, id, get_bind_point(ty));
     }
     TextureAny {
         context: facade.get_context().clone(),
@@ -486,10 +735,85 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
         actual_format: Cell::new(None),
         ty,
         levels: mipmap_levels,
-        generate_mipmaps: should_generate_mipmaps,
+        generate_mipmaps: mipmaps.should_regenerate(),
         owned,
         memory: None,
         latest_shader_write: Cell::new(0),
+        stencil_texture_mode: Cell::new(false),
+    }
+}
+
+/// A texture that has been detached from its context with `into_transfer`, waiting to be
+/// attached to another context of the same share group with `from_transfer`.
+///
+/// This object can be sent to another thread. If it is dropped instead of being attached to a
+/// context, the texture is destroyed the next time the context it was detached from is flushed
+/// or swaps its buffers.
+#[must_use]
+pub struct TextureTransfer<T> {
+    // `0` once the transfer has been consumed
+    id: gl::types::GLuint,
+    requested_format: TextureFormatRequest,
+    ty: Dimensions,
+    levels: u32,
+    generate_mipmaps: bool,
+    owned: bool,
+    fence: Option<LinearSyncFence>,
+    orphans: sync::TransferOrphans,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TextureTransfer<T> {
+    /// Changes the type of texture that this transfer produces.
+    #[inline]
+    pub(crate) fn cast<U>(mut self) -> TextureTransfer<U> {
+        TextureTransfer {
+            id: mem::replace(&mut self.id, 0),
+            requested_format: self.requested_format,
+            ty: self.ty,
+            levels: self.levels,
+            generate_mipmaps: self.generate_mipmaps,
+            owned: self.owned,
+            fence: self.fence.take(),
+            orphans: self.orphans.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Waits for the fence and builds the texture in the context of `facade`.
+    pub(crate) unsafe fn finish<F: ?Sized>(mut self, facade: &F) -> TextureAny where F: Facade {
+        let mut ctxt = facade.get_context().make_current();
+        sync::wait_transfer_fence(&mut ctxt, self.fence.take());
+
+        TextureAny {
+            context: facade.get_context().clone(),
+            id: mem::replace(&mut self.id, 0),
+            requested_format: self.requested_format,
+            actual_format: Cell::new(None),
+            ty: self.ty,
+            levels: self.levels,
+            generate_mipmaps: self.generate_mipmaps,
+            owned: self.owned,
+            memory: None,
+            latest_shader_write: Cell::new(0),
+            stencil_texture_mode: Cell::new(false),
+        }
+    }
+}
+
+impl<T> Drop for TextureTransfer<T> {
+    fn drop(&mut self) {
+        if self.id == 0 {
+            return;
+        }
+
+        let id = self.id;
+        let owned = self.owned;
+        self.orphans.push(self.fence.take(), move |ctxt| {
+            if owned {
+                unsafe { ctxt.gl.DeleteTextures(1, [id].as_ptr()); }
+            }
+        });
     }
 }
 
@@ -602,7 +926,7 @@ pub unsafe fn new_from_fd<F: Facade + ?Sized>(facade: &F,
         }
 
         if should_generate_mipmaps {
-            generate_mipmaps(&ctxt, bind_point);
+            generate_mipmaps(&ctxt, id, bind_point);
         }
 
         id
@@ -615,10 +939,11 @@ pub unsafe fn new_from_fd<F: Facade + ?Sized>(facade: &F,
         actual_format: Cell::new(None),
         ty,
         levels: mipmap_levels,
-        generate_mipmaps: should_generate_mipmaps,
+        generate_mipmaps: mipmaps.should_regenerate(),
         owned: false,
         memory: Some(memory),
         latest_shader_write: Cell::new(0),
+        stencil_texture_mode: Cell::new(false),
     })
 }
 
@@ -696,6 +1021,57 @@ impl TextureAny {
         }
     }
 
+    /// Sets whether sampling this depth-stencil texture returns the stencil component instead
+    /// of the depth component.
+    ///
+    /// Returns `false` if the backend doesn't support sampling the stencil component.
+    pub(crate) fn set_stencil_texture_mode(&self, ctxt: &mut CommandContext<'_>, stencil: bool)
+                                           -> bool
+    {
+        if self.stencil_texture_mode.get() == stencil {
+            return true;
+        }
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+             ctxt.extensions.gl_arb_stencil_texturing)
+        {
+            return false;
+        }
+
+        let mode = if stencil { gl::STENCIL_INDEX } else { gl::DEPTH_COMPONENT };
+
+        // the texture is incomplete when sampling the stencil component with linear filtering
+        let (min_filter, mag_filter) = if stencil {
+            (gl::NEAREST, gl::NEAREST)
+        } else if self.levels > 1 {
+            (gl::LINEAR_MIPMAP_LINEAR, gl::LINEAR)
+        } else {
+            (gl::LINEAR, gl::LINEAR)
+        };
+
+        let params = [
+            (gl::DEPTH_STENCIL_TEXTURE_MODE, mode),
+            (gl::TEXTURE_MIN_FILTER, min_filter),
+            (gl::TEXTURE_MAG_FILTER, mag_filter),
+        ];
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+                for &(name, value) in params.iter() {
+                    ctxt.gl.TextureParameteri(self.id, name, value as i32);
+                }
+            } else {
+                let bind_point = self.bind_to_current(ctxt);
+                for &(name, value) in params.iter() {
+                    ctxt.gl.TexParameteri(bind_point, name, value as i32);
+                }
+            }
+        }
+
+        self.stencil_texture_mode.set(stencil);
+        true
+    }
+
     /// Returns the dimensions of the texture.
     #[inline]
     pub fn dimensions(&self) -> Dimensions {
@@ -792,6 +1168,56 @@ impl TextureAny {
         self.levels
     }
 
+    /// Returns an estimate of the amount of video memory used by this texture, in bytes.
+    ///
+    /// The estimate takes into account the internal format, the dimensions, the number of
+    /// layers and samples, and all the mipmap levels. Compressed formats are counted in blocks
+    /// of 4x4 texels.
+    ///
+    /// If a specific format was not requested when the texture was created, the backend is
+    /// queried for the actual format. Keep in mind that the backend is free to add padding or to
+    /// use more memory than what is returned here.
+    pub fn estimated_size_bytes(&self) -> usize {
+        let format = match self.requested_format {
+            TextureFormatRequest::Specific(format) => Some(format),
+            _ => None,
+        };
+
+        // bytes per texel used when the format wasn't specified
+        let fallback_texel_size = match (format, self.requested_format) {
+            (Some(_), _) => 0,
+            (None, TextureFormatRequest::AnyCompressed) |
+            (None, TextureFormatRequest::AnyCompressedSrgb) => 1,
+            (None, _) => match self.get_internal_format() {
+                Ok(internal) => (internal.get_total_bits() + 7) / 8,
+                Err(_) => 4,
+            },
+        };
+
+        let layers = match self.ty {
+            Dimensions::Cubemap { .. } => 6,
+            _ => self.get_array_size().unwrap_or(1) as usize * match self.ty {
+                Dimensions::CubemapArray { .. } => 6,
+                _ => 1,
+            },
+        };
+
+        let samples = self.get_samples().unwrap_or(1) as usize;
+
+        (0 .. self.levels).filter_map(|level| self.mipmap(level)).map(|mipmap| {
+            let width = mipmap.get_width();
+            let height = mipmap.get_height().unwrap_or(1);
+            let depth = mipmap.get_depth().unwrap_or(1);
+
+            let image_size = match format {
+                Some(format) => format.get_image_size(width, height, depth),
+                None => fallback_texel_size * width as usize * height as usize * depth as usize,
+            };
+
+            image_size * layers * samples
+        }).sum()
+    }
+
     /// Returns a structure that represents the main mipmap level of the texture.
     #[inline]
     pub fn main_level(&self) -> TextureAnyMipmap<'_> {
@@ -817,12 +1243,138 @@ impl TextureAny {
         })
     }
 
-    /// Binds this texture and generates mipmaps.
-    #[inline]
+    /// Replaces the whole content of an image of a 2D texture, a 2D texture array or a cubemap.
+    ///
+    /// `layer` is the layer for arrays, the face in the order of `CubeLayer` for cubemaps,
+    /// and must be 0 for other textures. The size of `data` isn't checked.
+    #[cfg(feature = "loader")]
+    pub(crate) fn upload_image(&self, level: u32, layer: u32, format: ClientFormatAny,
+                               data: &[u8]) -> Result<(), TextureCreationError>
+    {
+        let mipmap = self.mipmap(level).unwrap();
+        let width = mipmap.width as gl::types::GLsizei;
+        let height = mipmap.height.unwrap_or(1) as gl::types::GLsizei;
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&self.context, format, self.requested_format,
+                                                  false)?;
+
+        let mut ctxt = self.context.make_current();
+        self.prepare_for_access(&mut ctxt, crate::TextureAccess::TextureUpdate);
+
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+            let bind_point = self.bind_to_current(&mut ctxt);
+            let level = level as gl::types::GLint;
+            let data_size = data.len() as gl::types::GLsizei;
+            let data = data.as_ptr() as *const c_void;
+
+            match bind_point {
+                gl::TEXTURE_2D | gl::TEXTURE_CUBE_MAP => {
+                    let target = if bind_point == gl::TEXTURE_CUBE_MAP {
+                        gl::TEXTURE_CUBE_MAP_POSITIVE_X + layer
+                    } else {
+                        assert_eq!(layer, 0);
+                        gl::TEXTURE_2D
+                    };
+
+                    if format.is_compressed() {
+                        ctxt.gl.CompressedTexSubImage2D(target, level, 0, 0, width, height,
+                                                        client_format, data_size, data);
+                    } else {
+                        ctxt.gl.TexSubImage2D(target, level, 0, 0, width, height, client_format,
+                                              client_type, data);
+                    }
+                },
+                gl::TEXTURE_2D_ARRAY => {
+                    let layer = layer as gl::types::GLint;
+                    if format.is_compressed() {
+                        ctxt.gl.CompressedTexSubImage3D(bind_point, level, 0, 0, layer, width,
+                                                        height, 1, client_format, data_size, data);
+                    } else {
+                        ctxt.gl.TexSubImage3D(bind_point, level, 0, 0, layer, width, height, 1,
+                                              client_format, client_type, data);
+                    }
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generates the content of all the mipmap levels from the main level, with
+    /// `glGenerateMipmap` or `glGenerateMipmapEXT`.
+    ///
+    /// This does nothing if the texture has a single level.
+    ///
+    /// # Safety
+    ///
+    /// The format of the texture must be filterable, and the texture must not be multisampled.
+    /// Otherwise an OpenGL error is triggered.
     pub unsafe fn generate_mipmaps(&self) {
+        if self.levels <= 1 {
+            return;
+        }
+
         let mut ctxt = self.context.make_current();
-        self.bind_to_current(&mut ctxt);
-        generate_mipmaps(&ctxt, self.get_bind_point());
+        if !is_dsa_supported(&ctxt) {
+            self.bind_to_current(&mut ctxt);
+        }
+        generate_mipmaps(&ctxt, self.id, self.get_bind_point());
+    }
+
+    /// Detaches this texture from its context, so that it can be used by another context that
+    /// shares lists with it.
+    ///
+    /// A fence is inserted after the commands that have been submitted so far, and
+    /// `from_transfer` waits for this fence before returning the texture.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture is backed by a memory object imported from a foreign API.
+    pub fn into_transfer(mut self) -> TextureTransfer<TextureAny> {
+        assert!(self.memory.is_none(), "Textures backed by external memory can't be transferred");
+
+        let fence = {
+            let mut ctxt = self.context.make_current();
+            unsafe { sync::new_transfer_fence(&mut ctxt) }
+        };
+
+        let transfer = TextureTransfer {
+            id: self.id,
+            requested_format: self.requested_format,
+            ty: self.ty,
+            levels: self.levels,
+            generate_mipmaps: self.generate_mipmaps,
+            owned: self.owned,
+            fence,
+            orphans: self.context.get_transfer_orphans().clone(),
+            marker: PhantomData,
+        };
+
+        // the destructor cleans up the state of the old context without deleting the texture
+        self.owned = false;
+        transfer
+    }
+
+    /// Attaches a texture that was detached with `into_transfer` to the context of `facade`.
+    ///
+    /// Blocks until the commands that were submitted before the transfer have finished.
+    ///
+    /// # Safety
+    ///
+    /// The context of `facade` must share lists with the context the texture was detached from.
+    #[inline]
+    pub unsafe fn from_transfer<F: ?Sized>(facade: &F, transfer: TextureTransfer<TextureAny>)
+                                           -> TextureAny where F: Facade
+    {
+        transfer.finish(facade)
     }
 }
 
@@ -878,7 +1430,13 @@ impl TextureExt for TextureAny {
                     ctxt.state.latest_memory_barrier_framebuffer = ctxt.state.next_draw_call_id;
                 }
             },
-        }        
+            crate::TextureAccess::TextureUpdate => {
+                if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_update {
+                    unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_UPDATE_BARRIER_BIT); }
+                    ctxt.state.latest_memory_barrier_texture_update = ctxt.state.next_draw_call_id;
+                }
+            },
+        }
     }
 
 
@@ -904,6 +1462,31 @@ impl fmt::Debug for TextureAny {
     }
 }
 
+impl Recreatable for TextureAny {
+    /// Returns `TextureImportError::NotRecreatable` if the texture is backed by a memory object
+    /// imported from a foreign API.
+    fn recreate(&mut self) -> Result<(), crate::Error> {
+        if self.memory.is_some() {
+            return Err(super::TextureImportError::NotRecreatable.into());
+        }
+
+        let mipmaps = match (self.levels, self.generate_mipmaps) {
+            (1, _) => MipmapsOption::NoMipmap,
+            (levels, true) => MipmapsOption::AutoGeneratedMipmapsMax(levels - 1),
+            (levels, false) => MipmapsOption::EmptyMipmapsMax(levels - 1),
+        };
+
+        let context = self.context.clone();
+        let new = new_texture(&context, self.requested_format, None::<(ClientFormatAny, Cow<'_, [u8]>)>,
+                              mipmaps, self.ty)?;
+
+        // the old texture belonged to the lost context and must not be destroyed
+        let mut old = mem::replace(self, new);
+        old.owned = false;
+        Ok(())
+    }
+}
+
 impl Drop for TextureAny {
     fn drop(&mut self) {
         let mut ctxt = self.context.make_current();
@@ -1021,7 +1604,7 @@ impl<'a> TextureAnyMipmap<'a> {
     /// In the case of 1D texture arrays, use array size as width.
     /// In the case of 2D texture arrays, use array size as depth.
     #[inline]
-    fn get_mipmap_dimensions(&self) -> (u32, u32, u32) {
+    pub(crate) fn get_mipmap_dimensions(&self) -> (u32, u32, u32) {
         let tex_depth = match self.texture.ty {
             Dimensions::Texture2dArray { array_size, .. } => array_size,
             _ => self.depth.unwrap_or(1),
@@ -1151,6 +1734,13 @@ impl<'a> TextureAnyMipmap<'a> {
                                                   self.texture.requested_format, inverted).unwrap();
 
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::TextureUpdate);
+
+        // the rows of the buffer are tightly packed
+        if ctxt.state.pixel_store_unpack_alignment != 1 {
+            ctxt.state.pixel_store_unpack_alignment = 1;
+            unsafe { ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1); }
+        }
 
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
@@ -1294,6 +1884,104 @@ impl<'a> TextureAnyMipmap<'a> {
             fence.insert(&mut ctxt);
         }
     }
+
+    /// Uploads a part of a larger image to the texture.
+    ///
+    /// The rows of `data` start every `stride_in_pixels` pixels, from the bottom of the region
+    /// to its top. This lets you upload a sub-rectangle of an image that is in memory without
+    /// copying it to a tightly packed buffer first. Backends that don't support
+    /// `GL_UNPACK_ROW_LENGTH` still need a temporary copy.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the offsets and dimensions are outside the boundaries of the texture, if the
+    /// texture isn't a 2D texture, if `stride_in_pixels` is smaller than the width of the region
+    /// or if `data` is not big enough.
+    pub fn raw_upload_with_stride<P>(&self, data: &[P], stride_in_pixels: u32, x: Range<u32>,
+                                     y: Range<u32>)
+                                     where P: PixelValue
+    {
+        let tex_dim = self.get_mipmap_dimensions();
+        assert!(x.start <= x.end && x.end <= tex_dim.0);
+        assert!(y.start <= y.end && y.end <= tex_dim.1);
+
+        let width = x.end - x.start;
+        let height = y.end - y.start;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        assert!(stride_in_pixels >= width, "The stride is smaller than the width of the region");
+        if data.len() < (height as usize - 1) * stride_in_pixels as usize + width as usize {
+            panic!("Data is too small");
+        }
+
+        let bind_point = self.texture.get_bind_point();
+        assert!(bind_point == gl::TEXTURE_2D, "Only 2D textures are supported");
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&self.texture.context,
+                                                  ClientFormatAny::ClientFormat(P::get_format()),
+                                                  self.texture.requested_format, false).unwrap();
+
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::TextureUpdate);
+
+        let row_length_supported = ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                                   ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                                   ctxt.extensions.gl_ext_unpack_subimage;
+
+        // without `GL_UNPACK_ROW_LENGTH`, the rows have to be packed together
+        let packed;
+        let data = if row_length_supported || stride_in_pixels == width {
+            data
+        } else {
+            packed = data.chunks(stride_in_pixels as usize).take(height as usize)
+                         .flat_map(|row| &row[.. width as usize]).cloned()
+                         .collect::<Vec<_>>();
+            &packed[..]
+        };
+
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+
+            if row_length_supported {
+                ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, stride_in_pixels as gl::types::GLint);
+            }
+
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.TextureSubImage2D(self.texture.id, self.level as gl::types::GLint,
+                                          x.start as gl::types::GLint,
+                                          y.start as gl::types::GLint,
+                                          width as gl::types::GLsizei,
+                                          height as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+
+            } else {
+                self.texture.bind_to_current(&mut ctxt);
+                ctxt.gl.TexSubImage2D(bind_point, self.level as gl::types::GLint,
+                                      x.start as gl::types::GLint,
+                                      y.start as gl::types::GLint,
+                                      width as gl::types::GLsizei,
+                                      height as gl::types::GLsizei,
+                                      client_format, client_type,
+                                      data.as_ptr() as *const _);
+            }
+
+            // the other uploads expect tightly packed rows
+            if row_length_supported {
+                ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, 0);
+            }
+        }
+    }
 }
 
 impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
@@ -1331,6 +2019,7 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
                                                                                  .map_err(|_| ())?;
 
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::TextureUpdate);
 
         unsafe {
             if ctxt.state.pixel_store_unpack_alignment != 1 {
@@ -1339,7 +2028,12 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
             }
 
             BufferAny::unbind_pixel_unpack(&mut ctxt);
-            let bind_point = self.texture.bind_to_current(&mut ctxt);
+            let dsa = is_dsa_supported(&ctxt);
+            let bind_point = if dsa {
+                self.texture.get_bind_point()
+            } else {
+                self.texture.bind_to_current(&mut ctxt)
+            };
 
             if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY {
                 unimplemented!();
@@ -1347,7 +2041,24 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
             } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY {
                 assert!(z_offset == 0);
                 // FIXME should glTexImage be used here somewhere or glTexSubImage does it just fine?
-                if is_client_compressed {
+                if dsa && is_client_compressed {
+                    ctxt.gl.CompressedTextureSubImage2D(id, level as gl::types::GLint,
+                                                        x_offset as gl::types::GLint,
+                                                        y_offset as gl::types::GLint,
+                                                        width as gl::types::GLsizei,
+                                                        height.unwrap_or(1) as gl::types::GLsizei,
+                                                        client_format,
+                                                        data_bufsize as gl::types::GLsizei,
+                                                        data.as_ptr() as *const _);
+                } else if dsa {
+                    ctxt.gl.TextureSubImage2D(id, level as gl::types::GLint,
+                                              x_offset as gl::types::GLint,
+                                              y_offset as gl::types::GLint,
+                                              width as gl::types::GLsizei,
+                                              height.unwrap_or(1) as gl::types::GLsizei,
+                                              client_format, client_type,
+                                              data.as_ptr() as *const _);
+                } else if is_client_compressed {
                     ctxt.gl.CompressedTexSubImage2D(bind_point, level as gl::types::GLint,
                                                     x_offset as gl::types::GLint,
                                                     y_offset as gl::types::GLint,
@@ -1375,11 +2086,7 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
 
             // regenerate mipmaps if there are some
             if regen_mipmaps {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) {
-                    ctxt.gl.GenerateMipmap(bind_point);
-                } else {
-                    ctxt.gl.GenerateMipmapEXT(bind_point);
-                }
+                generate_mipmaps(&ctxt, id, bind_point);
             }
 
             Ok(())
@@ -1594,6 +2301,7 @@ impl<'a> TextureAnyImage<'a> {
         assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
 
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
 
         let mut data = Vec::new();
         ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, &mut data, false)
@@ -1602,6 +2310,40 @@ impl<'a> TextureAnyImage<'a> {
         T::from_raw(Cow::Owned(data), self.width, self.height.unwrap_or(1))
     }
 
+    /// Reads a part of the image, with a specific pixel format and layout.
+    ///
+    /// Contrary to `raw_read`, the dimensions of the returned image are the ones of `rect`, and
+    /// an error is returned if the backend doesn't support the requested format or layout.
+    pub fn raw_read_with_options<T, P>(&self, rect: &Rect, options: &ReadOptions)
+                                       -> Result<T, ReadError>
+                                       where T: Texture2dDataSink<P>, P: PixelValue
+    {
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
+
+        let mut data = Vec::new();
+        ops::read_with_options(&mut ctxt, &fbo::RegularAttachment::Texture(*self), rect,
+                               &mut data, false, options)?;
+
+        Ok(T::from_raw(Cow::Owned(data), rect.width, rect.height))
+    }
+
+    /// Reads a part of the image to a pixel buffer, with a specific pixel format and layout.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is not large enough.
+    pub fn raw_read_to_pixel_buffer_with_options<P>(&self, rect: &Rect, dest: &PixelBuffer<P>,
+                                                    options: &ReadOptions)
+                                                    -> Result<(), ReadError>
+                                                    where P: PixelValue
+    {
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
+        ops::read_with_options(&mut ctxt, &fbo::RegularAttachment::Texture(*self), rect, dest,
+                               false, options)
+    }
+
     /// Reads the content of the image to a pixel buffer.
     ///
     /// # Panic
@@ -1619,6 +2361,28 @@ impl<'a> TextureAnyImage<'a> {
 
         let size = rect.width as usize * rect.height as usize * 4;
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
+        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false)
+            .unwrap();
+    }
+
+    /// Reads the content of the image into an arbitrary buffer, without going through the CPU.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the rect is out of range.
+    /// - Panics if the buffer is not large enough.
+    /// - Panics if it fails to read the texture.
+    ///
+    pub fn raw_read_to_buffer<P>(&self, rect: &Rect, dest: BufferSlice<'_, [P]>)
+        where P: PixelValue
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+        assert!(dest.len() >= rect.width as usize * rect.height as usize);
+
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
         ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false)
             .unwrap();
     }
@@ -1635,8 +2399,97 @@ impl<'a> TextureAnyImage<'a> {
     {
         unsafe {
             let mut ctxt = self.texture.context.make_current();
+            self.texture.prepare_for_access(&mut ctxt, crate::TextureAccess::Framebuffer);
             let attachment = fbo::RegularAttachment::Texture(*self);
             fbo::FramebuffersContainer::clear_buffer(&mut ctxt, &attachment, data);
         }
     }
+
+    /// Copies a rectangle of pixels from this image to another image.
+    ///
+    /// This is the same as `Surface::blit_color`, except that the framebuffers are built
+    /// internally. This makes it easy to blit between the mipmap levels or layers of a texture,
+    /// for example to build a custom downsampling chain.
+    ///
+    /// Depth, stencil and depth-stencil images are supported as well, in which case the
+    /// corresponding buffers are copied instead of the color.
+    ///
+    /// ```no_run
+    /// # fn example(texture: glium::texture::Texture2d) {
+    /// use glium::{BlitTarget, Rect};
+    /// use glium::texture::TextureAnyImage;
+    /// use glium::uniforms::MagnifySamplerFilter;
+    ///
+    /// let source: TextureAnyImage = texture.mipmap(0).unwrap().into();
+    /// let target: TextureAnyImage = texture.mipmap(1).unwrap().into();
+    ///
+    /// let (width, height) = (source.get_width(), source.get_height().unwrap());
+    /// source.blit_to(&Rect { left: 0, bottom: 0, width, height }, target,
+    ///                &BlitTarget { left: 0, bottom: 0, width: width as i32 / 2,
+    ///                              height: height as i32 / 2 },
+    ///                MagnifySamplerFilter::Linear).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// Returns an error if the two images are not of the same kind, for example a color image
+    /// and a depth image, or if the filter is not `Nearest` when blitting depth or stencil images.
+    pub fn blit_to<'b, T>(&self, source_rect: &Rect, target: T, target_rect: &BlitTarget,
+                          filter: MagnifySamplerFilter) -> Result<(), BlitError>
+        where T: Into<TextureAnyImage<'b>>
+    {
+        let target = target.into();
+
+        let (source, mask) = image_attachments(*self);
+        let (target, target_mask) = image_attachments(target);
+        if mask != target_mask {
+            return Err(BlitError::ImageKindMismatch);
+        }
+
+        if mask != gl::COLOR_BUFFER_BIT && filter != MagnifySamplerFilter::Nearest {
+            return Err(BlitError::FilterNotSupported);
+        }
+
+        let context = &self.texture.context;
+        let source = source.validate(&**context)?;
+        let target = target.validate(&**context)?;
+
+        ops::blit(context, Some(&source), Some(&target), mask, source_rect, target_rect,
+                  filter.to_glenum());
+        Ok(())
+    }
+}
+
+/// Builds the attachments of a framebuffer that contains only the given image, and returns
+/// the buffers that must be blitted.
+fn image_attachments(image: TextureAnyImage<'_>)
+                     -> (fbo::FramebufferAttachments<'_>, gl::types::GLbitfield)
+{
+    let attachment = fbo::RegularAttachment::Texture(image);
+
+    let (colors, depth_stencil, mask) = match image.texture.kind() {
+        TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned => {
+            let mut colors = SmallVec::new();
+            colors.push((0, attachment));
+            (colors, fbo::DepthStencilAttachments::None, gl::COLOR_BUFFER_BIT)
+        },
+        TextureKind::Depth => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::DepthAttachment(attachment),
+             gl::DEPTH_BUFFER_BIT)
+        },
+        TextureKind::Stencil => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::StencilAttachment(attachment),
+             gl::STENCIL_BUFFER_BIT)
+        },
+        TextureKind::DepthStencil => {
+            (SmallVec::new(), fbo::DepthStencilAttachments::DepthStencilAttachment(attachment),
+             gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT)
+        },
+    };
+
+    let attachments = fbo::FramebufferAttachments::Regular(fbo::FramebufferSpecificAttachments {
+        colors,
+        depth_stencil,
+    });
+
+    (attachments, mask)
 }
@@ -0,0 +1,349 @@
+#![cfg(feature = "atlas")]
+/*!
+
+Packing of many small images into one big texture.
+
+A `TextureAtlas` owns a `Texture2d` and keeps track of which parts of it are in use. Regions are
+allocated with a shelf packer: the texture is divided into horizontal shelves, and each shelf
+contains images of a similar height placed side by side. This works well for glyph caches and
+sprites, whose sizes don't vary much.
+
+The packing itself is done by `AtlasAllocator`, which doesn't depend on OpenGL and can be used
+on its own, for example to manage the layers of an array texture.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::texture::atlas::TextureAtlas;
+use glium::texture::UncompressedFloatFormat;
+
+let mut atlas = TextureAtlas::new(&display, UncompressedFloatFormat::U8U8U8U8, 1024, 1024).unwrap();
+
+let glyph = atlas.allocate(2, 2).unwrap();
+atlas.write(&glyph, vec![vec![(255u8, 255u8, 255u8, 255u8); 2]; 2]);
+let (uv_min, uv_max) = atlas.get_tex_coords(&glyph);
+# }
+```
+
+# Features
+
+Only available if the 'atlas' feature is enabled.
+
+*/
+use std::ops::Range;
+
+use crate::backend::Facade;
+use crate::texture::{MipmapsOption, Texture2d, Texture2dDataSource, TextureCreationError};
+use crate::texture::UncompressedFloatFormat;
+use crate::Rect;
+
+/// A rectangle allocated in an atlas, in texels. The origin is the bottom-left corner.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AtlasRegion {
+    /// Number of texels between the left border of the atlas and the region.
+    pub left: u32,
+    /// Number of texels between the bottom border of the atlas and the region.
+    pub bottom: u32,
+    /// Width of the region in texels.
+    pub width: u32,
+    /// Height of the region in texels.
+    pub height: u32,
+}
+
+impl From<AtlasRegion> for Rect {
+    #[inline]
+    fn from(region: AtlasRegion) -> Rect {
+        Rect {
+            left: region.left,
+            bottom: region.bottom,
+            width: region.width,
+            height: region.height,
+        }
+    }
+}
+
+/// A row of regions with the same maximum height.
+#[derive(Debug, Clone)]
+struct Shelf {
+    bottom: u32,
+    height: u32,
+    /// Free horizontal ranges, sorted and never adjacent.
+    free: Vec<Range<u32>>,
+}
+
+/// Shelf packer that allocates rectangles in an area of fixed dimensions.
+#[derive(Debug, Clone)]
+pub struct AtlasAllocator {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    /// Bottom of the area that isn't covered by any shelf.
+    next_shelf: u32,
+}
+
+impl AtlasAllocator {
+    /// Builds an allocator for an empty area of the given dimensions.
+    #[inline]
+    pub fn new(width: u32, height: u32) -> AtlasAllocator {
+        AtlasAllocator {
+            width,
+            height,
+            shelves: Vec::new(),
+            next_shelf: 0,
+        }
+    }
+
+    /// Returns the dimensions of the area.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Allocates a region of the given dimensions. Returns `None` if there isn't enough space.
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<AtlasRegion> {
+        if width == 0 || height == 0 || width > self.width || height > self.height {
+            return None;
+        }
+
+        // we avoid putting small images in shelves that are much taller, unless there is no
+        // other choice
+        let (shelf, range) = match self.find_free(width, height, Some(height.saturating_mul(2))) {
+            Some(found) => found,
+            None => match self.push_shelf(width, height) {
+                Some(found) => found,
+                None => self.find_free(width, height, None)?,
+            },
+        };
+
+        let shelf = &mut self.shelves[shelf];
+        let left = shelf.free[range].start;
+        shelf.free[range].start += width;
+        if shelf.free[range].start == shelf.free[range].end {
+            shelf.free.remove(range);
+        }
+
+        Some(AtlasRegion { left, bottom: shelf.bottom, width, height })
+    }
+
+    /// Gives back a region returned by `allocate`, so that it can be reused.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the region wasn't allocated by this allocator.
+    pub fn free(&mut self, region: AtlasRegion) {
+        let index = self.shelves.iter()
+            .position(|shelf| shelf.bottom == region.bottom && shelf.height >= region.height)
+            .expect("The region doesn't belong to this atlas");
+
+        let free = &mut self.shelves[index].free;
+        let range = region.left .. region.left + region.width;
+        let pos = free.iter().position(|r| r.start >= range.end).unwrap_or(free.len());
+        assert!(pos == 0 || free[pos - 1].end <= range.start, "The region is already free");
+
+        // merging with the neighbours
+        let merge_next = pos < free.len() && free[pos].start == range.end;
+        let merge_prev = pos > 0 && free[pos - 1].end == range.start;
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                free[pos - 1].end = free[pos].end;
+                free.remove(pos);
+            },
+            (true, false) => free[pos - 1].end = range.end,
+            (false, true) => free[pos].start = range.start,
+            (false, false) => free.insert(pos, range),
+        }
+
+        // removing the empty shelves at the top, so that their space can be reused by shelves
+        // of a different height
+        while let Some(shelf) = self.shelves.last() {
+            if shelf.free.len() != 1 || shelf.free[0] != (0 .. self.width) {
+                break;
+            }
+            self.next_shelf = shelf.bottom;
+            self.shelves.pop();
+        }
+    }
+
+    /// Frees all the regions.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+        self.next_shelf = 0;
+    }
+
+    /// Returns the index of a shelf and of one of its free ranges that can contain the
+    /// given dimensions. Picks the shortest shelf that fits.
+    fn find_free(&self, width: u32, height: u32, max_height: Option<u32>)
+                 -> Option<(usize, usize)>
+    {
+        let mut result: Option<(usize, usize)> = None;
+
+        for (index, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height < height || max_height.map_or(false, |max| shelf.height > max) {
+                continue;
+            }
+
+            if let Some(range) = shelf.free.iter().position(|r| r.end - r.start >= width) {
+                if result.map_or(true, |(best, _)| self.shelves[best].height > shelf.height) {
+                    result = Some((index, range));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Adds a new shelf on top of the existing ones.
+    fn push_shelf(&mut self, width: u32, height: u32) -> Option<(usize, usize)> {
+        if self.height - self.next_shelf < height || width > self.width {
+            return None;
+        }
+
+        self.shelves.push(Shelf {
+            bottom: self.next_shelf,
+            height,
+            free: vec![0 .. self.width],
+        });
+        self.next_shelf += height;
+
+        Some((self.shelves.len() - 1, 0))
+    }
+}
+
+/// A `Texture2d` whose content is split into regions allocated with an `AtlasAllocator`.
+pub struct TextureAtlas {
+    texture: Texture2d,
+    allocator: AtlasAllocator,
+}
+
+impl TextureAtlas {
+    /// Builds a new empty atlas with the given format and dimensions.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32)
+                          -> Result<TextureAtlas, TextureCreationError> where F: Facade
+    {
+        let texture = Texture2d::empty_with_format(facade, format, MipmapsOption::NoMipmap,
+                                                   width, height)?;
+
+        Ok(TextureAtlas {
+            texture,
+            allocator: AtlasAllocator::new(width, height),
+        })
+    }
+
+    /// Allocates a region of the given dimensions. Returns `None` if there isn't enough space.
+    ///
+    /// The content of the region is undefined until you write to it.
+    #[inline]
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<AtlasRegion> {
+        self.allocator.allocate(width, height)
+    }
+
+    /// Gives back a region returned by `allocate`, so that it can be reused.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the region wasn't allocated by this atlas.
+    #[inline]
+    pub fn free(&mut self, region: AtlasRegion) {
+        self.allocator.free(region)
+    }
+
+    /// Frees all the regions.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.allocator.clear()
+    }
+
+    /// Uploads data to a region.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the dimensions of the data don't match the dimensions of the region.
+    #[inline]
+    pub fn write<'a, T>(&self, region: &AtlasRegion, data: T) where T: Texture2dDataSource<'a> {
+        self.texture.write((*region).into(), data)
+    }
+
+    /// Returns the texture coordinates of the bottom-left and top-right corners of a region.
+    pub fn get_tex_coords(&self, region: &AtlasRegion) -> ([f32; 2], [f32; 2]) {
+        let width = self.texture.width() as f32;
+        let height = self.texture.height() as f32;
+
+        ([region.left as f32 / width, region.bottom as f32 / height],
+         [(region.left + region.width) as f32 / width,
+          (region.bottom + region.height) as f32 / height])
+    }
+
+    /// Returns the texture that contains the regions.
+    #[inline]
+    pub fn get_texture(&self) -> &Texture2d {
+        &self.texture
+    }
+
+    /// Returns the allocator that keeps track of the regions.
+    #[inline]
+    pub fn get_allocator(&self) -> &AtlasAllocator {
+        &self.allocator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtlasAllocator, AtlasRegion};
+
+    #[test]
+    fn shelves() {
+        let mut atlas = AtlasAllocator::new(16, 16);
+
+        let a = atlas.allocate(10, 4).unwrap();
+        let b = atlas.allocate(6, 4).unwrap();
+        let c = atlas.allocate(4, 8).unwrap();
+
+        assert_eq!(a, AtlasRegion { left: 0, bottom: 0, width: 10, height: 4 });
+        assert_eq!(b, AtlasRegion { left: 10, bottom: 0, width: 6, height: 4 });
+        assert_eq!(c, AtlasRegion { left: 0, bottom: 4, width: 4, height: 8 });
+
+        // too tall for the remaining space
+        assert_eq!(atlas.allocate(4, 9), None);
+        assert_eq!(atlas.allocate(17, 1), None);
+    }
+
+    #[test]
+    fn free_and_reuse() {
+        let mut atlas = AtlasAllocator::new(16, 16);
+
+        let a = atlas.allocate(8, 4).unwrap();
+        let b = atlas.allocate(8, 4).unwrap();
+        atlas.free(a);
+
+        assert_eq!(atlas.allocate(8, 3), Some(AtlasRegion { height: 3, ..a }));
+        atlas.free(AtlasRegion { height: 3, ..a });
+        atlas.free(b);
+
+        // the atlas is empty again, so a region of any height fits
+        assert_eq!(atlas.allocate(16, 16), Some(AtlasRegion { left: 0, bottom: 0, width: 16, height: 16 }));
+    }
+
+    #[test]
+    fn no_waste_on_tall_shelves() {
+        let mut atlas = AtlasAllocator::new(16, 16);
+
+        atlas.allocate(4, 12).unwrap();
+        let small = atlas.allocate(4, 2).unwrap();
+        assert_eq!(small.bottom, 12);
+
+        // no room for a new shelf, so the tall one is used
+        let medium = atlas.allocate(4, 3).unwrap();
+        assert_eq!(medium, AtlasRegion { left: 4, bottom: 0, width: 4, height: 3 });
+    }
+
+    #[test]
+    #[should_panic]
+    fn double_free() {
+        let mut atlas = AtlasAllocator::new(16, 16);
+        let a = atlas.allocate(4, 4).unwrap();
+        atlas.allocate(4, 4).unwrap();
+        atlas.free(a);
+        atlas.free(a);
+    }
+}
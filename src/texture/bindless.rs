@@ -55,6 +55,33 @@ let uniform_buffer = glium::uniforms::UniformBuffer::new(&display, UniformBuffer
 # }
 ```
 
+A `TextureHandle` can also be passed directly as a uniform value. The texture isn't bound to
+any texture unit, so the number of textures used by a draw call isn't limited.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>, texture: glium::texture::bindless::ResidentTexture,
+#               program: glium::Program, vertex_buffer: glium::vertex::VertexBufferAny)
+#     where T: SurfaceTypeTrait + ResizeableSurface {
+# use glium::Surface;
+# let mut frame = display.draw();
+let uniforms = uniform! {
+    tex: glium::texture::TextureHandle::new(&texture, &Default::default()),
+};
+
+frame.draw(&vertex_buffer, &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+           &program, &uniforms, &Default::default()).unwrap();
+# }
+# }
+```
+
+Drawing returns `DrawError::NonResidentTextureHandle` if the handle doesn't belong to a texture
+that is resident in the context that draws.
+
 Inside your shader, you can refer to the texture with a traditional `sampler*` variable. Glium
 currently doesn't check whether the type of your texture matches the expected type (but it may
 do in the future). Binding the wrong type of texture may lead to undefined values when sampling
@@ -68,6 +95,8 @@ use crate::GlObject;
 use crate::ContextExt;
 use crate::gl;
 
+use std::error::Error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
@@ -177,13 +206,18 @@ impl<'a> TextureHandle<'a> {
         // FIXME: take sampler into account
         self.value = texture.handle;
     }
+
+    /// Returns the raw value of the handle.
+    #[inline]
+    pub(crate) fn get_value(&self) -> gl::types::GLuint64 {
+        self.value
+    }
 }
 
 impl<'a> AsUniformValue for TextureHandle<'a> {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
-        // TODO: u64
-        unimplemented!();
+        UniformValue::Handle(*self)
     }
 }
 
@@ -197,52 +231,11 @@ impl<'a> UniformBlock for TextureHandle<'a> {
             //
             //       however there is no safety problem here ; the worse that can happen in case of
             //       wrong type is zeroes or undefined data being returned when sampling
-            match ty {
-                UniformType::Sampler1d => (),
-                UniformType::ISampler1d => (),
-                UniformType::USampler1d => (),
-                UniformType::Sampler2d => (),
-                UniformType::ISampler2d => (),
-                UniformType::USampler2d => (),
-                UniformType::Sampler3d => (),
-                UniformType::ISampler3d => (),
-                UniformType::USampler3d => (),
-                UniformType::Sampler1dArray => (),
-                UniformType::ISampler1dArray => (),
-                UniformType::USampler1dArray => (),
-                UniformType::Sampler2dArray => (),
-                UniformType::ISampler2dArray => (),
-                UniformType::USampler2dArray => (),
-                UniformType::SamplerCube => (),
-                UniformType::ISamplerCube => (),
-                UniformType::USamplerCube => (),
-                UniformType::Sampler2dRect => (),
-                UniformType::ISampler2dRect => (),
-                UniformType::USampler2dRect => (),
-                UniformType::Sampler2dRectShadow => (),
-                UniformType::SamplerCubeArray => (),
-                UniformType::ISamplerCubeArray => (),
-                UniformType::USamplerCubeArray => (),
-                UniformType::SamplerBuffer => (),
-                UniformType::ISamplerBuffer => (),
-                UniformType::USamplerBuffer => (),
-                UniformType::Sampler2dMultisample => (),
-                UniformType::ISampler2dMultisample => (),
-                UniformType::USampler2dMultisample => (),
-                UniformType::Sampler2dMultisampleArray => (),
-                UniformType::ISampler2dMultisampleArray => (),
-                UniformType::USampler2dMultisampleArray => (),
-                UniformType::Sampler1dShadow => (),
-                UniformType::Sampler2dShadow => (),
-                UniformType::SamplerCubeShadow => (),
-                UniformType::Sampler1dArrayShadow => (),
-                UniformType::Sampler2dArrayShadow => (),
-                UniformType::SamplerCubeArrayShadow => (),
-
-                _ => return Err(LayoutMismatchError::TypeMismatch {
+            if !is_sampler_type(ty) {
+                return Err(LayoutMismatchError::TypeMismatch {
                     expected: ty,
                     obtained: UniformType::Sampler2d,       // TODO: wrong
-                })
+                });
             }
 
             if offset_in_buffer != base_offset {
@@ -290,10 +283,46 @@ impl<'a> UniformBlock for TextureHandle<'a> {
 
 // TODO: implement `vertex::Attribute` on `TextureHandle`
 
+/// Returns true if a texture handle can be used with a uniform of the given type.
+///
+/// We have no idea what the exact type of the texture behind a handle is, so all the sampler
+/// types are accepted. There is no safety problem here ; the worse that can happen in case of
+/// wrong type is zeroes or undefined data being returned when sampling.
+pub(crate) fn is_sampler_type(ty: UniformType) -> bool {
+    matches!(ty,
+        UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+        UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+        UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+        UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+        UniformType::USampler1dArray | UniformType::Sampler2dArray |
+        UniformType::ISampler2dArray | UniformType::USampler2dArray |
+        UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+        UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+        UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+        UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+        UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+        UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+        UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+        UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+        UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+        UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+        UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+        UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow)
+}
+
 /// Bindless textures are not supported.
 #[derive(Debug, Copy, Clone)]
 pub struct BindlessTexturesNotSupportedError;
 
+impl fmt::Display for BindlessTexturesNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("Bindless textures are not supported by the backend")
+    }
+}
+
+impl Error for BindlessTexturesNotSupportedError {}
+
 #[cfg(test)]
 mod test {
     use std::mem;
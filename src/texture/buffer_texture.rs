@@ -35,6 +35,9 @@ most chances of being supported:
  - `U32U32U32U32` (unsigned only)
  - `I32I32I32I32` (signed only)
 
+Three-component formats are only available with 32-bit components. With the `half` feature,
+`half::f16` and its tuples and arrays can be used for the `F16` formats.
+
 # Buffer texture type
 
 The template parameter that you use for `BufferTexture` defines the content of the buffer. For
@@ -51,6 +54,15 @@ sample from a buffer texture of type `Integral` you need to use a `isamplerBuffe
 to sample from a buffer texture of type `Unsigned` you need to use a `usamplerBuffer`. Using the
 wrong type will result in an error.
 
+# Size
+
+The number of texels of a buffer texture is limited by `GL_MAX_TEXTURE_BUFFER_SIZE`, which
+you can query with `Context::get_max_texture_buffer_size`. Creating a buffer texture from a
+larger buffer returns `TextureCreationError::TooLarge`.
+
+The buffer of a buffer texture can be replaced with `set_buffer`, for example after the data has
+outgrown the previous buffer.
+
 */
 use std::{ mem, fmt };
 use std::marker::PhantomData;
@@ -90,6 +102,11 @@ pub enum TextureCreationError {
 
     /// The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
     TooLarge,
+
+    /// The buffer doesn't start at the beginning of its allocation, and either
+    /// `glTexBufferRange` isn't supported or the offset isn't a multiple of
+    /// `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`.
+    OffsetNotSupported,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -102,6 +119,8 @@ impl fmt::Display for TextureCreationError {
                 "The requested format is not supported in combination with the given texture buffer type",
             TooLarge =>
                 "The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`",
+            OffsetNotSupported =>
+                "The offset of the buffer in its allocation is not supported",
         };
         fmt.write_str(desc)
     }
@@ -282,176 +301,250 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
         let context = context.get_context();
         let mut ctxt = context.make_current();
 
-        // checking capabilities
-        if buffer.get_size() / mem::size_of::<T>() > ctxt.capabilities
-                                                         .max_texture_buffer_size.unwrap() as usize
-        {
-            return Err((TextureCreationError::TooLarge, buffer));
-        }
-
-        // before starting, we determine the internal format and check that buffer textures are
-        // supported
-        let internal_format = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-                                 ctxt.extensions.gl_oes_texture_buffer ||
-                                 ctxt.extensions.gl_ext_texture_buffer
-        {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8, BufferTextureType::Float) => gl::R8,
-                (TextureBufferContentType::U8, BufferTextureType::Unsigned) => gl::R8UI,
-                (TextureBufferContentType::I8, BufferTextureType::Integral) => gl::R8I,
-                (TextureBufferContentType::U16, BufferTextureType::Float) => gl::R16,
-                (TextureBufferContentType::U16, BufferTextureType::Unsigned) => gl::R16UI,
-                (TextureBufferContentType::I16, BufferTextureType::Integral) => gl::R16I,
-                (TextureBufferContentType::U32, BufferTextureType::Unsigned) => gl::R32UI,
-                (TextureBufferContentType::I32, BufferTextureType::Integral) => gl::R32I,
-                (TextureBufferContentType::U8U8, BufferTextureType::Float) => gl::RG8,
-                (TextureBufferContentType::U8U8, BufferTextureType::Unsigned) => gl::RG8UI,
-                (TextureBufferContentType::I8I8, BufferTextureType::Integral) => gl::RG8I,
-                (TextureBufferContentType::U16U16, BufferTextureType::Float) => gl::RG16,
-                (TextureBufferContentType::U16U16, BufferTextureType::Unsigned) => gl::RG16UI,
-                (TextureBufferContentType::I16I16, BufferTextureType::Integral) => gl::RG16I,
-                (TextureBufferContentType::U32U32, BufferTextureType::Unsigned) => gl::RG32UI,
-                (TextureBufferContentType::I32I32, BufferTextureType::Integral) => gl::RG32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
-                                                                                       gl::RGBA16I,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA32UI,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
-                                                                                       gl::RGBA32I,
-                (TextureBufferContentType::F16, BufferTextureType::Float) => gl::R16F,
-                (TextureBufferContentType::F32, BufferTextureType::Float) => gl::R32F,
-                (TextureBufferContentType::F16F16, BufferTextureType::Float) => gl::RG16F,
-                (TextureBufferContentType::F32F32, BufferTextureType::Float) => gl::RG32F,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-
-                (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32UI,
-                (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32I,
-                (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32F,
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
-            }
-
-        } else if ctxt.extensions.gl_arb_texture_buffer_object ||
-                  ctxt.extensions.gl_ext_texture_buffer_object
-        {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA16I,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA32UI,
-
-                (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32UI,
-                (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32I,
-                (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
-                                            if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32F,
-
-                // TODO: intensity?
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
-            }
-
-        } else {
-            return Err((TextureCreationError::NotSupported, buffer));
+        let internal_format = match check_buffer::<T>(&mut ctxt, &buffer, ty) {
+            Ok(f) => f,
+            Err(e) => return Err((e, buffer)),
         };
 
         // now the texture creation
-        debug_assert_eq!(buffer.get_offset_bytes(), 0);
         let id = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                     ctxt.extensions.gl_arb_direct_state_access
         {
             unsafe {
                 let mut id = 0;
                 ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
-                ctxt.gl.TextureBuffer(id, internal_format, buffer.get_id());
                 id
             }
 
         } else {
             // reserving the ID
-            let id = unsafe {
+            unsafe {
                 let mut id = 0;
                 ctxt.gl.GenTextures(1, &mut id);
                 id
-            };
-
-            // binding the texture
-            unsafe {
-                ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
-                let act = ctxt.state.active_texture as usize;
-                ctxt.state.texture_units[act].texture = id;
-            }
-
-            // binding the buffer
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-               ctxt.version >= &Version(Api::GlEs, 3, 2)
-            {
-                unsafe {
-                    ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_arb_texture_buffer_object {
-                unsafe {
-                    ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_ext_texture_buffer_object ||
-                      ctxt.extensions.gl_ext_texture_buffer
-            {
-                unsafe {
-                    ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-            } else if ctxt.extensions.gl_oes_texture_buffer {
-                unsafe {
-                    ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format,
-                                         buffer.get_id());
-                }
-
-            } else {
-                // handled during the choice for the internal format
-                // note that this panic will leak the texture
-                unreachable!();
             }
-
-            id
         };
 
+        attach_buffer(&mut ctxt, id, internal_format, &buffer);
+
         Ok(BufferTexture {
             buffer,
             ty,
             texture: id,
         })
     }
+
+    /// Makes the texture point to another buffer, and returns the previous buffer.
+    ///
+    /// The texture keeps its type. On error, the buffer that was passed is returned and the
+    /// texture is left untouched.
+    pub fn set_buffer(&mut self, buffer: Buffer<[T]>)
+                      -> Result<Buffer<[T]>, (TextureCreationError, Buffer<[T]>)>
+    {
+        let context = self.buffer.get_context().clone();
+        let mut ctxt = context.make_current();
+
+        let internal_format = match check_buffer::<T>(&mut ctxt, &buffer, self.ty) {
+            Ok(f) => f,
+            Err(e) => return Err((e, buffer)),
+        };
+
+        attach_buffer(&mut ctxt, self.texture, internal_format, &buffer);
+        Ok(mem::replace(&mut self.buffer, buffer))
+    }
+}
+
+/// Checks that `buffer` can be bound to a buffer texture of the given type, and returns the
+/// internal format to use.
+fn check_buffer<T>(ctxt: &mut CommandContext<'_>, buffer: &Buffer<[T]>, ty: BufferTextureType)
+                   -> Result<gl::types::GLenum, TextureCreationError>
+                   where [T]: BufferContent, T: TextureBufferContent
+{
+    // checking capabilities
+    let max_size = match ctxt.capabilities.max_texture_buffer_size {
+        Some(s) => s as usize,
+        None => return Err(TextureCreationError::NotSupported),
+    };
+
+    if buffer.get_size() / mem::size_of::<T>() > max_size {
+        return Err(TextureCreationError::TooLarge);
+    }
+
+    // a buffer that doesn't start at the beginning of its allocation requires `glTexBufferRange`
+    let offset = buffer.get_offset_bytes();
+    if offset != 0 {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 2)) {
+            return Err(TextureCreationError::OffsetNotSupported);
+        }
+
+        let alignment = unsafe {
+            let mut val = 0;
+            ctxt.gl.GetIntegerv(gl::TEXTURE_BUFFER_OFFSET_ALIGNMENT, &mut val);
+            val
+        };
+
+        if alignment > 0 && offset % alignment as usize != 0 {
+            return Err(TextureCreationError::OffsetNotSupported);
+        }
+    }
+
+    let internal_format = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                             ctxt.extensions.gl_oes_texture_buffer ||
+                             ctxt.extensions.gl_ext_texture_buffer
+    {
+        match (T::get_type(), ty) {
+            (TextureBufferContentType::U8, BufferTextureType::Float) => gl::R8,
+            (TextureBufferContentType::U8, BufferTextureType::Unsigned) => gl::R8UI,
+            (TextureBufferContentType::I8, BufferTextureType::Integral) => gl::R8I,
+            (TextureBufferContentType::U16, BufferTextureType::Float) => gl::R16,
+            (TextureBufferContentType::U16, BufferTextureType::Unsigned) => gl::R16UI,
+            (TextureBufferContentType::I16, BufferTextureType::Integral) => gl::R16I,
+            (TextureBufferContentType::U32, BufferTextureType::Unsigned) => gl::R32UI,
+            (TextureBufferContentType::I32, BufferTextureType::Integral) => gl::R32I,
+            (TextureBufferContentType::U8U8, BufferTextureType::Float) => gl::RG8,
+            (TextureBufferContentType::U8U8, BufferTextureType::Unsigned) => gl::RG8UI,
+            (TextureBufferContentType::I8I8, BufferTextureType::Integral) => gl::RG8I,
+            (TextureBufferContentType::U16U16, BufferTextureType::Float) => gl::RG16,
+            (TextureBufferContentType::U16U16, BufferTextureType::Unsigned) => gl::RG16UI,
+            (TextureBufferContentType::I16I16, BufferTextureType::Integral) => gl::RG16I,
+            (TextureBufferContentType::U32U32, BufferTextureType::Unsigned) => gl::RG32UI,
+            (TextureBufferContentType::I32I32, BufferTextureType::Integral) => gl::RG32I,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
+            (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA16UI,
+            (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
+                                                                                   gl::RGBA16I,
+            (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA32UI,
+            (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
+                                                                                   gl::RGBA32I,
+            (TextureBufferContentType::F16, BufferTextureType::Float) => gl::R16F,
+            (TextureBufferContentType::F32, BufferTextureType::Float) => gl::R32F,
+            (TextureBufferContentType::F16F16, BufferTextureType::Float) => gl::RG16F,
+            (TextureBufferContentType::F32F32, BufferTextureType::Float) => gl::RG32F,
+            (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
+            (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
+
+            (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32UI,
+            (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32I,
+            (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
+                                        if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32F,
+
+            _ => return Err(TextureCreationError::FormatNotSupported)
+        }
+
+    } else if ctxt.extensions.gl_arb_texture_buffer_object ||
+              ctxt.extensions.gl_ext_texture_buffer_object
+    {
+        match (T::get_type(), ty) {
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
+            (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
+            (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
+            (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
+            (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
+                                                                                  gl::RGBA16I,
+            (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
+                                                                                  gl::RGBA32I,
+            (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
+            (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA16UI,
+            (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
+                                                                                  gl::RGBA32UI,
+
+            (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32UI,
+            (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32I,
+            (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
+                                        if ctxt.extensions.gl_arb_texture_buffer_object_rgb32
+                                                                                => gl::RGB32F,
+
+            // TODO: intensity?
+
+            _ => return Err(TextureCreationError::FormatNotSupported)
+        }
+
+    } else {
+        return Err(TextureCreationError::NotSupported);
+    };
+
+    Ok(internal_format)
+}
+
+/// Attaches `buffer` to `texture`. Unless direct state access is supported, the texture is bound.
+///
+/// `check_buffer` must have returned `internal_format` for this buffer.
+fn attach_buffer<T>(ctxt: &mut CommandContext<'_>, texture: gl::types::GLuint,
+                    internal_format: gl::types::GLenum, buffer: &Buffer<[T]>)
+                    where [T]: BufferContent
+{
+    let offset = buffer.get_offset_bytes() as gl::types::GLintptr;
+    let size = buffer.get_size() as gl::types::GLsizeiptr;
+    let buffer = buffer.get_id();
+
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        unsafe {
+            if offset != 0 {
+                ctxt.gl.TextureBufferRange(texture, internal_format, buffer, offset, size);
+            } else {
+                ctxt.gl.TextureBuffer(texture, internal_format, buffer);
+            }
+        }
+        return;
+    }
+
+    // binding the texture
+    unsafe {
+        ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, texture);
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = texture;
+    }
+
+    if offset != 0 {
+        // `check_buffer` has made sure that `glTexBufferRange` is supported
+        unsafe {
+            ctxt.gl.TexBufferRange(gl::TEXTURE_BUFFER, internal_format, buffer, offset, size);
+        }
+    } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 2)
+    {
+        unsafe {
+            ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer);
+        }
+    } else if ctxt.extensions.gl_arb_texture_buffer_object {
+        unsafe {
+            ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format, buffer);
+        }
+    } else if ctxt.extensions.gl_ext_texture_buffer_object ||
+              ctxt.extensions.gl_ext_texture_buffer
+    {
+        unsafe {
+            ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format, buffer);
+        }
+    } else if ctxt.extensions.gl_oes_texture_buffer {
+        unsafe {
+            ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format, buffer);
+        }
+
+    } else {
+        // handled during the choice for the internal format
+        // note that this panic will leak the texture
+        unreachable!();
+    }
 }
 
 impl<T> Deref for BufferTexture<T> where [T]: BufferContent {
@@ -598,289 +691,4 @@ pub unsafe trait TextureBufferContent: BufferContent {
     fn get_type() -> TextureBufferContentType;
 }
 
-unsafe impl TextureBufferContent for u8 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U8
-    }
-}
-
-unsafe impl TextureBufferContent for i8 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I8
-    }
-}
-
-unsafe impl TextureBufferContent for u16 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U16
-    }
-}
-
-unsafe impl TextureBufferContent for i16 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I16
-    }
-}
-
-unsafe impl TextureBufferContent for u32 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32
-    }
-}
-
-unsafe impl TextureBufferContent for i32 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32
-    }
-}
-
-unsafe impl TextureBufferContent for (u8, u8) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U8U8
-    }
-}
-
-unsafe impl TextureBufferContent for [u8; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U8U8
-    }
-}
-
-unsafe impl TextureBufferContent for (i8, i8) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I8I8
-    }
-}
-
-unsafe impl TextureBufferContent for [i8; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I8I8
-    }
-}
-
-unsafe impl TextureBufferContent for (u16, u16) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U16U16
-    }
-}
-
-unsafe impl TextureBufferContent for [u16; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U16U16
-    }
-}
-
-unsafe impl TextureBufferContent for (i16, i16) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I16I16
-    }
-}
-
-unsafe impl TextureBufferContent for [i16; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I16I16
-    }
-}
-
-unsafe impl TextureBufferContent for (u32, u32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for [u32; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for (i32, i32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for [i32; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for (u32, u32, u32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for [u32; 3] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for (i32, i32, i32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for [i32; 3] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for (u8, u8, u8, u8) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U8U8U8U8
-    }
-}
-
-unsafe impl TextureBufferContent for [u8; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U8U8U8U8
-    }
-}
-
-unsafe impl TextureBufferContent for (i8, i8, i8, i8) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I8I8I8I8
-    }
-}
-
-unsafe impl TextureBufferContent for [i8; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I8I8I8I8
-    }
-}
-
-unsafe impl TextureBufferContent for (u16, u16, u16, u16) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U16U16U16U16
-    }
-}
-
-unsafe impl TextureBufferContent for [u16; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U16U16U16U16
-    }
-}
-
-unsafe impl TextureBufferContent for (i16, i16, i16, i16) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I16I16I16I16
-    }
-}
-
-unsafe impl TextureBufferContent for [i16; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I16I16I16I16
-    }
-}
-
-unsafe impl TextureBufferContent for (u32, u32, u32, u32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for [u32; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::U32U32U32U32
-    }
-}
-
-unsafe impl TextureBufferContent for (i32, i32, i32, i32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for [i32; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::I32I32I32I32
-    }
-}
-
-unsafe impl TextureBufferContent for f32 {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32
-    }
-}
-
-unsafe impl TextureBufferContent for (f32, f32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32
-    }
-}
-
-unsafe impl TextureBufferContent for [f32; 2] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32
-    }
-}
-
-unsafe impl TextureBufferContent for (f32, f32, f32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32F32
-    }
-}
-
-unsafe impl TextureBufferContent for [f32; 3] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32F32
-    }
-}
-
-unsafe impl TextureBufferContent for (f32, f32, f32, f32) {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32F32F32
-    }
-}
-
-unsafe impl TextureBufferContent for [f32; 4] {
-    #[inline]
-    fn get_type() -> TextureBufferContentType {
-        TextureBufferContentType::F32F32F32F32
-    }
-}
+include!(concat!(env!("OUT_DIR"), "/buffer_textures.rs"));
@@ -0,0 +1,240 @@
+#![cfg(all(target_os = "linux", feature = "egl_image"))]
+/*!
+Import of `EGLImage`s and of Linux DMA-BUFs as textures, without copying their content.
+
+This is useful for Wayland compositors, that receive the buffers of their clients as
+DMA-BUFs, and for video players and camera applications, whose decoders and drivers write the
+frames in DMA-BUFs.
+
+The OpenGL context must support `GL_OES_EGL_image`. Importing DMA-BUFs also requires an
+`EglDisplay` that supports `EGL_EXT_image_dma_buf_import`, and
+`EGL_EXT_image_dma_buf_import_modifiers` if a format modifier is given.
+
+# Example
+
+```no_run
+# fn example(display: &glium::Display<glium::glutin::surface::WindowSurface>,
+#            egl_display: *const std::os::raw::c_void,
+#            get_proc_address: fn(&str) -> *const std::os::raw::c_void,
+#            fd: std::os::unix::io::BorrowedFd<'_>) {
+use glium::texture::egl_image::{DmaBufPlane, EglDisplay};
+use glium::texture::UncompressedFloatFormat;
+
+let egl_display = unsafe { EglDisplay::new(egl_display, get_proc_address) }.unwrap();
+
+// `XR24`, the fourcc code of `DRM_FORMAT_XRGB8888`
+let fourcc = u32::from_le_bytes(*b"XR24");
+let planes = [DmaBufPlane { fd, offset: 0, stride: 1920 * 4 }];
+
+let texture = unsafe {
+    glium::Texture2d::from_dmabuf(display, &egl_display, &planes, fourcc, None,
+                                  UncompressedFloatFormat::U8U8U8U8, (1920, 1080))
+}.unwrap();
+# }
+```
+
+*/
+use std::fmt;
+use std::os::raw::c_void;
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+use std::ptr;
+
+use crate::backend::Facade;
+use crate::context::CommandContext;
+use crate::gl;
+use crate::texture::{Dimensions, MipmapsOption, Texture2d, TextureImportError};
+use crate::texture::UncompressedFloatFormat;
+use crate::ContextExt;
+
+const EGL_NONE: i32 = 0x3038;
+const EGL_WIDTH: i32 = 0x3057;
+const EGL_HEIGHT: i32 = 0x3056;
+const EGL_LINUX_DMA_BUF_EXT: u32 = 0x3270;
+const EGL_LINUX_DRM_FOURCC_EXT: i32 = 0x3271;
+
+/// `FD`, `OFFSET`, `PITCH`, `MODIFIER_LO` and `MODIFIER_HI` attributes of each plane.
+const EGL_DMA_BUF_PLANE_ATTRIBUTES: [[i32; 5]; 4] = [
+    [0x3272, 0x3273, 0x3274, 0x3443, 0x3444],
+    [0x3275, 0x3276, 0x3277, 0x3445, 0x3446],
+    [0x3278, 0x3279, 0x327A, 0x3447, 0x3448],
+    [0x3440, 0x3441, 0x3442, 0x3449, 0x344A],
+];
+
+type CreateImageFn = unsafe extern "system" fn(*const c_void, *const c_void, u32, *const c_void,
+                                                *const i32) -> *const c_void;
+type DestroyImageFn = unsafe extern "system" fn(*const c_void, *const c_void) -> u32;
+type GetErrorFn = unsafe extern "system" fn() -> i32;
+
+/// An EGL display, used to create the `EGLImage`s of DMA-BUFs.
+///
+/// With the glutin backend, the raw display is returned by `raw_display` on the glutin display,
+/// and the function pointers by `get_proc_address`.
+pub struct EglDisplay {
+    display: *const c_void,
+    create_image: CreateImageFn,
+    destroy_image: DestroyImageFn,
+    get_error: GetErrorFn,
+}
+
+impl EglDisplay {
+    /// Builds an `EglDisplay` from a raw `EGLDisplay` and a function that returns the address
+    /// of an EGL function.
+    ///
+    /// # Safety
+    ///
+    /// `display` must be a valid and initialized `EGLDisplay`, and must outlive the returned
+    /// object. `get_proc_address` must return valid function pointers for this display.
+    ///
+    /// Returns `TextureImportError::EglFunctionNotFound` if `eglCreateImageKHR`,
+    /// `eglDestroyImageKHR` or `eglGetError` can't be found.
+    pub unsafe fn new<F>(display: *const c_void, get_proc_address: F)
+                         -> Result<EglDisplay, TextureImportError>
+        where F: Fn(&str) -> *const c_void
+    {
+        let load = |name: &'static str| {
+            let ptr = get_proc_address(name);
+            if ptr.is_null() {
+                return Err(TextureImportError::EglFunctionNotFound(name));
+            }
+            Ok(ptr)
+        };
+
+        Ok(EglDisplay {
+            display,
+            create_image: std::mem::transmute::<*const c_void, CreateImageFn>(
+                load("eglCreateImageKHR")?),
+            destroy_image: std::mem::transmute::<*const c_void, DestroyImageFn>(
+                load("eglDestroyImageKHR")?),
+            get_error: std::mem::transmute::<*const c_void, GetErrorFn>(load("eglGetError")?),
+        })
+    }
+}
+
+impl fmt::Debug for EglDisplay {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "EglDisplay({:?})", self.display)
+    }
+}
+
+/// A plane of a DMA-BUF.
+#[derive(Debug, Copy, Clone)]
+pub struct DmaBufPlane<'a> {
+    /// File descriptor of the buffer that contains the plane.
+    pub fd: BorrowedFd<'a>,
+    /// Offset of the plane in the buffer, in bytes.
+    pub offset: u32,
+    /// Number of bytes between the start of two rows of the plane.
+    pub stride: u32,
+}
+
+impl Texture2d {
+    /// Builds a texture whose content is the content of an `EGLImage`.
+    ///
+    /// The texture and the image share their storage. Modifying one modifies the other, and
+    /// the texture can't be resized. The image can be destroyed afterwards, the storage is
+    /// kept alive by the texture.
+    ///
+    /// `format` and `dimensions` must match the image. They are only used by glium and aren't
+    /// checked.
+    ///
+    /// # Safety
+    ///
+    /// `image` must be a valid `EGLImage` of the display of the OpenGL context of `facade`.
+    pub unsafe fn from_egl_image<F: ?Sized>(facade: &F, image: *const c_void,
+                                            format: UncompressedFloatFormat,
+                                            dimensions: (u32, u32))
+                                            -> Result<Texture2d, TextureImportError>
+        where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+        let id = texture_from_egl_image(&mut ctxt, image)?;
+        drop(ctxt);
+
+        Ok(Texture2d::from_id(facade, format, id, true, MipmapsOption::NoMipmap,
+                              Dimensions::Texture2d { width: dimensions.0,
+                                                      height: dimensions.1 }))
+    }
+
+    /// Builds a texture whose content is the content of a DMA-BUF.
+    ///
+    /// `fourcc` is the DRM format of the buffer, for example `DRM_FORMAT_ARGB8888`, and
+    /// `modifier` its DRM format modifier, if any. Multi-planar formats such as NV12 need one
+    /// entry per plane in `planes`, and many drivers can't import them as a 2D texture.
+    ///
+    /// The texture and the buffer share their storage, see `from_egl_image`. The file
+    /// descriptors can be closed afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The planes must describe a valid buffer of `dimensions` pixels with the format `fourcc`.
+    /// `egl_display` must be the display of the OpenGL context of `facade`.
+    pub unsafe fn from_dmabuf<F: ?Sized>(facade: &F, egl_display: &EglDisplay,
+                                         planes: &[DmaBufPlane<'_>], fourcc: u32,
+                                         modifier: Option<u64>,
+                                         format: UncompressedFloatFormat,
+                                         dimensions: (u32, u32))
+                                         -> Result<Texture2d, TextureImportError>
+        where F: Facade
+    {
+        assert!(!planes.is_empty() && planes.len() <= EGL_DMA_BUF_PLANE_ATTRIBUTES.len(),
+                "A DMA-BUF must have between 1 and 4 planes");
+
+        let mut attributes = vec![
+            EGL_WIDTH, dimensions.0 as i32,
+            EGL_HEIGHT, dimensions.1 as i32,
+            EGL_LINUX_DRM_FOURCC_EXT, fourcc as i32,
+        ];
+
+        for (plane, names) in planes.iter().zip(EGL_DMA_BUF_PLANE_ATTRIBUTES.iter()) {
+            attributes.extend_from_slice(&[
+                names[0], plane.fd.as_raw_fd(),
+                names[1], plane.offset as i32,
+                names[2], plane.stride as i32,
+            ]);
+
+            if let Some(modifier) = modifier {
+                attributes.extend_from_slice(&[
+                    names[3], modifier as u32 as i32,
+                    names[4], (modifier >> 32) as u32 as i32,
+                ]);
+            }
+        }
+
+        attributes.push(EGL_NONE);
+
+        let image = (egl_display.create_image)(egl_display.display, ptr::null(),
+                                               EGL_LINUX_DMA_BUF_EXT, ptr::null(),
+                                               attributes.as_ptr());
+        if image.is_null() {
+            return Err(TextureImportError::EglImageCreation((egl_display.get_error)()));
+        }
+
+        let texture = Texture2d::from_egl_image(facade, image, format, dimensions);
+        (egl_display.destroy_image)(egl_display.display, image);
+        texture
+    }
+}
+
+/// Creates a texture and binds `image` as its storage. Returns the id of the texture.
+unsafe fn texture_from_egl_image(ctxt: &mut CommandContext<'_>, image: *const c_void)
+                                 -> Result<gl::types::GLuint, TextureImportError>
+{
+    if !ctxt.extensions.gl_oes_egl_image {
+        return Err(TextureImportError::EglImageNotSupported);
+    }
+
+    let mut id = 0;
+    ctxt.gl.GenTextures(1, &mut id);
+
+    ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+    let act = ctxt.state.active_texture as usize;
+    ctxt.state.texture_units[act].texture = id;
+
+    ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+    ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+    ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+    ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+    ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_2D, image);
+
+    Ok(id)
+}
@@ -0,0 +1,678 @@
+#![cfg(feature = "loader")]
+/*!
+
+Loading of textures from DDS and KTX2 files.
+
+`load` reads the header of a file and creates a texture of the right type with all the images
+and mipmap levels that the file contains. 2D textures, 2D texture arrays and cubemaps are
+supported, in both linear and sRGB color spaces.
+
+The texels must use one of the following formats:
+
+- Uncompressed formats with 8-bit components: R, RG, RGB and RGBA.
+- S3TC (BC1, BC2 and BC3), RGTC (BC4 and BC5) and BPTC (BC6H and BC7).
+- ETC2, EAC and ASTC.
+
+Supercompressed KTX2 files aren't supported.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::texture::loader::{self, LoadedTexture};
+
+let bytes = std::fs::read("skybox.ktx2").unwrap();
+let skybox = match loader::load(&display, &bytes).unwrap() {
+    LoadedTexture::CompressedSrgbCubemap(cubemap) => cubemap,
+    _ => panic!("The skybox must be a compressed sRGB cubemap"),
+};
+# }
+```
+
+# Features
+
+Only available if the 'loader' feature is enabled.
+
+*/
+use std::cmp;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::backend::Facade;
+use crate::image_format::{ClientFormatAny, TextureFormatRequest};
+use crate::texture::any::{self, Dimensions, TextureAny};
+use crate::texture::{AstcBlockSize, ClientFormat, CompressedFormat, CompressedSrgbFormat};
+use crate::texture::{MipmapsOption, SrgbFormat, TextureCreationError, TextureFormat};
+use crate::texture::UncompressedFloatFormat;
+use crate::texture::{Texture2d, SrgbTexture2d, CompressedTexture2d, CompressedSrgbTexture2d};
+use crate::texture::{Texture2dArray, SrgbTexture2dArray, CompressedTexture2dArray};
+use crate::texture::{CompressedSrgbTexture2dArray, Cubemap, SrgbCubemap, CompressedCubemap};
+use crate::texture::CompressedSrgbCubemap;
+
+/// Error that can happen when loading a texture.
+#[derive(Debug)]
+pub enum LoaderError {
+    /// The data doesn't start with the signature of a DDS or KTX2 file.
+    UnknownContainer,
+
+    /// The data is truncated, or its header is invalid.
+    InvalidData,
+
+    /// The texels use a format that the loader doesn't support.
+    UnsupportedFormat,
+
+    /// The file contains a type of texture that the loader doesn't support, such as 3D
+    /// textures or cubemap arrays, or is supercompressed.
+    UnsupportedFeature,
+
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for LoaderError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoaderError::UnknownContainer => write!(fmt, "The data isn't a DDS or KTX2 file"),
+            LoaderError::InvalidData => write!(fmt, "The data is truncated or its header is invalid"),
+            LoaderError::UnsupportedFormat => write!(fmt, "The format of the texels isn't supported"),
+            LoaderError::UnsupportedFeature => write!(fmt, "This type of texture isn't supported"),
+            LoaderError::TextureCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for LoaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoaderError::TextureCreationError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for LoaderError {
+    #[inline]
+    fn from(err: TextureCreationError) -> LoaderError {
+        LoaderError::TextureCreationError(err)
+    }
+}
+
+/// A texture created by `load`. The variant depends on the content of the file.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum LoadedTexture {
+    Texture2d(Texture2d),
+    SrgbTexture2d(SrgbTexture2d),
+    CompressedTexture2d(CompressedTexture2d),
+    CompressedSrgbTexture2d(CompressedSrgbTexture2d),
+    Texture2dArray(Texture2dArray),
+    SrgbTexture2dArray(SrgbTexture2dArray),
+    CompressedTexture2dArray(CompressedTexture2dArray),
+    CompressedSrgbTexture2dArray(CompressedSrgbTexture2dArray),
+    Cubemap(Cubemap),
+    SrgbCubemap(SrgbCubemap),
+    CompressedCubemap(CompressedCubemap),
+    CompressedSrgbCubemap(CompressedSrgbCubemap),
+}
+
+impl Deref for LoadedTexture {
+    type Target = TextureAny;
+
+    fn deref(&self) -> &TextureAny {
+        match self {
+            LoadedTexture::Texture2d(t) => t,
+            LoadedTexture::SrgbTexture2d(t) => t,
+            LoadedTexture::CompressedTexture2d(t) => t,
+            LoadedTexture::CompressedSrgbTexture2d(t) => t,
+            LoadedTexture::Texture2dArray(t) => t,
+            LoadedTexture::SrgbTexture2dArray(t) => t,
+            LoadedTexture::CompressedTexture2dArray(t) => t,
+            LoadedTexture::CompressedSrgbTexture2dArray(t) => t,
+            LoadedTexture::Cubemap(t) => t,
+            LoadedTexture::SrgbCubemap(t) => t,
+            LoadedTexture::CompressedCubemap(t) => t,
+            LoadedTexture::CompressedSrgbCubemap(t) => t,
+        }
+    }
+}
+
+/// Builds a texture from the content of a DDS or KTX2 file.
+///
+/// The type of container is detected from the first bytes of `data`.
+pub fn load<F: ?Sized>(facade: &F, data: &[u8]) -> Result<LoadedTexture, LoaderError>
+                       where F: Facade
+{
+    create(facade, &parse(data)?)
+}
+
+/// Format of the texels of a file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Uncompressed(ClientFormat, UncompressedFloatFormat),
+    Srgb(ClientFormat, SrgbFormat),
+    Compressed(CompressedFormat),
+    CompressedSrgb(CompressedSrgbFormat),
+}
+
+impl Format {
+    fn client_format(self) -> ClientFormatAny {
+        match self {
+            Format::Uncompressed(client, _) | Format::Srgb(client, _) => {
+                ClientFormatAny::ClientFormat(client)
+            },
+            Format::Compressed(format) => ClientFormatAny::CompressedFormat(format),
+            Format::CompressedSrgb(format) => ClientFormatAny::CompressedSrgbFormat(format),
+        }
+    }
+
+    fn texture_format(self) -> TextureFormat {
+        match self {
+            Format::Uncompressed(_, format) => TextureFormat::UncompressedFloat(format),
+            Format::Srgb(_, format) => TextureFormat::Srgb(format),
+            Format::Compressed(format) => TextureFormat::CompressedFormat(format),
+            Format::CompressedSrgb(format) => TextureFormat::CompressedSrgbFormat(format),
+        }
+    }
+
+    /// Number of bytes of an image of the given dimensions.
+    fn image_size(self, width: u32, height: u32) -> usize {
+        self.texture_format().get_image_size(width, height, 1)
+    }
+}
+
+/// Content of a file, borrowed from its bytes.
+#[derive(Debug)]
+struct Container<'a> {
+    format: Format,
+    width: u32,
+    height: u32,
+    /// Number of layers, or `None` if the texture isn't an array.
+    array_size: Option<u32>,
+    cubemap: bool,
+    /// Images of each mipmap level, ordered by layer then by face.
+    images: Vec<Vec<&'a [u8]>>,
+}
+
+fn parse(data: &[u8]) -> Result<Container<'_>, LoaderError> {
+    if data.starts_with(DDS_MAGIC) {
+        parse_dds(data)
+    } else if data.starts_with(KTX2_MAGIC) {
+        parse_ktx2(data)
+    } else {
+        Err(LoaderError::UnknownContainer)
+    }
+}
+
+fn create<F: ?Sized>(facade: &F, container: &Container<'_>) -> Result<LoadedTexture, LoaderError>
+                     where F: Facade
+{
+    let Container { format, width, height, array_size, cubemap, ref images } = *container;
+
+    let dimensions = match (cubemap, array_size) {
+        (false, None) => Dimensions::Texture2d { width, height },
+        (false, Some(array_size)) => Dimensions::Texture2dArray { width, height, array_size },
+        (true, None) if width == height => Dimensions::Cubemap { dimension: width },
+        (true, None) => return Err(LoaderError::InvalidData),
+        (true, Some(_)) => return Err(LoaderError::UnsupportedFeature),
+    };
+
+    let mipmaps = match images.len() as u32 {
+        1 => MipmapsOption::NoMipmap,
+        levels => MipmapsOption::EmptyMipmapsMax(levels - 1),
+    };
+
+    let texture = any::new_texture::<_, u8>(facade,
+                                            TextureFormatRequest::Specific(format.texture_format()),
+                                            None, mipmaps, dimensions)?;
+
+    for (level, level_images) in images.iter().enumerate() {
+        for (layer, image) in level_images.iter().enumerate() {
+            texture.upload_image(level as u32, layer as u32, format.client_format(), image)?;
+        }
+    }
+
+    Ok(match (format, dimensions) {
+        (Format::Uncompressed(..), Dimensions::Texture2d { .. }) => {
+            LoadedTexture::Texture2d(Texture2d::from_any(texture))
+        },
+        (Format::Srgb(..), Dimensions::Texture2d { .. }) => {
+            LoadedTexture::SrgbTexture2d(SrgbTexture2d::from_any(texture))
+        },
+        (Format::Compressed(_), Dimensions::Texture2d { .. }) => {
+            LoadedTexture::CompressedTexture2d(CompressedTexture2d::from_any(texture))
+        },
+        (Format::CompressedSrgb(_), Dimensions::Texture2d { .. }) => {
+            LoadedTexture::CompressedSrgbTexture2d(CompressedSrgbTexture2d::from_any(texture))
+        },
+        (Format::Uncompressed(..), Dimensions::Texture2dArray { .. }) => {
+            LoadedTexture::Texture2dArray(Texture2dArray::from_any(texture))
+        },
+        (Format::Srgb(..), Dimensions::Texture2dArray { .. }) => {
+            LoadedTexture::SrgbTexture2dArray(SrgbTexture2dArray::from_any(texture))
+        },
+        (Format::Compressed(_), Dimensions::Texture2dArray { .. }) => {
+            LoadedTexture::CompressedTexture2dArray(CompressedTexture2dArray::from_any(texture))
+        },
+        (Format::CompressedSrgb(_), Dimensions::Texture2dArray { .. }) => {
+            LoadedTexture::CompressedSrgbTexture2dArray(
+                CompressedSrgbTexture2dArray::from_any(texture))
+        },
+        (Format::Uncompressed(..), Dimensions::Cubemap { .. }) => {
+            LoadedTexture::Cubemap(Cubemap::from_any(texture))
+        },
+        (Format::Srgb(..), Dimensions::Cubemap { .. }) => {
+            LoadedTexture::SrgbCubemap(SrgbCubemap::from_any(texture))
+        },
+        (Format::Compressed(_), Dimensions::Cubemap { .. }) => {
+            LoadedTexture::CompressedCubemap(CompressedCubemap::from_any(texture))
+        },
+        (Format::CompressedSrgb(_), Dimensions::Cubemap { .. }) => {
+            LoadedTexture::CompressedSrgbCubemap(CompressedSrgbCubemap::from_any(texture))
+        },
+        _ => unreachable!(),
+    })
+}
+
+/// Returns the dimension of a mipmap level.
+#[inline]
+fn level_dimension(dimension: u32, level: u32) -> u32 {
+    cmp::max(1, dimension >> level)
+}
+
+/// Checks the dimensions and the number of mipmap levels found in a header.
+fn check_dimensions(width: u32, height: u32, levels: u32) -> Result<(), LoaderError> {
+    let max_levels = 32 - cmp::max(width, height).leading_zeros();
+    if width == 0 || height == 0 || levels > max_levels {
+        return Err(LoaderError::InvalidData);
+    }
+    Ok(())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, LoaderError> {
+    let bytes = data.get(offset .. offset + 4).ok_or(LoaderError::InvalidData)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<usize, LoaderError> {
+    let low = read_u32(data, offset)? as u64;
+    let high = read_u32(data, offset + 4)? as u64;
+    usize::try_from(high << 32 | low).map_err(|_| LoaderError::InvalidData)
+}
+
+fn get_bytes(data: &[u8], offset: usize, len: usize) -> Result<&[u8], LoaderError> {
+    let end = offset.checked_add(len).ok_or(LoaderError::InvalidData)?;
+    data.get(offset .. end).ok_or(LoaderError::InvalidData)
+}
+
+const DDS_MAGIC: &[u8] = b"DDS ";
+
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xfc00;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+const D3D10_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+fn parse_dds(data: &[u8]) -> Result<Container<'_>, LoaderError> {
+    if read_u32(data, 4)? != 124 {
+        return Err(LoaderError::InvalidData);
+    }
+
+    let flags = read_u32(data, 8)?;
+    let height = read_u32(data, 12)?;
+    let width = read_u32(data, 16)?;
+    let levels = if flags & DDSD_MIPMAPCOUNT != 0 { cmp::max(1, read_u32(data, 28)?) } else { 1 };
+    let pixel_flags = read_u32(data, 80)?;
+    let four_cc = get_bytes(data, 84, 4)?;
+    let caps2 = read_u32(data, 112)?;
+    check_dimensions(width, height, levels)?;
+
+    if caps2 & DDSCAPS2_VOLUME != 0 {
+        return Err(LoaderError::UnsupportedFeature);
+    }
+
+    let mut cubemap = caps2 & DDSCAPS2_CUBEMAP != 0;
+    if cubemap && caps2 & DDSCAPS2_CUBEMAP_ALLFACES != DDSCAPS2_CUBEMAP_ALLFACES {
+        return Err(LoaderError::UnsupportedFeature);
+    }
+
+    let mut array_size = None;
+    let mut offset = 128;
+
+    let format = if pixel_flags & DDPF_FOURCC != 0 {
+        match four_cc {
+            b"DX10" => {
+                let dxgi_format = read_u32(data, 128)?;
+                let dimension = read_u32(data, 132)?;
+                let misc_flags = read_u32(data, 136)?;
+                let size = read_u32(data, 140)?;
+                offset = 148;
+
+                if dimension != D3D10_RESOURCE_DIMENSION_TEXTURE2D {
+                    return Err(LoaderError::UnsupportedFeature);
+                }
+
+                cubemap = misc_flags & D3D10_RESOURCE_MISC_TEXTURECUBE != 0;
+                match size {
+                    0 => return Err(LoaderError::InvalidData),
+                    1 => (),
+                    _ if cubemap => return Err(LoaderError::UnsupportedFeature),
+                    size => array_size = Some(size),
+                }
+
+                dxgi_to_format(dxgi_format)?
+            },
+            b"DXT1" if pixel_flags & DDPF_ALPHAPIXELS != 0 => {
+                Format::Compressed(CompressedFormat::S3tcDxt1Alpha)
+            },
+            b"DXT1" => Format::Compressed(CompressedFormat::S3tcDxt1NoAlpha),
+            b"DXT3" => Format::Compressed(CompressedFormat::S3tcDxt3Alpha),
+            b"DXT5" => Format::Compressed(CompressedFormat::S3tcDxt5Alpha),
+            b"ATI1" | b"BC4U" => Format::Compressed(CompressedFormat::RgtcFormatU),
+            b"BC4S" => Format::Compressed(CompressedFormat::RgtcFormatI),
+            b"ATI2" | b"BC5U" => Format::Compressed(CompressedFormat::RgtcFormatUU),
+            b"BC5S" => Format::Compressed(CompressedFormat::RgtcFormatII),
+            _ => return Err(LoaderError::UnsupportedFormat),
+        }
+
+    } else if pixel_flags & DDPF_RGB != 0 {
+        let masks = (read_u32(data, 92)?, read_u32(data, 96)?, read_u32(data, 100)?,
+                     if pixel_flags & DDPF_ALPHAPIXELS != 0 { read_u32(data, 104)? } else { 0 });
+
+        match (read_u32(data, 88)?, masks) {
+            (32, (0xff, 0xff00, 0xff0000, 0xff000000)) => {
+                Format::Uncompressed(ClientFormat::U8U8U8U8, UncompressedFloatFormat::U8U8U8U8)
+            },
+            (24, (0xff, 0xff00, 0xff0000, 0)) => {
+                Format::Uncompressed(ClientFormat::U8U8U8, UncompressedFloatFormat::U8U8U8)
+            },
+            _ => return Err(LoaderError::UnsupportedFormat),
+        }
+
+    } else {
+        return Err(LoaderError::UnsupportedFormat);
+    };
+
+    // DDS files store all the levels of an image before the next image
+    let images_count = array_size.unwrap_or(1) * if cubemap { 6 } else { 1 };
+    let mut images = vec![Vec::with_capacity(images_count as usize); levels as usize];
+    for _ in 0 .. images_count {
+        for (level, level_images) in images.iter_mut().enumerate() {
+            let size = format.image_size(level_dimension(width, level as u32),
+                                         level_dimension(height, level as u32));
+            level_images.push(get_bytes(data, offset, size)?);
+            offset += size;
+        }
+    }
+
+    Ok(Container { format, width, height, array_size, cubemap, images })
+}
+
+fn dxgi_to_format(format: u32) -> Result<Format, LoaderError> {
+    Ok(match format {
+        28 => Format::Uncompressed(ClientFormat::U8U8U8U8, UncompressedFloatFormat::U8U8U8U8),
+        29 => Format::Srgb(ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8),
+        49 => Format::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8),
+        61 => Format::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8),
+        71 => Format::Compressed(CompressedFormat::S3tcDxt1Alpha),
+        72 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1Alpha),
+        74 => Format::Compressed(CompressedFormat::S3tcDxt3Alpha),
+        75 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt3Alpha),
+        77 => Format::Compressed(CompressedFormat::S3tcDxt5Alpha),
+        78 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt5Alpha),
+        80 => Format::Compressed(CompressedFormat::RgtcFormatU),
+        81 => Format::Compressed(CompressedFormat::RgtcFormatI),
+        83 => Format::Compressed(CompressedFormat::RgtcFormatUU),
+        84 => Format::Compressed(CompressedFormat::RgtcFormatII),
+        95 => Format::Compressed(CompressedFormat::BptcUnsignedFloat3),
+        96 => Format::Compressed(CompressedFormat::BptcSignedFloat3),
+        98 => Format::Compressed(CompressedFormat::BptcUnorm4),
+        99 => Format::CompressedSrgb(CompressedSrgbFormat::Bptc),
+        _ => return Err(LoaderError::UnsupportedFormat),
+    })
+}
+
+const KTX2_MAGIC: &[u8] = &[0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb, b'\r', b'\n', 0x1a, b'\n'];
+
+fn parse_ktx2(data: &[u8]) -> Result<Container<'_>, LoaderError> {
+    let vk_format = read_u32(data, 12)?;
+    let width = read_u32(data, 20)?;
+    let height = read_u32(data, 24)?;
+    let depth = read_u32(data, 28)?;
+    let layers = read_u32(data, 32)?;
+    let faces = read_u32(data, 36)?;
+    let levels = cmp::max(1, read_u32(data, 40)?);
+    let supercompression = read_u32(data, 44)?;
+
+    // 1D and 3D textures
+    if height == 0 || depth != 0 {
+        return Err(LoaderError::UnsupportedFeature);
+    }
+
+    if supercompression != 0 {
+        return Err(LoaderError::UnsupportedFeature);
+    }
+
+    check_dimensions(width, height, levels)?;
+
+    let cubemap = match faces {
+        1 => false,
+        6 => true,
+        _ => return Err(LoaderError::InvalidData),
+    };
+
+    // a layer count of 0 means that the texture isn't an array
+    let array_size = match layers {
+        0 => None,
+        _ if cubemap => return Err(LoaderError::UnsupportedFeature),
+        layers => Some(layers),
+    };
+
+    let format = vk_to_format(vk_format)?;
+    let images_count = (array_size.unwrap_or(1) * faces) as usize;
+
+    let mut images = Vec::with_capacity(levels as usize);
+    for level in 0 .. levels {
+        let index = 80 + level as usize * 24;
+        let offset = read_u64(data, index)?;
+        let length = read_u64(data, index + 8)?;
+
+        let size = format.image_size(level_dimension(width, level),
+                                     level_dimension(height, level));
+        if size.checked_mul(images_count) != Some(length) {
+            return Err(LoaderError::InvalidData);
+        }
+
+        images.push(get_bytes(data, offset, length)?.chunks(size).collect());
+    }
+
+    Ok(Container { format, width, height, array_size, cubemap, images })
+}
+
+fn vk_to_format(format: u32) -> Result<Format, LoaderError> {
+    Ok(match format {
+        9 => Format::Uncompressed(ClientFormat::U8, UncompressedFloatFormat::U8),
+        16 => Format::Uncompressed(ClientFormat::U8U8, UncompressedFloatFormat::U8U8),
+        23 => Format::Uncompressed(ClientFormat::U8U8U8, UncompressedFloatFormat::U8U8U8),
+        29 => Format::Srgb(ClientFormat::U8U8U8, SrgbFormat::U8U8U8),
+        37 => Format::Uncompressed(ClientFormat::U8U8U8U8, UncompressedFloatFormat::U8U8U8U8),
+        43 => Format::Srgb(ClientFormat::U8U8U8U8, SrgbFormat::U8U8U8U8),
+        131 => Format::Compressed(CompressedFormat::S3tcDxt1NoAlpha),
+        132 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1NoAlpha),
+        133 => Format::Compressed(CompressedFormat::S3tcDxt1Alpha),
+        134 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt1Alpha),
+        135 => Format::Compressed(CompressedFormat::S3tcDxt3Alpha),
+        136 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt3Alpha),
+        137 => Format::Compressed(CompressedFormat::S3tcDxt5Alpha),
+        138 => Format::CompressedSrgb(CompressedSrgbFormat::S3tcDxt5Alpha),
+        139 => Format::Compressed(CompressedFormat::RgtcFormatU),
+        140 => Format::Compressed(CompressedFormat::RgtcFormatI),
+        141 => Format::Compressed(CompressedFormat::RgtcFormatUU),
+        142 => Format::Compressed(CompressedFormat::RgtcFormatII),
+        143 => Format::Compressed(CompressedFormat::BptcUnsignedFloat3),
+        144 => Format::Compressed(CompressedFormat::BptcSignedFloat3),
+        145 => Format::Compressed(CompressedFormat::BptcUnorm4),
+        146 => Format::CompressedSrgb(CompressedSrgbFormat::Bptc),
+        147 => Format::Compressed(CompressedFormat::Etc2Rgb8),
+        148 => Format::CompressedSrgb(CompressedSrgbFormat::Etc2Rgb8),
+        149 => Format::Compressed(CompressedFormat::Etc2Rgb8A1),
+        150 => Format::CompressedSrgb(CompressedSrgbFormat::Etc2Rgb8A1),
+        151 => Format::Compressed(CompressedFormat::Etc2Rgba8),
+        152 => Format::CompressedSrgb(CompressedSrgbFormat::Etc2Rgba8),
+        153 => Format::Compressed(CompressedFormat::EacR11U),
+        154 => Format::Compressed(CompressedFormat::EacR11I),
+        155 => Format::Compressed(CompressedFormat::EacRg11U),
+        156 => Format::Compressed(CompressedFormat::EacRg11I),
+
+        // the ASTC formats come in pairs of UNORM and SRGB, in the order of `AstcBlockSize`
+        157 ..= 184 => {
+            let size = AstcBlockSize::get_sizes_list()[(format as usize - 157) / 2];
+            if format % 2 == 1 {
+                Format::Compressed(CompressedFormat::Astc(size))
+            } else {
+                Format::CompressedSrgb(CompressedSrgbFormat::Astc(size))
+            }
+        },
+
+        _ => return Err(LoaderError::UnsupportedFormat),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Format, LoaderError, DDS_MAGIC, KTX2_MAGIC};
+    use crate::texture::{AstcBlockSize, ClientFormat, CompressedFormat, CompressedSrgbFormat};
+    use crate::texture::UncompressedFloatFormat;
+
+    fn push_u32(data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Builds the header of a DDS file that uses a FourCC.
+    fn dds_header(width: u32, height: u32, levels: u32, four_cc: &[u8; 4], caps2: u32) -> Vec<u8> {
+        let mut data = DDS_MAGIC.to_vec();
+        push_u32(&mut data, 124);
+        push_u32(&mut data, 0x1007 | 0x20000);
+        push_u32(&mut data, height);
+        push_u32(&mut data, width);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, levels);
+        data.resize(76, 0);
+        push_u32(&mut data, 32);
+        push_u32(&mut data, 0x4);
+        data.extend_from_slice(four_cc);
+        data.resize(112, 0);
+        push_u32(&mut data, caps2);
+        data.resize(128, 0);
+        data
+    }
+
+    /// Builds a KTX2 file whose levels contain `level_sizes` bytes each.
+    fn ktx2(vk_format: u32, width: u32, height: u32, layers: u32, faces: u32,
+            level_sizes: &[usize]) -> Vec<u8>
+    {
+        let mut data = KTX2_MAGIC.to_vec();
+        for &value in &[vk_format, 1, width, height, 0, layers, faces,
+                        level_sizes.len() as u32, 0]
+        {
+            push_u32(&mut data, value);
+        }
+        data.resize(80, 0);
+
+        let mut offset = 80 + 24 * level_sizes.len();
+        for &size in level_sizes {
+            data.extend_from_slice(&(offset as u64).to_le_bytes());
+            data.extend_from_slice(&(size as u64).to_le_bytes());
+            data.extend_from_slice(&(size as u64).to_le_bytes());
+            offset += size;
+        }
+
+        for (level, &size) in level_sizes.iter().enumerate() {
+            data.extend(std::iter::repeat(level as u8).take(size));
+        }
+
+        data
+    }
+
+    #[test]
+    fn dds_dxt1_mipmaps() {
+        // levels of 8x8, 4x4, 2x2 and 1x1 texels, with 8 bytes per block
+        let mut data = dds_header(8, 8, 4, b"DXT1", 0);
+        data.extend(std::iter::repeat(0).take(32 + 8 + 8 + 8));
+
+        let container = parse(&data).unwrap();
+        assert_eq!(container.format, Format::Compressed(CompressedFormat::S3tcDxt1NoAlpha));
+        assert_eq!((container.width, container.height), (8, 8));
+        assert_eq!(container.array_size, None);
+        assert!(!container.cubemap);
+
+        let sizes: Vec<_> = container.images.iter().map(|l| (l.len(), l[0].len())).collect();
+        assert_eq!(sizes, vec![(1, 32), (1, 8), (1, 8), (1, 8)]);
+
+        data.pop();
+        assert!(matches!(parse(&data), Err(LoaderError::InvalidData)));
+    }
+
+    #[test]
+    fn dds_cubemap_order() {
+        // each face stores its two levels before the next face
+        let mut data = dds_header(4, 4, 2, b"DXT5", 0x200 | 0xfc00);
+        for face in 0 .. 6u8 {
+            data.extend(std::iter::repeat(face).take(16));
+            data.extend(std::iter::repeat(face + 10).take(16));
+        }
+
+        let container = parse(&data).unwrap();
+        assert!(container.cubemap);
+        assert_eq!(container.images.len(), 2);
+        assert_eq!(container.images[0].len(), 6);
+        assert_eq!(container.images[0][3][0], 3);
+        assert_eq!(container.images[1][3][0], 13);
+    }
+
+    #[test]
+    fn ktx2_srgb_array() {
+        // two layers of 2x2 then 1x1 RGBA texels
+        let data = ktx2(43, 2, 2, 2, 1, &[2 * 16, 2 * 4]);
+
+        let container = parse(&data).unwrap();
+        assert_eq!(container.format, Format::Srgb(ClientFormat::U8U8U8U8,
+                                                  crate::texture::SrgbFormat::U8U8U8U8));
+        assert_eq!(container.array_size, Some(2));
+        assert_eq!(container.images[0].len(), 2);
+        assert_eq!(container.images[0][1].len(), 16);
+        assert_eq!(container.images[1][1], &[1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn ktx2_formats() {
+        let data = ktx2(23, 1, 1, 0, 1, &[3]);
+        assert_eq!(parse(&data).unwrap().format,
+                   Format::Uncompressed(ClientFormat::U8U8U8, UncompressedFloatFormat::U8U8U8));
+
+        // ASTC 6x5 UNORM and 12x12 SRGB
+        let data = ktx2(163, 6, 5, 0, 1, &[16]);
+        assert_eq!(parse(&data).unwrap().format,
+                   Format::Compressed(CompressedFormat::Astc(AstcBlockSize::Block6x5)));
+        let data = ktx2(184, 13, 12, 0, 1, &[32]);
+        assert_eq!(parse(&data).unwrap().format,
+                   Format::CompressedSrgb(CompressedSrgbFormat::Astc(AstcBlockSize::Block12x12)));
+
+        // level of the wrong size
+        let data = ktx2(37, 2, 2, 0, 1, &[15]);
+        assert!(matches!(parse(&data), Err(LoaderError::InvalidData)));
+
+        let data = ktx2(0, 2, 2, 0, 1, &[16]);
+        assert!(matches!(parse(&data), Err(LoaderError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn unknown_container() {
+        assert!(matches!(parse(b"\x89PNG\r\n\x1a\n"), Err(LoaderError::UnknownContainer)));
+    }
+}
@@ -121,24 +121,32 @@ use crate::image_format::FormatNotSupportedError;
 pub use crate::image_format::{ClientFormat, TextureFormat};
 pub use crate::image_format::{UncompressedFloatFormat, UncompressedIntFormat, UncompressedUintFormat};
 pub use crate::image_format::{CompressedFormat, DepthFormat, DepthStencilFormat, StencilFormat};
-pub use crate::image_format::{CompressedSrgbFormat, SrgbFormat};
+pub use crate::image_format::{CompressedSrgbFormat, SrgbFormat, AstcBlockSize};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
-pub use self::any::{TextureAnyImage, Dimensions};
+pub use self::any::{TextureAnyImage, TextureTransfer, Dimensions};
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
 pub use self::pixel::PixelValue;
+pub use self::sparse::{CommitRegionError, SparseTexture2d, SparseTextureCreationError};
 pub use self::ty_support::{is_texture_1d_supported, is_texture_2d_supported};
 pub use self::ty_support::{is_texture_3d_supported, is_texture_1d_array_supported};
 pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisample_supported};
 pub use self::ty_support::{is_texture_2d_multisample_array_supported, is_cubemaps_supported};
-pub use self::ty_support::is_cubemap_arrays_supported;
+pub use self::ty_support::{is_cubemap_arrays_supported, is_immutable_storage_supported};
 pub use self::texture_import::ExternalTilingMode;
 pub use self::texture_import::ImportParameters;
 pub use self::texture_import::TextureImportError;
 
+#[cfg(feature = "atlas")]
+pub mod atlas;
 pub mod bindless;
 pub mod buffer_texture;
+#[cfg(all(target_os = "linux", feature = "egl_image"))]
+pub mod egl_image;
+#[cfg(feature = "loader")]
+pub mod loader;
 pub mod pixel_buffer;
+pub mod sparse;
 
 mod any;
 mod get_format;
@@ -220,6 +228,13 @@ pub enum MipmapsOption {
     /// This does not mean that you will get mipmaps, instead it indicates that mipmaps are *allowed*
     /// to be generated if possible.
     AutoGeneratedMipmapsMax(u32),
+
+    /// Allocates space for all the possible amount of mipmaps given the texture dimensions, but
+    /// does not generate them when the texture is created.
+    ///
+    /// The mipmaps are generated again each time the texture is written to. This is meant for
+    /// textures that are rendered to: draw to the main level, then call `generate_mipmaps`.
+    EmptyMipmapsThenGenerate,
 }
 
 impl MipmapsOption {
@@ -230,6 +245,13 @@ impl MipmapsOption {
         matches!(self, AutoGeneratedMipmaps | AutoGeneratedMipmapsMax(_))
     }
 
+    /// Tells whether mipmaps should be generated again after the texture is written to.
+    #[inline]
+    fn should_regenerate(self) -> bool {
+        use self::MipmapsOption::*;
+        self.should_generate() || self == EmptyMipmapsThenGenerate
+    }
+
     /// Number of levels (including the main level).
     fn num_levels(self, width: u32, height: Option<u32>, depth: Option<u32>) -> u32 {
         use self::MipmapsOption::*;
@@ -238,7 +260,7 @@ impl MipmapsOption {
 
         match self {
             NoMipmap => 1,
-            EmptyMipmaps | AutoGeneratedMipmaps => {
+            EmptyMipmaps | AutoGeneratedMipmaps | EmptyMipmapsThenGenerate => {
                 let max_dimension = cmp::max(width, cmp::max(height.unwrap_or(1),
                                              depth.unwrap_or(1))) as f32;
 
@@ -788,9 +810,10 @@ impl<'a, P: PixelValue + Clone> Texture3dDataSource<'a> for RawImage3d<'a, P> {
 }
 
 impl<P> Texture3dDataSink<P> for Vec<Vec<Vec<P>>> where P: Copy + Clone {
-    #[inline]
-    fn from_raw(_data: Cow<'_, [P]>, _width: u32, _height: u32, _depth: u32) -> Self {
-        unimplemented!()
+    fn from_raw(data: Cow<'_, [P]>, width: u32, height: u32, _depth: u32) -> Self {
+        data.chunks(width as usize * height as usize)
+            .map(|layer| layer.chunks(width as usize).map(|e| e.to_vec()).collect())
+            .collect()
     }
 }
 
@@ -808,6 +831,12 @@ pub enum TextureCreationError {
 
     /// The size of the data doesn't match the texture dimensions.
     DataSizeMismatch,
+
+    /// Immutable storage is not supported by the backend.
+    ImmutableStorageNotSupported,
+
+    /// The number of mipmap levels is `0` or is larger than what the dimensions allow.
+    MipmapLevelsOutOfRange,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -822,6 +851,10 @@ impl fmt::Display for TextureCreationError {
                 "The texture format is not supported by the backend",
             DataSizeMismatch =>
                 "The size of the data doesn't match the texture dimensions",
+            ImmutableStorageNotSupported =>
+                "Immutable storage is not supported by the backend",
+            MipmapLevelsOutOfRange =>
+                "The number of mipmap levels is zero or is larger than what the dimensions allow",
         };
         fmt.write_str(desc)
     }
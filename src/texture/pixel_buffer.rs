@@ -3,16 +3,25 @@ Pixel buffers are buffers that contain two-dimensional texture data.
 
 Contrary to textures, pixel buffers are stored in a client-defined format. They are used
 to transfer data to or from the video memory, before or after being turned into a texture.
+
+`Surface::read_to_pixel_buffer` reads the content of a surface into a new pixel buffer and
+returns a `PendingRead`, which can be polled until the data has arrived.
 */
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
 use crate::backend::Facade;
+use crate::context::Context;
+use crate::ops;
 
+use crate::ContextExt;
 use crate::GlObject;
+use crate::Rect;
 use crate::buffer::{ReadError, Buffer, BufferType, BufferMode};
 use crate::gl;
+use crate::sync::SyncFence;
 
 use crate::texture::PixelValue;
 use crate::texture::Texture2dDataSink;
@@ -71,6 +80,61 @@ impl<T> GlObject for PixelBuffer<T> where T: PixelValue {
     }
 }
 
+/// Pixels being read into a pixel buffer, returned by `Surface::read_to_pixel_buffer`.
+///
+/// The read is executed by the GPU after the commands that were issued before it. A fence is
+/// inserted after the read, so that `is_ready` can tell whether `read` would block.
+pub struct PendingRead<T> where T: PixelValue {
+    buffer: PixelBuffer<T>,
+    fence: Option<SyncFence>,
+}
+
+impl<T> PendingRead<T> where T: PixelValue {
+    /// Starts reading `rect` from `source` into a new pixel buffer.
+    pub(crate) fn new(context: &Rc<Context>, source: ops::Source<'_>, rect: &Rect)
+                      -> Result<PendingRead<T>, ops::ReadError>
+    {
+        let pixels = ops::pixels_to_read::<T>(&source, rect)?;
+        let buffer = PixelBuffer::new_empty(context, pixels);
+
+        {
+            let mut ctxt = context.make_current();
+            ops::read(&mut ctxt, source, rect, &buffer, false)?;
+        }
+
+        // if fences are not supported, reading the buffer will simply block
+        let fence = SyncFence::new(context).ok();
+
+        Ok(PendingRead {
+            buffer,
+            fence,
+        })
+    }
+
+    /// Returns true if the pixels are available and `read` won't block.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.fence.as_ref().map_or(true, |fence| fence.is_signaled())
+    }
+
+    /// Returns the pixels that have been read.
+    ///
+    /// Blocks until the pixels are available if `is_ready` returned false.
+    pub fn read<S>(mut self) -> Result<S, ReadError> where S: Texture2dDataSink<T> {
+        if let Some(fence) = self.fence.take() {
+            fence.wait();
+        }
+
+        self.buffer.read_as_texture_2d()
+    }
+
+    /// Returns the pixel buffer that receives the pixels, without waiting for the read to finish.
+    #[inline]
+    pub fn into_pixel_buffer(self) -> PixelBuffer<T> {
+        self.buffer
+    }
+}
+
 // TODO: remove this hack
 #[doc(hidden)]
 #[inline]
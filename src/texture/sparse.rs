@@ -0,0 +1,362 @@
+/*!
+
+Sparse textures, whose memory is allocated page by page.
+
+A sparse texture reserves an address space for all its texels, but no memory is used until
+regions of the texture are committed with `commit_region`. This lets a large virtual texture,
+such as the megatexture of a terrain, keep only the regions that are visible in video memory.
+
+Texels are committed by pages, whose size depends on the format of the texture. The dimensions
+of a region that is committed or decommitted must be multiples of the page size, except at the
+borders of the texture. The sizes supported by a format can be queried with
+`SparseTexture2d::page_sizes`.
+
+Sampling texels that aren't committed returns undefined values, and writing them has no effect.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+use glium::texture::{MipmapsOption, SparseTexture2d, UncompressedFloatFormat};
+
+let texture = SparseTexture2d::empty_with_format(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                 MipmapsOption::NoMipmap, 16384, 16384).unwrap();
+
+let (page_width, page_height) = texture.page_size();
+texture.commit_region(glium::Rect { left: 0, bottom: 0, width: page_width * 4,
+                                    height: page_height * 4 }, 0, true).unwrap();
+# }
+```
+
+Sparse textures are only available if the backend supports the `GL_ARB_sparse_texture`
+extension.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::backend::Facade;
+use crate::context::CommandContext;
+use crate::gl;
+use crate::image_format::{self, RequestType, TextureFormatRequest};
+use crate::texture::{Dimensions, MipmapsOption, Texture2d, TextureCreationError, TextureFormat};
+use crate::texture::UncompressedFloatFormat;
+use crate::CapabilitiesSource;
+use crate::ContextExt;
+use crate::Rect;
+use crate::TextureExt;
+
+/// Error that can happen when creating a sparse texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseTextureCreationError {
+    /// Sparse textures are not supported by the backend.
+    NotSupported,
+
+    /// The format can't be used for sparse textures, or the page size index is out of range.
+    PageSizeNotSupported,
+
+    /// The dimensions aren't multiples of the page size, or are larger than
+    /// `Context::get_max_sparse_texture_size`.
+    DimensionsNotSupported,
+
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for SparseTextureCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SparseTextureCreationError::NotSupported =>
+                write!(fmt, "Sparse textures are not supported by the backend"),
+            SparseTextureCreationError::PageSizeNotSupported =>
+                write!(fmt, "The page size is not supported for this format"),
+            SparseTextureCreationError::DimensionsNotSupported =>
+                write!(fmt, "The dimensions of the texture are not supported"),
+            SparseTextureCreationError::TextureCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for SparseTextureCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SparseTextureCreationError::TextureCreationError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for SparseTextureCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> SparseTextureCreationError {
+        SparseTextureCreationError::TextureCreationError(err)
+    }
+}
+
+/// Error that can happen when committing or decommitting a region of a sparse texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitRegionError {
+    /// The mipmap level doesn't exist.
+    LevelOutOfRange,
+
+    /// The region is out of the bounds of the mipmap level.
+    RegionOutOfBounds,
+
+    /// The region isn't aligned to the page size, or doesn't cover the whole level of the
+    /// mip tail.
+    RegionNotAligned,
+}
+
+impl fmt::Display for CommitRegionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitRegionError::LevelOutOfRange =>
+                write!(fmt, "The mipmap level doesn't exist"),
+            CommitRegionError::RegionOutOfBounds =>
+                write!(fmt, "The region is out of the bounds of the mipmap level"),
+            CommitRegionError::RegionNotAligned =>
+                write!(fmt, "The region isn't aligned to the page size"),
+        }
+    }
+}
+
+impl Error for CommitRegionError {}
+
+/// A two-dimensional texture whose memory is committed page by page.
+///
+/// Derefs to a `Texture2d`, which can be sampled and written to like any other texture.
+pub struct SparseTexture2d {
+    texture: Texture2d,
+    page_size: (u32, u32),
+    sparse_levels: u32,
+}
+
+impl SparseTexture2d {
+    /// Returns true if sparse textures are supported by the backend.
+    #[inline]
+    pub fn is_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+        facade.get_context().get_extensions().gl_arb_sparse_texture
+    }
+
+    /// Returns the list of page sizes that can be used with a format, in the order of their
+    /// index. The list is empty if the format can't be used for sparse textures.
+    pub fn page_sizes<F: ?Sized>(facade: &F, format: UncompressedFloatFormat) -> Vec<(u32, u32)>
+                                 where F: Facade
+    {
+        if !SparseTexture2d::is_supported(facade) {
+            return Vec::new();
+        }
+
+        let internal_format = match storage_internal_format(facade, format) {
+            Ok(f) => f,
+            Err(_) => return Vec::new(),
+        };
+
+        let ctxt = facade.get_context().make_current();
+        unsafe { get_page_sizes(&ctxt, internal_format) }
+    }
+
+    /// Builds a new sparse texture that uses the first page size of the format. No page is
+    /// committed.
+    #[inline]
+    pub fn empty_with_format<F: ?Sized>(facade: &F, format: UncompressedFloatFormat,
+                                        mipmaps: MipmapsOption, width: u32, height: u32)
+                                        -> Result<SparseTexture2d, SparseTextureCreationError>
+                                        where F: Facade
+    {
+        SparseTexture2d::empty_with_page_size(facade, format, mipmaps, width, height, 0)
+    }
+
+    /// Builds a new sparse texture that uses the page size at index `page_size_index` in the
+    /// list returned by `page_sizes`. No page is committed.
+    ///
+    /// The width and height must be multiples of the page size. Mipmaps are never generated
+    /// automatically, as the content of the texture is undefined until it is committed.
+    pub fn empty_with_page_size<F: ?Sized>(facade: &F, format: UncompressedFloatFormat,
+                                           mipmaps: MipmapsOption, width: u32, height: u32,
+                                           page_size_index: usize)
+                                           -> Result<SparseTexture2d, SparseTextureCreationError>
+                                           where F: Facade
+    {
+        if !SparseTexture2d::is_supported(facade) ||
+           !super::is_immutable_storage_supported(facade.get_context())
+        {
+            return Err(SparseTextureCreationError::NotSupported);
+        }
+
+        let internal_format = storage_internal_format(facade, format)?;
+
+        let page_size = {
+            let ctxt = facade.get_context().make_current();
+            let page_sizes = unsafe { get_page_sizes(&ctxt, internal_format) };
+            match page_sizes.get(page_size_index) {
+                Some(&(w, h)) if w != 0 && h != 0 => (w, h),
+                _ => return Err(SparseTextureCreationError::PageSizeNotSupported),
+            }
+        };
+
+        let max_size = facade.get_context().get_max_sparse_texture_size().unwrap_or(0);
+        if width == 0 || height == 0 || width > max_size || height > max_size ||
+           width % page_size.0 != 0 || height % page_size.1 != 0
+        {
+            return Err(SparseTextureCreationError::DimensionsNotSupported);
+        }
+
+        let levels = mipmaps.num_levels(width, Some(height), None);
+        let mipmaps = match levels {
+            1 => MipmapsOption::NoMipmap,
+            levels => MipmapsOption::EmptyMipmapsMax(levels - 1),
+        };
+
+        let id = {
+            let ctxt = facade.get_context().make_current();
+            let mut id = 0;
+            unsafe { ctxt.gl.GenTextures(1, &mut id) };
+            id
+        };
+
+        // the texture is owned, so that it is destroyed if the creation fails from now on
+        let texture = unsafe {
+            Texture2d::from_id(facade, format, id, true, mipmaps,
+                               Dimensions::Texture2d { width, height })
+        };
+
+        let sparse_levels = {
+            let mut ctxt = facade.get_context().make_current();
+            let bind_point = texture.bind_to_current(&mut ctxt);
+
+            unsafe {
+                // the sparse parameters must be set before the storage is allocated
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SPARSE_ARB, gl::TRUE as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::VIRTUAL_PAGE_SIZE_INDEX_ARB,
+                                      page_size_index as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER,
+                                      if levels > 1 { gl::LINEAR_MIPMAP_LINEAR as i32 }
+                                      else { gl::LINEAR as i32 });
+
+                ctxt.gl.TexStorage2D(bind_point, levels as gl::types::GLsizei, internal_format,
+                                     width as gl::types::GLsizei, height as gl::types::GLsizei);
+
+                let mut sparse_levels = 0;
+                ctxt.gl.GetTexParameteriv(bind_point, gl::NUM_SPARSE_LEVELS_ARB,
+                                          &mut sparse_levels);
+                sparse_levels as u32
+            }
+        };
+
+        Ok(SparseTexture2d { texture, page_size, sparse_levels })
+    }
+
+    /// Returns the width and height of a page, in texels.
+    #[inline]
+    pub fn page_size(&self) -> (u32, u32) {
+        self.page_size
+    }
+
+    /// Returns the number of mipmap levels that can be committed page by page.
+    ///
+    /// The levels that follow, which are smaller than a page, are stored together in the mip
+    /// tail. They can only be committed or decommitted as a whole.
+    #[inline]
+    pub fn sparse_levels(&self) -> u32 {
+        self.sparse_levels
+    }
+
+    /// Allocates or frees the memory of a region of a mipmap level.
+    ///
+    /// Committing a region that is already committed, or decommitting a region that isn't, has
+    /// no effect. The content of newly committed pages is undefined.
+    ///
+    /// The region must be aligned to the page size. Its dimensions must be multiples of the
+    /// page size unless it extends to the border of the level. Regions of the mip tail must
+    /// cover the whole level.
+    pub fn commit_region(&self, rect: Rect, level: u32, commit: bool)
+                         -> Result<(), CommitRegionError>
+    {
+        let mipmap = self.texture.mipmap(level).ok_or(CommitRegionError::LevelOutOfRange)?;
+        let (width, height) = (mipmap.width(), mipmap.height());
+
+        let right = rect.left.checked_add(rect.width);
+        let top = rect.bottom.checked_add(rect.height);
+        if !right.map_or(false, |right| right <= width) || !top.map_or(false, |top| top <= height) {
+            return Err(CommitRegionError::RegionOutOfBounds);
+        }
+
+        let aligned = if level < self.sparse_levels {
+            let (page_width, page_height) = self.page_size;
+            rect.left % page_width == 0 && rect.bottom % page_height == 0 &&
+            (rect.width % page_width == 0 || right == Some(width)) &&
+            (rect.height % page_height == 0 || top == Some(height))
+        } else {
+            rect.left == 0 && rect.bottom == 0 && rect.width == width && rect.height == height
+        };
+
+        if !aligned {
+            return Err(CommitRegionError::RegionNotAligned);
+        }
+
+        let mut ctxt = self.texture.get_context().make_current();
+        let bind_point = self.texture.bind_to_current(&mut ctxt);
+
+        unsafe {
+            ctxt.gl.TexPageCommitmentARB(bind_point, level as gl::types::GLint,
+                                         rect.left as gl::types::GLint,
+                                         rect.bottom as gl::types::GLint, 0,
+                                         rect.width as gl::types::GLsizei,
+                                         rect.height as gl::types::GLsizei, 1,
+                                         if commit { gl::TRUE } else { gl::FALSE });
+        }
+
+        Ok(())
+    }
+}
+
+impl Deref for SparseTexture2d {
+    type Target = Texture2d;
+
+    #[inline]
+    fn deref(&self) -> &Texture2d {
+        &self.texture
+    }
+}
+
+impl fmt::Debug for SparseTexture2d {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "SparseTexture2d {{ texture: {:?}, page_size: {:?} }}", self.texture,
+               self.page_size)
+    }
+}
+
+/// Returns the internal format used to allocate the storage of a texture.
+fn storage_internal_format<F: ?Sized>(facade: &F, format: UncompressedFloatFormat)
+                                      -> Result<gl::types::GLenum, TextureCreationError>
+                                      where F: Facade
+{
+    let request = TextureFormatRequest::Specific(TextureFormat::UncompressedFloat(format));
+    Ok(image_format::format_request_to_glenum(facade.get_context(), request,
+                                              RequestType::TexStorage)?)
+}
+
+/// Queries the page sizes of an internal format.
+unsafe fn get_page_sizes(ctxt: &CommandContext<'_>, internal_format: gl::types::GLenum)
+                         -> Vec<(u32, u32)>
+{
+    let mut num = 0;
+    ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format, gl::NUM_VIRTUAL_PAGE_SIZES_ARB,
+                                1, &mut num);
+    if num <= 0 {
+        return Vec::new();
+    }
+
+    let mut widths = vec![0; num as usize];
+    let mut heights = vec![0; num as usize];
+    ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format, gl::VIRTUAL_PAGE_SIZE_X_ARB,
+                                num, widths.as_mut_ptr());
+    ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format, gl::VIRTUAL_PAGE_SIZE_Y_ARB,
+                                num, heights.as_mut_ptr());
+
+    widths.into_iter().zip(heights).map(|(w, h)| (w as u32, h as u32)).collect()
+}
@@ -42,6 +42,15 @@ pub enum TextureImportError {
     MemoryObjectCreation(MemoryObjectCreationError),
     /// Texture format not supported by this OpenGL context
     FormatNotSupported(FormatNotSupportedError),
+    /// The OpenGL context doesn't support `GL_OES_EGL_image`.
+    EglImageNotSupported,
+    /// EGL failed to create an image. Contains the value of `eglGetError`.
+    EglImageCreation(i32),
+    /// A function required to create EGL images can't be found. Contains its name.
+    EglFunctionNotFound(&'static str),
+    /// The texture is backed by external memory, which can't be re-created after the context
+    /// has been lost. The texture must be imported again.
+    NotRecreatable,
 }
 
 impl fmt::Display for TextureImportError {
@@ -51,6 +60,10 @@ impl fmt::Display for TextureImportError {
             FormatNotPresent => write!(fmt, "A specific format for the texture was not given."),
             MemoryObjectCreation(e) => e.fmt(fmt),
             FormatNotSupported(e) => e.fmt(fmt),
+            EglImageNotSupported => write!(fmt, "EGL images are not supported by the backend."),
+            EglImageCreation(e) => write!(fmt, "EGL failed to create an image (error 0x{:x}).", e),
+            EglFunctionNotFound(name) => write!(fmt, "The EGL function `{}` can't be found.", name),
+            NotRecreatable => write!(fmt, "Textures backed by external memory can't be re-created."),
         }
     }
 }
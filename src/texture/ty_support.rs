@@ -80,3 +80,12 @@ pub fn is_cubemap_arrays_supported<C: ?Sized>(context: &C) -> bool where C: Capa
     context.get_extensions().gl_ext_texture_cube_map_array ||
     context.get_extensions().gl_oes_texture_cube_map_array
 }
+
+/// Returns true is textures with immutable storage are supported.
+///
+/// See `Texture2d::with_mipmaps_storage` for example.
+#[inline]
+pub fn is_immutable_storage_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 4, 2) ||
+    context.get_extensions().gl_arb_texture_storage
+}
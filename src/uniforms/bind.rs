@@ -5,10 +5,7 @@ Handles binding uniforms to the OpenGL state machine.
 */
 use crate::gl;
 
-use std::collections::HashMap;
-use std::hash::BuildHasherDefault;
-
-use fnv::FnvHasher;
+use smallvec::SmallVec;
 
 use crate::BufferExt;
 use crate::BufferSliceExt;
@@ -16,10 +13,13 @@ use crate::DrawError;
 use crate::ProgramExt;
 use crate::UniformsExt;
 use crate::RawUniformValue;
+use crate::RawUniformArray;
 use crate::TextureExt;
+use crate::GlObject;
 
 use crate::uniforms::Uniforms;
 use crate::uniforms::UniformValue;
+use crate::uniforms::UniformType;
 use crate::uniforms::SamplerBehavior;
 use crate::uniforms::ImageUnitBehavior;
 
@@ -35,7 +35,7 @@ use crate::version::Version;
 use crate::version::Api;
 
 impl<U> UniformsExt for U where U: Uniforms {
-    fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext<'_>, program: &P,
+    fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext<'_>, programs: &[&P],
                             fences: &mut Vec<Inserter<'a>>)
                             -> Result<(), DrawError>
                             where P: ProgramExt
@@ -47,92 +47,106 @@ impl<U> UniformsExt for U where U: Uniforms {
         image_unit_bind_points.set_used(0); // Trying to attach data to image unit 0 would not go well
 
         // Subroutine uniforms must be bound all at once, so we collect them first and process them at the end.
-        // The vec contains the uniform we want to set and the value we want to set it to.
-        let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, _>
-            = HashMap::with_hasher(Default::default());
+        // The vec contains the stage, the uniform we want to set and the value we want to set it to.
+        let mut subroutine_bindings: SmallVec<[(program::ShaderStage, &program::SubroutineUniform, &str); 8]>
+            = SmallVec::new();
+
+        // number of textures that didn't find a texture unit ; the other uniforms are still
+        // visited so that the error reports how many units the draw command requires
+        let mut missing_texture_units = 0;
 
         let mut visiting_result = Ok(());
         self.visit_values(|name, value| {
             if visiting_result.is_err() { return; }
 
-            if let Some(uniform) = program.get_uniform(name) {
-                // TODO: remove the size member
-                debug_assert!(uniform.size.is_none());
-
-                if !value.is_usable_with(&uniform.ty) {
-                    visiting_result = Err(DrawError::UniformTypeMismatch {
-                        name: name.to_owned(),
-                        expected: uniform.ty,
-                    });
-                    return;
-                }
-
-                match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, &mut image_unit_bind_points, name)
-                {
-                    Ok(_) => (),
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        
-                    }
-                };
-
-            } else if let Some(block) = program.get_uniform_blocks().get(name) {
-                let fence = match bind_uniform_block(&mut ctxt, &value, block,
-                                                     program, &mut uniform_buffer_bind_points, name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
+            for &program in programs {
+                if let Some(uniform) = get_uniform(program, name, &value) {
+                    if let Err(e) = check_uniform(uniform, &value, name) {
                         visiting_result = Err(e);
                         return;
                     }
-                };
-
-                if let Some(fence) = fence {
-                    fences.push(fence);
-                }
 
-            } else if let Some(block) = program.get_shader_storage_blocks().get(name) {
-                let fence = match bind_shared_storage_block(&mut ctxt, &value, block, program,
-                                                            &mut shared_storage_buffer_bind_points,
-                                                            name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        return;
+                    match bind_uniform(&mut ctxt, &value, program, uniform.location, uniform.ty,
+                                       &mut texture_bind_points, &mut image_unit_bind_points, name)
+                    {
+                        Ok(_) => (),
+                        Err(DrawError::TooManyTextureUnits { .. }) => {
+                            missing_texture_units += 1;
+                        },
+                        Err(e) => {
+                            visiting_result = Err(e);
+                            return;
+                        }
+                    };
+
+                } else if let Some(block) = program.get_uniform_blocks().get(name) {
+                    let fence = match bind_uniform_block(&mut ctxt, &value, block,
+                                                         program, &mut uniform_buffer_bind_points, name)
+                    {
+                        Ok(f) => f,
+                        Err(e) => {
+                            visiting_result = Err(e);
+                            return;
+                        }
+                    };
+
+                    if let Some(fence) = fence {
+                        fences.push(fence);
                     }
-                };
 
-                if let Some(fence) = fence {
-                    fences.push(fence);
-                }
-            } else if let Some(block) = program.get_atomic_counters().get(name) {
-                let fence = match bind_atomic_counter(&mut ctxt, &value, block, program,
-                                                      name)
-                {
-                    Ok(f) => f,
-                    Err(e) => {
-                        visiting_result = Err(e);
-                        return;
+                } else if let Some(block) = program.get_shader_storage_blocks().get(name) {
+                    let fence = match bind_shared_storage_block(&mut ctxt, &value, block, program,
+                                                                &mut shared_storage_buffer_bind_points,
+                                                                name)
+                    {
+                        Ok(f) => f,
+                        Err(e) => {
+                            visiting_result = Err(e);
+                            return;
+                        }
+                    };
+
+                    if let Some(fence) = fence {
+                        fences.push(fence);
+                    }
+                } else if let Some(block) = program.get_atomic_counters().get(name) {
+                    let fence = match bind_atomic_counter(&mut ctxt, &value, block, program,
+                                                          name)
+                    {
+                        Ok(f) => f,
+                        Err(e) => {
+                            visiting_result = Err(e);
+                            return;
+                        }
+                    };
+
+                    if let Some(fence) = fence {
+                        fences.push(fence);
+                    }
+                } else if let UniformValue::Subroutine(stage, sr_name) = value {
+                    // subroutine uniforms of programs bound to a pipeline are left untouched
+                    if programs.len() != 1 {
+                        continue;
                     }
-                };
 
-                if let Some(fence) = fence {
-                    fences.push(fence);
-                }
-            } else if let UniformValue::Subroutine(stage, sr_name) = value {
-                if let Some(subroutine_uniform) = program.get_subroutine_data().subroutine_uniforms.get(&(name.into(), stage)) {
-                    subroutine_bindings.entry(stage).or_insert(Vec::new());
-                    let vec = subroutine_bindings.get_mut(&stage).unwrap();
-                    vec.push((subroutine_uniform, sr_name));
+                    if let Some(subroutine_uniform) = program.get_subroutine_data().subroutine_uniforms.get(&(name.into(), stage)) {
+                        subroutine_bindings.push((stage, subroutine_uniform, sr_name));
+                    }
                 }
             }
         });
 
+        if visiting_result.is_ok() && missing_texture_units != 0 {
+            let max = ctxt.capabilities.max_combined_texture_image_units as usize;
+            return Err(DrawError::TooManyTextureUnits {
+                used: max + missing_texture_units,
+                max,
+            });
+        }
+
         // Process all subroutine uniforms in one batch.
         if !subroutine_bindings.is_empty() {
-            match bind_subroutine_uniforms(&mut ctxt, program, &subroutine_bindings) {
+            match bind_subroutine_uniforms(&mut ctxt, programs[0], &subroutine_bindings) {
                 Ok(_) => (),
                 Err(e) => {
                     visiting_result = Err(e);
@@ -142,46 +156,161 @@ impl<U> UniformsExt for U where U: Uniforms {
 
         visiting_result
     }
+
+    fn stage_uniform_values<P>(&self, mut ctxt: &mut CommandContext<'_>, program: &P)
+                               -> Result<(), DrawError>
+                               where P: ProgramExt
+    {
+        let mut visiting_result = Ok(());
+        self.visit_values(|name, value| {
+            if visiting_result.is_err() || !is_plain_value(&value) { return; }
+
+            if let Some(uniform) = get_uniform(program, name, &value) {
+                if let Err(e) = check_uniform(uniform, &value, name) {
+                    visiting_result = Err(e);
+                    return;
+                }
+
+                // plain values don't use any bind point
+                visiting_result = bind_uniform(&mut ctxt, &value, program, uniform.location,
+                                               uniform.ty, &mut Bitsfield::new(),
+                                               &mut Bitsfield::new(), name);
+            }
+        });
+
+        visiting_result
+    }
+}
+
+/// Looks up the uniform that `value` is set to. Values that contain arrays are first looked up
+/// in the uniform arrays of the program.
+fn get_uniform<'p, P>(program: &'p P, name: &str, value: &UniformValue<'_>)
+                      -> Option<&'p program::Uniform> where P: ProgramExt
+{
+    if to_raw_uniform_array(value).is_some() {
+        if let Some(uniform) = program.get_uniform_array(name) {
+            return Some(uniform);
+        }
+    }
+
+    program.get_uniform(name)
+}
+
+/// Checks that `value` can be used with `uniform`, including the number of elements of arrays.
+fn check_uniform(uniform: &program::Uniform, value: &UniformValue<'_>, name: &str)
+                 -> Result<(), DrawError>
+{
+    if !value.is_usable_with(&uniform.ty) {
+        return Err(DrawError::UniformTypeMismatch {
+            name: name.to_owned(),
+            expected: uniform.ty,
+        });
+    }
+
+    if let Some(array) = to_raw_uniform_array(value) {
+        let max = uniform.size.unwrap_or(1);
+        if array.len() > max {
+            return Err(DrawError::UniformArrayTooLong {
+                name: name.to_owned(),
+                len: array.len(),
+                max,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the elements of the value if it contains an array.
+fn to_raw_uniform_array<'a>(value: &UniformValue<'a>) -> Option<RawUniformArray<'a>> {
+    Some(match *value {
+        UniformValue::FloatArray(v) => RawUniformArray::Float(v),
+        UniformValue::Vec2Array(v) => RawUniformArray::Vec2(v),
+        UniformValue::Vec3Array(v) => RawUniformArray::Vec3(v),
+        UniformValue::Vec4Array(v) => RawUniformArray::Vec4(v),
+        UniformValue::IntArray(v) => RawUniformArray::SignedInt(v),
+        UniformValue::IntVec2Array(v) => RawUniformArray::IntVec2(v),
+        UniformValue::IntVec3Array(v) => RawUniformArray::IntVec3(v),
+        UniformValue::IntVec4Array(v) => RawUniformArray::IntVec4(v),
+        UniformValue::UnsignedIntArray(v) => RawUniformArray::UnsignedInt(v),
+        UniformValue::UnsignedIntVec2Array(v) => RawUniformArray::UnsignedIntVec2(v),
+        UniformValue::UnsignedIntVec3Array(v) => RawUniformArray::UnsignedIntVec3(v),
+        UniformValue::UnsignedIntVec4Array(v) => RawUniformArray::UnsignedIntVec4(v),
+        UniformValue::Mat2Array(v) => RawUniformArray::Mat2(v),
+        UniformValue::Mat3Array(v) => RawUniformArray::Mat3(v),
+        UniformValue::Mat4Array(v) => RawUniformArray::Mat4(v),
+        _ => return None,
+    })
+}
+
+/// Returns true if the value is passed with `glUniform` and doesn't require any bind point.
+fn is_plain_value(value: &UniformValue<'_>) -> bool {
+    to_raw_uniform_array(value).is_some() ||
+    matches!(*value,
+        UniformValue::SignedInt(_) | UniformValue::UnsignedInt(_) | UniformValue::Float(_) |
+        UniformValue::Mat2(_) | UniformValue::Mat3(_) | UniformValue::Mat4(_) |
+        UniformValue::Vec2(_) | UniformValue::Vec3(_) | UniformValue::Vec4(_) |
+        UniformValue::IntVec2(_) | UniformValue::IntVec3(_) | UniformValue::IntVec4(_) |
+        UniformValue::UnsignedIntVec2(_) | UniformValue::UnsignedIntVec3(_) |
+        UniformValue::UnsignedIntVec4(_) | UniformValue::Bool(_) | UniformValue::BoolVec2(_) |
+        UniformValue::BoolVec3(_) | UniformValue::BoolVec4(_) | UniformValue::Double(_) |
+        UniformValue::DoubleVec2(_) | UniformValue::DoubleVec3(_) | UniformValue::DoubleVec4(_) |
+        UniformValue::DoubleMat2(_) | UniformValue::DoubleMat3(_) | UniformValue::DoubleMat4(_) |
+        UniformValue::Int64(_) | UniformValue::Int64Vec2(_) | UniformValue::Int64Vec3(_) |
+        UniformValue::Int64Vec4(_) | UniformValue::UnsignedInt64(_) |
+        UniformValue::UnsignedInt64Vec2(_) | UniformValue::UnsignedInt64Vec3(_) |
+        UniformValue::UnsignedInt64Vec4(_) | UniformValue::Handle(_))
 }
 
 fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext<'_>, program: &P,
-                            subroutine_bindings: &HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, BuildHasherDefault<FnvHasher>>)
+                            subroutine_bindings: &[(program::ShaderStage, &program::SubroutineUniform, &str)])
                             -> Result<(), DrawError>
                             where P: ProgramExt
 {
     let subroutine_data = program.get_subroutine_data();
-    for (stage, bindings) in subroutine_bindings {
+    let scratch_buffers = ctxt.scratch_buffers;
+    let mut indices = scratch_buffers.take_subroutine_indices();
+
+    for (num, &(stage, _, _)) in subroutine_bindings.iter().enumerate() {
+        // each stage is processed when its first binding is encountered
+        if subroutine_bindings[..num].iter().any(|&(s, _, _)| s == stage) {
+            continue;
+        }
+
+        let bindings = subroutine_bindings[num..].iter().filter(|&&(s, _, _)| s == stage);
+
         // Validate that all subroutine uniforms of this stage are set, otherwise OpenGL will throw an error.
-        let set_cnt = bindings.len();
+        let set_cnt = bindings.clone().count();
         let expected_cnt = subroutine_data.subroutine_uniforms.iter()
-                                  .filter(|&(&(_, uni_stage), _)| *stage == uni_stage)
+                                  .filter(|&(&(_, uni_stage), _)| stage == uni_stage)
                                   .count();
         if set_cnt != expected_cnt {
             return Err(DrawError::SubroutineUniformMissing {
-                stage: *stage,
+                stage,
                 real_count: set_cnt,
                 expected_count: expected_cnt,
             })
         }
 
         // Build the indices array
-        let mut indices = vec![0 as gl::types::GLuint; *subroutine_data.location_counts.get(stage).unwrap()];
-        for binding in bindings {
-            let uniform = binding.0;
-            let subroutine_str = binding.1;
+        indices.clear();
+        indices.resize(*subroutine_data.location_counts.get(&stage).unwrap(), 0);
+        for &(_, uniform, subroutine_str) in bindings {
             let subroutine = match uniform.compatible_subroutines.iter()
                                    .find(|subroutine| subroutine.name == subroutine_str) {
                 Some(subroutine) => subroutine,
                 None => return Err(DrawError::SubroutineNotFound {
-                                    stage: *stage,
+                                    stage,
                                     name: subroutine_str.into(),
                                 })
             };
 
             indices[uniform.location as usize] = subroutine.index;
         }
-        program.set_subroutine_uniforms_for_stage(ctxt, *stage, &indices);
+        program.set_subroutine_uniforms_for_stage(ctxt, stage, &indices);
     }
+
+    scratch_buffers.give_back_subroutine_indices(indices);
     Ok(())
 }
 
@@ -291,6 +420,7 @@ fn bind_atomic_counter<'a, P>(ctxt: &mut context::CommandContext<'_>, value: &Un
 
 fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
+                   ty: UniformType,
                    texture_bind_points: &mut Bitsfield,
                    image_unit_bind_points: &mut Bitsfield,
                    name: &str)
@@ -456,6 +586,30 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::UnsignedInt64Vec4(val));
             Ok(())
         },
+        UniformValue::FloatArray(_) | UniformValue::Vec2Array(_) | UniformValue::Vec3Array(_) |
+        UniformValue::Vec4Array(_) | UniformValue::IntArray(_) | UniformValue::IntVec2Array(_) |
+        UniformValue::IntVec3Array(_) | UniformValue::IntVec4Array(_) |
+        UniformValue::UnsignedIntArray(_) | UniformValue::UnsignedIntVec2Array(_) |
+        UniformValue::UnsignedIntVec3Array(_) | UniformValue::UnsignedIntVec4Array(_) |
+        UniformValue::Mat2Array(_) | UniformValue::Mat3Array(_) | UniformValue::Mat4Array(_) => {
+            let array = to_raw_uniform_array(value).unwrap();
+            program.set_uniform_array(ctxt, location, &array);
+            Ok(())
+        },
+        UniformValue::Handle(handle) => {
+            if !ctxt.extensions.gl_arb_bindless_texture {
+                return Err(DrawError::BindlessTexturesNotSupported);
+            }
+
+            // the handle may come from a texture of another context
+            let value = handle.get_value();
+            if !ctxt.resident_texture_handles.contains(&value) {
+                return Err(DrawError::NonResidentTextureHandle { name: name.to_owned() });
+            }
+
+            program.set_uniform(ctxt, location, &RawUniformValue::Handle(value));
+            Ok(())
+        },
         UniformValue::Texture1d(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
@@ -498,6 +652,18 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::DepthTexture2d(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
+        UniformValue::StencilTexture2d(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+        },
+        UniformValue::DepthStencilTexture2d(texture, sampler) => {
+            // an unsigned sampler reads the stencil component, other samplers read the depth
+            let stencil = ty == UniformType::USampler2d;
+            if !texture.set_stencil_texture_mode(ctxt, stencil) && stencil {
+                return Err(DrawError::StencilTexturingNotSupported);
+            }
+
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+        },
         UniformValue::Texture2dMultisample(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
@@ -699,6 +865,11 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::UnsignedImageCubeArray(texture, unit) => {
             bind_image_uniform(ctxt, &**texture, unit, location, program, image_unit_bind_points)
         },
+        UniformValue::TextureWithSampler(texture, sampler) => {
+            texture.prepare_for_access(ctxt, crate::TextureAccess::TextureFetch);
+            bind_texture_to_unit(ctxt, texture, sampler.get_id(), location, program,
+                                 texture_bind_points)
+        },
     }
 }
 
@@ -718,6 +889,18 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
 
     let sampler = sampler.unwrap_or(0);
 
+    bind_texture_to_unit(ctxt, texture, sampler, location, program, texture_bind_points)
+}
+
+/// Binds the texture and the sampler object with the given ID to a texture unit, and sets the
+/// uniform to this unit.
+fn bind_texture_to_unit<P, T>(ctxt: &mut context::CommandContext<'_>, texture: &T,
+                              sampler: gl::types::GLuint, location: gl::types::GLint,
+                              program: &P, texture_bind_points: &mut Bitsfield)
+                              -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
+{
+    let max_units = ctxt.capabilities.max_combined_texture_image_units;
+
     // finding an appropriate texture unit
     let texture_unit =
         ctxt.state.texture_units
@@ -728,19 +911,22 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext<'_>,
             })
             .map(|(unit, _)| unit as u16)
             .or_else(|| {
-                if ctxt.state.texture_units.len() <
-                    ctxt.capabilities.max_combined_texture_image_units as usize
-                {
+                if ctxt.state.texture_units.len() < max_units as usize {
                     Some(ctxt.state.texture_units.len() as u16)
                 } else {
                     None
                 }
             })
-            .unwrap_or_else(|| {
-                texture_bind_points.get_unused().expect("Not enough texture units available")
-            });
-    assert!((texture_unit as gl::types::GLint) <
-            ctxt.capabilities.max_combined_texture_image_units);
+            .or_else(|| texture_bind_points.get_unused())
+            .filter(|&unit| (unit as gl::types::GLint) < max_units);
+
+    let texture_unit = match texture_unit {
+        Some(unit) => unit,
+        None => return Err(DrawError::TooManyTextureUnits {
+            used: max_units as usize + 1,
+            max: max_units as usize,
+        }),
+    };
     texture_bind_points.set_used(texture_unit);
 
     // updating the program to use the right unit
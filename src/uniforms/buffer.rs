@@ -239,7 +239,7 @@ impl<'a, T: ?Sized> AsUniformValue for &'a UniformBuffer<T> where T: UniformBloc
                         -> Result<(), LayoutMismatchError> where T: UniformBlock + Content
         {
             // TODO: more checks?
-            T::matches(&block.layout, 0)
+            T::matches_memory_layout(&block.layout, 0, block.memory_layout)
         }
 
         UniformValue::Block(self.buffer.as_slice_any(), f::<T>)
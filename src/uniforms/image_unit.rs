@@ -23,18 +23,32 @@ impl std::fmt::Display for ImageUnitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use self::ImageUnitError::*;
 
-        let desc = match *self {
+        match *self {
             NoMipmapAtLevel(level) => write!(f, "No mipmap level {} found", level),
             LayeringNotSupported(kind) => write!(f, "Layering is not supported with textures of dimensions {:?}", kind),
             LayerOutOfBounds(layer) => write!(f, "Request layer {} is out of bounds", layer),
             BadFormatClass(tbits, ibits) => write!(f, "Texture format has {} bits but image format has {} bits", tbits, ibits),
-            GetFormat(error) => write!(f, "{}", error),
-        };
-        Ok(())
+            GetFormat(ref error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImageUnitError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            ImageUnitError::GetFormat(ref error) => Some(error),
+            _ => None,
+        }
     }
 }
 
-impl std::error::Error for ImageUnitError {}
+impl From<GetFormatError> for ImageUnitError {
+    #[inline]
+    fn from(err: GetFormatError) -> ImageUnitError {
+        ImageUnitError::GetFormat(err)
+    }
+}
 
 
 /// How we bind a texture to an image unit
@@ -67,15 +81,25 @@ pub struct ImageUnit<'t, T: 't + core::ops::Deref<Target = crate::texture::Textu
 impl<'t, T: 't + core::ops::Deref<Target = crate::texture::TextureAny>> ImageUnit<'t, T> {
     /// Create a new marker
     pub fn new(texture: &'t T, format: ImageUnitFormat) -> Result<ImageUnit<'t, T>, ImageUnitError> {
-        let tbits = texture.get_internal_format().unwrap().get_total_bits();
+        let tbits = texture.get_internal_format()?.get_total_bits();
         if tbits != format.get_total_bits() {
             return Err(ImageUnitError::BadFormatClass(tbits, format.get_total_bits()))
         }
 
         Ok(ImageUnit(texture, ImageUnitBehavior {
-	    format,
-	    ..Default::default()
-	}))
+            format,
+            ..Default::default()
+        }))
+    }
+
+    /// Create a new marker with the given access.
+    ///
+    /// This is the same as calling `new` followed by `set_access`.
+    #[inline]
+    pub fn with_access(texture: &'t T, format: ImageUnitFormat, access: ImageUnitAccess)
+                       -> Result<ImageUnit<'t, T>, ImageUnitError>
+    {
+        Ok(ImageUnit::new(texture, format)?.set_access(access))
     }
 
     /// Set the mip level that will be bound
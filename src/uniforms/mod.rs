@@ -132,23 +132,36 @@ let program = glium::Program::from_source(&display,
 pub use self::buffer::UniformBuffer;
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter, DepthTextureComparison};
 pub use self::sampler::{Sampler, SamplerBehavior};
+pub use self::sampler_object::{SamplerObject, SamplerObjectCreationError, WithSampler};
+pub use self::storage_buffer::{ShaderStorageBuffer, AtomicCounterBuffer};
 pub use self::uniforms::{EmptyUniforms, UniformsStorage, DynamicUniforms};
 pub use self::image_unit::{ImageUnitAccess, ImageUnitFormat, ImageUnitError};
 pub use self::image_unit::{ImageUnit, ImageUnitBehavior};
 pub use self::value::{UniformValue, UniformType};
+pub use self::std140::{Std140, std140_round_up};
+pub use self::texture_batch::{TextureBatch, TextureBatchError};
+
+#[cfg(feature = "derive")]
+pub use glium_macros::UniformBlock;
 
 use std::error::Error;
 use std::fmt;
+use std::mem;
 
 use crate::buffer::Content as BufferContent;
 use crate::buffer::Buffer;
 use crate::program;
 use crate::program::BlockLayout;
+use crate::program::BlockMemoryLayout;
 
 mod bind;
 mod buffer;
 mod image_unit;
 mod sampler;
+mod sampler_object;
+mod std140;
+mod storage_buffer;
+mod texture_batch;
 mod uniforms;
 mod value;
 
@@ -160,6 +173,29 @@ pub trait Uniforms {
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, _: F);
 }
 
+/// Object-safe equivalent of `Uniforms`.
+///
+/// This trait is automatically implemented on all the types that implement `Uniforms`. It is
+/// used by `Surface::draw_dyn`, which can't take a generic `Uniforms` parameter.
+pub trait DynUniforms {
+    /// Calls the parameter once with the name and value of each uniform.
+    fn visit_values_dyn<'a>(&'a self, output: &mut dyn FnMut(&str, UniformValue<'a>));
+}
+
+impl<U> DynUniforms for U where U: Uniforms {
+    #[inline]
+    fn visit_values_dyn<'a>(&'a self, output: &mut dyn FnMut(&str, UniformValue<'a>)) {
+        self.visit_values(output)
+    }
+}
+
+impl<'u> Uniforms for &'u dyn DynUniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        (**self).visit_values_dyn(&mut output)
+    }
+}
+
 /// Error about a block layout mismatch.
 #[derive(Clone, Debug)]
 pub enum LayoutMismatchError {
@@ -202,6 +238,14 @@ pub enum LayoutMismatchError {
         /// Name of the field.
         name: String,
     },
+
+    /// The elements of an array don't have the same stride in the shader and in the input.
+    StrideMismatch {
+        /// Stride expected by the shader.
+        expected: usize,
+        /// Stride of the elements of the input.
+        obtained: usize,
+    },
 }
 
 impl Error for LayoutMismatchError {
@@ -228,6 +272,8 @@ impl fmt::Display for LayoutMismatchError {
                 "There is a mismatch in a submember of this layout",
             MissingField { .. } =>
                 "A field is missing in either the expected of the input data layout",
+            StrideMismatch { .. } =>
+                "The elements of an array don't have the same stride in the shader and in the input",
         };
         match *self {
             //duplicate Patternmatching, different Types can't be condensed
@@ -270,6 +316,14 @@ impl fmt::Display for LayoutMismatchError {
                     desc,
                     name,
                 ),
+            StrideMismatch { ref expected, ref obtained } =>
+                write!(
+                    fmt,
+                    "{}, got: {}, expected: {}",
+                    desc,
+                    obtained,
+                    expected,
+                ),
         }
     }
 }
@@ -291,7 +345,7 @@ impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + Buf
                         -> Result<(), LayoutMismatchError> where T: UniformBlock + BufferContent
         {
             // TODO: more checks?
-            T::matches(&block.layout, 0)
+            T::matches_memory_layout(&block.layout, 0, block.memory_layout)
         }
 
         UniformValue::Block(self.as_slice_any(), f::<T>)
@@ -306,35 +360,142 @@ pub trait UniformBlock {        // TODO: `: Copy`, but unsized structs don't imp
 
     /// Builds the `BlockLayout` corresponding to the current object.
     fn build_layout(base_offset: usize) -> BlockLayout;
+
+    /// Same as `matches`, but the members of the block are laid out with the given rules.
+    ///
+    /// This is what glium calls when binding a buffer. The default implementation ignores
+    /// `memory_layout` and calls `matches`.
+    #[inline]
+    fn matches_memory_layout(layout: &BlockLayout, base_offset: usize,
+                             memory_layout: BlockMemoryLayout)
+                             -> Result<(), LayoutMismatchError>
+    {
+        let _ = memory_layout;
+        Self::matches(layout, base_offset)
+    }
+
+    /// Returns the basic type that an array of `len` elements of this type stands for, if any.
+    ///
+    /// This is what makes `[f32; 4]` a `vec4` and `[[f32; 4]; 4]` a `mat4` instead of arrays.
+    #[doc(hidden)]
+    #[inline]
+    fn array_basic_type(len: usize) -> Option<UniformType> {
+        let _ = len;
+        None
+    }
+}
+
+/// Checks that `layout` is the basic type `ty` at `base_offset`.
+fn matches_basic_type(layout: &BlockLayout, base_offset: usize, ty: UniformType)
+                      -> Result<(), LayoutMismatchError>
+{
+    if let BlockLayout::BasicType { ty: expected, offset_in_buffer } = *layout {
+        if expected != ty {
+            return Err(LayoutMismatchError::TypeMismatch {
+                expected,
+                obtained: ty,
+            });
+        }
+
+        if offset_in_buffer != base_offset {
+            return Err(LayoutMismatchError::OffsetMismatch {
+                expected: offset_in_buffer,
+                obtained: base_offset,
+            });
+        }
+
+        Ok(())
+
+    } else {
+        Err(LayoutMismatchError::LayoutMismatch {
+            expected: layout.clone(),
+            obtained: BlockLayout::BasicType {
+                ty,
+                offset_in_buffer: base_offset,
+            }
+        })
+    }
+}
+
+/// Returns the matrix made of `columns` columns of type `column`.
+fn matrix_type(column: UniformType, columns: usize) -> Option<UniformType> {
+    match (column, columns) {
+        (UniformType::FloatVec2, 2) => Some(UniformType::FloatMat2),
+        (UniformType::FloatVec3, 3) => Some(UniformType::FloatMat3),
+        (UniformType::FloatVec4, 4) => Some(UniformType::FloatMat4),
+        (UniformType::DoubleVec2, 2) => Some(UniformType::DoubleMat2),
+        (UniformType::DoubleVec3, 3) => Some(UniformType::DoubleMat3),
+        (UniformType::DoubleVec4, 4) => Some(UniformType::DoubleMat4),
+        _ => None,
+    }
+}
+
+/// Checks that the elements of an array of `T` have the stride that `memory_layout` gives to
+/// the elements of `content`.
+///
+/// Only arrays of basic types are checked, as the size of a struct in the shader can't be known
+/// if some of its members are inactive.
+fn check_array_stride<T>(content: &BlockLayout, memory_layout: BlockMemoryLayout)
+                         -> Result<(), LayoutMismatchError>
+{
+    if let BlockLayout::BasicType { ty, .. } = *content {
+        if let Some(expected) = memory_layout.array_stride(ty) {
+            let obtained = mem::size_of::<T>();
+            if expected != obtained {
+                return Err(LayoutMismatchError::StrideMismatch { expected, obtained });
+            }
+        }
+    }
+
+    Ok(())
 }
 
 impl<T> UniformBlock for [T] where T: UniformBlock {
+    #[inline]
     fn matches(layout: &BlockLayout, base_offset: usize)
                -> Result<(), LayoutMismatchError>
+    {
+        Self::matches_memory_layout(layout, base_offset, BlockMemoryLayout::Std140)
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> BlockLayout {
+        BlockLayout::DynamicSizedArray {
+            content: Box::new(<T as UniformBlock>::build_layout(base_offset)),
+        }
+    }
+
+    fn matches_memory_layout(layout: &BlockLayout, base_offset: usize,
+                             memory_layout: BlockMemoryLayout)
+                             -> Result<(), LayoutMismatchError>
     {
         if let BlockLayout::Struct { members } = layout {
             if members.len() == 1 {
-                return Self::matches(&members[0].1, base_offset);
+                return Self::matches_memory_layout(&members[0].1, base_offset, memory_layout);
             }
         }
 
         if let BlockLayout::DynamicSizedArray { content } = layout {
-            <T as UniformBlock>::matches(content, base_offset)
+            <T as UniformBlock>::matches_memory_layout(content, base_offset, memory_layout)
                 .map_err(|err| {
                     LayoutMismatchError::MemberMismatch {
                         member: "<dynamic array content>".to_owned(),
                         err: Box::new(err),
                     }
-                })
+                })?;
+
+            check_array_stride::<T>(content, memory_layout)
 
         } else if let BlockLayout::Array { content, .. } = layout {
-            <T as UniformBlock>::matches(content, base_offset)
+            <T as UniformBlock>::matches_memory_layout(content, base_offset, memory_layout)
                 .map_err(|err| {
                     LayoutMismatchError::MemberMismatch {
                         member: "<dynamic array content>".to_owned(),
                         err: Box::new(err),
                     }
-                })
+                })?;
+
+            check_array_stride::<T>(content, memory_layout)
 
         } else {
             Err(LayoutMismatchError::LayoutMismatch {
@@ -343,95 +504,88 @@ impl<T> UniformBlock for [T] where T: UniformBlock {
             })
         }
     }
+}
 
+// arrays of two to four scalars are vectors, and square arrays of vectors are matrices
+impl<T, const N: usize> UniformBlock for [T; N] where T: UniformBlock {
     #[inline]
-    fn build_layout(base_offset: usize) -> BlockLayout {
-        BlockLayout::DynamicSizedArray {
-            content: Box::new(<T as UniformBlock>::build_layout(base_offset)),
+    fn matches(layout: &program::BlockLayout, base_offset: usize)
+               -> Result<(), LayoutMismatchError>
+    {
+        Self::matches_memory_layout(layout, base_offset, BlockMemoryLayout::Std140)
+    }
+
+    #[inline]
+    fn build_layout(base_offset: usize) -> program::BlockLayout {
+        match T::array_basic_type(N) {
+            Some(ty) => BlockLayout::BasicType {
+                ty,
+                offset_in_buffer: base_offset,
+            },
+            None => BlockLayout::Array {
+                content: Box::new(T::build_layout(base_offset)),
+                length: N,
+            },
         }
     }
-}
 
-macro_rules! impl_uniform_block_array {
-    ($len:expr) => (
-        impl<T> UniformBlock for [T; $len] where T: UniformBlock {
-            fn matches(layout: &program::BlockLayout, base_offset: usize)
-                       -> Result<(), LayoutMismatchError>
-            {
-                if let &BlockLayout::Struct { ref members } = layout {
-                    if members.len() == 1 {
-                        return Self::matches(&members[0].1, base_offset);
+    fn matches_memory_layout(layout: &program::BlockLayout, base_offset: usize,
+                             memory_layout: BlockMemoryLayout)
+                             -> Result<(), LayoutMismatchError>
+    {
+        if let Some(ty) = T::array_basic_type(N) {
+            // with the `std430` layout, arrays of scalars and of two-components vectors are
+            // tightly packed and have the same representation as Rust arrays
+            if let BlockLayout::Array { ref content, length } = *layout {
+                if let BlockLayout::BasicType { ty: content_ty, .. } = **content {
+                    if length == N &&
+                       memory_layout.array_stride(content_ty) == Some(mem::size_of::<T>())
+                    {
+                        return T::matches(content, base_offset)
+                            .map_err(|err| LayoutMismatchError::MemberMismatch {
+                                member: "<array content>".to_owned(),
+                                err: Box::new(err),
+                            });
                     }
                 }
+            }
 
-                if let &BlockLayout::Array { ref content, length } = layout {
-                    if let Err(err) = T::matches(content, base_offset) {
-                        return Err(LayoutMismatchError::MemberMismatch {
-                            member: "<array content>".to_owned(),
-                            err: Box::new(err),
-                        });
-                    }
-
-                    if length != $len {
-                        return Err(LayoutMismatchError::LayoutMismatch {
-                            expected: layout.clone(),
-                            obtained: Self::build_layout(base_offset),
-                        });
-                    }
+            return matches_basic_type(layout, base_offset, ty);
+        }
 
-                    Ok(())
+        if let BlockLayout::Struct { ref members } = *layout {
+            if members.len() == 1 {
+                return Self::matches_memory_layout(&members[0].1, base_offset, memory_layout);
+            }
+        }
 
-                } else {
-                    Err(LayoutMismatchError::LayoutMismatch {
-                        expected: layout.clone(),
-                        obtained: Self::build_layout(base_offset),
-                    })
-                }
+        if let BlockLayout::Array { ref content, length } = *layout {
+            if let Err(err) = T::matches_memory_layout(content, base_offset, memory_layout) {
+                return Err(LayoutMismatchError::MemberMismatch {
+                    member: "<array content>".to_owned(),
+                    err: Box::new(err),
+                });
             }
 
-            #[inline]
-            fn build_layout(base_offset: usize) -> program::BlockLayout {
-                BlockLayout::Array {
-                    content: Box::new(T::build_layout(base_offset)),
-                    length: $len,
-                }
+            if length != N {
+                return Err(LayoutMismatchError::LayoutMismatch {
+                    expected: layout.clone(),
+                    obtained: Self::build_layout(base_offset),
+                });
             }
+
+            check_array_stride::<T>(content, memory_layout)
+
+        } else {
+            Err(LayoutMismatchError::LayoutMismatch {
+                expected: layout.clone(),
+                obtained: Self::build_layout(base_offset),
+            })
         }
-    );
-}
+    }
 
-impl_uniform_block_array!(5);
-impl_uniform_block_array!(6);
-impl_uniform_block_array!(7);
-impl_uniform_block_array!(8);
-impl_uniform_block_array!(9);
-impl_uniform_block_array!(10);
-impl_uniform_block_array!(11);
-impl_uniform_block_array!(12);
-impl_uniform_block_array!(13);
-impl_uniform_block_array!(14);
-impl_uniform_block_array!(15);
-impl_uniform_block_array!(16);
-impl_uniform_block_array!(17);
-impl_uniform_block_array!(18);
-impl_uniform_block_array!(19);
-impl_uniform_block_array!(20);
-impl_uniform_block_array!(21);
-impl_uniform_block_array!(22);
-impl_uniform_block_array!(23);
-impl_uniform_block_array!(24);
-impl_uniform_block_array!(25);
-impl_uniform_block_array!(26);
-impl_uniform_block_array!(27);
-impl_uniform_block_array!(28);
-impl_uniform_block_array!(29);
-impl_uniform_block_array!(30);
-impl_uniform_block_array!(31);
-impl_uniform_block_array!(32);
-impl_uniform_block_array!(64);
-impl_uniform_block_array!(128);
-impl_uniform_block_array!(256);
-impl_uniform_block_array!(512);
-impl_uniform_block_array!(1024);
-impl_uniform_block_array!(2048);
-impl_uniform_block_array!(4096);
+    #[inline]
+    fn array_basic_type(len: usize) -> Option<UniformType> {
+        T::array_basic_type(N).and_then(|column| matrix_type(column, len))
+    }
+}
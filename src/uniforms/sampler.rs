@@ -169,12 +169,19 @@ impl<'t, T: 't> Sampler<'t, T> {
     }
 
     /// Sets the depth texture comparison method.
+    ///
+    /// Depth textures with a comparison method must be read with a shadow sampler, such as
+    /// `sampler2DShadow`, and depth textures without one with a regular sampler. Using the
+    /// wrong type of sampler returns a `DrawError::UniformTypeMismatch`.
     pub fn depth_texture_comparison(mut self, comparison: Option<DepthTextureComparison>) -> Sampler<'t, T> {
         self.1.depth_texture_comparison = comparison;
         self
     }
 
-    /// Changes the magnifying filter of the sampler.
+    /// Changes the maximum anisotropy of the sampler. `1` means no anisotropic filtering.
+    ///
+    /// The value is clamped to the maximum supported by the backend, and is ignored if
+    /// anisotropic filtering isn't supported.
     pub fn anisotropy(mut self, level: u16) -> Sampler<'t, T> {
         self.1.max_anisotropy = level;
         self
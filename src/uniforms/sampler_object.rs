@@ -0,0 +1,301 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::backend::Facade;
+use crate::context::CommandContext;
+use crate::context::Context;
+use crate::gl;
+use crate::texture::TextureAny;
+use crate::uniforms::{AsUniformValue, UniformValue};
+use crate::uniforms::{SamplerBehavior, SamplerWrapFunction, MinifySamplerFilter};
+use crate::uniforms::{MagnifySamplerFilter, DepthTextureComparison};
+use crate::version::Api;
+use crate::version::Version;
+use crate::ContextExt;
+use crate::GlObject;
+use crate::ToGlEnum;
+
+/// Error that can happen when creating a `SamplerObject`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SamplerObjectCreationError {
+    /// Sampler objects are not supported by the backend.
+    NotSupported,
+}
+
+impl fmt::Display for SamplerObjectCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc = match *self {
+            SamplerObjectCreationError::NotSupported =>
+                "Sampler objects are not supported by the backend",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for SamplerObjectCreationError {}
+
+/// A sampler object created by the user.
+///
+/// Contrary to `Sampler`, whose parameters are used to look up a sampler shared by the whole
+/// context, a `SamplerObject` is owned by you and can be modified after its creation. It can be
+/// used with any texture with `with_texture`, and the same texture can be bound to several
+/// uniforms with different sampler objects in the same draw call.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, texture: glium::Texture2d)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// use glium::uniforms::{MagnifySamplerFilter, SamplerBehavior, SamplerObject};
+///
+/// let smooth = SamplerObject::new(&display, &Default::default()).unwrap();
+/// let mut sharp = SamplerObject::new(&display, &SamplerBehavior {
+///     magnify_filter: MagnifySamplerFilter::Nearest,
+///     .. Default::default()
+/// }).unwrap();
+/// sharp.set_lod_bias(-0.5);
+///
+/// let uniforms = uniform! {
+///     smooth_tex: smooth.with_texture(&texture),
+///     sharp_tex: sharp.with_texture(&texture),
+/// };
+/// # }
+/// # fn main() {}
+/// ```
+pub struct SamplerObject {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+}
+
+impl SamplerObject {
+    /// Builds a new sampler object with the given parameters.
+    pub fn new<F: ?Sized>(facade: &F, behavior: &SamplerBehavior)
+                          -> Result<SamplerObject, SamplerObjectCreationError> where F: Facade
+    {
+        let context = facade.get_context().clone();
+        if !SamplerObject::is_supported(&context) {
+            return Err(SamplerObjectCreationError::NotSupported);
+        }
+
+        let mut ctxt = context.make_current();
+
+        let id = unsafe {
+            let mut id: gl::types::GLuint = 0;
+            ctxt.gl.GenSamplers(1, &mut id);
+            id
+        };
+
+        set_wrap_function(&mut ctxt, id, behavior.wrap_function);
+        set_parameter(&mut ctxt, id, gl::TEXTURE_MIN_FILTER, behavior.minify_filter.to_glenum());
+        set_parameter(&mut ctxt, id, gl::TEXTURE_MAG_FILTER, behavior.magnify_filter.to_glenum());
+        set_depth_texture_comparison(&mut ctxt, id, behavior.depth_texture_comparison);
+        set_anisotropy(&mut ctxt, id, behavior.max_anisotropy);
+
+        drop(ctxt);
+
+        Ok(SamplerObject {
+            context,
+            id,
+        })
+    }
+
+    /// Returns true if sampler objects are supported by the backend.
+    #[inline]
+    pub fn is_supported<C: ?Sized>(context: &C) -> bool where C: crate::CapabilitiesSource {
+        context.get_version() >= &Version(Api::Gl, 3, 3) ||
+        context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+        context.get_extensions().gl_arb_sampler_objects
+    }
+
+    /// Returns a marker that samples `texture` with this sampler object when passed as a
+    /// uniform.
+    #[inline]
+    pub fn with_texture<'t, T>(&'t self, texture: &'t T) -> WithSampler<'t, T>
+        where T: Deref<Target = TextureAny>
+    {
+        WithSampler(texture, self)
+    }
+
+    /// Changes the wrap functions of all three coordinates.
+    pub fn set_wrap_function(&mut self, function: SamplerWrapFunction) {
+        let mut ctxt = self.context.make_current();
+        set_wrap_function(&mut ctxt, self.id, (function, function, function));
+    }
+
+    /// Changes the minifying filter of the sampler.
+    pub fn set_minify_filter(&mut self, filter: MinifySamplerFilter) {
+        let mut ctxt = self.context.make_current();
+        set_parameter(&mut ctxt, self.id, gl::TEXTURE_MIN_FILTER, filter.to_glenum());
+    }
+
+    /// Changes the magnifying filter of the sampler.
+    pub fn set_magnify_filter(&mut self, filter: MagnifySamplerFilter) {
+        let mut ctxt = self.context.make_current();
+        set_parameter(&mut ctxt, self.id, gl::TEXTURE_MAG_FILTER, filter.to_glenum());
+    }
+
+    /// Sets the depth texture comparison method. `None` disables the comparison.
+    pub fn set_depth_texture_comparison(&mut self, comparison: Option<DepthTextureComparison>) {
+        let mut ctxt = self.context.make_current();
+        set_depth_texture_comparison(&mut ctxt, self.id, comparison);
+    }
+
+    /// Changes the maximum anisotropy. `1` means no anisotropic filtering.
+    ///
+    /// ## Compatibility
+    ///
+    /// Ignored on hardware that does not support anisotropic filtering. Values higher than what
+    /// the hardware supports are clamped.
+    pub fn set_anisotropy(&mut self, level: u16) {
+        let mut ctxt = self.context.make_current();
+        set_anisotropy(&mut ctxt, self.id, level);
+    }
+
+    /// Changes the bias added to the level of detail before choosing the mipmap to sample.
+    ///
+    /// ## Compatibility
+    ///
+    /// Ignored on OpenGL ES, which doesn't have this parameter.
+    pub fn set_lod_bias(&mut self, bias: f32) {
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 1, 0) {
+            unsafe { ctxt.gl.SamplerParameterf(self.id, gl::TEXTURE_LOD_BIAS, bias); }
+        }
+    }
+
+    /// Changes the range of levels of detail that can be sampled. The default range is
+    /// `-1000.0 .. 1000.0`.
+    pub fn set_lod_range(&mut self, min: f32, max: f32) {
+        let ctxt = self.context.make_current();
+
+        unsafe {
+            ctxt.gl.SamplerParameterf(self.id, gl::TEXTURE_MIN_LOD, min);
+            ctxt.gl.SamplerParameterf(self.id, gl::TEXTURE_MAX_LOD, max);
+        }
+    }
+
+    /// Changes the color of the samples outside of the texture when the wrap function is
+    /// `BorderClamp`.
+    ///
+    /// ## Compatibility
+    ///
+    /// Ignored on OpenGL ES before 3.2.
+    pub fn set_border_color(&mut self, color: [f32; 4]) {
+        let ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2)
+        {
+            unsafe {
+                ctxt.gl.SamplerParameterfv(self.id, gl::TEXTURE_BORDER_COLOR, color.as_ptr());
+            }
+        }
+    }
+
+    /// Gives a name to the sampler object, which debuggers and debug messages use to refer
+    /// to it.
+    ///
+    /// Does nothing if the backend doesn't support `KHR_debug`.
+    pub fn set_label(&self, label: &str) {
+        let ctxt = self.context.make_current();
+        let label = label.as_bytes();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            unsafe { ctxt.gl.ObjectLabel(gl::SAMPLER, self.id, label.len() as gl::types::GLsizei,
+                                         label.as_ptr() as *const _) };
+
+        } else if ctxt.extensions.gl_khr_debug {
+            unsafe { ctxt.gl.ObjectLabelKHR(gl::SAMPLER, self.id,
+                                            label.len() as gl::types::GLsizei,
+                                            label.as_ptr() as *const _) };
+        }
+    }
+}
+
+impl GlObject for SamplerObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl fmt::Debug for SamplerObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "SamplerObject #{}", self.id)
+    }
+}
+
+impl Drop for SamplerObject {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        // deleting the sampler unbinds it from the texture units
+        for unit in ctxt.state.texture_units.iter_mut() {
+            if unit.sampler == self.id {
+                unit.sampler = 0;
+            }
+        }
+
+        unsafe { ctxt.gl.DeleteSamplers(1, [self.id].as_ptr()); }
+    }
+}
+
+/// A uniform marker that samples a texture with a `SamplerObject`.
+pub struct WithSampler<'t, T: 't + Deref<Target = TextureAny>>(pub &'t T, pub &'t SamplerObject);
+
+impl<'t, T: 't + Deref<Target = TextureAny>> Copy for WithSampler<'t, T> {}
+
+impl<'t, T: 't + Deref<Target = TextureAny>> Clone for WithSampler<'t, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'t, T: 't + Deref<Target = TextureAny>> AsUniformValue for WithSampler<'t, T> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::TextureWithSampler(self.0, self.1)
+    }
+}
+
+fn set_parameter(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, name: gl::types::GLenum,
+                 value: gl::types::GLenum)
+{
+    unsafe { ctxt.gl.SamplerParameteri(id, name, value as gl::types::GLint); }
+}
+
+fn set_wrap_function(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint,
+                     function: (SamplerWrapFunction, SamplerWrapFunction, SamplerWrapFunction))
+{
+    set_parameter(ctxt, id, gl::TEXTURE_WRAP_S, function.0.to_glenum());
+    set_parameter(ctxt, id, gl::TEXTURE_WRAP_T, function.1.to_glenum());
+    set_parameter(ctxt, id, gl::TEXTURE_WRAP_R, function.2.to_glenum());
+}
+
+fn set_depth_texture_comparison(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint,
+                                comparison: Option<DepthTextureComparison>)
+{
+    if let Some(comparison) = comparison {
+        set_parameter(ctxt, id, gl::TEXTURE_COMPARE_MODE, gl::COMPARE_REF_TO_TEXTURE);
+        set_parameter(ctxt, id, gl::TEXTURE_COMPARE_FUNC, comparison.to_glenum());
+    } else {
+        set_parameter(ctxt, id, gl::TEXTURE_COMPARE_MODE, gl::NONE);
+    }
+}
+
+fn set_anisotropy(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint, level: u16) {
+    if let Some(max_value) = ctxt.capabilities.max_texture_max_anisotropy {
+        let value = (level as f32).min(max_value);
+        unsafe { ctxt.gl.SamplerParameterf(id, gl::TEXTURE_MAX_ANISOTROPY_EXT, value); }
+    }
+}
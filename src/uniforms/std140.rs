@@ -0,0 +1,73 @@
+/// Types whose alignment and size in a uniform block with the `std140` layout are known.
+///
+/// This trait is implemented for the types that have the same representation in Rust and in
+/// GLSL, and by `#[derive(UniformBlock)]` for structs. The derive uses it to check, at compile
+/// time, that each field of a struct is where the `std140` rules place it.
+///
+/// Arrays of two to four scalars are vectors. Other arrays, including matrices, are implemented
+/// with the `std140` stride, which rounds the size of each element up to 16 bytes. A Rust array
+/// of five `f32`s therefore doesn't have the same size as its `std140` counterpart, and using it
+/// in a derived struct is a compile-time error.
+pub trait Std140 {
+    /// Alignment of the type in bytes.
+    const ALIGNMENT: usize;
+
+    /// Size of the type in bytes, including the padding at the end.
+    const SIZE: usize;
+
+    /// True for the scalars, which make vectors when there are two to four of them in an array.
+    #[doc(hidden)]
+    const SCALAR: bool = false;
+}
+
+/// Rounds `offset` up to the next multiple of `alignment`.
+#[inline]
+pub const fn std140_round_up(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+macro_rules! impl_std140 {
+    ($ty:ty, $alignment:expr, $size:expr) => (
+        impl Std140 for $ty {
+            const ALIGNMENT: usize = $alignment;
+            const SIZE: usize = $size;
+        }
+    );
+}
+
+macro_rules! impl_std140_scalar {
+    ($ty:ty, $size:expr) => (
+        impl Std140 for $ty {
+            const ALIGNMENT: usize = $size;
+            const SIZE: usize = $size;
+            const SCALAR: bool = true;
+        }
+
+        impl_std140!(($ty, $ty), 2 * $size, 2 * $size);
+        impl_std140!(($ty, $ty, $ty), 4 * $size, 3 * $size);
+        impl_std140!(($ty, $ty, $ty, $ty), 4 * $size, 4 * $size);
+    );
+}
+
+impl_std140_scalar!(i32, 4);
+impl_std140_scalar!(u32, 4);
+impl_std140_scalar!(f32, 4);
+impl_std140_scalar!(f64, 8);
+impl_std140_scalar!(i64, 8);
+impl_std140_scalar!(u64, 8);
+
+// matrices are stored as arrays of column vectors, so they don't need to be handled separately
+impl<T, const N: usize> Std140 for [T; N] where T: Std140 {
+    const ALIGNMENT: usize = if T::SCALAR && N >= 2 && N <= 4 {
+        // `vec3`s are aligned like `vec4`s
+        T::SIZE * if N == 3 { 4 } else { N }
+    } else {
+        std140_round_up(T::ALIGNMENT, 16)
+    };
+
+    const SIZE: usize = if T::SCALAR && N >= 2 && N <= 4 {
+        N * T::SIZE
+    } else {
+        N * std140_round_up(T::SIZE, Self::ALIGNMENT)
+    };
+}
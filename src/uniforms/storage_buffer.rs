@@ -0,0 +1,257 @@
+use crate::buffer::{Content, Buffer, BufferType, BufferMode, BufferCreationError, ReadError};
+use crate::buffer::{BufferSlice, BufferMutSlice};
+use crate::uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
+use crate::program;
+
+use crate::gl;
+use crate::GlObject;
+
+use std::ops::{Deref, DerefMut};
+
+use crate::backend::Facade;
+
+/// Buffer that contains the data of a shader storage block.
+///
+/// The layout of the content is checked against the block declared in the program every time
+/// the buffer is used in a draw or compute command, and a mismatch produces a
+/// `DrawError::UniformBlockLayoutMismatch`.
+///
+/// For example, to use a `ShaderStorageBuffer<[u32]>`, you must declare it in GLSL as
+///```ignore
+///     buffer MyBlock {
+///         uint values[];
+///     };
+///```
+///
+/// and pass it to `uniform!` like this:
+///```ignore
+///     uniform! {
+///         MyBlock: &buffer,
+///     }
+///```
+#[derive(Debug)]
+pub struct ShaderStorageBuffer<T: ?Sized> where T: Content {
+    buffer: Buffer<T>,
+}
+
+impl<T: ?Sized + Content> GlObject for ShaderStorageBuffer<T> {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
+}
+
+impl<T: ?Sized> ShaderStorageBuffer<T> where T: Content {
+    /// Uploads data in the storage buffer.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, data: &T) -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                  where F: Facade
+    {
+        ShaderStorageBuffer::new_impl(facade, data, BufferMode::Default)
+    }
+
+    /// Uploads data in the storage buffer.
+    #[inline]
+    pub fn dynamic<F: ?Sized>(facade: &F, data: &T) -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                      where F: Facade
+    {
+        ShaderStorageBuffer::new_impl(facade, data, BufferMode::Dynamic)
+    }
+
+    /// Uploads data in the storage buffer.
+    #[inline]
+    pub fn persistent<F: ?Sized>(facade: &F, data: &T) -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                         where F: Facade
+    {
+        ShaderStorageBuffer::new_impl(facade, data, BufferMode::Persistent)
+    }
+
+    /// Uploads data in the storage buffer.
+    #[inline]
+    pub fn immutable<F: ?Sized>(facade: &F, data: &T) -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                        where F: Facade
+    {
+        ShaderStorageBuffer::new_impl(facade, data, BufferMode::Immutable)
+    }
+
+    #[inline]
+    fn new_impl<F: ?Sized>(facade: &F, data: &T, mode: BufferMode)
+                   -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                   where F: Facade
+    {
+        let buffer = Buffer::new(facade, data, BufferType::ShaderStorageBuffer, mode)?;
+
+        Ok(ShaderStorageBuffer {
+            buffer,
+        })
+    }
+
+    /// Creates an empty buffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the size passed as parameter is not suitable for the type of data.
+    ///
+    #[inline]
+    pub fn empty_unsized<F: ?Sized>(facade: &F, size: usize, mode: BufferMode)
+                            -> Result<ShaderStorageBuffer<T>, BufferCreationError>
+                            where F: Facade
+    {
+        let buffer = Buffer::empty_unsized(facade, BufferType::ShaderStorageBuffer, size, mode)?;
+
+        Ok(ShaderStorageBuffer {
+            buffer,
+        })
+    }
+}
+
+impl<T> ShaderStorageBuffer<[T]> where [T]: Content, T: Copy {
+    /// Creates an empty buffer that contains `len` elements.
+    #[inline]
+    pub fn empty_array<F: ?Sized>(facade: &F, len: usize, mode: BufferMode)
+                          -> Result<ShaderStorageBuffer<[T]>, BufferCreationError>
+                          where F: Facade
+    {
+        let buffer = Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, len, mode)?;
+
+        Ok(ShaderStorageBuffer {
+            buffer,
+        })
+    }
+}
+
+impl<T: ?Sized> Deref for ShaderStorageBuffer<T> where T: Content {
+    type Target = Buffer<T>;
+
+    #[inline]
+    fn deref(&self) -> &Buffer<T> {
+        &self.buffer
+    }
+}
+
+impl<T: ?Sized> DerefMut for ShaderStorageBuffer<T> where T: Content {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Buffer<T> {
+        &mut self.buffer
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a ShaderStorageBuffer<T>> for BufferSlice<'a, T> where T: Content {
+    #[inline]
+    fn from(b: &'a ShaderStorageBuffer<T>) -> BufferSlice<'a, T> {
+        b.buffer.as_slice()
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a mut ShaderStorageBuffer<T>> for BufferMutSlice<'a, T> where T: Content {
+    #[inline]
+    fn from(b: &'a mut ShaderStorageBuffer<T>) -> BufferMutSlice<'a, T> {
+        b.buffer.as_mut_slice()
+    }
+}
+
+impl<'a, T: ?Sized> AsUniformValue for &'a ShaderStorageBuffer<T> where T: UniformBlock + Content {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f<T: ?Sized>(block: &program::UniformBlock)
+                        -> Result<(), LayoutMismatchError> where T: UniformBlock + Content
+        {
+            T::matches_memory_layout(&block.layout, 0, block.memory_layout)
+        }
+
+        UniformValue::Block(self.buffer.as_slice_any(), f::<T>)
+    }
+}
+
+/// Buffer that contains the values of atomic counters.
+///
+/// Each counter is an `uint`. The buffer is bound to the binding point of the `atomic_uint`
+/// uniform that it is passed to, for example with this GLSL code:
+///```ignore
+///     layout(binding = 0) uniform atomic_uint counter;
+///```
+///
+/// and passed to `uniform!` like this:
+///```ignore
+///     uniform! {
+///         counter: &buffer,
+///     }
+///```
+#[derive(Debug)]
+pub struct AtomicCounterBuffer {
+    buffer: Buffer<[u32]>,
+}
+
+impl GlObject for AtomicCounterBuffer {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
+}
+
+impl AtomicCounterBuffer {
+    /// Builds a buffer that contains `counters` counters initialized to `0`.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, counters: usize)
+                          -> Result<AtomicCounterBuffer, BufferCreationError> where F: Facade
+    {
+        AtomicCounterBuffer::with_values(facade, &vec![0; counters])
+    }
+
+    /// Builds a buffer that contains one counter for each value.
+    #[inline]
+    pub fn with_values<F: ?Sized>(facade: &F, values: &[u32])
+                                  -> Result<AtomicCounterBuffer, BufferCreationError>
+                                  where F: Facade
+    {
+        let buffer = Buffer::new(facade, values, BufferType::AtomicCounterBuffer,
+                                 BufferMode::Default)?;
+
+        Ok(AtomicCounterBuffer {
+            buffer,
+        })
+    }
+
+    /// Returns the number of counters in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns true if the buffer doesn't contain any counter.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == 0
+    }
+
+    /// Reads the values of the counters.
+    ///
+    /// Waits until the commands that use the buffer have finished.
+    #[inline]
+    pub fn read_counters(&self) -> Result<Vec<u32>, ReadError> {
+        self.buffer.read()
+    }
+
+    /// Sets all the counters back to `0`.
+    #[inline]
+    pub fn reset(&self) {
+        self.buffer.write(&vec![0; self.buffer.len()]);
+    }
+}
+
+impl<'a> AsUniformValue for &'a AtomicCounterBuffer {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        #[inline]
+        fn f(block: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            <u32 as UniformBlock>::matches(&block.layout, 0)
+        }
+
+        UniformValue::Block(self.buffer.as_slice_any(), f)
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::backend::Facade;
+use crate::framebuffer::{SimpleFrameBuffer, ValidationError};
+use crate::gl;
+use crate::texture::{MipmapsOption, Texture2d, Texture2dArray, TextureCreationError};
+use crate::texture::UncompressedFloatFormat;
+use crate::uniforms::{AsUniformValue, MagnifySamplerFilter, UniformValue};
+use crate::GlObject;
+use crate::Surface;
+
+/// Error that can happen when building a `TextureBatch`.
+#[derive(Debug)]
+pub enum TextureBatchError {
+    /// The list of textures is empty.
+    NoTexture,
+
+    /// A texture doesn't have the same dimensions as the first one.
+    DimensionsMismatch {
+        /// Index of the texture in the list.
+        index: usize,
+        /// Dimensions of the first texture.
+        expected: (u32, u32),
+        /// Dimensions of the texture.
+        obtained: (u32, u32),
+    },
+
+    /// Error while creating the texture array.
+    TextureCreationError(TextureCreationError),
+
+    /// Error while creating the framebuffer used to copy a texture to its layer.
+    FramebufferError(ValidationError),
+}
+
+impl fmt::Display for TextureBatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureBatchError::NoTexture => write!(fmt, "The list of textures is empty"),
+            TextureBatchError::DimensionsMismatch { index, expected, obtained } =>
+                write!(fmt, "Texture {} has dimensions {:?}, expected {:?}", index, obtained,
+                       expected),
+            TextureBatchError::TextureCreationError(err) => write!(fmt, "{}", err),
+            TextureBatchError::FramebufferError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for TextureBatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextureBatchError::TextureCreationError(err) => Some(err),
+            TextureBatchError::FramebufferError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for TextureBatchError {
+    #[inline]
+    fn from(err: TextureCreationError) -> TextureBatchError {
+        TextureBatchError::TextureCreationError(err)
+    }
+}
+
+impl From<ValidationError> for TextureBatchError {
+    #[inline]
+    fn from(err: ValidationError) -> TextureBatchError {
+        TextureBatchError::FramebufferError(err)
+    }
+}
+
+/// Many 2D textures of the same size, packed in the layers of a single `Texture2dArray`.
+///
+/// A draw command can only use as many textures as the backend has texture units. Packing
+/// textures in a batch makes them use a single unit. The batch is bound to a `sampler2DArray`
+/// uniform, and the shader selects the texture with the layer returned by `layer`.
+///
+/// The content of the textures is copied when the batch is built. Modifying the textures
+/// afterwards doesn't modify the batch.
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, grass: glium::Texture2d, rock: glium::Texture2d)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// let batch = glium::uniforms::TextureBatch::new(&display, &[&grass, &rock]).unwrap();
+///
+/// let uniforms = glium::uniform! {
+///     textures: &batch,
+///     layer: batch.layer(&rock).unwrap(),
+/// };
+/// # }
+/// ```
+pub struct TextureBatch {
+    texture: Texture2dArray,
+    layers: HashMap<gl::types::GLuint, u32>,
+}
+
+impl TextureBatch {
+    /// Builds a new batch with the `U8U8U8U8` format and mipmaps.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, textures: &[&Texture2d])
+                          -> Result<TextureBatch, TextureBatchError> where F: Facade
+    {
+        TextureBatch::with_format(facade, textures, UncompressedFloatFormat::U8U8U8U8,
+                                  MipmapsOption::AutoGeneratedMipmaps)
+    }
+
+    /// Builds a new batch with a specific format for the texture array.
+    ///
+    /// If the texture array has mipmaps, they are generated from the copied textures.
+    pub fn with_format<F: ?Sized>(facade: &F, textures: &[&Texture2d],
+                                  format: UncompressedFloatFormat, mipmaps: MipmapsOption)
+                                  -> Result<TextureBatch, TextureBatchError> where F: Facade
+    {
+        let first = textures.first().ok_or(TextureBatchError::NoTexture)?;
+        let expected = first.dimensions();
+
+        // a texture that is in the list several times only uses one layer
+        let mut unique: Vec<&Texture2d> = Vec::with_capacity(textures.len());
+        for (index, texture) in textures.iter().enumerate() {
+            if texture.dimensions() != expected {
+                return Err(TextureBatchError::DimensionsMismatch {
+                    index,
+                    expected,
+                    obtained: texture.dimensions(),
+                });
+            }
+
+            if !unique.iter().any(|t| t.get_id() == texture.get_id()) {
+                unique.push(texture);
+            }
+        }
+
+        let array = Texture2dArray::empty_with_format(facade, format, mipmaps, expected.0,
+                                                      expected.1, unique.len() as u32)?;
+
+        let mut layers = HashMap::with_capacity(unique.len());
+        for (layer, texture) in unique.into_iter().enumerate() {
+            let target = SimpleFrameBuffer::new(facade,
+                                                array.layer(layer as u32).unwrap().main_level())?;
+            texture.as_surface().fill(&target, MagnifySamplerFilter::Nearest);
+            layers.insert(texture.get_id(), layer as u32);
+        }
+
+        if array.get_mipmap_levels() > 1 {
+            array.generate_mipmaps();
+        }
+
+        Ok(TextureBatch { texture: array, layers })
+    }
+
+    /// Returns the layer that contains a copy of `texture`, or `None` if the texture is not
+    /// part of the batch.
+    #[inline]
+    pub fn layer(&self, texture: &Texture2d) -> Option<u32> {
+        self.layers.get(&texture.get_id()).cloned()
+    }
+
+    /// Returns the number of layers of the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns true if the batch has no layer. This never happens, as a batch can't be built
+    /// from an empty list.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns the texture array that contains the textures.
+    #[inline]
+    pub fn texture(&self) -> &Texture2dArray {
+        &self.texture
+    }
+}
+
+impl fmt::Debug for TextureBatch {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "TextureBatch {{ layers: {} }}", self.layers.len())
+    }
+}
+
+impl<'a> AsUniformValue for &'a TextureBatch {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Texture2dArray(&self.texture, None)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::gl;
 use crate::program;
 use crate::program::BlockLayout;
 use crate::program::ShaderStage;
@@ -7,9 +8,11 @@ use crate::uniforms::AsUniformValue;
 use crate::uniforms::LayoutMismatchError;
 use crate::uniforms::UniformBlock;
 use crate::uniforms::SamplerBehavior;
+use crate::uniforms::SamplerObject;
 
 use crate::uniforms::ImageUnitBehavior;
 use crate::buffer::BufferAnySlice;
+use crate::GlObject;
 
 
 /// Type of a uniform in a program.
@@ -185,6 +188,29 @@ pub enum UniformValue<'a> {
     UnsignedInt64Vec2([u64; 2]),
     UnsignedInt64Vec3([u64; 3]),
     UnsignedInt64Vec4([u64; 4]),
+    /// First elements of an array of uniforms, set with a single `glUniform*v` call.
+    ///
+    /// The other variants whose name ends with `Array` work the same way. Matrices are
+    /// column-major.
+    FloatArray(&'a [f32]),
+    Vec2Array(&'a [[f32; 2]]),
+    Vec3Array(&'a [[f32; 3]]),
+    Vec4Array(&'a [[f32; 4]]),
+    IntArray(&'a [i32]),
+    IntVec2Array(&'a [[i32; 2]]),
+    IntVec3Array(&'a [[i32; 3]]),
+    IntVec4Array(&'a [[i32; 4]]),
+    UnsignedIntArray(&'a [u32]),
+    UnsignedIntVec2Array(&'a [[u32; 2]]),
+    UnsignedIntVec3Array(&'a [[u32; 3]]),
+    UnsignedIntVec4Array(&'a [[u32; 4]]),
+    Mat2Array(&'a [[[f32; 2]; 2]]),
+    Mat3Array(&'a [[[f32; 3]; 3]]),
+    Mat4Array(&'a [[[f32; 4]; 4]]),
+    /// Handle to a resident texture, used with a `sampler*` uniform without binding the texture.
+    Handle(texture::TextureHandle<'a>),
+    /// Texture sampled with a sampler object created by the user.
+    TextureWithSampler(&'a texture::TextureAny, &'a SamplerObject),
     Texture1d(&'a texture::Texture1d, Option<SamplerBehavior>),
     CompressedTexture1d(&'a texture::CompressedTexture1d, Option<SamplerBehavior>),
     SrgbTexture1d(&'a texture::SrgbTexture1d, Option<SamplerBehavior>),
@@ -199,6 +225,8 @@ pub enum UniformValue<'a> {
     IntegralTexture2d(&'a texture::IntegralTexture2d, Option<SamplerBehavior>),
     UnsignedTexture2d(&'a texture::UnsignedTexture2d, Option<SamplerBehavior>),
     DepthTexture2d(&'a texture::DepthTexture2d, Option<SamplerBehavior>),
+    StencilTexture2d(&'a texture::StencilTexture2d, Option<SamplerBehavior>),
+    DepthStencilTexture2d(&'a texture::DepthStencilTexture2d, Option<SamplerBehavior>),
     Texture2dMultisample(&'a texture::Texture2dMultisample, Option<SamplerBehavior>),
     SrgbTexture2dMultisample(&'a texture::SrgbTexture2dMultisample, Option<SamplerBehavior>),
     IntegralTexture2dMultisample(&'a texture::IntegralTexture2dMultisample, Option<SamplerBehavior>),
@@ -306,22 +334,53 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::DoubleVec2(_), UniformType::DoubleVec2) => true,
             (&UniformValue::DoubleVec3(_), UniformType::DoubleVec3) => true,
             (&UniformValue::DoubleVec4(_), UniformType::DoubleVec4) => true,
+            (&UniformValue::FloatArray(_), UniformType::Float) => true,
+            (&UniformValue::Vec2Array(_), UniformType::FloatVec2) => true,
+            (&UniformValue::Vec3Array(_), UniformType::FloatVec3) => true,
+            (&UniformValue::Vec4Array(_), UniformType::FloatVec4) => true,
+            (&UniformValue::IntArray(_), UniformType::Int) => true,
+            (&UniformValue::IntVec2Array(_), UniformType::IntVec2) => true,
+            (&UniformValue::IntVec3Array(_), UniformType::IntVec3) => true,
+            (&UniformValue::IntVec4Array(_), UniformType::IntVec4) => true,
+            (&UniformValue::UnsignedIntArray(_), UniformType::UnsignedInt) => true,
+            (&UniformValue::UnsignedIntVec2Array(_), UniformType::UnsignedIntVec2) => true,
+            (&UniformValue::UnsignedIntVec3Array(_), UniformType::UnsignedIntVec3) => true,
+            (&UniformValue::UnsignedIntVec4Array(_), UniformType::UnsignedIntVec4) => true,
+            (&UniformValue::Mat2Array(_), UniformType::FloatMat2) => true,
+            (&UniformValue::Mat3Array(_), UniformType::FloatMat3) => true,
+            (&UniformValue::Mat4Array(_), UniformType::FloatMat4) => true,
             (&UniformValue::Texture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::CompressedTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::SrgbTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::CompressedSrgbTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::IntegralTexture1d(_, _), UniformType::ISampler1d) => true,
             (&UniformValue::UnsignedTexture1d(_, _), UniformType::USampler1d) => true,
-            (&UniformValue::DepthTexture1d(_, _), UniformType::Sampler1d) => true,
-            (&UniformValue::DepthTexture1d(_, _), UniformType::Sampler1dShadow) => true,
+            (&UniformValue::DepthTexture1d(_, sampler), UniformType::Sampler1d) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthTexture1d(_, sampler), UniformType::Sampler1dShadow) => {
+                is_comparison_enabled(sampler)
+            },
             (&UniformValue::Texture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::CompressedTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::SrgbTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::CompressedSrgbTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::IntegralTexture2d(_, _), UniformType::ISampler2d) => true,
             (&UniformValue::UnsignedTexture2d(_, _), UniformType::USampler2d) => true,
-            (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2d) => true,
-            (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2dShadow) => true,
+            (&UniformValue::DepthTexture2d(_, sampler), UniformType::Sampler2d) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthTexture2d(_, sampler), UniformType::Sampler2dShadow) => {
+                is_comparison_enabled(sampler)
+            },
+            (&UniformValue::StencilTexture2d(_, _), UniformType::USampler2d) => true,
+            (&UniformValue::DepthStencilTexture2d(_, sampler), UniformType::Sampler2d) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthStencilTexture2d(_, sampler), UniformType::Sampler2dShadow) => {
+                is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthStencilTexture2d(_, _), UniformType::USampler2d) => true,
             (&UniformValue::Texture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::CompressedTexture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::SrgbTexture3d(_, _), UniformType::Sampler3d) => true,
@@ -335,32 +394,48 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::CompressedSrgbTexture1dArray(_, _), UniformType::Sampler1dArray) => true,
             (&UniformValue::IntegralTexture1dArray(_, _), UniformType::ISampler1dArray) => true,
             (&UniformValue::UnsignedTexture1dArray(_, _), UniformType::USampler1dArray) => true,
-            (&UniformValue::DepthTexture1dArray(_, _), UniformType::Sampler1dArray) => true,
-            (&UniformValue::DepthTexture1dArray(_, _), UniformType::Sampler1dArrayShadow) => true,
+            (&UniformValue::DepthTexture1dArray(_, sampler), UniformType::Sampler1dArray) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthTexture1dArray(_, sampler), UniformType::Sampler1dArrayShadow) => {
+                is_comparison_enabled(sampler)
+            },
             (&UniformValue::Texture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::CompressedTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::SrgbTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::CompressedSrgbTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
             (&UniformValue::IntegralTexture2dArray(_, _), UniformType::ISampler2dArray) => true,
             (&UniformValue::UnsignedTexture2dArray(_, _), UniformType::USampler2dArray) => true,
-            (&UniformValue::DepthTexture2dArray(_, _), UniformType::Sampler2dArray) => true,
-            (&UniformValue::DepthTexture2dArray(_, _), UniformType::Sampler2dArrayShadow) => true,
+            (&UniformValue::DepthTexture2dArray(_, sampler), UniformType::Sampler2dArray) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthTexture2dArray(_, sampler), UniformType::Sampler2dArrayShadow) => {
+                is_comparison_enabled(sampler)
+            },
             (&UniformValue::Cubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::CompressedCubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::SrgbCubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::CompressedSrgbCubemap(_, _), UniformType::SamplerCube) => true,
             (&UniformValue::IntegralCubemap(_, _), UniformType::ISamplerCube) => true,
             (&UniformValue::UnsignedCubemap(_, _), UniformType::USamplerCube) => true,
-            (&UniformValue::DepthCubemap(_, _), UniformType::SamplerCube) => true,
-            (&UniformValue::DepthCubemap(_, _), UniformType::SamplerCubeShadow) => true,
+            (&UniformValue::DepthCubemap(_, sampler), UniformType::SamplerCube) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthCubemap(_, sampler), UniformType::SamplerCubeShadow) => {
+                is_comparison_enabled(sampler)
+            },
             (&UniformValue::CubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::CompressedCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::SrgbCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::CompressedSrgbCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
             (&UniformValue::IntegralCubemapArray(_, _), UniformType::ISamplerCubeArray) => true,
             (&UniformValue::UnsignedCubemapArray(_, _), UniformType::USamplerCubeArray) => true,
-            (&UniformValue::DepthCubemapArray(_, _), UniformType::SamplerCubeArray) => true,
-            (&UniformValue::DepthCubemapArray(_, _), UniformType::SamplerCubeArrayShadow) => true,
+            (&UniformValue::DepthCubemapArray(_, sampler), UniformType::SamplerCubeArray) => {
+                !is_comparison_enabled(sampler)
+            },
+            (&UniformValue::DepthCubemapArray(_, sampler), UniformType::SamplerCubeArrayShadow) => {
+                is_comparison_enabled(sampler)
+            },
             (&UniformValue::BufferTexture(tex), UniformType::SamplerBuffer) => {
                 tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Float
             },
@@ -396,43 +471,172 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::ImageCubeArray(..), UniformType::ImageCubeArray) => true,
             (&UniformValue::IntegralImageCubeArray(..), UniformType::IImageCubeArray) => true,
             (&UniformValue::UnsignedImageCubeArray(..), UniformType::UImageCubeArray) => true,
+            (&UniformValue::Handle(_), ty) => texture::bindless::is_sampler_type(ty),
+            (&UniformValue::TextureWithSampler(texture, _), ty) => is_sampler_for(texture, ty),
             _ => false,
         }
     }
+
+    /// Returns the id of the texture that is sampled, if this value is a texture.
+    pub(crate) fn sampled_texture_id(&self) -> Option<gl::types::GLuint> {
+        match *self {
+            UniformValue::TextureWithSampler(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture1d(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::StencilTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthStencilTexture2d(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture2dMultisample(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture2dMultisample(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture2dMultisample(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture2dMultisample(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture2dMultisample(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture3d(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture1dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture2dArray(texture, _) => Some(texture.get_id()),
+            UniformValue::Texture2dMultisampleArray(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbTexture2dMultisampleArray(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralTexture2dMultisampleArray(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedTexture2dMultisampleArray(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthTexture2dMultisampleArray(texture, _) => Some(texture.get_id()),
+            UniformValue::Cubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthCubemap(texture, _) => Some(texture.get_id()),
+            UniformValue::CubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedCubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::SrgbCubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::CompressedSrgbCubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::IntegralCubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::UnsignedCubemapArray(texture, _) => Some(texture.get_id()),
+            UniformValue::DepthCubemapArray(texture, _) => Some(texture.get_id()),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if the sampler compares the depth values of the texture, in which case the
+/// texture must be read with a shadow sampler.
+fn is_comparison_enabled(sampler: Option<SamplerBehavior>) -> bool {
+    sampler.map_or(false, |s| s.depth_texture_comparison.is_some())
+}
+
+/// Returns true if `ty` is a type of sampler that can read from `texture`.
+fn is_sampler_for(texture: &texture::TextureAny, ty: UniformType) -> bool {
+    use crate::texture::Dimensions;
+    use crate::texture::TextureKind;
+
+    let kind = texture.kind();
+
+    match (texture.dimensions(), ty) {
+        (Dimensions::Texture1d { .. }, UniformType::Sampler1dShadow) |
+        (Dimensions::Texture1dArray { .. }, UniformType::Sampler1dArrayShadow) |
+        (Dimensions::Texture2d { .. }, UniformType::Sampler2dShadow) |
+        (Dimensions::Texture2dArray { .. }, UniformType::Sampler2dArrayShadow) |
+        (Dimensions::Cubemap { .. }, UniformType::SamplerCubeShadow) |
+        (Dimensions::CubemapArray { .. }, UniformType::SamplerCubeArrayShadow) => {
+            kind == TextureKind::Depth
+        },
+
+        (Dimensions::Texture1d { .. }, UniformType::Sampler1d) |
+        (Dimensions::Texture1dArray { .. }, UniformType::Sampler1dArray) |
+        (Dimensions::Texture2d { .. }, UniformType::Sampler2d) |
+        (Dimensions::Texture2dArray { .. }, UniformType::Sampler2dArray) |
+        (Dimensions::Texture2dMultisample { .. }, UniformType::Sampler2dMultisample) |
+        (Dimensions::Texture2dMultisampleArray { .. }, UniformType::Sampler2dMultisampleArray) |
+        (Dimensions::Texture3d { .. }, UniformType::Sampler3d) |
+        (Dimensions::Cubemap { .. }, UniformType::SamplerCube) |
+        (Dimensions::CubemapArray { .. }, UniformType::SamplerCubeArray) => {
+            kind == TextureKind::Float || kind == TextureKind::Depth
+        },
+
+        (Dimensions::Texture1d { .. }, UniformType::ISampler1d) |
+        (Dimensions::Texture1dArray { .. }, UniformType::ISampler1dArray) |
+        (Dimensions::Texture2d { .. }, UniformType::ISampler2d) |
+        (Dimensions::Texture2dArray { .. }, UniformType::ISampler2dArray) |
+        (Dimensions::Texture2dMultisample { .. }, UniformType::ISampler2dMultisample) |
+        (Dimensions::Texture2dMultisampleArray { .. }, UniformType::ISampler2dMultisampleArray) |
+        (Dimensions::Texture3d { .. }, UniformType::ISampler3d) |
+        (Dimensions::Cubemap { .. }, UniformType::ISamplerCube) |
+        (Dimensions::CubemapArray { .. }, UniformType::ISamplerCubeArray) => {
+            kind == TextureKind::Integral
+        },
+
+        (Dimensions::Texture1d { .. }, UniformType::USampler1d) |
+        (Dimensions::Texture1dArray { .. }, UniformType::USampler1dArray) |
+        (Dimensions::Texture2d { .. }, UniformType::USampler2d) |
+        (Dimensions::Texture2dArray { .. }, UniformType::USampler2dArray) |
+        (Dimensions::Texture2dMultisample { .. }, UniformType::USampler2dMultisample) |
+        (Dimensions::Texture2dMultisampleArray { .. }, UniformType::USampler2dMultisampleArray) |
+        (Dimensions::Texture3d { .. }, UniformType::USampler3d) |
+        (Dimensions::Cubemap { .. }, UniformType::USamplerCube) |
+        (Dimensions::CubemapArray { .. }, UniformType::USamplerCubeArray) => {
+            kind == TextureKind::Unsigned || kind == TextureKind::Stencil
+        },
+
+        _ => false,
+    }
 }
 
 macro_rules! impl_uniform_block_basic {
+    // scalars also give the type of the vectors made of two to four of them
+    ($ty:ty, $uniform_ty:expr, [$vec2:expr, $vec3:expr, $vec4:expr]) => (
+        impl_uniform_block_basic!(__impl $ty, $uniform_ty,
+            #[inline]
+            fn array_basic_type(len: usize) -> Option<UniformType> {
+                match len {
+                    2 => Some($vec2),
+                    3 => Some($vec3),
+                    4 => Some($vec4),
+                    _ => None,
+                }
+            }
+        );
+    );
+
     ($ty:ty, $uniform_ty:expr) => (
+        impl_uniform_block_basic!(__impl $ty, $uniform_ty,);
+    );
+
+    (__impl $ty:ty, $uniform_ty:expr, $($extra:tt)*) => (
         impl UniformBlock for $ty {
+            #[inline]
             fn matches(layout: &program::BlockLayout, base_offset: usize)
                        -> Result<(), LayoutMismatchError>
             {
-                if let &BlockLayout::BasicType { ty, offset_in_buffer } = layout {
-                    if ty != $uniform_ty {
-                        return Err(LayoutMismatchError::TypeMismatch {
-                            expected: ty,
-                            obtained: $uniform_ty,
-                        });
-                    }
-
-                    if offset_in_buffer != base_offset {
-                        return Err(LayoutMismatchError::OffsetMismatch {
-                            expected: offset_in_buffer,
-                            obtained: base_offset,
-                        });
-                    }
-
-                    Ok(())
-
-                } else {
-                    Err(LayoutMismatchError::LayoutMismatch {
-                        expected: layout.clone(),
-                        obtained: BlockLayout::BasicType {
-                            ty: $uniform_ty,
-                            offset_in_buffer: base_offset,
-                        }
-                    })
-                }
+                super::matches_basic_type(layout, base_offset, $uniform_ty)
             }
 
             #[inline]
@@ -442,6 +646,8 @@ macro_rules! impl_uniform_block_basic {
                     offset_in_buffer: base_offset,
                 }
             }
+
+            $($extra)*
         }
     );
 }
@@ -481,7 +687,8 @@ impl AsUniformValue for i32 {
     }
 }
 
-impl_uniform_block_basic!(i32, UniformType::Int);
+impl_uniform_block_basic!(i32, UniformType::Int,
+                          [UniformType::IntVec2, UniformType::IntVec3, UniformType::IntVec4]);
 
 impl AsUniformValue for [i32; 2] {
     #[inline]
@@ -490,8 +697,6 @@ impl AsUniformValue for [i32; 2] {
     }
 }
 
-impl_uniform_block_basic!([i32; 2], UniformType::IntVec2);
-
 impl AsUniformValue for (i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -508,8 +713,6 @@ impl AsUniformValue for [i32; 3] {
     }
 }
 
-impl_uniform_block_basic!([i32; 3], UniformType::IntVec3);
-
 impl AsUniformValue for (i32, i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -526,8 +729,6 @@ impl AsUniformValue for [i32; 4] {
     }
 }
 
-impl_uniform_block_basic!([i32; 4], UniformType::IntVec4);
-
 impl AsUniformValue for (i32, i32, i32, i32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -544,7 +745,8 @@ impl AsUniformValue for u32 {
     }
 }
 
-impl_uniform_block_basic!(u32, UniformType::UnsignedInt);
+impl_uniform_block_basic!(u32, UniformType::UnsignedInt,
+                          [UniformType::UnsignedIntVec2, UniformType::UnsignedIntVec3, UniformType::UnsignedIntVec4]);
 
 impl AsUniformValue for [u32; 2] {
     #[inline]
@@ -553,8 +755,6 @@ impl AsUniformValue for [u32; 2] {
     }
 }
 
-impl_uniform_block_basic!([u32; 2], UniformType::UnsignedIntVec2);
-
 impl AsUniformValue for (u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -571,8 +771,6 @@ impl AsUniformValue for [u32; 3] {
     }
 }
 
-impl_uniform_block_basic!([u32; 3], UniformType::UnsignedIntVec3);
-
 impl AsUniformValue for (u32, u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -589,8 +787,6 @@ impl AsUniformValue for [u32; 4] {
     }
 }
 
-impl_uniform_block_basic!([u32; 4], UniformType::UnsignedIntVec4);
-
 impl AsUniformValue for (u32, u32, u32, u32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -607,7 +803,8 @@ impl AsUniformValue for bool {
     }
 }
 
-impl_uniform_block_basic!(bool, UniformType::Bool);
+impl_uniform_block_basic!(bool, UniformType::Bool,
+                          [UniformType::BoolVec2, UniformType::BoolVec3, UniformType::BoolVec4]);
 
 impl AsUniformValue for [bool; 2] {
     #[inline]
@@ -616,8 +813,6 @@ impl AsUniformValue for [bool; 2] {
     }
 }
 
-impl_uniform_block_basic!([bool; 2], UniformType::BoolVec2);
-
 impl AsUniformValue for (bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -634,8 +829,6 @@ impl AsUniformValue for [bool; 3] {
     }
 }
 
-impl_uniform_block_basic!([bool; 3], UniformType::BoolVec3);
-
 impl AsUniformValue for (bool, bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -652,8 +845,6 @@ impl AsUniformValue for [bool; 4] {
     }
 }
 
-impl_uniform_block_basic!([bool; 4], UniformType::BoolVec4);
-
 impl AsUniformValue for (bool, bool, bool, bool) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -670,7 +861,8 @@ impl AsUniformValue for f32 {
     }
 }
 
-impl_uniform_block_basic!(f32, UniformType::Float);
+impl_uniform_block_basic!(f32, UniformType::Float,
+                          [UniformType::FloatVec2, UniformType::FloatVec3, UniformType::FloatVec4]);
 
 impl AsUniformValue for [[f32; 2]; 2] {
     #[inline]
@@ -679,8 +871,6 @@ impl AsUniformValue for [[f32; 2]; 2] {
     }
 }
 
-impl_uniform_block_basic!([[f32; 2]; 2], UniformType::FloatMat2);
-
 impl AsUniformValue for [[f32; 3]; 3] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -688,8 +878,6 @@ impl AsUniformValue for [[f32; 3]; 3] {
     }
 }
 
-impl_uniform_block_basic!([[f32; 3]; 3], UniformType::FloatMat3);
-
 impl AsUniformValue for [[f32; 4]; 4] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -697,8 +885,6 @@ impl AsUniformValue for [[f32; 4]; 4] {
     }
 }
 
-impl_uniform_block_basic!([[f32; 4]; 4], UniformType::FloatMat4);
-
 impl AsUniformValue for (f32, f32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -733,8 +919,6 @@ impl AsUniformValue for [f32; 2] {
     }
 }
 
-impl_uniform_block_basic!([f32; 2], UniformType::FloatVec2);
-
 impl AsUniformValue for [f32; 3] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -742,8 +926,6 @@ impl AsUniformValue for [f32; 3] {
     }
 }
 
-impl_uniform_block_basic!([f32; 3], UniformType::FloatVec3);
-
 impl AsUniformValue for [f32; 4] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -751,8 +933,6 @@ impl AsUniformValue for [f32; 4] {
     }
 }
 
-impl_uniform_block_basic!([f32; 4], UniformType::FloatVec4);
-
 //TODO bool, i32, u32 and f64 should also be implemented as cgmath and nalgebra variants (i.e. nalgebra::Vec3<f64>).
 // Start of double type variants
 impl AsUniformValue for f64 {
@@ -762,7 +942,8 @@ impl AsUniformValue for f64 {
     }
 }
 
-impl_uniform_block_basic!(f64, UniformType::Double);
+impl_uniform_block_basic!(f64, UniformType::Double,
+                          [UniformType::DoubleVec2, UniformType::DoubleVec3, UniformType::DoubleVec4]);
 
 impl AsUniformValue for [f64; 2] {
     #[inline]
@@ -771,8 +952,6 @@ impl AsUniformValue for [f64; 2] {
     }
 }
 
-impl_uniform_block_basic!([f64; 2], UniformType::DoubleVec2);
-
 impl AsUniformValue for (f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -789,8 +968,6 @@ impl AsUniformValue for [f64; 3] {
     }
 }
 
-impl_uniform_block_basic!([f64; 3], UniformType::DoubleVec3);
-
 impl AsUniformValue for (f64, f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -807,8 +984,6 @@ impl AsUniformValue for [f64; 4] {
     }
 }
 
-impl_uniform_block_basic!([f64; 4], UniformType::DoubleVec4);
-
 impl AsUniformValue for (f64, f64, f64, f64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -825,8 +1000,6 @@ impl AsUniformValue for [[f64; 2]; 2] {
     }
 }
 
-impl_uniform_block_basic!([[f64; 2]; 2], UniformType::DoubleMat2);
-
 impl AsUniformValue for [[f64; 3]; 3] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -834,8 +1007,6 @@ impl AsUniformValue for [[f64; 3]; 3] {
     }
 }
 
-impl_uniform_block_basic!([[f64; 3]; 3], UniformType::DoubleMat3);
-
 impl AsUniformValue for [[f64; 4]; 4] {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -843,8 +1014,6 @@ impl AsUniformValue for [[f64; 4]; 4] {
     }
 }
 
-impl_uniform_block_basic!([[f64; 4]; 4], UniformType::DoubleMat4);
-
 impl AsUniformValue for i64 {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -852,7 +1021,8 @@ impl AsUniformValue for i64 {
     }
 }
 
-impl_uniform_block_basic!(i64, UniformType::Int);
+impl_uniform_block_basic!(i64, UniformType::Int,
+                          [UniformType::Int64Vec2, UniformType::Int64Vec3, UniformType::Int64Vec4]);
 
 impl AsUniformValue for [i64; 2] {
     #[inline]
@@ -861,8 +1031,6 @@ impl AsUniformValue for [i64; 2] {
     }
 }
 
-impl_uniform_block_basic!([i64; 2], UniformType::Int64Vec2);
-
 impl AsUniformValue for (i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -879,8 +1047,6 @@ impl AsUniformValue for [i64; 3] {
     }
 }
 
-impl_uniform_block_basic!([i64; 3], UniformType::Int64Vec3);
-
 impl AsUniformValue for (i64, i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -897,8 +1063,6 @@ impl AsUniformValue for [i64; 4] {
     }
 }
 
-impl_uniform_block_basic!([i64; 4], UniformType::Int64Vec4);
-
 impl AsUniformValue for (i64, i64, i64, i64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -915,7 +1079,8 @@ impl AsUniformValue for u64 {
     }
 }
 
-impl_uniform_block_basic!(u64, UniformType::UnsignedInt64);
+impl_uniform_block_basic!(u64, UniformType::UnsignedInt64,
+                          [UniformType::UnsignedInt64Vec2, UniformType::UnsignedInt64Vec3, UniformType::UnsignedInt64Vec4]);
 
 impl AsUniformValue for [u64; 2] {
     #[inline]
@@ -924,8 +1089,6 @@ impl AsUniformValue for [u64; 2] {
     }
 }
 
-impl_uniform_block_basic!([u64; 2], UniformType::UnsignedInt64Vec2);
-
 impl AsUniformValue for (u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -942,8 +1105,6 @@ impl AsUniformValue for [u64; 3] {
     }
 }
 
-impl_uniform_block_basic!([u64; 3], UniformType::UnsignedInt64Vec3);
-
 impl AsUniformValue for (u64, u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -960,8 +1121,6 @@ impl AsUniformValue for [u64; 4] {
     }
 }
 
-impl_uniform_block_basic!([u64; 4], UniformType::UnsignedInt64Vec4);
-
 impl AsUniformValue for (u64, u64, u64, u64) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue<'_> {
@@ -978,3 +1137,213 @@ impl<'a> AsUniformValue for (&'a str, ShaderStage) {
         UniformValue::Subroutine(self.1, self.0)
     }
 }
+
+impl AsUniformValue for &[f32] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::FloatArray(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[f32; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::FloatArray(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[f32; 2]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec2Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[f32; 2]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec2Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[f32; 3]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec3Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[f32; 3]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec3Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[f32; 4]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec4Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[f32; 4]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Vec4Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[i32] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntArray(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[i32; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntArray(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[i32; 2]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec2Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[i32; 2]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec2Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[i32; 3]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec3Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[i32; 3]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec3Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[i32; 4]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec4Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[i32; 4]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::IntVec4Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[u32] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntArray(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[u32; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntArray(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[u32; 2]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec2Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[u32; 2]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec2Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[u32; 3]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec3Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[u32; 3]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec3Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[u32; 4]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec4Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[u32; 4]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::UnsignedIntVec4Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[[f32; 2]; 2]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat2Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[[f32; 2]; 2]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat2Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[[f32; 3]; 3]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat3Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[[f32; 3]; 3]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat3Array(&self[..])
+    }
+}
+
+impl AsUniformValue for &[[[f32; 4]; 4]] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat4Array(self)
+    }
+}
+
+impl<const N: usize> AsUniformValue for &[[[f32; 4]; 4]; N] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue<'_> {
+        UniformValue::Mat4Array(&self[..])
+    }
+}
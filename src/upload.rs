@@ -0,0 +1,408 @@
+/*!
+Uploading of resources from a background thread.
+
+Creating a big texture or buffer means copying all of its data to the GPU, which can take long
+enough to make a frame miss its deadline. A `ResourceUploader` owns a second OpenGL context that
+shares its objects with the main one, and runs the uploads on its own thread with this context.
+
+Each upload returns an `UploadFuture`. The object is created by the background thread, detached
+from the upload context with a fence, and attached to the main context once the future is
+resolved. The future can be polled with `is_ready`, blocked on with `wait`, or `.await`ed.
+
+```no_run
+# use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+# fn example<T>(display: glium::Display<T>) where T: SurfaceTypeTrait + ResizeableSurface {
+# let pixels = vec![0u8; 1024 * 1024 * 4];
+let uploader = display.new_shared_upload_context().unwrap();
+
+let image = glium::texture::RawImage2d::from_raw_rgba(pixels, (1024, 1024));
+let future = uploader.create_texture_async(image, glium::texture::MipmapsOption::NoMipmap);
+
+// later, for example once per frame
+if future.is_ready() {
+    let texture = future.wait().unwrap();
+}
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{self, Poll, Waker};
+use std::thread;
+
+use crate::backend::{Backend, Facade};
+use crate::buffer::{Buffer, BufferCreationError, BufferMode, BufferTransfer, BufferType};
+use crate::context::Context;
+use crate::debug::DebugCallbackBehavior;
+use crate::texture::{MipmapsOption, Texture2d, Texture2dDataSource, TextureCreationError};
+use crate::texture::TextureTransfer;
+
+/// Error that can happen while uploading a resource in the background.
+#[derive(Debug)]
+pub enum UploadError {
+    /// The upload thread has stopped before the resource could be created.
+    ThreadStopped,
+    /// The upload context could not be initialized. All the uploads of the `ResourceUploader`
+    /// fail with this error.
+    ContextCreationError(Arc<dyn Error + Send + Sync>),
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+    /// Error while creating the buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::ThreadStopped => write!(fmt, "The upload thread has stopped"),
+            UploadError::ContextCreationError(err) =>
+                write!(fmt, "The upload context could not be initialized: {}", err),
+            UploadError::TextureCreationError(err) => write!(fmt, "{}", err),
+            UploadError::BufferCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for UploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UploadError::ThreadStopped => None,
+            UploadError::ContextCreationError(err) => Some(&**err),
+            UploadError::TextureCreationError(err) => Some(err),
+            UploadError::BufferCreationError(err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for UploadError {
+    #[inline]
+    fn from(err: TextureCreationError) -> UploadError {
+        UploadError::TextureCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for UploadError {
+    #[inline]
+    fn from(err: BufferCreationError) -> UploadError {
+        UploadError::BufferCreationError(err)
+    }
+}
+
+/// Object that can be created on the upload thread and moved to the main context.
+pub trait Uploadable: Sized {
+    /// The detached version of the object, which is sent between the threads.
+    type Transfer: Send + 'static;
+
+    /// Detaches the object from its context.
+    fn into_transfer(self) -> Self::Transfer;
+
+    /// Attaches the object to the context of `facade`.
+    ///
+    /// # Safety
+    ///
+    /// The context of `facade` must share lists with the context the object was detached from.
+    unsafe fn from_transfer<F: ?Sized + Facade>(facade: &F, transfer: Self::Transfer) -> Self;
+}
+
+impl Uploadable for Texture2d {
+    type Transfer = TextureTransfer<Texture2d>;
+
+    #[inline]
+    fn into_transfer(self) -> TextureTransfer<Texture2d> {
+        Texture2d::into_transfer(self)
+    }
+
+    #[inline]
+    unsafe fn from_transfer<F: ?Sized + Facade>(facade: &F, transfer: TextureTransfer<Texture2d>)
+                                                -> Texture2d
+    {
+        Texture2d::from_transfer(facade, transfer)
+    }
+}
+
+impl<T> Uploadable for Buffer<[T]> where [T]: crate::buffer::Content, T: Copy + 'static {
+    type Transfer = BufferTransfer<[T]>;
+
+    #[inline]
+    fn into_transfer(self) -> BufferTransfer<[T]> {
+        Buffer::into_transfer(self)
+    }
+
+    #[inline]
+    unsafe fn from_transfer<F: ?Sized + Facade>(facade: &F, transfer: BufferTransfer<[T]>)
+                                                -> Buffer<[T]>
+    {
+        Buffer::from_transfer(facade, transfer)
+    }
+}
+
+type Job = Box<dyn FnOnce(&Rc<Context>) + Send>;
+
+/// Error that stopped the upload thread, if any.
+type Failure = Arc<Mutex<Option<Arc<dyn Error + Send + Sync>>>>;
+
+/// Runs uploads on a background thread with a context that shares lists with the main one.
+///
+/// Dropping the uploader waits for the uploads that are still queued.
+pub struct ResourceUploader {
+    context: Rc<Context>,
+    jobs: Option<Sender<Job>>,
+    failure: Failure,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl ResourceUploader {
+    /// Starts the upload thread.
+    ///
+    /// `build_backend` is called on the upload thread, and must return a backend whose context
+    /// is current on this thread. If it returns an error, all the uploads fail with
+    /// `UploadError::ContextCreationError`.
+    ///
+    /// # Safety
+    ///
+    /// The context of the backend must share lists with the context of `facade`.
+    pub unsafe fn new<F: ?Sized, B, E, C>(facade: &F, build_backend: C) -> ResourceUploader
+        where F: Facade, B: Backend + 'static, E: Error + Send + Sync + 'static,
+              C: FnOnce() -> Result<B, E> + Send + 'static
+    {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let failure: Failure = Arc::new(Mutex::new(None));
+
+        let thread_failure = failure.clone();
+        let thread = thread::Builder::new()
+            .name("glium upload".to_owned())
+            .spawn(move || {
+                let context = build_backend()
+                    .map_err(|err| Arc::new(err) as Arc<dyn Error + Send + Sync>)
+                    .and_then(|backend| {
+                        Context::new(backend, true, DebugCallbackBehavior::default())
+                            .map_err(|err| Arc::new(err) as Arc<dyn Error + Send + Sync>)
+                    });
+
+                let context = match context {
+                    Ok(context) => context,
+                    Err(err) => {
+                        // the error must be stored before the receiver is dropped, which makes
+                        // all the pending futures fail
+                        *thread_failure.lock().unwrap() = Some(err);
+                        return;
+                    },
+                };
+
+                for job in receiver {
+                    job(&context);
+                }
+            })
+            .unwrap();
+
+        ResourceUploader {
+            context: facade.get_context().clone(),
+            jobs: Some(jobs),
+            failure,
+            thread: Some(thread),
+        }
+    }
+
+    /// Creates a texture on the upload thread.
+    pub fn create_texture_async<D>(&self, data: D, mipmaps: MipmapsOption)
+                                   -> UploadFuture<Texture2d>
+        where D: Texture2dDataSource<'static> + Send + 'static
+    {
+        self.run(move |context| {
+            Texture2d::with_mipmaps(context, data, mipmaps).map_err(From::from)
+        })
+    }
+
+    /// Creates a buffer on the upload thread.
+    pub fn create_buffer_async<T>(&self, data: Vec<T>, ty: BufferType, mode: BufferMode)
+                                  -> UploadFuture<Buffer<[T]>>
+        where [T]: crate::buffer::Content, T: Copy + Send + 'static
+    {
+        self.run(move |context| {
+            Buffer::new(context, &data[..], ty, mode).map_err(From::from)
+        })
+    }
+
+    /// Runs a function on the upload thread and returns the object it creates.
+    pub fn run<R, G>(&self, function: G) -> UploadFuture<R>
+        where R: Uploadable, G: FnOnce(&Rc<Context>) -> Result<R, UploadError> + Send + 'static
+    {
+        let slot = Arc::new(Slot {
+            state: Mutex::new(SlotState { result: None, waker: None, dropped: false }),
+            condvar: Condvar::new(),
+        });
+
+        let mut sender = SlotSender { slot: Some(slot.clone()), failure: self.failure.clone() };
+        let job: Job = Box::new(move |context| {
+            let result = function(context).map(|object| {
+                let transfer = object.into_transfer();
+                // submitting the commands now, so that the main thread doesn't wait for them
+                context.flush();
+                transfer
+            });
+
+            if let Err(transfer) = sender.send(result) {
+                // the future has been dropped, destroying the object in this context
+                drop(unsafe { R::from_transfer(context, transfer) });
+            }
+        });
+
+        // if the thread has stopped, the job is dropped and the future fails
+        let _ = self.jobs.as_ref().unwrap().send(job);
+
+        UploadFuture {
+            context: self.context.clone(),
+            slot,
+        }
+    }
+}
+
+impl fmt::Debug for ResourceUploader {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "ResourceUploader")
+    }
+}
+
+impl Drop for ResourceUploader {
+    fn drop(&mut self) {
+        self.jobs.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Slot<T> {
+    state: Mutex<SlotState<T>>,
+    condvar: Condvar,
+}
+
+struct SlotState<T> {
+    result: Option<Result<T, UploadError>>,
+    waker: Option<Waker>,
+    dropped: bool,
+}
+
+/// Sending side of a `Slot`. If destroyed without sending anything, stores the error that
+/// stopped the upload thread, or `ThreadStopped`.
+struct SlotSender<T> {
+    slot: Option<Arc<Slot<T>>>,
+    failure: Failure,
+}
+
+impl<T> SlotSender<T> {
+    /// Stores the result, or gives back the transfer if the future has been dropped.
+    fn send(&mut self, result: Result<T, UploadError>) -> Result<(), T> {
+        let slot = self.slot.take().unwrap();
+        let mut state = slot.state.lock().unwrap();
+
+        if state.dropped {
+            return match result {
+                Ok(transfer) => Err(transfer),
+                Err(_) => Ok(()),
+            };
+        }
+
+        state.result = Some(result);
+        slot.condvar.notify_all();
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Drop for SlotSender<T> {
+    fn drop(&mut self) {
+        if self.slot.is_some() {
+            let err = match *self.failure.lock().unwrap() {
+                Some(ref err) => UploadError::ContextCreationError(err.clone()),
+                None => UploadError::ThreadStopped,
+            };
+            let _ = self.send(Err(err));
+        }
+    }
+}
+
+/// An object that is being created by a `ResourceUploader`.
+///
+/// Dropping the future destroys the object.
+pub struct UploadFuture<R> where R: Uploadable {
+    context: Rc<Context>,
+    slot: Arc<Slot<R::Transfer>>,
+}
+
+impl<R> UploadFuture<R> where R: Uploadable {
+    /// Returns true if the object has been created by the upload thread.
+    ///
+    /// When this returns true, `wait` only blocks until the GPU has finished the upload.
+    pub fn is_ready(&self) -> bool {
+        self.slot.state.lock().unwrap().result.is_some()
+    }
+
+    /// Blocks until the object is created, then attaches it to the main context.
+    pub fn wait(self) -> Result<R, UploadError> {
+        let result = {
+            let mut state = self.slot.state.lock().unwrap();
+            loop {
+                if let Some(result) = state.result.take() {
+                    break result;
+                }
+                state = self.slot.condvar.wait(state).unwrap();
+            }
+        };
+
+        self.attach(result)
+    }
+
+    fn attach(&self, result: Result<R::Transfer, UploadError>) -> Result<R, UploadError> {
+        // the upload context shares lists with the main context
+        result.map(|transfer| unsafe { R::from_transfer(&self.context, transfer) })
+    }
+}
+
+impl<R> Future for UploadFuture<R> where R: Uploadable {
+    type Output = Result<R, UploadError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let result = {
+            let mut state = self.slot.state.lock().unwrap();
+            match state.result.take() {
+                Some(result) => result,
+                None => {
+                    state.waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                },
+            }
+        };
+
+        Poll::Ready(self.attach(result))
+    }
+}
+
+impl<R> fmt::Debug for UploadFuture<R> where R: Uploadable {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "UploadFuture")
+    }
+}
+
+impl<R> Drop for UploadFuture<R> where R: Uploadable {
+    fn drop(&mut self) {
+        let result = {
+            let mut state = self.slot.state.lock().unwrap();
+            state.dropped = true;
+            state.result.take()
+        };
+
+        if let Some(Ok(transfer)) = result {
+            drop(unsafe { R::from_transfer(&self.context, transfer) });
+        }
+    }
+}
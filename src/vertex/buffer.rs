@@ -9,6 +9,7 @@ use crate::vertex::format::VertexFormat;
 
 use crate::gl;
 use crate::GlObject;
+use crate::Recreatable;
 
 use crate::backend::Facade;
 use crate::version::{Api, Version};
@@ -349,6 +350,13 @@ impl<T> VertexBuffer<T> where T: Copy + Send + 'static {
     }
 }
 
+impl<T> Recreatable for VertexBuffer<T> where T: Copy {
+    #[inline]
+    fn recreate(&mut self) -> Result<(), crate::Error> {
+        self.buffer.recreate()
+    }
+}
+
 impl<T> From<Buffer<[T]>> for VertexBuffer<T> where T: Vertex + Copy {
     #[inline]
     fn from(buffer: Buffer<[T]>) -> VertexBuffer<T> {
@@ -446,6 +454,15 @@ pub struct VertexBufferAny {
 }
 
 impl VertexBufferAny {
+    /// Builds a `VertexBufferAny` from a buffer and the format of its elements.
+    #[inline]
+    pub(super) fn from_buffer_any(buffer: BufferAny, bindings: VertexFormat) -> VertexBufferAny {
+        VertexBufferAny {
+            buffer,
+            bindings,
+        }
+    }
+
     /// Returns the number of bytes between two consecutive elements in the buffer.
     #[inline]
     pub fn get_elements_size(&self) -> usize {
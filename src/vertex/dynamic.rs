@@ -0,0 +1,344 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use crate::backend::Facade;
+use crate::buffer::{Buffer, BufferAny, BufferMode, BufferType};
+use crate::version::{Api, Version};
+use crate::vertex::{AttributeType, PerInstance, VertexBufferAny, VertexFormat, VerticesSource};
+use crate::vertex::BufferCreationError;
+use crate::vertex::buffer::InstancingNotSupported;
+use crate::CapabilitiesSource;
+
+/// Error that can happen when building a `DynamicVertexFormat`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicVertexFormatError {
+    /// The stride is 0.
+    ZeroStride,
+
+    /// An attribute doesn't fit between its offset and the stride.
+    AttributeOutOfBounds {
+        /// Name of the attribute.
+        name: String,
+    },
+
+    /// Two attributes have the same name.
+    DuplicateAttribute {
+        /// Name of the attributes.
+        name: String,
+    },
+}
+
+impl fmt::Display for DynamicVertexFormatError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicVertexFormatError::ZeroStride =>
+                write!(fmt, "The stride of the vertex format is 0"),
+            DynamicVertexFormatError::AttributeOutOfBounds { name } =>
+                write!(fmt, "The attribute `{}` doesn't fit in the stride of the vertex format",
+                       name),
+            DynamicVertexFormatError::DuplicateAttribute { name } =>
+                write!(fmt, "The attribute `{}` is present multiple times in the vertex format",
+                       name),
+        }
+    }
+}
+
+impl Error for DynamicVertexFormatError {}
+
+/// Describes the layout of a vertex at runtime.
+///
+/// This is an alternative to `implement_vertex!` when the layout isn't known at compile time,
+/// for example when it comes from the accessors of a glTF file.
+///
+/// # Example
+///
+/// ```
+/// use glium::vertex::{AttributeType, DynamicVertexFormat};
+///
+/// // a position followed by a normalized RGBA color
+/// let format = DynamicVertexFormat::new(16)
+///     .attribute("position", 0, AttributeType::F32F32F32)
+///     .normalized_attribute("color", 12, AttributeType::U8U8U8U8);
+///
+/// let bindings = format.build().unwrap();
+/// assert_eq!(bindings.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicVertexFormat {
+    attributes: Vec<(Cow<'static, str>, usize, i32, AttributeType, bool)>,
+    stride: usize,
+}
+
+impl DynamicVertexFormat {
+    /// Builds a format without attributes, whose vertices are `stride` bytes apart.
+    #[inline]
+    pub fn new(stride: usize) -> DynamicVertexFormat {
+        DynamicVertexFormat {
+            attributes: Vec::new(),
+            stride,
+        }
+    }
+
+    /// Adds an attribute at `offset` bytes from the start of each vertex.
+    #[inline]
+    pub fn attribute<S>(self, name: S, offset: usize, ty: AttributeType) -> DynamicVertexFormat
+        where S: Into<Cow<'static, str>>
+    {
+        self.push(name.into(), offset, ty, false)
+    }
+
+    /// Adds an attribute whose integer values are normalized to `[0.0, 1.0]`, or `[-1.0, 1.0]`
+    /// for signed types, when they are read by the vertex shader.
+    #[inline]
+    pub fn normalized_attribute<S>(self, name: S, offset: usize, ty: AttributeType)
+                                   -> DynamicVertexFormat
+        where S: Into<Cow<'static, str>>
+    {
+        self.push(name.into(), offset, ty, true)
+    }
+
+    #[inline]
+    fn push(mut self, name: Cow<'static, str>, offset: usize, ty: AttributeType,
+            normalize: bool) -> DynamicVertexFormat
+    {
+        self.attributes.push((name, offset, -1, ty, normalize));
+        self
+    }
+
+    /// Returns the number of bytes between the start of two consecutive vertices.
+    #[inline]
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Checks the attributes and turns them into a `VertexFormat`.
+    ///
+    /// A `VertexFormat` is a `'static` slice, so the attributes are kept in memory until the end
+    /// of the program. Building the same layout multiple times returns the same slice, which also
+    /// allows the vertex array objects of this layout to be reused.
+    pub fn build(&self) -> Result<VertexFormat, DynamicVertexFormatError> {
+        if self.stride == 0 {
+            return Err(DynamicVertexFormatError::ZeroStride);
+        }
+
+        for (index, (name, offset, _, ty, _)) in self.attributes.iter().enumerate() {
+            if offset + ty.get_size_bytes() > self.stride {
+                return Err(DynamicVertexFormatError::AttributeOutOfBounds {
+                    name: name.to_string(),
+                });
+            }
+
+            if self.attributes[.. index].iter().any(|a| a.0 == *name) {
+                return Err(DynamicVertexFormatError::DuplicateAttribute {
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        // the formats that have been built so far
+        static FORMATS: Mutex<Vec<VertexFormat>> = Mutex::new(Vec::new());
+
+        let mut formats = FORMATS.lock().unwrap();
+        if let Some(format) = formats.iter().find(|f| **f == &self.attributes[..]) {
+            return Ok(format);
+        }
+
+        let format: VertexFormat = Box::leak(self.attributes.clone().into_boxed_slice());
+        formats.push(format);
+        Ok(format)
+    }
+}
+
+/// Error that can happen when creating a `RawVertexBuffer`.
+#[derive(Debug, Clone)]
+pub enum RawVertexBufferCreationError {
+    /// The format is not valid.
+    InvalidFormat(DynamicVertexFormatError),
+
+    /// The length of the data is not a multiple of the stride of the format.
+    WrongDataLength {
+        /// Length of the data in bytes.
+        len: usize,
+        /// Stride of the format.
+        stride: usize,
+    },
+
+    /// Error while creating the vertex buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for RawVertexBufferCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawVertexBufferCreationError::InvalidFormat(err) => write!(fmt, "{}", err),
+            RawVertexBufferCreationError::WrongDataLength { len, stride } =>
+                write!(fmt, "The length of the data ({} bytes) is not a multiple of the stride \
+                             of the vertex format ({} bytes)", len, stride),
+            RawVertexBufferCreationError::BufferCreationError(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for RawVertexBufferCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RawVertexBufferCreationError::InvalidFormat(err) => Some(err),
+            RawVertexBufferCreationError::WrongDataLength { .. } => None,
+            RawVertexBufferCreationError::BufferCreationError(err) => Some(err),
+        }
+    }
+}
+
+impl From<DynamicVertexFormatError> for RawVertexBufferCreationError {
+    #[inline]
+    fn from(err: DynamicVertexFormatError) -> RawVertexBufferCreationError {
+        RawVertexBufferCreationError::InvalidFormat(err)
+    }
+}
+
+impl From<BufferCreationError> for RawVertexBufferCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> RawVertexBufferCreationError {
+        RawVertexBufferCreationError::BufferCreationError(err)
+    }
+}
+
+/// A vertex buffer whose content is uploaded as bytes and described by a
+/// `DynamicVertexFormat`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, bytes: Vec<u8>)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// use glium::vertex::{AttributeType, DynamicVertexFormat, RawVertexBuffer};
+///
+/// let format = DynamicVertexFormat::new(20)
+///     .attribute("position", 0, AttributeType::F32F32F32)
+///     .attribute("tex_coords", 12, AttributeType::F32F32);
+///
+/// let vertex_buffer = RawVertexBuffer::new(&display, &format, &bytes).unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RawVertexBuffer {
+    buffer: BufferAny,
+    bindings: VertexFormat,
+}
+
+impl RawVertexBuffer {
+    /// Builds a new vertex buffer from bytes laid out according to `format`.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, format: &DynamicVertexFormat, data: &[u8])
+                          -> Result<RawVertexBuffer, RawVertexBufferCreationError>
+        where F: Facade
+    {
+        RawVertexBuffer::new_impl(facade, format, data, BufferMode::Default)
+    }
+
+    /// Builds a new vertex buffer that is intended to be modified frequently.
+    #[inline]
+    pub fn dynamic<F: ?Sized>(facade: &F, format: &DynamicVertexFormat, data: &[u8])
+                              -> Result<RawVertexBuffer, RawVertexBufferCreationError>
+        where F: Facade
+    {
+        RawVertexBuffer::new_impl(facade, format, data, BufferMode::Dynamic)
+    }
+
+    fn new_impl<F: ?Sized>(facade: &F, format: &DynamicVertexFormat, data: &[u8],
+                           mode: BufferMode)
+                           -> Result<RawVertexBuffer, RawVertexBufferCreationError>
+        where F: Facade
+    {
+        let bindings = format.build()?;
+
+        if bindings.iter().any(|&(_, _, _, ty, _)| !ty.is_supported(facade.get_context())) {
+            return Err(BufferCreationError::FormatNotSupported.into());
+        }
+
+        if data.len() % format.stride != 0 {
+            return Err(RawVertexBufferCreationError::WrongDataLength {
+                len: data.len(),
+                stride: format.stride,
+            });
+        }
+
+        let buffer = Buffer::new(facade, data, BufferType::ArrayBuffer, mode)
+            .map_err(BufferCreationError::from)?;
+        let mut buffer: BufferAny = buffer.into();
+        buffer.set_elements_size(format.stride);
+
+        Ok(RawVertexBuffer {
+            buffer,
+            bindings,
+        })
+    }
+
+    /// Returns the number of vertices in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.get_elements_count()
+    }
+
+    /// Returns true if the buffer doesn't contain any vertex.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes between two consecutive vertices.
+    #[inline]
+    pub fn get_stride(&self) -> usize {
+        self.buffer.get_elements_size()
+    }
+
+    /// Returns the associated `VertexFormat`.
+    #[inline]
+    pub fn get_bindings(&self) -> &VertexFormat {
+        &self.bindings
+    }
+
+    /// Creates a marker that instructs glium to use multiple instances.
+    ///
+    /// See `VertexBuffer::per_instance`.
+    #[inline]
+    pub fn per_instance(&self) -> Result<PerInstance<'_>, InstancingNotSupported> {
+        let context = self.buffer.get_context();
+
+        if !(context.get_version() >= &Version(Api::Gl, 3, 3)) &&
+            !(context.get_version() >= &Version(Api::GlEs, 3, 0)) &&
+            !context.get_extensions().gl_arb_instanced_arrays
+        {
+            return Err(InstancingNotSupported);
+        }
+
+        Ok(PerInstance(self.buffer.as_slice_any(), self.bindings))
+    }
+}
+
+impl Deref for RawVertexBuffer {
+    type Target = BufferAny;
+
+    #[inline]
+    fn deref(&self) -> &BufferAny {
+        &self.buffer
+    }
+}
+
+impl From<RawVertexBuffer> for VertexBufferAny {
+    #[inline]
+    fn from(buffer: RawVertexBuffer) -> VertexBufferAny {
+        VertexBufferAny::from_buffer_any(buffer.buffer, buffer.bindings)
+    }
+}
+
+impl<'a> From<&'a RawVertexBuffer> for VerticesSource<'a> {
+    #[inline]
+    fn from(buffer: &'a RawVertexBuffer) -> VerticesSource<'a> {
+        VerticesSource::VertexBuffer(buffer.buffer.as_slice_any(), buffer.bindings, false)
+    }
+}
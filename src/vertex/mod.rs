@@ -61,6 +61,21 @@ let vertex_buffer = glium::vertex::VertexBuffer::new(&display, data);
 # }
 ```
 
+If the layout of the vertices is only known at runtime, for example when loading a model file,
+describe it with a `DynamicVertexFormat` and upload the bytes in a `RawVertexBuffer` instead.
+
+## Compact attribute types
+
+Some attribute types are stored in fewer bytes than their `f32` equivalents, which reduces the
+memory and the bandwidth used by big meshes. The GPU converts them to floating-point values
+before they reach the vertex shader.
+
+ - `F16` is a half-precision floating-point value. With the `half` feature, `half::f16` can be
+   used as well.
+ - `I2I10I10I10Reversed` and `U2U10U10U10Reversed` pack four components in 32 bits, and are
+   usually used for normals and tangents with `normalize(true)` in `implement_vertex!`.
+ - `F10F11F11UnsignedIntReversed` packs three positive floating-point values in 32 bits.
+
 ## Drawing
 
 When you draw, you can pass either a single vertex source or a tuple of multiple sources.
@@ -130,20 +145,29 @@ The program you use when drawing must be the same as you the one you created the
 with, or else you will get an error.
 
 */
-use std::iter::Chain;
+use std::iter::{Chain, Cloned};
+use std::slice;
 use std::option::IntoIter;
 
 pub use self::buffer::{VertexBuffer, VertexBufferAny};
 pub use self::buffer::VertexBufferSlice;
 pub use self::buffer::CreationError as BufferCreationError;
+pub use self::dynamic::{DynamicVertexFormat, DynamicVertexFormatError};
+pub use self::dynamic::{RawVertexBuffer, RawVertexBufferCreationError};
 pub use self::format::{AttributeType, VertexFormat};
+pub use self::packed::{F16, I2I10I10I10Reversed, U2U10U10U10Reversed};
+pub use self::packed::F10F11F11UnsignedIntReversed;
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
+pub use self::transform_feedback::{TransformFeedbackSessionCreationError, TransformFeedbackPauseError};
+pub use self::transform_feedback::{is_transform_feedback_object_supported, TransformFeedbackObject};
 
 use crate::buffer::BufferAnySlice;
 use crate::CapabilitiesSource;
 
 mod buffer;
+mod dynamic;
 mod format;
+mod packed;
 mod transform_feedback;
 
 /// Describes the source to use for the vertices when drawing.
@@ -223,6 +247,15 @@ impl<'a, T> MultiVerticesSource<'a> for T
     }
 }
 
+impl<'a, 'b> MultiVerticesSource<'b> for &'a [VerticesSource<'b>] {
+    type Iterator = Cloned<slice::Iter<'a, VerticesSource<'b>>>;
+
+    #[inline]
+    fn iter(self) -> Cloned<slice::Iter<'a, VerticesSource<'b>>> {
+        <[VerticesSource<'b>]>::iter(self).cloned()
+    }
+}
+
 macro_rules! impl_for_tuple {
     ($t:ident) => (
         impl<'a, $t> MultiVerticesSource<'a> for ($t,)
@@ -0,0 +1,233 @@
+use crate::vertex::{Attribute, AttributeType};
+
+/// A half-precision floating-point value, as stored in a vertex buffer.
+///
+/// Corresponds to `AttributeType::F16`.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct F16(pub u16);
+
+impl F16 {
+    /// Converts a `f32` to the nearest half-precision value.
+    ///
+    /// Values that are too large become infinite.
+    pub fn from_f32(value: f32) -> F16 {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7f_ffff;
+
+        // infinity and NaN
+        if exponent == 0xff {
+            return F16(sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 });
+        }
+
+        let exponent = exponent - 127 + 15;
+        if exponent >= 0x1f {
+            return F16(sign | 0x7c00);
+        }
+
+        // the value is either a denormal or rounded to zero
+        if exponent <= 0 {
+            if exponent < -10 {
+                return F16(sign);
+            }
+
+            let mantissa = mantissa | 0x80_0000;
+            let shift = (14 - exponent) as u32;
+            return F16(sign | round_shift(mantissa, shift) as u16);
+        }
+
+        // the carry of the rounding can overflow in the exponent, which gives the right result
+        F16(sign | round_shift(((exponent as u32) << 23) | mantissa, 13) as u16)
+    }
+
+    /// Converts this value to a `f32`. The conversion is exact.
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exponent = ((self.0 >> 10) & 0x1f) as u32;
+        let mantissa = (self.0 & 0x3ff) as u32;
+
+        let bits = match exponent {
+            0 => {
+                // denormals are `mantissa * 2^-24`
+                let value = mantissa as f32 / (1 << 24) as f32;
+                return if sign != 0 { -value } else { value };
+            },
+            0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+            _ => sign | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+        };
+
+        f32::from_bits(bits)
+    }
+}
+
+impl From<f32> for F16 {
+    #[inline]
+    fn from(value: f32) -> F16 {
+        F16::from_f32(value)
+    }
+}
+
+impl From<F16> for f32 {
+    #[inline]
+    fn from(value: F16) -> f32 {
+        value.to_f32()
+    }
+}
+
+/// Shifts `value` to the right and rounds the result to the nearest, ties to even.
+#[inline]
+fn round_shift(value: u32, shift: u32) -> u32 {
+    let half = 1 << (shift - 1);
+    let result = value >> shift;
+
+    if (value & half) != 0 && (value & (3 * half - 1)) != 0 {
+        result + 1
+    } else {
+        result
+    }
+}
+
+/// Four signed components packed in 32 bits. From MSB to LSB: two bits for `w`, then ten bits
+/// for `z`, `y` and `x`.
+///
+/// Corresponds to `AttributeType::I2I10I10I10Reversed`.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct I2I10I10I10Reversed(pub u32);
+
+impl I2I10I10I10Reversed {
+    /// Packs four components. Only the lowest ten bits of `x`, `y` and `z` and the lowest two
+    /// bits of `w` are kept.
+    #[inline]
+    pub fn new(x: i16, y: i16, z: i16, w: i8) -> I2I10I10I10Reversed {
+        I2I10I10I10Reversed((x as u32 & 0x3ff) | ((y as u32 & 0x3ff) << 10) |
+                            ((z as u32 & 0x3ff) << 20) | ((w as u32 & 0x3) << 30))
+    }
+
+    /// Packs four values between -1.0 and 1.0, for an attribute that is normalized.
+    pub fn from_normalized(x: f32, y: f32, z: f32, w: f32) -> I2I10I10I10Reversed {
+        let pack = |value: f32, max: f32| (value.clamp(-1.0, 1.0) * max).round() as i16;
+        I2I10I10I10Reversed::new(pack(x, 511.0), pack(y, 511.0), pack(z, 511.0),
+                                 pack(w, 1.0) as i8)
+    }
+}
+
+/// Four unsigned components packed in 32 bits. From MSB to LSB: two bits for `w`, then ten
+/// bits for `z`, `y` and `x`.
+///
+/// Corresponds to `AttributeType::U2U10U10U10Reversed`.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct U2U10U10U10Reversed(pub u32);
+
+impl U2U10U10U10Reversed {
+    /// Packs four components. Only the lowest ten bits of `x`, `y` and `z` and the lowest two
+    /// bits of `w` are kept.
+    #[inline]
+    pub fn new(x: u16, y: u16, z: u16, w: u8) -> U2U10U10U10Reversed {
+        U2U10U10U10Reversed((x as u32 & 0x3ff) | ((y as u32 & 0x3ff) << 10) |
+                            ((z as u32 & 0x3ff) << 20) | ((w as u32 & 0x3) << 30))
+    }
+
+    /// Packs four values between 0.0 and 1.0, for an attribute that is normalized.
+    pub fn from_normalized(x: f32, y: f32, z: f32, w: f32) -> U2U10U10U10Reversed {
+        let pack = |value: f32, max: f32| (value.clamp(0.0, 1.0) * max).round() as u16;
+        U2U10U10U10Reversed::new(pack(x, 1023.0), pack(y, 1023.0), pack(z, 1023.0),
+                                 pack(w, 3.0) as u8)
+    }
+}
+
+/// Three positive floating-point values packed in 32 bits. From MSB to LSB: a 10 bits value for
+/// `z`, then 11 bits values for `y` and `x`. All the values have a 5 bits exponent.
+///
+/// Corresponds to `AttributeType::F10F11F11UnsignedIntReversed`.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct F10F11F11UnsignedIntReversed(pub u32);
+
+impl F10F11F11UnsignedIntReversed {
+    /// Packs three values. Negative values become 0.0, and the precision of the other values is
+    /// reduced by truncating their mantissa.
+    pub fn new(x: f32, y: f32, z: f32) -> F10F11F11UnsignedIntReversed {
+        F10F11F11UnsignedIntReversed(unsigned_float(x, 6) | (unsigned_float(y, 6) << 11) |
+                                     (unsigned_float(z, 5) << 22))
+    }
+}
+
+/// Converts a value to an unsigned float with a 5 bits exponent and `mantissa_bits` bits of
+/// mantissa.
+fn unsigned_float(value: f32, mantissa_bits: u32) -> u32 {
+    if value.is_nan() {
+        return (0x1f << mantissa_bits) | 1;
+    }
+
+    if value <= 0.0 {
+        return 0;
+    }
+
+    // half-precision values have the same exponent and a 10 bits mantissa
+    (F16::from_f32(value).0 as u32) >> (10 - mantissa_bits)
+}
+
+macro_rules! impl_half_float_attributes {
+    ($t:ty) => (
+        unsafe impl Attribute for $t {
+            const TYPE: AttributeType = AttributeType::F16;
+        }
+
+        unsafe impl Attribute for ($t, $t) {
+            const TYPE: AttributeType = AttributeType::F16F16;
+        }
+
+        unsafe impl Attribute for [$t; 2] {
+            const TYPE: AttributeType = AttributeType::F16F16;
+        }
+
+        unsafe impl Attribute for ($t, $t, $t) {
+            const TYPE: AttributeType = AttributeType::F16F16F16;
+        }
+
+        unsafe impl Attribute for [$t; 3] {
+            const TYPE: AttributeType = AttributeType::F16F16F16;
+        }
+
+        unsafe impl Attribute for ($t, $t, $t, $t) {
+            const TYPE: AttributeType = AttributeType::F16F16F16F16;
+        }
+
+        unsafe impl Attribute for [$t; 4] {
+            const TYPE: AttributeType = AttributeType::F16F16F16F16;
+        }
+
+        unsafe impl Attribute for [[$t; 2]; 2] {
+            const TYPE: AttributeType = AttributeType::F16x2x2;
+        }
+
+        unsafe impl Attribute for [[$t; 3]; 3] {
+            const TYPE: AttributeType = AttributeType::F16x3x3;
+        }
+
+        unsafe impl Attribute for [[$t; 4]; 4] {
+            const TYPE: AttributeType = AttributeType::F16x4x4;
+        }
+    );
+}
+
+impl_half_float_attributes!(F16);
+
+#[cfg(feature = "half")]
+impl_half_float_attributes!(half::f16);
+
+unsafe impl Attribute for I2I10I10I10Reversed {
+    const TYPE: AttributeType = AttributeType::I2I10I10I10Reversed;
+}
+
+unsafe impl Attribute for U2U10U10U10Reversed {
+    const TYPE: AttributeType = AttributeType::U2U10U10U10Reversed;
+}
+
+unsafe impl Attribute for F10F11F11UnsignedIntReversed {
+    const TYPE: AttributeType = AttributeType::F10F11F11UnsignedIntReversed;
+}
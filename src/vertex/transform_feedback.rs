@@ -1,17 +1,20 @@
 use std::{ mem, fmt };
 use std::error::Error;
+use std::rc::Rc;
 
 use crate::version::Api;
 use crate::version::Version;
 use crate::context::CommandContext;
+use crate::context::{Context, NonSharedObject};
 use crate::backend::Facade;
 use crate::BufferExt;
 use crate::GlObject;
 use crate::ContextExt;
+use crate::ProgramExt;
 use crate::CapabilitiesSource;
 use crate::TransformFeedbackSessionExt;
 use crate::buffer::{Buffer, BufferAnySlice};
-use crate::index::PrimitiveType;
+use crate::index::{IndicesSource, PrimitiveType};
 use crate::program::OutputPrimitives;
 use crate::program::Program;
 use crate::vertex::Vertex;
@@ -88,10 +91,21 @@ use crate::gl;
 /// println!("List of generated vertices: {:?}", result);
 /// # }
 /// ```
+///
+/// # Pausing and transform feedback objects
+///
+/// Drawing without the session ends it, and the next draw command with the session starts
+/// writing at the beginning of the buffer again. Call `pause` first to draw without capturing
+/// the vertices, then `resume` or draw with the session again to continue appending data.
+///
+/// A session created with `with_object` records its state in a `TransformFeedbackObject`. After
+/// the session has ended, the vertices can be drawn again with
+/// `TransformFeedbackObject::draw_transform_feedback`, without reading back their number.
 #[derive(Debug)]
 pub struct TransformFeedbackSession<'a> {
     buffer: BufferAnySlice<'a>,
     program: &'a Program,
+    object: Option<&'a TransformFeedbackObject>,
 }
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
@@ -119,6 +133,26 @@ impl fmt::Display for TransformFeedbackSessionCreationError {
 
 impl Error for TransformFeedbackSessionCreationError {}
 
+/// Error that can happen when pausing or resuming a `TransformFeedbackSession`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformFeedbackPauseError {
+    /// Pausing transform feedback is not supported by the OpenGL implementation.
+    NotSupported,
+}
+
+impl fmt::Display for TransformFeedbackPauseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::TransformFeedbackPauseError::*;
+        let desc = match *self {
+            NotSupported =>
+                "Pausing transform feedback is not supported by the OpenGL implementation",
+        };
+        fmt.write_str(desc)
+    }
+}
+
+impl Error for TransformFeedbackPauseError {}
+
 /// Returns true if transform feedback is supported by the OpenGL implementation.
 #[inline]
 pub fn is_transform_feedback_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
@@ -151,17 +185,88 @@ impl<'a> TransformFeedbackSession<'a> {
         Ok(TransformFeedbackSession {
             buffer: buffer.as_slice_any(),
             program,
+            object: None,
         })
     }
+
+    /// Builds a new transform feedback session that records its state in a transform feedback
+    /// object.
+    pub fn with_object<F: ?Sized, V>(facade: &F, program: &'a Program, buffer: &'a mut Buffer<[V]>,
+                                     object: &'a TransformFeedbackObject)
+                     -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
+                     where F: Facade, V: Vertex + Copy + Send + 'static
+    {
+        let mut session = TransformFeedbackSession::new(facade, program, buffer)?;
+        session.object = Some(object);
+        Ok(session)
+    }
+
+    /// Pauses the session. Draw commands that don't use the session don't end it while it is
+    /// paused, and the data written by the next draw commands that use it is appended after the
+    /// existing data.
+    ///
+    /// Does nothing if no draw command has used the session yet.
+    ///
+    /// Returns an error if `is_transform_feedback_object_supported` returns false.
+    pub fn pause(&self) -> Result<(), TransformFeedbackPauseError> {
+        let mut ctxt = self.buffer.get_context().make_current();
+        if !is_pause_supported(&ctxt) {
+            return Err(TransformFeedbackPauseError::NotSupported);
+        }
+
+        if self.is_current(&ctxt) && !ctxt.state.transform_feedback_paused {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = true;
+        }
+
+        Ok(())
+    }
+
+    /// Resumes the session after a call to `pause`.
+    ///
+    /// This is also done automatically by the next draw command that uses the session.
+    ///
+    /// Returns an error if `is_transform_feedback_object_supported` returns false.
+    pub fn resume(&self) -> Result<(), TransformFeedbackPauseError> {
+        let mut ctxt = self.buffer.get_context().make_current();
+        if !is_pause_supported(&ctxt) {
+            return Err(TransformFeedbackPauseError::NotSupported);
+        }
+
+        if self.is_current(&ctxt) && ctxt.state.transform_feedback_paused {
+            // transform feedback can only be resumed with the program that it was started with
+            self.program.use_program(&mut ctxt);
+
+            unsafe { ctxt.gl.ResumeTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = false;
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if transform feedback was started with this session and hasn't ended since.
+    pub(crate) fn is_current(&self, ctxt: &CommandContext<'_>) -> bool {
+        ctxt.state.transform_feedback_enabled.is_some() &&
+        ctxt.state.transform_feedback_object == self.object.map_or(0, |o| o.id) &&
+        ctxt.state.indexed_transform_feedback_buffer_bindings[0].buffer == self.buffer.get_id()
+    }
 }
 
 impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
     fn bind(&self, ctxt: &mut CommandContext<'_>, draw_primitives: PrimitiveType) {
-        // TODO: check that the state matches what is required
-        if ctxt.state.transform_feedback_enabled.is_some() {
-            unimplemented!();
+        if self.is_current(ctxt) {
+            // TODO: check that the primitives match
+            if ctxt.state.transform_feedback_paused {
+                unsafe { ctxt.gl.ResumeTransformFeedback(); }
+                ctxt.state.transform_feedback_paused = false;
+            }
+
+            return;
         }
 
+        Self::unbind(ctxt);
+        bind_object(ctxt, self.object.map_or(0, |o| o.id));
+
         // FIXME: use the memory barrier system
         self.buffer.bind_to_transform_feedback(ctxt, 0);
 
@@ -233,3 +338,165 @@ impl<'a> Drop for TransformFeedbackSession<'a> {
         Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, self.buffer.get_id());
     }
 }
+
+/// Returns true if transform feedback objects are supported by the OpenGL implementation.
+///
+/// This also indicates whether `TransformFeedbackSession::pause` and `resume` can be used.
+#[inline]
+pub fn is_transform_feedback_object_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+    let context = facade.get_context();
+
+    context.get_version() >= &Version(Api::Gl, 4, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_transform_feedback2
+}
+
+#[inline]
+fn is_pause_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 0) ||
+    ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.extensions.gl_arb_transform_feedback2
+}
+
+/// Binds a transform feedback object, or the default one if `id` is 0.
+fn bind_object(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
+    if ctxt.state.transform_feedback_object == id {
+        return;
+    }
+
+    unsafe { ctxt.gl.BindTransformFeedback(gl::TRANSFORM_FEEDBACK, id); }
+    ctxt.state.transform_feedback_object = id;
+
+    // the indexed bindings are part of the state of the transform feedback object
+    for binding in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
+        *binding = Default::default();
+    }
+}
+
+/// A transform feedback object, which stores the state of a transform feedback session.
+///
+/// Once a session created with `TransformFeedbackSession::with_object` has ended, the object
+/// remembers the number of vertices that have been written. `draw_transform_feedback` draws
+/// them without having to read this number back.
+///
+/// # Example
+///
+/// ```no_run
+/// # use glium::{implement_vertex, uniform};
+/// # use glium::Surface;
+/// # use glutin::surface::{ResizeableSurface, SurfaceTypeTrait};
+/// # fn example<T>(display: glium::Display<T>, program: glium::Program,
+/// #               draw_program: glium::Program, vb: glium::vertex::VertexBufferAny)
+/// #     where T: SurfaceTypeTrait + ResizeableSurface {
+/// # #[derive(Copy, Clone)] struct Vertex { output_val: (f32, f32) }
+/// # implement_vertex!(Vertex, output_val);
+/// use glium::index::{NoIndices, PrimitiveType};
+/// use glium::vertex::{TransformFeedbackObject, TransformFeedbackSession};
+///
+/// let mut out_buffer: glium::VertexBuffer<Vertex> =
+///     glium::VertexBuffer::empty(&display, 1024).unwrap();
+/// let object = TransformFeedbackObject::new(&display).unwrap();
+///
+/// {
+///     let session = TransformFeedbackSession::with_object(&display, &program,
+///                                                         &mut out_buffer, &object).unwrap();
+///     let params = glium::DrawParameters {
+///         transform_feedback: Some(&session),
+///         .. Default::default()
+///     };
+///
+///     let mut target = display.draw();
+///     target.draw(&vb, NoIndices(PrimitiveType::Points), &program, &uniform!{},
+///                 &params).unwrap();
+///     target.finish().unwrap();
+/// }
+///
+/// let mut target = display.draw();
+/// target.draw(&out_buffer, object.draw_transform_feedback(PrimitiveType::Points),
+///             &draw_program, &uniform!{}, &Default::default()).unwrap();
+/// target.finish().unwrap();
+/// # }
+/// ```
+pub struct TransformFeedbackObject {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    // value returned by `register_non_shared_object`
+    generation: usize,
+}
+
+impl TransformFeedbackObject {
+    /// Builds a new transform feedback object.
+    pub fn new<F: ?Sized>(facade: &F)
+                          -> Result<TransformFeedbackObject, TransformFeedbackSessionCreationError>
+                          where F: Facade
+    {
+        if !is_transform_feedback_object_supported(facade) {
+            return Err(TransformFeedbackSessionCreationError::NotSupported);
+        }
+
+        let context = facade.get_context().clone();
+
+        let id = unsafe {
+            let ctxt = context.make_current();
+            let mut id = 0;
+            ctxt.gl.GenTransformFeedbacks(1, &mut id);
+            id
+        };
+        let generation = context.register_non_shared_object(NonSharedObject::TransformFeedback(id));
+
+        Ok(TransformFeedbackObject {
+            context,
+            id,
+            generation,
+        })
+    }
+
+    /// Returns an indices source that draws the vertices written by the latest session that
+    /// used this object.
+    ///
+    /// The vertices source must be the buffer that the session wrote to. Drawing returns
+    /// `DrawTransformFeedbackNotSupported` on OpenGL ES, which doesn't have
+    /// `glDrawTransformFeedback`.
+    #[inline]
+    pub fn draw_transform_feedback(&self, primitives: PrimitiveType) -> IndicesSource<'_> {
+        IndicesSource::TransformFeedback {
+            object: self,
+            primitives,
+        }
+    }
+}
+
+impl GlObject for TransformFeedbackObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl fmt::Debug for TransformFeedbackObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "TransformFeedbackObject #{}", self.id)
+    }
+}
+
+impl Drop for TransformFeedbackObject {
+    fn drop(&mut self) {
+        if !self.context.unregister_non_shared_object(NonSharedObject::TransformFeedback(self.id),
+                                                      self.generation)
+        {
+            // the object belonged to a context that has been replaced with `rebuild`
+            return;
+        }
+
+        let mut ctxt = self.context.make_current();
+
+        if ctxt.state.transform_feedback_object == self.id {
+            TransformFeedbackSession::unbind(&mut ctxt);
+            bind_object(&mut ctxt, 0);
+        }
+
+        unsafe { ctxt.gl.DeleteTransformFeedbacks(1, [self.id].as_ptr()); }
+    }
+}
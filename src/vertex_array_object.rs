@@ -18,11 +18,27 @@ use crate::context::CommandContext;
 use crate::version::Api;
 use crate::version::Version;
 
+/// Maximum value of the relative offset of an attribute that is guaranteed to be supported
+/// by `glVertexAttribFormat`.
+const MAX_VERTEX_ATTRIB_RELATIVE_OFFSET: usize = 2047;
+
+/// Key of a VAO that is built with `ARB_vertex_attrib_binding`.
+///
+/// Contains, for each source of vertices, the address and length of its `VertexFormat`, its
+/// stride and its divisor, then the program. The buffers themselves are not part of the key:
+/// vertex buffers are swapped with `glBindVertexBuffer` and the element array buffer is
+/// rebound while the VAO is current.
+type LayoutKey = (SmallVec<[(usize, usize, usize, Option<u32>); 2]>, Handle);
+
 /// Stores and handles vertex attributes.
 pub struct VertexAttributesSystem {
     // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
     // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
     vaos: RefCell<HashMap<(SmallVec<[(gl::types::GLuint, usize); 3]>, Handle), VertexArrayObject>>,
+
+    // if `ARB_vertex_attrib_binding` is supported, VAOs are instead stored per layout and only
+    // the vertex buffers are changed between draws
+    layout_vaos: RefCell<HashMap<LayoutKey, VertexArrayObject>>,
 }
 
 /// Object allowing one to bind vertex attributes to the current context.
@@ -40,6 +56,7 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            layout_vaos: RefCell::new(HashMap::with_hasher(Default::default())),
         }
     }
 
@@ -71,14 +88,28 @@ impl VertexAttributesSystem {
     pub fn purge_buffer(ctxt: &mut CommandContext<'_>, id: gl::types::GLuint) {
         VertexAttributesSystem::purge_if(ctxt, |&(ref buffers, _)| {
             buffers.iter().any(|&(b, _)| b == id)
-        })
+        });
+
+        // the name of the buffer can be reused, so we must forget that it is bound
+        for vao in ctxt.vertex_array_objects.layout_vaos.borrow().values() {
+            if vao.element_array_buffer.get() == id {
+                vao.element_array_buffer.set(0);
+            }
+
+            for binding in vao.vertex_buffers.borrow_mut().iter_mut() {
+                if binding.map_or(false, |(b, _)| b == id) {
+                    *binding = None;
+                }
+            }
+        }
     }
 
     /// This function *must* be called whenever you destroy a program so that the system can
     /// purge its VAOs cache.
     #[inline]
     pub fn purge_program(ctxt: &mut CommandContext<'_>, program: Handle) {
-        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
+        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program);
+        VertexAttributesSystem::purge_layouts_if(ctxt, |&(_, p)| p == program);
     }
 
     /// Purges the VAOs cache.
@@ -89,6 +120,13 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache. Contrary to `purge_all`, this function expects the system to be
@@ -100,13 +138,21 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Tells the VAOs system that the currently bound element array buffer will change.
     pub fn hijack_current_element_array_buffer(ctxt: &mut CommandContext<'_>) {
         let vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
+        let layout_vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
 
-        for (_, vao) in vaos.iter() {
+        for vao in vaos.values().chain(layout_vaos.values()) {
             if vao.id == ctxt.state.vertex_array {
                 vao.element_array_buffer_hijacked.set(true);
                 return;
@@ -131,6 +177,24 @@ impl VertexAttributesSystem {
             vaos.remove(&key).unwrap().destroy(ctxt);
         }
     }
+
+    /// Purges the VAOs built with `ARB_vertex_attrib_binding` that match a certain condition.
+    fn purge_layouts_if<F>(ctxt: &mut CommandContext<'_>, mut condition: F)
+                           where F: FnMut(&LayoutKey) -> bool
+    {
+        let mut vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
+
+        let mut keys = Vec::new();
+        for key in (*vaos).keys() {
+            if condition(key) {
+                keys.push(key.clone());
+            }
+        }
+
+        for key in keys {
+            vaos.remove(&key).unwrap().destroy(ctxt);
+        }
+    }
 }
 
 impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
@@ -187,6 +251,38 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
                 }
             }
 
+            if is_vertex_attrib_binding_supported(ctxt) &&
+               self.vertex_buffers.iter().all(|&(_, format, _, _, _)| fits_relative_offsets(format))
+            {
+                let key: LayoutKey = (
+                    self.vertex_buffers.iter()
+                        .map(|&(_, format, _, stride, divisor)| {
+                            (format.as_ptr() as usize, format.len(), stride, divisor)
+                        })
+                        .collect(),
+                    self.program.get_id(),
+                );
+
+                // trying to find an existing VAO with the same layout
+                if let Some(value) = ctxt.vertex_array_objects.layout_vaos.borrow().get(&key) {
+                    value.bind(ctxt);
+                    unsafe {
+                        value.bind_element_array_buffer(ctxt, self.element_array_buffer);
+                        value.bind_vertex_buffers(ctxt, &self.vertex_buffers);
+                    }
+                    return base_vertex.map(|v| v as gl::types::GLint);
+                }
+
+                // if not found, building a new one
+                let new_vao = unsafe {
+                    VertexArrayObject::new_with_layout(ctxt, &self.vertex_buffers,
+                                                       self.element_array_buffer, self.program)
+                };
+
+                ctxt.vertex_array_objects.layout_vaos.borrow_mut().insert(key, new_vao);
+                return base_vertex.map(|v| v as gl::types::GLint);
+            }
+
             let mut buffers_list: SmallVec<[_; 3]> = self.vertex_buffers.iter()
                                                               .map(|&(v, _, o, s, _)| (v, o))
                                                               .collect();
@@ -246,8 +342,12 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
 struct VertexArrayObject {
     id: gl::types::GLuint,
     destroyed: bool,
-    element_array_buffer: gl::types::GLuint,
+    element_array_buffer: Cell<gl::types::GLuint>,
     element_array_buffer_hijacked: Cell<bool>,
+
+    // for VAOs built with `ARB_vertex_attrib_binding`, the buffer and offset currently bound to
+    // each binding index ; `None` if unknown
+    vertex_buffers: RefCell<SmallVec<[Option<(gl::types::GLuint, usize)>; 2]>>,
 }
 
 impl VertexArrayObject {
@@ -255,74 +355,58 @@ impl VertexArrayObject {
     ///
     /// The vertex buffer, index buffer and program must not outlive the
     /// VAO, and the VB & program attributes must not change.
-    unsafe fn new(mut ctxt: &mut CommandContext<'_>,
+    unsafe fn new(ctxt: &mut CommandContext<'_>,
                   vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
                   index_buffer: Option<BufferAnySlice<'_>>, program: &Program) -> VertexArrayObject
     {
-        // checking the attributes types
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, location, ty, _) in bindings.iter() {
-                let attribute = match location {
-                    -1 => {
-                        // No location specified in Vertex Format. Check name instead
-                        match program.get_attribute(Borrow::<str>::borrow(name)) {
-                            Some(a) => a,
-                            None => continue,
-                        }
-                    }
-                    _ => {
-                        match program.attributes().into_iter()
-                                .find(|(_, a)| a.location == location) {
-                            Some((_, a)) => a,
-                            None => continue,
-                        }
-                    }
-                };
+        check_attributes(vertex_buffers, program);
 
-                // Unfortunately internal API used by GLES implementation on Vita
-                // assumes all attributes as float4, so we should skip this check for it.
-                #[cfg(not(target_os = "vita"))]
-                if ty.get_num_components() != attribute.ty.get_num_components() ||
-                    attribute.size != 1
-                {
-                    panic!("The program attribute `{}` does not match the vertex format. \
-                            Program expected {:?}, got {:?}.", name, attribute.ty, ty);
-                }
-            }
-        }
+        // we don't use DSA as we're going to make multiple calls for this VAO
+        // and we're likely going to use the VAO right after it's been created
+        let vao = VertexArrayObject::generate(ctxt, index_buffer);
 
-        // checking for duplicate attribute locations
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for (i, bi) in bindings.iter().enumerate() {
-                for (o, bo) in bindings.iter().enumerate() {
-                    if i != o && bi.2 == bo.2 && bi.2 != -1 {
-                        panic!("The program attribute `{}` has the same binding location as program attribute `{}` (binding location {})",
-                               bi.0, bo.0, bi.2)
-                    }
-                }
-            }
+        for &(vertex_buffer, ref bindings, offset, stride, divisor) in vertex_buffers {
+            bind_attribute(ctxt, program, vertex_buffer, bindings, offset, stride, divisor);
         }
 
-        // checking for missing attributes
-        for (&ref name, attribute) in program.attributes() {
-            let mut found = false;
-            for &(_, ref bindings, _, _, _) in vertex_buffers {
-                if bindings.iter().any(|&(ref n, _, location, _, _)| (location != -1 && location == attribute.location) || n == name) {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                panic!("The program attribute `{}` is missing in the vertex bindings", name);
-            }
-        };
+        vao
+    }
 
-        // TODO: check for collisions between the vertices sources
+    /// Builds a new `VertexArrayObject` with `ARB_vertex_attrib_binding`, and leaves it bound.
+    ///
+    /// Contrary to `new`, the vertex buffers can later be swapped with `bind_vertex_buffers`.
+    /// The index buffer and program must not outlive the VAO, and the program attributes must
+    /// not change.
+    unsafe fn new_with_layout(ctxt: &mut CommandContext<'_>,
+                              vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                              index_buffer: Option<BufferAnySlice<'_>>, program: &Program)
+                              -> VertexArrayObject
+    {
+        check_attributes(vertex_buffers, program);
+
+        let vao = VertexArrayObject::generate(ctxt, index_buffer);
 
+        for (binding_index, &(_, ref bindings, _, _, divisor)) in vertex_buffers.iter().enumerate() {
+            bind_attribute_format(ctxt, program, vao.id, binding_index as gl::types::GLuint,
+                                  bindings,
+                                  divisor);
+        }
+
+        *vao.vertex_buffers.borrow_mut() = vertex_buffers.iter().map(|_| None).collect();
+        vao.bind_vertex_buffers(ctxt, vertex_buffers);
+        vao
+    }
+
+    /// Generates a new VAO, binds it and attaches the index buffer.
+    unsafe fn generate(mut ctxt: &mut CommandContext<'_>, index_buffer: Option<BufferAnySlice<'_>>)
+                       -> VertexArrayObject
+    {
         // building the VAO
         let id = {
             let mut id = 0;
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+            if is_dsa_supported(ctxt) {
+                ctxt.gl.CreateVertexArrays(1, &mut id);
+            } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                 ctxt.version >= &Version(Api::GlEs, 3, 0) ||
                 ctxt.extensions.gl_arb_vertex_array_object
             {
@@ -337,8 +421,6 @@ impl VertexArrayObject {
             id
         };
 
-        // we don't use DSA as we're going to make multiple calls for this VAO
-        // and we're likely going to use the VAO right after it's been created
         bind_vao(&mut ctxt, id);
 
         // binding index buffer
@@ -346,15 +428,61 @@ impl VertexArrayObject {
             index_buffer.bind_to_element_array(&mut ctxt);
         }
 
-        for &(vertex_buffer, ref bindings, offset, stride, divisor) in vertex_buffers {
-            bind_attribute(ctxt, program, vertex_buffer, bindings, offset, stride, divisor);
-        }
-
         VertexArrayObject {
             id,
             destroyed: false,
-            element_array_buffer: index_buffer.map(|b| b.get_id()).unwrap_or(0),
+            element_array_buffer: Cell::new(index_buffer.map(|b| b.get_id()).unwrap_or(0)),
             element_array_buffer_hijacked: Cell::new(false),
+            vertex_buffers: RefCell::new(SmallVec::new()),
+        }
+    }
+
+    /// Attaches an index buffer to a VAO built with `new_with_layout`, if it isn't attached yet.
+    ///
+    /// The VAO must be the current one. If `index_buffer` is `None`, the previous index buffer
+    /// is kept as it won't be used by the draw command.
+    unsafe fn bind_element_array_buffer(&self, ctxt: &mut CommandContext<'_>,
+                                        index_buffer: Option<BufferAnySlice<'_>>)
+    {
+        debug_assert_eq!(ctxt.state.vertex_array, self.id);
+
+        if let Some(index_buffer) = index_buffer {
+            if self.element_array_buffer.get() != index_buffer.get_id() {
+                if is_dsa_supported(ctxt) {
+                    ctxt.gl.VertexArrayElementBuffer(self.id, index_buffer.get_id());
+                } else {
+                    index_buffer.bind_to_element_array(ctxt);
+                }
+                self.element_array_buffer.set(index_buffer.get_id());
+            }
+        }
+    }
+
+    /// Binds the vertex buffers to the binding indices of a VAO built with `new_with_layout`.
+    ///
+    /// The VAO must be the current one. Buffers that are already bound at the same offset are
+    /// skipped.
+    unsafe fn bind_vertex_buffers(&self, ctxt: &mut CommandContext<'_>,
+                                  vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)])
+    {
+        debug_assert_eq!(ctxt.state.vertex_array, self.id);
+        let mut bound = self.vertex_buffers.borrow_mut();
+
+        for (binding_index, &(buffer, _, offset, stride, _)) in vertex_buffers.iter().enumerate() {
+            if bound[binding_index] == Some((buffer, offset)) {
+                continue;
+            }
+
+            if is_dsa_supported(ctxt) {
+                ctxt.gl.VertexArrayVertexBuffer(self.id, binding_index as gl::types::GLuint,
+                                                buffer, offset as gl::types::GLintptr,
+                                                stride as gl::types::GLsizei);
+            } else {
+                ctxt.gl.BindVertexBuffer(binding_index as gl::types::GLuint, buffer,
+                                         offset as gl::types::GLintptr,
+                                         stride as gl::types::GLsizei);
+            }
+            bound[binding_index] = Some((buffer, offset));
         }
     }
 
@@ -368,9 +496,10 @@ impl VertexArrayObject {
                 if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0)
                 {
-                    ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_array_buffer);
+                    ctxt.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_array_buffer.get());
                 } else if ctxt.extensions.gl_arb_vertex_buffer_object {
-                    ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB, self.element_array_buffer);
+                    ctxt.gl.BindBufferARB(gl::ELEMENT_ARRAY_BUFFER_ARB,
+                                         self.element_array_buffer.get());
                 } else {
                     unreachable!();
                 }
@@ -422,6 +551,83 @@ impl GlObject for VertexArrayObject {
     }
 }
 
+/// Checks that the vertex sources match the attributes of the program.
+///
+/// ## Panic
+///
+/// Panics if an attribute has the wrong type, is missing, or if two attributes share the same
+/// location.
+fn check_attributes(vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                    program: &Program)
+{
+    // checking the attributes types
+    for &(_, ref bindings, _, _, _) in vertex_buffers {
+        for &(ref name, _, location, ty, normalize) in bindings.iter() {
+            let attribute = match location {
+                -1 => {
+                    // No location specified in Vertex Format. Check name instead
+                    match program.get_attribute(Borrow::<str>::borrow(name)) {
+                        Some(a) => a,
+                        None => continue,
+                    }
+                }
+                _ => {
+                    match program.attributes().into_iter()
+                            .find(|(_, a)| a.location == location) {
+                        Some((_, a)) => a,
+                        None => continue,
+                    }
+                }
+            };
+
+            // Unfortunately internal API used by GLES implementation on Vita
+            // assumes all attributes as float4, so we should skip this check for it.
+            #[cfg(not(target_os = "vita"))]
+            if ty.get_num_components() != attribute.ty.get_num_components() ||
+                attribute.size != 1
+            {
+                panic!("The program attribute `{}` does not match the vertex format. \
+                        Program expected {:?}, got {:?}.", name, attribute.ty, ty);
+            }
+
+            #[cfg(not(target_os = "vita"))]
+            if !is_binding_mode_compatible(attribute.ty, ty, normalize) {
+                panic!("The program attribute `{}` can't be read from the vertex format. \
+                        Program expected {:?}, got {:?}{}.", name, attribute.ty, ty,
+                       if normalize { " (normalized)" } else { "" });
+            }
+        }
+    }
+
+    // checking for duplicate attribute locations
+    for &(_, ref bindings, _, _, _) in vertex_buffers {
+        for (i, bi) in bindings.iter().enumerate() {
+            for (o, bo) in bindings.iter().enumerate() {
+                if i != o && bi.2 == bo.2 && bi.2 != -1 {
+                    panic!("The program attribute `{}` has the same binding location as program attribute `{}` (binding location {})",
+                           bi.0, bo.0, bi.2)
+                }
+            }
+        }
+    }
+
+    // checking for missing attributes
+    for (&ref name, attribute) in program.attributes() {
+        let mut found = false;
+        for &(_, ref bindings, _, _, _) in vertex_buffers {
+            if bindings.iter().any(|&(ref n, _, location, _, _)| (location != -1 && location == attribute.location) || n == name) {
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            panic!("The program attribute `{}` is missing in the vertex bindings", name);
+        }
+    };
+
+    // TODO: check for collisions between the vertices sources
+}
+
 fn vertex_binding_type_to_gl(ty: AttributeType) -> (gl::types::GLenum, gl::types::GLint, gl::types::GLint) {
     match ty {
         AttributeType::I8 => (gl::BYTE, 1, 1),
@@ -528,6 +734,152 @@ fn bind_vao(ctxt: &mut CommandContext<'_>, vao_id: gl::types::GLuint) {
     }
 }
 
+/// Returns true if the backend supports `glBindVertexBuffer` and `glVertexAttribFormat`.
+#[inline]
+fn is_vertex_attrib_binding_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+    ctxt.extensions.gl_arb_vertex_attrib_binding
+}
+
+/// Returns true if the VAOs built with `ARB_vertex_attrib_binding` can be modified with
+/// `ARB_direct_state_access`, without being the current VAO.
+#[inline]
+fn is_dsa_supported(ctxt: &CommandContext<'_>) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access
+}
+
+/// Returns true if all the attributes of the format can be passed to `glVertexAttribFormat`.
+#[inline]
+fn fits_relative_offsets(format: VertexFormat) -> bool {
+    format.iter().all(|&(_, offset, _, ty, _)| {
+        offset + ty.get_size_bytes() <= MAX_VERTEX_ATTRIB_RELATIVE_OFFSET + 1
+    })
+}
+
+/// Which variant of `glVertexAttribFormat` an attribute uses.
+#[derive(Copy, Clone)]
+enum AttribFormatKind {
+    Float,
+    Integer,
+    Long,
+}
+
+/// Returns which variant of `glVertexAttribPointer` or `glVertexAttribFormat` feeds a program
+/// attribute of type `ty`.
+fn attrib_format_kind(ty: AttributeType) -> AttribFormatKind {
+    match vertex_binding_type_to_gl(ty).0 {
+        gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+        gl::INT | gl::UNSIGNED_INT => AttribFormatKind::Integer,
+        gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => AttribFormatKind::Long,
+        _ => AttribFormatKind::Float,
+    }
+}
+
+/// Returns true if vertex data of type `ty` can feed a program attribute of type `attribute`.
+///
+/// Integer and double program attributes can't be fed with normalized data, or with data of a
+/// different kind, as the values would otherwise be converted.
+pub(crate) fn is_binding_mode_compatible(attribute: AttributeType, ty: AttributeType,
+                                         normalize: bool) -> bool
+{
+    let data_type = vertex_binding_type_to_gl(ty).0;
+
+    match attrib_format_kind(attribute) {
+        AttribFormatKind::Float => data_type != gl::INT64_NV && data_type != gl::UNSIGNED_INT64_NV,
+        AttribFormatKind::Integer => !normalize && matches!(data_type,
+            gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+            gl::INT | gl::UNSIGNED_INT),
+        AttribFormatKind::Long => !normalize && matches!(data_type,
+            gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV),
+    }
+}
+
+/// Specifies the format of the attributes of a vertex source and associates them with a
+/// binding index of `vao`.
+///
+/// This is the `ARB_vertex_attrib_binding` equivalent of `bind_attribute`, except that no
+/// buffer is bound. Unless `ARB_direct_state_access` is supported, `vao` must be the current
+/// VAO.
+unsafe fn bind_attribute_format(ctxt: &mut CommandContext<'_>, program: &Program,
+                                vao: gl::types::GLuint, binding_index: gl::types::GLuint,
+                                bindings: &VertexFormat, divisor: Option<u32>)
+{
+    let dsa = is_dsa_supported(ctxt);
+
+    for &(ref name, offset, location, ty, normalize) in bindings.iter() {
+        let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
+
+        let attribute = match location {
+            -1 => {
+                // No location specified in Vertex Format. Check name instead
+                match program.get_attribute(Borrow::<str>::borrow(name)) {
+                    Some(a) => a,
+                    None => continue,
+                }
+            }
+            _ => {
+                match program.attributes().into_iter()
+                        .find(|(_, a)| a.location == location) {
+                    Some((_, a)) => a,
+                    None => continue,
+                }
+            }
+        };
+
+        if attribute.location == -1 {
+            continue;
+        }
+
+        // integer and double inputs must be fed with `glVertexAttribIFormat` and
+        // `glVertexAttribLFormat`, otherwise the values are converted to floats
+        let kind = attrib_format_kind(attribute.ty);
+        let column_size = ty.get_size_bytes() / instances_count as usize;
+
+        for i in 0..instances_count {
+            let location = (attribute.location + i) as gl::types::GLuint;
+            let normalized = normalize as gl::types::GLboolean;
+            let relative_offset = (offset + i as usize * column_size) as gl::types::GLuint;
+
+            match (kind, dsa) {
+                (AttribFormatKind::Float, true) =>
+                    ctxt.gl.VertexArrayAttribFormat(vao, location, elements_count, data_type,
+                                                    normalized, relative_offset),
+                (AttribFormatKind::Float, false) =>
+                    ctxt.gl.VertexAttribFormat(location, elements_count, data_type, normalized,
+                                               relative_offset),
+                (AttribFormatKind::Integer, true) =>
+                    ctxt.gl.VertexArrayAttribIFormat(vao, location, elements_count, data_type,
+                                                     relative_offset),
+                (AttribFormatKind::Integer, false) =>
+                    ctxt.gl.VertexAttribIFormat(location, elements_count, data_type,
+                                                relative_offset),
+                (AttribFormatKind::Long, true) =>
+                    ctxt.gl.VertexArrayAttribLFormat(vao, location, elements_count, data_type,
+                                                     relative_offset),
+                (AttribFormatKind::Long, false) =>
+                    ctxt.gl.VertexAttribLFormat(location, elements_count, data_type,
+                                                relative_offset),
+            }
+
+            if dsa {
+                ctxt.gl.VertexArrayAttribBinding(vao, location, binding_index);
+                ctxt.gl.EnableVertexArrayAttrib(vao, location);
+            } else {
+                ctxt.gl.VertexAttribBinding(location, binding_index);
+                ctxt.gl.EnableVertexAttribArray(location);
+            }
+        }
+    }
+
+    if let Some(divisor) = divisor {
+        if dsa {
+            ctxt.gl.VertexArrayBindingDivisor(vao, binding_index, divisor);
+        } else {
+            ctxt.gl.VertexBindingDivisor(binding_index, divisor);
+        }
+    }
+}
+
 /// Binds an individual attribute to the current VAO.
 unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
                          vertex_buffer: gl::types::GLuint, bindings: &VertexFormat,
@@ -552,6 +904,16 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
     for &(ref name, offset, location, ty, normalize) in bindings.iter() {
         let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
 
+        // OpenGL ES 2 supports half-floats through `GL_OES_vertex_half_float`, whose enum has
+        // a different value
+        let data_type = if data_type == gl::HALF_FLOAT && ctxt.version.0 == Api::GlEs &&
+                           ctxt.version < &Version(Api::GlEs, 3, 0)
+        {
+            gl::HALF_FLOAT_OES
+        } else {
+            data_type
+        };
+
         let attribute = match location {
             -1 => {
                 // No location specified in Vertex Format. Check name instead
@@ -570,50 +932,26 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext<'_>, program: &Program,
         };
 
         if attribute.location != -1 {
-            let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute.ty);
-            if normalize {
-                for i in 0..instances_count {
-                    ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
-                                                elements_count as gl::types::GLint, data_type, 1,
-                                                stride as i32,
-                                                (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
-                }
-            } else {
-                match attribute_ty {
-                    gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
-                    gl::INT | gl::UNSIGNED_INT =>
-                        ctxt.gl.VertexAttribIPointer(attribute.location as u32,
-                                                     elements_count as gl::types::GLint, data_type,
-                                                     stride as i32,
-                                                     (buffer_offset + offset) as *const _),
-
-                    gl::FLOAT => {
-                        for i in 0..instances_count {
-                            ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
-                                                        elements_count as gl::types::GLint, data_type, 0,
-                                                        stride as i32,
-                                                        (buffer_offset + offset + (i * elements_count * 4) as usize) as *const _)
-                        }
-                    },
-                    gl::HALF_FLOAT => {
-                        for i in 0..instances_count {
-                            ctxt.gl.VertexAttribPointer((attribute.location + i) as u32,
-                                                        elements_count as gl::types::GLint, data_type, 0,
-                                                        stride as i32,
-                                                        (buffer_offset + offset + (i * elements_count * 2) as usize) as *const _)
-                        }
-                    },
-
-                    gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => {
-                        for i in 0..instances_count {
-                            ctxt.gl.VertexAttribLPointer((attribute.location + i) as u32,
-                                                         elements_count as gl::types::GLint, data_type,
-                                                         stride as i32,
-                                                         (buffer_offset + offset + (i * elements_count * 8) as usize) as *const _)
-                        }
-                    },
-
-                    _ => unreachable!()
+            // integer and double inputs must be fed with `glVertexAttribIPointer` and
+            // `glVertexAttribLPointer`, otherwise the values are converted to floats
+            let kind = attrib_format_kind(attribute.ty);
+            let column_size = ty.get_size_bytes() / instances_count as usize;
+
+            for i in 0..instances_count {
+                let location = (attribute.location + i) as gl::types::GLuint;
+                let pointer = (buffer_offset + offset + i as usize * column_size) as *const _;
+
+                match kind {
+                    AttribFormatKind::Float =>
+                        ctxt.gl.VertexAttribPointer(location, elements_count, data_type,
+                                                    normalize as gl::types::GLboolean,
+                                                    stride as i32, pointer),
+                    AttribFormatKind::Integer =>
+                        ctxt.gl.VertexAttribIPointer(location, elements_count, data_type,
+                                                     stride as i32, pointer),
+                    AttribFormatKind::Long =>
+                        ctxt.gl.VertexAttribLPointer(location, elements_count, data_type,
+                                                     stride as i32, pointer),
                 }
             }
 
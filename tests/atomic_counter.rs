@@ -61,3 +61,61 @@ fn basic() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn read_and_reset() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            out vec4 f_color;
+
+            layout(binding = 0) uniform atomic_uint counter;
+
+            void main() {
+                f_color = vec4(0.0, 0.0, 0.0, 1.0);
+                atomicCounterIncrement(counter);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let buffer = match glium::uniforms::AtomicCounterBuffer::with_values(&display, &[5]) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+    assert_eq!(buffer.len(), 1);
+
+    // Texture size 1024x1024
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ counter: &buffer },
+                              &Default::default()).unwrap();
+    assert_eq!(buffer.read_counters().unwrap(), vec![1024 * 1024 + 5]);
+
+    buffer.reset();
+    assert_eq!(buffer.read_counters().unwrap(), vec![0]);
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ counter: &buffer },
+                              &Default::default()).unwrap();
+    assert_eq!(buffer.read_counters().unwrap(), vec![1024 * 1024]);
+
+    display.assert_no_error(None);
+}
@@ -307,3 +307,308 @@ attribute_test!(attribute_vec3_i32, [i32; 3], "vec3", [0, 0, 0], "vec4(field1, 1
 attribute_test!(attribute_vec3_tuple_i32, (i32, i32, i32), "vec3", (0, 0, 0), "vec4(field1, 1.0)");
 attribute_test!(attribute_vec4_i32, [i32; 4], "vec4", [0, 0, 0, 0], "field1");
 attribute_test!(attribute_vec4_tuple_i32, (i32, i32, i32, i32), "vec4", (0, 0, 0, 0), "field1");
+
+#[test]
+fn attributes_reflection() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            attribute mat4 transform;
+
+            void main() {
+                gl_Position = transform * vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let position = program.get_attribute("position").unwrap();
+    assert_eq!(position.ty, glium::vertex::AttributeType::F32F32);
+    assert_eq!(position.size, 1);
+    assert_eq!(position.get_num_locations(), 1);
+
+    let transform = program.get_attribute("transform").unwrap();
+    assert_eq!(transform.ty, glium::vertex::AttributeType::F32x4x4);
+    assert!(!transform.is_array());
+    assert_eq!(transform.get_num_locations(), 4);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn attribute_array_reflection() {
+    let display = support::build_display();
+
+    // vertex shader inputs can't be arrays in GLSL ES
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 150
+
+            in vec2 positions[2];
+
+            void main() {
+                gl_Position = vec4(positions[0] + positions[1], 0.0, 1.0);
+            }
+        ",
+        "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let positions = program.get_attribute("positions").unwrap();
+    assert!(positions.is_array());
+    assert_eq!(positions.size, 2);
+    assert!(program.get_attribute("positions[0]").is_some());
+    assert!(program.attributes().all(|(name, _)| name == "positions"));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn check_vertex_formats() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    #[derive(Copy, Clone)]
+    struct WrongVertex {
+        field1: [f32; 4],
+    }
+
+    implement_vertex!(WrongVertex, field1);
+
+    #[derive(Copy, Clone)]
+    struct OtherVertex {
+        field2: [f32; 2],
+    }
+
+    implement_vertex!(OtherVertex, field2);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 field1;
+
+            void main() {
+                gl_Position = vec4(field1, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    use glium::Vertex as _;
+    assert!(program.check_vertex_formats(&[Vertex::build_bindings()]).is_ok());
+    assert!(program.check_vertex_formats(&[OtherVertex::build_bindings(),
+                                           Vertex::build_bindings()]).is_ok());
+
+    match program.check_vertex_formats(&[WrongVertex::build_bindings()]) {
+        Err(glium::DrawError::AttributeTypeMismatch) => (),
+        e => panic!("{:?}", e)
+    }
+
+    match program.check_vertex_formats(&[OtherVertex::build_bindings()]) {
+        Err(glium::DrawError::AttributeMissing) => (),
+        e => panic!("{:?}", e)
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn integer_attribute() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        value: [i32; 2],
+    }
+
+    implement_vertex!(Vertex, position, value);
+
+    // 16777217 can't be represented by a `f32`, so the attribute must not go through a float
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0, -1.0], value: [16777217, -7] },
+        Vertex { position: [1.0, -1.0], value: [16777217, -7] },
+        Vertex { position: [-1.0, 1.0], value: [16777217, -7] },
+        Vertex { position: [1.0, 1.0], value: [16777217, -7] },
+    ]).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+                in ivec2 value;
+                flat out ivec2 v_value;
+
+                void main() {
+                    v_value = value;
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                flat in ivec2 v_value;
+                out vec4 color;
+
+                void main() {
+                    if (v_value == ivec2(16777217, -7)) {
+                        color = vec4(0.0, 1.0, 0.0, 1.0);
+                    } else {
+                        color = vec4(1.0, 0.0, 0.0, 1.0);
+                    }
+                }
+            "
+        },
+        300 es => {
+            vertex: "
+                #version 300 es
+
+                in vec2 position;
+                in highp ivec2 value;
+                flat out highp ivec2 v_value;
+
+                void main() {
+                    v_value = value;
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 300 es
+
+                flat in highp ivec2 v_value;
+                out lowp vec4 color;
+
+                void main() {
+                    if (v_value == ivec2(16777217, -7)) {
+                        color = vec4(0.0, 1.0, 0.0, 1.0);
+                    } else {
+                        color = vec4(1.0, 0.0, 0.0, 1.0);
+                    }
+                }
+            "
+        }
+    );
+
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer, glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                              &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "The program attribute `value` can't be read from the vertex format")]
+fn integer_attribute_normalized() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        value: [u8; 4],
+    }
+
+    implement_vertex!(Vertex, value normalize(true));
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { value: [0, 0, 0, 255] },
+    ]).unwrap();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in uvec4 value;
+
+                void main() {
+                    gl_Position = vec4(value);
+                }
+            ",
+            fragment: "
+                #version 140
+                out vec4 color;
+                void main() {
+                    color = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        300 es => {
+            vertex: "
+                #version 300 es
+
+                in highp uvec4 value;
+
+                void main() {
+                    gl_Position = vec4(value);
+                }
+            ",
+            fragment: "
+                #version 300 es
+                out lowp vec4 color;
+                void main() {
+                    color = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        }
+    ).unwrap();
+
+    use glium::Vertex as _;
+    match program.check_vertex_formats(&[Vertex::build_bindings()]) {
+        Err(glium::DrawError::AttributeTypeMismatch) => (),
+        e => panic!("{:?}", e)
+    }
+
+    let mut target = display.draw();
+    target.draw(&vertex_buffer, glium::index::NoIndices(PrimitiveType::Points), &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+    target.finish().unwrap();
+}
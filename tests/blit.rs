@@ -1,8 +1,9 @@
 #[macro_use]
 extern crate glium;
 
-use glium::{Surface, BlitTarget, Rect, BlitMask};
-use glium::framebuffer::SimpleFrameBuffer;
+use glium::{Surface, BlitTarget, Rect, BlitMask, BlitError};
+use glium::framebuffer::{SimpleFrameBuffer, EmptyFrameBuffer, DepthRenderBuffer};
+use glium::texture::DepthFormat;
 use glium::uniforms::MagnifySamplerFilter;
 
 mod support;
@@ -93,3 +94,242 @@ fn blit_color_and_depth_buffer() {
     // todo: how to check dst_tex_depth? There is no .read() on a DepthTexture2d...
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_depth_buffer() {
+    let display = support::build_display();
+
+    let src_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.5, 1.0);
+    let src_tex_depth = support::build_constant_depth_texture(&display, 0.5);
+    let src_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &src_tex_color, &src_tex_depth).unwrap();
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_tex_depth = support::build_constant_depth_texture(&display, 0.0);
+    let dst_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &dst_tex_color, &dst_tex_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    src_frame_buffer.blit_depth(&src_rect, &dst_frame_buffer, &dst_rect).unwrap();
+
+    // only the depth buffer must have been copied
+    let color_data: Vec<Vec<(u8, u8, u8, u8)>> = dst_tex_color.read();
+    assert_eq!(color_data, vec![
+        vec![(0, 0, 0, 255), (0, 0, 0, 255),],
+        vec![(0, 0, 0, 255), (0, 0, 0, 255),],
+    ]);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer_missing() {
+    let display = support::build_display();
+
+    let src_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.5, 1.0);
+    let src_tex_depth = support::build_constant_depth_texture(&display, 0.5);
+    let src_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &src_tex_color, &src_tex_depth).unwrap();
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_frame_buffer = SimpleFrameBuffer::new(&display, &dst_tex_color).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    assert_eq!(src_frame_buffer.blit_depth(&src_rect, &dst_frame_buffer, &dst_rect),
+               Err(BlitError::NoDepthBuffer));
+    assert_eq!(src_frame_buffer.blit_stencil(&src_rect, &dst_frame_buffer, &dst_rect),
+               Err(BlitError::NoStencilBuffer));
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer_linear_filter() {
+    let display = support::build_display();
+
+    let src_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.5, 1.0);
+    let src_tex_depth = support::build_constant_depth_texture(&display, 0.5);
+    let src_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &src_tex_color, &src_tex_depth).unwrap();
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_tex_depth = support::build_constant_depth_texture(&display, 0.0);
+    let dst_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &dst_tex_color, &dst_tex_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    assert_eq!(src_frame_buffer.blit_buffers(&src_rect, &dst_frame_buffer, &dst_rect,
+                                             MagnifySamplerFilter::Linear,
+                                             BlitMask::color_and_depth()),
+               Err(BlitError::FilterNotSupported));
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer_format_mismatch() {
+    let display = support::build_display();
+
+    let src_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.5, 1.0);
+    let src_depth = DepthRenderBuffer::new(&display, DepthFormat::I16, 2, 2).unwrap();
+    let src_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &src_tex_color, &src_depth).unwrap();
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_depth = DepthRenderBuffer::new(&display, DepthFormat::I24, 2, 2).unwrap();
+    let dst_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &dst_tex_color, &dst_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    assert_eq!(src_frame_buffer.blit_depth(&src_rect, &dst_frame_buffer, &dst_rect),
+               Err(BlitError::DepthFormatMismatch));
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer_same_bits_format_mismatch() {
+    let display = support::build_display();
+
+    let src_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.5, 1.0);
+    let src_depth = DepthRenderBuffer::new(&display, DepthFormat::I32, 2, 2).unwrap();
+    let src_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &src_tex_color, &src_depth).unwrap();
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_depth = DepthRenderBuffer::new(&display, DepthFormat::F32, 2, 2).unwrap();
+    let dst_frame_buffer = SimpleFrameBuffer::with_depth_buffer(&display, &dst_tex_color, &dst_depth).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    assert_eq!(src_frame_buffer.blit_depth(&src_rect, &dst_frame_buffer, &dst_rect),
+               Err(BlitError::DepthFormatMismatch));
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_empty_framebuffer() {
+    let display = support::build_display();
+
+    let src_frame_buffer = match EmptyFrameBuffer::new(&display, 2, 2, None, None, true) {
+        Ok(fb) => fb,
+        Err(_) => return,
+    };
+
+    let dst_tex_color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let dst_frame_buffer = SimpleFrameBuffer::new(&display, &dst_tex_color).unwrap();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dst_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+    assert_eq!(src_frame_buffer.blit_buffers(&src_rect, &dst_frame_buffer, &dst_rect,
+                                             MagnifySamplerFilter::Nearest,
+                                             BlitMask::color()),
+               Err(BlitError::NoColorBuffer));
+    assert_eq!(src_frame_buffer.blit_depth(&src_rect, &dst_frame_buffer, &dst_rect),
+               Err(BlitError::NoDepthBuffer));
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_between_mipmap_levels() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::EmptyMipmaps,
+                                            64, 64).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let source: glium::texture::TextureAnyImage<'_> = texture.mipmap(0).unwrap().into();
+    let target: glium::texture::TextureAnyImage<'_> = texture.mipmap(1).unwrap().into();
+
+    source.blit_to(&Rect { left: 0, bottom: 0, width: 64, height: 64 }, target,
+                   &BlitTarget { left: 0, bottom: 0, width: 32, height: 32 },
+                   MagnifySamplerFilter::Linear).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.raw_read(&Rect {
+        left: 0, bottom: 0, width: 32, height: 32
+    });
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_between_images_of_different_kinds() {
+    let display = support::build_display();
+
+    let color = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    let depth = match glium::texture::DepthTexture2d::empty(&display, 2, 2) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let source: glium::texture::TextureAnyImage<'_> = color.mipmap(0).unwrap().into();
+    let target: glium::texture::TextureAnyImage<'_> = depth.mipmap(0).unwrap().into();
+
+    assert_eq!(source.blit_to(&Rect { left: 0, bottom: 0, width: 2, height: 2 }, target,
+                              &BlitTarget { left: 0, bottom: 0, width: 2, height: 2 },
+                              MagnifySamplerFilter::Nearest),
+               Err(BlitError::ImageKindMismatch));
+
+    // depth images can only be blitted with `Nearest`
+    assert_eq!(target.blit_to(&Rect { left: 0, bottom: 0, width: 2, height: 2 }, target,
+                              &BlitTarget { left: 0, bottom: 0, width: 2, height: 2 },
+                              MagnifySamplerFilter::Linear),
+               Err(BlitError::FilterNotSupported));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resolve_multisample_texture() {
+    let display = support::build_display();
+
+    let multisample = match glium::texture::Texture2dMultisample::empty(&display, 2, 2, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+    multisample.as_surface().clear_color(0.0, 0.2, 1.0, 1.0);
+
+    let target = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    multisample.resolve_to(&target).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data, vec![
+        vec![(0, 51, 255, 255), (0, 51, 255, 255),],
+        vec![(0, 51, 255, 255), (0, 51, 255, 255),],
+    ]);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resolve_multisample_depth_texture() {
+    let display = support::build_display();
+
+    let multisample = match glium::texture::DepthTexture2dMultisample::empty(&display, 2, 2, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let target = support::build_constant_depth_texture(&display, 0.0);
+    multisample.resolve_to(&target).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resolve_multisample_texture_wrong_dimensions() {
+    let display = support::build_display();
+
+    let multisample = match glium::texture::Texture2dMultisample::empty(&display, 4, 4, 4) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let target = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+    match multisample.resolve_to(&target) {
+        Err(glium::BlitError::ValidationError(
+            glium::framebuffer::ValidationError::DimensionsMismatch)) => (),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
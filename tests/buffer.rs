@@ -593,6 +593,37 @@ fn invalidate_range() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn invalidate_range_then_write() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::<[u32]>::new(&display, &[1, 2, 3, 4],
+                                                     glium::buffer::BufferType::ArrayBuffer,
+                                                     BufferMode::Dynamic).unwrap();
+
+    buffer.invalidate_range(2 ..).unwrap();
+    buffer.slice(2 ..).unwrap().write(&[5, 6]);
+
+    buffer.slice(1 ..).unwrap().invalidate_range(.. 1).unwrap();
+    buffer.slice(1 .. 2).unwrap().write(&[7]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn invalidate_range_out_of_bounds() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::<[u32]>::new(&display, &[1, 2, 3, 4],
+                                                     glium::buffer::BufferType::ArrayBuffer,
+                                                     BufferMode::Dynamic).unwrap();
+
+    assert!(buffer.invalidate_range(2 .. 5).is_none());
+    assert!(buffer.slice(1 ..).unwrap().invalidate_range(.. 4).is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn immutable_mapping_forget_then_remap() {
     let display = support::build_display();
@@ -976,3 +1007,126 @@ fn copy_to_slice() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn copy_range_to_same_buffer() {
+    let display = support::build_display();
+
+    let buf = glium::buffer::Buffer::<[u8]>::new(&display, &[1, 2, 3, 4, 5],
+                                                 glium::buffer::BufferType::ArrayBuffer,
+                                                 BufferMode::Persistent);
+    let buf = if let Ok(buf) = buf { buf } else { return };
+
+    if buf.copy_range_to(3 .. 5, &buf, 0).is_err() {
+        return;
+    }
+
+    let result = match buf.read() {
+        Ok(r) => r,
+        Err(_) => return
+    };
+
+    assert_eq!(result, [4, 5, 3, 4, 5]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn copy_range_to_overlapping() {
+    let display = support::build_display();
+
+    let buf = glium::buffer::Buffer::<[u8]>::new(&display, &[1, 2, 3, 4, 5],
+                                                 glium::buffer::BufferType::ArrayBuffer,
+                                                 BufferMode::Persistent);
+    let buf = if let Ok(buf) = buf { buf } else { return };
+
+    match buf.copy_range_to(0 .. 3, &buf, 2) {
+        Err(glium::buffer::CopyError::OverlappingRanges) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn copy_range_to_out_of_range() {
+    let display = support::build_display();
+
+    let buf1 = glium::buffer::Buffer::<[u8]>::new(&display, &[1, 2, 3],
+                                                  glium::buffer::BufferType::ArrayBuffer,
+                                                  BufferMode::Default).unwrap();
+    let buf2 = glium::buffer::Buffer::<[u8]>::new(&display, &[0, 0],
+                                                  glium::buffer::BufferType::ArrayBuffer,
+                                                  BufferMode::Default).unwrap();
+
+    match buf1.copy_range_to(0 .. 3, &buf2, 0) {
+        Err(glium::buffer::CopyError::OutOfBounds) => (),
+        _ => panic!()
+    }
+
+    match buf1.copy_range_to(2 .. 3, &buf2, usize::MAX) {
+        Err(glium::buffer::CopyError::OutOfBounds) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_transfer_roundtrip() {
+    let display = support::build_display();
+
+    let buf = glium::buffer::Buffer::new(&display, &[1u32, 2, 3],
+                                         glium::buffer::BufferType::ArrayBuffer,
+                                         BufferMode::Default).unwrap();
+
+    // a context trivially shares lists with itself
+    let transfer = buf.into_transfer();
+    let buf = unsafe { glium::buffer::Buffer::from_transfer(&display, transfer) };
+
+    match buf.read() {
+        Ok(data) => assert_eq!(data, [1, 2, 3]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_transfer_dropped() {
+    let display = support::build_display();
+
+    let buf = glium::buffer::Buffer::new(&display, &[1u32, 2, 3],
+                                         glium::buffer::BufferType::ArrayBuffer,
+                                         BufferMode::Default).unwrap();
+
+    // the transfer can be dropped on any thread, the buffer is destroyed when flushing
+    let transfer = buf.into_transfer();
+    std::thread::spawn(move || drop(transfer)).join().unwrap();
+    display.flush();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn ring_buffer_rotation() {
+    use glium::buffer::{BufferType, RingBuffer};
+
+    let display = support::build_display();
+
+    let mut ring = RingBuffer::<u32>::new(&display, BufferType::ArrayBuffer, 4, 3).unwrap();
+    if ring.is_persistent() {
+        assert_eq!(ring.regions(), 3);
+    } else {
+        assert_eq!(ring.regions(), 1);
+    }
+
+    for frame in 0 .. 7u32 {
+        ring.write(&[frame, frame + 1, frame + 2, frame + 3]);
+        assert_eq!(ring.current_region().len(), 4);
+        assert_eq!(ring.current_region().read().unwrap(), vec![frame, frame + 1, frame + 2, frame + 3]);
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::capture::{CaptureFormat, FrameRecorder, RecorderOptions};
+
+mod support;
+
+#[test]
+fn capture_frames() {
+    let display = support::build_display();
+
+    let (mut recorder, frames) = FrameRecorder::new(&display, RecorderOptions::default());
+
+    for _ in 0 .. 5 {
+        let mut frame = display.draw();
+        frame.clear_color(1.0, 0.0, 0.0, 1.0);
+        recorder.finish(frame).unwrap();
+    }
+
+    recorder.flush().unwrap();
+
+    let captured: Vec<_> = frames.try_iter().collect();
+    assert_eq!(captured.len(), 5);
+
+    let (width, height) = display.get_framebuffer_dimensions();
+    for (index, frame) in captured.iter().enumerate() {
+        assert_eq!(frame.index, index as u64);
+        assert_eq!((frame.width, frame.height), (width, height));
+        assert_eq!(frame.data.len(), width as usize * height as usize * 4);
+        assert_eq!(&frame.data[.. 4], &[255, 0, 0, 255]);
+    }
+
+    for pair in captured.windows(2) {
+        assert!(pair[0].timestamp <= pair[1].timestamp);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn capture_downscale_and_format() {
+    let display = support::build_display();
+
+    let options = RecorderOptions {
+        ring_size: 1,
+        downscale: 2,
+        format: CaptureFormat::Bgra8,
+        .. Default::default()
+    };
+    let (mut recorder, frames) = FrameRecorder::new(&display, options);
+
+    let mut frame = display.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    recorder.finish(frame).unwrap();
+    recorder.flush().unwrap();
+
+    let captured = frames.try_recv().unwrap();
+
+    let (width, height) = display.get_framebuffer_dimensions();
+    assert_eq!((captured.width, captured.height), ((width / 2).max(1), (height / 2).max(1)));
+    assert_eq!(captured.format, CaptureFormat::Bgra8);
+    assert_eq!(&captured.data[.. 4], &[0, 0, 255, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn capture_zero_ring_size() {
+    let display = support::build_display();
+
+    let options = RecorderOptions { ring_size: 0, .. Default::default() };
+    let _ = FrameRecorder::new(&display, options);
+}
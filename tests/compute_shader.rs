@@ -0,0 +1,138 @@
+#[macro_use]
+extern crate glium;
+
+use glium::program::{ComputeError, ComputeShader};
+
+mod support;
+
+fn build_doubling_shader<F: ?Sized>(display: &F) -> Option<ComputeShader>
+    where F: glium::backend::Facade
+{
+    if !ComputeShader::is_supported(display.get_context()) {
+        return None;
+    }
+
+    ComputeShader::from_source(display, "
+        #version 430
+        layout(local_size_x = 4) in;
+
+        buffer MyBlock {
+            uint values[16];
+        };
+
+        void main() {
+            values[gl_GlobalInvocationID.x] *= 2;
+        }
+    ").ok()
+}
+
+#[test]
+fn execute() {
+    let display = support::build_display();
+    let shader = match build_doubling_shader(&display) {
+        Some(s) => s,
+        None => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        values: [u32; 16],
+    }
+
+    implement_uniform_block!(Data, values);
+
+    let mut values = [0u32; 16];
+    for (i, v) in values.iter_mut().enumerate() {
+        *v = i as u32;
+    }
+
+    let buffer = glium::uniforms::UniformBuffer::new(&display, Data { values }).unwrap();
+
+    // executing twice checks that the second dispatch sees the writes of the first one
+    shader.execute(uniform! { MyBlock: &buffer }, 4, 1, 1).unwrap();
+    shader.execute(uniform! { MyBlock: &buffer }, 4, 1, 1).unwrap();
+
+    let data = buffer.read().unwrap();
+    for (i, v) in data.values.iter().enumerate() {
+        assert_eq!(*v, i as u32 * 4);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn work_group_count_too_large() {
+    let display = support::build_display();
+    let shader = match build_doubling_shader(&display) {
+        Some(s) => s,
+        None => return
+    };
+
+    match shader.execute(glium::uniforms::EmptyUniforms, u32::MAX, 1, 1) {
+        Err(ComputeError::WorkGroupCountTooLarge { requested, .. }) => {
+            assert_eq!(requested, (u32::MAX, 1, 1));
+        },
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_write_then_read() {
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let shader = ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1) in;
+
+        layout(rgba8) uniform writeonly image2D dest;
+
+        void main() {
+            imageStore(dest, ivec2(gl_GlobalInvocationID.xy), vec4(1.0, 0.0, 0.0, 1.0));
+        }
+    ").unwrap();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            4, 4).unwrap();
+
+    let unit = texture.image_unit_with_access(glium::uniforms::ImageUnitFormat::RGBA8,
+                                              glium::uniforms::ImageUnitAccess::Write).unwrap();
+    shader.execute(uniform! { dest: unit }, 4, 4, 1).unwrap();
+
+    // reading the texture must see the writes of the dispatch
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(*pixel, (255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_bad_format() {
+    let display = support::build_display();
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let texture = glium::texture::UnsignedTexture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedUintFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            4, 4).unwrap();
+
+    match texture.image_unit_with_access(glium::uniforms::ImageUnitFormat::RGBA32UI,
+                                         glium::uniforms::ImageUnitAccess::Read) {
+        Err(glium::uniforms::ImageUnitError::BadFormatClass(32, 128)) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
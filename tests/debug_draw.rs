@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::debug_draw::{DebugDraw, DebugDrawList};
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+#[test]
+fn shapes_line_count() {
+    let display = support::build_display();
+    let mut debug_draw = DebugDraw::new(&display).unwrap();
+
+    debug_draw.wire_box([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(debug_draw.len(), 12);
+
+    debug_draw.axes([0.0, 0.0, 0.0], 1.0);
+    assert_eq!(debug_draw.len(), 15);
+
+    debug_draw.clear();
+    assert!(debug_draw.is_empty());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn flush_draws_lines() {
+    let display = support::build_display();
+    let mut debug_draw = DebugDraw::new(&display).unwrap();
+
+    let texture = glium::texture::Texture2d::empty(&display, 64, 64).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    for i in 0 .. 64 {
+        let y = (i as f32 + 0.5) / 32.0 - 1.0;
+        debug_draw.line([-1.0, y, 0.0], [1.0, y, 0.0], [1.0, 0.0, 0.0, 1.0]);
+    }
+    debug_draw.flush(&mut texture.as_surface(), IDENTITY, &Default::default()).unwrap();
+    assert!(debug_draw.is_empty());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[32][32], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn list_line_count() {
+    let display = support::build_display();
+    let mut list = DebugDrawList::new(&display).unwrap();
+
+    list.push_grid([0.0, 0.0, 0.0], 10.0, 10, [0.5, 0.5, 0.5, 1.0]);
+    assert_eq!(list.len(), 22);
+
+    list.push_wire_box([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 1.0]);
+    list.push_axes([0.0, 0.0, 0.0], 1.0);
+    assert_eq!(list.len(), 37);
+
+    list.clear();
+    assert!(list.is_empty());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn list_is_kept_after_render() {
+    let display = support::build_display();
+    let mut list = DebugDrawList::new(&display).unwrap();
+
+    for i in 0 .. 64 {
+        let y = (i as f32 + 0.5) / 32.0 - 1.0;
+        list.push_line([-1.0, y, 0.0], [1.0, y, 0.0], [0.0, 1.0, 0.0, 1.0]);
+    }
+
+    for _ in 0 .. 2 {
+        let texture = glium::texture::Texture2d::empty(&display, 64, 64).unwrap();
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+        list.render(&mut texture.as_surface(), IDENTITY).unwrap();
+        assert_eq!(list.len(), 64);
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[32][32], (0, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,132 @@
+#[macro_use]
+extern crate glium;
+
+use glium::deferred::{CommandQueue, DeferredError};
+
+use std::thread;
+
+mod support;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Vertex {
+    field: [f32; 2],
+}
+
+implement_vertex!(Vertex, field);
+
+#[test]
+fn create_from_other_thread() {
+    let display = support::build_display();
+
+    let mut queue = CommandQueue::new(&display);
+    let deferred = queue.deferred_context();
+
+    let (texture, buffer) = thread::spawn(move || {
+        let texture = deferred.create_texture_2d(vec![
+            vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+        ], glium::texture::MipmapsOption::NoMipmap);
+
+        let buffer = deferred.create_vertex_buffer(vec![
+            Vertex { field: [1.0, 2.0] },
+            Vertex { field: [3.0, 4.0] },
+        ]);
+        buffer.write(1, vec![Vertex { field: [5.0, 6.0] }]);
+
+        (texture, buffer)
+    }).join().unwrap();
+
+    assert!(queue.texture_2d(&texture).is_none());
+    assert!(queue.process().is_empty());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = queue.texture_2d(&texture).unwrap().read();
+    assert_eq!(data[0][1], (4, 8, 16, 32));
+
+    let data = queue.vertex_buffer(&buffer).unwrap().read().unwrap();
+    assert_eq!(data, vec![Vertex { field: [1.0, 2.0] }, Vertex { field: [5.0, 6.0] }]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn write_out_of_range() {
+    let display = support::build_display();
+
+    let mut queue = CommandQueue::new(&display);
+    let deferred = queue.deferred_context();
+
+    let buffer = deferred.create_vertex_buffer(vec![Vertex { field: [1.0, 2.0] }]);
+    buffer.write(1, vec![Vertex { field: [5.0, 6.0] }]);
+
+    match &queue.process()[..] {
+        [DeferredError::OutOfRange] => (),
+        errors => panic!("{:?}", errors),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn write_overflow() {
+    let display = support::build_display();
+
+    let mut queue = CommandQueue::new(&display);
+    let deferred = queue.deferred_context();
+
+    let buffer = deferred.create_vertex_buffer(vec![Vertex { field: [1.0, 2.0] }]);
+    buffer.write(usize::MAX, vec![Vertex { field: [5.0, 6.0] }]);
+
+    let texture = deferred.create_texture_2d(vec![vec![(0u8, 0u8, 0u8, 0u8)]],
+                                             glium::texture::MipmapsOption::NoMipmap);
+    texture.write(glium::Rect { left: u32::MAX, bottom: 0, width: 1, height: 1 },
+                  vec![vec![(0u8, 0u8, 0u8, 0u8)]]);
+
+    match &queue.process()[..] {
+        [DeferredError::OutOfRange, DeferredError::OutOfRange] => (),
+        errors => panic!("{:?}", errors),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn write_texture_wrong_dimensions() {
+    let display = support::build_display();
+
+    let mut queue = CommandQueue::new(&display);
+    let deferred = queue.deferred_context();
+
+    let texture = deferred.create_texture_2d(vec![vec![(0u8, 0u8, 0u8, 0u8); 2]; 2],
+                                             glium::texture::MipmapsOption::NoMipmap);
+    texture.write(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                  vec![vec![(0u8, 0u8, 0u8, 0u8)]]);
+
+    match &queue.process()[..] {
+        [DeferredError::DataSizeMismatch] => (),
+        errors => panic!("{:?}", errors),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn handle_drop_destroys() {
+    let display = support::build_display();
+
+    let mut queue = CommandQueue::new(&display);
+    let deferred = queue.deferred_context();
+
+    let buffer = deferred.create_vertex_buffer(vec![Vertex { field: [1.0, 2.0] }]);
+    let clone = buffer.clone();
+    drop(buffer);
+    assert!(queue.process().is_empty());
+    assert!(queue.vertex_buffer(&clone).is_some());
+
+    let texture = deferred.create_texture_2d(vec![vec![(0u8, 0u8, 0u8, 0u8)]],
+                                             glium::texture::MipmapsOption::NoMipmap);
+    drop(texture);
+    drop(clone);
+    assert!(queue.process().is_empty());
+    assert_eq!(format!("{:?}", queue), "CommandQueue { resources: 0 }");
+
+    display.assert_no_error(None);
+}
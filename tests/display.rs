@@ -141,6 +141,18 @@ fn debug_string() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn debug_group() {
+    // tests that nested debug groups don't trigger an OpenGL error
+    let display = support::build_display();
+    {
+        let _outer = display.debug_group("Outer");
+        let _inner = display.debug_group("Inner");
+        display.insert_debug_marker("Hello world").ok();
+    }
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn is_context_lost() {
@@ -149,3 +161,151 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+#[should_panic]
+fn frame_drop_panics_by_default() {
+    let display = support::build_display();
+    assert_eq!(display.get_frame_drop_policy(), glium::FrameDropPolicy::Panic);
+
+    let _frame = display.draw();
+}
+
+#[test]
+fn frame_drop_policy_finish() {
+    let display = support::build_display();
+    display.set_frame_drop_policy(glium::FrameDropPolicy::Finish);
+
+    let mut frame = display.draw();
+    assert_eq!(frame.get_drop_policy(), glium::FrameDropPolicy::Finish);
+    frame.clear_color(0.0, 0.0, 0.0, 0.0);
+    drop(frame);
+
+    // overriding the policy of a single frame
+    let mut frame = display.draw();
+    frame.set_drop_policy(glium::FrameDropPolicy::Panic);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn seamless_cubemaps() {
+    let display = support::build_display();
+    if !display.is_seamless_cubemap_supported() {
+        return;
+    }
+
+    display.set_seamless_cubemaps(false);
+    assert!(!display.is_seamless_cubemaps_enabled());
+
+    display.set_seamless_cubemaps(true);
+    assert!(display.is_seamless_cubemaps_enabled());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn exec_with_state_invalidation() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let value = unsafe { display.exec_with_state_invalidation(|| 5) };
+    assert_eq!(value, 5);
+
+    // everything must be bound again after the invalidation
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn max_frames_in_flight() {
+    let display = support::build_display();
+    assert_eq!(display.get_max_frames_in_flight(), None);
+
+    display.set_max_frames_in_flight(Some(1));
+    assert_eq!(display.get_max_frames_in_flight(), Some(1));
+
+    for _ in 0 .. 3 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 1.0);
+        frame.finish().unwrap();
+    }
+
+    display.set_max_frames_in_flight(None);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn context_reset_registry() {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    let display = support::build_display();
+
+    let texture = Rc::new(RefCell::new(glium::Texture2d::empty(&display, 4, 4).unwrap()));
+    display.register_recreatable(&texture);
+
+    let called = Rc::new(Cell::new(false));
+    let id = {
+        let called = called.clone();
+        display.on_context_reset(move || called.set(true))
+    };
+
+    // the context hasn't been lost, so nothing happens at the swap
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    assert!(!called.get());
+    assert!(display.take_recreation_errors().is_empty());
+
+    display.remove_context_reset_callback(id);
+    drop(texture);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn recreate_resources() {
+    use glium::Recreatable;
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let display = support::build_display();
+
+    let mut texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                        glium::texture::MipmapsOption::EmptyMipmapsMax(2),
+                                        8, 4).unwrap();
+    texture.recreate().unwrap();
+    assert_eq!(texture.dimensions(), (8, 4));
+    assert_eq!(texture.get_mipmap_levels(), 3);
+
+    let mut buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [0.0, 0.0] },
+        Vertex { position: [1.0, 1.0] },
+    ]).unwrap();
+    buffer.recreate().unwrap();
+    assert_eq!(buffer.len(), 2);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate glium;
+
+use glium::{DrawCommand, Surface};
+
+mod support;
+
+#[test]
+fn draw_batch() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                uniform vec4 rect;
+                attribute vec2 position;
+                varying vec2 v_tex_coords;
+
+                void main() {
+                    gl_Position = vec4(rect.xy + (position * 0.5 + 0.5) * rect.zw, 0.0, 1.0);
+                    v_tex_coords = position * 0.5 + 0.5;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D tex;
+                uniform vec4 color;
+                varying vec2 v_tex_coords;
+
+                void main() {
+                    gl_FragColor = color * texture2D(tex, v_tex_coords);
+                }
+            ",
+        },
+        100 es => {
+            vertex: "
+                #version 100
+
+                uniform highp vec4 rect;
+                attribute highp vec2 position;
+                varying mediump vec2 v_tex_coords;
+
+                void main() {
+                    gl_Position = vec4(rect.xy + (position * 0.5 + 0.5) * rect.zw, 0.0, 1.0);
+                    v_tex_coords = position * 0.5 + 0.5;
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform sampler2D tex;
+                uniform lowp vec4 color;
+                varying mediump vec2 v_tex_coords;
+
+                void main() {
+                    gl_FragColor = color * texture2D(tex, v_tex_coords);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let red = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let green = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+
+    let uniforms = [
+        uniform! { rect: [-1.0, -1.0, 1.0, 2.0f32], tex: &red, color: [1.0, 1.0, 1.0, 1.0f32] },
+        uniform! { rect: [0.0, -1.0, 1.0, 2.0f32], tex: &green, color: [1.0, 1.0, 1.0, 1.0f32] },
+        // drawn after the first command, as it uses the same program and texture
+        uniform! { rect: [-1.0, 0.0, 1.0, 1.0f32], tex: &red, color: [0.0, 0.0, 0.0, 1.0f32] },
+    ];
+    let params = Default::default();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+    texture.as_surface().draw_batch(uniforms.iter().map(|uniforms| {
+        DrawCommand::new(&vb, &ib, &program, uniforms, &params)
+    })).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[256][256], (255, 0, 0, 255));
+    assert_eq!(data[768][256], (0, 0, 0, 255));
+    assert_eq!(data[256][768], (0, 255, 0, 255));
+    assert_eq!(data[768][768], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -1408,3 +1408,226 @@ fn primitive_restart_index() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn builder_invalid_depth_range() {
+    let display = support::build_display();
+
+    let res = glium::draw_parameters::DrawParametersBuilder::new()
+        .with_depth(glium::Depth {
+            range: (0.0, 2.0),
+            .. Default::default()
+        })
+        .build(&*display);
+
+    match res {
+        Err(glium::DrawError::InvalidDepthRange) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn builder_matches_draw() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        provoking_vertex: glium::draw_parameters::ProvokingVertex::FirstVertex,
+        depth: glium::Depth {
+            clamp: glium::draw_parameters::DepthClamp::Clamp,
+            .. Default::default()
+        },
+        clip_control_depth: glium::draw_parameters::ClipControlDepth::ZeroToOne,
+        .. Default::default()
+    };
+
+    let built = glium::draw_parameters::DrawParametersBuilder::from_parameters(params.clone())
+        .build(&*display);
+
+    let texture = support::build_renderable_texture(&display);
+    let drawn = texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                                          &glium::uniforms::EmptyUniforms, &params);
+
+    // the builder must reject exactly the parameters that `draw` rejects
+    match (built, drawn) {
+        (Ok(_), Ok(_)) => (),
+        (Err(a), Err(b)) => assert_eq!(a.to_string(), b.to_string()),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn tessellation_default_levels() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        tessellation_default_levels: Some(([4.0, 4.0, 4.0, 4.0], [2.0, 2.0])),
+        .. Default::default()
+    };
+
+    let built = glium::draw_parameters::DrawParametersBuilder::new()
+        .with_tessellation_default_levels([4.0, 4.0, 4.0, 4.0], [2.0, 2.0])
+        .build(&*display);
+
+    let texture = support::build_renderable_texture(&display);
+    let drawn = texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                                          &glium::uniforms::EmptyUniforms, &params);
+
+    match (built, drawn) {
+        (Ok(_), Ok(_)) => (),
+        (Err(glium::DrawError::TessellationDefaultLevelsNotSupported),
+         Err(glium::DrawError::TessellationDefaultLevelsNotSupported)) => return,
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_shading() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        sample_shading: Some(1.0),
+        .. Default::default()
+    };
+
+    let built = glium::draw_parameters::DrawParametersBuilder::new()
+        .with_sample_shading(Some(1.0))
+        .build(&*display);
+
+    let texture = support::build_renderable_texture(&display);
+    let drawn = texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                                          &glium::uniforms::EmptyUniforms, &params);
+
+    match (built, drawn) {
+        (Ok(_), Ok(_)) => (),
+        (Err(glium::DrawError::SampleShadingNotSupported),
+         Err(glium::DrawError::SampleShadingNotSupported)) => return,
+        _ => panic!()
+    }
+
+    // drawing again without sample shading disables it
+    texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn scissor_array() {
+    let display = support::build_display();
+
+    let scissors = [glium::Rect { left: 0, bottom: 0, width: 1, height: 1 }];
+    let params = glium::DrawParameters {
+        scissors: Some(&scissors),
+        .. Default::default()
+    };
+
+    let built = glium::draw_parameters::DrawParametersBuilder::new()
+        .with_scissors(&scissors)
+        .build(&*display);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let drawn = texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                          &params);
+
+    match (built, drawn) {
+        (Ok(_), Ok(_)) => (),
+        (Err(glium::DrawError::ViewportArrayNotSupported),
+         Err(glium::DrawError::ViewportArrayNotSupported)) => return,
+        _ => panic!()
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1][0], (0, 0, 0, 0));
+    assert_eq!(data[0][1], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn too_many_viewports() {
+    let display = support::build_display();
+
+    let viewport = glium::Rect { left: 0, bottom: 0, width: 1, height: 1 };
+    let max = glium::CapabilitiesSource::get_capabilities(&*display).max_viewports as usize;
+    let viewports = vec![viewport; max + 1];
+
+    let built = glium::draw_parameters::DrawParametersBuilder::new()
+        .with_viewports(&viewports)
+        .build(&*display);
+
+    match built {
+        Err(glium::DrawError::TooManyViewports) => (),
+        Err(glium::DrawError::ViewportArrayNotSupported) => return,
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clip_control_zero_to_one() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, -0.5, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let params = glium::DrawParameters {
+        clip_control_depth: glium::draw_parameters::ClipControlDepth::ZeroToOne,
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // a depth of -0.5 is within the clip volume with `NegativeOneToOne` but not with `ZeroToOne`
+    match texture.as_surface().draw(&vertex_buffer, &index_buffer, &program,
+                                    &glium::uniforms::EmptyUniforms, &params)
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::ClipControlNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
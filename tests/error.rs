@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate glium;
+
+use std::error::Error as StdError;
+
+use glium::Surface;
+
+mod support;
+
+fn draw_with_mismatch() -> Result<(), glium::Error> {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None)?;
+
+    let texture = glium::Texture2d::empty(&display, 64, 64)?;
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture)?;
+    framebuffer.draw(&vb, &ib, &program, &uniform! { color: 1.0f32 },
+                     &Default::default())?;
+
+    Ok(())
+}
+
+#[test]
+fn draw_error_conversion() {
+    match draw_with_mismatch() {
+        Err(glium::Error::DrawError(glium::DrawError::UniformTypeMismatch { .. })) => (),
+        _ => panic!()
+    };
+}
+
+#[test]
+fn source_chain() {
+    let err = glium::Error::from(glium::SwapBuffersError::ContextLost);
+
+    match err {
+        glium::Error::SwapBuffersError(glium::SwapBuffersError::ContextLost) => (),
+        _ => panic!()
+    };
+
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), glium::SwapBuffersError::ContextLost.to_string());
+}
@@ -254,6 +254,91 @@ fn multioutput() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multioutput_blend_per_buffer() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            out vec4 color1;
+            out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 0.0, 0.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(..)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color1.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color2.as_surface().clear_color(0.0, 0.0, 1.0, 1.0);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+
+    // the first buffer replaces, the second one adds
+    let additive = glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::One,
+    };
+    let blends = [
+        Default::default(),
+        glium::Blend { color: additive, alpha: additive, .. Default::default() },
+    ];
+    let params = glium::DrawParameters {
+        blend_per_buffer: Some(&blends),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => (),
+        Err(glium::DrawError::BlendPerBufferNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    }
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    for row in read_back1.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    for row in read_back2.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_level() {
     let display = support::build_display();
@@ -447,3 +532,378 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn color_attachments_layout() {
+    let display = support::build_display();
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &color1).unwrap();
+    let attachments = framebuffer.get_color_attachments();
+    assert_eq!(framebuffer.get_color_attachments_count(), 1);
+    assert_eq!(attachments[0].index, 0);
+    assert_eq!(attachments[0].format, color1.get_internal_format().ok());
+    assert_eq!(attachments[0].samples, None);
+
+    let framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                                        [("color1", &color1), ("color2", &color2)].iter().cloned())
+                                        .unwrap();
+    assert_eq!(framebuffer.get_color_attachments_count(), 2);
+
+    let frame = display.draw();
+    assert_eq!(frame.get_color_attachments_count(), 1);
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_dyn_surface() {
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                            glium::texture::MipmapsOption::NoMipmap, 128, 128).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    fn render(surface: &mut dyn Surface, vertex_buffer: &glium::vertex::VertexBufferAny,
+              index_buffer: &glium::index::IndexBufferAny, program: &glium::Program)
+    {
+        surface.clear_color(0.0, 0.0, 0.0, 0.0);
+        surface.draw_dyn(&[vertex_buffer.into()], index_buffer.into(), program,
+                         &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+    }
+
+    render(&mut framebuffer, &vertex_buffer, &index_buffer, &program);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn rect_from_top_left() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                            glium::texture::MipmapsOption::NoMipmap, 128, 64).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    framebuffer.clear_color(0.0, 0.0, 0.0, 0.0);
+    let rect = framebuffer.rect_from_top_left(0, 0, 128, 1);
+    assert_eq!(rect, glium::Rect { left: 0, bottom: 63, width: 128, height: 1 });
+    framebuffer.clear(Some(&rect), Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    // the last row in OpenGL order is the top row of the surface
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[63][0], (255, 0, 0, 255));
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "The rectangle is out of the surface")]
+fn rect_from_top_left_overflow() {
+    glium::Rect::from_top_left(64, 0, u32::MAX, 0, 1);
+}
+
+#[test]
+fn rect_contains_overflow() {
+    let rect = glium::Rect { left: u32::MAX - 1, bottom: u32::MAX - 1, width: 2, height: 2 };
+    assert!(rect.contains(u32::MAX, u32::MAX));
+    assert!(!rect.contains(0, 0));
+}
+
+#[test]
+fn transient_pool_recycling() {
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+    let pool = glium::framebuffer::TransientPool::new(&display);
+    pool.set_max_unused_frames(1);
+
+    let id = {
+        let texture = pool.texture_2d(UncompressedFloatFormat::U8U8U8U8, 16, 16).unwrap();
+        glium::GlObject::get_id(&*texture)
+    };
+    assert_eq!(pool.get_free_count(), 1);
+
+    // same parameters, the texture is reused
+    {
+        let texture = pool.texture_2d(UncompressedFloatFormat::U8U8U8U8, 16, 16).unwrap();
+        assert_eq!(glium::GlObject::get_id(&*texture), id);
+        assert_eq!(pool.get_free_count(), 0);
+
+        // different size, a new texture is created
+        let other = pool.texture_2d(UncompressedFloatFormat::U8U8U8U8, 32, 16).unwrap();
+        assert!(glium::GlObject::get_id(&*other) != id);
+    }
+    assert_eq!(pool.get_free_count(), 2);
+
+    pool.next_frame();
+    assert_eq!(pool.get_free_count(), 2);
+    pool.next_frame();
+    assert_eq!(pool.get_free_count(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dimensions_mismatch_policy() {
+    use glium::framebuffer::{DepthRenderBuffer, DimensionsMismatchPolicy, SimpleFrameBuffer};
+    use glium::framebuffer::ValidationError;
+    use glium::texture::DepthFormat;
+
+    let display = support::build_display();
+    if !glium::framebuffer::is_dimensions_mismatch_supported(&display) {
+        return;
+    }
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                              glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                              glium::texture::MipmapsOption::NoMipmap,
+                                              128, 64).unwrap();
+    let smaller = DepthRenderBuffer::new(&display, DepthFormat::I24, 64, 32).unwrap();
+    let crossed = DepthRenderBuffer::new(&display, DepthFormat::I24, 64, 128).unwrap();
+
+    // the default is the intersection
+    let framebuffer = SimpleFrameBuffer::with_depth_buffer(&display, &texture, &crossed).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 64));
+    assert_eq!(framebuffer.get_attachments_dimensions(), &[(128, 64), (64, 128)]);
+
+    match SimpleFrameBuffer::with_depth_buffer(&display, &texture, &smaller).unwrap()
+                            .with_dimensions_policy(DimensionsMismatchPolicy::Error)
+    {
+        Err(ValidationError::DimensionsMismatch) => (),
+        _ => panic!()
+    };
+
+    let framebuffer = SimpleFrameBuffer::with_depth_buffer(&display, &texture, &smaller).unwrap()
+                            .with_dimensions_policy(DimensionsMismatchPolicy::Smallest).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 32));
+
+    // none of the attachments is the smallest in both axes
+    match SimpleFrameBuffer::with_depth_buffer(&display, &texture, &crossed).unwrap()
+                            .with_dimensions_policy(DimensionsMismatchPolicy::Smallest)
+    {
+        Err(ValidationError::DimensionsMismatch) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn implicit_multisampling() {
+    use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer, ValidationError};
+    use glium::texture::DepthFormat;
+
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                              glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                              glium::texture::MipmapsOption::NoMipmap,
+                                              64, 64).unwrap();
+
+    if !glium::framebuffer::is_implicit_multisampling_supported(&display) {
+        match SimpleFrameBuffer::with_implicit_multisampling(&display, &texture, 4) {
+            Err(ValidationError::ImplicitMultisamplingNotSupported) => (),
+            _ => panic!()
+        };
+        return;
+    }
+
+    let depth = DepthRenderBuffer::new_multisample(&display, DepthFormat::I24, 64, 64, 4).unwrap();
+    let mut framebuffer = SimpleFrameBuffer::with_implicit_multisampling_and_depth_buffer(
+                                                        &display, &texture, &depth, 4).unwrap();
+    framebuffer.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[32][32], (255, 0, 0, 255));
+
+    // the depth buffer must have the same number of samples
+    let depth = DepthRenderBuffer::new(&display, DepthFormat::I24, 64, 64).unwrap();
+    match SimpleFrameBuffer::with_implicit_multisampling_and_depth_buffer(&display, &texture,
+                                                                         &depth, 4)
+    {
+        Err(ValidationError::SamplesCountMismatch) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn layered_framebuffer() {
+    use glium::framebuffer::{LayeredColorAttachment, LayeredFrameBuffer, ValidationError};
+
+    let display = support::build_display();
+    if !glium::framebuffer::is_layered_rendering_supported(&display) {
+        return;
+    }
+
+    let array = match glium::texture::Texture2dArray::empty(&display, 64, 64, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+    let cubemap = match glium::texture::Cubemap::empty(&display, 32) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = LayeredFrameBuffer::new(&display, &array).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 64));
+    assert_eq!(framebuffer.get_layers(), 4);
+    framebuffer.clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let framebuffer = LayeredFrameBuffer::new(&display, &cubemap).unwrap();
+    assert_eq!(framebuffer.get_layers(), 6);
+
+    // a regular texture can't be attached as a whole
+    let texture = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    match LayeredFrameBuffer::new(&display, LayeredColorAttachment::Texture(*texture.main_level())) {
+        Err(ValidationError::NonLayeredAttachment) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn layered_framebuffer_blit_color() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+    if !glium::framebuffer::is_layered_rendering_supported(&display) {
+        return;
+    }
+
+    let array = match glium::texture::Texture2dArray::empty(&display, 64, 64, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = LayeredFrameBuffer::new(&display, &array).unwrap();
+    framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    // blits read from the first layer
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    framebuffer.fill(&target.as_surface(), glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1023][1023], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn cubemap_array_face_layer() {
+    use glium::texture::CubeLayer;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::CubemapArray::empty(&display, 32, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = texture.as_surface_for_face_layer(1, CubeLayer::NegativeZ).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (32, 32));
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    assert!(texture.as_surface_for_face_layer(2, CubeLayer::PositiveX).is_none());
+
+    let depth = match glium::texture::DepthCubemapArray::empty(&display, 32, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = depth.as_surface_for_face_layer(0, CubeLayer::PositiveY).unwrap();
+    framebuffer.clear_depth(1.0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn srgb_conversion_override() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(0.5, 0.5, 0.5, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let texture = match glium::texture::SrgbTexture2d::empty(&display, 16, 16) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    assert_eq!(framebuffer.get_srgb_conversion(), None);
+
+    // without conversion, the value is written as is
+    let params = glium::DrawParameters {
+        srgb: Some(false),
+        .. Default::default()
+    };
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => (),
+        Err(glium::DrawError::SrgbConversionNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[0][0].0 == 127 || data[0][0].0 == 128, "{:?}", data[0][0]);
+
+    // with conversion, 0.5 in linear space becomes ~0.735 in sRGB space
+    framebuffer.set_srgb_conversion(true);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[0][0].0 >= 186 && data[0][0].0 <= 189, "{:?}", data[0][0]);
+
+    // the draw parameters take precedence over the framebuffer
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[0][0].0 == 127 || data[0][0].0 == 128, "{:?}", data[0][0]);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "surfaceless")]
+
+use glium::Surface;
+use glium::backend::glutin::headless::Headless;
+
+#[test]
+fn surfaceless_clear_and_read() {
+    let headless = Headless::new_surfaceless((64, 32)).unwrap();
+    assert_eq!(headless.get_framebuffer_dimensions(), (64, 32));
+
+    let mut target = headless.draw();
+    target.clear_color(0.0, 1.0, 0.0, 1.0);
+    target.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = headless.read_front_buffer().unwrap();
+    assert_eq!(data.len(), 32);
+    assert_eq!(data[0].len(), 64);
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    headless.assert_no_error(None);
+}
+
+#[test]
+fn surfaceless_resize() {
+    let headless = Headless::new_surfaceless((64, 32)).unwrap();
+    headless.resize((16, 8));
+    assert_eq!(headless.get_framebuffer_dimensions(), (16, 8));
+
+    let mut target = headless.draw();
+    assert_eq!(target.get_dimensions(), (16, 8));
+    target.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+    target.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = headless.read_front_buffer().unwrap();
+    assert_eq!(data.len(), 8);
+    assert_eq!(data[0].len(), 16);
+    assert_eq!(data[3][5], (255, 0, 0, 255));
+
+    headless.assert_no_error(None);
+}
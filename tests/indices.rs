@@ -412,3 +412,335 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn multidraw_elements_builder() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 1, 3, 2]).unwrap();
+
+    let mut builder = index::DrawCommandsIndicesBuilder::for_index_buffer(&indices);
+    builder.push(index::DrawElementsIndirectCommand {
+        count: 3,
+        instance_count: 1,
+        first_index: 0,
+        base_vertex: 0,
+        base_instance: 0,
+    }).unwrap();
+    builder.push(index::DrawElementsIndirectCommand {
+        count: 3,
+        instance_count: 1,
+        first_index: 3,
+        base_vertex: 0,
+        base_instance: 0,
+    }).unwrap();
+
+    let multidraw = match builder.build(&display) {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, multidraw.with_index_buffer(&indices),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multidraw_builder_out_of_bounds() {
+    let mut builder = index::DrawCommandsNoIndicesBuilder::new(4);
+
+    builder.push(index::DrawArraysIndirectCommand {
+        count: 4,
+        instance_count: 1,
+        first_index: 0,
+        base_instance: 0,
+    }).unwrap();
+
+    let err = builder.push(index::DrawArraysIndirectCommand {
+        count: 2,
+        instance_count: 1,
+        first_index: 3,
+        base_instance: 0,
+    });
+
+    assert_eq!(err, Err(index::DrawCommandError::OutOfBounds { command: 1, end: 5, len: 4 }));
+    assert_eq!(builder.commands().len(), 1);
+}
+
+#[test]
+fn multi_range() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        // bottom-left quarter
+        Vertex { position: [-1.0,  0.0] },
+        Vertex { position: [ 0.0,  0.0] },
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [ 0.0, -1.0] },
+        // top-left quarter, not drawn
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 0.0,  1.0] },
+        Vertex { position: [-1.0,  0.0] },
+        Vertex { position: [ 0.0,  0.0] },
+        // right half
+        Vertex { position: [ 0.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [ 0.0, -1.0] },
+        Vertex { position: [ 1.0, -1.0] },
+    ]).unwrap();
+
+    let ranges = [(0, 4), (8, 4)];
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, index::MultiRange(index::PrimitiveType::TriangleStrip, &ranges),
+                              &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[256][256], (255, 0, 0, 255));
+    assert_eq!(data[768][256], (0, 0, 0, 0));
+    assert_eq!(data[256][768], (255, 0, 0, 255));
+    assert_eq!(data[768][768], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multi_range_out_of_bounds() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [ 1.0, -1.0] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let result = texture.as_surface().draw(&vb,
+                                           index::MultiRange(PrimitiveType::TriangleStrip,
+                                                             &[(0, 4), (2, 3)]),
+                                           &program, &glium::uniforms::EmptyUniforms,
+                                           &Default::default());
+
+    assert!(matches!(result, Err(glium::DrawError::VertexRangeOutOfBounds)));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn base_vertex() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        // left half, not drawn
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 0.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [ 0.0, -1.0] },
+        // right half
+        Vertex { position: [ 0.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [ 0.0, -1.0] },
+        Vertex { position: [ 1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                          &[0u16, 1, 2, 3]).unwrap();
+    let indices = indices.slice(..).unwrap().with_base_vertex(4);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw(&vb, indices, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::BaseVertexNotSupported) => return,
+        result => result.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[512][256], (0, 0, 0, 0));
+    assert_eq!(data[512][768], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn base_instance() {
+    let display = support::build_display();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec2 offset;
+
+                void main() {
+                    gl_Position = vec4(position + offset, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec2 offset;
+
+                void main() {
+                    gl_Position = vec4(position + offset, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    #[derive(Copy, Clone)]
+    struct Instance {
+        offset: [f32; 2],
+    }
+
+    implement_vertex!(Instance, offset);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 0.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [ 0.0, -1.0] },
+    ]).unwrap();
+
+    // the first instance is outside of the viewport and is skipped
+    let instances = glium::VertexBuffer::new(&display, &[
+        Instance { offset: [-4.0, 0.0] },
+        Instance { offset: [ 1.0, 0.0] },
+    ]).unwrap();
+    let instances = match instances.per_instance() {
+        Ok(instances) => instances,
+        Err(_) => return,
+    };
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                          &[0u16, 1, 2, 3]).unwrap();
+    let indices = indices.slice(..).unwrap().with_base_instance(1);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw((&vb, instances), indices, &program,
+                                    &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::BaseInstanceNotSupported) => return,
+        result => result.unwrap(),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[512][256], (0, 0, 0, 0));
+    assert_eq!(data[512][768], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn validate_indices() {
+    let display = support::build_display();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 4]).unwrap();
+
+    match indices.validate(4) {
+        Err(index::IndexValidationError::ReadError(_)) => return,
+        Err(index::IndexValidationError::IndexOutOfRange { position: 5, vertex: 4,
+                                                           vertices_count: 4 }) => (),
+        result => panic!("{:?}", result),
+    };
+
+    indices.validate(5).unwrap();
+    indices.slice(..).unwrap().with_base_vertex(-1).validate(5).unwrap_err();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn validate_indices_draw() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 3]).unwrap();
+
+    let params = glium::DrawParameters {
+        validate_indices: true,
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    let result = texture.as_surface().draw(&vb, &indices, &program,
+                                           &glium::uniforms::EmptyUniforms, &params);
+
+    match result {
+        Err(glium::DrawError::IndexValidationError(
+            index::IndexValidationError::ReadError(_))) => (),
+        Err(glium::DrawError::IndexValidationError(
+            index::IndexValidationError::IndexOutOfRange { position: 2, .. })) => (),
+        result => panic!("{:?}", result),
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn index_buffer_too_large() {
+    let display = support::build_display();
+
+    match glium::IndexBuffer::<u32>::empty(&display, PrimitiveType::TrianglesList, usize::MAX / 2) {
+        Err(index::BufferCreationError::ValidationError(
+            index::IndexValidationError::BufferTooLarge { .. })) => (),
+        result => panic!("{:?}", result),
+    };
+
+    display.assert_no_error(None);
+}
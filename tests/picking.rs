@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::backend::Facade;
+use glium::picking::{PickError, Picker};
+
+mod support;
+
+/// Fills the picker with the given ID. Returns false if integral outputs are not supported.
+fn draw_id<F: ?Sized>(display: &F, picker: &Picker, id: u32) -> bool where F: Facade {
+    let (vb, ib) = support::build_rectangle_vb_ib(display);
+
+    let program = glium::Program::from_source(display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            uniform uint id;
+            out uint f_id;
+
+            void main() {
+                f_id = id;
+            }
+        ",
+        None);
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return false
+    };
+
+    picker.framebuffer(display).unwrap()
+          .draw(&vb, &ib, &program, &uniform!{ id: id }, &Default::default()).unwrap();
+    true
+}
+
+#[test]
+fn empty_after_clear() {
+    let display = support::build_display();
+    let picker = Picker::new(&display, 16, 16).unwrap();
+
+    picker.clear();
+    assert_eq!(picker.id_at(8, 8).unwrap(), None);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sync_and_async_reads() {
+    let display = support::build_display();
+    let picker = Picker::new(&display, 16, 16).unwrap();
+
+    picker.clear();
+    if !draw_id(&display, &picker, 7) {
+        return;
+    }
+
+    assert_eq!(picker.id_at(3, 12).unwrap(), Some(7));
+
+    let query = picker.request_id_at(3, 12).unwrap();
+    assert_eq!(query.get().unwrap(), Some(7));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn out_of_bounds() {
+    let display = support::build_display();
+    let picker = Picker::new(&display, 16, 16).unwrap();
+
+    match picker.id_at(16, 0) {
+        Err(PickError::OutOfBounds) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
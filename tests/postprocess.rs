@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::postprocess::FilterChain;
+use glium::texture::Texture2d;
+
+mod support;
+
+const INVERT: &str = "
+    #version 110
+
+    uniform sampler2D source;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        gl_FragColor = vec4(1.0 - texture2D(source, v_tex_coords).rgb, 1.0);
+    }
+";
+
+const SCALE: &str = "
+    #version 140
+
+    uniform sampler2D source;
+    uniform float factor;
+    in vec2 v_tex_coords;
+    out vec4 color;
+
+    void main() {
+        color = vec4(texture(source, v_tex_coords).rgb * factor, 1.0);
+    }
+";
+
+#[test]
+fn empty_chain_copies() {
+    let display = support::build_display();
+    let mut chain = FilterChain::new(&display).unwrap();
+    assert!(chain.is_empty());
+
+    let input = Texture2d::empty(&display, 16, 16).unwrap();
+    input.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    let output = Texture2d::empty(&display, 32, 32).unwrap();
+
+    chain.draw(&input, &mut output.as_surface()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[20][20], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn passes_and_uniforms() {
+    let display = support::build_display();
+    let mut chain = FilterChain::new(&display).unwrap();
+
+    chain.push_pass(INVERT).unwrap();
+    let scale = chain.push_pass(SCALE).unwrap();
+    chain.push_pass(INVERT).unwrap();
+    chain.set_uniform(scale, "factor", 0.5f32);
+    assert_eq!(chain.len(), 3);
+
+    let input = Texture2d::empty(&display, 16, 16).unwrap();
+    input.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    // the intermediate textures are created again when the dimensions change
+    for size in [32, 8] {
+        let output = Texture2d::empty(&display, size, size).unwrap();
+        chain.draw(&input, &mut output.as_surface()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        let (r, g, b, a) = data[size as usize / 2][size as usize / 2];
+        assert_eq!((r, a), (255, 255));
+        assert!((127 ..= 128).contains(&g) && (127 ..= 128).contains(&b));
+    }
+
+    display.assert_no_error(None);
+}
@@ -190,6 +190,41 @@ fn primitives_generated() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn pipeline_statistics() {
+    let display = support::build_display();
+
+    let vertices = match glium::draw_parameters::VerticesSubmittedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+    let fragments = glium::draw_parameters::FragmentShaderInvocationsQuery::new(&display).unwrap();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            pipeline_statistics_queries: glium::draw_parameters::PipelineStatisticsQueries {
+                vertices_submitted: Some(&vertices),
+                fragment_shader_invocations: Some(&fragments),
+                .. Default::default()
+            },
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    assert!(vertices.get() >= 4);
+    assert!(fragments.get() >= 1024 * 1024);
+
+    display.assert_no_error(None);
+}
+
 // FIXME: add test for transform feedback query
 
 // FIXME: add more tests for conditional rendering
@@ -309,3 +344,137 @@ fn query_to_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn query_availability_to_buffer() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query).into()),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    // waiting for the result, after which it must be available
+    let mut result = glium::buffer::BufferView::empty(&display,
+                                                      glium::buffer::BufferType::ArrayBuffer,
+                                                      glium::buffer::BufferMode::Default).unwrap();
+    if query.to_buffer_u32(result.as_slice()).is_err() {
+        return;
+    }
+
+    let mut available = glium::buffer::BufferView::empty(&display,
+                                                         glium::buffer::BufferType::ArrayBuffer,
+                                                         glium::buffer::BufferMode::Default).unwrap();
+    query.availability_to_buffer_u32(available.as_slice()).unwrap();
+    assert_eq!(*available.map(), 1);
+
+    let mut no_wait = glium::buffer::BufferView::empty(&display,
+                                                       glium::buffer::BufferType::ArrayBuffer,
+                                                       glium::buffer::BufferMode::Default).unwrap();
+    query.to_buffer_u32_no_wait(no_wait.as_slice()).unwrap();
+    assert_eq!(*no_wait.map(), *result.map());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn unused_query_is_not_ready() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    assert!(!query.is_ready());
+
+    let buffer = glium::buffer::BufferView::<u32>::empty(&display,
+                                                         glium::buffer::BufferType::ArrayBuffer,
+                                                         glium::buffer::BufferMode::Default).unwrap();
+    match query.to_buffer_u32(buffer.as_slice()) {
+        Err(glium::draw_parameters::ToBufferError::QueryNotUsed) => (),
+        Err(glium::draw_parameters::ToBufferError::NotSupported) => return,
+        Ok(_) => panic!(),
+    };
+
+    assert_eq!(query.get(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn gpu_profiler_scopes() {
+    let display = support::build_display();
+
+    let profiler = match glium::debug::GpuProfiler::new(&display) {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    for frame in 0 .. 3 {
+        {
+            let _outer = profiler.scope("outer");
+            texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+            let _inner = profiler.scope(format!("draw {}", frame));
+            texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).unwrap();
+        }
+
+        if frame == 2 {
+            display.finish();
+        }
+
+        profiler.end_frame().unwrap();
+    }
+
+    assert_eq!(profiler.pending_frames(), 0);
+
+    let report = profiler.last_report().unwrap();
+    assert_eq!(report.frame, 2);
+    assert_eq!(report.scopes.len(), 2);
+    assert_eq!(report.scopes[0].name, "outer");
+    assert_eq!(report.scopes[0].depth, 0);
+    assert_eq!(report.scopes[0].start, 0);
+    assert_eq!(report.scopes[1].name, "draw 2");
+    assert_eq!(report.scopes[1].depth, 1);
+    assert!(report.scopes[1].duration <= report.scopes[0].duration);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn gpu_profiler_scope_active_at_end_of_frame() {
+    let display = support::build_display();
+
+    let profiler = match glium::debug::GpuProfiler::new(&display) {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let scope = profiler.scope("unfinished");
+    assert_eq!(profiler.end_frame(), Err(glium::debug::ProfilerError::ScopeStillActive));
+
+    drop(scope);
+    profiler.end_frame().unwrap();
+
+    display.assert_no_error(None);
+}
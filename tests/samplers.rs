@@ -79,3 +79,176 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn sampler_objects_same_texture() {
+    use glium::uniforms::{MagnifySamplerFilter, SamplerBehavior, SamplerObject};
+    use glium::uniforms::{SamplerObjectCreationError, SamplerWrapFunction};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D repeated;
+            uniform sampler2D clamped;
+
+            void main() {
+                gl_FragColor = vec4(texture2D(repeated, vec2(1.25, 0.0)).r,
+                                    texture2D(clamped, vec2(1.25, 0.0)).r, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let behavior = SamplerBehavior {
+        magnify_filter: MagnifySamplerFilter::Nearest,
+        .. Default::default()
+    };
+
+    let mut repeat = match SamplerObject::new(&display, &behavior) {
+        Ok(s) => s,
+        Err(SamplerObjectCreationError::NotSupported) => return,
+    };
+    repeat.set_wrap_function(SamplerWrapFunction::Repeat);
+    repeat.set_label("repeat");
+
+    let mut clamp = SamplerObject::new(&display, &behavior).unwrap();
+    clamp.set_wrap_function(SamplerWrapFunction::Clamp);
+
+    let texture_data = vec![vec![(0u8, 0, 0), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let uniforms = uniform! {
+        repeated: repeat.with_texture(&texture),
+        clamped: clamp.with_texture(&texture),
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn anisotropy_clamped() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let texture_data = vec![vec![(255u8, 255, 255), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // values above the maximum supported are clamped, or ignored if anisotropic filtering
+    // isn't supported
+    let max = display.get_max_anisotropy_support().unwrap_or(1);
+    for &level in &[max, u16::MAX] {
+        let uniforms = uniform! {
+            texture: texture.sampled().anisotropy(level),
+        };
+
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+            Ok(_) => (),
+            Err(glium::DrawError::SamplersNotSupported) => return,
+            Err(e) => panic!("{:?}", e)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (255, 255, 255, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_texture_comparison_shadow_sampler() {
+    use glium::uniforms::{DepthTextureComparison, MagnifySamplerFilter, MinifySamplerFilter};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2DShadow shadow_map;
+
+            void main() {
+                gl_FragColor = vec4(shadow2D(shadow_map, vec3(0.5, 0.5, 0.25)).r, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let depth = support::build_constant_depth_texture(&display, 0.5);
+    let sampler = depth.sampled()
+                       .minify_filter(MinifySamplerFilter::Nearest)
+                       .magnify_filter(MagnifySamplerFilter::Nearest);
+
+    let output = support::build_renderable_texture(&display);
+
+    // a shadow sampler needs the comparison to be enabled
+    let uniforms = uniform! { shadow_map: sampler };
+    match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformTypeMismatch { .. }) => (),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        r => panic!("{:?}", r)
+    };
+
+    let uniforms = uniform! {
+        shadow_map: sampler.depth_texture_comparison(Some(DepthTextureComparison::LessOrEqual)),
+    };
+
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,112 @@
+#[macro_use]
+extern crate glium;
+
+use glium::{Api, CapabilitiesSource, Surface, Version};
+use glium::program::{ProgramPipeline, ProgramPipelineError, SeparableProgram, ShaderType};
+
+mod support;
+
+const VERTEX_SHADER: &str = "
+    #version 410
+
+    in vec2 position;
+
+    out gl_PerVertex {
+        vec4 gl_Position;
+    };
+
+    void main() {
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+";
+
+fn is_supported<C: CapabilitiesSource>(ctxt: &C) -> bool {
+    glium::program::is_program_pipeline_supported(ctxt) &&
+        ctxt.get_version() >= &Version(Api::Gl, 4, 1)
+}
+
+#[test]
+fn pipeline_draw() {
+    let display = support::build_display();
+    if !is_supported(&display) {
+        return;
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex = SeparableProgram::new(&display, ShaderType::Vertex, VERTEX_SHADER).unwrap();
+    let uniform_color = SeparableProgram::new(&display, ShaderType::Fragment, "
+        #version 410
+
+        uniform vec4 color;
+        out vec4 f_color;
+
+        void main() {
+            f_color = color;
+        }
+    ").unwrap();
+    let green = SeparableProgram::new(&display, ShaderType::Fragment, "
+        #version 410
+
+        out vec4 f_color;
+
+        void main() {
+            f_color = vec4(0.0, 1.0, 0.0, 1.0);
+        }
+    ").unwrap();
+
+    let mut pipeline = ProgramPipeline::new(&display, &vertex, &uniform_color).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_with_program(&vb, &ib, &pipeline,
+                                           &uniform! { color: [1.0, 0.0, 0.0, 1.0f32] },
+                                           &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    pipeline.set_fragment_stage(&green).unwrap();
+    texture.as_surface().draw_with_program(&vb, &ib, &pipeline, &glium::uniforms::EmptyUniforms,
+                                           &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pipeline_wrong_stage() {
+    let display = support::build_display();
+    if !is_supported(&display) {
+        return;
+    }
+
+    let vertex = SeparableProgram::new(&display, ShaderType::Vertex, VERTEX_SHADER).unwrap();
+
+    match ProgramPipeline::new(&display, &vertex, &vertex) {
+        Err(ProgramPipelineError::WrongStage { expected: ShaderType::Fragment,
+                                               obtained: ShaderType::Vertex }) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn separable_compute_stage() {
+    let display = support::build_display();
+    if !is_supported(&display) {
+        return;
+    }
+
+    match SeparableProgram::new(&display, ShaderType::Compute, "") {
+        Err(glium::ShaderTypeNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
@@ -4,7 +4,7 @@
 #[macro_use]
 extern crate glium;
 
-use glium::Surface;
+use glium::{CapabilitiesSource, Surface};
 
 mod support;
 
@@ -77,6 +77,46 @@ fn program_compilation_error() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_compilation_error_diagnostics() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        // vertex shader
+        "
+            #version 110
+
+            void main() {
+                gl_Position = undeclared_variable;
+            }
+        ",
+
+        // fragment shader
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        // geometry shader
+        None);
+
+    match program {
+        Err(glium::CompilationError(_, glium::program::ShaderType::Vertex, diagnostics)) => {
+            assert!(!diagnostics.is_empty());
+            assert!(diagnostics.iter().all(|d| d.stage == glium::program::ShaderType::Vertex));
+            assert!(diagnostics.iter().any(|d| {
+                d.severity == glium::program::DiagnosticSeverity::Error
+            }));
+        },
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 // This test is disabled because some OpenGL drivers don't catch
 // the linking error (even though they are supposed to)
 #[test]
@@ -154,6 +194,57 @@ fn get_frag_data_location() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn frag_data_outputs() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 color;
+            layout(location = 1) out ivec2 other;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+                other = ivec2(1, 2);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version 3.30 may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let outputs = program.frag_data_outputs();
+    if outputs.is_empty() {
+        // program interface queries are not supported
+        return;
+    }
+
+    assert_eq!(outputs.len(), 2);
+
+    let color = outputs.get("color").unwrap();
+    assert_eq!(color.location, 0);
+    assert_eq!(color.ty, glium::uniforms::UniformType::FloatVec4);
+    assert_eq!(color.size, None);
+
+    let other = outputs.get("other").unwrap();
+    assert_eq!(other.location, 1);
+    assert_eq!(other.ty, glium::uniforms::UniformType::IntVec2);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_uniform_blocks() {
     let display = support::build_display();
@@ -310,6 +401,57 @@ fn program_binary_reload() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_cache_directory() {
+    let display = support::build_display();
+
+    if !glium::program::is_binary_supported(&display) {
+        return;
+    }
+
+    let directory = std::env::temp_dir().join(format!("glium-program-cache-{}", std::process::id()));
+    display.set_program_cache_directory(Some(directory.clone()));
+
+    let vertex = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let fragment = "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let _program1 = glium::Program::from_source(&display, vertex, fragment, None).unwrap();
+    let mut entries = std::fs::read_dir(&directory).unwrap().map(|e| e.unwrap().path());
+    let entry = entries.next().unwrap();
+    assert!(entries.next().is_none());
+
+    // backdating the file, so that we can tell whether it is written again
+    let old_time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    std::fs::File::options().write(true).open(&entry).unwrap().set_modified(old_time).unwrap();
+
+    let _program2 = glium::Program::from_source(&display, vertex, fragment, None).unwrap();
+    assert!(_program2.get_attribute("position").is_some());
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+    // a cache miss would have compiled the program again and replaced the file
+    assert_eq!(std::fs::metadata(&entry).unwrap().modified().unwrap(), old_time);
+
+    display.set_program_cache_directory(None);
+    let _ = std::fs::remove_dir_all(&directory);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn program_binary_working() {
     let display = support::build_display();
@@ -463,6 +605,76 @@ fn get_output_primitives_simple() {
 
 // TODO: add tests for get_output_primitives with geometry shader, TES, and both
 
+#[test]
+fn geometry_shader_vertices_out() {
+    let display = support::build_display();
+
+    let max = match display.get_capabilities().max_geometry_output_vertices {
+        Some(max) => max as u32,
+        None => return
+    };
+
+    let vertex = "
+        #version 150
+
+        void main() {
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let fragment = "
+        #version 150
+
+        out vec4 color;
+
+        void main() {
+            color = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+
+    let program = glium::Program::from_source(&display, vertex, fragment, Some("
+        #version 150
+
+        layout(points) in;
+        layout(points, max_vertices = 2) out;
+
+        void main() {
+            gl_Position = gl_in[0].gl_Position;
+            EmitVertex();
+        }
+    "));
+
+    // ignoring test in case of compilation error (version 1.50 may not be supported)
+    if let Ok(program) = program {
+        assert_eq!(program.get_geometry_vertices_out(), Some(2));
+    }
+
+    let source = format!("
+        #version 150
+
+        layout(points) in;
+        layout(points, max_vertices = {}) out;
+
+        void main() {{
+            gl_Position = gl_in[0].gl_Position;
+            EmitVertex();
+        }}
+    ", max + 1);
+
+    match glium::Program::from_source(&display, vertex, fragment, Some(&source)) {
+        Err(glium::ProgramCreationError::GeometryShaderTooManyOutputVertices { requested, max: m }) => {
+            assert_eq!(requested, max + 1);
+            assert_eq!(m, max);
+        },
+        // most drivers refuse to compile the shader
+        Err(glium::ProgramCreationError::CompilationError(..)) => (),
+        Err(glium::ProgramCreationError::LinkingError(..)) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn ssbos() {
     let display = support::build_display();
@@ -0,0 +1,72 @@
+#![cfg(feature = "sprite")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::sprite::{Sprite, SpriteBatch};
+use glium::texture::Texture2d;
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+#[test]
+fn flush_draws_sprites_per_texture() {
+    let display = support::build_display();
+
+    let white = Texture2d::new(&display, vec![vec![(255u8, 255u8, 255u8, 255u8)]]).unwrap();
+    let green = Texture2d::new(&display, vec![vec![(0u8, 255u8, 0u8, 255u8)]]).unwrap();
+    let mut batch = SpriteBatch::new(&display).unwrap();
+
+    let target = Texture2d::empty(&display, 64, 64).unwrap();
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    batch.add(&white, Sprite { position: [-0.5, 0.0], size: [1.0, 2.0],
+                               color: [1.0, 0.0, 0.0, 1.0], .. Default::default() });
+    batch.add(&green, Sprite { position: [0.5, 0.0], size: [1.0, 2.0], .. Default::default() });
+    // drawn after the first sprite, as they use the same texture
+    batch.add(&white, Sprite { position: [-0.5, 0.5], size: [1.0, 1.0],
+                               color: [0.0, 0.0, 1.0, 1.0], .. Default::default() });
+    assert_eq!(batch.len(), 3);
+
+    batch.flush(&mut target.as_surface(), IDENTITY, &Default::default()).unwrap();
+    assert!(batch.is_empty());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[16][16], (255, 0, 0, 255));
+    assert_eq!(data[48][16], (0, 0, 255, 255));
+    assert_eq!(data[16][48], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn rotation() {
+    let display = support::build_display();
+
+    let white = Texture2d::new(&display, vec![vec![(255u8, 255u8, 255u8, 255u8)]]).unwrap();
+    let mut batch = SpriteBatch::new(&display).unwrap();
+
+    let target = Texture2d::empty(&display, 64, 64).unwrap();
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    // a thin horizontal bar rotated by 90 degrees becomes vertical
+    batch.add(&white, Sprite {
+        size: [2.0, 0.25],
+        rotation: std::f32::consts::FRAC_PI_2,
+        .. Default::default()
+    });
+    batch.flush(&mut target.as_surface(), IDENTITY, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[4][32], (255, 255, 255, 255));
+    assert_eq!(data[32][4], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
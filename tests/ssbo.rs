@@ -134,3 +134,169 @@ fn custom_bind_point() {
 
     display.assert_no_error(None);
 }
+
+fn build_doubling_shader<F: ?Sized>(display: &F) -> Option<glium::program::ComputeShader>
+    where F: glium::backend::Facade
+{
+    if !glium::program::ComputeShader::is_supported(display.get_context()) {
+        return None;
+    }
+
+    glium::program::ComputeShader::from_source(display, "
+        #version 430
+        layout(local_size_x = 1) in;
+
+        buffer MyBlock {
+            uint values[];
+        };
+
+        void main() {
+            values[gl_GlobalInvocationID.x] *= 2;
+        }
+    ").ok()
+}
+
+#[test]
+fn typed_array() {
+    let display = support::build_display();
+    let shader = match build_doubling_shader(&display) {
+        Some(s) => s,
+        None => return
+    };
+
+    let data: Vec<u32> = (0 .. 16).collect();
+    let buffer = glium::uniforms::ShaderStorageBuffer::new(&display, &data[..]).unwrap();
+
+    shader.execute(uniform! { MyBlock: &buffer }, 16, 1, 1).unwrap();
+
+    let data = buffer.read().unwrap();
+    for (i, v) in data.iter().enumerate() {
+        assert_eq!(*v, i as u32 * 2);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn typed_layout_mismatch() {
+    let display = support::build_display();
+    let shader = match build_doubling_shader(&display) {
+        Some(s) => s,
+        None => return
+    };
+
+    let buffer = glium::uniforms::ShaderStorageBuffer::<[f32]>::empty_array(&display, 16,
+                                                    glium::buffer::BufferMode::Default).unwrap();
+
+    match shader.execute(uniform! { MyBlock: &buffer }, 16, 1, 1) {
+        Err(glium::program::ComputeError::DrawError(
+            glium::DrawError::UniformBlockLayoutMismatch { name, .. })) => assert_eq!(name, "MyBlock"),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn std430_packed_arrays() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let shader = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1) in;
+
+        layout(std430) buffer MyBlock {
+            vec2 offset;
+            float weights[3];
+            uint count;
+        };
+
+        void main() {
+            count = 2u;
+            weights[0] *= 2.0;
+            weights[2] *= 2.0;
+        }
+    ");
+
+    let shader = match shader {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let block = shader.get_shader_storage_blocks().get("MyBlock").unwrap();
+    assert_eq!(block.memory_layout, glium::program::BlockMemoryLayout::Std430);
+
+    #[derive(Copy, Clone)]
+    #[repr(C)]
+    struct Data {
+        offset: [f32; 2],
+        weights: [f32; 3],
+        count: u32,
+    }
+
+    implement_uniform_block!(Data, offset, weights, count);
+
+    let buffer = glium::uniforms::ShaderStorageBuffer::new(&display, &Data {
+        offset: [0.0, 0.0],
+        weights: [1.0, 2.0, 3.0],
+        count: 0,
+    }).unwrap();
+
+    shader.execute(uniform! { MyBlock: &buffer }, 1, 1, 1).unwrap();
+
+    let data = buffer.read().unwrap();
+    assert_eq!(data.weights, [2.0, 2.0, 6.0]);
+    assert_eq!(data.count, 2);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn memory_layout_array_stride() {
+    use glium::program::BlockMemoryLayout;
+    use glium::uniforms::UniformType;
+
+    assert_eq!(BlockMemoryLayout::Std140.array_stride(UniformType::Float), Some(16));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::Float), Some(4));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::FloatVec2), Some(8));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::FloatVec3), Some(16));
+    assert_eq!(BlockMemoryLayout::Std140.array_stride(UniformType::FloatMat2), Some(32));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::FloatMat2), Some(16));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::FloatMat3), Some(48));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::DoubleVec3), Some(32));
+    assert_eq!(BlockMemoryLayout::Std430.array_stride(UniformType::Sampler2d), None);
+}
+
+#[test]
+fn memory_layout_matches() {
+    use glium::program::{BlockLayout, BlockMemoryLayout};
+    use glium::uniforms::{LayoutMismatchError, UniformBlock, UniformType};
+
+    // `float data[4]` followed by `float data2[]`
+    let float_array = |offset_in_buffer| BlockLayout::Array {
+        content: Box::new(BlockLayout::BasicType { ty: UniformType::Float, offset_in_buffer }),
+        length: 4,
+    };
+    let dynamic_float_array = BlockLayout::DynamicSizedArray {
+        content: Box::new(BlockLayout::BasicType { ty: UniformType::Float, offset_in_buffer: 0 }),
+    };
+
+    // tightly packed arrays are only valid with std430
+    assert!(<[f32; 4]>::matches_memory_layout(&float_array(0), 0,
+                                              BlockMemoryLayout::Std430).is_ok());
+    assert!(<[f32; 4]>::matches_memory_layout(&float_array(0), 0,
+                                              BlockMemoryLayout::Std140).is_err());
+    assert!(<[f32; 4]>::matches_memory_layout(&float_array(4), 0,
+                                              BlockMemoryLayout::Std430).is_err());
+
+    assert!(<[f32]>::matches_memory_layout(&dynamic_float_array, 0,
+                                           BlockMemoryLayout::Std430).is_ok());
+    match <[f32]>::matches_memory_layout(&dynamic_float_array, 0, BlockMemoryLayout::Std140) {
+        Err(LayoutMismatchError::StrideMismatch { expected: 16, obtained: 4 }) => (),
+        _ => panic!()
+    }
+}
@@ -10,10 +10,10 @@ use glium::backend::Facade;
 use glium::index::PrimitiveType;
 
 use glutin::config::{Config, ConfigTemplateBuilder};
-use glutin::context::ContextAttributesBuilder;
+use glutin::context::{ContextAttributesBuilder, PossiblyCurrentContext};
 use glutin::display::GetGlDisplay;
 use glutin::prelude::*;
-use glutin::surface::{SurfaceAttributesBuilder, WindowSurface};
+use glutin::surface::{Surface, SurfaceAttributesBuilder, WindowSurface};
 use glutin_winit::DisplayBuilder;
 use raw_window_handle::{HasWindowHandle, WindowHandle, RawWindowHandle};
 use glium::winit::event::Event;
@@ -154,6 +154,29 @@ unsafe fn initialize_event_loop() {
 
 /// Builds a display for tests.
 pub fn build_display() -> Display<WindowSurface> {
+    // Then the configuration which decides which OpenGL version we'll end up using, here we just use the default which is currently 3.3 core
+    // When this fails we'll try and create an ES context, this is mainly used on mobile devices or various ARM SBC's
+    // If you depend on features available in modern OpenGL Versions you need to request a specific, modern, version. Otherwise things will very likely fail.
+    let version = parse_version();
+    let context_attributes = ContextAttributesBuilder::new().with_context_api(version);
+    let (current_context, surface) = build_context_surface(context_attributes);
+
+    Display::from_context_surface(current_context, surface).unwrap()
+}
+
+/// Rebuilds an existing display.
+///
+/// In real applications this is used for things such as switching to fullscreen. Some things are
+/// invalidated during a rebuild, and this has to be handled by glium.
+pub fn rebuild_display(display: &glium::Display<WindowSurface>) {
+    let (current_context, surface) = build_context_surface(display.shared_context_attributes());
+    display.rebuild(current_context, surface).unwrap();
+}
+
+/// Creates a new window, then a context with the given attributes and a surface for this window.
+fn build_context_surface(context_attributes: ContextAttributesBuilder)
+                         -> (PossiblyCurrentContext, Surface<WindowSurface>)
+{
     // SAFETY
     // This is the first function to run when any test thread calls build_display.
     // `Once` spawns a new thread to create the event loop and sets up the communication channels.
@@ -173,14 +196,8 @@ pub fn build_display() -> Display<WindowSurface> {
             .as_ref().unwrap()
             .recv().unwrap();
 
-    // Then the configuration which decides which OpenGL version we'll end up using, here we just use the default which is currently 3.3 core
-    // When this fails we'll try and create an ES context, this is mainly used on mobile devices or various ARM SBC's
-    // If you depend on features available in modern OpenGL Versions you need to request a specific, modern, version. Otherwise things will very likely fail.
-    let version = parse_version();
     let raw_window_handle = handle_or_window.into();
-    let context_attributes = ContextAttributesBuilder::new()
-        .with_context_api(version)
-        .build(Some(raw_window_handle));
+    let context_attributes = context_attributes.build(Some(raw_window_handle));
 
     let not_current_gl_context = unsafe {
         gl_config.display().create_context(&gl_config, &context_attributes).unwrap()
@@ -192,29 +209,11 @@ pub fn build_display() -> Display<WindowSurface> {
         NonZeroU32::new(600).unwrap(),
     );
 
-    // Now we can create our surface, use it to make our context current and finally create our display
+    // Now we can create our surface, use it to make our context current
     let surface = unsafe { gl_config.display().create_window_surface(&gl_config, &attrs).unwrap() };
     let current_context = not_current_gl_context.make_current(&surface).unwrap();
 
-    Display::from_context_surface(current_context, surface).unwrap()
-}
-
-
-/// Rebuilds an existing display.
-///
-/// In real applications this is used for things such as switching to fullscreen. Some things are
-/// invalidated during a rebuild, and this has to be handled by glium.
-pub fn rebuild_display(_display: &glium::Display<WindowSurface>) {
-    todo!();
-    /*
-    let version = parse_version();
-    let event_loop = glium::winit::event_loop::EventLoop::new();
-    let wb = glium::winit::window::WindowBuilder::new().with_visible(false);
-    let cb = glutin::ContextBuilder::new()
-        .with_gl_debug_flag(true)
-        .with_gl(version);
-    display.rebuild(wb, cb, &event_loop).unwrap();
-     */
+    (current_context, surface)
 }
 
 fn parse_version() -> glutin::context::ContextApi {
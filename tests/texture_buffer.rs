@@ -125,3 +125,69 @@ fn wrong_type() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn set_buffer() {
+    let display = support::build_display();
+
+    let buf_tex = BufferTexture::new(&display, &[(255, 0, 0, 255)], BufferTextureType::Float);
+    let mut buf_tex: BufferTexture<(u8, u8, u8, u8)> = match buf_tex {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let buffer = glium::buffer::Buffer::new(&display, &[(0u8, 255u8, 0u8, 255u8); 4][..],
+                                            glium::buffer::BufferType::TextureBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+
+    let previous = buf_tex.set_buffer(buffer).ok().unwrap();
+    assert_eq!(previous.len(), 1);
+    assert_eq!(buf_tex.len(), 4);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn too_large() {
+    let display = support::build_display();
+
+    let max = match display.get_max_texture_buffer_size() {
+        Some(m) => m,
+        None => return
+    };
+
+    let texture = BufferTexture::<u8>::empty(&display, max + 1, BufferTextureType::Float);
+    match texture {
+        Err(glium::texture::buffer_texture::CreationError::TextureCreationError(
+            glium::texture::buffer_texture::TextureCreationError::TooLarge)) => (),
+        // the buffer itself may fail to be created
+        Err(glium::texture::buffer_texture::CreationError::BufferCreationError(_)) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn two_and_three_components() {
+    use glium::texture::buffer_texture::{CreationError, TextureCreationError};
+
+    let display = support::build_display();
+
+    let texture = BufferTexture::<(u16, u16)>::empty(&display, 8, BufferTextureType::Unsigned);
+    match texture {
+        Ok(_) => (),
+        Err(CreationError::TextureCreationError(TextureCreationError::NotSupported)) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    // RGB32F requires OpenGL 4.0 or `GL_ARB_texture_buffer_object_rgb32`
+    let texture = BufferTexture::<[f32; 3]>::empty(&display, 8, BufferTextureType::Float);
+    match texture {
+        Ok(_) => (),
+        Err(CreationError::TextureCreationError(TextureCreationError::FormatNotSupported)) => (),
+        Err(e) => panic!("{:?}", e),
+    };
+
+    display.assert_no_error(None);
+}
@@ -407,3 +407,339 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn estimated_size_bytes_with_mipmaps() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                                       glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                       glium::texture::MipmapsOption::EmptyMipmaps,
+                                                       4, 4).unwrap();
+
+    // 4x4 + 2x2 + 1x1 texels of 4 bytes each
+    assert_eq!(texture.estimated_size_bytes(), (16 + 4 + 1) * 4);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn estimated_size_bytes_compressed() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::CompressedTexture2d::empty_with_format(&display,
+                                    glium::texture::CompressedFormat::S3tcDxt5Alpha,
+                                    glium::texture::CompressedMipmapsOption::NoMipmap, 6, 6)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    // 2x2 blocks of 16 bytes each
+    assert_eq!(texture.estimated_size_bytes(), 4 * 16);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_formats_list() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let capabilities = display.get_capabilities();
+
+    assert!(capabilities.compressed_texture_formats.len() <=
+            capabilities.compressed_texture_formats_raw.len());
+
+    for format in &capabilities.compressed_texture_formats {
+        match format {
+            glium::texture::TextureFormat::CompressedFormat(_) => (),
+            glium::texture::TextureFormat::CompressedSrgbFormat(_) => (),
+            _ => panic!("{:?} is not a compressed format", format),
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_transfer_roundtrip() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+    ]).unwrap();
+    let id = glium::GlObject::get_id(&texture);
+
+    // a context trivially shares lists with itself
+    let transfer = texture.into_transfer();
+    let texture = unsafe { glium::texture::Texture2d::from_transfer(&display, transfer) };
+
+    assert_eq!(glium::GlObject::get_id(&texture), id);
+    assert_eq!(texture.dimensions(), (2, 1));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][1], (4, 8, 16, 32));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_transfer_dropped() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+    ]).unwrap();
+
+    // the transfer can be dropped on any thread, the texture is destroyed when flushing
+    let transfer = texture.into_transfer();
+    std::thread::spawn(move || drop(transfer)).join().unwrap();
+    display.flush();
+
+    // dropping a transfer after the context has been destroyed leaks the texture
+    let texture = glium::texture::Texture2d::new(&display, vec![vec![(0u8, 0u8, 0u8, 0u8)]]).unwrap();
+    let transfer = texture.into_transfer();
+    display.assert_no_error(None);
+    drop(display);
+    drop(transfer);
+}
+
+#[test]
+fn shared_upload_context() {
+    let display = support::build_display();
+
+    let uploader = match display.new_shared_upload_context() {
+        Ok(u) => u,
+        Err(_) => return,
+    };
+
+    let texture = uploader.create_texture_async(vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+    ], glium::texture::MipmapsOption::NoMipmap);
+    let buffer = uploader.create_buffer_async(vec![1u32, 2, 3],
+                                              glium::buffer::BufferType::ArrayBuffer,
+                                              glium::buffer::BufferMode::Default);
+
+    let texture = texture.wait().unwrap();
+    assert_eq!(texture.dimensions(), (2, 1));
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][1], (4, 8, 16, 32));
+
+    let buffer = buffer.wait().unwrap();
+    assert_eq!(buffer.read().unwrap(), vec![1, 2, 3]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn upload_context_failure() {
+    use std::io;
+    use glium::backend::embedded::ForeignContext;
+    use glium::upload::{ResourceUploader, UploadError};
+
+    let display = support::build_display();
+
+    let uploader = unsafe {
+        ResourceUploader::new(&display, || {
+            Err::<ForeignContext, _>(io::Error::new(io::ErrorKind::Other, "no context"))
+        })
+    };
+
+    let buffer = uploader.create_buffer_async(vec![1u32, 2, 3],
+                                              glium::buffer::BufferType::ArrayBuffer,
+                                              glium::buffer::BufferMode::Default);
+
+    match buffer.wait() {
+        Err(UploadError::ContextCreationError(err)) => assert_eq!(err.to_string(), "no context"),
+        e => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_with_mipmaps_storage() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2d::with_mipmaps_storage(&display,
+                                                       glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                       2, 4, 4)
+    {
+        Ok(t) => t,
+        Err(glium::texture::TextureCreationError::ImmutableStorageNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    assert_eq!(texture.get_mipmap_levels(), 2);
+    assert!(texture.mipmap(2).is_none());
+
+    let level = texture.mipmap(1).unwrap();
+    level.write(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                vec![vec![(255u8, 0u8, 0u8, 255u8); 2]; 2]);
+
+    texture.generate_mipmaps();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_with_mipmaps_storage_levels_out_of_range() {
+    let display = support::build_display();
+
+    for &levels in &[0, 4] {
+        match glium::texture::Texture2d::with_mipmaps_storage(&display,
+                                                      glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                      levels, 4, 4)
+        {
+            Err(glium::texture::TextureCreationError::MipmapLevelsOutOfRange) => (),
+            Err(glium::texture::TextureCreationError::ImmutableStorageNotSupported) => return,
+            _ => panic!()
+        };
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_empty_mipmaps_then_generate() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                                glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                glium::texture::MipmapsOption::EmptyMipmapsThenGenerate,
+                                                4, 4).unwrap();
+    assert_eq!(texture.get_mipmap_levels(), 3);
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 1, height: 1 };
+
+    // writing to the texture regenerates the mipmaps
+    texture.write(glium::Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                  vec![vec![(0u8, 255u8, 0u8, 255u8); 4]; 4]);
+    let level: glium::texture::TextureAnyImage<'_> = texture.mipmap(2).unwrap().into();
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = level.raw_read(&rect);
+    assert_eq!(read, vec![vec![(0, 255, 0, 255)]]);
+
+    // drawing to the texture doesn't
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    texture.generate_mipmaps();
+    let level: glium::texture::TextureAnyImage<'_> = texture.mipmap(2).unwrap().into();
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = level.raw_read(&rect);
+    assert_eq!(read, vec![vec![(255, 0, 0, 255)]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_from_compressed_raw() {
+    use glium::texture::{CompressedFormat, CompressedTexture2d, TextureCreationError};
+
+    let display = support::build_display();
+
+    // levels of 8x8, 4x4, 2x2 and 1x1 texels, with 8 bytes per 4x4 block
+    let data = vec![0u8; 32 + 8 + 8 + 8];
+
+    let texture = match CompressedTexture2d::from_compressed_raw(&display, CompressedFormat::Etc2Rgb8,
+                                                                 &data, (8, 8), 4)
+    {
+        Ok(t) => t,
+        Err(TextureCreationError::FormatNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    assert_eq!(texture.get_width(), 8);
+    assert_eq!(texture.get_height(), Some(8));
+    assert_eq!(texture.get_mipmap_levels(), 4);
+
+    match CompressedTexture2d::from_compressed_raw(&display, CompressedFormat::Etc2Rgb8,
+                                                   &data[1..], (8, 8), 4)
+    {
+        Err(TextureCreationError::DataSizeMismatch) => (),
+        _ => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_3d_etc2_not_supported() {
+    use glium::texture::{CompressedFormat, CompressedMipmapsOption, CompressedTexture3d};
+    use glium::texture::TextureCreationError;
+
+    let display = support::build_display();
+
+    // 8x8x2 texels, with 8 bytes per 4x4 block
+    let data = vec![0u8; 64];
+
+    match CompressedTexture3d::with_compressed_data(&display, &data, 8, 8, 2,
+                                                    CompressedFormat::Etc2Rgb8,
+                                                    CompressedMipmapsOption::NoMipmap)
+    {
+        Err(TextureCreationError::DimensionsNotSupported) => (),
+        Err(TextureCreationError::FormatNotSupported) => (),
+        _ => panic!(),
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sparse_texture_2d_commit_region() {
+    use glium::texture::{CommitRegionError, SparseTexture2d, SparseTextureCreationError};
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+
+    let format = UncompressedFloatFormat::U8U8U8U8;
+    let (page_width, page_height) = match SparseTexture2d::page_sizes(&display, format).first() {
+        Some(&size) => size,
+        None => return,
+    };
+
+    match SparseTexture2d::empty_with_format(&display, format, glium::texture::MipmapsOption::NoMipmap,
+                                             page_width + 1, page_height)
+    {
+        Err(SparseTextureCreationError::DimensionsNotSupported) => (),
+        _ => panic!(),
+    };
+
+    let texture = SparseTexture2d::empty_with_format(&display, format,
+                                                     glium::texture::MipmapsOption::NoMipmap,
+                                                     page_width * 2, page_height * 2).unwrap();
+    assert_eq!(texture.page_size(), (page_width, page_height));
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: page_width, height: page_height };
+    texture.commit_region(rect, 0, true).unwrap();
+    texture.write(rect, vec![vec![(255u8, 0u8, 0u8, 255u8); page_width as usize];
+                             page_height as usize]);
+
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = texture.main_level().first_layer().into_image(None)
+                                                  .unwrap().raw_read(&rect);
+    assert_eq!(read[0][0], (255, 0, 0, 255));
+
+    texture.commit_region(rect, 0, false).unwrap();
+
+    assert_eq!(texture.commit_region(rect, 100, true),
+               Err(CommitRegionError::LevelOutOfRange));
+    let rect = glium::Rect { left: 1, bottom: 0, width: page_width, height: page_height };
+    assert_eq!(texture.commit_region(rect, 0, true),
+               Err(CommitRegionError::RegionNotAligned));
+    let rect = glium::Rect { left: u32::MAX, bottom: 0, width: page_width, height: page_height };
+    assert_eq!(texture.commit_region(rect, 0, true),
+               Err(CommitRegionError::RegionOutOfBounds));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", feature = "egl_image"))]
+fn egl_display_missing_function() {
+    use glium::texture::TextureImportError;
+    use glium::texture::egl_image::EglDisplay;
+
+    let result = unsafe { EglDisplay::new(std::ptr::null(), |_| std::ptr::null()) };
+    match result {
+        Err(TextureImportError::EglFunctionNotFound("eglCreateImageKHR")) => (),
+        _ => panic!()
+    }
+}
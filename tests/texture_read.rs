@@ -134,3 +134,243 @@ read_texture_test!(read_unsignedtexture2darray, UnsignedTexture2dArray, (u8, u8,
 read_texture_test!(read_unsignedtexture3d, UnsignedTexture3d, (u8, u8, u8, u8),
     vec![vec![vec![(0, 1, 2, 3), (4, 5, 6, 7)], vec![(8, 9, 10, 11), (12, 13, 14, 15)]]]);*/
 // TODO: srgb textures
+
+#[test]
+fn texture_2d_copy_to_and_from_buffer() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+        vec![(32u8, 64u8, 128u8, 255u8), (32u8, 16u8, 4u8, 2u8)],
+    ]).unwrap();
+
+    let buffer: glium::buffer::Buffer<[(u8, u8, u8, u8)]> =
+        glium::buffer::Buffer::empty_array(&display, glium::buffer::BufferType::PixelPackBuffer,
+                                           4, glium::buffer::BufferMode::Default).unwrap();
+    texture.copy_to_buffer(buffer.as_slice());
+
+    match buffer.read() {
+        Ok(data) => assert_eq!(data, vec![(0, 1, 2, 3), (4, 8, 16, 32),
+                                          (32, 64, 128, 255), (32, 16, 4, 2)]),
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    let copy = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    copy.copy_from_buffer(buffer.as_slice());
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = copy.read();
+    assert_eq!(read_back[0][1], (4, 8, 16, 32));
+    assert_eq!(read_back[1][0], (32, 64, 128, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn cubemap_read_face() {
+    let display = support::build_display();
+
+    let cubemap = match glium::texture::Cubemap::empty(&display, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    for &(face, color) in &[(glium::texture::CubeLayer::PositiveY, (0.0, 0.0, 1.0, 1.0)),
+                            (glium::texture::CubeLayer::NegativeY, (1.0, 0.0, 0.0, 1.0))]
+    {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                        cubemap.main_level().image(face)).unwrap();
+        glium::Surface::clear_color(&mut framebuffer, color.0, color.1, color.2, color.3);
+    }
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = cubemap.read(glium::texture::CubeLayer::NegativeY);
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[1][1], (255, 0, 0, 255));
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> =
+        match cubemap.read_to_pixel_buffer(glium::texture::CubeLayer::PositiveY)
+                     .read_as_texture_2d()
+    {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    assert_eq!(read_back[0][1], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_read() {
+    let display = support::build_display();
+
+    let data = vec![
+        vec![vec![(0u8, 1u8, 2u8, 3u8), (4u8, 5u8, 6u8, 7u8)],
+             vec![(8u8, 9u8, 10u8, 11u8), (12u8, 13u8, 14u8, 15u8)]],
+        vec![vec![(16u8, 17u8, 18u8, 19u8), (20u8, 21u8, 22u8, 23u8)],
+             vec![(24u8, 25u8, 26u8, 27u8), (28u8, 29u8, 30u8, 31u8)]],
+    ];
+
+    let texture = match glium::texture::Texture3d::new(&display, data.clone()) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let read_back: Vec<Vec<Vec<(u8, u8, u8, u8)>>> = texture.read();
+    assert_eq!(read_back, data);
+
+    let read_back = match texture.read_to_pixel_buffer().read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    assert_eq!(read_back, data.into_iter().flatten().flatten().collect::<Vec<_>>());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_write_from_pixel_buffer() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 0u8, 0u8, 0u8), (0u8, 0u8, 0u8, 0u8)],
+        vec![(0u8, 0u8, 0u8, 0u8), (0u8, 0u8, 0u8, 0u8)],
+    ]).unwrap();
+
+    let buffer = glium::texture::pixel_buffer::PixelBuffer::new_empty(&display, 2);
+    buffer.write(&[(1u8, 2u8, 3u8, 4u8), (5u8, 6u8, 7u8, 8u8)]);
+
+    let rect = glium::Rect { left: 0, bottom: 1, width: 2, height: 1 };
+    texture.write_from_pixel_buffer(rect, &buffer);
+
+    // writing to the buffer again must not affect the upload
+    buffer.write(&[(0u8, 0u8, 0u8, 0u8), (0u8, 0u8, 0u8, 0u8)]);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back, vec![
+        vec![(0, 0, 0, 0), (0, 0, 0, 0)],
+        vec![(1, 2, 3, 4), (5, 6, 7, 8)],
+    ]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_write_from_buffer_slice() {
+    let display = support::build_display();
+
+    // rows of 9 bytes check that the unpack alignment is set to 1
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 0u8, 0u8), (0u8, 0u8, 0u8), (0u8, 0u8, 0u8)],
+        vec![(0u8, 0u8, 0u8), (0u8, 0u8, 0u8), (0u8, 0u8, 0u8)],
+    ]).unwrap();
+
+    let data = [(9u8, 9u8, 9u8), (1, 2, 3), (4, 5, 6), (7, 8, 9),
+                (10, 11, 12), (13, 14, 15), (16, 17, 18)];
+    let buffer = glium::buffer::Buffer::<[(u8, u8, u8)]>::new(&display, &data,
+                                         glium::buffer::BufferType::PixelUnpackBuffer,
+                                         glium::buffer::BufferMode::Persistent).unwrap();
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 3, height: 2 };
+    texture.write_from_buffer_slice(rect, buffer.slice(1 ..).unwrap());
+
+    // writing to the buffer again must not affect the upload
+    buffer.write(&[(0u8, 0u8, 0u8); 7]);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back, vec![
+        vec![(1, 2, 3, 255), (4, 5, 6, 255), (7, 8, 9, 255)],
+        vec![(10, 11, 12, 255), (13, 14, 15, 255), (16, 17, 18, 255)],
+    ]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn surface_read_to_pixel_buffer() {
+    use glium::Surface;
+
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let pending = framebuffer.read_to_pixel_buffer(&rect).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = match pending.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back.len(), 2);
+    for row in read_back.iter() {
+        assert_eq!(row, &vec![(255, 0, 0, 255), (255, 0, 0, 255)]);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn surface_read_to_pixel_buffer_out_of_bounds() {
+    use glium::Surface;
+
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let (width, height) = framebuffer.get_dimensions();
+    let rect = glium::Rect { left: 1, bottom: 0, width, height };
+    match framebuffer.read_to_pixel_buffer(&rect) {
+        Err(glium::ReadError::RectOutOfBounds) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_with_options() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8, 3u8), (4u8, 8u8, 16u8, 32u8)],
+        vec![(32u8, 64u8, 128u8, 255u8), (32u8, 16u8, 4u8, 2u8)],
+    ]).unwrap();
+
+    let image: glium::texture::TextureAnyImage<'_> = texture.main_level().into();
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let options = glium::ReadOptions { bgra: true, top_to_bottom: true };
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = match image.raw_read_with_options(&rect, &options) {
+        Ok(r) => r,
+        Err(glium::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(read_back[0][0], (128, 64, 32, 255));
+    assert_eq!(read_back[0][1], (4, 16, 32, 2));
+    assert_eq!(read_back[1][0], (2, 1, 0, 3));
+    assert_eq!(read_back[1][1], (16, 8, 4, 32));
+
+    let options = glium::ReadOptions { bgra: false, top_to_bottom: true };
+    let rect = glium::Rect { left: 1, bottom: 0, width: 1, height: 2 };
+    let read_back: Vec<Vec<(f32, f32, f32, f32)>> = match image.raw_read_with_options(&rect, &options) {
+        Ok(r) => r,
+        Err(glium::ReadError::OutputFormatNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let to_u8 = |(r, g, b, a): (f32, f32, f32, f32)| {
+        ((r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8,
+         (a * 255.0).round() as u8)
+    };
+    assert_eq!(to_u8(read_back[0][0]), (32, 16, 4, 2));
+    assert_eq!(to_u8(read_back[1][0]), (4, 8, 16, 32));
+
+    display.assert_no_error(None);
+}
@@ -130,3 +130,216 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn bindless_texture_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let texture = match texture.resident() {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            layout(bindless_sampler) uniform sampler2D tex;
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(tex, vec2(0.0, 0.0));
+            }
+        ",
+        None).unwrap();
+
+    let handle = glium::texture::TextureHandle::new(&texture, &Default::default());
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ tex: handle },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+fn build_stencil_sampling_program<F: ?Sized>(display: &F) -> Option<glium::Program>
+    where F: glium::backend::Facade
+{
+    glium::Program::from_source(display,
+        "
+            #version 330
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            uniform usampler2D stencil;
+            out vec4 color;
+
+            void main() {
+                color = vec4(float(texelFetch(stencil, ivec2(0, 0), 0).r) / 255.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).ok()
+}
+
+#[test]
+fn stencil_texture_2d_sample() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = match glium::texture::StencilTexture2d::new(&display, vec![
+        vec![255u8, 255u8],
+        vec![255u8, 255u8],
+    ]) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let program = match build_stencil_sampling_program(&display) {
+        Some(p) => p,
+        None => return,
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ stencil: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_stencil_texture_2d_sample_stencil() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = match glium::texture::DepthStencilTexture2d::empty(&display, 2, 2) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let program = match build_stencil_sampling_program(&display) {
+        Some(p) => p,
+        None => return,
+    };
+
+    let color = support::build_renderable_texture(&display);
+    glium::framebuffer::SimpleFrameBuffer::with_depth_stencil_buffer(&display, &color, &texture)
+        .unwrap().clear_all((0.0, 0.0, 0.0, 0.0), 1.0, 255);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match output.as_surface().draw(&vb, &ib, &program, &uniform!{ stencil: &texture },
+                                   &Default::default())
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::StencilTexturingNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_batch_sample() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+    let blue = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 0, 255, 255), (0, 0, 255, 255)],
+        vec![(0, 0, 255, 255), (0, 0, 255, 255u8)],
+    ]).unwrap();
+
+    let batch = match glium::uniforms::TextureBatch::new(&display, &[&red, &blue, &red]) {
+        Ok(b) => b,
+        Err(glium::uniforms::TextureBatchError::TextureCreationError(_)) => return,
+        Err(e) => panic!("{}", e),
+    };
+    assert_eq!(batch.len(), 2);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            uniform sampler2DArray textures;
+            uniform uint layer;
+
+            void main() {
+                gl_FragColor = texture(textures, vec3(0.5, 0.5, float(layer)));
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return,
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program,
+                             &uniform!{ textures: &batch, layer: batch.layer(&blue).unwrap() },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
@@ -24,3 +24,36 @@ fn texture_2d_write() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_2d_write_with_stride() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            2, 2).unwrap();
+
+    // a 3x3 image, of which the 2x2 sub-rectangle starting at the second pixel is uploaded
+    let image = vec![
+        (0u8, 0u8, 0u8, 0u8), (1, 2, 4, 8), (16, 32, 64, 128),
+        (0, 0, 0, 0), (128, 64, 32, 16), (8, 4, 2, 1),
+        (0, 0, 0, 0), (0, 0, 0, 0), (0, 0, 0, 0),
+    ];
+    texture.write_with_stride(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                              &image[1..], 3);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (1, 2, 4, 8));
+    assert_eq!(read_back[0][1], (16, 32, 64, 128));
+    assert_eq!(read_back[1][0], (128, 64, 32, 16));
+    assert_eq!(read_back[1][1], (8, 4, 2, 1));
+
+    // the next uploads expect tightly packed data again
+    texture.write(glium::Rect { left: 1, bottom: 1, width: 1, height: 1 },
+                  vec![vec![(2u8, 2u8, 2u8, 2u8)]]);
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[1][1], (2, 2, 2, 2));
+
+    display.assert_no_error(None);
+}
@@ -482,3 +482,221 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn uniform_value_read_back() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ color: [1.0, 0.0, 0.0, 0.5f32] },
+                              &Default::default()).unwrap();
+
+    match program.get_uniform_value("color") {
+        Some(glium::RawUniformValue::Vec4(v)) => assert_eq!(v, [1.0, 0.0, 0.0, 0.5]),
+        v => panic!("{:?}", v)
+    };
+
+    assert!(program.get_uniform_value("unknown").is_none());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_cache_toggle() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    assert!(!program.is_uniform_cache_enabled());
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the same value drawn with and without the cache must give the same result
+    for &enabled in &[true, false, true] {
+        program.set_uniform_cache_enabled(enabled);
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{ color: [1.0, 0.0, 0.0, 1.0f32] },
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], (255, 0, 0, 255));
+    }
+
+    program.invalidate_uniform_cache();
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ color: [0.0, 1.0, 0.0, 1.0f32] },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stage_uniforms_before_draw() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let uniforms = uniform!{ color: [0.0, 0.0, 1.0, 1.0f32] };
+    program.stage_uniforms(&uniforms).unwrap();
+
+    // the staged value is used when the draw doesn't pass any value
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 colors[3];
+
+            void main() {
+                gl_FragColor = colors[0] + colors[1] + colors[2];
+            }
+        ",
+        None).unwrap();
+
+    let colors = [[0.5, 0.0, 0.0, 0.0], [0.5, 0.0, 0.0, 0.0], [0.0, 0.0, 1.0, 1.0f32]];
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ colors: &colors },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+
+    // the elements are still reachable individually
+    match program.get_uniform_value("colors[2]") {
+        Some(glium::RawUniformValue::Vec4(v)) => assert_eq!(v, [0.0, 0.0, 1.0, 1.0]),
+        v => panic!("{:?}", v)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_array_too_long() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform float weights[2];
+
+            void main() {
+                gl_FragColor = vec4(weights[0], weights[1], 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let weights = [1.0f32, 0.5, 0.25];
+
+    let mut target = display.draw();
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    match target.draw(&vb, &ib, &program, &uniform!{ weights: &weights[..] },
+                      &Default::default())
+    {
+        Err(glium::DrawError::UniformArrayTooLong { len: 3, max: 2, .. }) => (),
+        a => panic!("{:?}", a)
+    };
+    target.finish().unwrap();
+
+    display.assert_no_error(None);
+}
@@ -90,3 +90,131 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_object_pause_and_draw() {
+    let display = support::build_display();
+    if !glium::vertex::is_transform_feedback_object_supported(&display) {
+        return;
+    }
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 12).unwrap();
+    let object = glium::vertex::TransformFeedbackObject::new(&display).unwrap();
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::with_object(&display, &program,
+                                                                           &mut out_buffer,
+                                                                           &object).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+
+        // this draw command isn't captured
+        if session.pause().is_err() {
+            return;
+        }
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+    }
+
+    let result = match out_buffer.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    for i in 0 .. 6 {
+        assert!(result[i] == result[i + 6]);
+    }
+    assert_eq!(result[11].output_val, (1.0, -1.0));
+
+    let draw_program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 output_val;
+
+            void main() {
+                gl_Position = vec4(output_val, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw(&out_buffer,
+                                    object.draw_transform_feedback(glium::index::PrimitiveType::TrianglesList),
+                                    &draw_program, &uniform!{}, &Default::default())
+    {
+        Ok(()) => (),
+        Err(glium::DrawError::DrawTransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
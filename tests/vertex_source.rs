@@ -762,3 +762,354 @@ fn normalized_vertex() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn swap_buffers_with_same_format() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Vertex, position, color);
+
+    // two buffers with the same format, covering the left and the right halves of the target
+    let half = |left: f32, color: [f32; 3]| {
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [left,        1.0], color },
+                Vertex { position: [left + 1.0,  1.0], color },
+                Vertex { position: [left,       -1.0], color },
+                Vertex { position: [left + 1.0, -1.0], color },
+            ]
+        ).unwrap()
+    };
+
+    let left = half(-1.0, [1.0, 0.0, 0.0]);
+    let right = half(0.0, [0.0, 0.0, 1.0]);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            attribute vec3 color;
+
+            varying vec3 v_color;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                v_color = color;
+            }
+        ",
+        "
+            #version 110
+            varying vec3 v_color;
+
+            void main() {
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let indices = glium::index::NoIndices(PrimitiveType::TriangleStrip);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // drawing twice in a row checks that the second buffer replaces the first one
+    for _ in 0 .. 2 {
+        texture.as_surface().draw(&left, &indices, &program, &uniform!{},
+                                  &Default::default()).unwrap();
+        texture.as_surface().draw(&right, &indices, &program, &uniform!{},
+                                  &Default::default()).unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(*data[0].last().unwrap(), (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn swap_index_buffers_with_same_format() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vertex_buffer = glium::VertexBuffer::new(&display,
+        &[
+            Vertex { position: [-1.0,  1.0] },
+            Vertex { position: [ 0.0,  1.0] },
+            Vertex { position: [-1.0, -1.0] },
+            Vertex { position: [ 0.0, -1.0] },
+            Vertex { position: [ 1.0,  1.0] },
+            Vertex { position: [ 1.0, -1.0] },
+        ]
+    ).unwrap();
+
+    // two index buffers covering the left and the right halves of the target
+    let left = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                       &[0u16, 1, 2, 3]).unwrap();
+    let right = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                        &[1u16, 4, 3, 5]).unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+            uniform vec3 color;
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // drawing twice in a row checks that the second index buffer replaces the first one
+    for _ in 0 .. 2 {
+        texture.as_surface().draw(&vertex_buffer, &left, &program,
+                                  &uniform!{ color: [1.0f32, 0.0, 0.0] },
+                                  &Default::default()).unwrap();
+        texture.as_surface().draw(&vertex_buffer, &right, &program,
+                                  &uniform!{ color: [0.0f32, 0.0, 1.0] },
+                                  &Default::default()).unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(*data[0].last().unwrap(), (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn raw_vertex_buffer() {
+    let display = support::build_display();
+
+    use glium::vertex::{AttributeType, DynamicVertexFormat, RawVertexBuffer};
+
+    // a position followed by a normalized RGBA color
+    let format = DynamicVertexFormat::new(12)
+        .attribute("position", 0, AttributeType::F32F32)
+        .normalized_attribute("color", 8, AttributeType::U8U8U8U8);
+
+    let mut bytes = Vec::new();
+    for position in [[-1.0f32, 1.0], [1.0, 1.0], [-1.0, -1.0], [1.0, -1.0]] {
+        bytes.extend_from_slice(&position[0].to_ne_bytes());
+        bytes.extend_from_slice(&position[1].to_ne_bytes());
+        bytes.extend_from_slice(&[255, 0, 0, 255]);
+    }
+
+    let vertex_buffer = RawVertexBuffer::new(&display, &format, &bytes).unwrap();
+    assert_eq!(vertex_buffer.len(), 4);
+    assert_eq!(vertex_buffer.get_stride(), 12);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec4 color;
+
+                varying vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec4 color;
+
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+                              glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dynamic_vertex_format_errors() {
+    use glium::vertex::{AttributeType, DynamicVertexFormat, DynamicVertexFormatError};
+
+    let format = DynamicVertexFormat::new(12)
+        .attribute("position", 0, AttributeType::F32F32)
+        .attribute("color", 8, AttributeType::F32F32);
+    assert_eq!(format.build(), Err(DynamicVertexFormatError::AttributeOutOfBounds {
+        name: "color".to_owned()
+    }));
+
+    let format = DynamicVertexFormat::new(16)
+        .attribute("position", 0, AttributeType::F32F32)
+        .attribute("position", 8, AttributeType::F32F32);
+    assert_eq!(format.build(), Err(DynamicVertexFormatError::DuplicateAttribute {
+        name: "position".to_owned()
+    }));
+
+    // the same layout is only stored once
+    let format = DynamicVertexFormat::new(8).attribute("position", 0, AttributeType::F32F32);
+    assert!(std::ptr::eq(format.build().unwrap(), format.build().unwrap()));
+}
+
+#[test]
+fn half_float_conversions() {
+    use glium::vertex::{F16, F10F11F11UnsignedIntReversed, I2I10I10I10Reversed};
+
+    assert_eq!(F16::from_f32(1.0), F16(0x3c00));
+    assert_eq!(F16::from_f32(-2.5), F16(0xc100));
+    assert_eq!(F16::from_f32(65504.0), F16(0x7bff));
+    assert_eq!(F16::from_f32(1.0e6), F16(0x7c00));
+    assert_eq!(F16::from_f32(5.9604645e-8), F16(0x0001));
+    assert!(F16::from_f32(f32::NAN).to_f32().is_nan());
+
+    for value in [0.0f32, 0.5, -0.333, 1024.0, 6.1035156e-5, 3.0517578e-5] {
+        let converted = F16::from_f32(value).to_f32();
+        assert!((converted - value).abs() <= value.abs() / 1024.0);
+    }
+
+    assert_eq!(I2I10I10I10Reversed::from_normalized(1.0, -1.0, 0.0, 1.0),
+               I2I10I10I10Reversed(0x1ff | (0x201 << 10) | (1 << 30)));
+    assert_eq!(F10F11F11UnsignedIntReversed::new(1.0, -1.0, 1.0),
+               F10F11F11UnsignedIntReversed(0x3c0 | (0x1e0 << 22)));
+}
+
+#[test]
+fn packed_vertex() {
+    let display = support::build_display();
+
+    use glium::vertex::{F16, U2U10U10U10Reversed};
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [F16; 2],
+        color: U2U10U10U10Reversed,
+    }
+
+    implement_vertex!(Vertex, position normalize(false), color normalize(true));
+
+    let color = U2U10U10U10Reversed::from_normalized(0.0, 1.0, 0.0, 1.0);
+    let vertices = [[-1.0, 1.0], [1.0, 1.0], [-1.0, -1.0], [1.0, -1.0]].map(|[x, y]| {
+        Vertex { position: [F16::from_f32(x), F16::from_f32(y)], color }
+    });
+
+    let vertex_buffer = match glium::VertexBuffer::new(&display, &vertices) {
+        Err(glium::vertex::BufferCreationError::FormatNotSupported) => return,
+        result => result.unwrap(),
+    };
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec4 color;
+
+                varying vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+                attribute lowp vec4 color;
+
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 100
+                varying lowp vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+                              glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[512][512], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}